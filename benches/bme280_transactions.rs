@@ -0,0 +1,284 @@
+//! BME280驱动的I2C传输次数
+//!
+//! 使用模拟芯片统计创建实例和每次采样的I2C传输次数，对比原来的读取方式（分三次读取
+//! 芯片ID和校准参数、每2毫秒查询一次状态）与连续读取、按转换时间等待和复用t_fine的方式
+//!
+//! 运行：`cargo bench --bench bme280_transactions`
+
+use std::cell::Cell;
+use std::convert::Infallible;
+use std::rc::Rc;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::{ErrorType, I2c, Operation, SevenBitAddress};
+use raspi_sensor::core::EnvReading;
+use raspi_sensor::core::bme280::{Bme280, CHIP_ID, Calibration, Channels, RawData, reg};
+
+/// 采样次数
+const SAMPLES: u32 = 1000;
+
+/// 模拟时钟（微秒）
+#[derive(Clone, Default)]
+struct Clock(Rc<Cell<u64>>);
+
+impl Clock {
+    fn now(&self) -> u64 {
+        self.0.get()
+    }
+}
+
+impl DelayNs for Clock {
+    fn delay_ns(&mut self, ns: u32) {
+        self.0.set(self.0.get() + ns.div_ceil(1000) as u64);
+    }
+}
+
+/// 模拟的BME280（寄存器地址自动递增，按过采样设置计算典型转换时间）
+struct MockBme280 {
+    /// 寄存器
+    registers: [u8; 256],
+    /// 模拟时钟
+    clock: Clock,
+    /// 转换完成的时间
+    ready_at: u64,
+    /// I2C传输次数
+    transactions: u32,
+}
+
+impl MockBme280 {
+    fn new(clock: Clock) -> Self {
+        let mut registers = [0u8; 256];
+        // 数据手册中的校准参数示例（湿度参数为常见模块的典型值）
+        let tp: [u16; 12] = [
+            27504,
+            26435,
+            -1000i16 as u16,
+            36477,
+            -10685i16 as u16,
+            3024,
+            2855,
+            140,
+            -7i16 as u16,
+            15500,
+            -14600i16 as u16,
+            6000,
+        ];
+        for (i, value) in tp.iter().enumerate() {
+            let at = reg::CALIB_TP as usize + i * 2;
+            registers[at..at + 2].copy_from_slice(&value.to_le_bytes());
+        }
+        registers[0xA1] = 75;
+        let (h4, h5) = (324i16, 50i16);
+        let h = reg::CALIB_H as usize;
+        registers[h..h + 2].copy_from_slice(&362i16.to_le_bytes());
+        registers[h + 2] = 0;
+        registers[h + 3] = (h4 >> 4) as u8;
+        registers[h + 4] = (h4 & 0x0F) as u8 | ((h5 & 0x0F) << 4) as u8;
+        registers[h + 5] = (h5 >> 4) as u8;
+        registers[h + 6] = 30;
+        registers[reg::ID as usize] = CHIP_ID;
+        // 测量数据：adc_P=415148，adc_T=519888，adc_H=30000
+        let data = reg::DATA as usize;
+        registers[data..data + 8]
+            .copy_from_slice(&[0x65, 0x5A, 0xC0, 0x7E, 0xED, 0x00, 0x75, 0x30]);
+        Self {
+            registers,
+            clock,
+            ready_at: 0,
+            transactions: 0,
+        }
+    }
+
+    /// 写入寄存器（写入CTRL_MEAS时开始转换）
+    fn write_register(&mut self, register: u8, value: u8) {
+        self.registers[register as usize] = value;
+        if register == reg::CTRL_MEAS && value & 0x03 != 0 {
+            // 典型转换时间：1 + 2*osrs_t + (2*osrs_p + 0.5) + (2*osrs_h + 0.5) 毫秒
+            let osrs_t = (value >> 5) as u64;
+            let osrs_p = ((value >> 2) & 0x07) as u64;
+            let osrs_h = (self.registers[reg::CTRL_HUM as usize] & 0x07) as u64;
+            let mut time = 1000 + osrs_t * 2000;
+            if osrs_p > 0 {
+                time += osrs_p * 2000 + 500;
+            }
+            if osrs_h > 0 {
+                time += osrs_h * 2000 + 500;
+            }
+            self.ready_at = self.clock.now() + time;
+        }
+    }
+
+    /// 读取寄存器（转换期间状态寄存器的measuring位为1）
+    fn read_register(&self, register: u8) -> u8 {
+        if register == reg::STATUS {
+            return if self.clock.now() < self.ready_at {
+                0x08
+            } else {
+                0x00
+            };
+        }
+        self.registers[register as usize]
+    }
+}
+
+impl ErrorType for MockBme280 {
+    type Error = Infallible;
+}
+
+impl I2c for MockBme280 {
+    fn transaction(
+        &mut self,
+        _address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.transactions += 1;
+        let mut pointer = 0u8;
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => {
+                    // 第一个字节为寄存器地址，之后为寄存器、值交替
+                    if let Some(&first) = bytes.first() {
+                        pointer = first;
+                    }
+                    if bytes.len() >= 2 {
+                        self.write_register(bytes[0], bytes[1]);
+                        for pair in bytes[2..].chunks_exact(2) {
+                            self.write_register(pair[0], pair[1]);
+                        }
+                    }
+                }
+                Operation::Read(buffer) => {
+                    for byte in buffer.iter_mut() {
+                        *byte = self.read_register(pointer);
+                        pointer = pointer.wrapping_add(1);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 原来的读取方式：分三次读取芯片ID和校准参数
+fn legacy_new(i2c: &mut MockBme280, address: u8) -> Calibration {
+    let mut id = [0u8; 1];
+    let mut tp = [0u8; 26];
+    let mut h = [0u8; 7];
+    let _ = i2c.write_read(address, &[reg::ID], &mut id);
+    let _ = i2c.write_read(address, &[reg::CALIB_TP], &mut tp);
+    let _ = i2c.write_read(address, &[reg::CALIB_H], &mut h);
+    Calibration::parse(&tp, &h)
+}
+
+/// 原来的读取方式：每2毫秒查询一次状态，转换完成后读取测量数据
+fn legacy_read(
+    i2c: &mut MockBme280,
+    delay: &mut Clock,
+    calibration: &Calibration,
+    address: u8,
+) -> EnvReading {
+    let _ = i2c.write(address, &[reg::CTRL_MEAS, 0x25]);
+    let mut status = [0u8; 1];
+    for _ in 0..10 {
+        delay.delay_ms(2);
+        let _ = i2c.write_read(address, &[reg::STATUS], &mut status);
+        if status[0] & 0x08 == 0 {
+            break;
+        }
+    }
+    let mut data = [0u8; 8];
+    let _ = i2c.write_read(address, &[reg::DATA], &mut data);
+    calibration.compensate(&RawData::parse(&data))
+}
+
+/// 输出一行统计结果
+fn report(name: &str, init: u32, sampling: u32, elapsed: u64, reading: &EnvReading) {
+    println!(
+        "{:<24} 初始化{:>2}次  每次采样{:>5.2}次  每次采样{:>6.2}ms  {:.2}℃ {:.0}Pa {}",
+        name,
+        init,
+        sampling as f64 / SAMPLES as f64,
+        elapsed as f64 / SAMPLES as f64 / 1000.0,
+        reading.temperature,
+        reading.pressure.unwrap_or_default(),
+        reading
+            .humidity
+            .map(|humidity| format!("{:.1}%", humidity))
+            .unwrap_or_default(),
+    );
+}
+
+fn main() {
+    let address = 0x76;
+
+    // 原来的方式
+    {
+        let mut clock = Clock::default();
+        let mut i2c = MockBme280::new(clock.clone());
+        let calibration = legacy_new(&mut i2c, address);
+        let init = i2c.transactions;
+        let _ = i2c.write(address, &[reg::CTRL_HUM, 0x01]);
+        let start = clock.now();
+        let mut reading = EnvReading::default();
+        for _ in 0..SAMPLES {
+            reading = legacy_read(&mut i2c, &mut clock, &calibration, address);
+        }
+        report(
+            "原方式（全部通道）",
+            init,
+            i2c.transactions - init,
+            clock.now() - start,
+            &reading,
+        );
+    }
+
+    // 连续读取（全部通道、只测量气压、只测量气压并复用t_fine）
+    let cases = [
+        ("连续读取（全部通道）", Channels::ALL, 0),
+        (
+            "连续读取（只测气压）",
+            Channels {
+                pressure: true,
+                humidity: false,
+            },
+            0,
+        ),
+        (
+            "复用t_fine（每10次）",
+            Channels {
+                pressure: true,
+                humidity: false,
+            },
+            9,
+        ),
+    ];
+    for (name, channels, reuse) in cases {
+        let mut clock = Clock::default();
+        let mut i2c = MockBme280::new(clock.clone());
+        let mut bme280 = match Bme280::new(&mut i2c, Some(address)) {
+            Ok(bme280) => bme280,
+            Err(err) => {
+                eprintln!("创建实例失败: {}", err);
+                return;
+            }
+        };
+        let init = i2c.transactions;
+        bme280.set_channels(channels);
+        bme280.set_t_fine_reuse(reuse);
+        let start = clock.now();
+        let mut reading = EnvReading::default();
+        for _ in 0..SAMPLES {
+            if let Err(err) = bme280.read_into(&mut i2c, &mut clock, &mut reading) {
+                eprintln!("读取失败: {}", err);
+                return;
+            }
+        }
+        report(
+            name,
+            init,
+            i2c.transactions - init,
+            clock.now() - start,
+            &reading,
+        );
+    }
+}
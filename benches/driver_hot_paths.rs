@@ -0,0 +1,326 @@
+//! 驱动热点路径的CPU耗时
+//!
+//! 使用模拟引脚在主机上测量BME280补偿计算、HX711组装数据位、DHT11解码、步进序列生成和
+//! 跳变过滤每次采样的耗时。criterion输出详细统计，之后按每项的耗时上限检查，超出时以
+//! 非零状态退出，用于在CI中发现精度改进、重构导致的性能退化
+//!
+//! 耗时上限按Pi Zero（ARM11 1GHz）估算，在更快的主机上可以用`BENCH_BUDGET_SCALE`收紧，如：
+//! `BENCH_BUDGET_SCALE=0.2 cargo bench --bench driver_hot_paths`
+//!
+//! 运行：`cargo bench --bench driver_hot_paths`
+
+use std::convert::Infallible;
+use std::env;
+use std::hint::black_box;
+use std::process;
+use std::time::{Duration, Instant};
+
+use criterion::Criterion;
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+use raspi_sensor::core::EnvReading;
+use raspi_sensor::core::bme280::{Calibration, Channels, Compensation, RawData};
+use raspi_sensor::core::dht::{self, DhtModel, FRAME_BITS};
+use raspi_sensor::core::hx711::{ChannelGain, Hx711};
+use raspi_sensor::core::stepper::{Direction, Sequencer, StepMode, Stepper4};
+use raspi_sensor::outlier::{Method, SpikeDetector};
+
+/// 检查耗时上限时每轮的测量时间
+const ROUND_TIME: Duration = Duration::from_millis(20);
+
+/// 检查耗时上限的轮数（取最快的一轮，减少调度干扰）
+const ROUNDS: usize = 5;
+
+/// 不等待的延时
+struct NoDelay;
+
+impl DelayNs for NoDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
+
+/// 模拟引脚（输出忽略；输入时is_low总是为true，is_high按位模式循环）
+struct MockPin {
+    /// 位模式（高位在前）
+    pattern: u32,
+    /// 下一次读取的位
+    bit: u32,
+}
+
+impl MockPin {
+    fn new(pattern: u32) -> Self {
+        Self { pattern, bit: 0 }
+    }
+}
+
+impl ErrorType for MockPin {
+    type Error = Infallible;
+}
+
+impl InputPin for MockPin {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        let level = self.pattern >> (31 - self.bit) & 1 == 1;
+        self.bit = (self.bit + 1) % 32;
+        Ok(level)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+}
+
+impl OutputPin for MockPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// 热点路径
+struct Case {
+    /// 名称（分组/名称）
+    name: &'static str,
+    /// Pi Zero上每次执行的耗时上限
+    budget: Duration,
+    /// 执行一次
+    run: Box<dyn FnMut()>,
+}
+
+impl Case {
+    fn new(name: &'static str, budget_us: f64, run: impl FnMut() + 'static) -> Self {
+        Self {
+            name,
+            budget: Duration::from_secs_f64(budget_us / 1_000_000.0),
+            run: Box::new(run),
+        }
+    }
+}
+
+/// 数据手册中的BME280校准参数示例（与bme280_transactions相同）
+fn bme280_calibration() -> Calibration {
+    let tp: [u16; 12] = [
+        27504,
+        26435,
+        -1000i16 as u16,
+        36477,
+        -10685i16 as u16,
+        3024,
+        2855,
+        140,
+        -7i16 as u16,
+        15500,
+        -14600i16 as u16,
+        6000,
+    ];
+    let mut tp_bytes = [0u8; 26];
+    for (i, value) in tp.iter().enumerate() {
+        tp_bytes[i * 2..i * 2 + 2].copy_from_slice(&value.to_le_bytes());
+    }
+    tp_bytes[25] = 75;
+    let (h4, h5) = (324i16, 50i16);
+    let mut h = [0u8; 7];
+    h[..2].copy_from_slice(&362i16.to_le_bytes());
+    h[3] = (h4 >> 4) as u8;
+    h[4] = (h4 & 0x0F) as u8 | ((h5 & 0x0F) << 4) as u8;
+    h[5] = (h5 >> 4) as u8;
+    h[6] = 30;
+    Calibration::parse(&tp_bytes, &h)
+}
+
+/// DHT11的一帧数据（湿度55%，温度23.4℃）
+const DHT11_FRAME: [u8; 5] = [55, 0, 23, 4, 82];
+
+/// 一帧数据对应的高电平宽度（微秒）
+fn dht_pulses(frame: &[u8; 5]) -> [u32; FRAME_BITS] {
+    let mut widths = [0u32; FRAME_BITS];
+    for (i, width) in widths.iter_mut().enumerate() {
+        let one = frame[i / 8] & (0x80 >> (i % 8)) != 0;
+        *width = if one { 70 } else { 27 };
+    }
+    widths
+}
+
+/// 一帧数据对应的SPI采样波形（每个bit为1µs，与DhtSpiCapture的采样长度相同）
+#[cfg(feature = "dht11")]
+fn dht_waveform(frame: &[u8; 5]) -> Vec<u8> {
+    let mut levels = vec![true; 20];
+    // 响应脉冲
+    levels.extend([false; 80]);
+    levels.extend([true; 80]);
+    for width in dht_pulses(frame) {
+        levels.extend([false; 50]);
+        levels.extend(std::iter::repeat_n(true, width as usize));
+    }
+    levels.extend([false; 50]);
+    levels.resize(2048 * 8, true);
+    levels
+        .chunks(8)
+        .map(|bits| bits.iter().fold(0u8, |byte, bit| byte << 1 | *bit as u8))
+        .collect()
+}
+
+/// DHT11的SPI采样解码
+#[cfg(feature = "dht11")]
+fn dht_spi_case() -> Option<Case> {
+    use raspi_sensor::sensor::dht_capture;
+
+    let samples = dht_waveform(&DHT11_FRAME);
+    Some(Case::new("dht11/decode_spi_samples", 1000.0, move || {
+        black_box(dht_capture::decode(black_box(&samples), DhtModel::Dht11).ok());
+    }))
+}
+
+/// DHT11的SPI采样解码（没有启用dht11特性时跳过）
+#[cfg(not(feature = "dht11"))]
+fn dht_spi_case() -> Option<Case> {
+    None
+}
+
+/// 所有热点路径
+fn cases() -> Vec<Case> {
+    let calibration = bme280_calibration();
+    // adc_P=415148，adc_T=519888，adc_H=30000
+    let raw = RawData::parse(&[0x65, 0x5A, 0xC0, 0x7E, 0xED, 0x00, 0x75, 0x30]);
+    let mut reading = EnvReading::default();
+
+    let mut hx711 = match Hx711::new(
+        MockPin::new(0),
+        MockPin::new(0x8000_0F5A),
+        NoDelay,
+        ChannelGain::ChannelA128,
+    ) {
+        Ok(hx711) => hx711,
+        Err(err) => panic!("创建HX711实例失败: {}", err),
+    };
+
+    let pulses = dht_pulses(&DHT11_FRAME);
+    let mut sequencer = Sequencer::new(StepMode::HalfStep);
+    let pins = [(); 4].map(|_| MockPin::new(0));
+    let mut stepper = Stepper4::new(pins, NoDelay, StepMode::HalfStep);
+
+    let start = Instant::now();
+    let mut z_score = SpikeDetector::new(Method::z_score(0.5));
+    let mut rate_of_change = SpikeDetector::new(Method::rate_of_change(1.0, 0.5));
+    let mut tick = 0u64;
+    let mut next_sample = move || {
+        tick += 1;
+        // 缓慢变化并偶尔跳变的读数，每次间隔1秒
+        let value =
+            20.0 + (tick % 50) as f64 * 0.01 + if tick.is_multiple_of(97) { 15.0 } else { 0.0 };
+        (value, start + Duration::from_secs(tick))
+    };
+    let mut next_rate_sample = next_sample;
+
+    let cases = vec![
+        Case::new("bme280/compensate_float", 20.0, move || {
+            black_box(calibration.compensate(black_box(&raw)));
+        }),
+        Case::new("bme280/compensate_integer", 10.0, move || {
+            let (temperature, t_fine) =
+                calibration.compensate_temperature_with(raw.temperature, Compensation::Integer);
+            let pressure =
+                calibration.compensate_pressure_with(raw.pressure, t_fine, Compensation::Integer);
+            let humidity =
+                calibration.compensate_humidity_with(raw.humidity, t_fine, Compensation::Integer);
+            black_box((temperature, pressure, humidity));
+        }),
+        Case::new("bme280/compensate_pressure_only", 15.0, move || {
+            let channels = Channels {
+                pressure: true,
+                humidity: false,
+            };
+            calibration.compensate_into(black_box(&raw), channels, &mut reading);
+            black_box(&reading);
+        }),
+        Case::new("hx711/read_bits", 5.0, move || {
+            black_box(hx711.read(0).ok());
+        }),
+        Case::new("dht11/decode_frame", 2.0, move || {
+            let frame = dht::frame_from_pulses(black_box(&pulses));
+            black_box(dht::parse_frame(&frame, DhtModel::Dht11).ok());
+        }),
+        Case::new("stepper/sequence", 0.2, move || {
+            black_box(sequencer.step(Direction::Clockwise));
+        }),
+        Case::new("stepper/step_pins", 1.0, move || {
+            black_box(stepper.step(Direction::CounterClockwise).ok());
+        }),
+        Case::new("filter/z_score", 5.0, move || {
+            let (value, at) = next_sample();
+            black_box(z_score.check(value, at));
+        }),
+        Case::new("filter/rate_of_change", 1.0, move || {
+            let (value, at) = next_rate_sample();
+            black_box(rate_of_change.check(value, at));
+        }),
+    ];
+    cases.into_iter().chain(dht_spi_case()).collect()
+}
+
+/// 每次执行的耗时（多轮中最快的一轮的平均值）
+fn measure(run: &mut dyn FnMut()) -> Duration {
+    // 预热并估算每轮的执行次数
+    let start = Instant::now();
+    let mut iterations = 0u32;
+    while start.elapsed() < ROUND_TIME / 4 {
+        run();
+        iterations += 1;
+    }
+    let iterations = iterations.saturating_mul(4).max(1);
+    (0..ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..iterations {
+                run();
+            }
+            start.elapsed() / iterations
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+fn main() {
+    let mut criterion = Criterion::default().configure_from_args();
+    let mut cases = cases();
+    for case in cases.iter_mut() {
+        criterion.bench_function(case.name, |bencher| bencher.iter(&mut case.run));
+    }
+    criterion.final_summary();
+
+    // 按耗时上限检查
+    let scale = match env::var("BENCH_BUDGET_SCALE") {
+        Ok(scale) => match scale.parse::<f64>() {
+            Ok(scale) if scale > 0.0 => scale,
+            _ => {
+                eprintln!("BENCH_BUDGET_SCALE的值无效: {}", scale);
+                process::exit(2);
+            }
+        },
+        Err(_) => 1.0,
+    };
+    let mut exceeded = 0;
+    println!("{:<32} {:>12} {:>12}", "热点路径", "耗时", "上限");
+    for case in cases.iter_mut() {
+        let elapsed = measure(&mut case.run);
+        let budget = case.budget.mul_f64(scale);
+        let mark = if elapsed > budget {
+            exceeded += 1;
+            "❌"
+        } else {
+            "✅"
+        };
+        println!(
+            "{:<32} {:>12} {:>12} {}",
+            case.name,
+            format!("{:.2?}", elapsed),
+            format!("{:.2?}", budget),
+            mark
+        );
+    }
+    if exceeded > 0 {
+        eprintln!("{}项热点路径的耗时超过上限", exceeded);
+        process::exit(1);
+    }
+}
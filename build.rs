@@ -0,0 +1,17 @@
+// 启用grpc功能时由proto/raspi_sensor.proto生成gRPC代码（使用内置的protoc，不需要另外安装）
+
+fn main() {
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/raspi_sensor.proto");
+        let protoc = match protoc_bin_vendored::protoc_bin_path() {
+            Ok(protoc) => protoc,
+            Err(err) => panic!("找不到内置的protoc: {}", err),
+        };
+        // SAFETY: 构建脚本是单线程的
+        unsafe { std::env::set_var("PROTOC", protoc) };
+        if let Err(err) = tonic_build::compile_protos("proto/raspi_sensor.proto") {
+            panic!("生成gRPC代码失败: {}", err);
+        }
+    }
+}
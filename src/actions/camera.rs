@@ -0,0 +1,314 @@
+//! 传感器事件触发拍照
+//!
+//! 人体感应、门磁、称重变化等事件触发时调用`rpicam-still`（旧版系统为`libcamera-still`）
+//! 或自定义命令拍照，触发的传感器、读数和时间写入文件名或同名的JSON文件。两次拍照之间
+//! 至少间隔`debounce`，间隔内的事件直接丢弃，避免持续触发的传感器连续拍照占满存储
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde_json::json;
+
+use crate::alerts::AlertState;
+use crate::event_bus::{Event, EventBus, Topic};
+use crate::reading::Reading;
+use crate::schedule::UtcDateTime;
+#[cfg(feature = "button")]
+use crate::sensor::button::ButtonEvent;
+
+/// 默认的拍照程序（依次尝试）
+const STILL_PROGRAMS: [&str; 2] = ["rpicam-still", "libcamera-still"];
+
+/// 命令参数中替换为输出文件路径的占位符
+pub const OUTPUT_PLACEHOLDER: &str = "{output}";
+
+/// 触发拍照的事件
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptureEvent {
+    /// 触发的传感器或按钮名称
+    pub source: String,
+    /// 触发原因（告警规则名称、事件类型等）
+    pub label: String,
+    /// 触发时的读数
+    pub reading: Reading,
+    /// 触发时间
+    pub timestamp: SystemTime,
+}
+
+impl CaptureEvent {
+    /// 创建事件（触发时间为当前时间，读数为空）
+    pub fn new(source: &str, label: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            label: label.to_string(),
+            reading: Reading::new(),
+            timestamp: SystemTime::now(),
+        }
+    }
+
+    /// 设置触发时的读数
+    pub fn with_reading(mut self, reading: Reading) -> Self {
+        self.reading = reading;
+        self
+    }
+
+    /// 默认的触发条件：告警触发（不包括解除）、按钮或触摸等开关量输入按下
+    pub fn from_event(event: &Event) -> Option<Self> {
+        match event {
+            Event::Alert(alert) if alert.state == AlertState::Raised => Some(Self {
+                source: alert.sensor.clone(),
+                label: alert.rule.clone(),
+                reading: Reading::new().with(alert.quantity, alert.value),
+                timestamp: alert.timestamp,
+            }),
+            #[cfg(feature = "button")]
+            Event::Button {
+                source,
+                event: ButtonEvent::Pressed,
+            } => Some(Self::new(source, "pressed")),
+            _ => None,
+        }
+    }
+
+    /// UTC时间（如20260102-030405.678）
+    fn time_label(&self) -> String {
+        let t = UtcDateTime::from_system_time(self.timestamp);
+        format!(
+            "{:04}{:02}{:02}-{:02}{:02}{:02}.{:03}",
+            t.year, t.month, t.day, t.hour, t.minute, t.second, t.millisecond
+        )
+    }
+}
+
+/// 元数据的保存方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metadata {
+    /// 写入文件名（时间_来源_原因_物理量-数值）
+    Filename,
+    /// 写入同名的JSON文件（文件名只包含时间和来源）
+    Sidecar,
+    /// 同时写入文件名和JSON文件
+    Both,
+}
+
+/// 事件触发的相机
+///
+/// ```ignore
+/// let camera = CameraTrigger::new("/var/lib/raspi-sensor/captures")
+///     .with_debounce(Duration::from_secs(30))
+///     .with_metadata(Metadata::Sidecar);
+/// let _handle = camera.attach(&bus);
+/// ```
+pub struct CameraTrigger {
+    /// 图片目录
+    dir: PathBuf,
+    /// 自定义拍照命令（为None时使用rpicam-still/libcamera-still）
+    command: Option<Vec<String>>,
+    /// 图片扩展名
+    extension: String,
+    /// 两次拍照的最小间隔
+    debounce: Duration,
+    /// 元数据的保存方式
+    metadata: Metadata,
+    /// 上一次拍照的时间
+    last: Option<Instant>,
+}
+
+impl CameraTrigger {
+    /// 创建实例（默认间隔10秒，元数据写入文件名）
+    ///
+    /// - dir: 图片目录（不存在时自动创建）
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self {
+            dir: dir.into(),
+            command: None,
+            extension: "jpg".to_string(),
+            debounce: Duration::from_secs(10),
+            metadata: Metadata::Filename,
+            last: None,
+        }
+    }
+
+    /// 使用自定义拍照命令（第一个元素为程序，参数中的`{output}`替换为输出文件路径）
+    ///
+    /// 如USB摄像头：`["fswebcam", "-r", "1280x720", "--no-banner", "{output}"]`
+    pub fn with_command<S: Into<String>>(mut self, command: Vec<S>) -> Self {
+        self.command = Some(command.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// 设置图片扩展名（默认jpg）
+    pub fn with_extension(mut self, extension: &str) -> Self {
+        self.extension = extension.trim_start_matches('.').to_string();
+        self
+    }
+
+    /// 设置两次拍照的最小间隔
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// 设置元数据的保存方式
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// 图片目录
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 拍照，返回图片路径（距上一次拍照不足最小间隔时不拍照，返回None）
+    pub fn fire(&mut self, event: &CaptureEvent) -> anyhow::Result<Option<PathBuf>> {
+        if let Some(last) = self.last
+            && last.elapsed() < self.debounce
+        {
+            trace_event!(debug, source = %event.source, "距上一次拍照时间过短，忽略");
+            return Ok(None);
+        }
+        self.last = Some(Instant::now());
+
+        fs::create_dir_all(&self.dir)
+            .map_err(|err| anyhow::anyhow!("创建图片目录{}失败: {}", self.dir.display(), err))?;
+        let path = self.dir.join(self.file_name(event));
+        self.capture(&path)?;
+        if matches!(self.metadata, Metadata::Sidecar | Metadata::Both) {
+            let sidecar = path.with_extension("json");
+            let readings: serde_json::Map<String, serde_json::Value> = event
+                .reading
+                .iter()
+                .map(|(quantity, value)| (quantity.name().to_string(), json!(value)))
+                .collect();
+            let metadata = json!({
+                "image": path.file_name().map(|name| name.to_string_lossy()),
+                "source": event.source,
+                "label": event.label,
+                "timestamp": event
+                    .timestamp
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs_f64(),
+                "readings": readings,
+            });
+            fs::write(&sidecar, format!("{:#}\n", metadata))
+                .map_err(|err| anyhow::anyhow!("写入{}失败: {}", sidecar.display(), err))?;
+        }
+        trace_event!(info, source = %event.source, path = %path.display(), "拍照完成");
+        // OK
+        Ok(Some(path))
+    }
+
+    /// 图片文件名
+    fn file_name(&self, event: &CaptureEvent) -> String {
+        let mut parts = vec![event.time_label(), sanitize(&event.source)];
+        if matches!(self.metadata, Metadata::Filename | Metadata::Both) {
+            if !event.label.is_empty() {
+                parts.push(sanitize(&event.label));
+            }
+            for (quantity, value) in event.reading.iter() {
+                parts.push(sanitize(&format!("{}-{:.2}", quantity.name(), value)));
+            }
+        }
+        format!("{}.{}", parts.join("_"), self.extension)
+    }
+
+    /// 执行拍照命令
+    fn capture(&self, path: &Path) -> anyhow::Result<()> {
+        let output = path.to_string_lossy();
+        let output = match &self.command {
+            Some(command) => {
+                let (program, args) = command
+                    .split_first()
+                    .ok_or_else(|| anyhow::anyhow!("拍照命令为空"))?;
+                let args = args
+                    .iter()
+                    .map(|arg| arg.replace(OUTPUT_PLACEHOLDER, &output));
+                Command::new(program)
+                    .args(args)
+                    .output()
+                    .map_err(|err| anyhow::anyhow!("执行拍照命令{}失败: {}", program, err))?
+            }
+            None => {
+                let args = ["--nopreview", "--immediate", "-o", &output];
+                let mut result = Err(anyhow::anyhow!("没有找到rpicam-still或libcamera-still"));
+                for program in STILL_PROGRAMS {
+                    match Command::new(program).args(args).output() {
+                        Ok(output) => {
+                            result = Ok(output);
+                            break;
+                        }
+                        // 旧版系统只有libcamera-still
+                        Err(err) if err.kind() == ErrorKind::NotFound => continue,
+                        Err(err) => {
+                            return Err(anyhow::anyhow!("执行{}失败: {}", program, err));
+                        }
+                    }
+                }
+                result?
+            }
+        };
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "拍照失败（{}）: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        // OK
+        Ok(())
+    }
+
+    /// 订阅事件总线，按默认条件（告警触发、按钮按下）拍照
+    pub fn attach(self, bus: &EventBus) -> JoinHandle<()> {
+        self.attach_with(
+            bus,
+            &[Topic::Alert, Topic::Button],
+            CaptureEvent::from_event,
+        )
+    }
+
+    /// 订阅事件总线的指定主题，按自定义条件拍照（总线的所有副本都释放后退出）
+    ///
+    /// - filter: 返回Some时拍照，如称重变化超过阈值时：
+    ///   `|event| match event { Event::Reading(s) if s.sensor == "scale" => ..., _ => None }`
+    pub fn attach_with<F>(
+        mut self,
+        bus: &EventBus,
+        topics: &[Topic],
+        mut filter: F,
+    ) -> JoinHandle<()>
+    where
+        F: FnMut(&Event) -> Option<CaptureEvent> + Send + 'static,
+    {
+        let events = bus.subscribe(topics);
+        thread::spawn(move || {
+            for event in events {
+                let Some(capture) = filter(&event) else {
+                    continue;
+                };
+                if let Err(err) = self.fire(&capture) {
+                    eprintln!("{}触发拍照失败: {}", capture.source, err);
+                }
+            }
+        })
+    }
+}
+
+/// 文件名中只保留字母、数字、点和减号
+fn sanitize(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,186 @@
+//! 告警邮件通知
+//!
+//! 通过SMTP服务器发送纯文本邮件，主题和正文由模板生成（占位符见`actions::format_alert`）
+
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use lettre::message::Mailbox;
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+
+use super::{format_alert, notify_alerts};
+use crate::alerts::{AlertEngine, AlertEvent};
+
+/// 与SMTP服务器的连接加密方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Security {
+    /// 不加密（只用于局域网内的中继）
+    None,
+    /// 先建立明文连接再升级为TLS（通常为587端口）
+    StartTls,
+    /// 直接建立TLS连接（通常为465端口）
+    Tls,
+}
+
+impl Security {
+    /// 默认端口
+    pub fn default_port(&self) -> u16 {
+        match self {
+            Security::None => 25,
+            Security::StartTls => 587,
+            Security::Tls => 465,
+        }
+    }
+}
+
+/// 告警邮件通知
+///
+/// ```ignore
+/// let email = EmailNotifier::new("smtp.example.com", "pi@example.com", &["me@example.com"])?
+///     .with_credentials("pi@example.com", "password")
+///     .with_subject("[{state}] {sensor} {quantity} {value}{unit}");
+/// let _handle = email.attach(&mut alerts);
+/// ```
+pub struct EmailNotifier {
+    /// SMTP服务器
+    host: String,
+    /// 端口（为None时使用加密方式的默认端口）
+    port: Option<u16>,
+    /// 加密方式
+    security: Security,
+    /// 用户名和密码
+    credentials: Option<Credentials>,
+    /// 发件人
+    from: Mailbox,
+    /// 收件人
+    to: Vec<Mailbox>,
+    /// 主题模板
+    subject: String,
+    /// 正文模板
+    body: String,
+    /// 连接超时时间
+    timeout: Duration,
+    /// 告警解除时是否也发送
+    cleared: bool,
+}
+
+impl EmailNotifier {
+    /// 创建实例（STARTTLS，587端口，只发送告警触发）
+    ///
+    /// - host: SMTP服务器
+    /// - from: 发件人（如`树莓派 <pi@example.com>`）
+    /// - to: 收件人
+    pub fn new(host: &str, from: &str, to: &[&str]) -> anyhow::Result<Self> {
+        let parse = |address: &str| -> anyhow::Result<Mailbox> {
+            address
+                .parse()
+                .map_err(|err| anyhow::anyhow!("邮件地址{}格式错误: {}", address, err))
+        };
+        if to.is_empty() {
+            return Err(anyhow::anyhow!("没有收件人"));
+        }
+        // OK
+        Ok(Self {
+            host: host.to_string(),
+            port: None,
+            security: Security::StartTls,
+            credentials: None,
+            from: parse(from)?,
+            to: to
+                .iter()
+                .map(|address| parse(address))
+                .collect::<anyhow::Result<_>>()?,
+            subject: "[{state}] {rule}: {sensor} {quantity} {value}{unit}".to_string(),
+            body: "规则: {rule}\n传感器: {sensor}\n物理量: {quantity}\n读数: {value}{unit}\n状态: {state}\n时间: {time}\n"
+                .to_string(),
+            timeout: Duration::from_secs(30),
+            cleared: false,
+        })
+    }
+
+    /// 设置加密方式
+    pub fn with_security(mut self, security: Security) -> Self {
+        self.security = security;
+        self
+    }
+
+    /// 设置端口（默认使用加密方式的默认端口）
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// 设置登录的用户名和密码
+    pub fn with_credentials(mut self, username: &str, password: &str) -> Self {
+        self.credentials = Some(Credentials::new(username.to_string(), password.to_string()));
+        self
+    }
+
+    /// 设置主题模板
+    pub fn with_subject(mut self, subject: &str) -> Self {
+        self.subject = subject.to_string();
+        self
+    }
+
+    /// 设置正文模板
+    pub fn with_body(mut self, body: &str) -> Self {
+        self.body = body.to_string();
+        self
+    }
+
+    /// 设置连接超时时间
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 设置告警解除时是否也发送
+    pub fn with_cleared(mut self, cleared: bool) -> Self {
+        self.cleared = cleared;
+        self
+    }
+
+    /// 连接SMTP服务器
+    fn transport(&self) -> anyhow::Result<SmtpTransport> {
+        let builder = match self.security {
+            Security::None => SmtpTransport::builder_dangerous(&self.host),
+            Security::StartTls => SmtpTransport::starttls_relay(&self.host)?,
+            Security::Tls => SmtpTransport::relay(&self.host)?,
+        };
+        let mut builder = builder
+            .port(self.port.unwrap_or(self.security.default_port()))
+            .timeout(Some(self.timeout));
+        if let Some(credentials) = &self.credentials {
+            builder = builder.credentials(credentials.clone());
+        }
+        // OK
+        Ok(builder.build())
+    }
+
+    /// 发送一个告警
+    pub fn send(&self, event: &AlertEvent) -> anyhow::Result<()> {
+        let mut message = Message::builder()
+            .from(self.from.clone())
+            .subject(format_alert(&self.subject, event))
+            .header(ContentType::TEXT_PLAIN);
+        for to in &self.to {
+            message = message.to(to.clone());
+        }
+        let message = message
+            .body(format_alert(&self.body, event))
+            .map_err(|err| anyhow::anyhow!("生成邮件失败: {}", err))?;
+        self.transport()?
+            .send(&message)
+            .map_err(|err| anyhow::anyhow!("发送邮件到{}失败: {}", self.host, err))?;
+        // OK
+        Ok(())
+    }
+
+    /// 订阅告警引擎，告警触发时在独立线程中发送（发送频率由规则的冷却时间控制）
+    pub fn attach(self, engine: &mut AlertEngine) -> JoinHandle<()> {
+        let cleared = self.cleared;
+        notify_alerts(engine, "邮件", cleared, move |event| self.send(event))
+    }
+}
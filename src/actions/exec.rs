@@ -0,0 +1,390 @@
+//! 告警和读数触发外部命令
+//!
+//! 把告警规则接到任意脚本（发短信、重启服务等）：读数和告警的各字段通过`RASPI_`开头的环境变量
+//! 传给命令，命令在独立线程中运行，超时后强制结束；同时运行的命令数量有上限，达到上限时新的触发
+//! 直接跳过，避免慢速脚本堆积
+
+use std::io::Read;
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use super::unix_seconds;
+use crate::alerts::{AlertEvent, AlertState};
+use crate::reading::Sample;
+use crate::sink::Sink;
+
+/// 等待命令结束时检查状态的间隔
+const WAIT_INTERVAL: Duration = Duration::from_millis(20);
+
+/// 每个输出流最多保留的字节数
+const MAX_OUTPUT: usize = 16 * 1024;
+
+/// 命令结束后等待输出读取完成的最长时间
+const DRAIN_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// 命令的运行结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecOutput {
+    /// 退出码（被信号结束或超时时为None）
+    pub code: Option<i32>,
+    /// 是否超时被结束
+    pub timed_out: bool,
+    /// 标准输出（超过16KB的部分丢弃）
+    pub stdout: String,
+    /// 标准错误（超过16KB的部分丢弃）
+    pub stderr: String,
+    /// 运行时长
+    pub elapsed: Duration,
+}
+
+impl ExecOutput {
+    /// 是否正常退出且退出码为0
+    pub fn success(&self) -> bool {
+        !self.timed_out && self.code == Some(0)
+    }
+}
+
+/// 输出流的读取线程和已读取的内容
+type Captured = (Arc<Mutex<Vec<u8>>>, JoinHandle<()>);
+
+/// 命令完成回调
+type OutputCallback = Box<dyn FnMut(&ExecOutput) + Send>;
+
+/// 外部命令动作
+///
+/// 可以克隆，所有副本共享同时运行的命令数量
+///
+/// ```ignore
+/// let notify = ExecAction::new("/usr/local/bin/send-sms", &["13800000000"])
+///     .with_timeout(Duration::from_secs(20))
+///     .with_rule("freezer-warm");
+/// let action = notify.clone();
+/// alerts.on_alert(move |event| {
+///     let _ = action.alert(event);
+/// });
+/// ```
+///
+/// 告警触发时的环境变量：
+///
+/// - `RASPI_EVENT`: `alert`
+/// - `RASPI_RULE`、`RASPI_SENSOR`、`RASPI_QUANTITY`、`RASPI_VALUE`
+/// - `RASPI_STATE`: `raised`或`cleared`
+/// - `RASPI_TIMESTAMP`: Unix时间戳（秒）
+///
+/// 读数触发时的环境变量：
+///
+/// - `RASPI_EVENT`: `sample`
+/// - `RASPI_SENSOR`、`RASPI_READING`（所有物理量的文本形式）
+/// - `RASPI_TEMPERATURE`等：各物理量的数值
+/// - `RASPI_TIMESTAMP`: Unix时间戳（秒）
+#[derive(Clone)]
+pub struct ExecAction {
+    /// 程序
+    program: String,
+    /// 参数
+    args: Vec<String>,
+    /// 额外的环境变量
+    env: Vec<(String, String)>,
+    /// 超时时间
+    timeout: Duration,
+    /// 同时运行的命令数量上限
+    concurrency: usize,
+    /// 只响应指定规则的告警
+    rule: Option<String>,
+    /// 只响应告警触发（不包括解除）
+    raised_only: bool,
+    /// 只响应指定传感器的读数
+    sensor: Option<String>,
+    /// 正在运行的命令数量
+    running: Arc<AtomicUsize>,
+    /// 最近一次的运行结果
+    last: Arc<Mutex<Option<ExecOutput>>>,
+    /// 命令完成回调
+    callback: Option<Arc<Mutex<OutputCallback>>>,
+}
+
+impl ExecAction {
+    /// 创建动作（超时30秒，同时只运行一个命令，响应所有告警的触发和解除）
+    ///
+    /// - program: 程序路径（不在PATH中时使用绝对路径）
+    /// - args: 参数
+    pub fn new(program: &str, args: &[&str]) -> Self {
+        Self {
+            program: program.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            env: Vec::new(),
+            timeout: Duration::from_secs(30),
+            concurrency: 1,
+            rule: None,
+            raised_only: false,
+            sensor: None,
+            running: Arc::new(AtomicUsize::new(0)),
+            last: Arc::new(Mutex::new(None)),
+            callback: None,
+        }
+    }
+
+    /// 通过`sh -c`运行命令行（可以使用管道和重定向，环境变量用`$RASPI_VALUE`引用）
+    pub fn shell(command: &str) -> Self {
+        Self::new("sh", &["-c", command])
+    }
+
+    /// 添加环境变量
+    pub fn with_env(mut self, key: &str, value: &str) -> Self {
+        self.env.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// 设置超时时间（超时后强制结束命令）
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 设置同时运行的命令数量上限
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// 只响应指定规则的告警
+    pub fn with_rule(mut self, rule: &str) -> Self {
+        self.rule = Some(rule.to_string());
+        self
+    }
+
+    /// 只响应告警触发，忽略解除
+    pub fn raised_only(mut self) -> Self {
+        self.raised_only = true;
+        self
+    }
+
+    /// 作为读数输出时只响应指定传感器的读数
+    pub fn with_sensor(mut self, sensor: &str) -> Self {
+        self.sensor = Some(sensor.to_string());
+        self
+    }
+
+    /// 命令完成时回调（在命令的等待线程中调用）
+    pub fn on_output<F>(mut self, cb: F) -> Self
+    where
+        F: FnMut(&ExecOutput) + Send + 'static,
+    {
+        self.callback = Some(Arc::new(Mutex::new(Box::new(cb))));
+        self
+    }
+
+    /// 正在运行的命令数量
+    pub fn running(&self) -> usize {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// 最近一次的运行结果
+    pub fn last_output(&self) -> Option<ExecOutput> {
+        self.last.lock().ok().and_then(|last| last.clone())
+    }
+
+    /// 告警状态变化时运行命令（不匹配过滤条件时返回None）
+    pub fn alert(&self, event: &AlertEvent) -> anyhow::Result<Option<JoinHandle<()>>> {
+        if self.rule.as_ref().is_some_and(|rule| *rule != event.rule)
+            || (self.raised_only && event.state == AlertState::Cleared)
+        {
+            return Ok(None);
+        }
+        let state = match event.state {
+            AlertState::Raised => "raised",
+            AlertState::Cleared => "cleared",
+        };
+        let env = vec![
+            ("RASPI_EVENT".to_string(), "alert".to_string()),
+            ("RASPI_RULE".to_string(), event.rule.clone()),
+            ("RASPI_SENSOR".to_string(), event.sensor.clone()),
+            (
+                "RASPI_QUANTITY".to_string(),
+                event.quantity.name().to_string(),
+            ),
+            ("RASPI_VALUE".to_string(), event.value.to_string()),
+            ("RASPI_STATE".to_string(), state.to_string()),
+            ("RASPI_TIMESTAMP".to_string(), unix_seconds(event.timestamp)),
+        ];
+        self.spawn(env).map(Some)
+    }
+
+    /// 收到读数时运行命令（不匹配过滤条件时返回None）
+    pub fn sample(&self, sample: &Sample) -> anyhow::Result<Option<JoinHandle<()>>> {
+        if self
+            .sensor
+            .as_ref()
+            .is_some_and(|sensor| *sensor != sample.sensor)
+        {
+            return Ok(None);
+        }
+        let mut env = vec![
+            ("RASPI_EVENT".to_string(), "sample".to_string()),
+            ("RASPI_SENSOR".to_string(), sample.sensor.clone()),
+            ("RASPI_READING".to_string(), sample.reading.to_string()),
+            (
+                "RASPI_TIMESTAMP".to_string(),
+                unix_seconds(sample.timestamp),
+            ),
+        ];
+        for (quantity, value) in sample.reading.iter() {
+            let key = format!("RASPI_{}", quantity.name().to_ascii_uppercase());
+            env.push((key, value.to_string()));
+        }
+        self.spawn(env).map(Some)
+    }
+
+    /// 启动命令并在独立线程中等待结束
+    fn spawn(&self, env: Vec<(String, String)>) -> anyhow::Result<JoinHandle<()>> {
+        // 先占用名额，超过上限时退回
+        if self.running.fetch_add(1, Ordering::AcqRel) >= self.concurrency {
+            self.running.fetch_sub(1, Ordering::AcqRel);
+            return Err(anyhow::anyhow!(
+                "已有{}个命令在运行，跳过本次{}",
+                self.concurrency,
+                self.program
+            ));
+        }
+        let child = Command::new(&self.program)
+            .args(&self.args)
+            .envs(self.env.iter().cloned())
+            .envs(env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            // 独立的进程组，超时时连同命令启动的子进程一起结束
+            .process_group(0)
+            .spawn();
+        let child = match child {
+            Ok(child) => child,
+            Err(err) => {
+                self.running.fetch_sub(1, Ordering::AcqRel);
+                return Err(anyhow::anyhow!("启动命令{}失败: {}", self.program, err));
+            }
+        };
+
+        let action = self.clone();
+        let handle = thread::spawn(move || {
+            let output = action.wait(child);
+            action.running.fetch_sub(1, Ordering::AcqRel);
+            if output.timed_out {
+                eprintln!("命令{}超时（{:?}），已结束", action.program, action.timeout);
+            } else if !output.success() {
+                eprintln!(
+                    "命令{}退出码{:?}: {}",
+                    action.program,
+                    output.code,
+                    output.stderr.trim()
+                );
+            }
+            if let Some(callback) = &action.callback
+                && let Ok(mut cb) = callback.lock()
+            {
+                cb(&output);
+            }
+            if let Ok(mut last) = action.last.lock() {
+                *last = Some(output);
+            }
+        });
+        // OK
+        Ok(handle)
+    }
+
+    /// 等待命令结束（超时强制结束），同时读取输出
+    fn wait(&self, mut child: Child) -> ExecOutput {
+        let started = Instant::now();
+        // 输出较多时管道写满会阻塞命令，需要边运行边读取
+        let stdout = child.stdout.take().map(capture);
+        let stderr = child.stderr.take().map(capture);
+
+        let mut timed_out = false;
+        let status: Option<ExitStatus> = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break Some(status),
+                Ok(None) if started.elapsed() >= self.timeout => {
+                    timed_out = true;
+                    kill_group(&mut child);
+                    break child.wait().ok();
+                }
+                Ok(None) => thread::sleep(WAIT_INTERVAL),
+                Err(err) => {
+                    eprintln!("等待命令{}失败: {}", self.program, err);
+                    break None;
+                }
+            }
+        };
+        // 命令启动的后台进程可能继承输出管道一直不关闭，只等待一小段时间
+        let drained = Instant::now();
+        while [&stdout, &stderr].iter().any(|reader| {
+            reader
+                .as_ref()
+                .is_some_and(|(_, handle)| !handle.is_finished())
+        }) && drained.elapsed() < DRAIN_TIMEOUT
+        {
+            thread::sleep(WAIT_INTERVAL);
+        }
+        let collect = |reader: Option<Captured>| {
+            reader
+                .and_then(|(kept, _)| {
+                    kept.lock()
+                        .ok()
+                        .map(|kept| String::from_utf8_lossy(&kept).into_owned())
+                })
+                .unwrap_or_default()
+        };
+        ExecOutput {
+            code: if timed_out {
+                None
+            } else {
+                status.and_then(|status| status.code())
+            },
+            timed_out,
+            stdout: collect(stdout),
+            stderr: collect(stderr),
+            elapsed: started.elapsed(),
+        }
+    }
+}
+
+impl Sink for ExecAction {
+    fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        self.sample(sample)?;
+        Ok(())
+    }
+}
+
+/// 在独立线程中读取输出流（超过上限的部分丢弃）
+fn capture<R: Read + Send + 'static>(mut reader: R) -> Captured {
+    let kept = Arc::new(Mutex::new(Vec::new()));
+    let shared = kept.clone();
+    let handle = thread::spawn(move || {
+        let mut buf = [0u8; 1024];
+        while let Ok(n) = reader.read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+            if let Ok(mut kept) = shared.lock() {
+                let room = MAX_OUTPUT.saturating_sub(kept.len());
+                kept.extend_from_slice(&buf[..n.min(room)]);
+            }
+        }
+    });
+    (kept, handle)
+}
+
+/// 结束命令所在的整个进程组（`sh -c`等启动的子进程也一起结束）
+fn kill_group(child: &mut Child) {
+    let Ok(pid) = libc::pid_t::try_from(child.id()) else {
+        let _ = child.kill();
+        return;
+    };
+    // SAFETY: kill没有内存安全方面的前置条件，失败时检查返回值
+    if unsafe { libc::kill(-pid, libc::SIGKILL) } != 0 {
+        let _ = child.kill();
+    }
+}
@@ -0,0 +1,79 @@
+//! 事件触发的动作（拍照、运行命令、发送通知）
+
+#[cfg(any(feature = "webhook", feature = "email"))]
+use std::thread::{self, JoinHandle};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(any(feature = "webhook", feature = "email"))]
+use crate::alerts::AlertEngine;
+use crate::alerts::{AlertEvent, AlertState};
+use crate::schedule::UtcDateTime;
+
+#[cfg(feature = "camera")]
+pub mod camera;
+#[cfg(feature = "email")]
+pub mod email;
+#[cfg(feature = "exec")]
+pub mod exec;
+#[cfg(feature = "webhook")]
+pub mod webhook;
+
+/// 按模板格式化告警事件
+///
+/// 支持的占位符：`{rule}`、`{sensor}`、`{quantity}`、`{value}`、`{unit}`、
+/// `{state}`（raised/cleared）、`{timestamp}`（Unix时间戳）、`{time}`（UTC时间）
+pub fn format_alert(template: &str, event: &AlertEvent) -> String {
+    let t = UtcDateTime::from_system_time(event.timestamp);
+    let time = format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        t.year, t.month, t.day, t.hour, t.minute, t.second
+    );
+    let state = match event.state {
+        AlertState::Raised => "raised",
+        AlertState::Cleared => "cleared",
+    };
+    template
+        .replace("{rule}", &event.rule)
+        .replace("{sensor}", &event.sensor)
+        .replace("{quantity}", event.quantity.name())
+        .replace("{value}", &format!("{:.2}", event.value))
+        .replace("{unit}", event.quantity.unit())
+        .replace("{state}", state)
+        .replace("{timestamp}", &unix_seconds(event.timestamp))
+        .replace("{time}", &time)
+}
+
+/// Unix时间戳（秒，保留毫秒）
+fn unix_seconds(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map(|t| t.as_secs_f64())
+        .unwrap_or_default();
+    format!("{:.3}", seconds)
+}
+
+/// 订阅告警引擎，在独立线程中逐个发送通知（规则引擎释放后退出）
+///
+/// 发送频率由规则的冷却时间（`Rule::with_cooldown`）控制，网络较慢时不会阻塞规则引擎
+#[cfg(any(feature = "webhook", feature = "email"))]
+fn notify_alerts<F>(
+    engine: &mut AlertEngine,
+    name: &'static str,
+    cleared: bool,
+    mut send: F,
+) -> JoinHandle<()>
+where
+    F: FnMut(&AlertEvent) -> anyhow::Result<()> + Send + 'static,
+{
+    let events = engine.subscribe();
+    thread::spawn(move || {
+        for event in events {
+            if event.state == AlertState::Cleared && !cleared {
+                continue;
+            }
+            if let Err(err) = send(&event) {
+                eprintln!("{}发送告警{}失败: {}", name, event.rule, err);
+            }
+        }
+    })
+}
@@ -0,0 +1,154 @@
+//! 告警Webhook通知
+//!
+//! 告警触发时向指定地址POST一个JSON，JSON由模板生成，可以直接对接企业微信、钉钉、Slack、
+//! Home Assistant等的Webhook
+
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, UNIX_EPOCH};
+
+use serde_json::{Value, json};
+
+use super::{format_alert, notify_alerts};
+use crate::alerts::{AlertEngine, AlertEvent};
+
+/// 告警Webhook
+///
+/// ```ignore
+/// let webhook = Webhook::new("https://hooks.slack.com/services/...")
+///     .with_template(r#"{"text": "{sensor}的{quantity}为{value}{unit}（{rule}）"}"#)?;
+/// let _handle = webhook.attach(&mut alerts);
+/// ```
+pub struct Webhook {
+    /// 地址
+    url: String,
+    /// JSON模板
+    template: Value,
+    /// 额外的请求头
+    headers: Vec<(String, String)>,
+    /// 请求超时时间
+    timeout: Duration,
+    /// 失败后的重试次数
+    retries: u32,
+    /// 告警解除时是否也发送
+    cleared: bool,
+}
+
+impl Webhook {
+    /// 创建Webhook（默认模板包含告警的所有字段，超时10秒，失败重试2次，只发送告警触发）
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            template: json!({
+                "rule": "{rule}",
+                "sensor": "{sensor}",
+                "quantity": "{quantity}",
+                "value": "{value}",
+                "unit": "{unit}",
+                "state": "{state}",
+                "timestamp": "{timestamp}",
+            }),
+            headers: Vec::new(),
+            timeout: Duration::from_secs(10),
+            retries: 2,
+            cleared: false,
+        }
+    }
+
+    /// 设置JSON模板
+    ///
+    /// 所有字符串中的占位符（见`actions::format_alert`）替换为告警的字段；
+    /// 整个字符串为`"{value}"`或`"{timestamp}"`时替换为数字
+    pub fn with_template(mut self, template: &str) -> anyhow::Result<Self> {
+        self.template = serde_json::from_str(template)
+            .map_err(|err| anyhow::anyhow!("Webhook模板不是有效的JSON: {}", err))?;
+        // OK
+        Ok(self)
+    }
+
+    /// 添加请求头（如`Authorization`）
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// 设置请求超时时间
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 设置失败后的重试次数（每次重试前等待的时间依次增加1秒）
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// 设置告警解除时是否也发送
+    pub fn with_cleared(mut self, cleared: bool) -> Self {
+        self.cleared = cleared;
+        self
+    }
+
+    /// 按模板生成告警的JSON
+    pub fn render(&self, event: &AlertEvent) -> Value {
+        render_value(&self.template, event)
+    }
+
+    /// 发送一个告警（失败时按重试次数重试）
+    pub fn send(&self, event: &AlertEvent) -> anyhow::Result<()> {
+        let agent = ureq::AgentBuilder::new().timeout(self.timeout).build();
+        let body = self.render(event);
+        let mut attempt = 0;
+        loop {
+            let mut request = agent.post(&self.url);
+            for (name, value) in &self.headers {
+                request = request.set(name, value);
+            }
+            let err = match request.send_json(&body) {
+                Ok(_) => return Ok(()),
+                Err(ureq::Error::Status(code, response)) => {
+                    let text = response.into_string().unwrap_or_default();
+                    anyhow::anyhow!("Webhook返回{}: {}", code, text.trim())
+                }
+                Err(err) => anyhow::anyhow!("请求Webhook失败: {}", err),
+            };
+            if attempt >= self.retries {
+                return Err(err);
+            }
+            attempt += 1;
+            thread::sleep(Duration::from_secs(attempt as u64));
+        }
+    }
+
+    /// 订阅告警引擎，告警触发时在独立线程中发送（发送频率由规则的冷却时间控制）
+    pub fn attach(self, engine: &mut AlertEngine) -> JoinHandle<()> {
+        let cleared = self.cleared;
+        notify_alerts(engine, "Webhook", cleared, move |event| self.send(event))
+    }
+}
+
+/// 替换JSON中所有字符串的占位符
+fn render_value(template: &Value, event: &AlertEvent) -> Value {
+    match template {
+        Value::String(text) if text == "{value}" => json!(event.value),
+        Value::String(text) if text == "{timestamp}" => {
+            let seconds = event
+                .timestamp
+                .duration_since(UNIX_EPOCH)
+                .map(|t| t.as_secs_f64())
+                .unwrap_or_default();
+            json!(seconds)
+        }
+        Value::String(text) => Value::String(format_alert(text, event)),
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|item| render_value(item, event)).collect())
+        }
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| (key.clone(), render_value(value, event)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
@@ -0,0 +1,324 @@
+//! 可远程控制的执行器注册表
+//!
+//! MQTT、gRPC等远程接口共用同一个注册表：只有注册过的执行器可以被控制（允许列表），
+//! 每个执行器的两次命令之间至少间隔`min_interval`，间隔不足的命令被拒绝
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, TryLockError};
+use std::thread;
+use std::time::Duration;
+
+use crate::core::stepper::Direction;
+use crate::rate_limit::{Policy, RateLimit};
+use crate::scale_service::ScaleHandle;
+use crate::sensor::speed::ContinuousStepper;
+use crate::switch::{PwmSwitch, Switch};
+
+/// 执行器命令
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ActuatorCommand {
+    /// 打开或关闭（继电器、开关，文本为ON/OFF）
+    Switch(bool),
+    /// 设置占空比（PWM，文本为0.0~1.0或百分比如"50%"）
+    Duty(f64),
+    /// 运行指定步数（步进电机，文本为整数，负数为逆时针）
+    Move(i64),
+    /// 去皮（称重服务，文本内容任意）
+    Tare,
+}
+
+/// 执行器类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ActuatorKind {
+    /// 开关
+    Switch,
+    /// PWM输出
+    Pwm,
+    /// 步进电机
+    Stepper,
+    /// 称重服务
+    Scale,
+}
+
+impl ActuatorKind {
+    /// 名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Switch => "switch",
+            Self::Pwm => "pwm",
+            Self::Stepper => "stepper",
+            Self::Scale => "scale",
+        }
+    }
+
+    /// 解析命令文本
+    pub fn parse(&self, payload: &str) -> anyhow::Result<ActuatorCommand> {
+        let text = payload.trim();
+        match self {
+            Self::Switch => match text.to_ascii_lowercase().as_str() {
+                "on" | "1" | "true" => Ok(ActuatorCommand::Switch(true)),
+                "off" | "0" | "false" => Ok(ActuatorCommand::Switch(false)),
+                _ => Err(anyhow::anyhow!("开关命令无效: {}", text)),
+            },
+            Self::Pwm => {
+                let duty = match text.strip_suffix('%') {
+                    Some(percent) => percent.trim().parse::<f64>().map(|value| value / 100.0),
+                    None => text.parse::<f64>(),
+                }
+                .map_err(|_| anyhow::anyhow!("占空比命令无效: {}", text))?;
+                if !(0.0..=1.0).contains(&duty) {
+                    return Err(anyhow::anyhow!("占空比超出0~1范围: {}", text));
+                }
+                Ok(ActuatorCommand::Duty(duty))
+            }
+            Self::Stepper => text
+                .parse::<i64>()
+                .map(ActuatorCommand::Move)
+                .map_err(|_| anyhow::anyhow!("步数命令无效: {}", text)),
+            Self::Scale => Ok(ActuatorCommand::Tare),
+        }
+    }
+}
+
+/// 执行器
+enum Actuator {
+    /// 开关
+    Switch(Box<dyn Switch>),
+    /// PWM输出
+    Pwm(PwmSwitch),
+    /// 步进电机
+    Stepper(Arc<Mutex<dyn ContinuousStepper>>),
+    /// 称重服务
+    Scale(ScaleHandle),
+}
+
+impl Actuator {
+    /// 执行器类型
+    fn kind(&self) -> ActuatorKind {
+        match self {
+            Self::Switch(_) => ActuatorKind::Switch,
+            Self::Pwm(_) => ActuatorKind::Pwm,
+            Self::Stepper(_) => ActuatorKind::Stepper,
+            Self::Scale(_) => ActuatorKind::Scale,
+        }
+    }
+
+    /// 当前状态文本（开关为ON/OFF，PWM为占空比，称重服务为最新重量，步进电机没有状态）
+    fn state(&self) -> Option<String> {
+        match self {
+            Self::Switch(switch) => Some(if switch.is_on() { "ON" } else { "OFF" }.to_string()),
+            Self::Pwm(output) => Some(format!("{:.3}", output.level())),
+            Self::Stepper(_) => None,
+            Self::Scale(scale) => scale
+                .latest()
+                .map(|reading| format!("{:.1} g", reading.grams)),
+        }
+    }
+
+    /// 执行命令，返回执行后的状态文本
+    fn execute(&mut self, command: ActuatorCommand) -> anyhow::Result<String> {
+        match (self, command) {
+            (Self::Switch(switch), ActuatorCommand::Switch(on)) => {
+                switch.set(on)?;
+                Ok(if switch.is_on() { "ON" } else { "OFF" }.to_string())
+            }
+            (Self::Pwm(output), ActuatorCommand::Duty(duty)) => {
+                output.set_level(duty)?;
+                Ok(format!("{:.3}", output.level()))
+            }
+            (Self::Stepper(motor), ActuatorCommand::Move(steps)) => {
+                let direction = if steps >= 0 {
+                    Direction::Clockwise
+                } else {
+                    Direction::CounterClockwise
+                };
+                let lock = || {
+                    motor
+                        .lock()
+                        .map_err(|_| anyhow::anyhow!("步进电机锁已损坏"))
+                };
+                for _ in 0..steps.unsigned_abs() {
+                    // 每步之后释放锁，运行期间其他线程可以修改转速
+                    let delay = {
+                        let mut motor = lock()?;
+                        if let Err(err) = motor.step(direction) {
+                            motor.save_position()?;
+                            return Err(err);
+                        }
+                        motor.step_delay()
+                    };
+                    thread::sleep(delay);
+                }
+                lock()?.save_position()?;
+                Ok(steps.to_string())
+            }
+            (Self::Scale(scale), ActuatorCommand::Tare) => {
+                scale.tare()?;
+                Ok("tared".to_string())
+            }
+            (_, command) => Err(anyhow::anyhow!("执行器不支持该命令: {:?}", command)),
+        }
+    }
+}
+
+/// 已注册的执行器
+struct Entry {
+    /// 执行器
+    actuator: Actuator,
+    /// 命令频率限制
+    limit: RateLimit,
+}
+
+/// 执行器注册表（可克隆，克隆后共享同一组执行器）
+///
+/// ```ignore
+/// let actuators = ActuatorRegistry::new().with_rate_limit(Duration::from_millis(500));
+/// actuators.add_switch("pump", GpioSwitch::new(22, false)?)?;
+/// actuators.add_pwm("fan", PwmSwitch::new(PwmWapper::hardware(Channel::Pwm0, 25000.0)?)?)?;
+/// actuators.invoke("pump", "ON")?;
+/// ```
+#[derive(Clone)]
+pub struct ActuatorRegistry {
+    /// 执行器（每个执行器单独加锁，步进电机运行期间不影响其他执行器）
+    entries: Arc<Mutex<BTreeMap<String, Arc<Mutex<Entry>>>>>,
+    /// 之后注册的执行器两次命令之间的最小间隔
+    min_interval: Duration,
+}
+
+impl Default for ActuatorRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActuatorRegistry {
+    /// 创建空的注册表（两次命令之间的最小间隔默认为1秒）
+    pub fn new() -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(BTreeMap::new())),
+            min_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// 设置之后注册的执行器两次命令之间的最小间隔
+    pub fn with_rate_limit(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    /// 注册执行器
+    fn add(&self, name: &str, actuator: Actuator) -> anyhow::Result<()> {
+        if name.is_empty() || name.contains(['/', '+', '#']) {
+            return Err(anyhow::anyhow!("执行器名称不能为空或包含/、+、#: {}", name));
+        }
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|_| anyhow::anyhow!("执行器注册表锁已损坏"))?;
+        if entries.contains_key(name) {
+            return Err(anyhow::anyhow!("执行器名称重复: {}", name));
+        }
+        entries.insert(
+            name.to_string(),
+            Arc::new(Mutex::new(Entry {
+                actuator,
+                limit: RateLimit::new(self.min_interval, Policy::Error),
+            })),
+        );
+        Ok(())
+    }
+
+    /// 注册开关（继电器、GPIO输出等）
+    pub fn add_switch<W: Switch + 'static>(&self, name: &str, switch: W) -> anyhow::Result<()> {
+        self.add(name, Actuator::Switch(Box::new(switch)))
+    }
+
+    /// 注册PWM输出（风扇、调光等）
+    pub fn add_pwm(&self, name: &str, output: PwmSwitch) -> anyhow::Result<()> {
+        self.add(name, Actuator::Pwm(output))
+    }
+
+    /// 注册步进电机（共享的电机，可以同时用于本地控制）
+    pub fn add_stepper<M>(&self, name: &str, motor: Arc<Mutex<M>>) -> anyhow::Result<()>
+    where
+        M: ContinuousStepper + 'static,
+    {
+        self.add(name, Actuator::Stepper(motor))
+    }
+
+    /// 注册称重服务（去皮）
+    pub fn add_scale(&self, name: &str, scale: ScaleHandle) -> anyhow::Result<()> {
+        self.add(name, Actuator::Scale(scale))
+    }
+
+    /// 已注册的执行器名称和类型
+    pub fn list(&self) -> Vec<(String, ActuatorKind)> {
+        let Ok(entries) = self.entries.lock() else {
+            return Vec::new();
+        };
+        entries
+            .iter()
+            .filter_map(|(name, entry)| {
+                let kind = entry.lock().ok()?.actuator.kind();
+                Some((name.clone(), kind))
+            })
+            .collect()
+    }
+
+    /// 已注册的执行器名称和当前状态（正在执行命令时为busy，不等待命令完成）
+    pub fn states(&self) -> Vec<(String, Option<String>)> {
+        let Ok(entries) = self.entries.lock() else {
+            return Vec::new();
+        };
+        entries
+            .iter()
+            .filter_map(|(name, entry)| match entry.try_lock() {
+                Ok(entry) => Some((name.clone(), entry.actuator.state())),
+                Err(TryLockError::WouldBlock) => Some((name.clone(), Some("busy".to_string()))),
+                Err(TryLockError::Poisoned(_)) => None,
+            })
+            .collect()
+    }
+
+    /// 执行器类型（未注册时返回错误）
+    pub fn kind(&self, name: &str) -> anyhow::Result<ActuatorKind> {
+        let entry = self.entry(name)?;
+        let kind = entry
+            .lock()
+            .map_err(|_| anyhow::anyhow!("执行器{}锁已损坏", name))?
+            .actuator
+            .kind();
+        // OK
+        Ok(kind)
+    }
+
+    /// 查找执行器（未注册时返回错误）
+    fn entry(&self, name: &str) -> anyhow::Result<Arc<Mutex<Entry>>> {
+        self.entries
+            .lock()
+            .map_err(|_| anyhow::anyhow!("执行器注册表锁已损坏"))?
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("执行器未注册: {}", name))
+    }
+
+    /// 执行命令，返回执行后的状态文本（开关为ON/OFF，PWM为占空比，步进电机为步数）
+    pub fn execute(&self, name: &str, command: ActuatorCommand) -> anyhow::Result<String> {
+        let entry = self.entry(name)?;
+        let mut entry = entry
+            .lock()
+            .map_err(|_| anyhow::anyhow!("执行器{}锁已损坏", name))?;
+        entry.limit.acquire()?;
+        let state = entry.actuator.execute(command)?;
+        trace_event!(info, actuator = name, state = %state, "执行执行器命令");
+        // OK
+        Ok(state)
+    }
+
+    /// 按执行器类型解析命令文本并执行
+    pub fn invoke(&self, name: &str, payload: &str) -> anyhow::Result<String> {
+        let command = self.kind(name)?.parse(payload)?;
+        self.execute(name, command)
+    }
+}
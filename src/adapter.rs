@@ -0,0 +1,755 @@
+#[cfg(any(feature = "aht30", feature = "bme280"))]
+use rppal::i2c::I2c;
+#[cfg(feature = "dht11")]
+use rppal::spi::{Bus, SlaveSelect};
+#[cfg(feature = "aht30")]
+use sensor_hal::aht30;
+#[cfg(feature = "bme280")]
+use sensor_hal::bme280;
+#[cfg(any(feature = "aht30", feature = "bme280", feature = "hx711"))]
+use std::thread;
+#[cfg(any(
+    feature = "dht11",
+    feature = "aht30",
+    feature = "bme280",
+    feature = "hx711",
+    feature = "bh1750"
+))]
+use std::time::Duration;
+#[cfg(feature = "hx711")]
+use std::time::Instant;
+#[cfg(any(feature = "aht30", feature = "bme280"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(any(feature = "dht11", feature = "hx711"))]
+use crate::capture::SignalCapture;
+#[cfg(any(feature = "aht30", feature = "bme280"))]
+use crate::core::EnvReading;
+#[cfg(feature = "bme280")]
+use crate::core::bme280::{Bme280, Compensation};
+#[cfg(feature = "dht11")]
+use crate::debug::{self, PinMode};
+#[cfg(any(
+    feature = "dht11",
+    feature = "aht30",
+    feature = "bme280",
+    feature = "hx711"
+))]
+use crate::diagnostics::DiagnosticsReport;
+#[cfg(feature = "dht11")]
+use crate::gpio::Pull;
+#[cfg(any(feature = "aht30", feature = "bme280"))]
+use crate::i2c_bus::SharedBus;
+use crate::manager::Sensor;
+#[cfg(any(feature = "dht11", feature = "aht30"))]
+use crate::rate_limit::{Policy, RateLimit};
+use crate::reading::{Quantity, Reading};
+use crate::scale::{Scale, WeightAdc};
+#[cfg(feature = "anemometer")]
+use crate::sensor::anemometer::Anemometer;
+#[cfg(feature = "bh1750")]
+use crate::sensor::bh1750::BH1750;
+#[cfg(feature = "dht11")]
+use crate::sensor::dht_capture::{self, DhtModel, DhtSpiCapture};
+#[cfg(feature = "dht11")]
+use crate::sensor::dht_gpio::DhtGpio;
+#[cfg(feature = "hx711")]
+use crate::sensor::hx711::HX711;
+#[cfg(feature = "nau7802")]
+use crate::sensor::nau7802::NAU7802;
+#[cfg(feature = "rain-gauge")]
+use crate::sensor::rain_gauge::RainGauge;
+#[cfg(feature = "soil-moisture")]
+use crate::sensor::soil_moisture::SoilMoisture;
+#[cfg(any(feature = "aht30", feature = "bme280"))]
+use crate::std_clock::{self, StdClock};
+
+/// 线程休眠实现的延时（供核心驱动使用）
+#[cfg(feature = "bme280")]
+struct SleepDelay;
+
+#[cfg(feature = "bme280")]
+impl embedded_hal::delay::DelayNs for SleepDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        thread::sleep(Duration::from_nanos(ns as u64));
+    }
+}
+
+/// 当前Unix毫秒时间戳
+#[cfg(any(feature = "aht30", feature = "bme280"))]
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// DHT11读取方式
+#[cfg(feature = "dht11")]
+enum Dht11Backend {
+    /// GPIO按时间读取
+    Gpio(DhtGpio),
+    /// SPI采样读取
+    SpiCapture(DhtSpiCapture),
+}
+
+/// DHT11温湿度传感器（单总线）
+#[cfg(feature = "dht11")]
+pub struct Dht11Sensor {
+    driver: Dht11Backend,
+    limit: RateLimit,
+    /// 数据线的信号捕获
+    capture: SignalCapture,
+}
+
+#[cfg(feature = "dht11")]
+impl Dht11Sensor {
+    /// 创建实例（起始信号期间睡眠，只在约5ms的数据传输期间忙等）
+    ///
+    /// - pin: 单总线接入的GPIO针脚
+    pub fn new(pin: u8) -> anyhow::Result<Self> {
+        let capture = SignalCapture::new(&["data"]);
+        let driver = DhtGpio::new(pin, DhtModel::Dht11)?.with_capture(capture.clone());
+        debug::claim(driver.pin(), "dht11", PinMode::InputOutput);
+        // OK
+        Ok(Self::with_backend(Dht11Backend::Gpio(driver), capture))
+    }
+
+    /// 使用SPI采样读取（数据线同时接SPI MISO和起始信号GPIO），不受线程调度抖动影响
+    ///
+    /// - bus: 数据线接入MISO的SPI总线
+    /// - slave_select: 占用的硬件片选（该引脚悬空）
+    /// - start_pin: 与数据线相连、用于发送起始信号的GPIO针脚
+    pub fn new_spi_capture(
+        bus: Bus,
+        slave_select: SlaveSelect,
+        start_pin: u8,
+    ) -> anyhow::Result<Self> {
+        let capture = DhtSpiCapture::new(bus, slave_select, start_pin, DhtModel::Dht11)?;
+        // OK
+        Ok(Self::with_backend(
+            Dht11Backend::SpiCapture(capture),
+            SignalCapture::new(&["data"]),
+        ))
+    }
+
+    fn with_backend(driver: Dht11Backend, capture: SignalCapture) -> Self {
+        // DHT11芯片必须间隔2秒以上才能读取下一次数据，否则会自热并返回上一次的数据
+        let limit = RateLimit::new(Duration::from_secs(2), Policy::Wait);
+        Self {
+            driver,
+            limit,
+            capture,
+        }
+    }
+
+    /// 设置读取频率限制（默认间隔2秒，间隔不足时等待）
+    pub fn set_rate_limit(&mut self, limit: RateLimit) {
+        self.limit = limit;
+    }
+
+    /// 设置数据线的内部上下拉（默认不修改，模块上没有上拉电阻时设置为上拉）
+    pub fn set_pull(&mut self, pull: Pull) {
+        match &mut self.driver {
+            Dht11Backend::Gpio(driver) => driver.set_pull(pull),
+            Dht11Backend::SpiCapture(capture) => capture.set_pull(pull),
+        }
+    }
+}
+
+#[cfg(feature = "dht11")]
+impl Sensor for Dht11Sensor {
+    fn kind(&self) -> &'static str {
+        "dht11"
+    }
+
+    fn min_interval(&self) -> Duration {
+        self.limit.min_interval()
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "dht11.read", level = "debug", skip_all, err(level = "warn"))
+    )]
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        self.limit.acquire()?;
+        self.capture.begin();
+        let result = match &mut self.driver {
+            Dht11Backend::Gpio(driver) => driver
+                .read()
+                .map_err(|err| anyhow::anyhow!("读取DHT11传感器失败: {}", err)),
+            Dht11Backend::SpiCapture(capture) => capture
+                .sample()
+                .and_then(|samples| {
+                    self.capture
+                        .record_samples(0, &samples, dht_capture::SAMPLE_PERIOD);
+                    dht_capture::decode(&samples, capture.model())
+                })
+                .map_err(|err| anyhow::anyhow!("读取DHT11传感器失败: {}", err)),
+        };
+        self.capture.finish();
+        let (temperature, humidity) = result?;
+        Ok(Reading::new()
+            .with(Quantity::Temperature, temperature)
+            .with(Quantity::Humidity, humidity))
+    }
+
+    fn signal_capture(&self) -> Option<SignalCapture> {
+        Some(self.capture.clone())
+    }
+
+    /// 检查响应脉冲和校验和
+    fn self_test(&mut self) -> DiagnosticsReport {
+        let mut report = DiagnosticsReport::new("dht11");
+        match Sensor::read(self) {
+            Ok(reading) => {
+                report.pass("响应脉冲", "收到响应");
+                report.pass("校验和", "正确");
+                let humidity = reading.humidity().unwrap_or_default();
+                if (0.0..=100.0).contains(&humidity) {
+                    report.pass("读数", reading);
+                } else {
+                    report.warn("读数", reading, "湿度超出范围，检查数据线长度和干扰");
+                }
+            }
+            Err(err) => {
+                let message = err.to_string();
+                let lower = message.to_lowercase();
+                if lower.contains("checksum") || message.contains("校验") {
+                    report.pass("响应脉冲", "收到响应");
+                    report.fail(
+                        "校验和",
+                        message,
+                        "数据位错误：缩短数据线，检查上拉电阻（4.7k~10k）和电源去耦",
+                    );
+                } else if lower.contains("timeout") || message.contains("超时") {
+                    report.fail(
+                        "响应脉冲",
+                        message,
+                        "没有收到响应脉冲：检查数据线接到配置的GPIO、上拉电阻（4.7k~10k）和3.3V/5V供电",
+                    );
+                } else {
+                    report.fail("读取", message, "检查接线和供电");
+                }
+            }
+        }
+        report
+    }
+}
+
+/// HX711噪声测量的采样次数
+#[cfg(feature = "hx711")]
+const NOISE_SAMPLES: usize = 10;
+
+/// HX711噪声标准差上限（ADC读数，增益128时约为满量程的0.01%）
+#[cfg(feature = "hx711")]
+const NOISE_LIMIT: f64 = 1000.0;
+
+/// I2C设备无应答时的接线提示
+#[cfg(any(feature = "aht30", feature = "bme280"))]
+const I2C_HINT: &str = "检查SDA(GPIO2)/SCL(GPIO3)接线、设备地址和3.3V供电，可以运行`raspi-sensor scan i2c`确认设备是否应答";
+
+/// AHT30温湿度传感器（I2C）
+///
+/// 默认使用rppal的I2C总线，也可以使用其他实现了embedded-hal I2C接口的总线（如回放记录的总线）
+#[cfg(feature = "aht30")]
+pub struct Aht30Sensor<I = I2c> {
+    i2c_bus: SharedBus<I>,
+    driver: aht30::Driver<'static, StdClock>,
+    limit: RateLimit,
+    /// 设备地址
+    address: u8,
+}
+
+#[cfg(feature = "aht30")]
+impl<I: embedded_hal::i2c::I2c> Aht30Sensor<I> {
+    /// 创建实例
+    ///
+    /// - i2c_bus: 共享的I2C通信总线（`SharedBus`或`Arc<Mutex<I>>`）
+    /// - address: 设备地址，为None时使用默认地址0x38
+    pub fn new<B: Into<SharedBus<I>>>(i2c_bus: B, address: Option<u8>) -> anyhow::Result<Self> {
+        let i2c_bus = i2c_bus.into();
+        let driver =
+            i2c_bus.transaction(|i2c| aht30::Driver::new(std_clock::global(), i2c, address))?;
+        // 连续测量会导致芯片自热，数据手册建议间隔1秒以上
+        let limit = RateLimit::new(Duration::from_secs(1), Policy::Wait);
+        // OK
+        Ok(Self {
+            i2c_bus,
+            driver,
+            limit,
+            address: address.unwrap_or(crate::core::aht30::DEFAULT_ADDRESS),
+        })
+    }
+
+    /// 设置读取频率限制（默认间隔1秒，间隔不足时等待）
+    pub fn set_rate_limit(&mut self, limit: RateLimit) {
+        self.limit = limit;
+    }
+
+    /// 发送命令字节后读取（AHT30没有寄存器地址，`start`为命令字节，如0x71读取状态）
+    ///
+    /// 供驱动未封装的功能使用，总线锁和设备地址与驱动一致
+    pub fn read_registers(&self, start: u8, buffer: &mut [u8]) -> anyhow::Result<()> {
+        self.i2c_bus.read_registers(self.address, start, buffer)
+    }
+
+    /// 发送命令字节和一个参数字节（如0xBA软复位后的初始化命令）
+    ///
+    /// 修改芯片状态后驱动不会感知，可能需要重新创建实例
+    pub fn write_register(&self, register: u8, value: u8) -> anyhow::Result<()> {
+        self.i2c_bus.write_register(self.address, register, value)
+    }
+
+    /// 读取温度和湿度
+    pub fn read_env(&mut self) -> anyhow::Result<EnvReading> {
+        self.limit.acquire()?;
+        let mut i2c = self.i2c_bus.lock();
+        let (temperature, humidity) = self
+            .driver
+            .read(&mut *i2c)
+            .map_err(|err| anyhow::anyhow!("读取AHT30传感器失败: {:?}", err))?;
+        // OK
+        Ok(EnvReading {
+            temperature: temperature as f64,
+            pressure: None,
+            humidity: Some(humidity as f64),
+            timestamp: Some(unix_millis()),
+        })
+    }
+}
+
+#[cfg(feature = "aht30")]
+impl<I: embedded_hal::i2c::I2c + Send> Sensor for Aht30Sensor<I> {
+    fn kind(&self) -> &'static str {
+        "aht30"
+    }
+
+    fn min_interval(&self) -> Duration {
+        self.limit.min_interval()
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "aht30.read", level = "debug", skip_all, err(level = "warn"))
+    )]
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        Ok(self.read_env()?.into())
+    }
+
+    /// 检查状态字节的校准位，并触发一次测量检查CRC
+    fn self_test(&mut self) -> DiagnosticsReport {
+        let mut report = DiagnosticsReport::new("aht30");
+        let address = self.address;
+        let mut i2c = self.i2c_bus.lock();
+        let mut status = [0u8; 1];
+        if let Err(err) = i2c.read(address, &mut status) {
+            report.fail(
+                "应答",
+                format!("0x{:02X}无应答: {:?}", address, err),
+                I2C_HINT,
+            );
+            return report;
+        }
+        report.pass("应答", format!("0x{:02X}", address));
+        if status[0] & 0x08 != 0 {
+            report.pass("校准位", format!("状态字节0x{:02X}", status[0]));
+        } else {
+            report.warn(
+                "校准位",
+                format!("状态字节0x{:02X}", status[0]),
+                "芯片未校准：断电重启传感器，仍未校准时芯片可能损坏",
+            );
+        }
+
+        let mut data = [0u8; 7];
+        let result = i2c
+            .write(address, &crate::core::aht30::CMD_TRIGGER)
+            .and_then(|_| {
+                thread::sleep(Duration::from_millis(crate::core::aht30::MEASURE_MS as u64));
+                i2c.read(address, &mut data)
+            });
+        if let Err(err) = result {
+            report.fail("测量", format!("{:?}", err), I2C_HINT);
+            return report;
+        }
+        if crate::core::aht30::is_busy(data[0]) {
+            report.warn("测量", "转换未完成", "芯片转换时间过长，检查供电电压");
+            return report;
+        }
+        match crate::core::aht30::parse(&data) {
+            Ok((temperature, humidity)) => {
+                report.pass("CRC", format!("0x{:02X}", data[6]));
+                report.pass(
+                    "读数",
+                    format!("温度{:.2}℃, 湿度{:.2}%", temperature, humidity),
+                );
+            }
+            Err(_) => {
+                report.fail(
+                    "CRC",
+                    format!("0x{:02X}与数据不符", data[6]),
+                    "数据传输错误：缩短I2C连线，检查上拉电阻，或降低I2C时钟频率",
+                );
+            }
+        }
+        report
+    }
+}
+
+/// BME280温湿度、气压传感器（I2C，总线类型同`Aht30Sensor`）
+#[cfg(feature = "bme280")]
+pub struct Bme280Sensor<I = I2c> {
+    i2c_bus: SharedBus<I>,
+    driver: bme280::Driver<'static, StdClock>,
+    /// 指定补偿算法时使用的核心驱动
+    core_driver: Option<Bme280>,
+    /// 设备地址
+    address: u8,
+}
+
+#[cfg(feature = "bme280")]
+impl<I: embedded_hal::i2c::I2c> Bme280Sensor<I> {
+    /// 创建实例
+    ///
+    /// - i2c_bus: 共享的I2C通信总线（`SharedBus`或`Arc<Mutex<I>>`）
+    /// - address: 设备地址，为None时使用默认地址
+    pub fn new<B: Into<SharedBus<I>>>(i2c_bus: B, address: Option<u8>) -> anyhow::Result<Self> {
+        let i2c_bus = i2c_bus.into();
+        let driver =
+            i2c_bus.transaction(|i2c| bme280::Driver::new(std_clock::global(), i2c, address))?;
+        // OK
+        Ok(Self {
+            i2c_bus,
+            driver,
+            core_driver: None,
+            address: address.unwrap_or(crate::core::bme280::DEFAULT_ADDRESS),
+        })
+    }
+
+    /// 指定补偿算法（浮点或整数），改用核心驱动读取
+    ///
+    /// 未指定时使用sensor-hal驱动自带的补偿计算
+    pub fn with_compensation(mut self, compensation: Compensation) -> anyhow::Result<Self> {
+        let mut driver = Bme280::new(&mut *self.i2c_bus.lock(), Some(self.address))
+            .map_err(|err| anyhow::anyhow!("初始化BME280传感器失败: {}", err))?;
+        driver.set_compensation(compensation);
+        self.core_driver = Some(driver);
+        // OK
+        Ok(self)
+    }
+
+    /// 当前的补偿算法（None为sensor-hal驱动自带的补偿计算）
+    pub fn compensation(&self) -> Option<Compensation> {
+        self.core_driver.as_ref().map(Bme280::compensation)
+    }
+
+    /// 从指定寄存器开始连续读取（如0xF5的config寄存器、0x88开始的校准参数）
+    ///
+    /// 供驱动未封装的功能使用，总线锁和设备地址与驱动一致
+    pub fn read_registers(&self, start: u8, buffer: &mut [u8]) -> anyhow::Result<()> {
+        self.i2c_bus.read_registers(self.address, start, buffer)
+    }
+
+    /// 写入单个寄存器（如修改0xF5的待机时间和IIR滤波系数）
+    ///
+    /// 驱动不会感知寄存器的修改，驱动重新配置芯片时会覆盖写入的值
+    pub fn write_register(&self, register: u8, value: u8) -> anyhow::Result<()> {
+        self.i2c_bus.write_register(self.address, register, value)
+    }
+
+    /// 读取温度、气压和湿度
+    pub fn read_env(&mut self) -> anyhow::Result<EnvReading> {
+        let mut i2c = self.i2c_bus.lock();
+        if let Some(driver) = &mut self.core_driver {
+            let mut reading = driver
+                .read(&mut *i2c, &mut SleepDelay)
+                .map_err(|err| anyhow::anyhow!("读取BME280传感器失败: {}", err))?;
+            reading.timestamp = Some(unix_millis());
+            return Ok(reading);
+        }
+        let (temperature, pressure, humidity) = self
+            .driver
+            .read(&mut *i2c)
+            .map_err(|err| anyhow::anyhow!("读取BME280传感器失败: {:?}", err))?;
+        // OK
+        Ok(EnvReading {
+            temperature: temperature as f64,
+            pressure: Some(pressure as f64),
+            humidity: Some(humidity as f64),
+            timestamp: Some(unix_millis()),
+        })
+    }
+}
+
+#[cfg(feature = "bme280")]
+impl<I: embedded_hal::i2c::I2c + Send> Sensor for Bme280Sensor<I> {
+    fn kind(&self) -> &'static str {
+        "bme280"
+    }
+
+    fn min_interval(&self) -> Duration {
+        Duration::from_millis(100)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "bme280.read", level = "debug", skip_all, err(level = "warn"))
+    )]
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        Ok(self.read_env()?.into())
+    }
+
+    /// 检查芯片ID和状态寄存器，并检查读数是否在量程内
+    fn self_test(&mut self) -> DiagnosticsReport {
+        use crate::core::bme280::{CHIP_ID, reg};
+
+        let mut report = DiagnosticsReport::new("bme280");
+        let address = self.address;
+        {
+            let mut i2c = self.i2c_bus.lock();
+            let mut id = [0u8; 1];
+            match i2c.write_read(address, &[reg::ID], &mut id) {
+                Ok(()) if id[0] == CHIP_ID => {
+                    report.pass("芯片ID", format!("0x{:02X}", id[0]));
+                }
+                Ok(()) if id[0] == 0x58 => {
+                    report.warn(
+                        "芯片ID",
+                        "0x58（BMP280）",
+                        "模块为BMP280，没有湿度测量，湿度读数无效",
+                    );
+                }
+                Ok(()) => {
+                    report.fail(
+                        "芯片ID",
+                        format!("0x{:02X}", id[0]),
+                        "不是BME280：检查配置的设备地址（SDO接地为0x76，接VCC为0x77）",
+                    );
+                    return report;
+                }
+                Err(err) => {
+                    report.fail(
+                        "芯片ID",
+                        format!("0x{:02X}无应答: {:?}", address, err),
+                        I2C_HINT,
+                    );
+                    return report;
+                }
+            }
+            // 校准参数复制（im_update）在上电后几毫秒内完成，一直为1说明芯片异常
+            let mut status = [0u8; 1];
+            let stuck = (0..3).all(|_| {
+                thread::sleep(Duration::from_millis(5));
+                i2c.write_read(address, &[reg::STATUS], &mut status).is_ok()
+                    && status[0] & 0x01 != 0
+            });
+            if stuck {
+                report.warn(
+                    "状态",
+                    format!("0x{:02X}", status[0]),
+                    "校准参数复制一直未完成：断电重启传感器",
+                );
+            } else {
+                report.pass("状态", format!("0x{:02X}", status[0]));
+            }
+        }
+        match self.read_env() {
+            Ok(env) => {
+                let pressure = env.pressure.unwrap_or_default();
+                if (-40.0..=85.0).contains(&env.temperature)
+                    && (30_000.0..=110_000.0).contains(&pressure)
+                {
+                    report.pass("读数", Reading::from(env));
+                } else {
+                    report.warn(
+                        "读数",
+                        Reading::from(env),
+                        "读数超出量程：校准参数可能读取错误，检查I2C连线质量",
+                    );
+                }
+            }
+            Err(err) => {
+                report.fail("读数", err, I2C_HINT);
+            }
+        }
+        report
+    }
+}
+
+#[cfg(feature = "hx711")]
+impl Sensor for HX711 {
+    fn kind(&self) -> &'static str {
+        "hx711"
+    }
+
+    fn min_interval(&self) -> Duration {
+        self.rate_limit().min_interval()
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "hx711.read", level = "debug", skip_all, err(level = "warn"))
+    )]
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        Ok(Reading::new().with(Quantity::Raw, HX711::read(self)? as f64))
+    }
+
+    fn signal_capture(&self) -> Option<SignalCapture> {
+        Some(HX711::signal_capture(self))
+    }
+
+    /// 检查数据就绪信号、满量程和噪声
+    fn self_test(&mut self) -> DiagnosticsReport {
+        let mut report = DiagnosticsReport::new("hx711");
+        // 最长等待两个转换周期
+        let timeout = self.rate().period() * 2;
+        let start = Instant::now();
+        while !self.is_ready() && start.elapsed() < timeout {
+            thread::sleep(Duration::from_millis(1));
+        }
+        if !self.is_ready() {
+            report.fail(
+                "数据就绪",
+                format!("DOUT在{}毫秒内一直为高电平", timeout.as_millis()),
+                "检查DT、SCK接线是否接反，VCC是否为2.6~5.5V，芯片是否处于掉电状态",
+            );
+            return report;
+        }
+        report.pass("数据就绪", "DOUT拉低");
+
+        let mut samples = Vec::with_capacity(NOISE_SAMPLES);
+        for _ in 0..NOISE_SAMPLES {
+            match HX711::read(self) {
+                Ok(value) => samples.push(value as f64),
+                Err(err) => {
+                    report.fail("读取", err, "检查SCK接线");
+                    return report;
+                }
+            }
+        }
+        // 24位补码满量程
+        if samples
+            .iter()
+            .any(|v| *v >= 8_388_607.0 || *v <= -8_388_608.0)
+        {
+            report.fail(
+                "量程",
+                "读数达到满量程",
+                "称重传感器输入开路或接反：检查E+/E-、A+/A-四根线",
+            );
+        } else {
+            report.pass("量程", "未饱和");
+        }
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let noise =
+            (samples.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / samples.len() as f64).sqrt();
+        if noise == 0.0 {
+            report.warn(
+                "噪声",
+                format!("{}次读数完全相同（{}）", NOISE_SAMPLES, mean),
+                "读数不变：检查A+/A-是否短路，或DOUT是否被固定为某个电平",
+            );
+        } else if noise > NOISE_LIMIT {
+            report.warn(
+                "噪声",
+                format!("标准差{:.0}（均值{:.0}）", noise, mean),
+                "噪声较大：使用屏蔽线，远离电机和开关电源，检查E+/E-供电是否稳定",
+            );
+        } else {
+            report.pass("噪声", format!("标准差{:.0}（均值{:.0}）", noise, mean));
+        }
+        report
+    }
+}
+
+#[cfg(feature = "nau7802")]
+impl Sensor for NAU7802 {
+    fn kind(&self) -> &'static str {
+        "nau7802"
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "nau7802.read", level = "debug", skip_all, err(level = "warn"))
+    )]
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        Ok(Reading::new().with(Quantity::Raw, NAU7802::read(self)? as f64))
+    }
+}
+
+/// 雨量计输出上一次读取后的降雨量
+#[cfg(feature = "rain-gauge")]
+impl Sensor for RainGauge {
+    fn kind(&self) -> &'static str {
+        "rain-gauge"
+    }
+
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        Ok(Reading::new().with(Quantity::Rainfall, RainGauge::read(self)))
+    }
+}
+
+/// 风速计输出上一次读取以来的平均风速和阵风风速
+#[cfg(feature = "anemometer")]
+impl Sensor for Anemometer {
+    fn kind(&self) -> &'static str {
+        "anemometer"
+    }
+
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        let wind = Anemometer::read(self);
+        Ok(Reading::new()
+            .with(Quantity::WindSpeed, wind.speed)
+            .with(Quantity::WindGust, wind.gust))
+    }
+}
+
+#[cfg(feature = "bh1750")]
+impl Sensor for BH1750 {
+    fn kind(&self) -> &'static str {
+        "bh1750"
+    }
+
+    fn min_interval(&self) -> Duration {
+        // 高分辨率模式的最长转换时间
+        Duration::from_millis(180)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "bh1750.read", level = "debug", skip_all, err(level = "warn"))
+    )]
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        Ok(Reading::new().with(Quantity::Illuminance, BH1750::read(self)?))
+    }
+}
+
+#[cfg(feature = "soil-moisture")]
+impl Sensor for SoilMoisture {
+    fn kind(&self) -> &'static str {
+        "soil-moisture"
+    }
+
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        Ok(Reading::new().with(Quantity::SoilMoisture, SoilMoisture::read(self)?))
+    }
+}
+
+/// 电子秤输出重量和ADC原始读数
+impl<A: WeightAdc + 'static> Sensor for Scale<A> {
+    fn kind(&self) -> &'static str {
+        "scale"
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "scale.read", level = "debug", skip_all, err(level = "warn"))
+    )]
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        let raw = self.read_average(self.samples())?;
+        Ok(Reading::new()
+            .with(Quantity::Weight, self.transform(raw)? as f64)
+            .with(Quantity::Raw, raw as f64))
+    }
+}
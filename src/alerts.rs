@@ -0,0 +1,456 @@
+use rppal::gpio::{Gpio, OutputPin};
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, SystemTime};
+
+use crate::debug::{self, PinMode};
+use crate::reading::{Quantity, Sample};
+use crate::sink::Sink;
+use crate::trend::{RateEstimator, Trend};
+
+/// 告警条件
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Condition {
+    /// 高于阈值
+    Above(f64),
+    /// 低于阈值
+    Below(f64),
+    /// 与时间窗口内最早的读数相比变化超过指定值（增加或减少）
+    Change {
+        /// 变化量
+        delta: f64,
+        /// 时间窗口
+        window: Duration,
+    },
+    /// 变化率（时间窗口内拟合的斜率）超过指定值，趋势为平稳时为变化率的绝对值低于指定值
+    Rate {
+        /// 趋势
+        trend: Trend,
+        /// 每`per`时间的变化量
+        rate: f64,
+        /// 变化率的时间单位
+        per: Duration,
+        /// 时间窗口
+        window: Duration,
+    },
+}
+
+/// 告警规则
+///
+/// 条件持续满足`duration`后触发；触发后需回落超过`hysteresis`才解除，避免在阈值附近反复触发；
+/// 两次触发之间至少间隔`cooldown`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    /// 规则名称
+    pub name: String,
+    /// 传感器名称
+    pub sensor: String,
+    /// 物理量
+    pub quantity: Quantity,
+    /// 告警条件
+    pub condition: Condition,
+    /// 回差
+    pub hysteresis: f64,
+    /// 条件需持续满足的时长
+    pub duration: Duration,
+    /// 两次触发之间的最小间隔
+    pub cooldown: Duration,
+}
+
+/// 解析时长（如"60s"、"5m"、"1h"，不带单位时为秒）
+fn parse_duration(text: &str) -> anyhow::Result<Duration> {
+    let (number, unit) = match text.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(i) => text.split_at(i),
+        None => (text, "s"),
+    };
+    let value: f64 = number
+        .parse()
+        .map_err(|_| anyhow::anyhow!("时长格式错误: {}", text))?;
+    let seconds = match unit {
+        "ms" => value / 1000.0,
+        "s" => value,
+        "m" | "min" => value * 60.0,
+        "h" => value * 3600.0,
+        _ => return Err(anyhow::anyhow!("时长单位错误: {}", text)),
+    };
+    // 负数、非有限值和超出范围的时长都视为格式错误
+    let duration = Duration::try_from_secs_f64(seconds)
+        .map_err(|_| anyhow::anyhow!("时长格式错误: {}", text))?;
+    // OK
+    Ok(duration)
+}
+
+impl Rule {
+    /// 创建规则（无回差、立即触发、无冷却时间）
+    pub fn new(name: &str, sensor: &str, quantity: Quantity, condition: Condition) -> Self {
+        Self {
+            name: name.to_string(),
+            sensor: sensor.to_string(),
+            quantity,
+            condition,
+            hysteresis: 0.0,
+            duration: Duration::ZERO,
+            cooldown: Duration::ZERO,
+        }
+    }
+
+    /// 解析文本形式的规则
+    ///
+    /// - `temperature > 30 for 60s`
+    /// - `humidity < 40`
+    /// - `weight change > 50 within 10s`
+    /// - `pressure falling > 200 per 3h`（时间窗口默认与时间单位相同）
+    /// - `temperature rising > 0.5 per 1m within 10m`
+    /// - `weight steady < 1 per 1m`
+    pub fn parse(name: &str, sensor: &str, text: &str) -> anyhow::Result<Self> {
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        let error = || anyhow::anyhow!("告警规则格式错误: {}", text);
+        let quantity = Quantity::from_name(tokens.first().ok_or_else(error)?)
+            .ok_or_else(|| anyhow::anyhow!("未知的物理量: {}", tokens[0]))?;
+        let number = |token: Option<&&str>| -> anyhow::Result<f64> {
+            token.ok_or_else(error)?.parse().map_err(|_| error())
+        };
+
+        let (condition, rest) = match tokens.get(1).copied() {
+            Some(">") => (Condition::Above(number(tokens.get(2))?), &tokens[3..]),
+            Some("<") => (Condition::Below(number(tokens.get(2))?), &tokens[3..]),
+            Some("change") if tokens.get(2) == Some(&">") => {
+                let delta = number(tokens.get(3))?;
+                match (tokens.get(4).copied(), tokens.get(5)) {
+                    (Some("within"), Some(window)) => (
+                        Condition::Change {
+                            delta,
+                            window: parse_duration(window)?,
+                        },
+                        &tokens[6..],
+                    ),
+                    _ => (
+                        Condition::Change {
+                            delta,
+                            window: Duration::from_secs(60),
+                        },
+                        &tokens[4..],
+                    ),
+                }
+            }
+            Some(name) if Trend::from_name(name).is_some() => {
+                let trend = Trend::from_name(name).ok_or_else(error)?;
+                let expected = if trend == Trend::Steady { "<" } else { ">" };
+                if tokens.get(2) != Some(&expected) || tokens.get(4) != Some(&"per") {
+                    return Err(error());
+                }
+                let rate = number(tokens.get(3))?;
+                let per = parse_duration(tokens.get(5).ok_or_else(error)?)?;
+                match (tokens.get(6).copied(), tokens.get(7)) {
+                    (Some("within"), Some(window)) => (
+                        Condition::Rate {
+                            trend,
+                            rate,
+                            per,
+                            window: parse_duration(window)?,
+                        },
+                        &tokens[8..],
+                    ),
+                    _ => (
+                        Condition::Rate {
+                            trend,
+                            rate,
+                            per,
+                            window: per,
+                        },
+                        &tokens[6..],
+                    ),
+                }
+            }
+            _ => return Err(error()),
+        };
+
+        let mut rule = Self::new(name, sensor, quantity, condition);
+        match rest {
+            [] => {}
+            ["for", duration] => rule.duration = parse_duration(duration)?,
+            _ => return Err(error()),
+        }
+        // OK
+        Ok(rule)
+    }
+
+    /// 设置回差
+    pub fn with_hysteresis(mut self, hysteresis: f64) -> Self {
+        self.hysteresis = hysteresis.abs();
+        self
+    }
+
+    /// 设置条件需持续满足的时长
+    pub fn for_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// 设置两次触发之间的最小间隔
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+}
+
+/// 告警状态变化
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AlertState {
+    /// 触发
+    Raised,
+    /// 解除
+    Cleared,
+}
+
+/// 告警事件
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AlertEvent {
+    /// 规则名称
+    pub rule: String,
+    /// 传感器名称
+    pub sensor: String,
+    /// 物理量
+    pub quantity: Quantity,
+    /// 触发或解除时的读数（变化量条件为变化量）
+    pub value: f64,
+    /// 状态变化
+    pub state: AlertState,
+    /// 读数时间
+    #[cfg_attr(feature = "serde", serde(with = "crate::reading::unix_seconds"))]
+    pub timestamp: SystemTime,
+}
+
+/// 规则的运行状态
+struct RuleState {
+    rule: Rule,
+    /// 是否处于告警中
+    active: bool,
+    /// 条件开始满足的时间
+    pending_since: Option<SystemTime>,
+    /// 上一次触发的时间
+    last_raised: Option<SystemTime>,
+    /// 变化量条件的时间窗口内的读数
+    window: VecDeque<(SystemTime, f64)>,
+    /// 变化率条件的拟合
+    rate: Option<RateEstimator>,
+    /// 告警期间保持有效的输出引脚（继电器、LED、蜂鸣器等）
+    outputs: Vec<(OutputPin, bool)>,
+}
+
+impl RuleState {
+    /// 计算条件的比较值（变化量条件为与窗口内最早读数的差，变化率条件为变化率，读数不足时为None）
+    fn observe(&mut self, timestamp: SystemTime, value: f64) -> Option<f64> {
+        if let Condition::Rate { per, .. } = self.rule.condition {
+            let estimator = self.rate.as_mut()?;
+            estimator.push(timestamp, value);
+            return estimator.rate(per);
+        }
+        Some(match self.rule.condition {
+            Condition::Change { window, .. } => {
+                self.window.push_back((timestamp, value));
+                while let Some((t, _)) = self.window.front()
+                    && timestamp.duration_since(*t).unwrap_or_default() > window
+                {
+                    self.window.pop_front();
+                }
+                self.window.front().map(|(_, v)| value - v).unwrap_or(0.0)
+            }
+            _ => value,
+        })
+    }
+
+    /// 是否满足触发条件
+    fn triggered(&self, value: f64) -> bool {
+        match self.rule.condition {
+            Condition::Above(threshold) => value > threshold,
+            Condition::Below(threshold) => value < threshold,
+            Condition::Change { delta, .. } => value.abs() > delta,
+            Condition::Rate { trend, rate, .. } => match trend {
+                Trend::Rising => value > rate,
+                Trend::Falling => value < -rate,
+                Trend::Steady => value.abs() < rate,
+            },
+        }
+    }
+
+    /// 告警中是否已回落到可以解除
+    fn recovered(&self, value: f64) -> bool {
+        let hysteresis = self.rule.hysteresis;
+        match self.rule.condition {
+            Condition::Above(threshold) => value <= threshold - hysteresis,
+            Condition::Below(threshold) => value >= threshold + hysteresis,
+            Condition::Change { delta, .. } => value.abs() <= delta - hysteresis,
+            Condition::Rate { trend, rate, .. } => match trend {
+                Trend::Rising => value <= rate - hysteresis,
+                Trend::Falling => value >= -(rate - hysteresis),
+                Trend::Steady => value.abs() >= rate + hysteresis,
+            },
+        }
+    }
+
+    /// 处理一个读数，状态变化时返回新状态和比较值
+    fn update(&mut self, timestamp: SystemTime, value: f64) -> Option<(AlertState, f64)> {
+        let value = self.observe(timestamp, value)?;
+        if self.active {
+            if self.recovered(value) {
+                self.active = false;
+                self.pending_since = None;
+                return Some((AlertState::Cleared, value));
+            }
+            return None;
+        }
+
+        if !self.triggered(value) {
+            self.pending_since = None;
+            return None;
+        }
+        let since = *self.pending_since.get_or_insert(timestamp);
+        let held = timestamp.duration_since(since).unwrap_or_default() >= self.rule.duration;
+        let cooled = self
+            .last_raised
+            .is_none_or(|t| timestamp.duration_since(t).unwrap_or_default() >= self.rule.cooldown);
+        if held && cooled {
+            self.active = true;
+            self.last_raised = Some(timestamp);
+            return Some((AlertState::Raised, value));
+        }
+        None
+    }
+}
+
+/// 告警回调
+type AlertCallback = Box<dyn FnMut(&AlertEvent) + Send>;
+
+/// 告警规则引擎
+///
+/// 实现了`Sink`，可以直接加入读数分发管道
+#[derive(Default)]
+pub struct AlertEngine {
+    /// 规则
+    rules: Vec<RuleState>,
+    /// 告警回调
+    callbacks: Vec<AlertCallback>,
+    /// 告警事件订阅者
+    subscribers: Vec<Sender<AlertEvent>>,
+}
+
+impl AlertEngine {
+    /// 创建空的规则引擎
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加规则
+    pub fn add_rule(&mut self, rule: Rule) -> anyhow::Result<()> {
+        if self.rules.iter().any(|state| state.rule.name == rule.name) {
+            return Err(anyhow::anyhow!("告警规则名称重复: {}", rule.name));
+        }
+        let rate = match rule.condition {
+            Condition::Rate { window, .. } => Some(RateEstimator::new(window)),
+            _ => None,
+        };
+        self.rules.push(RuleState {
+            rule,
+            active: false,
+            pending_since: None,
+            last_raised: None,
+            window: VecDeque::new(),
+            rate,
+            outputs: Vec::new(),
+        });
+        Ok(())
+    }
+
+    /// 告警期间驱动GPIO输出（继电器、LED、蜂鸣器等）
+    ///
+    /// - rule: 规则名称
+    /// - pin: 输出引脚
+    /// - active_high: 告警时输出高电平
+    pub fn bind_output(&mut self, rule: &str, pin: u8, active_high: bool) -> anyhow::Result<()> {
+        let state = self
+            .rules
+            .iter_mut()
+            .find(|state| state.rule.name == rule)
+            .ok_or_else(|| anyhow::anyhow!("告警规则不存在: {}", rule))?;
+        let pin = if active_high {
+            Gpio::new()?.get(pin)?.into_output_low()
+        } else {
+            Gpio::new()?.get(pin)?.into_output_high()
+        };
+        debug::claim(pin.pin(), "alerts", PinMode::Output);
+        state.outputs.push((pin, active_high));
+        Ok(())
+    }
+
+    /// 告警状态变化时回调
+    pub fn on_alert<F>(&mut self, cb: F)
+    where
+        F: FnMut(&AlertEvent) + Send + 'static,
+    {
+        self.callbacks.push(Box::new(cb));
+    }
+
+    /// 订阅告警事件
+    pub fn subscribe(&mut self) -> Receiver<AlertEvent> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.push(tx);
+        rx
+    }
+
+    /// 指定规则当前是否处于告警中
+    pub fn is_active(&self, rule: &str) -> bool {
+        self.rules
+            .iter()
+            .any(|state| state.rule.name == rule && state.active)
+    }
+
+    /// 处理一个读数，返回产生的告警事件
+    pub fn evaluate(&mut self, sample: &Sample) -> Vec<AlertEvent> {
+        let mut events = Vec::new();
+        for state in &mut self.rules {
+            if state.rule.sensor != sample.sensor {
+                continue;
+            }
+            let Some(value) = sample.reading.get(state.rule.quantity) else {
+                continue;
+            };
+            let Some((alert, value)) = state.update(sample.timestamp, value) else {
+                continue;
+            };
+            for (pin, active_high) in &mut state.outputs {
+                let on = alert == AlertState::Raised;
+                if on == *active_high {
+                    pin.set_high();
+                } else {
+                    pin.set_low();
+                }
+            }
+            events.push(AlertEvent {
+                rule: state.rule.name.clone(),
+                sensor: sample.sensor.clone(),
+                quantity: state.rule.quantity,
+                value,
+                state: alert,
+                timestamp: sample.timestamp,
+            });
+        }
+
+        for event in &events {
+            for cb in &mut self.callbacks {
+                cb(event);
+            }
+            self.subscribers.retain(|tx| tx.send(event.clone()).is_ok());
+        }
+        events
+    }
+}
+
+impl Sink for AlertEngine {
+    fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        self.evaluate(sample);
+        Ok(())
+    }
+}
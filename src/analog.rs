@@ -0,0 +1,22 @@
+/// 模拟量输入通道
+///
+/// ADC芯片的每个通道实现该接口后，摇杆、电位器等模拟传感器无需关心具体的ADC型号
+pub trait AnalogIn: Send {
+    /// 读取原始值
+    fn read_raw(&mut self) -> anyhow::Result<u32>;
+
+    /// 满量程对应的原始值（如10位ADC为1023）
+    fn max_raw(&self) -> u32;
+
+    /// 读取归一化值（0.0~1.0）
+    fn read_fraction(&mut self) -> anyhow::Result<f64> {
+        Ok(self.read_raw()? as f64 / self.max_raw() as f64)
+    }
+
+    /// 读取电压
+    ///
+    /// - vref: ADC参考电压
+    fn read_voltage(&mut self, vref: f64) -> anyhow::Result<f64> {
+        Ok(self.read_fraction()? * vref)
+    }
+}
@@ -0,0 +1,412 @@
+//! 恒温恒湿箱（孵化箱、爬宠箱、发酵箱）
+//!
+//! 一个温湿度传感器同时驱动加热继电器、加湿继电器和风扇PWM：加热和加湿为带回差的
+//! 开关控制，风扇为PID控制（默认温度高于设定值时加大转速），三路相互独立。
+//! 温湿度超出报警范围时通过告警规则引擎报警，每次读数写入数据记录（文件、SQLite等）
+
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::alerts::{AlertEngine, Condition, Rule};
+use crate::control::pid::Pid;
+use crate::manager::Sensor;
+use crate::reading::{Quantity, Reading, Sample};
+use crate::sink::Sink;
+use crate::switch::{PwmSwitch, Switch};
+
+/// 回差控制参数（加热为温度℃，加湿为相对湿度%）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HysteresisConfig {
+    /// 设定值
+    pub setpoint: f64,
+    /// 回差，测量值在设定值±回差/2范围内时保持当前状态
+    pub deadband: f64,
+    /// 打开后的最短运行时间
+    pub min_on: Duration,
+    /// 关闭后的最短停止时间
+    pub min_off: Duration,
+}
+
+/// 恒温恒湿箱参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClimateConfig {
+    /// 加热控制
+    pub temperature: HysteresisConfig,
+    /// 加湿控制
+    pub humidity: HysteresisConfig,
+    /// 温度报警范围（下限, 上限，℃）
+    pub temperature_alarm: Option<(f64, f64)>,
+    /// 湿度报警范围（下限, 上限，%）
+    pub humidity_alarm: Option<(f64, f64)>,
+    /// 超出报警范围持续多久后报警
+    pub alarm_delay: Duration,
+    /// 连续读取失败多少次后进入故障保护（关闭加热和加湿，风扇按故障输出运行）
+    pub max_failures: u32,
+    /// 故障保护时的风扇输出（0.0~1.0）
+    pub fan_failsafe: f64,
+}
+
+impl Default for ClimateConfig {
+    fn default() -> Self {
+        Self {
+            temperature: HysteresisConfig {
+                setpoint: 28.0,
+                deadband: 1.0,
+                min_on: Duration::from_secs(30),
+                min_off: Duration::from_secs(30),
+            },
+            humidity: HysteresisConfig {
+                setpoint: 60.0,
+                deadband: 6.0,
+                min_on: Duration::from_secs(10),
+                min_off: Duration::from_secs(30),
+            },
+            temperature_alarm: Some((20.0, 35.0)),
+            humidity_alarm: Some((30.0, 90.0)),
+            alarm_delay: Duration::from_secs(300),
+            max_failures: 3,
+            fan_failsafe: 1.0,
+        }
+    }
+}
+
+/// 恒温恒湿箱状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClimateStatus {
+    /// 温度（℃，读取失败时为None）
+    pub temperature: Option<f64>,
+    /// 相对湿度（%，读取失败时为None）
+    pub humidity: Option<f64>,
+    /// 加热是否打开
+    pub heater: bool,
+    /// 加湿是否打开
+    pub humidifier: bool,
+    /// 风扇输出（0.0~1.0）
+    pub fan: f64,
+    /// 是否处于故障保护中
+    pub failsafe: bool,
+}
+
+/// 带回差和最短运行/停止时间的开关控制（默认测量值低于设定值时打开）
+pub(crate) struct RelayLoop {
+    /// 执行器
+    switch: Box<dyn Switch>,
+    /// 上一次切换的时间
+    last_change: Option<Instant>,
+    /// 测量值高于设定值时打开（通风、降温等）
+    reversed: bool,
+}
+
+impl RelayLoop {
+    /// 创建实例（测量值低于设定值时打开）
+    pub(crate) fn new(switch: Box<dyn Switch>) -> Self {
+        Self {
+            switch,
+            last_change: None,
+            reversed: false,
+        }
+    }
+
+    /// 创建实例（测量值高于设定值时打开）
+    pub(crate) fn reversed(switch: Box<dyn Switch>) -> Self {
+        Self {
+            reversed: true,
+            ..Self::new(switch)
+        }
+    }
+
+    /// 是否打开
+    pub(crate) fn is_on(&self) -> bool {
+        self.switch.is_on()
+    }
+
+    /// 距上一次切换的时间（从未切换时为None）
+    pub(crate) fn since_change(&self) -> Option<Duration> {
+        self.last_change.map(|at| at.elapsed())
+    }
+
+    /// 切换执行器
+    pub(crate) fn switch_to(&mut self, on: bool) -> anyhow::Result<()> {
+        if self.switch.is_on() != on {
+            self.switch.set(on)?;
+            self.last_change = Some(Instant::now());
+        }
+        Ok(())
+    }
+
+    /// 根据测量值更新执行器状态
+    pub(crate) fn update(&mut self, value: f64, config: &HysteresisConfig) -> anyhow::Result<bool> {
+        let on = self.switch.is_on();
+        let half = config.deadband.abs() / 2.0;
+        let want = if value <= config.setpoint - half {
+            !self.reversed
+        } else if value >= config.setpoint + half {
+            self.reversed
+        } else {
+            on
+        };
+        let min_hold = if on { config.min_on } else { config.min_off };
+        if want != on && self.last_change.is_none_or(|at| at.elapsed() >= min_hold) {
+            self.switch_to(want)?;
+        }
+        Ok(self.switch.is_on())
+    }
+}
+
+/// 风扇PID控制
+struct FanLoop {
+    /// 输出
+    output: PwmSwitch,
+    /// 控制器
+    pid: Pid,
+    /// 控制的物理量
+    quantity: Quantity,
+    /// 上一次计算的时间
+    last_update: Option<Instant>,
+}
+
+/// 恒温恒湿箱
+///
+/// ```ignore
+/// let fan = PwmSwitch::new(PwmWapper::hardware(Channel::Pwm0, 25000.0)?)?;
+/// let mut chamber = ClimateChamber::new("terrarium", Aht30Sensor::new(bus, None)?, ClimateConfig::default())?
+///     .with_heater(GpioSwitch::new(22, false)?)
+///     .with_humidifier(GpioSwitch::new(27, false)?)
+///     .with_fan(fan, Pid::new(0.5, 0.02, 0.0, 30.0).reversed(), Quantity::Temperature)
+///     .with_sink(FileLogger::new("terrarium.csv", FileFormat::Csv)?);
+/// chamber.alerts().bind_output("temperature_high", 5, true)?;
+/// chamber.run(Duration::from_secs(10), |status| println!("{:?}", status));
+/// ```
+pub struct ClimateChamber {
+    /// 名称（数据记录和告警中的传感器名称）
+    name: String,
+    /// 温湿度传感器
+    sensor: Box<dyn Sensor>,
+    /// 参数
+    config: ClimateConfig,
+    /// 加热控制
+    heater: Option<RelayLoop>,
+    /// 加湿控制
+    humidifier: Option<RelayLoop>,
+    /// 风扇控制
+    fan: Option<FanLoop>,
+    /// 报警
+    alerts: AlertEngine,
+    /// 数据记录
+    sinks: Vec<Box<dyn Sink>>,
+    /// 连续读取失败次数
+    failures: u32,
+}
+
+impl ClimateChamber {
+    /// 创建实例（按参数中的报警范围生成告警规则）
+    ///
+    /// 告警规则名称为temperature_low、temperature_high、humidity_low和humidity_high
+    pub fn new<S>(name: &str, sensor: S, config: ClimateConfig) -> anyhow::Result<Self>
+    where
+        S: Sensor + 'static,
+    {
+        let mut alerts = AlertEngine::new();
+        let ranges = [
+            (Quantity::Temperature, config.temperature_alarm, 0.5),
+            (Quantity::Humidity, config.humidity_alarm, 2.0),
+        ];
+        for (quantity, range, hysteresis) in ranges {
+            let Some((low, high)) = range else {
+                continue;
+            };
+            for (suffix, condition) in [
+                ("low", Condition::Below(low)),
+                ("high", Condition::Above(high)),
+            ] {
+                alerts.add_rule(
+                    Rule::new(
+                        &format!("{}_{}", quantity.name(), suffix),
+                        name,
+                        quantity,
+                        condition,
+                    )
+                    .with_hysteresis(hysteresis)
+                    .for_duration(config.alarm_delay),
+                )?;
+            }
+        }
+        // OK
+        Ok(Self {
+            name: name.to_string(),
+            sensor: Box::new(sensor),
+            config,
+            heater: None,
+            humidifier: None,
+            fan: None,
+            alerts,
+            sinks: Vec::new(),
+            failures: 0,
+        })
+    }
+
+    /// 设置加热继电器（温度低于设定值时打开）
+    pub fn with_heater<W: Switch + 'static>(mut self, switch: W) -> Self {
+        self.heater = Some(RelayLoop::new(Box::new(switch)));
+        self
+    }
+
+    /// 设置加湿继电器（湿度低于设定值时打开）
+    pub fn with_humidifier<W: Switch + 'static>(mut self, switch: W) -> Self {
+        self.humidifier = Some(RelayLoop::new(Box::new(switch)));
+        self
+    }
+
+    /// 设置风扇PWM
+    ///
+    /// - pid: 控制器（降温、除湿时需要`reversed`）
+    /// - quantity: 控制的物理量（温度或湿度）
+    pub fn with_fan(mut self, output: PwmSwitch, pid: Pid, quantity: Quantity) -> Self {
+        self.fan = Some(FanLoop {
+            output,
+            pid,
+            quantity,
+            last_update: None,
+        });
+        self
+    }
+
+    /// 添加数据记录
+    pub fn with_sink<S: Sink + 'static>(mut self, sink: S) -> Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    /// 参数
+    pub fn config(&self) -> &ClimateConfig {
+        &self.config
+    }
+
+    /// 修改加热和加湿的设定值
+    pub fn set_setpoints(&mut self, temperature: f64, humidity: f64) {
+        self.config.temperature.setpoint = temperature;
+        self.config.humidity.setpoint = humidity;
+    }
+
+    /// 报警规则引擎（订阅告警、绑定蜂鸣器输出等）
+    pub fn alerts(&mut self) -> &mut AlertEngine {
+        &mut self.alerts
+    }
+
+    /// 进入故障保护
+    fn failsafe(&mut self) -> anyhow::Result<()> {
+        for relay in [self.heater.as_mut(), self.humidifier.as_mut()]
+            .into_iter()
+            .flatten()
+        {
+            relay.switch_to(false)?;
+        }
+        if let Some(fan) = self.fan.as_mut() {
+            fan.last_update = None;
+            fan.pid.reset();
+            fan.output.set_level(self.config.fan_failsafe)?;
+        }
+        Ok(())
+    }
+
+    /// 当前状态
+    fn status(&self, reading: Option<&Reading>, failsafe: bool) -> ClimateStatus {
+        let is_on =
+            |relay: &Option<RelayLoop>| relay.as_ref().is_some_and(|relay| relay.switch.is_on());
+        ClimateStatus {
+            temperature: reading.and_then(|reading| reading.get(Quantity::Temperature)),
+            humidity: reading.and_then(|reading| reading.get(Quantity::Humidity)),
+            heater: is_on(&self.heater),
+            humidifier: is_on(&self.humidifier),
+            fan: self.fan.as_ref().map_or(0.0, |fan| fan.output.level()),
+            failsafe,
+        }
+    }
+
+    /// 读取一次传感器，更新所有执行器，检查报警并写入数据记录
+    pub fn step(&mut self) -> anyhow::Result<ClimateStatus> {
+        let reading = match self.sensor.read() {
+            Ok(reading) => reading,
+            Err(err) => {
+                trace_event!(warn, error = %err, "恒温恒湿箱读取传感器失败");
+                eprintln!("恒温恒湿箱读取传感器失败: {}", err);
+                self.failures += 1;
+                let failsafe = self.failures >= self.config.max_failures;
+                if failsafe {
+                    self.failsafe()?;
+                }
+                return Ok(self.status(None, failsafe));
+            }
+        };
+        self.failures = 0;
+
+        if let (Some(relay), Some(temperature)) =
+            (self.heater.as_mut(), reading.get(Quantity::Temperature))
+        {
+            relay.update(temperature, &self.config.temperature)?;
+        }
+        if let (Some(relay), Some(humidity)) =
+            (self.humidifier.as_mut(), reading.get(Quantity::Humidity))
+        {
+            relay.update(humidity, &self.config.humidity)?;
+        }
+        if let Some(fan) = self.fan.as_mut()
+            && let Some(value) = reading.get(fan.quantity)
+        {
+            let now = Instant::now();
+            let dt = fan
+                .last_update
+                .map(|last| now.duration_since(last))
+                .unwrap_or_default();
+            fan.last_update = Some(now);
+            let output = fan.pid.update(value, dt);
+            fan.output.set_level(output)?;
+        }
+
+        let sample = Sample {
+            sensor: self.name.clone(),
+            timestamp: SystemTime::now(),
+            reading,
+        };
+        self.alerts.evaluate(&sample);
+        for sink in &mut self.sinks {
+            if let Err(err) = sink.write(&sample) {
+                eprintln!("恒温恒湿箱数据记录失败: {}", err);
+            }
+        }
+        // OK
+        Ok(self.status(Some(&sample.reading), false))
+    }
+
+    /// 在后台线程中按固定间隔运行（间隔不小于传感器的最小读取间隔）
+    ///
+    /// 每次更新后回调状态，执行器切换失败时线程退出并关闭所有执行器
+    pub fn run<F>(mut self, interval: Duration, mut cb: F) -> JoinHandle<()>
+    where
+        F: FnMut(&ClimateStatus) + Send + 'static,
+    {
+        let interval = interval.max(self.sensor.min_interval());
+        thread::spawn(move || {
+            loop {
+                match self.step() {
+                    Ok(status) => cb(&status),
+                    Err(err) => {
+                        eprintln!("恒温恒湿箱执行器切换失败: {}", err);
+                        for relay in [self.heater.as_mut(), self.humidifier.as_mut()]
+                            .into_iter()
+                            .flatten()
+                        {
+                            let _ = relay.switch.off();
+                        }
+                        if let Some(fan) = self.fan.as_mut() {
+                            let _ = fan.output.set_level(0.0);
+                        }
+                        break;
+                    }
+                }
+                thread::sleep(interval);
+            }
+        })
+    }
+}
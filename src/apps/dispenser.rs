@@ -0,0 +1,232 @@
+//! 定量出料器（宠物喂食器、饲料/咖啡豆分装）
+//!
+//! 步进电机（如ULN2003A驱动的28BYJ-48）带动螺旋推料杆，称重服务实时反馈出料重量，
+//! 接近目标时降低转速减小过冲，电机运行但重量长时间不变时判定为卡料并停止
+
+use std::sync::mpsc::Receiver;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::core::stepper::Direction;
+use crate::scale_service::{ScaleHandle, WeightReading, WeightStatus};
+use crate::sensor::speed::ContinuousStepper;
+
+/// 出料参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct DispenserConfig {
+    /// 推料方向
+    pub direction: Direction,
+    /// 减速区（克），剩余重量小于该值时降低转速
+    pub slow_zone: f64,
+    /// 减速后每步间隔的倍数
+    pub slow_factor: u32,
+    /// 落差（克），停止时仍在下落的重量，剩余重量小于该值时提前停止
+    pub in_flight: f64,
+    /// 卡料判断时间，电机运行期间重量在该时间内没有增加时判定为卡料
+    pub jam_timeout: Duration,
+    /// 卡料判断的最小重量增加（克）
+    pub jam_threshold: f64,
+    /// 停止后等待读数稳定的最长时间
+    pub settle_timeout: Duration,
+    /// 单次出料的最长时间
+    pub timeout: Duration,
+}
+
+impl Default for DispenserConfig {
+    fn default() -> Self {
+        Self {
+            direction: Direction::Clockwise,
+            slow_zone: 10.0,
+            slow_factor: 3,
+            in_flight: 0.0,
+            jam_timeout: Duration::from_secs(5),
+            jam_threshold: 1.0,
+            settle_timeout: Duration::from_secs(3),
+            timeout: Duration::from_secs(120),
+        }
+    }
+}
+
+/// 出料结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DispenseReport {
+    /// 目标重量（克）
+    pub target: f64,
+    /// 实际出料重量（克，停止并稳定后的重量变化）
+    pub dispensed: f64,
+    /// 运行步数
+    pub steps: u64,
+    /// 用时
+    pub duration: Duration,
+    /// 停止后读数是否稳定
+    pub settled: bool,
+}
+
+/// 定量出料器
+///
+/// ```ignore
+/// let motor = ULN2003A::new(6, 13, 19, 26, StepMode::HalfStep)?;
+/// let scale = ScaleService::new(adc, ScaleSettings::default()).start();
+/// let mut feeder = Dispenser::new(motor, scale, DispenserConfig::default());
+/// let report = feeder.dispense(30.0)?;
+/// println!("出料{:.1}g，用时{:?}", report.dispensed, report.duration);
+/// ```
+pub struct Dispenser<M: ContinuousStepper> {
+    /// 推料电机
+    motor: M,
+    /// 称重服务
+    scale: ScaleHandle,
+    /// 参数
+    config: DispenserConfig,
+}
+
+impl<M: ContinuousStepper> Dispenser<M> {
+    /// 创建实例（称重服务需要已经启动并完成去皮、矫正）
+    pub fn new(motor: M, scale: ScaleHandle, config: DispenserConfig) -> Self {
+        Self {
+            motor,
+            scale,
+            config,
+        }
+    }
+
+    /// 参数
+    pub fn config(&self) -> &DispenserConfig {
+        &self.config
+    }
+
+    /// 修改参数
+    pub fn set_config(&mut self, config: DispenserConfig) {
+        self.config = config;
+    }
+
+    /// 推料电机
+    pub fn motor(&mut self) -> &mut M {
+        &mut self.motor
+    }
+
+    /// 出料指定重量
+    ///
+    /// - target_grams: 目标重量（克）
+    ///
+    /// 以开始时的重量为基准，卡料、超时或称重出错时停止电机并返回错误（错误信息中包含已出料重量）
+    pub fn dispense(&mut self, target_grams: f64) -> anyhow::Result<DispenseReport> {
+        if !target_grams.is_finite() || target_grams <= 0.0 {
+            return Err(anyhow::anyhow!("目标重量必须大于0: {}", target_grams));
+        }
+        let readings = self.scale.subscribe();
+        let start_weight = match self.scale.latest() {
+            Some(reading) if reading.status != WeightStatus::Error => reading.grams,
+            _ => return Err(anyhow::anyhow!("称重服务没有有效读数，无法出料")),
+        };
+
+        let started = Instant::now();
+        let result = self.feed(&readings, start_weight, target_grams, started);
+        // 无论是否成功都保存位置
+        let saved = self.motor.save_position();
+        let steps = result?;
+        saved?;
+
+        // 等待落料结束、读数稳定
+        let (current, settled) = self.settle(&readings);
+        let dispensed = current.unwrap_or(start_weight) - start_weight;
+        trace_event!(
+            info,
+            target = target_grams,
+            dispensed,
+            steps,
+            "定量出料完成"
+        );
+        // OK
+        Ok(DispenseReport {
+            target: target_grams,
+            dispensed,
+            steps,
+            duration: started.elapsed(),
+            settled,
+        })
+    }
+
+    /// 运行电机直到达到目标重量，返回运行步数
+    fn feed(
+        &mut self,
+        readings: &Receiver<WeightReading>,
+        start_weight: f64,
+        target_grams: f64,
+        started: Instant,
+    ) -> anyhow::Result<u64> {
+        let mut current = start_weight;
+        let mut progress = (start_weight, Instant::now());
+        let mut steps = 0u64;
+        loop {
+            // 取最新的读数
+            for reading in readings.try_iter() {
+                match reading.status {
+                    WeightStatus::Error => {
+                        return Err(anyhow::anyhow!(
+                            "称重出错，停止出料: 已出料{:.1}g",
+                            current - start_weight
+                        ));
+                    }
+                    WeightStatus::Overload => {
+                        return Err(anyhow::anyhow!(
+                            "称重超载，停止出料: 已出料{:.1}g",
+                            reading.grams - start_weight
+                        ));
+                    }
+                    _ => current = reading.grams,
+                }
+            }
+
+            let remaining = target_grams - (current - start_weight);
+            if remaining <= self.config.in_flight {
+                return Ok(steps);
+            }
+            if current - progress.0 >= self.config.jam_threshold {
+                progress = (current, Instant::now());
+            } else if progress.1.elapsed() >= self.config.jam_timeout {
+                trace_event!(warn, dispensed = current - start_weight, "出料卡住");
+                return Err(anyhow::anyhow!(
+                    "出料卡住（{:?}内重量没有增加）: 已出料{:.1}g",
+                    self.config.jam_timeout,
+                    current - start_weight
+                ));
+            }
+            if started.elapsed() >= self.config.timeout {
+                return Err(anyhow::anyhow!(
+                    "出料超时: 已出料{:.1}g",
+                    current - start_weight
+                ));
+            }
+
+            self.motor.step(self.config.direction)?;
+            steps += 1;
+            let delay = self.motor.step_delay();
+            if remaining <= self.config.slow_zone {
+                thread::sleep(delay * self.config.slow_factor.max(1));
+            } else {
+                thread::sleep(delay);
+            }
+        }
+    }
+
+    /// 等待读数稳定，返回最后的重量和是否稳定
+    fn settle(&self, readings: &Receiver<WeightReading>) -> (Option<f64>, bool) {
+        let deadline = Instant::now() + self.config.settle_timeout;
+        let mut last = None;
+        while let Some(wait) = deadline.checked_duration_since(Instant::now()) {
+            match readings.recv_timeout(wait) {
+                Ok(reading) if reading.status == WeightStatus::Stable => {
+                    return (Some(reading.grams), true);
+                }
+                Ok(reading) if reading.status != WeightStatus::Error => {
+                    last = Some(reading.grams);
+                }
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+        (last, false)
+    }
+}
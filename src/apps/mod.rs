@@ -0,0 +1,4 @@
+//! 组合应用（把本库驱动的传感器和执行器组合为完整的子系统）
+
+pub mod climate_chamber;
+pub mod dispenser;
@@ -0,0 +1,353 @@
+//! 同型号传感器阵列
+//!
+//! 同一场景中经常安装多个相同的传感器（种植帐篷四角的DHT11、三台HX711电子秤）。
+//! `SensorArray`为每个传感器指定标签，按顺序错开读取——DHT11等软件模拟时序的传感器
+//! 同时读取会互相干扰时序——并把各传感器的读数汇总为一个读数（平均值、中位数等），
+//! 各传感器的读数和汇总统计可以通过`ArrayReportHandle`查看或转换为读数样本
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::diagnostics::{Check, DiagnosticsReport};
+use crate::manager::Sensor;
+use crate::reading::{Quantity, Reading, Sample};
+
+/// 汇总方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Aggregate {
+    /// 平均值
+    Mean,
+    /// 中位数（个别传感器偏差较大时更稳定）
+    Median,
+    /// 最小值
+    Min,
+    /// 最大值
+    Max,
+    /// 总和（如多台秤的总重量）
+    Sum,
+}
+
+impl Aggregate {
+    /// 计算汇总值（values不能为空）
+    fn apply(&self, values: &mut [f64]) -> f64 {
+        match self {
+            Aggregate::Mean => values.iter().sum::<f64>() / values.len() as f64,
+            Aggregate::Median => {
+                values.sort_by(f64::total_cmp);
+                let mid = values.len() / 2;
+                if values.len().is_multiple_of(2) {
+                    (values[mid - 1] + values[mid]) / 2.0
+                } else {
+                    values[mid]
+                }
+            }
+            Aggregate::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+            Aggregate::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Aggregate::Sum => values.iter().sum(),
+        }
+    }
+}
+
+impl std::str::FromStr for Aggregate {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> anyhow::Result<Self> {
+        match text {
+            "mean" => Ok(Aggregate::Mean),
+            "median" => Ok(Aggregate::Median),
+            "min" => Ok(Aggregate::Min),
+            "max" => Ok(Aggregate::Max),
+            "sum" => Ok(Aggregate::Sum),
+            _ => Err(anyhow::anyhow!("未知的汇总方式: {}", text)),
+        }
+    }
+}
+
+/// 单个传感器的最近一次读取结果
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct InstanceReading {
+    /// 标签
+    pub label: String,
+    /// 读数（读取失败时为None）
+    pub reading: Option<Reading>,
+    /// 读取失败的错误信息
+    pub error: Option<String>,
+    /// 读取时间
+    #[cfg_attr(feature = "serde", serde(with = "crate::reading::unix_seconds"))]
+    pub timestamp: SystemTime,
+}
+
+/// 单个物理量的汇总统计
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct QuantitySummary {
+    /// 参与汇总的传感器数量
+    pub count: usize,
+    /// 最小值
+    pub min: f64,
+    /// 最大值
+    pub max: f64,
+    /// 平均值
+    pub mean: f64,
+    /// 按汇总方式计算的值
+    pub value: f64,
+}
+
+impl QuantitySummary {
+    /// 最大值与最小值之差
+    pub fn spread(&self) -> f64 {
+        self.max - self.min
+    }
+}
+
+/// 最近一次读取的阵列报告
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ArrayReport {
+    /// 各传感器的读取结果（按添加顺序）
+    pub instances: Vec<InstanceReading>,
+    /// 各物理量的汇总统计
+    pub summary: BTreeMap<Quantity, QuantitySummary>,
+}
+
+impl ArrayReport {
+    /// 读取成功的传感器数量
+    pub fn ok_count(&self) -> usize {
+        self.instances
+            .iter()
+            .filter(|instance| instance.reading.is_some())
+            .count()
+    }
+
+    /// 各传感器的读数样本（传感器名称为`阵列名称.标签`）
+    pub fn to_samples(&self, array: &str) -> Vec<Sample> {
+        self.instances
+            .iter()
+            .filter_map(|instance| {
+                Some(Sample {
+                    sensor: format!("{}.{}", array, instance.label),
+                    timestamp: instance.timestamp,
+                    reading: instance.reading.clone()?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// 阵列报告（可克隆，传感器注册到管理器后仍然可以查看）
+#[derive(Debug, Clone, Default)]
+pub struct ArrayReportHandle {
+    report: Arc<Mutex<ArrayReport>>,
+}
+
+impl ArrayReportHandle {
+    /// 最近一次读取的阵列报告
+    pub fn snapshot(&self) -> ArrayReport {
+        self.report
+            .lock()
+            .map(|report| report.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// 同型号传感器阵列
+///
+/// ```ignore
+/// let tent = SensorArray::new("dht11")
+///     .with_instance("north-east", Dht11Sensor::new(4)?)
+///     .with_instance("north-west", Dht11Sensor::new(17)?)
+///     .with_instance("south-east", Dht11Sensor::new(27)?)
+///     .with_instance("south-west", Dht11Sensor::new(22)?)
+///     .with_aggregate(Aggregate::Median)
+///     .with_min_instances(2);
+/// let report = tent.report();
+/// manager.register("tent", tent)?;
+/// ```
+pub struct SensorArray {
+    /// 传感器型号
+    kind: &'static str,
+    /// 各传感器（标签, 传感器）
+    instances: Vec<(String, Box<dyn Sensor>)>,
+    /// 相邻两个传感器读取之间的间隔
+    gap: Duration,
+    /// 汇总方式
+    aggregate: Aggregate,
+    /// 至少需要读取成功的传感器数量
+    min_instances: usize,
+    /// 阵列报告
+    report: ArrayReportHandle,
+}
+
+impl SensorArray {
+    /// 创建空的阵列（平均值汇总，相邻读取间隔100毫秒，至少1个传感器读取成功）
+    ///
+    /// - kind: 传感器型号
+    pub fn new(kind: &'static str) -> Self {
+        Self {
+            kind,
+            instances: Vec::new(),
+            gap: Duration::from_millis(100),
+            aggregate: Aggregate::Mean,
+            min_instances: 1,
+            report: ArrayReportHandle::default(),
+        }
+    }
+
+    /// 添加传感器
+    pub fn with_instance<S: Sensor + 'static>(self, label: &str, sensor: S) -> Self {
+        self.with_boxed(label, Box::new(sensor))
+    }
+
+    /// 添加已装箱的传感器
+    pub fn with_boxed(mut self, label: &str, sensor: Box<dyn Sensor>) -> Self {
+        self.instances.push((label.to_string(), sensor));
+        self
+    }
+
+    /// 设置相邻两个传感器读取之间的间隔
+    pub fn with_gap(mut self, gap: Duration) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// 设置汇总方式
+    pub fn with_aggregate(mut self, aggregate: Aggregate) -> Self {
+        self.aggregate = aggregate;
+        self
+    }
+
+    /// 设置至少需要读取成功的传感器数量（不足时读取失败）
+    pub fn with_min_instances(mut self, min_instances: usize) -> Self {
+        self.min_instances = min_instances.max(1);
+        self
+    }
+
+    /// 传感器标签（按添加顺序）
+    pub fn labels(&self) -> Vec<String> {
+        self.instances
+            .iter()
+            .map(|(label, _)| label.clone())
+            .collect()
+    }
+
+    /// 传感器数量
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// 是否没有传感器
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+
+    /// 阵列报告
+    pub fn report(&self) -> ArrayReportHandle {
+        self.report.clone()
+    }
+}
+
+impl Sensor for SensorArray {
+    fn kind(&self) -> &'static str {
+        self.kind
+    }
+
+    /// 各传感器各自的最小间隔中最大的一个
+    fn min_interval(&self) -> Duration {
+        self.instances
+            .iter()
+            .map(|(_, sensor)| sensor.min_interval())
+            .max()
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// 依次自检所有传感器，合并为一份报告
+    fn self_test(&mut self) -> DiagnosticsReport {
+        let mut report = DiagnosticsReport::new(self.kind);
+        for (index, (label, sensor)) in self.instances.iter_mut().enumerate() {
+            if index > 0 {
+                thread::sleep(self.gap);
+            }
+            for check in sensor.self_test().checks {
+                report.checks.push(Check {
+                    name: format!("{}.{}", label, check.name),
+                    ..check
+                });
+            }
+        }
+        report
+    }
+
+    /// 按添加顺序依次读取，相邻两个传感器之间间隔`gap`，返回汇总后的读数
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        let mut instances = Vec::with_capacity(self.instances.len());
+        let mut values: BTreeMap<Quantity, Vec<f64>> = BTreeMap::new();
+        for (index, (label, sensor)) in self.instances.iter_mut().enumerate() {
+            if index > 0 && !self.gap.is_zero() {
+                thread::sleep(self.gap);
+            }
+            let result = sensor.read();
+            if let Ok(reading) = &result {
+                for (quantity, value) in reading.iter() {
+                    values.entry(quantity).or_default().push(value);
+                }
+            }
+            instances.push(InstanceReading {
+                label: label.clone(),
+                error: result.as_ref().err().map(|err| {
+                    trace_event!(warn, sensor = %label, error = %err, "阵列中的传感器读取失败");
+                    err.to_string()
+                }),
+                reading: result.ok(),
+                timestamp: SystemTime::now(),
+            });
+        }
+
+        let mut reading = Reading::new();
+        let mut summary = BTreeMap::new();
+        for (quantity, mut values) in values {
+            let count = values.len();
+            let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+            let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            let mean = values.iter().sum::<f64>() / count as f64;
+            let value = self.aggregate.apply(&mut values);
+            reading.set(quantity, value);
+            summary.insert(
+                quantity,
+                QuantitySummary {
+                    count,
+                    min,
+                    max,
+                    mean,
+                    value,
+                },
+            );
+        }
+
+        let report = ArrayReport { instances, summary };
+        let ok = report.ok_count();
+        let errors: Vec<String> = report
+            .instances
+            .iter()
+            .filter_map(|instance| {
+                Some(format!("{}: {}", instance.label, instance.error.as_ref()?))
+            })
+            .collect();
+        if let Ok(mut shared) = self.report.report.lock() {
+            *shared = report;
+        }
+        if ok < self.min_instances {
+            return Err(anyhow::anyhow!(
+                "阵列中读取成功的传感器不足{}个: {}",
+                self.min_instances,
+                errors.join("; ")
+            ));
+        }
+        // OK
+        Ok(reading)
+    }
+}
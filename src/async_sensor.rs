@@ -0,0 +1,312 @@
+use rppal::gpio::{Event, Gpio, InputPin, Trigger};
+use rppal::i2c::I2c;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+#[cfg(feature = "hx711")]
+use tokio::sync::Notify;
+use tokio::sync::mpsc;
+
+#[cfg(feature = "bme280")]
+use crate::adapter::Bme280Sensor;
+#[cfg(feature = "dht11")]
+use crate::adapter::Dht11Sensor;
+use crate::core::aht30;
+use crate::debug::{self, PinMode};
+use crate::i2c_bus::SharedBus;
+use crate::manager::Sensor;
+use crate::reading::{Quantity, Reading, Sample};
+#[cfg(feature = "hx711")]
+use crate::sensor::hx711::HX711;
+
+/// 在阻塞线程池中执行同步读取的异步传感器
+///
+/// - 软件模拟时序（DHT11）和带内部等待的驱动（BME280）在`spawn_blocking`中读取，不占用异步工作线程
+/// - 两次读取之间的最小间隔使用tokio定时器等待
+pub struct AsyncSensor<S: Sensor> {
+    /// 同步传感器
+    sensor: Arc<Mutex<S>>,
+    /// 两次读取之间的最小间隔
+    min_interval: Duration,
+    /// 上一次读取完成的时间
+    last: Option<Instant>,
+}
+
+impl<S: Sensor + 'static> AsyncSensor<S> {
+    /// 包装同步传感器
+    pub fn from_sensor(sensor: S) -> Self {
+        Self {
+            min_interval: sensor.min_interval(),
+            sensor: Arc::new(Mutex::new(sensor)),
+            last: None,
+        }
+    }
+
+    /// 读取一次数据（距上一次读取未达到最小间隔时先异步等待）
+    pub async fn read(&mut self) -> anyhow::Result<Reading> {
+        if let Some(last) = self.last {
+            tokio::time::sleep_until((last + self.min_interval).into()).await;
+        }
+        let sensor = self.sensor.clone();
+        let reading = tokio::task::spawn_blocking(move || {
+            sensor
+                .lock()
+                .map_err(|_| anyhow::anyhow!("传感器状态异常"))?
+                .read()
+        })
+        .await
+        .map_err(|err| anyhow::anyhow!("读取任务异常: {}", err))??;
+        self.last = Some(Instant::now());
+        // OK
+        Ok(reading)
+    }
+
+    /// 转换为按间隔持续读取的异步流（需要在tokio运行时中调用）
+    ///
+    /// - name: 传感器名称（写入样本）
+    /// - interval: 读取间隔（不小于传感器的最小间隔）
+    pub fn into_stream(mut self, name: &str, interval: Duration) -> AsyncSensorStream {
+        let name = name.to_string();
+        let interval = interval.max(self.min_interval);
+        let (tx, rx) = mpsc::channel(1);
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let sample = self.read().await.map(|reading| Sample::new(&name, reading));
+                if tx.send(sample).await.is_err() {
+                    break;
+                }
+            }
+        });
+        AsyncSensorStream { rx, task }
+    }
+}
+
+/// 按间隔持续读取传感器的异步流（实现`futures::Stream`，释放时停止读取）
+pub struct AsyncSensorStream {
+    /// 读数通道
+    rx: mpsc::Receiver<anyhow::Result<Sample>>,
+    /// 读取任务
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl futures_core::Stream for AsyncSensorStream {
+    type Item = anyhow::Result<Sample>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl Drop for AsyncSensorStream {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// 异步DHT11温湿度传感器
+#[cfg(feature = "dht11")]
+pub type AsyncDht11 = AsyncSensor<Dht11Sensor>;
+
+#[cfg(feature = "dht11")]
+impl AsyncDht11 {
+    /// 创建实例
+    ///
+    /// - pin: 单总线接入的GPIO针脚
+    pub fn new(pin: u8) -> anyhow::Result<Self> {
+        Ok(Self::from_sensor(Dht11Sensor::new(pin)?))
+    }
+}
+
+/// 异步BME280温湿度、气压传感器
+#[cfg(feature = "bme280")]
+pub type AsyncBme280 = AsyncSensor<Bme280Sensor>;
+
+#[cfg(feature = "bme280")]
+impl AsyncBme280 {
+    /// 创建实例
+    ///
+    /// - i2c_bus: 共享的I2C通信总线
+    /// - address: 设备地址，为None时使用默认地址
+    pub fn new<B: Into<SharedBus>>(i2c_bus: B, address: Option<u8>) -> anyhow::Result<Self> {
+        Ok(Self::from_sensor(Bme280Sensor::new(i2c_bus, address)?))
+    }
+}
+
+/// 异步AHT30温湿度传感器
+///
+/// 触发测量后使用tokio定时器等待转换完成（约80毫秒），等待期间不占用I2C总线
+pub struct AsyncAht30 {
+    /// 共享的I2C通信总线
+    i2c_bus: SharedBus,
+    /// 设备地址
+    address: u8,
+    /// 上一次读取完成的时间
+    last: Option<Instant>,
+}
+
+impl AsyncAht30 {
+    /// 创建实例
+    ///
+    /// - i2c_bus: 共享的I2C通信总线
+    /// - address: 设备地址，为None时使用默认地址0x38
+    pub fn new<B: Into<SharedBus>>(i2c_bus: B, address: Option<u8>) -> Self {
+        Self {
+            i2c_bus: i2c_bus.into(),
+            address: address.unwrap_or(aht30::DEFAULT_ADDRESS),
+            last: None,
+        }
+    }
+
+    /// 锁定总线并切换到本设备地址后执行操作
+    fn with_i2c<R, F>(&self, f: F) -> anyhow::Result<R>
+    where
+        F: FnOnce(&mut I2c) -> anyhow::Result<R>,
+    {
+        self.i2c_bus.transaction_at(self.address as u16, f)
+    }
+
+    /// 读取温度（℃）和湿度（%）
+    pub async fn read(&mut self) -> anyhow::Result<Reading> {
+        // 连续测量会导致芯片自热，间隔1秒以上
+        if let Some(last) = self.last {
+            tokio::time::sleep_until((last + Duration::from_secs(1)).into()).await;
+        }
+
+        // 触发测量
+        self.with_i2c(|i2c| {
+            i2c.write(&aht30::CMD_TRIGGER)?;
+            Ok(())
+        })?;
+
+        // 等待转换完成（状态字节最高位为0）
+        let mut data = [0u8; 7];
+        let mut ready = false;
+        for _ in 0..5 {
+            tokio::time::sleep(Duration::from_millis(aht30::MEASURE_MS as u64)).await;
+            self.with_i2c(|i2c| {
+                i2c.read(&mut data)?;
+                Ok(())
+            })?;
+            if !aht30::is_busy(data[0]) {
+                ready = true;
+                break;
+            }
+        }
+        self.last = Some(Instant::now());
+        if !ready {
+            return Err(anyhow::anyhow!("AHT30测量超时"));
+        }
+        let (temperature, humidity) =
+            aht30::parse(&data).map_err(|_| anyhow::anyhow!("AHT30数据校验失败"))?;
+        // OK
+        Ok(Reading::new()
+            .with(Quantity::Temperature, temperature)
+            .with(Quantity::Humidity, humidity))
+    }
+}
+
+/// 异步HX711称重ADC
+///
+/// 数据就绪（DOUT下降沿）通过GPIO中断唤醒等待的任务，无需轮询引脚
+#[cfg(feature = "hx711")]
+pub struct AsyncHx711 {
+    /// 同步驱动
+    hx711: Arc<Mutex<HX711>>,
+    /// 数据就绪通知
+    ready: Arc<Notify>,
+}
+
+#[cfg(feature = "hx711")]
+impl AsyncHx711 {
+    /// 包装同步驱动
+    pub fn new(mut hx711: HX711) -> anyhow::Result<Self> {
+        let ready = Arc::new(Notify::new());
+        let notify = ready.clone();
+        hx711.set_ready_callback(move |_| notify.notify_one())?;
+        // OK
+        Ok(Self {
+            hx711: Arc::new(Mutex::new(hx711)),
+            ready,
+        })
+    }
+
+    /// 数据是否就绪
+    fn is_ready(&self) -> anyhow::Result<bool> {
+        Ok(self
+            .hx711
+            .lock()
+            .map_err(|_| anyhow::anyhow!("HX711状态异常"))?
+            .is_ready())
+    }
+
+    /// 读取一次ADC读数（异步等待数据就绪）
+    pub async fn read(&mut self) -> anyhow::Result<i32> {
+        // 最长等待10SPS下的两个转换周期
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(250);
+        while !self.is_ready()? {
+            // 读取数据时DOUT的跳变也会触发中断，唤醒后需重新判断
+            if tokio::time::timeout_at(deadline, self.ready.notified())
+                .await
+                .is_err()
+            {
+                return Err(anyhow::anyhow!("HX711等待数据就绪超时"));
+            }
+        }
+        let hx711 = self.hx711.clone();
+        tokio::task::spawn_blocking(move || {
+            hx711
+                .lock()
+                .map_err(|_| anyhow::anyhow!("HX711状态异常"))?
+                .read()
+        })
+        .await
+        .map_err(|err| anyhow::anyhow!("读取任务异常: {}", err))?
+    }
+}
+
+/// 异步等待电平变化的输入引脚
+///
+/// GPIO中断回调将事件转发到tokio通道，在异步任务中`await`即可等待下一次电平变化
+pub struct AsyncInputPin {
+    /// 输入引脚
+    pin: InputPin,
+    /// 中断事件
+    events: mpsc::UnboundedReceiver<Event>,
+}
+
+impl AsyncInputPin {
+    /// 创建实例
+    ///
+    /// - pin: GPIO针脚（启用内部上拉）
+    /// - trigger: 触发条件
+    /// - debounce: 消抖时间（None为不消抖）
+    pub fn new(pin: u8, trigger: Trigger, debounce: Option<Duration>) -> anyhow::Result<Self> {
+        let mut pin = Gpio::new()?.get(pin)?.into_input_pullup();
+        let number = pin.pin();
+        debug::claim(number, "async-input", PinMode::Input);
+        let (tx, events) = mpsc::unbounded_channel();
+        pin.set_async_interrupt(trigger, debounce, move |event| {
+            debug::record_interrupt(number);
+            let _ = tx.send(event);
+        })?;
+        // OK
+        Ok(Self { pin, events })
+    }
+
+    /// 当前是否为高电平
+    pub fn is_high(&self) -> bool {
+        self.pin.is_high()
+    }
+
+    /// 等待下一次电平变化
+    pub async fn wait_for_edge(&mut self) -> anyhow::Result<Event> {
+        self.events
+            .recv()
+            .await
+            .ok_or_else(|| anyhow::anyhow!("GPIO中断已关闭"))
+    }
+}
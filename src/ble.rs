@@ -0,0 +1,393 @@
+//! 蓝牙低功耗（BLE）读数广播
+//!
+//! 通过BlueZ的D-Bus接口注册GATT应用，以标准的环境传感服务（Environmental Sensing
+//! Service，0x181A）提供温度、湿度和气压特征值（支持读取和通知），并可以在广播包中附带
+//! BTHome v2格式的服务数据，手机和Home Assistant的蓝牙代理不需要WiFi就能获取读数
+//!
+//! 需要BlueZ 5.50以上，运行用户需要有访问`org.bluez`的权限（root或加入bluetooth组）
+
+use std::collections::HashMap;
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use zbus::blocking::Connection;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+use zbus::{fdo, interface};
+
+use crate::reading::{Quantity, Sample};
+use crate::sink::Sink;
+
+/// 应用在D-Bus上的根路径
+const APP_PATH: &str = "/org/raspi_sensor/ble";
+
+/// 环境传感服务UUID
+const ESS_UUID: &str = "0000181a-0000-1000-8000-00805f9b34fb";
+
+/// BTHome服务数据UUID
+const BTHOME_UUID: &str = "0000fcd2-0000-1000-8000-00805f9b34fb";
+
+/// BTHome v2设备信息（未加密、定期广播）
+const BTHOME_DEVICE_INFO: u8 = 0x40;
+
+/// 蓝牙SIG标准UUID（16位短UUID展开为128位）
+fn sig_uuid(short: u16) -> String {
+    format!("0000{:04x}-0000-1000-8000-00805f9b34fb", short)
+}
+
+/// 提供的特征值（16位UUID、物理量）
+const CHARACTERISTICS: [(u16, Quantity); 3] = [
+    // Temperature：sint16，单位0.01℃
+    (0x2A6E, Quantity::Temperature),
+    // Humidity：uint16，单位0.01%
+    (0x2A6F, Quantity::Humidity),
+    // Pressure：uint32，单位0.1Pa
+    (0x2A6D, Quantity::Pressure),
+];
+
+/// 按环境传感服务的格式编码特征值
+fn encode_characteristic(quantity: Quantity, value: f64) -> Vec<u8> {
+    match quantity {
+        Quantity::Temperature => ((value * 100.0).round().clamp(-32767.0, 32767.0) as i16)
+            .to_le_bytes()
+            .to_vec(),
+        Quantity::Humidity => ((value * 100.0).round().clamp(0.0, 10000.0) as u16)
+            .to_le_bytes()
+            .to_vec(),
+        _ => ((value * 10.0).round().max(0.0) as u32)
+            .to_le_bytes()
+            .to_vec(),
+    }
+}
+
+/// 编码BTHome v2服务数据（对象按ID升序排列）
+///
+/// - packet_id: 包序号（接收端据此去重）
+pub fn bthome_payload(sample: &Sample, packet_id: u8) -> Vec<u8> {
+    let mut payload = vec![BTHOME_DEVICE_INFO, 0x00, packet_id];
+    if let Some(temperature) = sample.reading.get(Quantity::Temperature) {
+        // 0x02：温度，sint16，0.01℃
+        payload.push(0x02);
+        let value = (temperature * 100.0).round().clamp(-32767.0, 32767.0) as i16;
+        payload.extend_from_slice(&value.to_le_bytes());
+    }
+    if let Some(humidity) = sample.reading.get(Quantity::Humidity) {
+        // 0x03：湿度，uint16，0.01%
+        payload.push(0x03);
+        let value = (humidity * 100.0).round().clamp(0.0, 10000.0) as u16;
+        payload.extend_from_slice(&value.to_le_bytes());
+    }
+    if let Some(pressure) = sample.reading.get(Quantity::Pressure) {
+        // 0x04：气压，uint24，0.01hPa（即1Pa）
+        payload.push(0x04);
+        let value = pressure.round().clamp(0.0, 16_777_215.0) as u32;
+        payload.extend_from_slice(&value.to_le_bytes()[..3]);
+    }
+    payload
+}
+
+/// 广播和GATT配置
+#[derive(Debug, Clone)]
+pub struct BleConfig {
+    /// 蓝牙适配器名称
+    pub adapter: String,
+    /// 广播的设备名称
+    pub local_name: String,
+    /// 提供读数的传感器名称（其他传感器的读数被忽略）
+    pub sensor: String,
+    /// 是否在广播包中附带BTHome服务数据
+    pub bthome: bool,
+}
+
+impl BleConfig {
+    /// 创建默认配置（适配器hci0，附带BTHome服务数据）
+    ///
+    /// - local_name: 广播的设备名称
+    /// - sensor: 提供读数的传感器名称
+    pub fn new(local_name: &str, sensor: &str) -> Self {
+        Self {
+            adapter: "hci0".to_string(),
+            local_name: local_name.to_string(),
+            sensor: sensor.to_string(),
+            bthome: true,
+        }
+    }
+
+    /// 适配器在D-Bus上的路径
+    fn adapter_path(&self) -> String {
+        format!("/org/bluez/{}", self.adapter)
+    }
+}
+
+/// GATT服务（org.bluez.GattService1）
+struct GattService;
+
+#[interface(name = "org.bluez.GattService1")]
+impl GattService {
+    #[zbus(property, name = "UUID")]
+    fn uuid(&self) -> String {
+        ESS_UUID.to_string()
+    }
+
+    #[zbus(property)]
+    fn primary(&self) -> bool {
+        true
+    }
+}
+
+/// GATT特征值（org.bluez.GattCharacteristic1）
+struct GattCharacteristic {
+    /// 特征值UUID
+    uuid: String,
+    /// 所属服务的路径
+    service: OwnedObjectPath,
+    /// 当前值（还没有读数时为空）
+    value: Vec<u8>,
+    /// 是否有客户端订阅了通知
+    notifying: bool,
+}
+
+#[interface(name = "org.bluez.GattCharacteristic1")]
+impl GattCharacteristic {
+    fn read_value(&self, _options: HashMap<String, OwnedValue>) -> fdo::Result<Vec<u8>> {
+        if self.value.is_empty() {
+            return Err(fdo::Error::Failed("还没有读数".to_string()));
+        }
+        Ok(self.value.clone())
+    }
+
+    fn start_notify(&mut self) {
+        self.notifying = true;
+    }
+
+    fn stop_notify(&mut self) {
+        self.notifying = false;
+    }
+
+    #[zbus(property, name = "UUID")]
+    fn uuid(&self) -> String {
+        self.uuid.clone()
+    }
+
+    #[zbus(property)]
+    fn service(&self) -> OwnedObjectPath {
+        self.service.clone()
+    }
+
+    #[zbus(property)]
+    fn flags(&self) -> Vec<String> {
+        vec!["read".to_string(), "notify".to_string()]
+    }
+
+    #[zbus(property)]
+    fn value(&self) -> Vec<u8> {
+        self.value.clone()
+    }
+
+    #[zbus(property)]
+    fn notifying(&self) -> bool {
+        self.notifying
+    }
+}
+
+/// 广播（org.bluez.LEAdvertisement1）
+struct Advertisement {
+    /// 设备名称
+    local_name: String,
+    /// BTHome服务数据（None为不附带）
+    bthome: Option<Vec<u8>>,
+}
+
+#[interface(name = "org.bluez.LEAdvertisement1")]
+impl Advertisement {
+    fn release(&self) {}
+
+    #[zbus(property, name = "Type")]
+    fn kind(&self) -> String {
+        "peripheral".to_string()
+    }
+
+    #[zbus(property, name = "ServiceUUIDs")]
+    fn service_uuids(&self) -> Vec<String> {
+        vec![ESS_UUID.to_string()]
+    }
+
+    #[zbus(property)]
+    fn local_name(&self) -> String {
+        self.local_name.clone()
+    }
+
+    #[zbus(property)]
+    fn service_data(&self) -> fdo::Result<HashMap<String, OwnedValue>> {
+        let mut data = HashMap::new();
+        if let Some(payload) = &self.bthome {
+            let value = OwnedValue::try_from(Value::from(payload.clone()))
+                .map_err(|err| fdo::Error::Failed(err.to_string()))?;
+            data.insert(BTHOME_UUID.to_string(), value);
+        }
+        Ok(data)
+    }
+}
+
+/// BLE外设（可克隆，克隆后共享同一个D-Bus连接）
+///
+/// BlueZ只在注册广播时读取广播数据，附带BTHome服务数据时每个新读数都重新注册一次广播
+///
+/// ```ignore
+/// let ble = BlePeripheral::start(BleConfig::new("raspi-greenhouse", "greenhouse"))?;
+/// ble.record_from(scheduler.subscribe());
+/// ```
+#[derive(Clone)]
+pub struct BlePeripheral {
+    /// D-Bus系统总线连接
+    connection: Connection,
+    /// 配置
+    config: Arc<BleConfig>,
+    /// BTHome包序号
+    packet_id: Arc<Mutex<u8>>,
+}
+
+impl BlePeripheral {
+    /// 注册GATT应用和广播
+    pub fn start(config: BleConfig) -> anyhow::Result<Self> {
+        let connection = Connection::system()
+            .map_err(|err| anyhow::anyhow!("连接D-Bus系统总线失败: {}", err))?;
+        Self::export(&connection, &config)?;
+
+        let this = Self {
+            connection,
+            config: Arc::new(config),
+            packet_id: Arc::new(Mutex::new(0)),
+        };
+        this.call("org.bluez.GattManager1", "RegisterApplication", APP_PATH)
+            .map_err(|err| anyhow::anyhow!("注册GATT应用失败: {}", err))?;
+        this.call(
+            "org.bluez.LEAdvertisingManager1",
+            "RegisterAdvertisement",
+            &Self::advertisement_path(),
+        )
+        .map_err(|err| anyhow::anyhow!("注册BLE广播失败: {}", err))?;
+        // OK
+        Ok(this)
+    }
+
+    /// 在D-Bus上导出GATT应用和广播对象
+    fn export(connection: &Connection, config: &BleConfig) -> anyhow::Result<()> {
+        let server = connection.object_server();
+        server.at(APP_PATH, fdo::ObjectManager)?;
+        let service_path = format!("{}/service0", APP_PATH);
+        server.at(service_path.as_str(), GattService)?;
+        for (index, (uuid, _)) in CHARACTERISTICS.iter().enumerate() {
+            server.at(
+                format!("{}/char{}", service_path, index),
+                GattCharacteristic {
+                    uuid: sig_uuid(*uuid),
+                    service: ObjectPath::try_from(service_path.as_str())?.into(),
+                    value: Vec::new(),
+                    notifying: false,
+                },
+            )?;
+        }
+        server.at(
+            Self::advertisement_path(),
+            Advertisement {
+                local_name: config.local_name.clone(),
+                bthome: None,
+            },
+        )?;
+        Ok(())
+    }
+
+    /// 配置
+    pub fn config(&self) -> &BleConfig {
+        &self.config
+    }
+
+    /// 广播在D-Bus上的路径
+    fn advertisement_path() -> String {
+        format!("{}/advertisement0", APP_PATH)
+    }
+
+    /// 调用适配器上的BlueZ方法（参数为对象路径和空选项）
+    fn call(&self, interface: &str, method: &str, path: &str) -> anyhow::Result<()> {
+        let options: HashMap<&str, Value> = HashMap::new();
+        self.connection.call_method(
+            Some("org.bluez"),
+            self.config.adapter_path().as_str(),
+            Some(interface),
+            method,
+            &(ObjectPath::try_from(path)?, options),
+        )?;
+        Ok(())
+    }
+
+    /// 更新读数（特征值有订阅时发送通知，附带BTHome时重新注册广播）
+    pub fn update(&self, sample: &Sample) -> anyhow::Result<()> {
+        if sample.sensor != self.config.sensor {
+            return Ok(());
+        }
+        let server = self.connection.object_server();
+        for (index, (_, quantity)) in CHARACTERISTICS.iter().enumerate() {
+            let Some(value) = sample.reading.get(*quantity) else {
+                continue;
+            };
+            let path = format!("{}/service0/char{}", APP_PATH, index);
+            let characteristic = server.interface::<_, GattCharacteristic>(path.as_str())?;
+            let notifying = {
+                let mut characteristic = characteristic.get_mut();
+                characteristic.value = encode_characteristic(*quantity, value);
+                characteristic.notifying
+            };
+            if notifying {
+                let emitter = characteristic.signal_emitter();
+                zbus::block_on(characteristic.get().value_changed(emitter))?;
+            }
+        }
+
+        if self.config.bthome {
+            let packet_id = {
+                let mut packet_id = self
+                    .packet_id
+                    .lock()
+                    .map_err(|_| anyhow::anyhow!("BLE外设状态异常"))?;
+                *packet_id = packet_id.wrapping_add(1);
+                *packet_id
+            };
+            let path = Self::advertisement_path();
+            server
+                .interface::<_, Advertisement>(path.as_str())?
+                .get_mut()
+                .bthome = Some(bthome_payload(sample, packet_id));
+            let _ = self.call(
+                "org.bluez.LEAdvertisingManager1",
+                "UnregisterAdvertisement",
+                &path,
+            );
+            self.call(
+                "org.bluez.LEAdvertisingManager1",
+                "RegisterAdvertisement",
+                &path,
+            )
+            .map_err(|err| anyhow::anyhow!("更新BLE广播失败: {}", err))?;
+        }
+        Ok(())
+    }
+
+    /// 在后台线程中更新收到的所有读数（如调度器的订阅），通道关闭后线程退出
+    pub fn record_from(&self, samples: Receiver<Sample>) -> JoinHandle<()> {
+        let this = self.clone();
+        thread::spawn(move || {
+            for sample in samples {
+                if let Err(err) = this.update(&sample) {
+                    eprintln!("BLE更新传感器{}的读数失败: {}", sample.sensor, err);
+                }
+            }
+        })
+    }
+}
+
+impl Sink for BlePeripheral {
+    fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        self.update(sample)
+    }
+}
@@ -0,0 +1,554 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::diagnostics::DiagnosticsReport;
+
+/// 40针排针引出的GPIO数量（GPIO0~27）
+const HEADER_GPIOS: u8 = 28;
+
+/// 启动配置文件（Bookworm之后位于/boot/firmware）
+const BOOT_CONFIG_PATHS: [&str; 2] = ["/boot/firmware/config.txt", "/boot/config.txt"];
+
+/// 树莓派SoC（决定PWM、GPIO等外设的映射方式）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Soc {
+    /// BCM2835/2836/2837（树莓派1~3、Zero系列）
+    Bcm2835,
+    /// BCM2711（树莓派4B、400、CM4）
+    Bcm2711,
+    /// BCM2712 + RP1南桥（树莓派5、500、CM5），GPIO和PWM都由RP1提供
+    Rp1,
+    /// 无法识别（非树莓派或读取型号失败）
+    Unknown,
+}
+
+/// 硬件PWM输出位置（/sys/class/pwm/pwmchip{chip}/pwm{channel}）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PwmChannel {
+    /// pwmchip编号
+    pub chip: u8,
+    /// 通道编号
+    pub channel: u8,
+}
+
+/// 开发板信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct Board {
+    /// 型号字符串（如"Raspberry Pi 5 Model B Rev 1.0"）
+    model: String,
+    /// SoC类型
+    soc: Soc,
+}
+
+impl Board {
+    /// 按型号字符串识别
+    pub fn from_model(model: &str) -> Self {
+        let model = model.trim_end_matches('\0').trim().to_string();
+        let soc = if !model.starts_with("Raspberry Pi") {
+            Soc::Unknown
+        } else if model.contains("Pi 5")
+            || model.contains("Pi 500")
+            || model.contains("Compute Module 5")
+        {
+            Soc::Rp1
+        } else if model.contains("Pi 4")
+            || model.contains("Pi 400")
+            || model.contains("Compute Module 4")
+        {
+            Soc::Bcm2711
+        } else {
+            Soc::Bcm2835
+        };
+        Self { model, soc }
+    }
+
+    /// 读取设备树中的型号识别当前开发板（结果缓存）
+    pub fn detect() -> &'static Board {
+        static BOARD: OnceLock<Board> = OnceLock::new();
+        BOARD.get_or_init(|| {
+            let model = fs::read_to_string("/proc/device-tree/model")
+                .or_else(|_| fs::read_to_string("/sys/firmware/devicetree/base/model"))
+                .unwrap_or_default();
+            Board::from_model(&model)
+        })
+    }
+
+    /// 型号字符串
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// SoC类型
+    pub fn soc(&self) -> Soc {
+        self.soc
+    }
+
+    /// 是否为树莓派5系列
+    pub fn is_pi5(&self) -> bool {
+        self.soc == Soc::Rp1
+    }
+
+    /// PWM控制器在设备树中的compatible字符串
+    fn pwm_compatible(&self) -> &'static str {
+        match self.soc {
+            Soc::Rp1 => "raspberrypi,rp1-pwm",
+            _ => "brcm,bcm2835-pwm",
+        }
+    }
+
+    /// 查找硬件PWM控制器对应的pwmchip编号
+    ///
+    /// 树莓派5上RP1的PWM编号随内核版本和启用的overlay变化（常见为pwmchip2），
+    /// 按设备树compatible查找，找不到时使用常见编号
+    pub fn pwm_chip(&self) -> u8 {
+        let compatible = self.pwm_compatible();
+        if let Ok(entries) = fs::read_dir("/sys/class/pwm") {
+            for entry in entries.flatten() {
+                let name = entry.file_name();
+                let Some(chip) = name
+                    .to_str()
+                    .and_then(|name| name.strip_prefix("pwmchip"))
+                    .and_then(|chip| chip.parse().ok())
+                else {
+                    continue;
+                };
+                let path = entry.path().join("device/of_node/compatible");
+                if Self::has_compatible(&path, compatible) {
+                    return chip;
+                }
+            }
+        }
+        match self.soc {
+            Soc::Rp1 => 2,
+            _ => 0,
+        }
+    }
+
+    /// compatible文件中是否包含指定字符串（多个值以\0分隔）
+    fn has_compatible(path: &Path, compatible: &str) -> bool {
+        fs::read(path).is_ok_and(|data| {
+            data.split(|&b| b == 0)
+                .any(|value| value == compatible.as_bytes())
+        })
+    }
+
+    /// 硬件PWM引脚对应的通道
+    ///
+    /// - 树莓派1~4：GPIO12/18为通道0，GPIO13/19为通道1
+    /// - 树莓派5：GPIO12、13、18、19分别为通道0~3
+    ///
+    /// 引脚需要通过dtoverlay（如`pwm-2chan`）切换到PWM功能
+    pub fn hardware_pwm(&self, gpio: u8) -> anyhow::Result<PwmChannel> {
+        let channel = match (self.soc, gpio) {
+            (Soc::Rp1, 12) => 0,
+            (Soc::Rp1, 13) => 1,
+            (Soc::Rp1, 18) => 2,
+            (Soc::Rp1, 19) => 3,
+            (Soc::Rp1, _) => {
+                return Err(anyhow::anyhow!("树莓派5的GPIO{}不支持硬件PWM", gpio));
+            }
+            (_, 12 | 18) => 0,
+            (_, 13 | 19) => 1,
+            _ => return Err(anyhow::anyhow!("GPIO{}不支持硬件PWM", gpio)),
+        };
+        // OK
+        Ok(PwmChannel {
+            chip: self.pwm_chip(),
+            channel,
+        })
+    }
+}
+
+/// config.txt中启用的外设功能
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinFunction {
+    /// I2C总线（总线编号）
+    I2c(u8),
+    /// SPI总线（总线编号）
+    Spi(u8),
+    /// 串口
+    Uart,
+    /// 硬件PWM
+    Pwm,
+    /// 1-Wire总线
+    OneWire,
+}
+
+impl fmt::Display for PinFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PinFunction::I2c(bus) => write!(f, "I2C{}", bus),
+            PinFunction::Spi(bus) => write!(f, "SPI{}", bus),
+            PinFunction::Uart => write!(f, "串口"),
+            PinFunction::Pwm => write!(f, "硬件PWM"),
+            PinFunction::OneWire => write!(f, "1-Wire"),
+        }
+    }
+}
+
+/// 被外设功能占用的引脚
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReservedPin {
+    /// GPIO编号
+    pub pin: u8,
+    /// 占用的功能
+    pub function: PinFunction,
+    /// 启用该功能的配置行（如"dtparam=i2c_arm=on"）
+    pub source: String,
+}
+
+/// 启动配置（config.txt）中启用的接口
+///
+/// 只处理`dtparam`、`dtoverlay`和`enable_uart`，`[none]`段之外的条件段都视为生效
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BootConfig {
+    /// 读取的文件
+    pub path: Option<PathBuf>,
+    /// 基础设备树参数（如i2c_arm -> on）
+    params: BTreeMap<String, String>,
+    /// 加载的overlay（名称和参数）
+    overlays: Vec<(String, BTreeMap<String, String>)>,
+    /// 是否启用了串口（enable_uart=1）
+    uart: bool,
+}
+
+impl BootConfig {
+    /// 解析config.txt的内容
+    pub fn parse(text: &str) -> Self {
+        let mut config = BootConfig::default();
+        let mut enabled = true;
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if let Some(section) = line.strip_prefix('[') {
+                enabled = section.trim_end_matches(']') != "none";
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            if !enabled {
+                continue;
+            }
+            match key.trim() {
+                "dtparam" => config.params.extend(Self::parse_params(value)),
+                "dtoverlay" => {
+                    let mut parts = value.splitn(2, ',');
+                    let name = parts.next().unwrap_or("").trim().to_string();
+                    if !name.is_empty() {
+                        let params = Self::parse_params(parts.next().unwrap_or(""));
+                        config.overlays.push((name, params));
+                    }
+                }
+                "enable_uart" => config.uart = value.trim() == "1",
+                _ => {}
+            }
+        }
+        config
+    }
+
+    /// 解析逗号分隔的参数（没有值的参数视为"on"）
+    fn parse_params(text: &str) -> BTreeMap<String, String> {
+        text.split(',')
+            .map(str::trim)
+            .filter(|param| !param.is_empty())
+            .map(|param| match param.split_once('=') {
+                Some((key, value)) => (key.trim().to_string(), value.trim().to_string()),
+                None => (param.to_string(), "on".to_string()),
+            })
+            .collect()
+    }
+
+    /// 读取当前系统的config.txt，找不到时返回None（不是树莓派OS）
+    pub fn load() -> Option<Self> {
+        BOOT_CONFIG_PATHS.iter().find_map(|path| {
+            let text = fs::read_to_string(path).ok()?;
+            Some(Self {
+                path: Some(PathBuf::from(path)),
+                ..Self::parse(&text)
+            })
+        })
+    }
+
+    /// 基础设备树参数是否打开
+    fn param_on(&self, name: &str) -> bool {
+        self.params
+            .get(name)
+            .is_some_and(|value| matches!(value.as_str(), "on" | "true" | "yes" | "1"))
+    }
+
+    /// 指定的overlay
+    fn overlay(&self, name: &str) -> Option<&BTreeMap<String, String>> {
+        self.overlays
+            .iter()
+            .find(|(overlay, _)| overlay == name)
+            .map(|(_, params)| params)
+    }
+
+    /// 指定编号的I2C总线是否已在config.txt中启用
+    pub fn i2c_enabled(&self, bus: u8) -> bool {
+        match bus {
+            0 => self.param_on("i2c_vc"),
+            1 => self.param_on("i2c_arm") || self.param_on("i2c"),
+            _ => {
+                self.overlay(&format!("i2c{}", bus)).is_some() || self.overlay("i2c-gpio").is_some()
+            }
+        }
+    }
+
+    /// 硬件PWM已切换到PWM功能的引脚
+    pub fn pwm_pins(&self) -> Vec<u8> {
+        self.reserved_pins()
+            .into_iter()
+            .filter(|reserved| reserved.function == PinFunction::Pwm)
+            .map(|reserved| reserved.pin)
+            .collect()
+    }
+
+    /// 被已启用的外设功能占用的引脚
+    pub fn reserved_pins(&self) -> Vec<ReservedPin> {
+        let mut pins = Vec::new();
+        let mut reserve = |list: &[u8], function: PinFunction, source: &str| {
+            for &pin in list {
+                pins.push(ReservedPin {
+                    pin,
+                    function,
+                    source: source.to_string(),
+                });
+            }
+        };
+        if self.param_on("i2c_arm") || self.param_on("i2c") {
+            reserve(&[2, 3], PinFunction::I2c(1), "dtparam=i2c_arm=on");
+        }
+        if self.param_on("spi") {
+            reserve(&[7, 8, 9, 10, 11], PinFunction::Spi(0), "dtparam=spi=on");
+        }
+        if self.uart {
+            reserve(&[14, 15], PinFunction::Uart, "enable_uart=1");
+        }
+        // overlay的参数为引脚编号，未指定时使用overlay的默认引脚
+        let pin = |params: &BTreeMap<String, String>, key: &str, default: u8| {
+            params
+                .get(key)
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(default)
+        };
+        for (name, params) in &self.overlays {
+            let source = format!("dtoverlay={}", name);
+            match name.as_str() {
+                "pwm" => reserve(&[pin(params, "pin", 18)], PinFunction::Pwm, &source),
+                "pwm-2chan" => reserve(
+                    &[pin(params, "pin", 18), pin(params, "pin2", 19)],
+                    PinFunction::Pwm,
+                    &source,
+                ),
+                "w1-gpio" | "w1-gpio-pullup" => {
+                    reserve(&[pin(params, "gpiopin", 4)], PinFunction::OneWire, &source)
+                }
+                "i2c-gpio" => reserve(
+                    &[
+                        pin(params, "i2c_gpio_sda", 23),
+                        pin(params, "i2c_gpio_scl", 24),
+                    ],
+                    PinFunction::I2c(pin(params, "bus", 3)),
+                    &source,
+                ),
+                // 树莓派4的额外I2C（默认引脚）
+                "i2c3" => reserve(&[4, 5], PinFunction::I2c(3), &source),
+                "i2c4" => reserve(&[6, 7], PinFunction::I2c(4), &source),
+                "i2c5" => reserve(&[12, 13], PinFunction::I2c(5), &source),
+                "i2c6" => reserve(&[22, 23], PinFunction::I2c(6), &source),
+                "spi1-1cs" => reserve(&[18, 19, 20, 21], PinFunction::Spi(1), &source),
+                "spi1-2cs" => reserve(&[17, 18, 19, 20, 21], PinFunction::Spi(1), &source),
+                "spi1-3cs" => reserve(&[16, 17, 18, 19, 20, 21], PinFunction::Spi(1), &source),
+                // 树莓派4的额外串口
+                "uart2" => reserve(&[0, 1], PinFunction::Uart, &source),
+                "uart3" => reserve(&[4, 5], PinFunction::Uart, &source),
+                "uart4" => reserve(&[8, 9], PinFunction::Uart, &source),
+                "uart5" => reserve(&[12, 13], PinFunction::Uart, &source),
+                _ => {}
+            }
+        }
+        pins
+    }
+}
+
+/// 引脚或总线的使用方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinUsage {
+    /// 普通GPIO（输入、输出、软件PWM）
+    Gpio(u8),
+    /// 硬件PWM输出
+    HardwarePwm(u8),
+    /// I2C总线（总线编号）
+    I2cBus(u8),
+}
+
+/// 配置中对引脚或总线的使用
+#[derive(Debug, Clone, PartialEq)]
+pub struct PinRequest {
+    /// 使用者（传感器名称）
+    pub owner: String,
+    /// 使用方式
+    pub usage: PinUsage,
+}
+
+impl PinRequest {
+    /// 创建实例
+    pub fn new(owner: &str, usage: PinUsage) -> Self {
+        Self {
+            owner: owner.to_string(),
+            usage,
+        }
+    }
+
+    /// 使用的GPIO（I2C总线为None）
+    fn pin(&self) -> Option<u8> {
+        match self.usage {
+            PinUsage::Gpio(pin) | PinUsage::HardwarePwm(pin) => Some(pin),
+            PinUsage::I2cBus(_) => None,
+        }
+    }
+}
+
+impl Board {
+    /// 启动时检查引脚配置
+    ///
+    /// - 引脚超出40针排针范围、多个使用者占用同一引脚、硬件PWM使用了不支持的引脚、
+    ///   使用的I2C总线不存在时为失败
+    /// - 引脚被config.txt启用的I2C、SPI、串口、PWM、1-Wire占用，或硬件PWM引脚未切换到PWM功能时为警告
+    ///
+    /// - boot: 启动配置（None时不检查与config.txt的冲突）
+    /// - requests: 配置中对引脚和总线的使用
+    pub fn validate(
+        &self,
+        boot: Option<&BootConfig>,
+        requests: &[PinRequest],
+    ) -> DiagnosticsReport {
+        let device = if self.model.is_empty() {
+            "开发板"
+        } else {
+            self.model.as_str()
+        };
+        let mut report = DiagnosticsReport::new(device);
+        let reserved = boot.map(BootConfig::reserved_pins).unwrap_or_default();
+        let config_file = boot
+            .and_then(|boot| boot.path.as_ref())
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "config.txt".to_string());
+        let mut checked_buses = Vec::new();
+
+        for (index, request) in requests.iter().enumerate() {
+            if let Some(pin) = request.pin() {
+                let name = format!("{}: GPIO{}", request.owner, pin);
+                if pin >= HEADER_GPIOS {
+                    report.fail(
+                        &name,
+                        format!("GPIO{}没有引出到40针排针", pin),
+                        "引脚使用BCM编号（GPIO0~27），不是排针的物理编号",
+                    );
+                    continue;
+                }
+                // 只报告与前面的使用者冲突，避免同一冲突重复报告
+                if let Some(other) = requests[..index]
+                    .iter()
+                    .find(|other| other.pin() == Some(pin))
+                {
+                    report.fail(
+                        &name,
+                        format!("GPIO{}同时被{}使用", pin, other.owner),
+                        "每个引脚只能连接一个设备，修改其中一个传感器的引脚配置",
+                    );
+                }
+                if let Some(used) = reserved.iter().find(|used| used.pin == pin)
+                    && !(used.function == PinFunction::Pwm
+                        && request.usage == PinUsage::HardwarePwm(pin))
+                {
+                    report.warn(
+                        &name,
+                        format!("GPIO{}已被{}占用（{}）", pin, used.function, used.source),
+                        &format!(
+                            "改用其他引脚，或在{}中删除`{}`后重启",
+                            config_file, used.source
+                        ),
+                    );
+                }
+            }
+            match request.usage {
+                PinUsage::HardwarePwm(pin) if pin < HEADER_GPIOS => {
+                    let name = format!("{}: GPIO{}", request.owner, pin);
+                    if let Err(err) = self.hardware_pwm(pin) {
+                        report.fail(
+                            &name,
+                            err,
+                            "硬件PWM只支持GPIO12、13、18、19，其他引脚请使用软件PWM",
+                        );
+                    } else if let Some(boot) = boot
+                        && !boot.pwm_pins().contains(&pin)
+                    {
+                        report.warn(
+                            &name,
+                            format!("GPIO{}没有切换到PWM功能", pin),
+                            &format!("在{}中添加`{}`后重启", config_file, Self::pwm_overlay(pin)),
+                        );
+                    }
+                }
+                PinUsage::I2cBus(bus) if !checked_buses.contains(&bus) => {
+                    checked_buses.push(bus);
+                    let name = format!("I2C{}", bus);
+                    let device = format!("/dev/i2c-{}", bus);
+                    if Path::new(&device).exists() {
+                        report.pass(&name, &device);
+                    } else if boot.is_some_and(|boot| boot.i2c_enabled(bus)) {
+                        report.fail(
+                            &name,
+                            format!("{}不存在（{}使用）", device, request.owner),
+                            "config.txt已启用该总线，修改后需要重启；仍不存在时运行`sudo modprobe i2c-dev`",
+                        );
+                    } else {
+                        report.fail(
+                            &name,
+                            format!("{}不存在（{}使用）", device, request.owner),
+                            &format!(
+                                "在{}中添加`{}`后重启（或使用raspi-config启用I2C）",
+                                config_file,
+                                Self::i2c_setting(bus)
+                            ),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+        if report
+            .checks
+            .iter()
+            .all(|check| check.name.starts_with("I2C"))
+        {
+            report.pass(
+                "引脚分配",
+                format!("{}个引脚或总线没有冲突", requests.len()),
+            );
+        }
+        report
+    }
+
+    /// 将引脚切换到PWM功能的overlay配置
+    ///
+    /// GPIO12/13的PWM为ALT0（func=4），GPIO18/19为ALT5（func=2）
+    fn pwm_overlay(pin: u8) -> String {
+        let func = if matches!(pin, 12 | 13) { 4 } else { 2 };
+        format!("dtoverlay=pwm,pin={},func={}", pin, func)
+    }
+
+    /// 启用I2C总线的配置
+    fn i2c_setting(bus: u8) -> String {
+        match bus {
+            0 => "dtparam=i2c_vc=on".to_string(),
+            1 => "dtparam=i2c_arm=on".to_string(),
+            _ => format!("dtoverlay=i2c{}", bus),
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[cfg(feature = "eeprom")]
+use crate::sensor::eeprom_24c::AT24Cxx;
+
+/// 校准数据（名称 -> 数值）
+///
+/// 如称重的0点偏移值、转换矫正因子等
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Calibration {
+    values: BTreeMap<String, f64>,
+}
+
+impl Calibration {
+    /// 创建空的校准数据
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 读取校准值
+    pub fn get(&self, key: &str) -> Option<f64> {
+        self.values.get(key).copied()
+    }
+
+    /// 设置校准值
+    pub fn set(&mut self, key: &str, value: f64) {
+        self.values.insert(key.to_string(), value);
+    }
+
+    /// 删除校准值
+    pub fn remove(&mut self, key: &str) -> Option<f64> {
+        self.values.remove(key)
+    }
+
+    /// 编码为文本（每行一条"名称=数值"）
+    pub fn encode(&self) -> String {
+        self.values
+            .iter()
+            .map(|(key, value)| format!("{}={}\n", key, value))
+            .collect()
+    }
+
+    /// 从文本解码
+    pub fn decode(text: &str) -> anyhow::Result<Self> {
+        let mut calibration = Self::new();
+        for line in text.lines().map(str::trim) {
+            // 忽略空行和注释
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("校准数据格式错误: {}", line))?;
+            let value = value
+                .trim()
+                .parse()
+                .map_err(|_| anyhow::anyhow!("校准数值格式错误: {}", line))?;
+            calibration.set(key.trim(), value);
+        }
+        // OK
+        Ok(calibration)
+    }
+}
+
+/// 校准数据持久化存储
+pub trait CalibrationStore: Send {
+    /// 读取校准数据（未保存过时返回空的校准数据）
+    fn load(&mut self) -> anyhow::Result<Calibration>;
+
+    /// 保存校准数据
+    fn save(&mut self, calibration: &Calibration) -> anyhow::Result<()>;
+}
+
+/// 基于文件的校准数据存储
+pub struct FileStore {
+    path: PathBuf,
+}
+
+impl FileStore {
+    /// 创建文件存储实例
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl CalibrationStore for FileStore {
+    fn load(&mut self) -> anyhow::Result<Calibration> {
+        match fs::read_to_string(&self.path) {
+            Ok(text) => Calibration::decode(&text),
+            // 文件不存在表示从未保存过
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Calibration::new()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn save(&mut self, calibration: &Calibration) -> anyhow::Result<()> {
+        // 先写临时文件再重命名，避免断电时损坏原有数据
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, calibration.encode())?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+/// EEPROM存储记录魔数
+#[cfg(feature = "eeprom")]
+const EEPROM_MAGIC: [u8; 4] = *b"CAL1";
+
+/// 基于AT24Cxx EEPROM的校准数据存储
+///
+/// 校准数据可以和传感器模块放在同一块板子上，更换树莓派时无需重新校准
+///
+/// 记录格式：魔数(4) + 长度(2) + 数据 + 校验和(1)
+#[cfg(feature = "eeprom")]
+pub struct EepromStore {
+    /// EEPROM对象
+    eeprom: AT24Cxx,
+    /// 记录起始偏移
+    offset: usize,
+    /// 记录最大长度（含记录头和校验和）
+    capacity: usize,
+}
+
+#[cfg(feature = "eeprom")]
+impl EepromStore {
+    /// 创建EEPROM存储实例
+    ///
+    /// - offset: 记录起始偏移
+    /// - capacity: 预留给校准数据的空间（字节）
+    pub fn new(eeprom: AT24Cxx, offset: usize, capacity: usize) -> Self {
+        Self {
+            eeprom,
+            offset,
+            capacity,
+        }
+    }
+
+    /// 计算校验和
+    fn checksum(data: &[u8]) -> u8 {
+        data.iter().fold(0u8, |sum, &b| sum.wrapping_add(b))
+    }
+}
+
+#[cfg(feature = "eeprom")]
+impl CalibrationStore for EepromStore {
+    fn load(&mut self) -> anyhow::Result<Calibration> {
+        // 读取记录头
+        let mut head = [0u8; 6];
+        self.eeprom.read(self.offset, &mut head)?;
+        if head[0..4] != EEPROM_MAGIC {
+            // 未写入过校准数据
+            return Ok(Calibration::new());
+        }
+        let len = u16::from_be_bytes([head[4], head[5]]) as usize;
+        if len + 7 > self.capacity {
+            return Err(anyhow::anyhow!("EEPROM校准数据长度错误: {}", len));
+        }
+
+        // 读取数据和校验和
+        let mut body = vec![0u8; len + 1];
+        self.eeprom.read(self.offset + 6, &mut body)?;
+        if Self::checksum(&body[..len]) != body[len] {
+            trace_event!(warn, offset = self.offset, len, "EEPROM校准数据校验和错误");
+            return Err(anyhow::anyhow!("EEPROM校准数据校验和错误"));
+        }
+
+        Calibration::decode(std::str::from_utf8(&body[..len])?)
+    }
+
+    fn save(&mut self, calibration: &Calibration) -> anyhow::Result<()> {
+        let data = calibration.encode().into_bytes();
+        if data.len() + 7 > self.capacity {
+            return Err(anyhow::anyhow!(
+                "EEPROM校准数据空间不足, 需要: {}, 可用: {}",
+                data.len() + 7,
+                self.capacity
+            ));
+        }
+
+        let mut record = Vec::with_capacity(data.len() + 7);
+        record.extend_from_slice(&EEPROM_MAGIC);
+        record.extend_from_slice(&(data.len() as u16).to_be_bytes());
+        record.extend_from_slice(&data);
+        record.push(Self::checksum(&data));
+        self.eeprom.write(self.offset, &record)
+    }
+}
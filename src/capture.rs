@@ -0,0 +1,400 @@
+//! 软件时序协议的信号捕获（逻辑分析仪）
+//!
+//! DHT11、HX711等软件模拟时序的驱动在一次传输中记录每个电平变化的时间，导出为VCD
+//! （PulseView、GTKWave可以直接打开）或CSV文件，离线分析时序和接线问题，
+//! 不必只凭"校验和错误"、"等待数据就绪超时"猜测原因
+//!
+//! ```ignore
+//! let capture = dht11.signal_capture().unwrap();
+//! capture.set_enabled(true);
+//! let result = dht11.read();
+//! if let Some(trace) = capture.last_trace() {
+//!     trace.save("dht11.vcd")?;
+//! }
+//! ```
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 一次传输最多记录的边沿数（超出后丢弃，避免忘记关闭捕获时占满内存）
+const MAX_EDGES: usize = 100_000;
+
+/// 电平变化
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edge {
+    /// 相对传输开始的时间
+    pub time: Duration,
+    /// 信号序号（对应`Trace::signals`）
+    pub signal: usize,
+    /// 变化后的电平
+    pub level: bool,
+}
+
+/// 一次传输的波形
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trace {
+    /// 信号名称
+    pub signals: Vec<String>,
+    /// 按时间排序的电平变化（每个信号的第一条为初始电平）
+    pub edges: Vec<Edge>,
+    /// 传输耗时
+    pub duration: Duration,
+    /// 是否因为超过边沿数上限而丢弃了部分边沿
+    pub truncated: bool,
+}
+
+impl Trace {
+    /// 导出为VCD（Value Change Dump）格式，时间单位为1ns
+    pub fn to_vcd(&self) -> String {
+        let mut vcd = String::new();
+        let _ = writeln!(vcd, "$version raspi-sensor $end");
+        let _ = writeln!(vcd, "$timescale 1ns $end");
+        let _ = writeln!(vcd, "$scope module capture $end");
+        for (i, name) in self.signals.iter().enumerate() {
+            let _ = writeln!(vcd, "$var wire 1 {} {} $end", vcd_id(i), name);
+        }
+        let _ = writeln!(vcd, "$upscope $end");
+        let _ = writeln!(vcd, "$enddefinitions $end");
+        // 第一次记录之前的电平未知
+        let _ = writeln!(vcd, "#0");
+        let _ = writeln!(vcd, "$dumpvars");
+        for i in 0..self.signals.len() {
+            let _ = writeln!(vcd, "x{}", vcd_id(i));
+        }
+        let _ = writeln!(vcd, "$end");
+        let mut time = None;
+        for edge in &self.edges {
+            let nanos = edge.time.as_nanos();
+            if time != Some(nanos) {
+                let _ = writeln!(vcd, "#{}", nanos);
+                time = Some(nanos);
+            }
+            let _ = writeln!(vcd, "{}{}", u8::from(edge.level), vcd_id(edge.signal));
+        }
+        let _ = writeln!(vcd, "#{}", self.duration.as_nanos());
+        vcd
+    }
+
+    /// 导出为CSV格式（每个电平变化一行，列为时间（µs）和各信号变化后的电平，未知为空）
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("time_us");
+        for name in &self.signals {
+            csv.push(',');
+            csv.push_str(name);
+        }
+        csv.push('\n');
+        let mut levels: Vec<Option<bool>> = vec![None; self.signals.len()];
+        for edge in &self.edges {
+            if let Some(level) = levels.get_mut(edge.signal) {
+                *level = Some(edge.level);
+            }
+            let _ = write!(csv, "{:.3}", edge.time.as_nanos() as f64 / 1000.0);
+            for level in &levels {
+                csv.push(',');
+                if let Some(level) = level {
+                    csv.push(if *level { '1' } else { '0' });
+                }
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// 按扩展名（.vcd或.csv）保存到文件
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let content = match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("vcd") => self.to_vcd(),
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => self.to_csv(),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "不支持的波形文件格式（需要.vcd或.csv）: {}",
+                    path.display()
+                ));
+            }
+        };
+        fs::write(path, content)
+            .map_err(|err| anyhow::anyhow!("保存波形文件{}失败: {}", path.display(), err))?;
+        Ok(())
+    }
+
+    /// 指定信号的脉冲（电平和持续时间），最后一段电平持续到传输结束
+    pub fn pulses(&self, signal: usize) -> Vec<(bool, Duration)> {
+        let edges: Vec<&Edge> = self
+            .edges
+            .iter()
+            .filter(|edge| edge.signal == signal)
+            .collect();
+        edges
+            .iter()
+            .enumerate()
+            .map(|(i, edge)| {
+                let end = edges.get(i + 1).map_or(self.duration, |next| next.time);
+                (edge.level, end.saturating_sub(edge.time))
+            })
+            .collect()
+    }
+}
+
+/// VCD中的信号标识（可打印字符，从'!'开始）
+fn vcd_id(index: usize) -> String {
+    let mut id = String::new();
+    let mut index = index;
+    loop {
+        id.push(char::from(b'!' + (index % 94) as u8));
+        index /= 94;
+        if index == 0 {
+            break;
+        }
+        index -= 1;
+    }
+    id
+}
+
+/// 记录中的状态
+struct State {
+    /// 传输开始的时间
+    start: Option<Instant>,
+    /// 各信号当前的电平
+    levels: Vec<Option<bool>>,
+    /// 电平变化
+    edges: Vec<Edge>,
+    /// 是否丢弃了部分边沿
+    truncated: bool,
+    /// 采样记录的波形的结束时间
+    sampled_until: Duration,
+    /// 上一次传输的波形
+    last: Option<Trace>,
+}
+
+/// 共享的捕获状态
+struct Inner {
+    /// 信号名称
+    signals: Vec<String>,
+    /// 是否启用
+    enabled: AtomicBool,
+    /// 记录中的状态
+    state: Mutex<State>,
+}
+
+/// 信号捕获（可克隆的句柄，驱动和使用者共享同一份记录）
+///
+/// 默认不启用，未启用时`record`只有一次原子读取的开销，不影响正常读取的时序
+#[derive(Clone)]
+pub struct SignalCapture {
+    inner: Arc<Inner>,
+}
+
+impl SignalCapture {
+    /// 创建实例
+    ///
+    /// - signals: 信号名称（如`["pd_sck", "dout"]`），记录时使用序号
+    pub fn new(signals: &[&str]) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                signals: signals.iter().map(|name| name.to_string()).collect(),
+                enabled: AtomicBool::new(false),
+                state: Mutex::new(State {
+                    start: None,
+                    levels: vec![None; signals.len()],
+                    edges: Vec::new(),
+                    truncated: false,
+                    sampled_until: Duration::ZERO,
+                    last: None,
+                }),
+            }),
+        }
+    }
+
+    /// 信号名称
+    pub fn signals(&self) -> &[String] {
+        &self.inner.signals
+    }
+
+    /// 启用或停用捕获
+    pub fn set_enabled(&self, enabled: bool) {
+        self.inner.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// 是否启用
+    pub fn is_enabled(&self) -> bool {
+        self.inner.enabled.load(Ordering::Relaxed)
+    }
+
+    /// 开始一次传输（清空之前的记录，时间从此刻开始计算）
+    pub fn begin(&self) {
+        if !self.is_enabled() {
+            return;
+        }
+        if let Ok(mut state) = self.inner.state.lock() {
+            state.start = Some(Instant::now());
+            state.levels.iter_mut().for_each(|level| *level = None);
+            state.edges.clear();
+            state.truncated = false;
+            state.sampled_until = Duration::ZERO;
+        }
+    }
+
+    /// 记录信号的电平（与上一次相同时忽略）
+    #[inline]
+    pub fn record(&self, signal: usize, level: bool) {
+        if !self.is_enabled() {
+            return;
+        }
+        let now = Instant::now();
+        if let Ok(mut state) = self.inner.state.lock() {
+            let start = *state.start.get_or_insert(now);
+            state.push(signal, level, now.duration_since(start));
+        }
+    }
+
+    /// 记录按固定间隔采样的波形（每个bit为一个采样点，高位在前，如SPI读取的MISO）
+    ///
+    /// - signal: 信号序号
+    /// - samples: 采样数据
+    /// - period: 采样间隔
+    pub fn record_samples(&self, signal: usize, samples: &[u8], period: Duration) {
+        if !self.is_enabled() {
+            return;
+        }
+        let now = Instant::now();
+        if let Ok(mut state) = self.inner.state.lock() {
+            let start = *state.start.get_or_insert(now);
+            let offset = now.duration_since(start);
+            for (i, byte) in samples.iter().enumerate() {
+                for bit in 0..8 {
+                    let level = byte >> (7 - bit) & 1 == 1;
+                    let time = offset + period * (i * 8 + bit) as u32;
+                    state.push(signal, level, time);
+                }
+            }
+            let end = offset + period * (samples.len() * 8) as u32;
+            state.sampled_until = state.sampled_until.max(end);
+        }
+    }
+
+    /// 结束一次传输，保存波形（之后可以通过`last_trace`取出）
+    pub fn finish(&self) {
+        if !self.is_enabled() {
+            return;
+        }
+        if let Ok(mut state) = self.inner.state.lock() {
+            // 采样记录的波形可能晚于当前时间结束
+            let elapsed = state
+                .start
+                .take()
+                .map_or(Duration::ZERO, |start| start.elapsed());
+            let duration = elapsed.max(state.sampled_until);
+            let trace = Trace {
+                signals: self.inner.signals.clone(),
+                edges: std::mem::take(&mut state.edges),
+                duration,
+                truncated: state.truncated,
+            };
+            trace_event!(
+                debug,
+                edges = trace.edges.len(),
+                truncated = trace.truncated,
+                "信号捕获完成"
+            );
+            state.last = Some(trace);
+        }
+    }
+
+    /// 上一次传输的波形
+    pub fn last_trace(&self) -> Option<Trace> {
+        self.inner
+            .state
+            .lock()
+            .ok()
+            .and_then(|state| state.last.clone())
+    }
+}
+
+impl State {
+    /// 添加电平变化
+    fn push(&mut self, signal: usize, level: bool, time: Duration) {
+        let Some(current) = self.levels.get_mut(signal) else {
+            return;
+        };
+        if *current == Some(level) {
+            return;
+        }
+        if self.edges.len() >= MAX_EDGES {
+            self.truncated = true;
+            return;
+        }
+        *current = Some(level);
+        self.edges.push(Edge {
+            time,
+            signal,
+            level,
+        });
+    }
+}
+
+/// 记录电平的GPIO包装（用于泛型引脚的驱动，如sensor-hal中的驱动）
+///
+/// 输出时记录设置的电平，输入时记录读取到的电平
+pub struct CapturePin<P> {
+    /// 实际的引脚
+    pin: P,
+    /// 信号捕获
+    capture: SignalCapture,
+    /// 信号序号
+    signal: usize,
+}
+
+impl<P> CapturePin<P> {
+    /// 创建实例
+    ///
+    /// - pin: 实际的引脚
+    /// - capture: 信号捕获
+    /// - signal: 信号序号
+    pub fn new(pin: P, capture: SignalCapture, signal: usize) -> Self {
+        Self {
+            pin,
+            capture,
+            signal,
+        }
+    }
+
+    /// 取出实际的引脚
+    pub fn into_inner(self) -> P {
+        self.pin
+    }
+}
+
+impl<P: embedded_hal::digital::ErrorType> embedded_hal::digital::ErrorType for CapturePin<P> {
+    type Error = P::Error;
+}
+
+impl<P: embedded_hal::digital::InputPin> embedded_hal::digital::InputPin for CapturePin<P> {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        let level = self.pin.is_high()?;
+        self.capture.record(self.signal, level);
+        Ok(level)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        self.is_high().map(|level| !level)
+    }
+}
+
+impl<P: embedded_hal::digital::OutputPin> embedded_hal::digital::OutputPin for CapturePin<P> {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.pin.set_low()?;
+        self.capture.record(self.signal, false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.pin.set_high()?;
+        self.capture.record(self.signal, true);
+        Ok(())
+    }
+}
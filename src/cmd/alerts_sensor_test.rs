@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use raspi_sensor::adapter::Dht11Sensor;
+use raspi_sensor::alerts::{AlertEngine, AlertState, Rule};
+use raspi_sensor::manager::SensorManager;
+use raspi_sensor::scheduler::Scheduler;
+use raspi_sensor::sink::{ConsoleSink, Pipeline};
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+/// 风扇继电器接入GPIO针脚
+const FAN_RELAY_PIN: u8 = 22;
+/// 蜂鸣器接入GPIO针脚
+const BUZZER_PIN: u8 = 27;
+
+/// 告警规则测试程序
+fn main() -> anyhow::Result<()> {
+    let manager = SensorManager::new();
+    manager.register("greenhouse", Dht11Sensor::new(DHT11_PIN)?)?;
+
+    // 温度持续1分钟高于30℃时打开风扇，回落到29℃以下时关闭
+    let mut alerts = AlertEngine::new();
+    alerts.add_rule(
+        Rule::parse("too_hot", "greenhouse", "temperature > 30 for 60s")?.with_hysteresis(1.0),
+    )?;
+    alerts.bind_output("too_hot", FAN_RELAY_PIN, true)?;
+    // 湿度过低时蜂鸣器报警，10分钟内不重复触发
+    alerts.add_rule(
+        Rule::parse("too_dry", "greenhouse", "humidity < 40")?
+            .with_hysteresis(3.0)
+            .with_cooldown(Duration::from_secs(600)),
+    )?;
+    alerts.bind_output("too_dry", BUZZER_PIN, true)?;
+    alerts.on_alert(|event| match event.state {
+        AlertState::Raised => println!(
+            "告警[{}]: {} = {:.1}",
+            event.rule, event.quantity, event.value
+        ),
+        AlertState::Cleared => println!(
+            "解除[{}]: {} = {:.1}",
+            event.rule, event.quantity, event.value
+        ),
+    });
+
+    let mut pipeline = Pipeline::new();
+    pipeline.add("console", ConsoleSink).add("alerts", alerts);
+
+    let scheduler = Scheduler::new(manager)?;
+    let samples = scheduler.subscribe();
+    let _scheduler = scheduler.start();
+    pipeline.start(samples).join();
+    Ok(())
+}
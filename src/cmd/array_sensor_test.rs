@@ -0,0 +1,52 @@
+use std::thread;
+use std::time::Duration;
+
+use raspi_sensor::adapter::Dht11Sensor;
+use raspi_sensor::array::{Aggregate, SensorArray};
+use raspi_sensor::manager::Sensor;
+
+/// 种植帐篷四角的DHT11（标签, 引脚）
+const DHT11_PINS: [(&str, u8); 4] = [
+    ("north-east", 4),
+    ("north-west", 17),
+    ("south-east", 27),
+    ("south-west", 22),
+];
+
+/// 同型号传感器阵列测试程序
+fn main() -> anyhow::Result<()> {
+    let mut tent = SensorArray::new("dht11")
+        .with_gap(Duration::from_millis(200))
+        .with_aggregate(Aggregate::Median)
+        .with_min_instances(2);
+    for (label, pin) in DHT11_PINS {
+        tent = tent.with_instance(label, Dht11Sensor::new(pin)?);
+    }
+    let report = tent.report();
+
+    loop {
+        match tent.read() {
+            Ok(reading) => println!("汇总读数: {}", reading),
+            Err(err) => eprintln!("读取失败: {}", err),
+        }
+        let report = report.snapshot();
+        for instance in &report.instances {
+            match (&instance.reading, &instance.error) {
+                (Some(reading), _) => println!("  {}: {}", instance.label, reading),
+                (None, Some(err)) => println!("  {}: 失败 {}", instance.label, err),
+                (None, None) => {}
+            }
+        }
+        for (quantity, summary) in &report.summary {
+            println!(
+                "  {}: 最小{:.1} 最大{:.1} 相差{:.1}（{}个）",
+                quantity,
+                summary.min,
+                summary.max,
+                summary.spread(),
+                summary.count
+            );
+        }
+        thread::sleep(tent.min_interval());
+    }
+}
@@ -0,0 +1,71 @@
+use rppal::gpio::Trigger;
+use rppal::i2c::I2c;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use raspi_sensor::async_sensor::{AsyncAht30, AsyncDht11, AsyncHx711, AsyncInputPin};
+use raspi_sensor::sensor::hx711::{ChannelGain, HX711};
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+/// HX711时钟引脚
+const HX711_CLOCK_PIN: u8 = 23;
+/// HX711数据引脚
+const HX711_DATA_PIN: u8 = 24;
+/// 按钮引脚
+const BUTTON_PIN: u8 = 17;
+
+/// 异步传感器接口测试程序
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let i2c_bus = Arc::new(Mutex::new(I2c::new()?));
+
+    // DHT11：最小读取间隔由tokio定时器等待
+    let mut dht11 = AsyncDht11::new(DHT11_PIN)?;
+    tokio::spawn(async move {
+        loop {
+            match dht11.read().await {
+                Ok(reading) => println!("dht11: {}", reading),
+                Err(err) => eprintln!("读取DHT11失败: {}", err),
+            }
+        }
+    });
+
+    // AHT30：测量等待期间不占用I2C总线
+    let mut aht30 = AsyncAht30::new(i2c_bus, None);
+    tokio::spawn(async move {
+        loop {
+            match aht30.read().await {
+                Ok(reading) => println!("aht30: {}", reading),
+                Err(err) => eprintln!("读取AHT30失败: {}", err),
+            }
+        }
+    });
+
+    // HX711：数据就绪中断唤醒
+    let mut hx711 = AsyncHx711::new(HX711::new(
+        HX711_CLOCK_PIN,
+        HX711_DATA_PIN,
+        ChannelGain::ChannelA128,
+        None,
+    )?)?;
+    tokio::spawn(async move {
+        loop {
+            match hx711.read().await {
+                Ok(value) => println!("hx711: {}", value),
+                Err(err) => eprintln!("读取HX711失败: {}", err),
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+    });
+
+    // 按钮按下时退出
+    let mut button = AsyncInputPin::new(
+        BUTTON_PIN,
+        Trigger::FallingEdge,
+        Some(Duration::from_millis(50)),
+    )?;
+    button.wait_for_edge().await?;
+    println!("按钮按下，退出");
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use raspi_sensor::adapter::Dht11Sensor;
+use raspi_sensor::ble::{BleConfig, BlePeripheral};
+use raspi_sensor::manager::SensorManager;
+use raspi_sensor::scheduler::Scheduler;
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+
+/// BLE读数广播测试程序
+///
+/// - 手机上用nRF Connect连接`raspi-sensor`，读取环境传感服务中的温度和湿度
+/// - Home Assistant的BTHome集成会自动发现该设备
+fn main() -> anyhow::Result<()> {
+    let manager = SensorManager::new();
+    manager.register("outdoor", Dht11Sensor::new(DHT11_PIN)?)?;
+
+    let ble = BlePeripheral::start(BleConfig::new("raspi-sensor", "outdoor"))?;
+    let scheduler = Scheduler::new(manager)?;
+    let worker = ble.record_from(scheduler.subscribe());
+    let _handle = scheduler.start();
+    worker
+        .join()
+        .map_err(|_| anyhow::anyhow!("BLE更新线程异常退出"))?;
+    Ok(())
+}
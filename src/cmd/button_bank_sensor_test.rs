@@ -0,0 +1,42 @@
+use std::thread;
+use std::time::Duration;
+
+use raspi_sensor::gpio::Pull;
+use raspi_sensor::sensor::button::{ButtonEvent, EventTiming};
+use raspi_sensor::sensor::button_bank::{ButtonBank, ButtonConfig};
+
+// 按钮接入GPIO针脚（上、下、确认、返回）
+const BUTTON_PINS: [u8; 4] = [17, 27, 22, 23];
+// 高电平有效的按钮（另一端接3.3V）
+const ACTIVE_HIGH_PIN: u8 = 24;
+
+/// 多按钮管理测试程序
+fn main() -> anyhow::Result<()> {
+    let mut bank = ButtonBank::new()?;
+    for pin in BUTTON_PINS {
+        bank.add(ButtonConfig::new(pin))?;
+    }
+    // 高电平有效的按钮使用下拉，长按时间缩短为500ms
+    bank.add(
+        ButtonConfig::new(ACTIVE_HIGH_PIN)
+            .with_active_low(false)
+            .with_pull(Pull::Down)
+            .with_timing(EventTiming {
+                debounce: Some(Duration::from_millis(30)),
+                hold: Duration::from_millis(500),
+            }),
+    )?;
+
+    // 所有按钮共用一个事件线程
+    bank.on_event(|id, event| match event {
+        ButtonEvent::Pressed => println!("按钮{}按下", id),
+        ButtonEvent::Held(duration) => println!("按钮{}长按: {:?}", id, duration),
+        ButtonEvent::Released(duration) => println!("按钮{}松开, 按下时长: {:?}", id, duration),
+    })?;
+    println!("已监听{}个按钮", bank.len());
+
+    // 防止程序退出
+    loop {
+        thread::sleep(Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use raspi_sensor::adapter::Dht11Sensor;
+use raspi_sensor::apps::climate_chamber::{ClimateChamber, ClimateConfig};
+use raspi_sensor::control::Pid;
+use raspi_sensor::pwm_wapper::PwmWapper;
+use raspi_sensor::reading::Quantity;
+use raspi_sensor::sink::ConsoleSink;
+use raspi_sensor::switch::{GpioSwitch, PwmSwitch};
+use rppal::pwm::Channel;
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+/// 加热器继电器接入GPIO针脚（低电平触发）
+const HEATER_RELAY_PIN: u8 = 22;
+/// 加湿器继电器接入GPIO针脚（低电平触发）
+const HUMIDIFIER_RELAY_PIN: u8 = 27;
+/// 报警蜂鸣器接入GPIO针脚
+const BUZZER_PIN: u8 = 5;
+
+/// 恒温恒湿箱测试程序：温度28℃、湿度60%，温度高于30℃时风扇加速（风扇接硬件PWM0，即GPIO18）
+fn main() -> anyhow::Result<()> {
+    let fan = PwmSwitch::new(PwmWapper::hardware(Channel::Pwm0, 25000.0)?)?;
+    let mut chamber = ClimateChamber::new(
+        "chamber",
+        Dht11Sensor::new(DHT11_PIN)?,
+        ClimateConfig::default(),
+    )?
+    .with_heater(GpioSwitch::new(HEATER_RELAY_PIN, false)?)
+    .with_humidifier(GpioSwitch::new(HUMIDIFIER_RELAY_PIN, false)?)
+    .with_fan(
+        fan,
+        Pid::new(0.2, 0.01, 0.0, 30.0).reversed(),
+        Quantity::Temperature,
+    )
+    .with_sink(ConsoleSink);
+    chamber
+        .alerts()
+        .bind_output("temperature_high", BUZZER_PIN, true)?;
+    chamber.alerts().on_alert(|event| {
+        println!("报警: {} {:?} {:.1}", event.rule, event.state, event.value);
+    });
+
+    chamber
+        .run(Duration::from_secs(5), |status| {
+            println!(
+                "加热: {}, 加湿: {}, 风扇: {:.0}%{}",
+                if status.heater { "开" } else { "关" },
+                if status.humidifier { "开" } else { "关" },
+                status.fan * 100.0,
+                if status.failsafe {
+                    "（传感器故障）"
+                } else {
+                    ""
+                }
+            )
+        })
+        .join()
+        .map_err(|_| anyhow::anyhow!("恒温恒湿箱线程异常退出"))?;
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use raspi_sensor::adapter::Dht11Sensor;
+use raspi_sensor::manager::SensorManager;
+use raspi_sensor::scheduler::Scheduler;
+use raspi_sensor::sink::Pipeline;
+use raspi_sensor::sink::coap::{CoapConfig, CoapSink};
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+
+/// CoAP上报测试程序
+///
+/// 服务器地址通过第一个参数指定（默认127.0.0.1:5683），每5个读数上报一次到`telemetry/outdoor`
+fn main() -> anyhow::Result<()> {
+    let endpoint = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:5683".to_string());
+    let manager = SensorManager::new();
+    manager.register("outdoor", Dht11Sensor::new(DHT11_PIN)?)?;
+
+    let mut config = CoapConfig::new(&endpoint, "telemetry/outdoor");
+    config.batch_size = 5;
+    let mut pipeline = Pipeline::new();
+    pipeline.add("coap", CoapSink::new(config)?);
+
+    let scheduler = Scheduler::new(manager)?;
+    let pipeline = pipeline.start(scheduler.subscribe());
+    let _handle = scheduler.start();
+    pipeline.join();
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use std::{thread, time::Duration};
+
+use raspi_sensor::config;
+
+/// 默认配置文件路径
+const DEFAULT_CONFIG_PATH: &str = "sensors.toml";
+
+/// 配置文件加载测试程序
+///
+/// 用法: config-sensor-test [配置文件路径]
+fn main() -> anyhow::Result<()> {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_CONFIG_PATH.to_string());
+
+    // 按配置文件创建所有传感器，修改接线只需修改配置文件
+    let manager = config::load_manager(&path)?;
+    println!(
+        "已加载{}个传感器: {:?}",
+        manager.names().len(),
+        manager.names()
+    );
+
+    // 每秒读取一次所有传感器
+    loop {
+        for (name, result) in manager.read_all() {
+            match result {
+                Ok(sample) => println!("{}: {}", name, sample.reading),
+                Err(err) => eprintln!("读取传感器{}失败: {}", name, err),
+            }
+        }
+        thread::sleep(Duration::from_secs(1));
+    }
+}
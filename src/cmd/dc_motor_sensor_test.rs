@@ -0,0 +1,44 @@
+use std::{thread, time::Duration};
+
+use raspi_sensor::pwm_wapper::PwmWapper;
+use raspi_sensor::sensor::dc_motor::{DcMotor, DifferentialDrive};
+use rppal::pwm::Channel;
+
+// 左侧电机L298N IN1/IN2接入GPIO针脚
+const LEFT_IN1_PIN: u8 = 5;
+const LEFT_IN2_PIN: u8 = 6;
+// 右侧电机L298N IN3/IN4接入GPIO针脚
+const RIGHT_IN1_PIN: u8 = 20;
+const RIGHT_IN2_PIN: u8 = 21;
+
+/// 直流电机差速驱动测试程序（L298N）
+fn main() -> anyhow::Result<()> {
+    // ENA接PWM0（GPIO18），ENB接PWM1（GPIO19），频率1kHz
+    let left_pwm = PwmWapper::hardware(Channel::Pwm0, 1000.0)?;
+    let right_pwm = PwmWapper::hardware(Channel::Pwm1, 1000.0)?;
+
+    // 创建电机实例
+    let left = DcMotor::new_l298n(LEFT_IN1_PIN, LEFT_IN2_PIN, left_pwm)?;
+    let right = DcMotor::new_l298n(RIGHT_IN1_PIN, RIGHT_IN2_PIN, right_pwm)?;
+    let mut drive = DifferentialDrive::new(left, right);
+
+    // 前进、后退、原地左转、原地右转
+    let actions = [
+        ("前进", 0.6, 0.6),
+        ("后退", -0.6, -0.6),
+        ("左转", -0.5, 0.5),
+        ("右转", 0.5, -0.5),
+    ];
+    for (name, left_speed, right_speed) in actions {
+        println!("{}", name);
+        drive.tank_drive(left_speed, right_speed)?;
+        thread::sleep(Duration::from_secs(2));
+        // 刹车后停顿，避免电机换向时电流冲击
+        drive.brake()?;
+        thread::sleep(Duration::from_millis(500));
+    }
+
+    drive.coast()?;
+    println!("测试完成");
+    Ok(())
+}
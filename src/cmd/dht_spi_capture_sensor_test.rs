@@ -0,0 +1,32 @@
+use std::{thread, time::Duration};
+
+use raspi_sensor::sensor::dht_capture::{DhtModel, DhtSpiCapture};
+use rppal::spi::{Bus, SlaveSelect};
+
+/// 与数据线相连、用于发送起始信号的GPIO针脚（数据线同时接SPI0 MISO，即GPIO9）
+const START_PIN: u8 = 4;
+
+fn main() -> anyhow::Result<()> {
+    // 型号通过第一个参数指定（dht11或dht22，默认dht11）
+    let model = match std::env::args().nth(1).as_deref() {
+        Some("dht22") => DhtModel::Dht22,
+        _ => DhtModel::Dht11,
+    };
+    let mut dht = DhtSpiCapture::new(Bus::Spi0, SlaveSelect::Ss0, START_PIN, model)?;
+    println!("使用SPI采样读取{:?}", model);
+
+    // 死循环读取传感器
+    loop {
+        match dht.read() {
+            Ok((temp, hum)) => {
+                println!("✅ 温度: {:.1}°C, 湿度: {:.1}%", temp, hum);
+            }
+            Err(e) => {
+                eprintln!("❌ 读取失败: {}", e);
+            }
+        }
+
+        // 两次读取需要间隔2秒以上
+        thread::sleep(Duration::from_secs(2));
+    }
+}
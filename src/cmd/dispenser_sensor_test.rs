@@ -0,0 +1,71 @@
+use std::thread;
+use std::time::Duration;
+
+use raspi_sensor::apps::dispenser::{Dispenser, DispenserConfig};
+use raspi_sensor::calibration::FileStore;
+use raspi_sensor::scale_service::{ScaleService, ScaleSettings};
+use raspi_sensor::sensor::hx711::{ChannelGain, HX711};
+use raspi_sensor::sensor::uln2003a::{StepMode, ULN2003A};
+
+// ULN2003A驱动模块接入GPIO针脚
+const MOTOR_PINS: [u8; 4] = [6, 13, 19, 26];
+// HX711传感器接入GPIO针脚
+const HX711_DATA_PIN: u8 = 23;
+const HX711_CLOCK_PIN: u8 = 24;
+// 皮重和矫正因子的保存位置（使用weight-sensor-test矫正后的数据）
+const CALIBRATION_PATH: &str = "weight-sensor-test.cal";
+// 每次出料重量（克）
+const PORTION: f64 = 20.0;
+
+/// 定量出料器测试程序：每次出料20g，共3次
+fn main() -> anyhow::Result<()> {
+    let hx711 = HX711::new(
+        HX711_CLOCK_PIN,
+        HX711_DATA_PIN,
+        ChannelGain::ChannelA128,
+        None,
+    )?;
+    let scale = ScaleService::new(hx711, ScaleSettings::default())
+        .with_store(FileStore::new(CALIBRATION_PATH), "scale")?
+        .start();
+    // 等待滤波和开机去皮完成
+    thread::sleep(Duration::from_secs(2));
+
+    let [pin1, pin2, pin3, pin4] = MOTOR_PINS;
+    let mut motor = ULN2003A::new(pin1, pin2, pin3, pin4, StepMode::HalfStep)?;
+    motor.set_speed_rpm(10.0)?;
+    let mut feeder = Dispenser::new(
+        motor,
+        scale.clone(),
+        DispenserConfig {
+            slow_zone: 5.0,
+            in_flight: 0.5,
+            ..DispenserConfig::default()
+        },
+    );
+
+    for _ in 0..3 {
+        match feeder.dispense(PORTION) {
+            Ok(report) => println!(
+                "出料{:.1}g（目标{:.1}g），{}步，用时{:.1}秒{}",
+                report.dispensed,
+                report.target,
+                report.steps,
+                report.duration.as_secs_f64(),
+                if report.settled {
+                    ""
+                } else {
+                    "，读数未稳定"
+                }
+            ),
+            Err(err) => {
+                eprintln!("出料失败: {}", err);
+                break;
+            }
+        }
+        thread::sleep(Duration::from_secs(2));
+    }
+    feeder.motor().release();
+    scale.stop();
+    Ok(())
+}
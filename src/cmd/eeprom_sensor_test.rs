@@ -0,0 +1,26 @@
+use std::sync::{Arc, Mutex};
+
+use raspi_sensor::calibration::{CalibrationStore, EepromStore};
+use raspi_sensor::sensor::eeprom_24c::{AT24Cxx, Model};
+use rppal::i2c::I2c;
+
+/// AT24C02 EEPROM测试程序
+fn main() -> anyhow::Result<()> {
+    // 初始化I2C通信总线
+    let i2c_bus = Arc::new(Mutex::new(I2c::new()?));
+
+    // 创建EEPROM实例，使用前128字节存放校准数据
+    let eeprom = AT24Cxx::new(i2c_bus, Some(0x50), Model::AT24C02);
+    let mut store = EepromStore::new(eeprom, 0, 128);
+
+    // 读取校准数据
+    let mut calibration = store.load()?;
+    println!("已保存的校准数据: {:?}", calibration);
+
+    // 写入新的转换矫正因子
+    calibration.set("hx711.transform_factor", 429.58);
+    store.save(&calibration)?;
+    println!("重新读取的校准数据: {:?}", store.load()?);
+
+    Ok(())
+}
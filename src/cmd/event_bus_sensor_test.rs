@@ -0,0 +1,79 @@
+use std::thread;
+
+use raspi_sensor::adapter::Dht11Sensor;
+use raspi_sensor::alerts::{AlertEngine, AlertState, Rule};
+use raspi_sensor::event_bus::{Event, EventBus, Topic};
+use raspi_sensor::manager::SensorManager;
+use raspi_sensor::scheduler::Scheduler;
+use raspi_sensor::sensor::button::{Button, ButtonEvent};
+use raspi_sensor::switch::{GpioSwitch, Switch};
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+/// 按钮接入GPIO针脚
+const BUTTON_PIN: u8 = 17;
+/// 风扇继电器接入GPIO针脚
+const FAN_RELAY_PIN: u8 = 22;
+
+/// 事件总线测试程序
+fn main() -> anyhow::Result<()> {
+    let bus = EventBus::new();
+
+    // 控制台：打印所有事件
+    let console = bus.subscribe_all();
+    thread::spawn(move || {
+        for event in console {
+            println!("{:?}: {:?}", event.topic(), event);
+        }
+    });
+
+    // 告警：根据读数判断并发布告警事件
+    let mut alerts = AlertEngine::new();
+    alerts
+        .add_rule(Rule::parse("too_hot", "greenhouse", "temperature > 30")?.with_hysteresis(1.0))?;
+    let readings = bus.subscribe(&[Topic::Reading]);
+    let alert_bus = bus.clone();
+    thread::spawn(move || {
+        for event in readings {
+            if let Event::Reading(sample) = event {
+                for alert in alerts.evaluate(&sample) {
+                    alert_bus.publish(alert);
+                }
+            }
+        }
+    });
+
+    // 风扇：温度过高时打开，长按按钮手动切换，状态变化发布到总线
+    let mut fan = bus.switch("fan", GpioSwitch::new(FAN_RELAY_PIN, true)?);
+    let controls = bus.subscribe(&[Topic::Alert, Topic::Button]);
+    thread::spawn(move || {
+        for event in controls {
+            let result = match event {
+                Event::Alert(alert) => fan.set(alert.state == AlertState::Raised),
+                Event::Button {
+                    event: ButtonEvent::Held(_),
+                    ..
+                } => {
+                    let on = !fan.is_on();
+                    fan.set(on)
+                }
+                _ => Ok(()),
+            };
+            if let Err(err) = result {
+                eprintln!("控制风扇失败: {}", err);
+            }
+        }
+    });
+
+    let mut button = Button::new(BUTTON_PIN)?;
+    button.on_event(bus.button_callback("fan_button"))?;
+
+    let manager = SensorManager::new();
+    manager.register("greenhouse", Dht11Sensor::new(DHT11_PIN)?)?;
+    let scheduler = Scheduler::new(manager)?;
+    let _errors = bus.forward(scheduler.subscribe_errors());
+    let samples = bus.forward(scheduler.subscribe());
+    let _scheduler = scheduler.start();
+    let _ = samples.join();
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use raspi_sensor::adapter::Dht11Sensor;
+use raspi_sensor::manager::SensorManager;
+use raspi_sensor::scheduler::Scheduler;
+use raspi_sensor::sink::file::{FileFormat, FileLogger, Rotation};
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+
+/// 文件数据记录测试程序
+fn main() -> anyhow::Result<()> {
+    let manager = SensorManager::new();
+    manager.register("outdoor", Dht11Sensor::new(DHT11_PIN)?)?;
+
+    // 同时记录CSV和JSON Lines，CSV每小时轮转一次
+    let mut csv = FileLogger::new("sensors.csv", FileFormat::Csv)?;
+    csv.set_rotation(Some(Rotation {
+        max_age: Some(Duration::from_secs(3600)),
+        ..Rotation::default()
+    }));
+    let mut jsonl = FileLogger::new("sensors.jsonl", FileFormat::JsonLines)?;
+
+    let scheduler = Scheduler::new(manager)?;
+    let samples = scheduler.subscribe();
+    let _handle = scheduler.start();
+    for sample in samples {
+        println!("{}: {}", sample.sensor, sample.reading);
+        for logger in [&mut csv, &mut jsonl] {
+            if let Err(err) = logger.write(&sample) {
+                eprintln!("写入{}失败: {}", logger.path().display(), err);
+            }
+        }
+    }
+    Ok(())
+}
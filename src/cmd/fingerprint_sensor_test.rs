@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use raspi_sensor::sensor::fingerprint::{Fingerprint, FingerprintError};
+
+// 指纹模块接入的串口
+const FINGERPRINT_UART: &str = "/dev/serial0";
+// 录入指纹使用的页码
+const ENROLL_PAGE_ID: u16 = 1;
+
+/// 指纹模块测试程序
+fn main() -> anyhow::Result<()> {
+    // 创建指纹模块实例（R503指纹库容量为200）
+    let mut fingerprint_driver = Fingerprint::new(FINGERPRINT_UART, 57600, 200)?;
+    fingerprint_driver.verify_password(0)?;
+    println!("已存储指纹数量: {}", fingerprint_driver.template_count()?);
+
+    // 指纹库为空时先录入一枚指纹
+    if fingerprint_driver.template_count()? == 0 {
+        println!("请按压手指两次以录入指纹");
+        fingerprint_driver.enroll(ENROLL_PAGE_ID, Duration::from_secs(10))?;
+        println!("录入指纹成功, 页码: {}", ENROLL_PAGE_ID);
+    }
+
+    // 死循环识别指纹
+    loop {
+        match fingerprint_driver.search(Duration::from_secs(10)) {
+            Ok(found) => {
+                println!("识别成功, 页码: {}, 得分: {}", found.page_id, found.score);
+            }
+            Err(err) => match err.downcast_ref::<FingerprintError>() {
+                Some(FingerprintError::NotFound) => println!("未识别的指纹"),
+                _ => eprintln!("识别指纹失败: {}", err),
+            },
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use std::thread;
+use std::time::Duration;
+
+use raspi_sensor::adapter::{Aht30Sensor, Bme280Sensor};
+use raspi_sensor::fusion::Fusion;
+use raspi_sensor::i2c_bus::SharedBus;
+use raspi_sensor::manager::Sensor;
+use raspi_sensor::reading::Quantity;
+
+/// 冗余温湿度传感器融合测试程序
+fn main() -> anyhow::Result<()> {
+    // AHT30和BME280接在同一条I2C总线上
+    let bus = SharedBus::open(1)?;
+    let mut fused = Fusion::new()
+        .with_source("aht30", Aht30Sensor::new(bus.clone(), Some(0x38))?, 1.0)
+        .with_source("bme280", Bme280Sensor::new(bus, Some(0x76))?, 1.0)
+        // BME280的湿度精度（±3%）低于AHT30（±2%）
+        .with_quantity_weight("bme280", Quantity::Humidity, 0.5)
+        .with_tolerance(Quantity::Temperature, 1.0)
+        .with_tolerance(Quantity::Humidity, 5.0);
+    let status = fused.status();
+
+    loop {
+        match fused.read() {
+            Ok(reading) => println!("融合读数: {}", reading),
+            Err(err) => eprintln!("读取失败: {}", err),
+        }
+        for disagreement in status.snapshot().disagreements {
+            eprintln!(
+                "{}不一致（相差{:.2}）: {:?}",
+                disagreement.quantity, disagreement.spread, disagreement.values
+            );
+        }
+        thread::sleep(fused.min_interval().max(Duration::from_secs(2)));
+    }
+}
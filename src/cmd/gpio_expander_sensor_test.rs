@@ -0,0 +1,46 @@
+use std::sync::{Arc, Mutex};
+use std::{thread, time::Duration};
+
+use raspi_sensor::sensor::gpio_expander::{Chip, GpioExpander};
+use rppal::i2c::I2c;
+use sensor_hal::led;
+
+// MCP23017中断信号接入GPIO针脚
+const EXPANDER_INT_PIN: u8 = 5;
+// 按钮接入扩展芯片的引脚（B0）
+const BUTTON_EXPANDER_PIN: u8 = 8;
+// LED灯接入扩展芯片的引脚（A0）
+const LED_EXPANDER_PIN: u8 = 0;
+
+/// GPIO扩展芯片测试程序
+fn main() -> anyhow::Result<()> {
+    // 初始化I2C通信总线
+    let i2c_bus = Arc::new(Mutex::new(I2c::new()?));
+
+    // 创建MCP23017实例并启用中断线
+    let mut expander = GpioExpander::new(i2c_bus, Some(0x20), Chip::MCP23017)?;
+    expander.enable_interrupt(EXPANDER_INT_PIN)?;
+
+    // 扩展引脚可以直接交给sensor-hal的驱动使用
+    let led_pin = expander.output_pin(LED_EXPANDER_PIN)?;
+    let mut led_driver = led::Driver::new(led_pin, led::PinState::High);
+
+    // 监听按钮（低电平为按下）
+    let button_pin = expander.input_pin(BUTTON_EXPANDER_PIN)?;
+    button_pin.on_change(move |level| {
+        let result = if level {
+            led_driver.off()
+        } else {
+            led_driver.on()
+        };
+        if let Err(err) = result {
+            eprintln!("控制LED灯失败: {:?}", err);
+        }
+        println!("检测到按钮{}", if level { "松开" } else { "按下" });
+    })?;
+
+    // 防止程序退出
+    loop {
+        thread::sleep(Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,34 @@
+use std::{thread, time::Duration};
+
+use raspi_sensor::sensor::gps::Gps;
+
+// GPS模块接入的串口
+const GPS_UART: &str = "/dev/serial0";
+
+/// GPS模块测试程序
+fn main() -> anyhow::Result<()> {
+    // 创建GPS模块实例（NEO-6M默认波特率9600）
+    let gps = Gps::new(GPS_UART, 9600)?;
+
+    // 死循环显示定位信息
+    loop {
+        match gps.latest_fix() {
+            Some(fix) => {
+                println!(
+                    "✅ 纬度: {:.6}, 经度: {:.6}, 海拔: {:.1}m, 速度: {:.1}km/h, HDOP: {:.1}, 卫星: {}",
+                    fix.latitude,
+                    fix.longitude,
+                    fix.altitude,
+                    fix.speed_kmh,
+                    fix.hdop,
+                    fix.satellites
+                );
+            }
+            None => {
+                println!("❌ 尚未定位");
+            }
+        }
+
+        thread::sleep(Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,32 @@
+use raspi_sensor::actuator::ActuatorRegistry;
+use raspi_sensor::adapter::Dht11Sensor;
+use raspi_sensor::grpc::GrpcServer;
+use raspi_sensor::manager::SensorManager;
+use raspi_sensor::scheduler::Scheduler;
+use raspi_sensor::switch::GpioSwitch;
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+/// 水泵继电器接入GPIO针脚（低电平触发）
+const PUMP_RELAY_PIN: u8 = 22;
+
+/// gRPC服务测试程序
+///
+/// - `grpcurl -plaintext -import-path proto -proto raspi_sensor.proto localhost:50051 raspi_sensor.v1.SensorService/ListSensors`
+/// - `grpcurl -plaintext -import-path proto -proto raspi_sensor.proto -d '{"name":"pump","command":"ON"}' localhost:50051 raspi_sensor.v1.SensorService/InvokeActuator`
+fn main() -> anyhow::Result<()> {
+    let manager = SensorManager::new();
+    manager.register("outdoor", Dht11Sensor::new(DHT11_PIN)?)?;
+
+    let actuators = ActuatorRegistry::new();
+    actuators.add_switch("pump", GpioSwitch::new(PUMP_RELAY_PIN, false)?)?;
+
+    let grpc = GrpcServer::new().with_actuators(actuators);
+    let scheduler = Scheduler::new(manager)?;
+    grpc.record_from(scheduler.subscribe());
+    let _handle = scheduler.start();
+    grpc.serve("0.0.0.0:50051")?
+        .join()
+        .map_err(|_| anyhow::anyhow!("gRPC服务线程异常退出"))?;
+    Ok(())
+}
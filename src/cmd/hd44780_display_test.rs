@@ -0,0 +1,46 @@
+use std::sync::{Arc, Mutex};
+use std::{thread, time::Duration};
+
+use raspi_sensor::display::hd44780::HD44780;
+use raspi_sensor::std_clock::StdClock;
+use rppal::gpio::{Gpio, Mode};
+use rppal::i2c::I2c;
+use sensor_hal::dht11;
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+
+/// 温度计图标
+const THERMOMETER: [u8; 8] = [0x04, 0x0A, 0x0A, 0x0A, 0x0E, 0x1F, 0x1F, 0x0E];
+
+/// HD44780 1602液晶屏测试程序（I2C转接板，显示DHT11温湿度）
+fn main() -> anyhow::Result<()> {
+    let gpio = Gpio::new()?;
+    let clock = StdClock::new();
+
+    // 创建液晶屏实例
+    let i2c_bus = Arc::new(Mutex::new(I2c::new()?));
+    let mut lcd = HD44780::new_i2c(i2c_bus, Some(0x27), 16, 2)?;
+    lcd.create_char(0, &THERMOMETER)?;
+
+    // 创建DHT11传感器驱动实例
+    let dht11_gpio = gpio.get(DHT11_PIN)?.into_io(Mode::Output);
+    let mut dht11_driver = dht11::Driver::new(&clock, dht11_gpio)?;
+
+    // 死循环刷新显示
+    loop {
+        match dht11_driver.read() {
+            Ok((temp, hum)) => {
+                lcd.write_line(0, &format!("\u{0} {:.1}C", temp))?;
+                lcd.write_line(1, &format!("RH {:.1}%", hum))?;
+            }
+            Err(e) => {
+                lcd.write_line(0, "Sensor error")?;
+                eprintln!("❌ 读取失败: {:?}", e);
+            }
+        }
+
+        // DHT11芯片必须间隔2秒以上才能读取下一次数据
+        thread::sleep(Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,39 @@
+use std::thread;
+use std::time::Duration;
+
+use raspi_sensor::scale::Scale;
+use raspi_sensor::sensor::hx711::{ChannelGain, Hx711Array, Rate};
+
+// 4个HX711共享的时钟引脚
+const HX711_CLOCK_PIN: u8 = 24;
+// 4个HX711的数据引脚（左前、右前、左后、右后）
+const HX711_DATA_PINS: [u8; 4] = [23, 25, 8, 7];
+// 共享的速率引脚
+const HX711_RATE_PIN: u8 = 12;
+
+/// 四角称重平台测试程序（4片HX711同步采样，80SPS）
+fn main() -> anyhow::Result<()> {
+    let mut array = Hx711Array::new(
+        HX711_CLOCK_PIN,
+        &HX711_DATA_PINS,
+        ChannelGain::ChannelA128,
+        Some(HX711_RATE_PIN),
+    )?;
+    array.set_rate(Rate::Sps80);
+
+    // 打印一次各角的原始读数
+    println!("各角ADC读数: {:?}", array.read()?);
+
+    // 四个角的读数之和作为秤的读数
+    let mut scale = Scale::new(array, 429.58);
+    scale.set_samples(8);
+    scale.tare()?;
+
+    loop {
+        match scale.read_weight() {
+            Ok(weight) => println!("读取到重量: {:.1}g", weight),
+            Err(err) => eprintln!("读取重量失败: {}", err),
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
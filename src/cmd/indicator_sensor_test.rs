@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use raspi_sensor::adapter::Dht11Sensor;
+use raspi_sensor::alerts::{AlertEngine, Rule};
+use raspi_sensor::indicator::{Indicator, Pattern, Trigger};
+use raspi_sensor::manager::SensorManager;
+use raspi_sensor::scheduler::Scheduler;
+use raspi_sensor::sink::{ConsoleSink, Pipeline};
+use raspi_sensor::switch::GpioSwitch;
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+/// 状态LED接入GPIO针脚
+const LED_PIN: u8 = 17;
+/// 蜂鸣器接入GPIO针脚
+const BUZZER_PIN: u8 = 27;
+/// 风扇继电器接入GPIO针脚
+const FAN_RELAY_PIN: u8 = 22;
+
+/// 状态指示测试程序
+fn main() -> anyhow::Result<()> {
+    let manager = SensorManager::new();
+    manager.register("greenhouse", Dht11Sensor::new(DHT11_PIN)?)?;
+
+    // 校验失败快闪3次，其他读取失败常亮，正常时每2秒闪一次作为心跳
+    let led = Indicator::new(GpioSwitch::new(LED_PIN, true)?)
+        .when(Trigger::error_containing("校验"), Pattern::fast_blinks(3))
+        .when(Trigger::any_error(), Pattern::On)
+        .idle(Pattern::slow_blinks(1))
+        .start();
+    // 湿度过低时蜂鸣器短鸣2声
+    let buzzer = Indicator::new(GpioSwitch::new(BUZZER_PIN, true)?)
+        .when(Trigger::alert("too_dry"), Pattern::chirp(2))
+        .start();
+    // 温度过高时风扇继电器吸合
+    let fan = Indicator::new(GpioSwitch::new(FAN_RELAY_PIN, true)?)
+        .when(Trigger::alert("too_hot"), Pattern::On)
+        .start();
+
+    let mut alerts = AlertEngine::new();
+    alerts.add_rule(
+        Rule::parse("too_hot", "greenhouse", "temperature > 30 for 60s")?.with_hysteresis(1.0),
+    )?;
+    alerts.add_rule(
+        Rule::parse("too_dry", "greenhouse", "humidity < 40")?
+            .with_hysteresis(3.0)
+            .with_cooldown(Duration::from_secs(600)),
+    )?;
+    alerts.on_alert(move |event| {
+        buzzer.alert(event);
+        fan.alert(event);
+    });
+
+    let mut pipeline = Pipeline::new();
+    pipeline
+        .add("console", ConsoleSink)
+        .add("alerts", alerts)
+        .add("led", led.clone());
+
+    let scheduler = Scheduler::new(manager)?;
+    let samples = scheduler.subscribe();
+    let _errors = led.watch_errors(scheduler.subscribe_errors());
+    let _scheduler = scheduler.start();
+    pipeline.start(samples).join();
+    Ok(())
+}
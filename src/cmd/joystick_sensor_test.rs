@@ -0,0 +1,37 @@
+use std::thread;
+use std::time::Duration;
+
+use raspi_sensor::sensor::joystick::{Joystick, JoystickEvent};
+use raspi_sensor::sensor::mcp3008::MCP3008;
+use rppal::spi::{Bus, SlaveSelect};
+
+// 摇杆按键接入GPIO针脚
+const BUTTON_PIN: u8 = 17;
+
+/// 双轴摇杆测试程序（MCP3008 CH0接VRx，CH1接VRy）
+fn main() -> anyhow::Result<()> {
+    // 创建ADC实例（SPI0 CE0）
+    let adc = MCP3008::new(Bus::Spi0, SlaveSelect::Ss0)?;
+
+    // 创建摇杆实例并校准中心位置
+    let mut joystick = Joystick::new(adc.channel(0)?, adc.channel(1)?, Some(BUTTON_PIN))?;
+    joystick.calibrate_center(32)?;
+    joystick.set_dead_zone(0.15);
+
+    let _handle = joystick.on_event(|event, state| match event {
+        JoystickEvent::Direction(direction) => {
+            println!(
+                "方向: {:?} (x: {:.2}, y: {:.2})",
+                direction, state.x, state.y
+            )
+        }
+        JoystickEvent::Button(pressed) => {
+            println!("按键: {}", if pressed { "按下" } else { "松开" })
+        }
+    });
+
+    // 防止程序退出
+    loop {
+        thread::sleep(Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,46 @@
+use std::thread;
+use std::time::Duration;
+
+use raspi_sensor::sensor::keypad::{KEYMAP_4X4, KeyEvent, Keypad};
+
+// 键盘行引脚接入GPIO针脚
+const ROW_PINS: [u8; 4] = [5, 6, 13, 19];
+// 键盘列引脚接入GPIO针脚
+const COL_PINS: [u8; 4] = [12, 16, 20, 21];
+// 密码
+const PIN_CODE: &str = "1234";
+
+/// 4×4矩阵键盘测试程序（输入密码，'#'确认，'*'清除）
+fn main() -> anyhow::Result<()> {
+    let keypad = Keypad::new(&ROW_PINS, &COL_PINS, &KEYMAP_4X4)?;
+
+    let mut input = String::new();
+    let _handle = keypad.on_key(move |event| {
+        let KeyEvent::Pressed(key) = event else {
+            return;
+        };
+        match key {
+            '#' => {
+                if input == PIN_CODE {
+                    println!("密码正确");
+                } else {
+                    println!("密码错误");
+                }
+                input.clear();
+            }
+            '*' => {
+                input.clear();
+                println!("已清除");
+            }
+            _ => {
+                input.push(key);
+                println!("已输入: {}", "*".repeat(input.len()));
+            }
+        }
+    });
+
+    // 防止程序退出
+    loop {
+        thread::sleep(Duration::from_secs(1));
+    }
+}
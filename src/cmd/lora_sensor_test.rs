@@ -0,0 +1,73 @@
+use std::time::{Duration, SystemTime};
+
+use raspi_sensor::adapter::Dht11Sensor;
+use raspi_sensor::codec::binary::BinaryCodec;
+use raspi_sensor::manager::SensorManager;
+use raspi_sensor::radio::payload;
+use raspi_sensor::radio::sx127x::{LoRaConfig, LoRaUplink, Sx127x};
+use raspi_sensor::scheduler::Scheduler;
+use rppal::spi::{Bus, SlaveSelect};
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+/// SX127x复位引脚接入GPIO针脚
+const LORA_RESET_PIN: u8 = 22;
+/// 载波频率（Ra-02模块）
+const FREQUENCY: u64 = 433_000_000;
+
+/// LoRa测试程序
+///
+/// - `lora-sensor-test`：节点，每30秒发送一次DHT11读数
+/// - `lora-sensor-test gateway`：网关，接收并解码节点发送的读数
+fn main() -> anyhow::Result<()> {
+    let mut radio = Sx127x::new(
+        Bus::Spi0,
+        SlaveSelect::Ss0,
+        Some(LORA_RESET_PIN),
+        LoRaConfig::new(FREQUENCY),
+    )?;
+    println!("SX127x芯片版本: 0x{:02X}", radio.version()?);
+    // 节点和网关使用相同的传感器列表
+    let codec = BinaryCodec::new(&["outdoor"])?;
+
+    if std::env::args().nth(1).as_deref() == Some("gateway") {
+        loop {
+            match radio.receive(Duration::from_secs(60)) {
+                Ok(Some(packet)) => {
+                    match payload::decode(&codec, &packet.data, SystemTime::now()) {
+                        Ok(uplink) => {
+                            for sample in uplink.samples {
+                                println!(
+                                    "✅ 节点{} #{} {}: {} ({}dBm, {:.1}dB)",
+                                    uplink.node,
+                                    uplink.sequence,
+                                    sample.sensor,
+                                    sample.reading,
+                                    packet.rssi,
+                                    packet.snr
+                                );
+                            }
+                        }
+                        Err(err) => eprintln!("❌ 解码失败: {}", err),
+                    }
+                }
+                Ok(None) => println!("60秒内没有收到数据包"),
+                Err(err) => eprintln!("❌ 接收失败: {}", err),
+            }
+        }
+    }
+
+    let manager = SensorManager::new();
+    manager.register("outdoor", Dht11Sensor::new(DHT11_PIN)?)?;
+    println!("单个数据包的空中时间: {:?}", radio.config().time_on_air(17));
+
+    let uplink = LoRaUplink::new(radio, 1, codec).with_interval(Duration::from_secs(30));
+    let scheduler = Scheduler::new(manager)?;
+    uplink.record_from(scheduler.subscribe());
+    let _handle = scheduler.start();
+    uplink
+        .start()
+        .join()
+        .map_err(|_| anyhow::anyhow!("LoRa上行线程异常退出"))?;
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use std::thread;
+use std::time::Duration;
+
+use raspi_sensor::display::max7219::MAX7219;
+use rppal::spi::{Bus, SlaveSelect};
+
+// 级联的点阵模块数量
+const MATRIX_DEVICES: usize = 4;
+
+/// MAX7219点阵显示测试程序（滚动字幕）
+fn main() -> anyhow::Result<()> {
+    // 创建点阵实例（SPI0 CE0，4个模块级联）
+    let mut matrix = MAX7219::new(Bus::Spi0, SlaveSelect::Ss0, MATRIX_DEVICES)?;
+    matrix.set_intensity(2)?;
+
+    // 画出边框
+    for x in 0..matrix.width() {
+        matrix.set_pixel(x, 0, true);
+        matrix.set_pixel(x, 7, true);
+    }
+    for y in 0..8 {
+        matrix.set_pixel(0, y, true);
+        matrix.set_pixel(matrix.width() - 1, y, true);
+    }
+    matrix.flush()?;
+    thread::sleep(Duration::from_secs(2));
+
+    // 死循环滚动显示
+    loop {
+        matrix.scroll_text("Hello Raspberry Pi!", Duration::from_millis(40))?;
+    }
+}
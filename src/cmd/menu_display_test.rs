@@ -0,0 +1,65 @@
+use std::sync::{Arc, Mutex};
+
+use raspi_sensor::display::hd44780::HD44780;
+use raspi_sensor::sensor::button::{Button, EventTiming};
+use raspi_sensor::sensor::rotary_encoder::RotaryEncoder;
+use raspi_sensor::ui::menu::{self, Menu, MenuItem};
+use rppal::i2c::I2c;
+
+// 旋转编码器A相（CLK）接入GPIO针脚
+const CLK_PIN: u8 = 5;
+// 旋转编码器B相（DT）接入GPIO针脚
+const DT_PIN: u8 = 6;
+// 旋转编码器按钮（SW）接入GPIO针脚
+const SW_PIN: u8 = 13;
+
+/// 旋转编码器菜单测试程序（HD44780 2004液晶屏，I2C转接板）
+fn main() -> anyhow::Result<()> {
+    // 创建液晶屏实例
+    let i2c_bus = Arc::new(Mutex::new(I2c::new()?));
+    let lcd = HD44780::new_i2c(i2c_bus, Some(0x27), 20, 4)?;
+
+    // 模拟的设定值
+    let setpoint = Arc::new(Mutex::new(25.0));
+    let fan = Arc::new(Mutex::new(50.0));
+    let value = |shared: &Arc<Mutex<f64>>| {
+        let (get, set) = (shared.clone(), shared.clone());
+        (
+            move || get.lock().map_or(0.0, |value| *value),
+            move |value| {
+                if let Ok(mut shared) = set.lock() {
+                    *shared = value;
+                }
+                println!("设置为: {}", value);
+                Ok(())
+            },
+        )
+    };
+    let (get_setpoint, set_setpoint) = value(&setpoint);
+    let (get_fan, set_fan) = value(&fan);
+
+    let items = vec![
+        MenuItem::value("Setpoint", 10.0..=35.0, 0.5, get_setpoint, set_setpoint).with_unit("C"),
+        MenuItem::submenu(
+            "Fan",
+            vec![MenuItem::value("Duty", 0.0..=100.0, 5.0, get_fan, set_fan).with_unit("%")],
+        ),
+        MenuItem::action("Tare", || {
+            println!("去皮");
+            Ok(())
+        }),
+        MenuItem::action("Fail", || Err(anyhow::anyhow!("failed"))),
+    ];
+
+    // 短按确认，长按返回
+    let mut button = Button::new(SW_PIN)?;
+    let inputs = menu::inputs(
+        RotaryEncoder::new(CLK_PIN, DT_PIN)?,
+        &mut button,
+        EventTiming::default(),
+    )?;
+    let handle = Menu::new(lcd, "Main", items).run(inputs);
+    let _ = handle.join();
+    // OK
+    Ok(())
+}
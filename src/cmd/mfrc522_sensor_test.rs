@@ -0,0 +1,33 @@
+use std::thread;
+use std::time::Duration;
+
+use raspi_sensor::sensor::mfrc522::{DEFAULT_KEY, KeyType, MFRC522};
+use rppal::spi::{Bus, SlaveSelect};
+
+// RC522复位引脚接入GPIO针脚
+const RC522_RST_PIN: u8 = 25;
+
+/// RC522 RFID读卡器测试程序
+fn main() -> anyhow::Result<()> {
+    // 创建读卡器实例（SPI0 CE0）
+    let mut rc522_driver = MFRC522::new(Bus::Spi0, SlaveSelect::Ss0, Some(RC522_RST_PIN))?;
+    println!("RC522芯片版本: 0x{:02X}", rc522_driver.version()?);
+
+    // 监听刷卡事件
+    let _handle = rc522_driver.on_card(|reader, uid| {
+        println!("检测到卡片, UID: {}", uid);
+        // 使用默认密钥读取扇区1的第1块
+        match reader
+            .authenticate(KeyType::KeyA, 4, &DEFAULT_KEY, uid)
+            .and_then(|_| reader.read_block(4))
+        {
+            Ok(data) => println!("块4数据: {:02X?}", data),
+            Err(err) => eprintln!("读取块4失败: {}", err),
+        }
+    });
+
+    // 防止程序退出
+    loop {
+        thread::sleep(Duration::from_millis(100));
+    }
+}
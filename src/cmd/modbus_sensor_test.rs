@@ -0,0 +1,48 @@
+use raspi_sensor::actuator::ActuatorRegistry;
+use raspi_sensor::adapter::Dht11Sensor;
+use raspi_sensor::manager::SensorManager;
+use raspi_sensor::modbus::{ModbusServer, RegisterFormat, RegisterMap};
+use raspi_sensor::reading::Quantity;
+use raspi_sensor::scheduler::Scheduler;
+use raspi_sensor::switch::GpioSwitch;
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+/// 水泵继电器接入GPIO针脚（低电平触发）
+const PUMP_RELAY_PIN: u8 = 22;
+/// RS-485收发器DE/RE接入GPIO针脚
+const RS485_DIRECTION_PIN: u8 = 17;
+
+/// Modbus从站测试程序
+///
+/// - 30001：温度×10，30002：湿度×10，40001：水泵
+/// - `mbpoll -m tcp -a 1 -t 3 -r 1 -c 2 127.0.0.1 -p 5020`
+/// - `mbpoll -m rtu -a 1 -b 9600 -P none -t 4 -r 1 /dev/ttyUSB0 1`
+fn main() -> anyhow::Result<()> {
+    let manager = SensorManager::new();
+    manager.register("outdoor", Dht11Sensor::new(DHT11_PIN)?)?;
+
+    let actuators = ActuatorRegistry::new();
+    actuators.add_switch("pump", GpioSwitch::new(PUMP_RELAY_PIN, false)?)?;
+
+    let mut map = RegisterMap::new();
+    map.input(
+        0,
+        "outdoor",
+        Quantity::Temperature,
+        RegisterFormat::I16(10.0),
+    )?;
+    map.input(1, "outdoor", Quantity::Humidity, RegisterFormat::U16(10.0))?;
+    map.holding(0, "pump", 1.0)?;
+
+    let modbus = ModbusServer::new(map).with_actuators(actuators);
+    let scheduler = Scheduler::new(manager)?;
+    modbus.record_from(scheduler.subscribe());
+    let _handle = scheduler.start();
+    let _rtu = modbus.serve_rtu("/dev/ttyAMA0", 9600, Some(RS485_DIRECTION_PIN))?;
+    modbus
+        .serve_tcp("0.0.0.0:5020")?
+        .join()
+        .map_err(|_| anyhow::anyhow!("Modbus TCP服务线程异常退出"))?;
+    Ok(())
+}
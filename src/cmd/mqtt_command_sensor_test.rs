@@ -0,0 +1,37 @@
+use std::time::Duration;
+
+use raspi_sensor::actuator::ActuatorRegistry;
+use raspi_sensor::mqtt::{MqttCommands, MqttConfig};
+use raspi_sensor::pwm_wapper::PwmWapper;
+use raspi_sensor::switch::{GpioSwitch, PwmSwitch};
+use rppal::pwm::Channel;
+
+/// 水泵继电器接入GPIO针脚（低电平触发）
+const PUMP_RELAY_PIN: u8 = 22;
+/// MQTT服务器地址
+const MQTT_HOST: &str = "192.168.1.10";
+
+/// MQTT远程控制测试程序
+///
+/// - `mosquitto_pub -t raspi-sensor/raspi_greenhouse/pump/set -m ON`
+/// - `mosquitto_pub -t raspi-sensor/raspi_greenhouse/fan/set -m 50%`（风扇接硬件PWM0，即GPIO18）
+fn main() -> anyhow::Result<()> {
+    let mut config = MqttConfig::new(MQTT_HOST, "raspi_greenhouse");
+    config.credentials = Some(("sensor".to_string(), "password".to_string()));
+
+    let actuators = ActuatorRegistry::new().with_rate_limit(Duration::from_millis(500));
+    actuators.add_switch("pump", GpioSwitch::new(PUMP_RELAY_PIN, false)?)?;
+    actuators.add_pwm(
+        "fan",
+        PwmSwitch::new(PwmWapper::hardware(Channel::Pwm0, 25000.0)?)?,
+    )?;
+    let commands = MqttCommands::new(config, actuators);
+    for (name, _) in commands.actuators().list() {
+        println!("命令主题: {}", commands.config().command_topic(&name));
+    }
+    commands
+        .start()?
+        .join()
+        .map_err(|_| anyhow::anyhow!("MQTT命令线程异常退出"))?;
+    Ok(())
+}
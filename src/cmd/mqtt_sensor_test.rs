@@ -0,0 +1,36 @@
+use std::sync::{Arc, Mutex};
+
+use raspi_sensor::adapter::{Bme280Sensor, Dht11Sensor};
+use raspi_sensor::manager::SensorManager;
+use raspi_sensor::mqtt::{MqttConfig, MqttPublisher};
+use raspi_sensor::scheduler::Scheduler;
+use rppal::i2c::I2c;
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+/// MQTT服务器地址
+const MQTT_HOST: &str = "192.168.1.10";
+
+/// MQTT发布测试程序（读数自动出现在Home Assistant中）
+fn main() -> anyhow::Result<()> {
+    let i2c_bus = Arc::new(Mutex::new(I2c::new()?));
+
+    let manager = SensorManager::new();
+    manager.register("outdoor", Dht11Sensor::new(DHT11_PIN)?)?;
+    manager.register("greenhouse", Bme280Sensor::new(i2c_bus, Some(0x76))?)?;
+
+    // 连接MQTT服务器
+    let mut config = MqttConfig::new(MQTT_HOST, "raspi_greenhouse");
+    config.credentials = Some(("sensor".to_string(), "password".to_string()));
+    let publisher = MqttPublisher::connect(config)?;
+
+    // 调度器的读数全部发布到MQTT
+    let scheduler = Scheduler::new(manager)?;
+    let samples = scheduler.subscribe();
+    let _handle = scheduler.start();
+    publisher
+        .publish_from(samples)
+        .join()
+        .map_err(|_| anyhow::anyhow!("MQTT发布线程异常退出"))?;
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use raspi_sensor::calibration::{CalibrationStore, FileStore};
+use raspi_sensor::scale::Scale;
+use raspi_sensor::sensor::button::{Button, ButtonEvent};
+use raspi_sensor::sensor::nau7802::NAU7802;
+use rppal::i2c::I2c;
+
+// Button接入GPIO针脚
+const BUTTON_PIN: u8 = 17;
+// 校准数据文件
+const CALIBRATION_FILE: &str = "nau7802_calibration.txt";
+
+/// NAU7802称重传感器测试程序
+///
+/// 短按去皮，长按（3秒以上）使用100g砝码矫正
+fn main() -> anyhow::Result<()> {
+    // 初始化I2C通信总线
+    let i2c_bus = Arc::new(Mutex::new(I2c::new()?));
+    // 创建电子秤实例并恢复校准数据
+    let adc = NAU7802::new(i2c_bus)?;
+    let mut store = FileStore::new(CALIBRATION_FILE);
+    let mut calibration = store.load()?;
+    let scale = Arc::new(Mutex::new(Scale::new(adc, 1.0)));
+    {
+        let mut scale = scale.lock().map_err(|_| anyhow::anyhow!("电子秤繁忙"))?;
+        scale.load_calibration(&calibration, "nau7802");
+        // 开机去皮
+        scale.tare()?;
+    }
+
+    // 监听按钮事件
+    let mut button = Button::new(BUTTON_PIN)?;
+    let button_scale = scale.clone();
+    button.on_event(move |event| {
+        let ButtonEvent::Released(duration) = event else {
+            return;
+        };
+        let Ok(mut scale) = button_scale.lock() else {
+            return;
+        };
+        if duration > Duration::from_secs(3) {
+            // TODO: 这里假设放置在秤盘上的砝码是100g
+            match scale.calibrate(100.0) {
+                Ok(factor) => {
+                    println!("设置转换矫正因子成功, 当前矫正因子: {}", factor);
+                    scale.save_calibration(&mut calibration, "nau7802");
+                    if let Err(err) = store.save(&calibration) {
+                        eprintln!("保存校准数据失败: {}", err);
+                    }
+                }
+                Err(err) => eprintln!("设置转换矫正因子失败: {}", err),
+            }
+        } else if let Err(err) = scale.tare() {
+            eprintln!("去皮失败: {}", err);
+        }
+    })?;
+
+    // 循环显示重量
+    loop {
+        let weight = scale
+            .lock()
+            .map_err(|_| anyhow::anyhow!("电子秤繁忙"))?
+            .read_weight();
+        match weight {
+            Ok(weight) => println!("读取到重量: {:.1}g", weight),
+            Err(err) => eprintln!("读取重量失败: {}", err),
+        }
+        thread::sleep(Duration::from_millis(200));
+    }
+}
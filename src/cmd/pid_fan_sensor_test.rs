@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+use raspi_sensor::adapter::Dht11Sensor;
+use raspi_sensor::control::Pid;
+use raspi_sensor::control::pid::PidLoop;
+use raspi_sensor::pwm_wapper::PwmWapper;
+use raspi_sensor::reading::Quantity;
+use raspi_sensor::switch::PwmSwitch;
+use rppal::pwm::Channel;
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+
+/// PID风扇调速测试程序：温度高于30℃时风扇加速（风扇接硬件PWM0，即GPIO18）
+fn main() -> anyhow::Result<()> {
+    let fan = PwmSwitch::new(PwmWapper::hardware(Channel::Pwm0, 25000.0)?)?;
+    let pid = Pid::new(0.2, 0.01, 0.05, 30.0)
+        .reversed()
+        .with_derivative_filter(Duration::from_secs(5));
+    let control = PidLoop::new(
+        Dht11Sensor::new(DHT11_PIN)?,
+        Quantity::Temperature,
+        pid,
+        fan,
+    )
+    // 传感器故障时全速运行
+    .with_failsafe(1.0);
+
+    control
+        .run(Duration::from_secs(2), |status| match status.measurement {
+            Some(temperature) => println!(
+                "温度: {:.1}℃, 风扇: {:.0}%",
+                temperature,
+                status.output * 100.0
+            ),
+            None => eprintln!("读取温度失败，风扇全速运行"),
+        })
+        .join()
+        .map_err(|_| anyhow::anyhow!("PID线程异常退出"))?;
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use std::thread;
+use std::time::Duration;
+
+use raspi_sensor::adapter::Dht11Sensor;
+use raspi_sensor::manager::SensorManager;
+use raspi_sensor::scheduler::Scheduler;
+use raspi_sensor::sink::sqlite::SqliteLogger;
+use raspi_sensor::sink::{ConsoleSink, Pipeline};
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+
+/// 读数分发管道测试程序（同时输出到控制台和SQLite）
+fn main() -> anyhow::Result<()> {
+    let manager = SensorManager::new();
+    manager.register("outdoor", Dht11Sensor::new(DHT11_PIN)?)?;
+
+    let mut pipeline = Pipeline::new();
+    pipeline
+        .add("console", ConsoleSink)
+        .add("sqlite", SqliteLogger::open("sensors.db")?);
+
+    let scheduler = Scheduler::new(manager)?;
+    let samples = scheduler.subscribe();
+    let scheduler = scheduler.start();
+    let pipeline = pipeline.start(samples);
+
+    // 运行1分钟后打印各输出的统计
+    thread::sleep(Duration::from_secs(60));
+    for (name, stats) in pipeline.stats() {
+        println!(
+            "{}: 写入{}条, 失败{}条, 丢弃{}条",
+            name, stats.written, stats.errors, stats.dropped
+        );
+    }
+    // 停止调度器后输入通道关闭，管道写完剩余读数后退出
+    scheduler.stop();
+    pipeline.join();
+    Ok(())
+}
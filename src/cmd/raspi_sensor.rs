@@ -0,0 +1,805 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use rppal::i2c::I2c;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tracing_subscriber::fmt::format::FmtSpan;
+
+use raspi_sensor::board::{Board, PinUsage};
+use raspi_sensor::calibration::{CalibrationStore, FileStore};
+use raspi_sensor::config::{HardwareConfig, SensorConfig};
+#[cfg(feature = "dashboard")]
+use raspi_sensor::dashboard::Dashboard;
+use raspi_sensor::debug;
+use raspi_sensor::diagnostics::{CheckStatus, DiagnosticsReport};
+use raspi_sensor::gpio;
+use raspi_sensor::privilege;
+use raspi_sensor::scale::{Scale, WeightAdc};
+use raspi_sensor::scheduler::Scheduler;
+use raspi_sensor::sensor::hx711::{ChannelGain, HX711};
+use raspi_sensor::sensor::nau7802::NAU7802;
+use raspi_sensor::sensor::uln2003a::{Direction, StepMode, ULN2003A};
+use raspi_sensor::server::SensorServer;
+use raspi_sensor::service::{self, Notifier, UnitFile};
+use raspi_sensor::watchdog::Watchdog;
+
+/// 树莓派传感器命令行工具
+#[derive(Parser)]
+#[command(name = "raspi-sensor", version, about)]
+struct Cli {
+    /// 以JSON格式输出（每行一个JSON对象）
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// 输出诊断日志到标准错误（-v为调试信息，-vv包括每次总线传输）
+    #[arg(long, short, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// 硬件配置文件（TOML/YAML），配置后可按名称读取传感器
+    #[arg(long, short, global = true)]
+    config: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 读取传感器（型号或配置文件中的名称）
+    Read(ReadArgs),
+    /// 扫描总线上的设备
+    Scan {
+        #[command(subcommand)]
+        bus: ScanBus,
+    },
+    /// 电子秤（去皮、矫正、称重）
+    Scale(ScaleArgs),
+    /// 步进电机（ULN2003A驱动的28BYJ-48）
+    Motor(MotorArgs),
+    /// 显示开发板型号和外设映射
+    Info,
+    /// 运行配置文件中所有传感器的自检并给出接线提示
+    Doctor(DoctorArgs),
+    /// 显示GPIO的模式、电平和使用者
+    Pins(PinsArgs),
+    /// 按配置文件持续轮询所有传感器（systemd服务的启动命令）
+    Run(RunArgs),
+    /// 按配置文件轮询所有传感器，在终端中实时显示读数、曲线和失败次数
+    #[cfg(feature = "dashboard")]
+    Dashboard(DashboardArgs),
+    /// 按当前配置生成systemd服务单元文件
+    InstallService(InstallServiceArgs),
+}
+
+#[derive(Args)]
+struct ReadArgs {
+    /// 传感器型号（dht11、aht30、bme280、hx711、nau7802、iio、hwmon、w1-therm），使用配置文件时为传感器名称
+    sensor: String,
+    /// 单总线引脚（DHT11）
+    #[arg(long)]
+    pin: Option<u8>,
+    /// I2C总线编号
+    #[arg(long)]
+    bus: Option<u8>,
+    /// I2C设备地址（如0x76）
+    #[arg(long, value_parser = parse_u8)]
+    addr: Option<u8>,
+    /// 时钟引脚（HX711）
+    #[arg(long)]
+    clock_pin: Option<u8>,
+    /// 数据引脚（HX711）
+    #[arg(long)]
+    data_pin: Option<u8>,
+    /// 内核驱动的设备（iio、hwmon、w1-therm）
+    #[arg(long)]
+    device: Option<String>,
+    /// 读取次数（0为持续读取）
+    #[arg(long, short = 'n', default_value_t = 1)]
+    count: u32,
+    /// 读取间隔（毫秒）
+    #[arg(long, default_value_t = 1000)]
+    interval_ms: u64,
+    /// 记录读取时的引脚波形并保存到文件（.vcd或.csv，DHT11、HX711支持），读取多次时保存最后一次
+    #[arg(long)]
+    capture: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum ScanBus {
+    /// 扫描I2C总线
+    I2c {
+        /// I2C总线编号
+        #[arg(long, default_value_t = 1)]
+        bus: u8,
+    },
+}
+
+#[derive(Args)]
+struct ScaleArgs {
+    #[command(subcommand)]
+    action: ScaleAction,
+    /// 称重ADC型号
+    #[arg(long, value_enum, default_value_t = AdcKind::Hx711)]
+    adc: AdcKind,
+    /// 时钟引脚（HX711）
+    #[arg(long, default_value_t = 5)]
+    clock_pin: u8,
+    /// 数据引脚（HX711）
+    #[arg(long, default_value_t = 6)]
+    data_pin: u8,
+    /// I2C总线编号（NAU7802）
+    #[arg(long, default_value_t = 1)]
+    bus: u8,
+    /// 校准数据文件
+    #[arg(long, default_value = "scale.cal")]
+    calibration: String,
+    /// 校准数据名称前缀
+    #[arg(long, default_value = "scale")]
+    prefix: String,
+    /// 每次称重的采样次数
+    #[arg(long, default_value_t = 10)]
+    samples: usize,
+}
+
+#[derive(Subcommand)]
+enum ScaleAction {
+    /// 去皮（记录当前读数为0点）并保存
+    Tare,
+    /// 放上已知重量的砝码后矫正并保存
+    Calibrate {
+        /// 砝码重量
+        weight: f32,
+    },
+    /// 称重
+    Read,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum AdcKind {
+    Hx711,
+    Nau7802,
+}
+
+#[derive(Args)]
+struct MotorArgs {
+    #[command(subcommand)]
+    action: MotorAction,
+    /// 4相引脚（IN1~IN4）
+    #[arg(long, value_delimiter = ',', num_args = 4, default_values_t = [6, 13, 19, 26])]
+    pins: Vec<u8>,
+    /// 步进模式
+    #[arg(long, value_enum, default_value_t = Mode::Half)]
+    mode: Mode,
+    /// 每步间隔（毫秒，最小3毫秒）
+    #[arg(long, default_value_t = 3)]
+    delay_ms: u64,
+    /// 转速（转/分钟，设置后忽略--delay-ms）
+    #[arg(long)]
+    rpm: Option<f32>,
+}
+
+#[derive(Subcommand)]
+enum MotorAction {
+    /// 转动指定角度（如90deg、-1.5rev、512steps，负数为逆时针）
+    Rotate {
+        #[arg(allow_hyphen_values = true)]
+        angle: String,
+    },
+}
+
+#[derive(Args)]
+struct DoctorArgs {
+    /// 同时检查步进电机的4相引脚（IN1~IN4，电机会来回转动）
+    #[arg(long, value_delimiter = ',', num_args = 4)]
+    motor: Option<Vec<u8>>,
+}
+
+#[derive(Args)]
+struct PinsArgs {
+    /// 只显示指定的引脚（如17,27），默认为40针排针上的所有GPIO
+    #[arg(long, value_delimiter = ',')]
+    pin: Vec<u8>,
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// HTTP服务监听地址（如0.0.0.0:8080），由systemd套接字激活时使用传入的套接字
+    #[arg(long)]
+    listen: Option<String>,
+    /// HTTP服务为每个传感器保留的历史读数条数
+    #[arg(long, default_value_t = 1000)]
+    history: usize,
+    /// 不输出读数（只通过HTTP服务提供）
+    #[arg(long, short)]
+    quiet: bool,
+    /// 低CPU占用模式（Pi Zero等单核设备）：合并轮询，计时线程的短等待让出CPU
+    #[arg(long)]
+    low_power: bool,
+}
+
+#[cfg(feature = "dashboard")]
+#[derive(Args)]
+struct DashboardArgs {
+    /// 每个物理量的曲线保留的读数条数
+    #[arg(long, default_value_t = 120)]
+    history: usize,
+    /// 低CPU占用模式（Pi Zero等单核设备）
+    #[arg(long)]
+    low_power: bool,
+}
+
+#[derive(Args)]
+struct InstallServiceArgs {
+    /// 服务名称
+    #[arg(long, default_value = "raspi-sensor")]
+    name: String,
+    /// 运行服务的用户（需要加入gpio、i2c、spi等用户组），默认为root
+    #[arg(long)]
+    user: Option<String>,
+    /// HTTP服务监听地址（如0.0.0.0:8080）
+    #[arg(long)]
+    listen: Option<String>,
+    /// 由systemd监听--listen的地址（同时生成.socket单元）
+    #[arg(long, requires = "listen")]
+    socket_activation: bool,
+    /// 服务看门狗超时时间（秒，0为不启用）
+    #[arg(long, default_value_t = 60)]
+    watchdog_sec: u64,
+    /// 单元文件目录
+    #[arg(long, default_value = "/etc/systemd/system")]
+    dir: PathBuf,
+    /// 只输出单元文件内容，不写入
+    #[arg(long)]
+    dry_run: bool,
+    /// 服务以低CPU占用模式运行（Pi Zero等单核设备）
+    #[arg(long)]
+    low_power: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Mode {
+    Wave,
+    Full,
+    Half,
+}
+
+/// 解析十进制或0x开头的十六进制数
+fn parse_u8(text: &str) -> Result<u8, String> {
+    let result = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u8::from_str_radix(hex, 16),
+        None => text.parse(),
+    };
+    result.map_err(|_| format!("无效的数值: {}", text))
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    if cli.verbose > 0 {
+        let level = if cli.verbose == 1 {
+            tracing::Level::DEBUG
+        } else {
+            tracing::Level::TRACE
+        };
+        // 操作结束时输出耗时，便于排查时序问题
+        tracing_subscriber::fmt()
+            .with_max_level(level)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_writer(std::io::stderr)
+            .init();
+    }
+    match &cli.command {
+        Command::Read(args) => read(&cli, args),
+        Command::Scan {
+            bus: ScanBus::I2c { bus },
+        } => scan_i2c(&cli, *bus),
+        Command::Scale(args) => match args.adc {
+            AdcKind::Hx711 => {
+                let adc = HX711::new(
+                    args.clock_pin,
+                    args.data_pin,
+                    ChannelGain::ChannelA128,
+                    None,
+                )?;
+                scale(&cli, args, adc)
+            }
+            AdcKind::Nau7802 => {
+                let adc = NAU7802::new(Arc::new(Mutex::new(I2c::with_bus(args.bus)?)))?;
+                scale(&cli, args, adc)
+            }
+        },
+        Command::Motor(args) => motor(&cli, args),
+        Command::Info => info(&cli),
+        Command::Doctor(args) => doctor(&cli, args),
+        Command::Pins(args) => pins(&cli, args),
+        Command::Run(args) => run(&cli, args),
+        #[cfg(feature = "dashboard")]
+        Command::Dashboard(args) => dashboard(&cli, args),
+        Command::InstallService(args) => install_service(&cli, args),
+    }
+}
+
+/// 读取传感器
+fn read(cli: &Cli, args: &ReadArgs) -> anyhow::Result<()> {
+    let config = match &cli.config {
+        Some(path) => HardwareConfig::load(path)?,
+        // 没有配置文件时按命令行参数创建单个传感器
+        None => HardwareConfig {
+            sensors: BTreeMap::from([(
+                args.sensor.clone(),
+                SensorConfig {
+                    pin: args.pin,
+                    bus: args.bus,
+                    addr: args.addr,
+                    clock_pin: args.clock_pin,
+                    data_pin: args.data_pin,
+                    device: args.device.clone(),
+                    ..SensorConfig::default()
+                },
+            )]),
+            ..HardwareConfig::default()
+        },
+    };
+    let manager = config.build()?;
+    let capture = match &args.capture {
+        Some(_) => {
+            let capture = manager
+                .signal_capture(&args.sensor)?
+                .ok_or_else(|| anyhow::anyhow!("传感器不支持信号捕获: {}", args.sensor))?;
+            capture.set_enabled(true);
+            Some(capture)
+        }
+        None => None,
+    };
+
+    let mut index = 0;
+    loop {
+        let result = manager.read_sample(&args.sensor);
+        // 读取失败时同样保存波形，用于分析时序和接线问题
+        if let (Some(path), Some(capture)) = (&args.capture, &capture)
+            && let Some(trace) = capture.last_trace()
+        {
+            trace.save(path)?;
+            eprintln!(
+                "波形已保存到{}（{}个电平变化，耗时{:?}）",
+                path.display(),
+                trace.edges.len(),
+                trace.duration
+            );
+        }
+        let sample = result?;
+        if cli.json {
+            println!("{}", sample.to_json());
+        } else {
+            println!("{}: {}", sample.sensor, sample.reading);
+        }
+        index += 1;
+        if args.count != 0 && index >= args.count {
+            break;
+        }
+        thread::sleep(Duration::from_millis(args.interval_ms));
+    }
+    Ok(())
+}
+
+/// 扫描I2C总线（与`i2cdetect -r`相同，逐个地址尝试读取一个字节）
+fn scan_i2c(cli: &Cli, bus: u8) -> anyhow::Result<()> {
+    let mut i2c = I2c::with_bus(bus)?;
+    let mut devices = Vec::new();
+    for address in 0x03..=0x77u8 {
+        i2c.set_slave_address(address as u16)?;
+        if i2c.read(&mut [0u8; 1]).is_ok() {
+            devices.push(address);
+        }
+    }
+
+    if cli.json {
+        println!("{}", json!({ "bus": bus, "devices": devices }));
+    } else if devices.is_empty() {
+        println!("I2C总线{}上没有发现设备", bus);
+    } else {
+        for address in devices {
+            println!("I2C总线{}: {:#04x}", bus, address);
+        }
+    }
+    Ok(())
+}
+
+/// 电子秤操作（校准数据保存在文件中）
+fn scale<A: WeightAdc>(cli: &Cli, args: &ScaleArgs, adc: A) -> anyhow::Result<()> {
+    let mut store = FileStore::new(&args.calibration);
+    let mut calibration = store.load()?;
+    let mut scale = Scale::new(adc, 1.0);
+    scale.set_samples(args.samples);
+    scale.load_calibration(&calibration, &args.prefix);
+
+    match args.action {
+        ScaleAction::Tare => {
+            scale.tare()?;
+            scale.save_calibration(&mut calibration, &args.prefix);
+            store.save(&calibration)?;
+            if cli.json {
+                println!("{}", json!({ "zero_offset": scale.zero_offset() }));
+            } else {
+                println!("去皮完成，0点偏移值: {}", scale.zero_offset());
+            }
+        }
+        ScaleAction::Calibrate { weight } => {
+            let transform_factor = scale.calibrate(weight)?;
+            scale.save_calibration(&mut calibration, &args.prefix);
+            store.save(&calibration)?;
+            if cli.json {
+                println!("{}", json!({ "transform_factor": transform_factor }));
+            } else {
+                println!("矫正完成，矫正因子: {}", transform_factor);
+            }
+        }
+        ScaleAction::Read => {
+            let weight = scale.read_weight()?;
+            if cli.json {
+                println!("{}", json!({ "weight": weight }));
+            } else {
+                println!("重量: {:.2}", weight);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 解析转动角度，返回步数（28BYJ-48减速后每圈2048个整步）
+fn parse_steps(text: &str, mode: StepMode) -> anyhow::Result<i32> {
+    let steps_per_rev = match mode {
+        StepMode::HalfStep => 4096.0,
+        StepMode::WaveDrive | StepMode::FullStep => 2048.0,
+    };
+    let error = || anyhow::anyhow!("无效的角度: {}（示例: 90deg、-1.5rev、512steps）", text);
+    let split = text
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let value: f64 = number.parse().map_err(|_| error())?;
+    let steps = match unit {
+        "" | "deg" => value / 360.0 * steps_per_rev,
+        "rev" => value * steps_per_rev,
+        "step" | "steps" => value,
+        _ => return Err(error()),
+    };
+    Ok(steps.round() as i32)
+}
+
+/// 步进电机操作
+fn motor(cli: &Cli, args: &MotorArgs) -> anyhow::Result<()> {
+    let mode = match args.mode {
+        Mode::Wave => StepMode::WaveDrive,
+        Mode::Full => StepMode::FullStep,
+        Mode::Half => StepMode::HalfStep,
+    };
+    let [pin1, pin2, pin3, pin4] = args.pins[..] else {
+        return Err(anyhow::anyhow!("需要4个引脚"));
+    };
+    let mut motor = ULN2003A::new(pin1, pin2, pin3, pin4, mode)?;
+
+    match &args.action {
+        MotorAction::Rotate { angle } => {
+            let steps = parse_steps(angle, mode)?;
+            let direction = if steps >= 0 {
+                Direction::Clockwise
+            } else {
+                Direction::CounterClockwise
+            };
+            let delay = match args.rpm {
+                Some(rpm) => {
+                    motor.set_speed_rpm(rpm)?;
+                    motor.step_delay()
+                }
+                None => Duration::from_millis(args.delay_ms),
+            };
+            motor.run_steps(steps, delay, direction)?;
+            // 转动完成后断电，避免线圈发热
+            motor.release();
+            if cli.json {
+                println!("{}", json!({ "steps": steps }));
+            } else {
+                println!("已转动{}步", steps);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 显示开发板信息
+fn info(cli: &Cli) -> anyhow::Result<()> {
+    let board = Board::detect();
+    let pwm: Vec<_> = [12u8, 13, 18, 19]
+        .into_iter()
+        .filter_map(|pin| board.hardware_pwm(pin).ok().map(|pwm| (pin, pwm)))
+        .collect();
+    if cli.json {
+        let pwm: BTreeMap<_, _> = pwm
+            .iter()
+            .map(|(pin, pwm)| {
+                (
+                    pin.to_string(),
+                    json!({ "chip": pwm.chip, "channel": pwm.channel }),
+                )
+            })
+            .collect();
+        println!(
+            "{}",
+            json!({
+                "model": board.model(),
+                "soc": format!("{:?}", board.soc()),
+                "gpio_backend": gpio::backend(),
+                "pwm": pwm,
+            })
+        );
+    } else {
+        println!("型号: {}", board.model());
+        println!("SoC: {:?}", board.soc());
+        println!("GPIO后端: {}", gpio::backend());
+        for (pin, pwm) in pwm {
+            println!("GPIO{}: pwmchip{}/pwm{}", pin, pwm.chip, pwm.channel);
+        }
+    }
+    Ok(())
+}
+
+/// 诊断报告转换为JSON
+fn report_json(name: &str, report: &DiagnosticsReport) -> serde_json::Value {
+    let checks: Vec<_> = report
+        .checks
+        .iter()
+        .map(|check| {
+            json!({
+                "name": check.name,
+                "status": format!("{:?}", check.status).to_lowercase(),
+                "detail": check.detail,
+                "hint": check.hint,
+            })
+        })
+        .collect();
+    json!({
+        "sensor": name,
+        "device": report.device,
+        "status": format!("{:?}", report.status()).to_lowercase(),
+        "checks": checks,
+    })
+}
+
+/// 运行所有传感器的自检，有失败项时返回错误（退出码非0）
+fn doctor(cli: &Cli, args: &DoctorArgs) -> anyhow::Result<()> {
+    let Some(path) = &cli.config else {
+        return Err(anyhow::anyhow!("需要使用-c指定硬件配置文件"));
+    };
+    let config = HardwareConfig::load(path)?;
+    let mut reports = vec![("board".to_string(), config.validate_board())];
+    // 引脚配置有误时创建传感器通常也会失败，只输出引脚检查结果
+    if reports[0].1.is_ok() {
+        let manager = config.build()?;
+        for (name, report) in manager.self_test_all() {
+            match report {
+                Ok(report) => reports.push((name, report)),
+                Err(err) => eprintln!("传感器{}自检失败: {}", name, err),
+            }
+        }
+    }
+    if let Some(pins) = &args.motor {
+        let [pin1, pin2, pin3, pin4] = pins[..] else {
+            return Err(anyhow::anyhow!("需要4个引脚"));
+        };
+        let mut motor = ULN2003A::new(pin1, pin2, pin3, pin4, StepMode::HalfStep)?;
+        reports.push(("motor".to_string(), motor.self_test()));
+    }
+
+    let mut failed = 0;
+    for (name, report) in &reports {
+        if !report.is_ok() {
+            failed += 1;
+        }
+        if cli.json {
+            println!("{}", report_json(name, report));
+        } else {
+            println!("{}: {}", name, report);
+        }
+    }
+    if failed > 0 {
+        return Err(anyhow::anyhow!("{}个设备自检失败", failed));
+    }
+    Ok(())
+}
+
+/// 显示GPIO状态（使用者按配置文件中的传感器确定）
+///
+/// 运行中的服务申请的引脚及中断次数可以通过HTTP服务的`/debug/pins`查看
+fn pins(cli: &Cli, args: &PinsArgs) -> anyhow::Result<()> {
+    let requests = match &cli.config {
+        Some(path) => HardwareConfig::load(path)?.pin_requests(),
+        None => Vec::new(),
+    };
+    let pins = if args.pin.is_empty() {
+        (0..28).collect()
+    } else {
+        args.pin.clone()
+    };
+    for mut state in debug::snapshot(pins) {
+        if state.owner.is_none() {
+            state.owner = requests
+                .iter()
+                .find(|request| match request.usage {
+                    PinUsage::Gpio(pin) | PinUsage::HardwarePwm(pin) => pin == state.pin,
+                    PinUsage::I2cBus(_) => false,
+                })
+                .map(|request| request.owner.clone());
+        }
+        if cli.json {
+            println!("{}", state.to_json());
+        } else {
+            println!("{}", state);
+        }
+    }
+    Ok(())
+}
+
+/// 按配置文件持续轮询所有传感器
+///
+/// 配置了`[privileges]`时打开所有设备后切换用户；由systemd启动时：配置了WatchdogSec=时
+/// 根据读数喂狗，否则直接发送READY=1
+fn run(cli: &Cli, args: &RunArgs) -> anyhow::Result<()> {
+    let Some(path) = &cli.config else {
+        return Err(anyhow::anyhow!("需要使用-c指定硬件配置文件"));
+    };
+    let config = HardwareConfig::load(path)?;
+    let board = config.validate_board();
+    if board.status() != CheckStatus::Pass {
+        eprintln!("{}", board);
+    }
+    if !board.is_ok() {
+        return Err(anyhow::anyhow!("引脚配置检查失败，按提示修改配置后重试"));
+    }
+    let manager = config.build()?;
+    // 监听端口可能小于1024，在降低权限之前打开
+    let listener = match &args.listen {
+        Some(addr) => Some(match service::tcp_listener()? {
+            Some(listener) => listener,
+            None => TcpListener::bind(addr)
+                .map_err(|err| anyhow::anyhow!("监听{}失败: {}", addr, err))?,
+        }),
+        None => None,
+    };
+    // 所有设备都已打开，在启动后台线程之前切换用户
+    if let Some(privileges) = &config.privileges {
+        privilege::preopen()?;
+        privileges.build()?.apply()?;
+    }
+
+    let health = manager.health();
+    let mut scheduler = Scheduler::new(manager)?;
+    if args.low_power {
+        scheduler.set_low_power();
+    }
+    let samples = scheduler.subscribe();
+    if let Some(listener) = listener {
+        let server = SensorServer::new(args.history).with_health(health.clone());
+        server.record_from(scheduler.subscribe());
+        server.serve_listener(listener)?;
+    }
+    if service::watchdog_timeout()?.is_some() {
+        // 看门狗线程启动时发送READY=1
+        Watchdog::systemd()?
+            .expect_healthy(health, Duration::from_secs(300))
+            .run(scheduler.subscribe());
+    } else if let Some(notifier) = Notifier::from_env()? {
+        notifier.ready()?;
+    }
+    let _handle = scheduler.start();
+
+    for sample in samples {
+        if args.quiet {
+            continue;
+        }
+        if cli.json {
+            println!("{}", sample.to_json());
+        } else {
+            println!("{}: {}", sample.sensor, sample.reading);
+        }
+    }
+    Ok(())
+}
+
+/// 终端实时仪表盘
+#[cfg(feature = "dashboard")]
+fn dashboard(cli: &Cli, args: &DashboardArgs) -> anyhow::Result<()> {
+    let Some(path) = &cli.config else {
+        return Err(anyhow::anyhow!("需要使用-c指定硬件配置文件"));
+    };
+    let config = HardwareConfig::load(path)?;
+    let board = config.validate_board();
+    if !board.is_ok() {
+        eprintln!("{}", board);
+        return Err(anyhow::anyhow!("引脚配置检查失败，按提示修改配置后重试"));
+    }
+    let manager = config.build()?;
+    let health = manager.health();
+    let mut scheduler = Scheduler::new(manager)?;
+    if args.low_power {
+        scheduler.set_low_power();
+    }
+    let samples = scheduler.subscribe();
+    let _handle = scheduler.start();
+    Dashboard::new()
+        .with_health(health)
+        .with_history(args.history)
+        .run(samples)
+}
+
+/// 按当前配置生成systemd服务单元文件（启动命令为当前可执行文件的`run`子命令）
+fn install_service(cli: &Cli, args: &InstallServiceArgs) -> anyhow::Result<()> {
+    let Some(path) = &cli.config else {
+        return Err(anyhow::anyhow!("需要使用-c指定硬件配置文件"));
+    };
+    // 检查配置文件，避免安装后服务启动失败
+    HardwareConfig::load(path)?;
+    let config = fs::canonicalize(path)
+        .map_err(|err| anyhow::anyhow!("无法获取配置文件的绝对路径: {}: {}", path, err))?;
+    let exe = env::current_exe()?;
+
+    let mut command = vec![
+        exe.to_string_lossy().into_owned(),
+        "--config".to_string(),
+        config.to_string_lossy().into_owned(),
+        "run".to_string(),
+        "--quiet".to_string(),
+    ];
+    if let Some(listen) = &args.listen {
+        command.extend(["--listen".to_string(), listen.clone()]);
+    }
+    if args.low_power {
+        command.push("--low-power".to_string());
+    }
+    let mut unit = UnitFile::new(&args.name, command);
+    if let Some(dir) = config.parent() {
+        unit = unit.with_working_directory(dir);
+    }
+    if let Some(user) = &args.user {
+        unit = unit.with_user(user);
+    }
+    if args.watchdog_sec > 0 {
+        unit = unit.with_watchdog(Duration::from_secs(args.watchdog_sec));
+    }
+    if args.socket_activation
+        && let Some(listen) = &args.listen
+    {
+        unit = unit.with_socket(listen);
+    }
+
+    if args.dry_run {
+        println!("# {}\n{}", unit.service_name(), unit.service());
+        if let Some(socket) = unit.socket() {
+            println!("# {}\n{}", unit.socket_name(), socket);
+        }
+        return Ok(());
+    }
+    let files = unit.install(&args.dir)?;
+    let enable = if unit.listen.is_some() {
+        unit.socket_name()
+    } else {
+        unit.service_name()
+    };
+    if cli.json {
+        println!("{}", json!({ "files": files, "enable": enable }));
+    } else {
+        for file in &files {
+            println!("已写入: {}", file.display());
+        }
+        println!(
+            "启用服务: sudo systemctl daemon-reload && sudo systemctl enable --now {}",
+            enable
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use std::sync::{Arc, Mutex};
+use std::{thread, time::Duration};
+
+use raspi_sensor::sensor::relay_board::RelayBoard;
+use raspi_sensor::switch::Switch;
+
+// 4路继电器模块IN1~IN4接入GPIO针脚
+const RELAY_PINS: [u8; 4] = [5, 6, 13, 19];
+
+/// 4路继电器模块测试程序（低电平触发）
+fn main() -> anyhow::Result<()> {
+    // 通道1、2互锁（如电机正反转）
+    let mut board = RelayBoard::new(&RELAY_PINS, true)?.with_interlock(1, 2)?;
+
+    // 依次吸合各通道
+    for channel in 1..=board.len() {
+        println!("通道{}吸合", channel);
+        board.pulse(channel, Duration::from_millis(500))?;
+    }
+
+    // 互锁的通道不能同时吸合
+    board.on(1)?;
+    if let Err(err) = board.on(2) {
+        println!("{}", err);
+    }
+    board.off(1)?;
+
+    board.toggle(4)?;
+    println!("当前状态: {:?}", board.states());
+    board.all_off()?;
+
+    // 单个通道作为开关使用（释放模块时断开所有通道）
+    let board = Arc::new(Mutex::new(board));
+    let mut lamp = RelayBoard::channel(&board, 3)?;
+    for _ in 0..3 {
+        lamp.on()?;
+        thread::sleep(Duration::from_millis(300));
+        lamp.off()?;
+        thread::sleep(Duration::from_millis(300));
+    }
+
+    println!("测试完成");
+    Ok(())
+}
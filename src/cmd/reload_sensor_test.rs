@@ -0,0 +1,35 @@
+use raspi_sensor::config::{HardwareConfig, I2cBuses};
+use raspi_sensor::reload::ConfigReloader;
+use raspi_sensor::scheduler::Scheduler;
+
+/// 默认配置文件路径
+const DEFAULT_CONFIG_PATH: &str = "sensors.toml";
+
+/// 配置热重载测试程序
+///
+/// 用法: reload-sensor-test [配置文件路径]
+///
+/// 运行期间修改配置文件后执行`kill -HUP <pid>`，新增、删除或修改的传感器立即生效
+fn main() -> anyhow::Result<()> {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_CONFIG_PATH.to_string());
+
+    let config = HardwareConfig::load(&path)?;
+    let mut buses = I2cBuses::default();
+    let scheduler = Scheduler::new(config.build_with(&mut buses)?)?;
+    let samples = scheduler.subscribe();
+    let handle = scheduler.start();
+    println!(
+        "已加载{}个传感器: {:?}，进程ID: {}",
+        handle.manager().names().len(),
+        handle.manager().names(),
+        std::process::id()
+    );
+    ConfigReloader::new(&path, config, buses, handle.control()).on_sighup()?;
+
+    for sample in samples {
+        println!("{}: {}", sample.sensor, sample.reading);
+    }
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use rppal::i2c::I2c;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use raspi_sensor::adapter::Bme280Sensor;
+use raspi_sensor::manager::Sensor;
+use raspi_sensor::sim::{Recorder, Replay};
+
+/// 记录/回放测试程序
+///
+/// - 在树莓派上记录：`replay-sensor-test record bme280.trace`
+/// - 在开发机上回放：`replay-sensor-test replay bme280.trace`
+fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    let (mode, path) = match args.as_slice() {
+        [_, mode, path] => (mode.as_str(), path.as_str()),
+        _ => {
+            return Err(anyhow::anyhow!(
+                "用法: replay-sensor-test <record|replay> <文件>"
+            ));
+        }
+    };
+
+    match mode {
+        "record" => {
+            // BME280的原始I2C传输和补偿后的读数都写入记录文件
+            let recorder = Recorder::create(path)?;
+            let i2c_bus = Arc::new(Mutex::new(recorder.i2c("i2c1", I2c::with_bus(1)?)));
+            let mut bme280 = recorder.sensor("bme280", Bme280Sensor::new(i2c_bus, None)?);
+            for _ in 0..10 {
+                match bme280.read() {
+                    Ok(reading) => println!("{}", reading),
+                    Err(err) => eprintln!("{}", err),
+                }
+                thread::sleep(Duration::from_secs(1));
+            }
+        }
+        "replay" => {
+            // 原始传输经过同样的驱动重新计算，与记录的读数对比
+            let replay = Replay::load(path)?;
+            let i2c_bus = Arc::new(Mutex::new(replay.i2c("i2c1")?));
+            let mut bme280 = Bme280Sensor::new(i2c_bus, None)?;
+            let mut recorded = replay.sensor("bme280")?;
+            while let Ok(expected) = recorded.read() {
+                let actual = bme280.read()?;
+                let flag = if actual == expected {
+                    "一致"
+                } else {
+                    "不一致"
+                };
+                println!("记录: {}\n回放: {}\n{}", expected, actual, flag);
+            }
+        }
+        _ => return Err(anyhow::anyhow!("未知的模式: {}", mode)),
+    }
+    Ok(())
+}
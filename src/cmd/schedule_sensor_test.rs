@@ -0,0 +1,48 @@
+use std::sync::{Arc, Mutex};
+use std::{thread, time::Duration};
+
+use raspi_sensor::schedule::{Location, Rule, Schedule, TimeSpec};
+use raspi_sensor::sensor::relay_board::RelayBoard;
+
+// 继电器模块IN1（电磁阀）、IN2（补光灯）接入GPIO针脚
+const RELAY_PINS: [u8; 2] = [5, 6];
+
+/// 定时浇水和补光测试程序（北京时间，上海）
+fn main() -> anyhow::Result<()> {
+    let board = Arc::new(Mutex::new(RelayBoard::new(&RELAY_PINS, true)?));
+
+    // 每2分钟浇水20秒
+    let valve = Schedule::new(RelayBoard::channel(&board, 1)?)
+        .with_utc_offset(8 * 60)
+        .with_rule(Rule::pulse(
+            "浇水",
+            TimeSpec::every(Duration::from_secs(120)),
+            Duration::from_secs(20),
+        ))
+        .start()?;
+
+    // 日出前30分钟到日落后2小时补光
+    let light = Schedule::new(RelayBoard::channel(&board, 2)?)
+        .with_utc_offset(8 * 60)
+        .with_location(Location::new(31.23, 121.47))
+        .with_rule(Rule::window(
+            "补光",
+            TimeSpec::sunrise(-30),
+            TimeSpec::sunset(120),
+        ))
+        .start()?;
+
+    // 手动打开电磁阀10秒
+    thread::sleep(Duration::from_secs(2));
+    valve.set_manual(true, Some(Duration::from_secs(10)));
+
+    for _ in 0..30 {
+        thread::sleep(Duration::from_secs(10));
+        println!("电磁阀: {:?}", valve.status());
+        println!("补光灯: {:?}", light.status());
+    }
+
+    valve.stop();
+    light.stop();
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use raspi_sensor::adapter::{Aht30Sensor, Dht11Sensor};
+use raspi_sensor::i2c_bus::{I2cBusMonitor, SharedBus};
+use raspi_sensor::manager::SensorManager;
+use raspi_sensor::scheduler::Scheduler;
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+
+/// 周期轮询调度器测试程序
+fn main() -> anyhow::Result<()> {
+    let i2c_bus = SharedBus::open(1)?;
+
+    let manager = SensorManager::new();
+    manager.register("outdoor", Dht11Sensor::new(DHT11_PIN)?)?;
+    manager.register("indoor", Aht30Sensor::new(i2c_bus.clone(), None)?)?;
+
+    // DHT11按最小间隔2秒轮询，AHT30每5秒轮询一次
+    let mut scheduler = Scheduler::new(manager)?;
+    scheduler.set_interval("indoor", Duration::from_secs(5))?;
+    let samples = scheduler.subscribe();
+    let errors = scheduler.subscribe_errors();
+    // AHT30连续3次总线错误时自动恢复I2C总线
+    let monitor = I2cBusMonitor::new(1, i2c_bus);
+    monitor.watch(
+        &["indoor"],
+        scheduler.subscribe(),
+        scheduler.subscribe_errors(),
+    );
+    let handle = scheduler.start();
+
+    // 读取失败通知
+    thread::spawn(move || {
+        for (name, err) in errors {
+            eprintln!("读取传感器{}失败: {}", name, err);
+        }
+    });
+
+    // 打印1分钟内的读数
+    let deadline = Instant::now() + Duration::from_secs(60);
+    while let Some(timeout) = deadline.checked_duration_since(Instant::now()) {
+        if let Ok(sample) = samples.recv_timeout(timeout) {
+            println!("{}: {}", sample.sensor, sample.reading);
+        }
+    }
+    handle.stop();
+    println!("I2C总线状态: {:?}", monitor.health());
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use std::sync::{Arc, Mutex};
+use std::{thread, time::Duration};
+
+use raspi_sensor::adapter::{Aht30Sensor, Bme280Sensor, Dht11Sensor};
+use raspi_sensor::manager::SensorManager;
+use rppal::i2c::I2c;
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+
+/// 传感器管理器测试程序
+fn main() -> anyhow::Result<()> {
+    // 初始化I2C通信总线，AHT30与BME280共用
+    let i2c_bus = Arc::new(Mutex::new(I2c::new()?));
+
+    // 按名称注册传感器
+    let manager = SensorManager::new();
+    manager.register("outdoor", Dht11Sensor::new(DHT11_PIN)?)?;
+    manager.register(
+        "greenhouse_temp",
+        Aht30Sensor::new(i2c_bus.clone(), Some(0x38))?,
+    )?;
+    manager.register("greenhouse_env", Bme280Sensor::new(i2c_bus, Some(0x76))?)?;
+
+    // 每秒读取一次所有传感器（DHT11未到2秒时返回上一次的读数）
+    loop {
+        for (name, result) in manager.read_all() {
+            match result {
+                Ok(sample) => println!("{}: {}", name, sample.reading),
+                Err(err) => eprintln!("读取传感器{}失败: {}", name, err),
+            }
+        }
+        thread::sleep(Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,38 @@
+use std::sync::{Arc, Mutex};
+
+use raspi_sensor::adapter::{Bme280Sensor, Dht11Sensor};
+use raspi_sensor::manager::SensorManager;
+use raspi_sensor::scheduler::Scheduler;
+use raspi_sensor::server::SensorServer;
+use rppal::i2c::I2c;
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+/// HTTP服务监听地址
+const LISTEN_ADDR: &str = "0.0.0.0:8080";
+
+/// HTTP/WebSocket服务测试程序
+///
+/// - curl http://树莓派IP:8080/sensors
+/// - curl http://树莓派IP:8080/sensors/outdoor/history?last=600
+/// - websocat ws://树莓派IP:8080/ws
+fn main() -> anyhow::Result<()> {
+    let i2c_bus = Arc::new(Mutex::new(I2c::new()?));
+
+    let manager = SensorManager::new();
+    manager.register("outdoor", Dht11Sensor::new(DHT11_PIN)?)?;
+    manager.register("greenhouse", Bme280Sensor::new(i2c_bus, Some(0x76))?)?;
+
+    // 每个传感器保留最近1小时的读数（DHT11每2秒一次）
+    let server = SensorServer::new(1800).with_health(manager.health());
+    let scheduler = Scheduler::new(manager)?;
+    server.record_from(scheduler.subscribe());
+    let _handle = scheduler.start();
+
+    println!("HTTP服务已启动: {}", LISTEN_ADDR);
+    server
+        .serve(LISTEN_ADDR)?
+        .join()
+        .map_err(|_| anyhow::anyhow!("HTTP服务线程异常退出"))?;
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use std::thread;
+use std::time::Duration;
+
+use raspi_sensor::manager::SensorManager;
+use raspi_sensor::reading::Quantity;
+use raspi_sensor::sim::{Signal, SimBackend};
+
+/// 模拟后端测试程序（无需树莓派，可在开发机上运行）
+fn main() -> anyhow::Result<()> {
+    let mut backend = SimBackend::new(42);
+    let manager = SensorManager::new();
+    manager.register("greenhouse", backend.bme280())?;
+    manager.register(
+        "freezer",
+        backend
+            .dht11()
+            .with(Quantity::Temperature, Signal::Constant(-18.0))
+            .with_failure_rate(0.1),
+    )?;
+    manager.register("scale", backend.hx711())?;
+
+    for _ in 0..10 {
+        for name in manager.names() {
+            match manager.read(&name) {
+                Ok(reading) => println!("{}: {}", name, reading),
+                Err(err) => eprintln!("{}: {}", name, err),
+            }
+        }
+        thread::sleep(Duration::from_secs(1));
+    }
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use std::{thread, time::Duration};
+
+use raspi_sensor::display::max7219::MAX7219;
+use raspi_sensor::sensor::gpio_expander::GpioExpander;
+use raspi_sensor::sensor::mcp3008::MCP3008;
+use raspi_sensor::spi_bus::SpiBus;
+use rppal::spi::{Bus, Mode, SlaveSelect};
+
+// 各设备的GPIO片选引脚
+const MCP3008_CS_PIN: u8 = 5;
+const MAX7219_CS_PIN: u8 = 6;
+const MCP23S17_CS_PIN: u8 = 13;
+// LED灯接入MCP23S17的引脚（A0）
+const LED_EXPANDER_PIN: u8 = 0;
+
+/// 共享SPI总线测试程序
+///
+/// MCP3008、MAX7219、MCP23S17挂在同一条SPI总线上，各自使用GPIO片选，
+/// 硬件片选CE0悬空
+fn main() -> anyhow::Result<()> {
+    let spi_bus = SpiBus::new(Bus::Spi0, SlaveSelect::Ss0)?;
+
+    // 各设备使用各自的通信速率
+    let adc = MCP3008::with_device(spi_bus.device(MCP3008_CS_PIN, 1_000_000, Mode::Mode0)?);
+    let mut matrix =
+        MAX7219::with_device(spi_bus.device(MAX7219_CS_PIN, 5_000_000, Mode::Mode0)?, 1)?;
+    let expander =
+        GpioExpander::new_spi(spi_bus.device(MCP23S17_CS_PIN, 5_000_000, Mode::Mode0)?, 0)?;
+    let mut led_pin = expander.output_pin(LED_EXPANDER_PIN)?;
+
+    loop {
+        // 电位器接MCP3008 CH0，读数映射为点阵亮起的列数
+        let value = adc.read(0)?;
+        let columns = (value as usize * 9 / 1024).min(8);
+        for x in 0..8 {
+            for y in 0..8 {
+                matrix.set_pixel(x, y, x < columns);
+            }
+        }
+        matrix.flush()?;
+        // 超过一半时点亮LED
+        led_pin.write(value > 512)?;
+
+        thread::sleep(Duration::from_millis(50));
+    }
+}
@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use raspi_sensor::adapter::Dht11Sensor;
+use raspi_sensor::manager::SensorManager;
+use raspi_sensor::scheduler::Scheduler;
+use raspi_sensor::sink::Pipeline;
+use raspi_sensor::sink::coap::{CoapConfig, CoapSink};
+use raspi_sensor::sink::spool::{DiskQueue, StoreAndForward};
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+
+/// 离线暂存测试程序
+///
+/// 服务器地址通过第一个参数指定（默认127.0.0.1:5683），停掉服务器一段时间再启动，
+/// 断开期间的读数暂存在`spool.bin`中，恢复后按顺序补发
+fn main() -> anyhow::Result<()> {
+    let endpoint = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:5683".to_string());
+    let manager = SensorManager::new();
+    manager.register("outdoor", Dht11Sensor::new(DHT11_PIN)?)?;
+
+    let mut config = CoapConfig::new(&endpoint, "telemetry/outdoor");
+    config.batch_size = 1;
+    let queue = DiskQueue::open("spool.bin", 1024 * 1024)?;
+    println!("暂存文件中有{}个读数等待补发", queue.len());
+    let sink = StoreAndForward::new(CoapSink::new(config)?, queue)
+        .with_retry_interval(Duration::from_secs(10));
+    let mut pipeline = Pipeline::new();
+    pipeline.add("coap", sink);
+
+    let scheduler = Scheduler::new(manager)?;
+    let pipeline = pipeline.start(scheduler.subscribe());
+    let _handle = scheduler.start();
+    pipeline.join();
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use std::time::{Duration, SystemTime};
+
+use raspi_sensor::adapter::Dht11Sensor;
+use raspi_sensor::manager::SensorManager;
+use raspi_sensor::scheduler::Scheduler;
+use raspi_sensor::sink::sqlite::SqliteLogger;
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+/// 数据库文件路径
+const DATABASE_PATH: &str = "sensors.db";
+
+/// SQLite数据记录测试程序
+fn main() -> anyhow::Result<()> {
+    let manager = SensorManager::new();
+    manager.register("outdoor", Dht11Sensor::new(DHT11_PIN)?)?;
+
+    // 打印数据库中最近1小时的历史读数
+    let mut logger = SqliteLogger::open(DATABASE_PATH)?;
+    let now = SystemTime::now();
+    for sample in logger.range("outdoor", now - Duration::from_secs(3600), now)? {
+        println!("历史 {:?}: {}", sample.timestamp, sample.reading);
+    }
+
+    // 新读数写入数据库（默认保留策略：原始读数7天，之后10分钟降采样，最多1年）
+    let scheduler = Scheduler::new(manager)?;
+    let samples = scheduler.subscribe();
+    let _handle = scheduler.start();
+    for sample in samples {
+        println!("{}: {}", sample.sensor, sample.reading);
+        if let Err(err) = logger.write(&sample) {
+            eprintln!("写入数据库失败: {}", err);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use std::sync::{Arc, Mutex};
+use std::{thread, time::Duration};
+
+use raspi_sensor::display::ssd1306::{DisplaySize, SSD1306};
+use raspi_sensor::std_clock::StdClock;
+use rppal::i2c::I2c;
+use sensor_hal::aht30;
+
+/// SSD1306 OLED显示屏测试程序（显示AHT30温湿度）
+fn main() -> anyhow::Result<()> {
+    // 初始化全局时钟
+    let clock = StdClock::new();
+    // 初始化I2C通信总线，显示屏与传感器共用
+    let i2c_bus = Arc::new(Mutex::new(I2c::new()?));
+
+    // 创建显示屏实例
+    let mut display = SSD1306::new(i2c_bus.clone(), Some(0x3C), DisplaySize::W128H64)?;
+    // 创建AHT30传感器实例
+    let mut aht30_driver = {
+        let mut i2c = i2c_bus
+            .lock()
+            .map_err(|_| anyhow::anyhow!("I2C通信总线繁忙"))?;
+        aht30::Driver::new(&clock, &mut *i2c, Some(0x38))?
+    };
+
+    // 死循环刷新显示
+    loop {
+        let result = {
+            let mut i2c = i2c_bus
+                .lock()
+                .map_err(|_| anyhow::anyhow!("I2C通信总线繁忙"))?;
+            aht30_driver.read(&mut *i2c)
+        };
+
+        display.clear();
+        display.draw_rect(0, 0, 128, 64, false);
+        match result {
+            Ok((temperature, humidity)) => {
+                display.draw_text(8, 16, &format!("Temp: {:.1} C", temperature));
+                display.draw_text(8, 32, &format!("Hum:  {:.1} %", humidity));
+            }
+            Err(err) => {
+                display.draw_text(8, 24, "Sensor error");
+                eprintln!("读取AHT30传感器温度、湿度失败: {}", err);
+            }
+        }
+        display.flush()?;
+
+        thread::sleep(Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+use raspi_sensor::adapter::{Aht30Sensor, Bme280Sensor};
+use raspi_sensor::i2c_bus::SharedBus;
+use raspi_sensor::sensor::anemometer::Anemometer;
+use raspi_sensor::sensor::bh1750::{self, BH1750};
+use raspi_sensor::sensor::rain_gauge::RainGauge;
+use raspi_sensor::sink::{ConsoleSink, Pipeline};
+use raspi_sensor::station::Weather;
+
+/// I2C总线编号
+const I2C_BUS: u8 = 1;
+/// 风速计接入GPIO针脚
+const ANEMOMETER_PIN: u8 = 5;
+/// 雨量计接入GPIO针脚
+const RAIN_GAUGE_PIN: u8 = 6;
+/// 测站海拔（m）
+const ELEVATION: f64 = 50.0;
+
+/// 气象站测试程序
+fn main() -> anyhow::Result<()> {
+    let bus = SharedBus::open(I2C_BUS)?;
+    // 温湿度按添加顺序优先使用BME280，BME280读取失败时使用AHT30
+    let station = Weather::new(ELEVATION)
+        .with_sensor("bme280", Bme280Sensor::new(bus.clone(), None)?)
+        .with_sensor("aht30", Aht30Sensor::new(bus.clone(), None)?)
+        .with_sensor("wind", Anemometer::new(ANEMOMETER_PIN)?)
+        .with_sensor("rain", RainGauge::new(RAIN_GAUGE_PIN)?)
+        .with_sensor("light", BH1750::new(bus, bh1750::ADDRESS_LOW)?)
+        .start("station", Duration::from_secs(10));
+
+    let samples = station.subscribe_samples();
+    let mut pipeline = Pipeline::new();
+    pipeline.add("console", ConsoleSink);
+    let pipeline = pipeline.start(samples);
+
+    // 打印派生量和读取失败的传感器
+    for observation in station.subscribe() {
+        println!(
+            "露点: {:?}℃, 海平面气压: {:?}Pa, 阵风: {:?}m/s, 异常: {:?}",
+            observation.dew_point,
+            observation.sea_level_pressure,
+            observation.wind_gust,
+            observation.errors
+        );
+    }
+    pipeline.join();
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use std::{thread, time::Duration};
+
+use raspi_sensor::sensor::ramp::Ramp;
+use raspi_sensor::sensor::step_dir_stepper::{DriverChip, Microstep, StepDirStepper};
+
+// A4988 STEP/DIR/ENABLE接入GPIO针脚
+const STEP_PIN: u8 = 23;
+const DIR_PIN: u8 = 24;
+const ENABLE_PIN: u8 = 25;
+// A4988 MS1/MS2/MS3接入GPIO针脚
+const MS_PINS: [u8; 3] = [16, 20, 21];
+
+/// A4988步进电机测试程序（NEMA17 + T8丝杆）
+fn main() -> anyhow::Result<()> {
+    let mut stepper = StepDirStepper::new(STEP_PIN, DIR_PIN, Some(ENABLE_PIN))?;
+    // 1/8细分
+    stepper.set_microstep_pins(DriverChip::A4988, MS_PINS)?;
+    stepper.set_microstep(Microstep::Eighth)?;
+    // 200步/圈，导程8mm
+    stepper.set_steps_per_mm(25.0);
+    // 细分步：起始400步/秒，最高4000步/秒，加速度8000步/秒²
+    stepper.set_ramp(Ramp::new(400.0, 4000.0, 8000.0));
+    stepper.set_enabled(true);
+
+    // 来回移动
+    for _ in 0..3 {
+        stepper.move_to_mm(40.0)?;
+        println!("当前位置: {:.2}mm", stepper.position_mm());
+        thread::sleep(Duration::from_millis(500));
+        stepper.move_to_mm(0.0)?;
+        println!("当前位置: {:.2}mm", stepper.position_mm());
+        thread::sleep(Duration::from_millis(500));
+    }
+
+    stepper.set_enabled(false);
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use raspi_sensor::adapter::Dht11Sensor;
+use raspi_sensor::control::thermostat::{Mode, Thermostat, ThermostatConfig};
+use raspi_sensor::shutdown::Shutdown;
+use raspi_sensor::switch::GpioSwitch;
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+/// 加热器继电器接入GPIO针脚（低电平触发）
+const HEATER_RELAY_PIN: u8 = 22;
+
+/// 温控器测试程序：低于24.5℃加热，高于25.5℃停止
+fn main() -> anyhow::Result<()> {
+    let heater = Arc::new(Mutex::new(GpioSwitch::new(HEATER_RELAY_PIN, false)?));
+    // 退出时断开加热器
+    let shutdown = Shutdown::new();
+    shutdown.switch("heater", heater.clone(), false);
+    shutdown.listen()?;
+
+    let thermostat = Thermostat::new(
+        Dht11Sensor::new(DHT11_PIN)?,
+        heater,
+        ThermostatConfig {
+            mode: Mode::Heat,
+            setpoint: 25.0,
+            deadband: 1.0,
+            min_on: Duration::from_secs(30),
+            min_off: Duration::from_secs(30),
+            ..ThermostatConfig::default()
+        },
+    )?;
+
+    thermostat
+        .run(Duration::from_secs(5), |status| match status.temperature {
+            Some(temperature) => println!(
+                "温度: {:.1}℃, 加热器: {}",
+                temperature,
+                if status.on { "开" } else { "关" }
+            ),
+            None if status.failsafe => eprintln!("传感器故障，加热器已关闭"),
+            None => eprintln!("读取温度失败"),
+        })
+        .join()
+        .map_err(|_| anyhow::anyhow!("温控器线程异常退出"))?;
+    Ok(())
+}
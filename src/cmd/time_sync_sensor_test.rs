@@ -0,0 +1,44 @@
+use raspi_sensor::adapter::Dht11Sensor;
+use raspi_sensor::i2c_bus::SharedBus;
+use raspi_sensor::manager::SensorManager;
+use raspi_sensor::scheduler::Scheduler;
+use raspi_sensor::sensor::ds3231::DS3231;
+use raspi_sensor::time_sync::TimeService;
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+
+/// 时间同步测试程序
+///
+/// 断网启动后运行，RTC时间有效时读数立即以RTC时间输出，否则等待NTP同步后输出修正后的读数
+fn main() -> anyhow::Result<()> {
+    let bus = SharedBus::open(1)?;
+    let mut rtc = DS3231::new(bus.clone());
+    match rtc.read_time() {
+        Ok(time) => println!(
+            "RTC时间: {:?}，芯片温度: {:.2}°C",
+            time,
+            rtc.read_temperature()?
+        ),
+        Err(err) => eprintln!("读取RTC时间失败: {}", err),
+    }
+
+    let manager = SensorManager::new();
+    manager.register("outdoor", Dht11Sensor::new(DHT11_PIN)?)?;
+
+    let time = TimeService::new().with_rtc(rtc)?;
+    println!("时间来源: {:?}", time.source());
+    let scheduler = Scheduler::new(manager)?;
+    let samples = time.correct_from(scheduler.subscribe());
+    let _handle = scheduler.start();
+    for sample in samples {
+        println!(
+            "[{:.0}] {}: {} ({:?})",
+            sample.unix_timestamp(),
+            sample.sensor,
+            sample.reading,
+            time.source()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use std::thread;
+use std::time::Duration;
+
+use raspi_sensor::sensor::button::ButtonEvent;
+use raspi_sensor::sensor::touch::Touch;
+use rppal::gpio::Level;
+
+// TTP223输出引脚接入GPIO针脚
+const TOUCH_PIN: u8 = 17;
+
+/// TTP223电容触摸传感器测试程序
+fn main() -> anyhow::Result<()> {
+    // 创建触摸传感器实例（默认高电平有效）
+    let mut touch = Touch::new(TOUCH_PIN, Level::High)?;
+
+    touch.on_event(|event| match event {
+        ButtonEvent::Pressed => println!("触摸"),
+        ButtonEvent::Held(duration) => println!("长按: {:?}", duration),
+        ButtonEvent::Released(duration) => println!("松开, 触摸时长: {:?}", duration),
+    })?;
+
+    // 防止程序退出
+    loop {
+        thread::sleep(Duration::from_secs(1));
+    }
+}
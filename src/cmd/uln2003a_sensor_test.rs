@@ -1,8 +1,10 @@
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
 use raspi_sensor::sensor::button::Button;
 use raspi_sensor::sensor::uln2003a::{Direction, StepMode, ULN2003A};
+use raspi_sensor::shutdown::Shutdown;
 
 // Button接入GPIO针脚
 const BUTTON_PIN: u8 = 17;
@@ -17,32 +19,47 @@ fn main() -> anyhow::Result<()> {
     //  创建Button实例
     let mut button = Button::new(BUTTON_PIN)?;
     // 创建步进电机实例
-    let mut ula2003a_driver = ULN2003A::new(
+    let ula2003a_driver = Arc::new(Mutex::new(ULN2003A::new(
         ULN2003A_INT1_PIN,
         ULN2003A_INT2_PIN,
         ULN2003A_INT3_PIN,
         ULN2003A_INT4_PIN,
         StepMode::HalfStep,
-    )?;
+    )?));
+
+    // 退出时释放电机，避免线圈持续通电发热
+    let shutdown = Shutdown::new();
+    shutdown.device("uln2003a", ula2003a_driver.clone());
+    shutdown.listen()?;
 
     let mut state = false;
     // 监听按钮状态中断信号
     button.on_change(move |btn_state| {
         // 假设True为按钮按下
         if btn_state {
+            let Ok(mut ula2003a_driver) = ula2003a_driver.lock() else {
+                return;
+            };
             // 检测缓存状态
+            let result = if !state {
+                ula2003a_driver.run_steps(1000, Duration::from_millis(5), Direction::Clockwise)
+            } else {
+                ula2003a_driver.run_steps(1500, Duration::from_millis(5), Direction::CounterClockwise)
+            };
+            if let Err(err) = result {
+                eprintln!("步进电机运行失败: {}", err);
+                return;
+            }
             if !state {
-                ula2003a_driver.run_steps(1000, Duration::from_millis(5), Direction::Clockwise);
                 println!("检测到按钮按下，顺时针旋转8步")
             } else {
-                ula2003a_driver.run_steps(1500, Duration::from_millis(5), Direction::CounterClockwise);
                 println!("检测到按钮按下，逆时针旋转10步")
             }
             state = !state;
         }
     })?;
 
-    // 防止程序退出
+    // 防止程序退出（Ctrl+C时由shutdown释放电机后退出）
     loop {
         thread::sleep(Duration::from_millis(100));
     }
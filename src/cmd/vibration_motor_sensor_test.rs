@@ -0,0 +1,39 @@
+use std::{thread, time::Duration};
+
+use raspi_sensor::pwm_wapper::PwmWapper;
+use raspi_sensor::sensor::vibration_motor::{HapticPattern, VibrationMotor};
+
+// 振动马达（经MOSFET）接入GPIO针脚
+const MOTOR_PIN: u8 = 21;
+
+/// 振动马达测试程序（软件PWM）
+fn main() -> anyhow::Result<()> {
+    // 偏心转子马达占空比低于35%时无法启动
+    let mut motor =
+        VibrationMotor::new(PwmWapper::software(MOTOR_PIN, 200.0)?)?.with_min_duty(0.35);
+
+    // 不同强度各振动0.5秒
+    for intensity in [0.25, 0.5, 0.75, 1.0] {
+        println!("强度: {:.0}%", intensity * 100.0);
+        motor.pulse(intensity, Duration::from_millis(500))?;
+        thread::sleep(Duration::from_millis(300));
+    }
+
+    // 阻塞播放
+    println!("双振");
+    motor.play(&HapticPattern::double_buzz())?;
+    thread::sleep(Duration::from_secs(1));
+
+    // 后台播放，新的模式打断正在播放的模式
+    let player = motor.into_player();
+    println!("心跳（3次）");
+    player.play(HapticPattern::heartbeat().with_repeat(3));
+    thread::sleep(Duration::from_millis(1200));
+    println!("打断，渐强");
+    player.play(HapticPattern::ramp_up(Duration::from_secs(1), 10));
+    thread::sleep(Duration::from_secs(2));
+
+    player.stop()?;
+    println!("测试完成");
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use raspi_sensor::adapter::{Aht30Sensor, Dht11Sensor};
+use raspi_sensor::manager::SensorManager;
+use raspi_sensor::scheduler::Scheduler;
+use raspi_sensor::watchdog::Watchdog;
+use rppal::i2c::I2c;
+
+/// DHT11传感器单总线接入GPIO针脚
+const DHT11_PIN: u8 = 4;
+
+/// 看门狗测试程序
+///
+/// 作为systemd服务运行时（WatchdogSec=30s）使用服务看门狗，否则使用/dev/watchdog。
+/// 拔掉AHT30后超过1分钟读取不到数据即停止喂狗
+fn main() -> anyhow::Result<()> {
+    let i2c_bus = Arc::new(Mutex::new(I2c::new()?));
+
+    let manager = SensorManager::new();
+    manager.register("outdoor", Dht11Sensor::new(DHT11_PIN)?)?;
+    manager.register("indoor", Aht30Sensor::new(i2c_bus, None)?)?;
+
+    let mut scheduler = Scheduler::new(manager)?;
+    scheduler.set_interval("indoor", Duration::from_secs(5))?;
+    let samples = scheduler.subscribe();
+    let errors = scheduler.subscribe_errors();
+
+    // DHT11偶尔校验失败，期限放宽到2分钟
+    let watchdog = Watchdog::detect()?
+        .expect("indoor", Duration::from_secs(60))
+        .expect("outdoor", Duration::from_secs(120));
+    let _scheduler = scheduler.start();
+    let watchdog = watchdog.run(samples);
+
+    // 读取失败通知
+    thread::spawn(move || {
+        for (name, err) in errors {
+            eprintln!("读取传感器{}失败: {}", name, err);
+        }
+    });
+
+    watchdog
+        .join()
+        .map_err(|_| anyhow::anyhow!("看门狗线程异常退出"))?;
+    Ok(())
+}
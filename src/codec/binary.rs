@@ -0,0 +1,207 @@
+//! 固定布局的紧凑二进制编码
+//!
+//! 一帧包含多个读数（多字节数值为大端）：
+//!
+//! | 字节 | 内容 |
+//! | --- | --- |
+//! | 0 | 格式版本（当前为1） |
+//! | 1..5 | 编码时间（Unix时间戳，秒，发送端没有可靠时钟时为0） |
+//! | 之后每个读数 | 传感器编号（1字节）+ 时间差（2字节）+ 物理量数量（1字节）+ 物理量 |
+//! | 每个物理量 | 物理量编号（`Quantity::ALL`中的位置，1字节）+ 定点整数（2或4字节） |
+//!
+//! 时间差为读数时间到编码时间的秒数（最大65535秒），接收端用收到的时间减去时间差，
+//! 发送端不需要校准时钟。定点整数的字节数和精度由物理量决定（见`layout`），
+//! 例如温度为2字节、精度0.01℃，一个温湿度读数只需要10字节
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::reading::{Quantity, Reading, Sample};
+
+/// 格式版本
+const VERSION: u8 = 1;
+
+/// 帧头长度
+pub const HEADER_LEN: usize = 5;
+
+/// 物理量的定点格式：字节数（有符号整数）和倍率
+pub fn layout(quantity: Quantity) -> (usize, f64) {
+    match quantity {
+        // 0.01℃、0.01%
+        Quantity::Temperature
+        | Quantity::DewPoint
+        | Quantity::Humidity
+        | Quantity::SoilMoisture => (2, 100.0),
+        // 0.1Pa
+        Quantity::Pressure | Quantity::SeaLevelPressure => (4, 10.0),
+        // 0.01（克或校准单位）
+        Quantity::Weight => (4, 100.0),
+        // 1mV
+        Quantity::Voltage => (2, 1000.0),
+        Quantity::Raw => (4, 1.0),
+        // 0.01m/s
+        Quantity::WindSpeed | Quantity::WindGust => (2, 100.0),
+        // 0.01mm
+        Quantity::Rainfall => (4, 100.0),
+        // 0.1lx
+        Quantity::Illuminance => (4, 10.0),
+    }
+}
+
+/// 一个读数编码后的字节数
+pub fn encoded_len(sample: &Sample) -> usize {
+    4 + sample
+        .reading
+        .iter()
+        .map(|(quantity, _)| 1 + layout(quantity).0)
+        .sum::<usize>()
+}
+
+/// 二进制编解码器
+///
+/// 收发双方使用相同的传感器列表，传感器编号为名称在列表中的位置
+///
+/// ```ignore
+/// let codec = BinaryCodec::new(&["soil", "weather"])?;
+/// let frame = codec.encode(&samples)?;
+/// let samples = codec.decode(&frame, Some(SystemTime::now()))?;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BinaryCodec {
+    /// 传感器名称（编号为位置）
+    sensors: Vec<String>,
+}
+
+impl BinaryCodec {
+    /// 创建实例（最多256个传感器）
+    pub fn new(sensors: &[&str]) -> anyhow::Result<Self> {
+        if sensors.len() > 256 {
+            return Err(anyhow::anyhow!(
+                "二进制编码最多支持256个传感器: {}",
+                sensors.len()
+            ));
+        }
+        // OK
+        Ok(Self {
+            sensors: sensors.iter().map(|name| name.to_string()).collect(),
+        })
+    }
+
+    /// 传感器名称
+    pub fn sensors(&self) -> &[String] {
+        &self.sensors
+    }
+
+    /// 传感器编号
+    fn sensor_id(&self, name: &str) -> anyhow::Result<u8> {
+        self.sensors
+            .iter()
+            .position(|sensor| sensor == name)
+            .map(|id| id as u8)
+            .ok_or_else(|| anyhow::anyhow!("传感器不在二进制编码的传感器列表中: {}", name))
+    }
+
+    /// 编码一帧（编码时间为当前时间）
+    pub fn encode(&self, samples: &[Sample]) -> anyhow::Result<Vec<u8>> {
+        self.encode_at(samples, SystemTime::now())
+    }
+
+    /// 编码一帧
+    ///
+    /// - now: 编码时间（时间差的参考点）
+    pub fn encode_at(&self, samples: &[Sample], now: SystemTime) -> anyhow::Result<Vec<u8>> {
+        let base = now
+            .duration_since(UNIX_EPOCH)
+            .map(|t| t.as_secs() as u32)
+            .unwrap_or_default();
+        let mut frame = vec![VERSION];
+        frame.extend_from_slice(&base.to_be_bytes());
+        for sample in samples {
+            frame.push(self.sensor_id(&sample.sensor)?);
+            let delta = now
+                .duration_since(sample.timestamp)
+                .map(|t| t.as_secs().min(u16::MAX as u64) as u16)
+                .unwrap_or_default();
+            frame.extend_from_slice(&delta.to_be_bytes());
+            frame.push(sample.reading.iter().count() as u8);
+            for (quantity, value) in sample.reading.iter() {
+                let index = Quantity::ALL
+                    .iter()
+                    .position(|q| *q == quantity)
+                    .unwrap_or_default();
+                frame.push(index as u8);
+                let (width, scale) = layout(quantity);
+                let scaled = (value * scale).round();
+                if width == 2 {
+                    let value = scaled.clamp(i16::MIN as f64, i16::MAX as f64) as i16;
+                    frame.extend_from_slice(&value.to_be_bytes());
+                } else {
+                    let value = scaled.clamp(i32::MIN as f64, i32::MAX as f64) as i32;
+                    frame.extend_from_slice(&value.to_be_bytes());
+                }
+            }
+        }
+        // OK
+        Ok(frame)
+    }
+
+    /// 解码一帧
+    ///
+    /// - received_at: 收到帧的时间，Some时读数时间为收到时间减去时间差，
+    ///   None时使用帧中的编码时间（发送端时钟可靠，如记录文件）
+    pub fn decode(
+        &self,
+        frame: &[u8],
+        received_at: Option<SystemTime>,
+    ) -> anyhow::Result<Vec<Sample>> {
+        let Some((header, mut rest)) = frame.split_at_checked(HEADER_LEN) else {
+            return Err(anyhow::anyhow!("二进制帧过短: {}字节", frame.len()));
+        };
+        if header[0] != VERSION {
+            return Err(anyhow::anyhow!("不支持的二进制帧版本: {}", header[0]));
+        }
+        let base = received_at.unwrap_or_else(|| {
+            let seconds = u32::from_be_bytes([header[1], header[2], header[3], header[4]]);
+            UNIX_EPOCH + Duration::from_secs(seconds as u64)
+        });
+
+        let truncated = || anyhow::anyhow!("二进制帧不完整: {}字节", frame.len());
+        let mut samples = Vec::new();
+        while !rest.is_empty() {
+            let [id, delta_high, delta_low, count, tail @ ..] = rest else {
+                return Err(truncated());
+            };
+            let sensor = self
+                .sensors
+                .get(*id as usize)
+                .ok_or_else(|| anyhow::anyhow!("二进制帧中的传感器编号无效: {}", id))?;
+            let delta = u16::from_be_bytes([*delta_high, *delta_low]);
+            rest = tail;
+
+            let mut reading = Reading::new();
+            for _ in 0..*count {
+                let (&index, tail) = rest.split_first().ok_or_else(truncated)?;
+                let quantity = *Quantity::ALL
+                    .get(index as usize)
+                    .ok_or_else(|| anyhow::anyhow!("二进制帧中的物理量编号无效: {}", index))?;
+                let (width, scale) = layout(quantity);
+                let (bytes, tail) = tail.split_at_checked(width).ok_or_else(truncated)?;
+                let value = if width == 2 {
+                    i16::from_be_bytes([bytes[0], bytes[1]]) as f64
+                } else {
+                    i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64
+                };
+                reading.set(quantity, value / scale);
+                rest = tail;
+            }
+            samples.push(Sample {
+                sensor: sensor.clone(),
+                timestamp: base
+                    .checked_sub(Duration::from_secs(delta as u64))
+                    .unwrap_or(UNIX_EPOCH),
+                reading,
+            });
+        }
+        // OK
+        Ok(samples)
+    }
+}
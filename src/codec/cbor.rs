@@ -0,0 +1,225 @@
+//! CBOR编码（RFC 8949）
+//!
+//! 每个读数编码为一个映射：`{"sensor": "outdoor", "timestamp": 1700000000.123, "temperature": 23.5}`，
+//! 一批读数编码为数组，记录文件使用CBOR序列（RFC 8742，多个映射直接首尾相连）
+
+use std::time::UNIX_EPOCH;
+
+use crate::reading::{Quantity, Reading, Sample, from_unix_seconds};
+
+/// 主类型
+mod major {
+    pub const UNSIGNED: u8 = 0;
+    pub const NEGATIVE: u8 = 1;
+    pub const TEXT: u8 = 3;
+    pub const ARRAY: u8 = 4;
+    pub const MAP: u8 = 5;
+    pub const SIMPLE: u8 = 7;
+}
+
+/// 数据项的头部（主类型和长度/数值）
+fn write_head(buffer: &mut Vec<u8>, major: u8, value: u64) {
+    let major = major << 5;
+    match value {
+        0..=23 => buffer.push(major | value as u8),
+        24..=0xFF => buffer.extend_from_slice(&[major | 24, value as u8]),
+        0x100..=0xFFFF => {
+            buffer.push(major | 25);
+            buffer.extend_from_slice(&(value as u16).to_be_bytes());
+        }
+        0x1_0000..=0xFFFF_FFFF => {
+            buffer.push(major | 26);
+            buffer.extend_from_slice(&(value as u32).to_be_bytes());
+        }
+        _ => {
+            buffer.push(major | 27);
+            buffer.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+}
+
+/// 文本字符串
+fn write_text(buffer: &mut Vec<u8>, text: &str) {
+    write_head(buffer, major::TEXT, text.len() as u64);
+    buffer.extend_from_slice(text.as_bytes());
+}
+
+/// 双精度浮点数
+fn write_float(buffer: &mut Vec<u8>, value: f64) {
+    buffer.push(major::SIMPLE << 5 | 27);
+    buffer.extend_from_slice(&value.to_be_bytes());
+}
+
+/// 把一个读数编码后追加到缓冲区
+pub fn encode_into(buffer: &mut Vec<u8>, sample: &Sample) {
+    let count = sample.reading.iter().count() + 2;
+    write_head(buffer, major::MAP, count as u64);
+    write_text(buffer, "sensor");
+    write_text(buffer, &sample.sensor);
+    write_text(buffer, "timestamp");
+    write_float(buffer, sample.unix_timestamp());
+    for (quantity, value) in sample.reading.iter() {
+        write_text(buffer, quantity.name());
+        write_float(buffer, value);
+    }
+}
+
+/// 编码一个读数
+pub fn encode(sample: &Sample) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    encode_into(&mut buffer, sample);
+    buffer
+}
+
+/// 把一批读数编码为数组
+pub fn encode_batch(samples: &[Sample]) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    write_head(&mut buffer, major::ARRAY, samples.len() as u64);
+    for sample in samples {
+        encode_into(&mut buffer, sample);
+    }
+    buffer
+}
+
+/// 解码器（只支持读数用到的数据类型）
+struct Decoder<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    /// 读取指定长度的字节
+    fn take(&mut self, len: usize) -> anyhow::Result<&'a [u8]> {
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| anyhow::anyhow!("CBOR数据不完整"))?;
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    /// 读取数据项的头部，返回主类型、附加信息和数值
+    fn head(&mut self) -> anyhow::Result<(u8, u8, u64)> {
+        let first = self.take(1)?[0];
+        let (major, info) = (first >> 5, first & 0x1F);
+        let value = match info {
+            0..=23 => info as u64,
+            24 => self.take(1)?[0] as u64,
+            25 => u16::from_be_bytes(self.take(2)?.try_into()?) as u64,
+            26 => u32::from_be_bytes(self.take(4)?.try_into()?) as u64,
+            27 => u64::from_be_bytes(self.take(8)?.try_into()?),
+            _ => return Err(anyhow::anyhow!("不支持的CBOR长度编码: 0x{:02X}", first)),
+        };
+        // OK
+        Ok((major, info, value))
+    }
+
+    /// 读取指定主类型的长度
+    fn length(&mut self, expected: u8) -> anyhow::Result<usize> {
+        let (major, _, value) = self.head()?;
+        if major != expected {
+            return Err(anyhow::anyhow!(
+                "CBOR数据类型错误: 应为{}，实际为{}",
+                expected,
+                major
+            ));
+        }
+        // OK
+        Ok(value as usize)
+    }
+
+    /// 读取文本字符串
+    fn text(&mut self) -> anyhow::Result<&'a str> {
+        let len = self.length(major::TEXT)?;
+        let bytes = self.take(len)?;
+        std::str::from_utf8(bytes).map_err(|_| anyhow::anyhow!("CBOR文本不是有效的UTF-8"))
+    }
+
+    /// 读取数值（整数或半精度、单精度、双精度浮点数）
+    fn number(&mut self) -> anyhow::Result<f64> {
+        let (major, info, value) = self.head()?;
+        let number = match (major, info) {
+            (major::UNSIGNED, _) => value as f64,
+            (major::NEGATIVE, _) => -1.0 - value as f64,
+            (major::SIMPLE, 25) => half_to_f64(value as u16),
+            (major::SIMPLE, 26) => f32::from_bits(value as u32) as f64,
+            (major::SIMPLE, 27) => f64::from_bits(value),
+            _ => return Err(anyhow::anyhow!("CBOR数据不是数值: 主类型{}", major)),
+        };
+        // OK
+        Ok(number)
+    }
+
+    /// 读取一个读数映射（未知的物理量被忽略）
+    fn sample(&mut self) -> anyhow::Result<Sample> {
+        let count = self.length(major::MAP)?;
+        let mut sensor = None;
+        let mut timestamp = UNIX_EPOCH;
+        let mut reading = Reading::new();
+        for _ in 0..count {
+            match self.text()? {
+                "sensor" => sensor = Some(self.text()?.to_string()),
+                "timestamp" => {
+                    let seconds = self.number()?;
+                    timestamp = from_unix_seconds(seconds)
+                        .map_err(|err| anyhow::anyhow!("CBOR读数时间戳无效: {}", err))?;
+                }
+                name => {
+                    let value = self.number()?;
+                    if let Some(quantity) = Quantity::from_name(name) {
+                        reading.set(quantity, value);
+                    }
+                }
+            }
+        }
+        let sensor = sensor.ok_or_else(|| anyhow::anyhow!("CBOR读数缺少传感器名称"))?;
+        // OK
+        Ok(Sample {
+            sensor,
+            timestamp,
+            reading,
+        })
+    }
+}
+
+/// 半精度浮点数转换为双精度
+fn half_to_f64(bits: u16) -> f64 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = (bits >> 10 & 0x1F) as i32;
+    let fraction = (bits & 0x03FF) as f64;
+    sign * match exponent {
+        0 => fraction * 2f64.powi(-24),
+        31 if fraction == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (1.0 + fraction / 1024.0) * 2f64.powi(exponent - 15),
+    }
+}
+
+/// 解码一个读数，返回读数和使用的字节数（用于逐个解码CBOR序列）
+pub fn decode(data: &[u8]) -> anyhow::Result<(Sample, usize)> {
+    let mut decoder = Decoder { data, position: 0 };
+    let sample = decoder.sample()?;
+    // OK
+    Ok((sample, decoder.position))
+}
+
+/// 解码一批读数（数组）
+pub fn decode_batch(data: &[u8]) -> anyhow::Result<Vec<Sample>> {
+    let mut decoder = Decoder { data, position: 0 };
+    let count = decoder.length(major::ARRAY)?;
+    (0..count).map(|_| decoder.sample()).collect()
+}
+
+/// 解码CBOR序列（记录文件）
+pub fn decode_sequence(mut data: &[u8]) -> anyhow::Result<Vec<Sample>> {
+    let mut samples = Vec::new();
+    while !data.is_empty() {
+        let (sample, used) = decode(data)?;
+        samples.push(sample);
+        data = &data[used..];
+    }
+    // OK
+    Ok(samples)
+}
@@ -0,0 +1,9 @@
+//! 读数编码
+//!
+//! 带宽受限的链路（LoRa、CoAP/UDP）和二进制记录文件不使用JSON，改用：
+//!
+//! - `cbor`：自描述的CBOR（RFC 8949），结构与`Sample::to_json`相同，服务端可以用通用库解码
+//! - `binary`：固定布局的紧凑二进制（传感器编号、时间差、定点整数），收发双方共享传感器列表
+
+pub mod binary;
+pub mod cbor;
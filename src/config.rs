@@ -0,0 +1,999 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+#[cfg(feature = "aht30")]
+use crate::adapter::Aht30Sensor;
+#[cfg(feature = "bme280")]
+use crate::adapter::Bme280Sensor;
+#[cfg(feature = "dht11")]
+use crate::adapter::Dht11Sensor;
+use crate::apps::climate_chamber::HysteresisConfig;
+use crate::array::SensorArray;
+use crate::board::{Board, BootConfig, PinRequest, PinUsage};
+use crate::calibration::{CalibrationStore, FileStore};
+#[cfg(feature = "bme280")]
+use crate::core::bme280::Compensation;
+use crate::diagnostics::DiagnosticsReport;
+use crate::enclosure::{Enclosure, EnclosureModel};
+#[cfg(any(feature = "dht11", feature = "hx711"))]
+use crate::gpio::Pull;
+use crate::gpio::{self, Drive};
+use crate::i2c_bus::SharedBus;
+#[cfg(feature = "iio")]
+use crate::iio::{HwmonSensor, IioSensor, W1Therm};
+use crate::manager::{Sensor, SensorManager};
+#[cfg(feature = "privdrop")]
+use crate::privilege::{Capability, PrivilegeDrop};
+use crate::profiles::greenhouse::{Greenhouse, GreenhouseSettings};
+#[cfg(any(feature = "hx711", feature = "nau7802"))]
+use crate::scale::{Scale, WeightAdc};
+use crate::scale_service::ScaleSettings;
+#[cfg(feature = "bh1750")]
+use crate::sensor::bh1750::BH1750;
+#[cfg(feature = "hx711")]
+use crate::sensor::hx711::{ChannelGain, HX711, Rate};
+#[cfg(all(feature = "soil-moisture", feature = "mcp3008"))]
+use crate::sensor::mcp3008::MCP3008;
+#[cfg(feature = "nau7802")]
+use crate::sensor::nau7802::NAU7802;
+#[cfg(all(feature = "soil-moisture", feature = "mcp3008"))]
+use crate::sensor::soil_moisture::SoilMoisture;
+#[cfg(feature = "sim")]
+use crate::sim::SimBackend;
+use crate::switch::GpioSwitch;
+#[cfg(all(feature = "soil-moisture", feature = "mcp3008"))]
+use rppal::spi::{Bus, SlaveSelect};
+
+/// 可以配置外壳补偿的型号（输出温度或湿度）
+const ENCLOSURE_KINDS: [&str; 6] = ["dht11", "aht30", "bme280", "iio", "hwmon", "w1-therm"];
+
+/// 按编号缓存已打开的I2C总线，同一编号的总线只打开一次
+#[derive(Clone, Default)]
+pub struct I2cBuses {
+    buses: BTreeMap<u8, SharedBus>,
+}
+
+impl I2cBuses {
+    /// 打开指定编号的总线（已打开时返回共享的总线）
+    pub fn open(&mut self, bus: u8) -> anyhow::Result<SharedBus> {
+        if let Some(i2c) = self.buses.get(&bus) {
+            return Ok(i2c.clone());
+        }
+        let i2c = SharedBus::open(bus)?;
+        self.buses.insert(bus, i2c.clone());
+        Ok(i2c)
+    }
+}
+
+/// 硬件配置文件
+///
+/// 传感器按名称配置，型号默认与名称相同：
+///
+/// ```toml
+/// [sensors.dht11]
+/// pin = 4
+///
+/// [sensors.greenhouse]
+/// kind = "bme280"
+/// bus = 1
+/// addr = 0x76
+/// interval_ms = 5000
+/// compensation = "float"
+///
+/// [sensors.hx711]
+/// clock_pin = 5
+/// data_pin = 6
+/// pull = "up"
+/// drive_ma = 4
+/// slew_limit = true
+///
+/// [sensors.aht30]
+/// calibration = "/var/lib/raspi-sensor/sensor.cal"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HardwareConfig {
+    /// 传感器配置（名称 -> 配置）
+    #[serde(default)]
+    pub sensors: BTreeMap<String, SensorConfig>,
+    /// 称重服务配置（名称 -> 配置），名称与称重传感器的名称相同
+    #[serde(default)]
+    pub scales: BTreeMap<String, ScaleConfig>,
+    /// 同型号传感器阵列（名称 -> 配置），每个阵列注册为一个传感器
+    #[serde(default)]
+    pub arrays: BTreeMap<String, ArrayConfig>,
+    /// 温室（名称 -> 配置），温室使用的传感器在温室的配置中单独配置，不注册到管理器
+    #[serde(default)]
+    pub greenhouses: BTreeMap<String, GreenhouseConfig>,
+    /// 打开所有设备后切换到的用户（需要`privdrop`功能）
+    pub privileges: Option<PrivilegeConfig>,
+}
+
+/// 单个传感器的配置
+///
+/// 不同型号使用的字段不同，未使用的字段留空即可
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SensorConfig {
+    /// 传感器型号（dht11、aht30、bme280、bh1750、hx711、nau7802、soil-moisture、iio、hwmon、
+    /// w1-therm），为空时使用名称
+    pub kind: Option<String>,
+    /// 是否启用（默认启用）
+    pub enabled: Option<bool>,
+    /// 单总线引脚（DHT11）
+    pub pin: Option<u8>,
+    /// I2C总线编号（默认为1）
+    pub bus: Option<u8>,
+    /// I2C设备地址，为空时使用芯片默认地址
+    pub addr: Option<u8>,
+    /// 最小读取间隔（毫秒），不能小于传感器自身的最小间隔
+    pub interval_ms: Option<u64>,
+    /// 时钟引脚（HX711的PD_SCK）
+    pub clock_pin: Option<u8>,
+    /// 数据引脚（HX711的DOUT）
+    pub data_pin: Option<u8>,
+    /// 速率引脚（HX711的RATE）
+    pub rate_pin: Option<u8>,
+    /// 通道和增益（HX711：a128、b32、a64）
+    pub gain: Option<String>,
+    /// 输出速率（HX711：10、80）
+    pub sps: Option<u32>,
+    /// 矫正因子，配置后按电子秤输出重量
+    pub transform_factor: Option<f32>,
+    /// 0点偏移值（皮重）
+    pub zero_offset: Option<i32>,
+    /// 内核驱动的设备（iio、hwmon为设备名称或路径，w1-therm为设备ID，为空时使用第一个）
+    pub device: Option<String>,
+    /// ADC通道（土壤湿度传感器接在MCP3008上的通道，0~7）
+    pub channel: Option<u8>,
+    /// SPI片选（MCP3008，0~2，默认为0）
+    pub chip_select: Option<u8>,
+    /// 完全干燥时的ADC原始值（土壤湿度传感器）
+    pub dry_raw: Option<u32>,
+    /// 完全浸水时的ADC原始值（土壤湿度传感器）
+    pub wet_raw: Option<u32>,
+    /// 补偿算法（BME280：float、integer），为空时使用sensor-hal驱动自带的补偿计算
+    pub compensation: Option<String>,
+    /// 内部上下拉（up、down、none；DHT11为数据线，默认不修改；HX711为数据引脚，默认上拉）
+    pub pull: Option<String>,
+    /// 引脚驱动电流（mA，2~16之间的偶数；按引脚组生效，树莓派5不支持）
+    pub drive_ma: Option<u8>,
+    /// 是否限制引脚的边沿速率（长线缆上减少振铃；按引脚组生效，树莓派5不支持）
+    pub slew_limit: Option<bool>,
+    /// 校准数据文件，其中保存了该传感器的外壳补偿系数时按系数补偿温湿度（只用于
+    /// dht11、aht30、bme280、iio、hwmon、w1-therm）
+    pub calibration: Option<String>,
+}
+
+impl SensorConfig {
+    /// 是否启用
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != Some(false)
+    }
+
+    /// 最小读取间隔（未配置时为0，即使用传感器自身的最小间隔）
+    pub fn min_interval(&self) -> Duration {
+        self.interval_ms
+            .map(Duration::from_millis)
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// 是否只有读取间隔不同（修改读取间隔不需要重新创建传感器）
+    pub fn same_hardware(&self, other: &SensorConfig) -> bool {
+        let strip = |config: &SensorConfig| SensorConfig {
+            enabled: None,
+            interval_ms: None,
+            ..config.clone()
+        };
+        strip(self) == strip(other)
+    }
+
+    /// 配置中使用的引脚和I2C总线
+    ///
+    /// - name: 传感器名称（未配置型号时作为型号）
+    pub fn pin_requests(&self, name: &str) -> Vec<PinRequest> {
+        let kind = self.kind.as_deref().unwrap_or(name);
+        let mut requests: Vec<PinRequest> =
+            [self.pin, self.clock_pin, self.data_pin, self.rate_pin]
+                .into_iter()
+                .flatten()
+                .map(|pin| PinRequest::new(name, PinUsage::Gpio(pin)))
+                .collect();
+        if matches!(kind, "aht30" | "bme280" | "bh1750" | "nau7802") {
+            requests.push(PinRequest::new(
+                name,
+                PinUsage::I2cBus(self.bus.unwrap_or(1)),
+            ));
+        }
+        requests
+    }
+
+    /// 按配置创建传感器
+    ///
+    /// - name: 传感器名称（未配置型号时作为型号）
+    /// - buses: 已打开的I2C总线
+    pub fn build(&self, name: &str, buses: &mut I2cBuses) -> anyhow::Result<Box<dyn Sensor>> {
+        let config = self;
+        // 没有编译I2C传感器的驱动时不使用总线
+        #[cfg(not(any(
+            feature = "aht30",
+            feature = "bme280",
+            feature = "bh1750",
+            feature = "nau7802"
+        )))]
+        let _ = buses;
+        let kind = config.kind.as_deref().unwrap_or(name);
+        #[cfg(any(
+            feature = "aht30",
+            feature = "bme280",
+            feature = "bh1750",
+            feature = "nau7802"
+        ))]
+        let mut i2c_bus = || buses.open(config.bus.unwrap_or(1));
+
+        config.apply_drive(name)?;
+
+        let sensor: Option<Box<dyn Sensor>> = match kind {
+            #[cfg(feature = "dht11")]
+            "dht11" => {
+                let mut dht11 = Dht11Sensor::new(SensorConfig::require(config.pin, name, "pin")?)?;
+                if let Some(pull) = config.pull(name)? {
+                    dht11.set_pull(pull);
+                }
+                Some(Box::new(dht11))
+            }
+            #[cfg(feature = "aht30")]
+            "aht30" => Some(Box::new(Aht30Sensor::new(i2c_bus()?, config.addr)?)),
+            #[cfg(feature = "bme280")]
+            "bme280" => {
+                let mut bme280 = Bme280Sensor::new(i2c_bus()?, config.addr)?;
+                if let Some(compensation) = config.compensation(name)? {
+                    bme280 = bme280.with_compensation(compensation)?;
+                }
+                Some(Box::new(bme280))
+            }
+            #[cfg(feature = "bh1750")]
+            "bh1750" => Some(Box::new(BH1750::new(
+                i2c_bus()?,
+                config.addr.unwrap_or(0x23) as u16,
+            )?)),
+            #[cfg(feature = "hx711")]
+            "hx711" => {
+                let mut hx711 = HX711::new(
+                    SensorConfig::require(config.clock_pin, name, "clock_pin")?,
+                    SensorConfig::require(config.data_pin, name, "data_pin")?,
+                    config.channel_gain(name)?,
+                    config.rate_pin,
+                )?;
+                hx711.set_rate(config.rate(name)?);
+                if let Some(pull) = config.pull(name)? {
+                    hx711.set_data_pull(pull);
+                }
+                Some(config.weigh(hx711))
+            }
+            #[cfg(feature = "nau7802")]
+            "nau7802" => Some(config.weigh(NAU7802::new(i2c_bus()?.inner().clone())?)),
+            #[cfg(all(feature = "soil-moisture", feature = "mcp3008"))]
+            "soil-moisture" => {
+                let slave_select = match config.chip_select.unwrap_or(0) {
+                    0 => SlaveSelect::Ss0,
+                    1 => SlaveSelect::Ss1,
+                    2 => SlaveSelect::Ss2,
+                    cs => return Err(anyhow::anyhow!("传感器{}的片选配置无效: {}", name, cs)),
+                };
+                let adc = MCP3008::new(Bus::Spi0, slave_select)?;
+                Some(Box::new(SoilMoisture::new(
+                    adc.channel(SensorConfig::require(config.channel, name, "channel")?)?,
+                    SensorConfig::require(config.dry_raw, name, "dry_raw")?,
+                    SensorConfig::require(config.wet_raw, name, "wet_raw")?,
+                )?))
+            }
+            #[cfg(feature = "iio")]
+            "iio" => Some(Box::new(IioSensor::open(config.require_device(name)?)?)),
+            #[cfg(feature = "iio")]
+            "hwmon" => Some(Box::new(HwmonSensor::open(config.require_device(name)?)?)),
+            #[cfg(feature = "iio")]
+            "w1-therm" => Some(Box::new(W1Therm::open(config.device.as_deref())?)),
+            // 型号不支持或对应驱动未编译
+            _ => None,
+        };
+        let sensor = sensor.ok_or_else(|| {
+            anyhow::anyhow!("传感器{}的型号不支持或未启用对应功能: {}", name, kind)
+        })?;
+        config.enclose(name, kind, sensor)
+    }
+
+    /// 按校准数据中的外壳补偿系数包装传感器（没有系数时原样返回）
+    ///
+    /// 只有输出温湿度的型号可以配置校准数据文件
+    fn enclose(
+        &self,
+        name: &str,
+        kind: &str,
+        sensor: Box<dyn Sensor>,
+    ) -> anyhow::Result<Box<dyn Sensor>> {
+        let Some(path) = &self.calibration else {
+            return Ok(sensor);
+        };
+        if !ENCLOSURE_KINDS.contains(&kind) {
+            return Err(anyhow::anyhow!(
+                "传感器{}的型号{}没有温湿度输出，不能配置外壳补偿的校准数据: {}",
+                name,
+                kind,
+                path
+            ));
+        }
+        let calibration = FileStore::new(path)
+            .load()
+            .map_err(|err| anyhow::anyhow!("读取传感器{}的校准数据{}失败: {}", name, path, err))?;
+        let model = EnclosureModel::load(&calibration, name)
+            .map_err(|err| anyhow::anyhow!("传感器{}的校准数据{}无效: {}", name, path, err))?;
+        match model {
+            Some(model) => Ok(Box::new(Enclosure::new(sensor, model))),
+            None => Ok(sensor),
+        }
+    }
+
+    /// 必填字段
+    #[cfg(any(
+        feature = "dht11",
+        feature = "hx711",
+        all(feature = "soil-moisture", feature = "mcp3008")
+    ))]
+    fn require<T: Copy>(value: Option<T>, name: &str, field: &str) -> anyhow::Result<T> {
+        value.ok_or_else(|| anyhow::anyhow!("传感器{}缺少配置项: {}", name, field))
+    }
+
+    /// 内部上下拉（未配置时为None，由驱动决定默认值）
+    #[cfg(any(feature = "dht11", feature = "hx711"))]
+    fn pull(&self, name: &str) -> anyhow::Result<Option<Pull>> {
+        self.pull
+            .as_deref()
+            .map(|pull| Pull::parse(pull).map_err(|err| anyhow::anyhow!("传感器{}的{}", name, err)))
+            .transpose()
+    }
+
+    /// 引脚驱动能力（drive_ma、slew_limit都未配置时为None，不修改寄存器）
+    pub fn drive(&self) -> Option<Drive> {
+        if self.drive_ma.is_none() && self.slew_limit.is_none() {
+            return None;
+        }
+        let default = Drive::default();
+        Some(Drive {
+            strength_ma: self.drive_ma.unwrap_or(default.strength_ma),
+            slew_limited: self.slew_limit.unwrap_or(default.slew_limited),
+            ..default
+        })
+    }
+
+    /// 按配置设置所有GPIO引脚的驱动能力
+    fn apply_drive(&self, name: &str) -> anyhow::Result<()> {
+        let Some(drive) = self.drive() else {
+            return Ok(());
+        };
+        for pin in [self.pin, self.clock_pin, self.data_pin, self.rate_pin]
+            .into_iter()
+            .flatten()
+        {
+            gpio::set_drive(pin, drive)
+                .map_err(|err| anyhow::anyhow!("传感器{}: {}", name, err))?;
+        }
+        // OK
+        Ok(())
+    }
+
+    /// 内核驱动的设备名称
+    #[cfg(feature = "iio")]
+    fn require_device(&self, name: &str) -> anyhow::Result<&str> {
+        self.device
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("传感器{}缺少配置项: device", name))
+    }
+
+    /// HX711通道和增益
+    #[cfg(feature = "hx711")]
+    fn channel_gain(&self, name: &str) -> anyhow::Result<ChannelGain> {
+        match self.gain.as_deref() {
+            None | Some("a128") => Ok(ChannelGain::ChannelA128),
+            Some("b32") => Ok(ChannelGain::ChannelB32),
+            Some("a64") => Ok(ChannelGain::ChannelA64),
+            Some(gain) => Err(anyhow::anyhow!("传感器{}的增益配置无效: {}", name, gain)),
+        }
+    }
+
+    /// BME280补偿算法
+    #[cfg(feature = "bme280")]
+    fn compensation(&self, name: &str) -> anyhow::Result<Option<Compensation>> {
+        match self.compensation.as_deref() {
+            None => Ok(None),
+            Some("float") => Ok(Some(Compensation::Float)),
+            Some("integer") => Ok(Some(Compensation::Integer)),
+            Some(compensation) => Err(anyhow::anyhow!(
+                "传感器{}的补偿算法配置无效: {}",
+                name,
+                compensation
+            )),
+        }
+    }
+
+    /// HX711输出速率
+    #[cfg(feature = "hx711")]
+    fn rate(&self, name: &str) -> anyhow::Result<Rate> {
+        match self.sps {
+            None | Some(10) => Ok(Rate::Sps10),
+            Some(80) => Ok(Rate::Sps80),
+            Some(sps) => Err(anyhow::anyhow!("传感器{}的输出速率配置无效: {}", name, sps)),
+        }
+    }
+
+    /// 称重ADC按需包装为电子秤
+    #[cfg(any(feature = "hx711", feature = "nau7802"))]
+    fn weigh<S>(&self, adc: S) -> Box<dyn Sensor>
+    where
+        S: WeightAdc + Sensor + 'static,
+    {
+        match self.transform_factor {
+            Some(transform_factor) => {
+                let mut scale = Scale::new(adc, transform_factor);
+                if let Some(zero_offset) = self.zero_offset {
+                    scale.set_zero_offset(zero_offset);
+                }
+                Box::new(scale)
+            }
+            None => Box::new(adc),
+        }
+    }
+}
+
+/// 同型号传感器阵列的配置
+///
+/// 各传感器的配置与`[sensors]`相同，未指定型号时使用阵列的型号。配置热重载不处理阵列
+///
+/// ```toml
+/// [arrays.tent]
+/// kind = "dht11"
+/// aggregate = "median"
+/// gap_ms = 200
+///
+/// [arrays.tent.instances.north]
+/// pin = 4
+///
+/// [arrays.tent.instances.south]
+/// pin = 17
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArrayConfig {
+    /// 传感器型号
+    pub kind: String,
+    /// 是否启用（默认启用）
+    pub enabled: Option<bool>,
+    /// 汇总方式（mean、median、min、max、sum，默认为mean）
+    pub aggregate: Option<String>,
+    /// 相邻两个传感器读取之间的间隔（毫秒，默认100）
+    pub gap_ms: Option<u64>,
+    /// 至少需要读取成功的传感器数量（默认1）
+    pub min_instances: Option<usize>,
+    /// 最小读取间隔（毫秒）
+    pub interval_ms: Option<u64>,
+    /// 各传感器的配置（标签 -> 配置）
+    #[serde(default)]
+    pub instances: BTreeMap<String, SensorConfig>,
+}
+
+impl ArrayConfig {
+    /// 按配置创建阵列
+    pub fn build(&self, name: &str, buses: &mut I2cBuses) -> anyhow::Result<SensorArray> {
+        if self.instances.is_empty() {
+            return Err(anyhow::anyhow!("阵列{}没有配置传感器", name));
+        }
+        let mut sensors = Vec::with_capacity(self.instances.len());
+        for (label, config) in &self.instances {
+            if !config.is_enabled() {
+                continue;
+            }
+            let config = SensorConfig {
+                kind: Some(config.kind.clone().unwrap_or_else(|| self.kind.clone())),
+                ..config.clone()
+            };
+            let sensor = config
+                .build(label, buses)
+                .map_err(|err| anyhow::anyhow!("阵列{}: {}", name, err))?;
+            sensors.push((label, sensor));
+        }
+        let kind = sensors.first().map_or("array", |(_, sensor)| sensor.kind());
+        let mut array = SensorArray::new(kind);
+        for (label, sensor) in sensors {
+            array = array.with_boxed(label, sensor);
+        }
+        if let Some(aggregate) = &self.aggregate {
+            array = array.with_aggregate(aggregate.parse()?);
+        }
+        if let Some(gap_ms) = self.gap_ms {
+            array = array.with_gap(Duration::from_millis(gap_ms));
+        }
+        if let Some(min_instances) = self.min_instances {
+            array = array.with_min_instances(min_instances);
+        }
+        // OK
+        Ok(array)
+    }
+}
+
+/// 称重服务的配置
+///
+/// ```toml
+/// [scales.kitchen]
+/// unit = "kg"
+/// capacity = 5000.0
+/// min_weight = -1.0
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScaleConfig {
+    /// 显示单位（g、kg、oz，默认为g）
+    pub unit: Option<String>,
+    /// 最大称量（克），超过时为超载
+    pub capacity: Option<f64>,
+    /// 最小重量（克），低于时为欠载
+    pub min_weight: Option<f64>,
+    /// 滑动平均滤波的ADC读数数量
+    pub filter_window: Option<usize>,
+    /// 稳定判断的滤波结果数量
+    pub stable_window: Option<usize>,
+    /// 稳定判断的允许波动（克）
+    pub stable_tolerance: Option<f64>,
+    /// 读取间隔（毫秒）
+    pub interval_ms: Option<u64>,
+}
+
+impl ScaleConfig {
+    /// 转换为称重服务参数（未配置的字段使用默认值）
+    pub fn settings(&self) -> anyhow::Result<ScaleSettings> {
+        let mut settings = ScaleSettings::default();
+        if let Some(unit) = &self.unit {
+            settings.unit = unit.parse()?;
+        }
+        if let Some(capacity) = self.capacity {
+            settings.capacity = capacity;
+        }
+        if let Some(min_weight) = self.min_weight {
+            settings.min_weight = min_weight;
+        }
+        if settings.min_weight >= settings.capacity {
+            return Err(anyhow::anyhow!(
+                "最小重量{}必须小于最大称量{}",
+                settings.min_weight,
+                settings.capacity
+            ));
+        }
+        if let Some(filter_window) = self.filter_window {
+            settings.filter_window = filter_window;
+        }
+        if let Some(stable_window) = self.stable_window {
+            settings.stable_window = stable_window;
+        }
+        if let Some(stable_tolerance) = self.stable_tolerance {
+            settings.stable_tolerance = stable_tolerance;
+        }
+        if let Some(interval_ms) = self.interval_ms {
+            settings.interval = Duration::from_millis(interval_ms);
+        }
+        // OK
+        Ok(settings)
+    }
+}
+
+/// 继电器输出的配置
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RelayConfig {
+    /// 输出引脚
+    pub pin: u8,
+    /// 高电平打开（默认为true，低电平触发的继电器模块设为false）
+    pub active_high: Option<bool>,
+}
+
+impl RelayConfig {
+    /// 创建开关
+    pub fn build(&self) -> anyhow::Result<GpioSwitch> {
+        GpioSwitch::new(self.pin, self.active_high.unwrap_or(true))
+    }
+}
+
+/// 温室的配置
+///
+/// 开关阈值成对配置（打开, 关闭），未配置的参数使用`GreenhouseSettings`的默认值
+///
+/// ```toml
+/// [greenhouses.north]
+/// heater_on = 3.0
+/// heater_off = 5.0
+/// mist_on_vpd = 1.5
+/// mist_off_vpd = 0.9
+/// frost_alarm = 1.0
+/// heater = { pin = 22, active_high = false }
+/// vent = { pin = 23 }
+/// mister = { pin = 24 }
+/// irrigation = { pin = 25 }
+///
+/// [greenhouses.north.climate]
+/// kind = "bme280"
+/// addr = 0x76
+///
+/// [greenhouses.north.soil]
+/// kind = "soil-moisture"
+/// channel = 0
+/// dry_raw = 820
+/// wet_raw = 360
+///
+/// [greenhouses.north.light]
+/// kind = "bh1750"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GreenhouseConfig {
+    /// 温湿度传感器
+    pub climate: Option<SensorConfig>,
+    /// 土壤湿度传感器
+    pub soil: Option<SensorConfig>,
+    /// 光照传感器
+    pub light: Option<SensorConfig>,
+    /// 加热继电器
+    pub heater: Option<RelayConfig>,
+    /// 通风继电器
+    pub vent: Option<RelayConfig>,
+    /// 喷雾继电器
+    pub mister: Option<RelayConfig>,
+    /// 灌溉继电器
+    pub irrigation: Option<RelayConfig>,
+    /// 加热打开温度（℃）
+    pub heater_on: Option<f64>,
+    /// 加热关闭温度（℃）
+    pub heater_off: Option<f64>,
+    /// 通风打开温度（℃）
+    pub vent_on: Option<f64>,
+    /// 通风关闭温度（℃）
+    pub vent_off: Option<f64>,
+    /// 喷雾打开的VPD（kPa）
+    pub mist_on_vpd: Option<f64>,
+    /// 喷雾关闭的VPD（kPa）
+    pub mist_off_vpd: Option<f64>,
+    /// 允许喷雾的最低光照度（lux）
+    pub mist_min_lux: Option<f64>,
+    /// 灌溉打开的土壤湿度（%）
+    pub irrigation_start: Option<f64>,
+    /// 灌溉关闭的土壤湿度（%）
+    pub irrigation_stop: Option<f64>,
+    /// 单次灌溉的最长运行时间（秒）
+    pub irrigation_max_s: Option<u64>,
+    /// 两次灌溉之间的最短间隔（秒）
+    pub irrigation_interval_s: Option<u64>,
+    /// 霜冻报警温度（℃）
+    pub frost_alarm: Option<f64>,
+    /// 热害报警温度（℃）
+    pub heat_stress_alarm: Option<f64>,
+    /// 土壤过干报警湿度（%）
+    pub soil_dry_alarm: Option<f64>,
+    /// 超出报警范围持续多久后报警（秒）
+    pub alarm_delay_s: Option<u64>,
+    /// 传感器连续读取失败多少次后关闭依赖它的执行器
+    pub max_failures: Option<u32>,
+}
+
+impl GreenhouseConfig {
+    /// 转换为温室参数（未配置的字段使用默认值）
+    pub fn settings(&self) -> anyhow::Result<GreenhouseSettings> {
+        let mut settings = GreenhouseSettings::default();
+        // 按打开和关闭阈值设置回差控制，rising为true时打开阈值高于关闭阈值
+        let thresholds = |config: &mut HysteresisConfig,
+                          on: Option<f64>,
+                          off: Option<f64>,
+                          rising: bool,
+                          label: &str|
+         -> anyhow::Result<()> {
+            let half = config.deadband.abs() / 2.0;
+            let (default_on, default_off) = if rising {
+                (config.setpoint + half, config.setpoint - half)
+            } else {
+                (config.setpoint - half, config.setpoint + half)
+            };
+            let (on, off) = (on.unwrap_or(default_on), off.unwrap_or(default_off));
+            if (on > off) != rising || on == off {
+                return Err(anyhow::anyhow!(
+                    "{}的打开阈值{}和关闭阈值{}不合理",
+                    label,
+                    on,
+                    off
+                ));
+            }
+            config.setpoint = (on + off) / 2.0;
+            config.deadband = (on - off).abs();
+            Ok(())
+        };
+        thresholds(
+            &mut settings.heater,
+            self.heater_on,
+            self.heater_off,
+            false,
+            "加热",
+        )?;
+        thresholds(
+            &mut settings.vent,
+            self.vent_on,
+            self.vent_off,
+            true,
+            "通风",
+        )?;
+        thresholds(
+            &mut settings.mister,
+            self.mist_on_vpd,
+            self.mist_off_vpd,
+            true,
+            "喷雾",
+        )?;
+        thresholds(
+            &mut settings.irrigation,
+            self.irrigation_start,
+            self.irrigation_stop,
+            false,
+            "灌溉",
+        )?;
+        if let Some(irrigation_max_s) = self.irrigation_max_s {
+            settings.irrigation_max_run = Duration::from_secs(irrigation_max_s);
+        }
+        if let Some(irrigation_interval_s) = self.irrigation_interval_s {
+            settings.irrigation.min_off = Duration::from_secs(irrigation_interval_s);
+        }
+        if self.mist_min_lux.is_some() {
+            settings.mist_min_lux = self.mist_min_lux;
+        }
+        if self.frost_alarm.is_some() {
+            settings.frost_alarm = self.frost_alarm;
+        }
+        if self.heat_stress_alarm.is_some() {
+            settings.heat_stress_alarm = self.heat_stress_alarm;
+        }
+        if self.soil_dry_alarm.is_some() {
+            settings.soil_dry_alarm = self.soil_dry_alarm;
+        }
+        if let Some(alarm_delay_s) = self.alarm_delay_s {
+            settings.alarm_delay = Duration::from_secs(alarm_delay_s);
+        }
+        if let Some(max_failures) = self.max_failures {
+            settings.max_failures = max_failures;
+        }
+        // OK
+        Ok(settings)
+    }
+
+    /// 配置中使用的引脚和I2C总线
+    pub fn pin_requests(&self, name: &str) -> Vec<PinRequest> {
+        let mut requests = Vec::new();
+        for (label, config) in [
+            ("climate", &self.climate),
+            ("soil", &self.soil),
+            ("light", &self.light),
+        ] {
+            if let Some(config) = config {
+                requests.extend(config.pin_requests(&format!("{}.{}", name, label)));
+            }
+        }
+        for (label, relay) in [
+            ("heater", &self.heater),
+            ("vent", &self.vent),
+            ("mister", &self.mister),
+            ("irrigation", &self.irrigation),
+        ] {
+            if let Some(relay) = relay {
+                requests.push(PinRequest::new(
+                    &format!("{}.{}", name, label),
+                    PinUsage::Gpio(relay.pin),
+                ));
+            }
+        }
+        requests
+    }
+
+    /// 按配置创建温室
+    pub fn build(&self, name: &str, buses: &mut I2cBuses) -> anyhow::Result<Greenhouse> {
+        let mut greenhouse = Greenhouse::new(name, self.settings()?)?;
+        let sensor = |config: &SensorConfig, label: &str, buses: &mut I2cBuses| {
+            config
+                .build(label, buses)
+                .map_err(|err| anyhow::anyhow!("温室{}: {}", name, err))
+        };
+        if let Some(config) = &self.climate {
+            greenhouse = greenhouse.with_climate_sensor(sensor(config, "climate", buses)?);
+        }
+        if let Some(config) = &self.soil {
+            greenhouse = greenhouse.with_soil_sensor(sensor(config, "soil", buses)?);
+        }
+        if let Some(config) = &self.light {
+            greenhouse = greenhouse.with_light_sensor(sensor(config, "light", buses)?);
+        }
+        if let Some(relay) = &self.heater {
+            greenhouse = greenhouse.with_heater(relay.build()?);
+        }
+        if let Some(relay) = &self.vent {
+            greenhouse = greenhouse.with_vent(relay.build()?);
+        }
+        if let Some(relay) = &self.mister {
+            greenhouse = greenhouse.with_mister(relay.build()?);
+        }
+        if let Some(relay) = &self.irrigation {
+            greenhouse = greenhouse.with_irrigation(relay.build()?);
+        }
+        // OK
+        Ok(greenhouse)
+    }
+}
+
+/// 降低权限的配置
+///
+/// ```toml
+/// [privileges]
+/// user = "sensor"
+/// capabilities = ["CAP_NET_BIND_SERVICE"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PrivilegeConfig {
+    /// 切换到的用户
+    pub user: String,
+    /// 切换到的用户组（默认为用户的主组）
+    pub group: Option<String>,
+    /// 切换用户后保留的能力
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+#[cfg(feature = "privdrop")]
+impl PrivilegeConfig {
+    /// 转换为`PrivilegeDrop`
+    pub fn build(&self) -> anyhow::Result<PrivilegeDrop> {
+        let mut privileges = PrivilegeDrop::new(&self.user);
+        if let Some(group) = &self.group {
+            privileges = privileges.with_group(group);
+        }
+        for capability in &self.capabilities {
+            privileges = privileges.keep(capability.parse::<Capability>()?);
+        }
+        // OK
+        Ok(privileges)
+    }
+}
+
+impl HardwareConfig {
+    /// 解析TOML格式的配置
+    pub fn from_toml_str(text: &str) -> anyhow::Result<Self> {
+        toml::from_str(text).map_err(|err| anyhow::anyhow!("解析TOML配置失败: {}", err))
+    }
+
+    /// 解析YAML格式的配置
+    pub fn from_yaml_str(text: &str) -> anyhow::Result<Self> {
+        serde_yaml::from_str(text).map_err(|err| anyhow::anyhow!("解析YAML配置失败: {}", err))
+    }
+
+    /// 读取配置文件（按扩展名区分格式：.toml、.yaml/.yml）
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| anyhow::anyhow!("读取配置文件{}失败: {}", path.display(), err))?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml_str(&text),
+            Some("yaml") | Some("yml") => Self::from_yaml_str(&text),
+            _ => Err(anyhow::anyhow!("不支持的配置文件格式: {}", path.display())),
+        }
+    }
+
+    /// 所有启用的传感器使用的引脚和I2C总线
+    pub fn pin_requests(&self) -> Vec<PinRequest> {
+        let mut requests = Vec::new();
+        for (name, config) in &self.sensors {
+            if config.is_enabled() {
+                requests.extend(config.pin_requests(name));
+            }
+        }
+        for (name, array) in &self.arrays {
+            if array.enabled == Some(false) {
+                continue;
+            }
+            for (instance, config) in &array.instances {
+                if !config.is_enabled() {
+                    continue;
+                }
+                let config = SensorConfig {
+                    kind: Some(config.kind.clone().unwrap_or_else(|| array.kind.clone())),
+                    ..config.clone()
+                };
+                requests.extend(config.pin_requests(&format!("{}.{}", name, instance)));
+            }
+        }
+        for (name, greenhouse) in &self.greenhouses {
+            requests.extend(greenhouse.pin_requests(name));
+        }
+        requests
+    }
+
+    /// 按当前开发板和config.txt检查引脚配置（引脚冲突、总线未启用等）
+    ///
+    /// 创建传感器之前检查，给出可操作的提示，而不是打开GPIO或I2C时的底层错误
+    pub fn validate_board(&self) -> DiagnosticsReport {
+        Board::detect().validate(BootConfig::load().as_ref(), &self.pin_requests())
+    }
+
+    /// 按配置创建所有传感器并注册到管理器
+    ///
+    /// 同一编号的I2C总线只打开一次，由该总线上的所有传感器共享
+    pub fn build(&self) -> anyhow::Result<SensorManager> {
+        self.build_with(&mut I2cBuses::default())
+    }
+
+    /// 按配置创建所有传感器并注册到管理器，打开的I2C总线保存在`buses`中
+    ///
+    /// 运行期间重新加载配置时使用同一个`buses`，新增的传感器与已有的传感器共享总线
+    pub fn build_with(&self, buses: &mut I2cBuses) -> anyhow::Result<SensorManager> {
+        let manager = SensorManager::new();
+        for (name, config) in &self.sensors {
+            if !config.is_enabled() {
+                continue;
+            }
+            let sensor = config.build(name, buses)?;
+            manager
+                .register_boxed(name, sensor, config.min_interval())
+                .map_err(|err| anyhow::anyhow!("注册传感器{}失败: {}", name, err))?;
+        }
+        for (name, config) in &self.arrays {
+            if config.enabled == Some(false) {
+                continue;
+            }
+            let min_interval = config
+                .interval_ms
+                .map(Duration::from_millis)
+                .unwrap_or(Duration::ZERO);
+            manager
+                .register_with_interval(name, config.build(name, buses)?, min_interval)
+                .map_err(|err| anyhow::anyhow!("注册传感器阵列{}失败: {}", name, err))?;
+        }
+        // OK
+        Ok(manager)
+    }
+
+    /// 按配置创建所有温室（温室的传感器与管理器中的传感器共享已打开的I2C总线）
+    pub fn build_greenhouses(&self, buses: &mut I2cBuses) -> anyhow::Result<Vec<Greenhouse>> {
+        self.greenhouses
+            .iter()
+            .map(|(name, config)| config.build(name, buses))
+            .collect()
+    }
+
+    /// 按配置创建模拟传感器并注册到管理器（不访问任何硬件）
+    ///
+    /// 引脚、总线等硬件字段被忽略，读取间隔配置仍然有效
+    #[cfg(feature = "sim")]
+    pub fn build_sim(&self, backend: &mut SimBackend) -> anyhow::Result<SensorManager> {
+        let manager = SensorManager::new();
+        for (name, config) in &self.sensors {
+            if !config.is_enabled() {
+                continue;
+            }
+            let kind = config.kind.as_deref().unwrap_or(name);
+            let sensor = backend
+                .sensor(kind)
+                .map_err(|err| anyhow::anyhow!("传感器{}: {}", name, err))?;
+            manager
+                .register_with_interval(name, sensor, config.min_interval())
+                .map_err(|err| anyhow::anyhow!("注册传感器{}失败: {}", name, err))?;
+        }
+        // OK
+        Ok(manager)
+    }
+}
+
+/// 读取配置文件并创建传感器管理器
+pub fn load_manager<P: AsRef<Path>>(path: P) -> anyhow::Result<SensorManager> {
+    HardwareConfig::load(path)?.build()
+}
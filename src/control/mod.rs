@@ -0,0 +1,4 @@
+pub mod pid;
+pub mod thermostat;
+
+pub use pid::Pid;
@@ -0,0 +1,224 @@
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::manager::Sensor;
+use crate::reading::Quantity;
+use crate::switch::PwmSwitch;
+
+/// PID控制器
+///
+/// - 输出限制在`[min, max]`范围内（默认0.0~1.0，即PWM占空比）
+/// - 抗积分饱和：输出饱和且误差方向会继续加深饱和时停止积分
+/// - 微分项基于测量值计算（设定值突变时不产生冲击），并经过一阶低通滤波
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pid {
+    /// 比例系数
+    pub kp: f64,
+    /// 积分系数
+    pub ki: f64,
+    /// 微分系数
+    pub kd: f64,
+    /// 设定值
+    pub setpoint: f64,
+    /// 输出下限
+    min: f64,
+    /// 输出上限
+    max: f64,
+    /// 反向控制（测量值高于设定值时增大输出，如风扇降温）
+    reverse: bool,
+    /// 微分滤波时间常数（秒，0为不滤波）
+    derivative_filter: f64,
+    /// 积分累计值（已乘以ki）
+    integral: f64,
+    /// 滤波后的微分值
+    derivative: f64,
+    /// 上一次的测量值
+    last_measurement: Option<f64>,
+}
+
+impl Pid {
+    /// 创建控制器（输出范围0.0~1.0，无微分滤波）
+    pub fn new(kp: f64, ki: f64, kd: f64, setpoint: f64) -> Self {
+        Self {
+            kp,
+            ki,
+            kd,
+            setpoint,
+            min: 0.0,
+            max: 1.0,
+            reverse: false,
+            derivative_filter: 0.0,
+            integral: 0.0,
+            derivative: 0.0,
+            last_measurement: None,
+        }
+    }
+
+    /// 设置输出范围
+    pub fn with_output_limits(mut self, min: f64, max: f64) -> Self {
+        self.min = min.min(max);
+        self.max = max.max(min);
+        self.integral = self.integral.clamp(self.min, self.max);
+        self
+    }
+
+    /// 反向控制（测量值高于设定值时增大输出）
+    pub fn reversed(mut self) -> Self {
+        self.reverse = true;
+        self
+    }
+
+    /// 设置微分滤波时间常数
+    pub fn with_derivative_filter(mut self, tau: Duration) -> Self {
+        self.derivative_filter = tau.as_secs_f64();
+        self
+    }
+
+    /// 清除积分和微分状态
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+        self.derivative = 0.0;
+        self.last_measurement = None;
+    }
+
+    /// 根据测量值和距上次计算的时间间隔计算输出
+    pub fn update(&mut self, measurement: f64, dt: Duration) -> f64 {
+        let dt = dt.as_secs_f64();
+        let sign = if self.reverse { -1.0 } else { 1.0 };
+        let error = sign * (self.setpoint - measurement);
+
+        // 微分（基于测量值，一阶低通滤波）
+        if let Some(last) = self.last_measurement
+            && dt > 0.0
+        {
+            let raw = -sign * (measurement - last) / dt;
+            let alpha = dt / (self.derivative_filter + dt);
+            self.derivative += alpha * (raw - self.derivative);
+        }
+        self.last_measurement = Some(measurement);
+
+        let proportional = self.kp * error;
+        let derivative = self.kd * self.derivative;
+        let unclamped = proportional + self.integral + self.ki * error * dt + derivative;
+
+        // 抗积分饱和：饱和方向与误差方向一致时不积分
+        let saturated_high = unclamped > self.max && error > 0.0;
+        let saturated_low = unclamped < self.min && error < 0.0;
+        if !saturated_high && !saturated_low {
+            self.integral = (self.integral + self.ki * error * dt).clamp(self.min, self.max);
+        }
+
+        (proportional + self.integral + derivative).clamp(self.min, self.max)
+    }
+}
+
+/// PID控制状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PidStatus {
+    /// 测量值（读取失败时为None）
+    pub measurement: Option<f64>,
+    /// 输出（0.0~1.0）
+    pub output: f64,
+}
+
+/// PID闭环：读取传感器的指定物理量，驱动PWM输出
+///
+/// 如根据温度控制风扇转速，或根据热电偶温度控制加热片功率
+pub struct PidLoop {
+    /// 输入传感器
+    sensor: Box<dyn Sensor>,
+    /// 控制的物理量
+    quantity: Quantity,
+    /// 控制器
+    pid: Pid,
+    /// 输出
+    output: PwmSwitch,
+    /// 传感器读取失败时的输出
+    failsafe: f64,
+    /// 上一次计算的时间
+    last_update: Option<Instant>,
+}
+
+impl PidLoop {
+    /// 创建闭环（传感器读取失败时输出0）
+    pub fn new<S>(sensor: S, quantity: Quantity, pid: Pid, output: PwmSwitch) -> Self
+    where
+        S: Sensor + 'static,
+    {
+        Self {
+            sensor: Box::new(sensor),
+            quantity,
+            pid,
+            output,
+            failsafe: 0.0,
+            last_update: None,
+        }
+    }
+
+    /// 设置传感器读取失败时的输出（0.0~1.0）
+    pub fn with_failsafe(mut self, output: f64) -> Self {
+        self.failsafe = output.clamp(0.0, 1.0);
+        self
+    }
+
+    /// 控制器
+    pub fn pid(&mut self) -> &mut Pid {
+        &mut self.pid
+    }
+
+    /// 读取一次传感器并更新输出
+    pub fn step(&mut self) -> anyhow::Result<PidStatus> {
+        let measurement = match self.sensor.read() {
+            Ok(reading) => reading.get(self.quantity),
+            Err(_) => None,
+        };
+        let Some(measurement) = measurement else {
+            // 读取失败后重新开始计时，避免恢复时微分项突变
+            self.last_update = None;
+            self.pid.reset();
+            self.output.set_level(self.failsafe)?;
+            return Ok(PidStatus {
+                measurement: None,
+                output: self.failsafe,
+            });
+        };
+
+        let now = Instant::now();
+        let dt = self
+            .last_update
+            .map(|last| now.duration_since(last))
+            .unwrap_or_default();
+        self.last_update = Some(now);
+        let output = self.pid.update(measurement, dt);
+        self.output.set_level(output)?;
+        // OK
+        Ok(PidStatus {
+            measurement: Some(measurement),
+            output: self.output.level(),
+        })
+    }
+
+    /// 在后台线程中按固定间隔运行（间隔不小于传感器的最小读取间隔）
+    ///
+    /// 每次更新后回调状态，PWM输出失败时线程退出并将输出置0
+    pub fn run<F>(mut self, interval: Duration, mut cb: F) -> JoinHandle<()>
+    where
+        F: FnMut(&PidStatus) + Send + 'static,
+    {
+        let interval = interval.max(self.sensor.min_interval());
+        thread::spawn(move || {
+            loop {
+                match self.step() {
+                    Ok(status) => cb(&status),
+                    Err(err) => {
+                        eprintln!("PID输出失败: {}", err);
+                        let _ = self.output.set_level(0.0);
+                        break;
+                    }
+                }
+                thread::sleep(interval);
+            }
+        })
+    }
+}
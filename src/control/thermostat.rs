@@ -0,0 +1,217 @@
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::manager::Sensor;
+use crate::reading::Quantity;
+use crate::switch::Switch;
+
+/// 工作模式
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mode {
+    /// 加热（低于设定值时打开）
+    Heat,
+    /// 制冷（高于设定值时打开）
+    Cool,
+}
+
+/// 传感器故障时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Failsafe {
+    /// 立即关闭（默认，防止加热失控）
+    Off,
+    /// 立即打开（如冷库制冷）
+    On,
+    /// 保持当前状态
+    Hold,
+}
+
+/// 温控器参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThermostatConfig {
+    /// 工作模式
+    pub mode: Mode,
+    /// 设定温度（℃）
+    pub setpoint: f64,
+    /// 回差（℃），温度在设定值±回差/2范围内时保持当前状态
+    pub deadband: f64,
+    /// 打开后的最短运行时间（保护压缩机、继电器）
+    pub min_on: Duration,
+    /// 关闭后的最短停止时间
+    pub min_off: Duration,
+    /// 连续读取失败多少次后进入故障保护
+    pub max_failures: u32,
+    /// 传感器故障时的处理方式
+    pub failsafe: Failsafe,
+}
+
+impl Default for ThermostatConfig {
+    fn default() -> Self {
+        Self {
+            mode: Mode::Heat,
+            setpoint: 20.0,
+            deadband: 1.0,
+            min_on: Duration::from_secs(60),
+            min_off: Duration::from_secs(60),
+            max_failures: 3,
+            failsafe: Failsafe::Off,
+        }
+    }
+}
+
+/// 温控器状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThermostatStatus {
+    /// 最近一次读取的温度（读取失败时为None）
+    pub temperature: Option<f64>,
+    /// 执行器是否打开
+    pub on: bool,
+    /// 是否处于故障保护中
+    pub failsafe: bool,
+}
+
+/// 温控器（带回差的开关控制）
+///
+/// 将温度传感器和开关型执行器（继电器、加热片、风扇）组合为温控器：
+///
+/// - 加热模式：温度 <= 设定值 - 回差/2 时打开，温度 >= 设定值 + 回差/2 时关闭
+/// - 制冷模式相反
+/// - 打开/关闭后必须保持最短运行/停止时间才能再次切换
+/// - 传感器连续读取失败时按`Failsafe`处理，恢复后自动退出故障保护
+pub struct Thermostat<W: Switch> {
+    /// 温度传感器
+    sensor: Box<dyn Sensor>,
+    /// 执行器
+    switch: W,
+    /// 参数
+    config: ThermostatConfig,
+    /// 上一次切换的时间
+    last_change: Option<Instant>,
+    /// 连续读取失败次数
+    failures: u32,
+}
+
+impl<W: Switch> Thermostat<W> {
+    /// 创建温控器（执行器初始为关闭状态）
+    pub fn new<S>(sensor: S, mut switch: W, config: ThermostatConfig) -> anyhow::Result<Self>
+    where
+        S: Sensor + 'static,
+    {
+        switch.off()?;
+        // OK
+        Ok(Self {
+            sensor: Box::new(sensor),
+            switch,
+            config,
+            last_change: None,
+            failures: 0,
+        })
+    }
+
+    /// 参数
+    pub fn config(&self) -> &ThermostatConfig {
+        &self.config
+    }
+
+    /// 修改设定温度
+    pub fn set_setpoint(&mut self, setpoint: f64) {
+        self.config.setpoint = setpoint;
+    }
+
+    /// 执行器
+    pub fn switch(&mut self) -> &mut W {
+        &mut self.switch
+    }
+
+    /// 切换执行器
+    fn switch_to(&mut self, on: bool) -> anyhow::Result<()> {
+        if self.switch.is_on() != on {
+            self.switch.set(on)?;
+            self.last_change = Some(Instant::now());
+        }
+        Ok(())
+    }
+
+    /// 根据温度更新执行器状态（None表示本次读取失败）
+    pub fn update(&mut self, temperature: Option<f64>) -> anyhow::Result<ThermostatStatus> {
+        let Some(temperature) = temperature else {
+            self.failures += 1;
+            let failsafe = self.failures >= self.config.max_failures;
+            if failsafe {
+                // 故障保护忽略最短运行/停止时间
+                match self.config.failsafe {
+                    Failsafe::Off => self.switch_to(false)?,
+                    Failsafe::On => self.switch_to(true)?,
+                    Failsafe::Hold => {}
+                }
+            }
+            return Ok(ThermostatStatus {
+                temperature: None,
+                on: self.switch.is_on(),
+                failsafe,
+            });
+        };
+        self.failures = 0;
+
+        let on = self.switch.is_on();
+        let half = self.config.deadband.abs() / 2.0;
+        let (low, high) = (self.config.setpoint - half, self.config.setpoint + half);
+        let want = match self.config.mode {
+            Mode::Heat if temperature <= low => true,
+            Mode::Heat if temperature >= high => false,
+            Mode::Cool if temperature >= high => true,
+            Mode::Cool if temperature <= low => false,
+            _ => on,
+        };
+        let min_hold = if on {
+            self.config.min_on
+        } else {
+            self.config.min_off
+        };
+        let can_change = self.last_change.is_none_or(|at| at.elapsed() >= min_hold);
+        if want != on && can_change {
+            self.switch_to(want)?;
+        }
+        // OK
+        Ok(ThermostatStatus {
+            temperature: Some(temperature),
+            on: self.switch.is_on(),
+            failsafe: false,
+        })
+    }
+
+    /// 读取一次温度并更新执行器状态
+    pub fn step(&mut self) -> anyhow::Result<ThermostatStatus> {
+        let temperature = match self.sensor.read() {
+            Ok(reading) => reading.get(Quantity::Temperature),
+            Err(_) => None,
+        };
+        self.update(temperature)
+    }
+
+    /// 在后台线程中按固定间隔运行（间隔不小于传感器的最小读取间隔）
+    ///
+    /// 每次更新后回调状态，执行器切换失败时线程退出并关闭执行器
+    pub fn run<F>(mut self, interval: Duration, mut cb: F) -> JoinHandle<()>
+    where
+        W: 'static,
+        F: FnMut(&ThermostatStatus) + Send + 'static,
+    {
+        let interval = interval.max(self.sensor.min_interval());
+        thread::spawn(move || {
+            loop {
+                match self.step() {
+                    Ok(status) => cb(&status),
+                    Err(err) => {
+                        eprintln!("温控器执行器切换失败: {}", err);
+                        let _ = self.switch.off();
+                        break;
+                    }
+                }
+                thread::sleep(interval);
+            }
+        })
+    }
+}
@@ -0,0 +1,110 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+
+use super::{EnvReading, Error};
+
+/// 默认设备地址
+pub const DEFAULT_ADDRESS: u8 = 0x38;
+
+/// 触发测量命令
+pub const CMD_TRIGGER: [u8; 3] = [0xAC, 0x33, 0x00];
+
+/// 单次转换时间（毫秒）
+pub const MEASURE_MS: u32 = 80;
+
+/// CRC8校验（多项式0x31，初始值0xFF）
+pub fn crc8(data: &[u8]) -> u8 {
+    let mut crc: u8 = 0xFF;
+    for &byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x31
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// 状态字节：是否正在转换
+pub fn is_busy(status: u8) -> bool {
+    status & 0x80 != 0
+}
+
+/// 解析测量结果（状态 + 湿度、温度各20位 + CRC），返回温度（℃）和湿度（%）
+pub fn parse(data: &[u8; 7]) -> Result<(f64, f64), Error<()>> {
+    if crc8(&data[..6]) != data[6] {
+        return Err(Error::Checksum);
+    }
+    let raw_humidity = ((data[1] as u32) << 12) | ((data[2] as u32) << 4) | ((data[3] as u32) >> 4);
+    let raw_temperature =
+        (((data[3] as u32) & 0x0F) << 16) | ((data[4] as u32) << 8) | data[5] as u32;
+    let humidity = raw_humidity as f64 / (1 << 20) as f64 * 100.0;
+    let temperature = raw_temperature as f64 / (1 << 20) as f64 * 200.0 - 50.0;
+    // OK
+    Ok((temperature, humidity))
+}
+
+/// AHT30温湿度传感器驱动（任意embedded-hal I2C总线）
+pub struct Aht30 {
+    /// 设备地址
+    address: u8,
+}
+
+impl Aht30 {
+    /// 创建实例
+    ///
+    /// - address: 设备地址，为None时使用默认地址0x38
+    pub fn new(address: Option<u8>) -> Self {
+        Self {
+            address: address.unwrap_or(DEFAULT_ADDRESS),
+        }
+    }
+
+    /// 设备地址
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// 触发一次测量并等待结果
+    ///
+    /// 最多等待5个转换周期
+    pub fn read<I, D>(&mut self, i2c: &mut I, delay: &mut D) -> Result<EnvReading, Error<I::Error>>
+    where
+        I: I2c,
+        D: DelayNs,
+    {
+        let mut reading = EnvReading::default();
+        self.read_into(i2c, delay, &mut reading)?;
+        Ok(reading)
+    }
+
+    /// 触发一次测量，结果写入已有的读数（不修改时间戳）
+    pub fn read_into<I, D>(
+        &mut self,
+        i2c: &mut I,
+        delay: &mut D,
+        reading: &mut EnvReading,
+    ) -> Result<(), Error<I::Error>>
+    where
+        I: I2c,
+        D: DelayNs,
+    {
+        i2c.write(self.address, &CMD_TRIGGER).map_err(Error::Bus)?;
+        let mut data = [0u8; 7];
+        for _ in 0..5 {
+            delay.delay_ms(MEASURE_MS);
+            i2c.read(self.address, &mut data).map_err(Error::Bus)?;
+            if !is_busy(data[0]) {
+                let (temperature, humidity) = parse(&data).map_err(|_| Error::Checksum)?;
+                reading.temperature = temperature;
+                reading.pressure = None;
+                reading.humidity = Some(humidity);
+                return Ok(());
+            }
+        }
+        Err(Error::Timeout)
+    }
+}
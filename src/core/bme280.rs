@@ -0,0 +1,504 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+
+use super::{EnvReading, Error};
+
+/// 默认设备地址（SDO接地，接VCC时为0x77）
+pub const DEFAULT_ADDRESS: u8 = 0x76;
+
+/// 芯片ID
+pub const CHIP_ID: u8 = 0x60;
+
+/// 校准参数和芯片ID的连续读取长度（0x88~0xE7）
+const CALIB_BURST: usize = (reg::CALIB_H - reg::CALIB_TP) as usize + 7;
+
+/// 状态和测量数据的连续读取长度（0xF3~0xFE）
+const DATA_BURST: usize = (reg::DATA - reg::STATUS) as usize + 8;
+
+/// 转换时间到达后状态仍为忙碌时的最多重试次数
+const STATUS_RETRIES: u32 = 5;
+
+/// 寄存器地址
+pub mod reg {
+    /// 温度、气压校准参数（0x88~0xA1，共26字节）
+    pub const CALIB_TP: u8 = 0x88;
+    /// 芯片ID
+    pub const ID: u8 = 0xD0;
+    /// 软复位
+    pub const RESET: u8 = 0xE0;
+    /// 湿度校准参数（0xE1~0xE7，共7字节）
+    pub const CALIB_H: u8 = 0xE1;
+    /// 湿度过采样
+    pub const CTRL_HUM: u8 = 0xF2;
+    /// 状态
+    pub const STATUS: u8 = 0xF3;
+    /// 温度、气压过采样和工作模式
+    pub const CTRL_MEAS: u8 = 0xF4;
+    /// 测量数据（0xF7~0xFE，共8字节）
+    pub const DATA: u8 = 0xF7;
+}
+
+/// 原始ADC值
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RawData {
+    /// 气压（20位）
+    pub pressure: i32,
+    /// 温度（20位）
+    pub temperature: i32,
+    /// 湿度（16位）
+    pub humidity: i32,
+}
+
+impl RawData {
+    /// 解析测量数据寄存器（0xF7~0xFE）
+    pub fn parse(data: &[u8; 8]) -> Self {
+        Self {
+            pressure: ((data[0] as i32) << 12) | ((data[1] as i32) << 4) | ((data[2] as i32) >> 4),
+            temperature: ((data[3] as i32) << 12)
+                | ((data[4] as i32) << 4)
+                | ((data[5] as i32) >> 4),
+            humidity: ((data[6] as i32) << 8) | data[7] as i32,
+        }
+    }
+}
+
+/// 补偿算法（数据手册8.1、8.2节）
+///
+/// 整数算法的中间结果多次右移截断，温度只有0.01℃的分辨率，气压误差约1Pa；
+/// 浮点算法没有截断，分辨率更高。树莓派有FPU，浮点计算只多几微秒，
+/// 需要与单片机上的整数算法结果逐位一致时选择整数算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compensation {
+    /// 双精度浮点算法
+    #[default]
+    Float,
+    /// 32/64位整数算法
+    Integer,
+}
+
+/// 出厂校准参数（字段与数据手册中的dig_T1~dig_H6对应）
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Calibration {
+    pub t1: u16,
+    pub t2: i16,
+    pub t3: i16,
+    pub p1: u16,
+    pub p2: i16,
+    pub p3: i16,
+    pub p4: i16,
+    pub p5: i16,
+    pub p6: i16,
+    pub p7: i16,
+    pub p8: i16,
+    pub p9: i16,
+    pub h1: u8,
+    pub h2: i16,
+    pub h3: u8,
+    pub h4: i16,
+    pub h5: i16,
+    pub h6: i8,
+}
+
+impl Calibration {
+    /// 解析校准参数寄存器
+    ///
+    /// - tp: 0x88~0xA1的26字节
+    /// - h: 0xE1~0xE7的7字节
+    pub fn parse(tp: &[u8; 26], h: &[u8; 7]) -> Self {
+        let u16_at = |i: usize| u16::from_le_bytes([tp[i], tp[i + 1]]);
+        let i16_at = |i: usize| i16::from_le_bytes([tp[i], tp[i + 1]]);
+        Self {
+            t1: u16_at(0),
+            t2: i16_at(2),
+            t3: i16_at(4),
+            p1: u16_at(6),
+            p2: i16_at(8),
+            p3: i16_at(10),
+            p4: i16_at(12),
+            p5: i16_at(14),
+            p6: i16_at(16),
+            p7: i16_at(18),
+            p8: i16_at(20),
+            p9: i16_at(22),
+            h1: tp[25],
+            h2: i16::from_le_bytes([h[0], h[1]]),
+            h3: h[2],
+            // H4、H5为12位有符号数，共用0xE5的高低4位
+            h4: ((h[3] as i8 as i16) << 4) | (h[4] & 0x0F) as i16,
+            h5: ((h[5] as i8 as i16) << 4) | (h[4] >> 4) as i16,
+            h6: h[6] as i8,
+        }
+    }
+
+    /// 温度补偿，返回温度（℃）和供气压、湿度补偿使用的t_fine
+    pub fn compensate_temperature(&self, adc_t: i32) -> (f64, f64) {
+        let adc_t = adc_t as f64;
+        let var1 = (adc_t / 16384.0 - self.t1 as f64 / 1024.0) * self.t2 as f64;
+        let delta = adc_t / 131072.0 - self.t1 as f64 / 8192.0;
+        let var2 = delta * delta * self.t3 as f64;
+        let t_fine = var1 + var2;
+        (t_fine / 5120.0, t_fine)
+    }
+
+    /// 气压补偿（Pa）
+    pub fn compensate_pressure(&self, adc_p: i32, t_fine: f64) -> f64 {
+        let mut var1 = t_fine / 2.0 - 64000.0;
+        let mut var2 = var1 * var1 * self.p6 as f64 / 32768.0;
+        var2 += var1 * self.p5 as f64 * 2.0;
+        var2 = var2 / 4.0 + self.p4 as f64 * 65536.0;
+        var1 = (self.p3 as f64 * var1 * var1 / 524288.0 + self.p2 as f64 * var1) / 524288.0;
+        var1 = (1.0 + var1 / 32768.0) * self.p1 as f64;
+        if var1 == 0.0 {
+            // 避免除0（校准参数无效）
+            return 0.0;
+        }
+        let mut p = 1048576.0 - adc_p as f64;
+        p = (p - var2 / 4096.0) * 6250.0 / var1;
+        let var1 = self.p9 as f64 * p * p / 2147483648.0;
+        let var2 = p * self.p8 as f64 / 32768.0;
+        p + (var1 + var2 + self.p7 as f64) / 16.0
+    }
+
+    /// 湿度补偿（%）
+    pub fn compensate_humidity(&self, adc_h: i32, t_fine: f64) -> f64 {
+        let mut h = t_fine - 76800.0;
+        h = (adc_h as f64 - (self.h4 as f64 * 64.0 + self.h5 as f64 / 16384.0 * h))
+            * (self.h2 as f64 / 65536.0
+                * (1.0
+                    + self.h6 as f64 / 67108864.0 * h * (1.0 + self.h3 as f64 / 67108864.0 * h)));
+        h *= 1.0 - self.h1 as f64 * h / 524288.0;
+        h.clamp(0.0, 100.0)
+    }
+
+    /// 温度补偿（数据手册整数算法），返回温度（0.01℃）和t_fine
+    pub fn compensate_temperature_int(&self, adc_t: i32) -> (i32, i32) {
+        let t1 = self.t1 as i32;
+        let var1 = (((adc_t >> 3) - (t1 << 1)) * self.t2 as i32) >> 11;
+        let delta = (adc_t >> 4) - t1;
+        let var2 = (((delta * delta) >> 12) * self.t3 as i32) >> 14;
+        let t_fine = var1 + var2;
+        ((t_fine * 5 + 128) >> 8, t_fine)
+    }
+
+    /// 气压补偿（数据手册64位整数算法），返回Q24.8格式的气压（Pa * 256）
+    pub fn compensate_pressure_int(&self, adc_p: i32, t_fine: i32) -> u32 {
+        let mut var1 = t_fine as i64 - 128000;
+        let mut var2 = var1 * var1 * self.p6 as i64;
+        var2 += (var1 * self.p5 as i64) << 17;
+        var2 += (self.p4 as i64) << 35;
+        var1 = ((var1 * var1 * self.p3 as i64) >> 8) + ((var1 * self.p2 as i64) << 12);
+        var1 = (((1i64 << 47) + var1) * self.p1 as i64) >> 33;
+        if var1 == 0 {
+            // 避免除0（校准参数无效）
+            return 0;
+        }
+        let mut p = 1048576 - adc_p as i64;
+        p = (((p << 31) - var2) * 3125) / var1;
+        let var1 = (self.p9 as i64 * (p >> 13) * (p >> 13)) >> 25;
+        let var2 = (self.p8 as i64 * p) >> 19;
+        (((p + var1 + var2) >> 8) + ((self.p7 as i64) << 4)) as u32
+    }
+
+    /// 湿度补偿（数据手册整数算法），返回Q22.10格式的湿度（% * 1024）
+    ///
+    /// 中间结果使用64位计算，数据手册范围内的输入与32位算法结果一致，超出范围时不会溢出
+    pub fn compensate_humidity_int(&self, adc_h: i32, t_fine: i32) -> u32 {
+        let x = t_fine as i64 - 76800;
+        let adc_h = adc_h as i64;
+        let mut h = ((((adc_h << 14) - ((self.h4 as i64) << 20) - (self.h5 as i64 * x)) + 16384)
+            >> 15)
+            * (((((((x * self.h6 as i64) >> 10) * (((x * self.h3 as i64) >> 11) + 32768)) >> 10)
+                + 2097152)
+                * self.h2 as i64
+                + 8192)
+                >> 14);
+        h -= ((((h >> 15) * (h >> 15)) >> 7) * self.h1 as i64) >> 4;
+        (h.clamp(0, 419430400) >> 12) as u32
+    }
+
+    /// 按指定算法补偿温度，返回温度（℃）和t_fine
+    pub fn compensate_temperature_with(
+        &self,
+        adc_t: i32,
+        compensation: Compensation,
+    ) -> (f64, f64) {
+        match compensation {
+            Compensation::Float => self.compensate_temperature(adc_t),
+            Compensation::Integer => {
+                let (temperature, t_fine) = self.compensate_temperature_int(adc_t);
+                (temperature as f64 / 100.0, t_fine as f64)
+            }
+        }
+    }
+
+    /// 按指定算法补偿气压（Pa）
+    pub fn compensate_pressure_with(
+        &self,
+        adc_p: i32,
+        t_fine: f64,
+        compensation: Compensation,
+    ) -> f64 {
+        match compensation {
+            Compensation::Float => self.compensate_pressure(adc_p, t_fine),
+            Compensation::Integer => {
+                self.compensate_pressure_int(adc_p, t_fine as i32) as f64 / 256.0
+            }
+        }
+    }
+
+    /// 按指定算法补偿湿度（%）
+    pub fn compensate_humidity_with(
+        &self,
+        adc_h: i32,
+        t_fine: f64,
+        compensation: Compensation,
+    ) -> f64 {
+        match compensation {
+            Compensation::Float => self.compensate_humidity(adc_h, t_fine),
+            Compensation::Integer => {
+                self.compensate_humidity_int(adc_h, t_fine as i32) as f64 / 1024.0
+            }
+        }
+    }
+
+    /// 补偿计算（数据手册浮点算法），计算所有通道
+    pub fn compensate(&self, raw: &RawData) -> EnvReading {
+        let mut reading = EnvReading::default();
+        self.compensate_into(raw, Channels::ALL, &mut reading);
+        reading
+    }
+
+    /// 按启用的通道补偿计算，结果写入已有的读数（不修改时间戳）
+    pub fn compensate_into(&self, raw: &RawData, channels: Channels, reading: &mut EnvReading) {
+        let (temperature, t_fine) = self.compensate_temperature(raw.temperature);
+        reading.temperature = temperature;
+        reading.pressure = channels
+            .pressure
+            .then(|| self.compensate_pressure(raw.pressure, t_fine));
+        reading.humidity = channels
+            .humidity
+            .then(|| self.compensate_humidity(raw.humidity, t_fine));
+    }
+}
+
+/// 测量通道（温度总是测量，用于气压和湿度补偿）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Channels {
+    /// 测量气压
+    pub pressure: bool,
+    /// 测量湿度
+    pub humidity: bool,
+}
+
+impl Channels {
+    /// 所有通道
+    pub const ALL: Channels = Channels {
+        pressure: true,
+        humidity: true,
+    };
+
+    /// 只测量温度
+    pub const TEMPERATURE: Channels = Channels {
+        pressure: false,
+        humidity: false,
+    };
+
+    /// CTRL_HUM寄存器的值（湿度过采样x1，跳过时为0）
+    fn ctrl_hum(&self) -> u8 {
+        self.humidity as u8
+    }
+
+    /// CTRL_MEAS寄存器的值（温度过采样x1或跳过，气压过采样x1或跳过，强制模式）
+    fn ctrl_meas(&self, temperature: bool) -> u8 {
+        ((temperature as u8) << 5) | ((self.pressure as u8) << 2) | 0x01
+    }
+
+    /// 过采样x1时的典型转换时间（微秒，数据手册9.1节），最长时间比典型时间多约15%
+    fn measure_time_us(&self, temperature: bool) -> u32 {
+        1000 + temperature as u32 * 2000 + self.pressure as u32 * 2500 + self.humidity as u32 * 2500
+    }
+}
+
+impl Default for Channels {
+    fn default() -> Self {
+        Channels::ALL
+    }
+}
+
+/// BME280温湿度、气压传感器驱动（任意embedded-hal I2C总线，强制模式单次测量）
+pub struct Bme280 {
+    /// 设备地址
+    address: u8,
+    /// 出厂校准参数
+    calibration: Calibration,
+    /// 测量通道
+    channels: Channels,
+    /// 湿度通道设置是否需要写入CTRL_HUM
+    ctrl_hum_dirty: bool,
+    /// 补偿算法
+    compensation: Compensation,
+    /// 两次测量温度之间复用t_fine的次数（0为每次都测量温度）
+    t_fine_reuse: u32,
+    /// 最近一次测量的温度和t_fine
+    t_fine_cache: Option<(f64, f64)>,
+    /// 剩余的复用次数
+    t_fine_left: u32,
+}
+
+impl Bme280 {
+    /// 创建实例（检查芯片ID并读取校准参数）
+    ///
+    /// 芯片ID和两段校准参数位于连续的寄存器中，一次读取完成
+    ///
+    /// - address: 设备地址，为None时使用默认地址0x76
+    pub fn new<I: I2c>(i2c: &mut I, address: Option<u8>) -> Result<Self, Error<I::Error>> {
+        let address = address.unwrap_or(DEFAULT_ADDRESS);
+        let mut burst = [0u8; CALIB_BURST];
+        i2c.write_read(address, &[reg::CALIB_TP], &mut burst)
+            .map_err(Error::Bus)?;
+        let id = burst[(reg::ID - reg::CALIB_TP) as usize];
+        if id != CHIP_ID {
+            return Err(Error::ChipId(id));
+        }
+
+        let mut tp = [0u8; 26];
+        let mut h = [0u8; 7];
+        tp.copy_from_slice(&burst[..26]);
+        h.copy_from_slice(&burst[(reg::CALIB_H - reg::CALIB_TP) as usize..]);
+        // OK
+        Ok(Self {
+            address,
+            calibration: Calibration::parse(&tp, &h),
+            channels: Channels::ALL,
+            ctrl_hum_dirty: true,
+            compensation: Compensation::Float,
+            t_fine_reuse: 0,
+            t_fine_cache: None,
+            t_fine_left: 0,
+        })
+    }
+
+    /// 出厂校准参数
+    pub fn calibration(&self) -> &Calibration {
+        &self.calibration
+    }
+
+    /// 测量通道
+    pub fn channels(&self) -> Channels {
+        self.channels
+    }
+
+    /// 设置测量通道（跳过的通道不转换也不做补偿计算，测量更快）
+    pub fn set_channels(&mut self, channels: Channels) {
+        if channels.humidity != self.channels.humidity {
+            self.ctrl_hum_dirty = true;
+        }
+        self.channels = channels;
+    }
+
+    /// 补偿算法
+    pub fn compensation(&self) -> Compensation {
+        self.compensation
+    }
+
+    /// 设置补偿算法（默认为浮点算法）
+    pub fn set_compensation(&mut self, compensation: Compensation) {
+        if compensation != self.compensation {
+            // 两种算法的t_fine不完全相同，切换后重新测量温度
+            self.t_fine_cache = None;
+        }
+        self.compensation = compensation;
+    }
+
+    /// 设置两次测量温度之间复用t_fine的次数（0为每次都测量温度）
+    ///
+    /// 温度变化缓慢时，高频采样气压（如气压高度计）可以跳过温度转换，用最近一次的t_fine
+    /// 补偿气压和湿度，转换时间更短；复用期间读数中的温度为最近一次测量的温度
+    pub fn set_t_fine_reuse(&mut self, reuse: u32) {
+        self.t_fine_reuse = reuse;
+        self.t_fine_left = self.t_fine_left.min(reuse);
+    }
+
+    /// 触发一次测量并等待结果
+    pub fn read<I, D>(&mut self, i2c: &mut I, delay: &mut D) -> Result<EnvReading, Error<I::Error>>
+    where
+        I: I2c,
+        D: DelayNs,
+    {
+        let mut reading = EnvReading::default();
+        self.read_into(i2c, delay, &mut reading)?;
+        Ok(reading)
+    }
+
+    /// 触发一次测量，结果写入已有的读数（不修改时间戳，未测量的通道为None）
+    pub fn read_into<I, D>(
+        &mut self,
+        i2c: &mut I,
+        delay: &mut D,
+        reading: &mut EnvReading,
+    ) -> Result<(), Error<I::Error>>
+    where
+        I: I2c,
+        D: DelayNs,
+    {
+        // 湿度过采样在写CTRL_MEAS后生效
+        if self.ctrl_hum_dirty {
+            i2c.write(self.address, &[reg::CTRL_HUM, self.channels.ctrl_hum()])
+                .map_err(Error::Bus)?;
+            self.ctrl_hum_dirty = false;
+        }
+        let cached = self.t_fine_cache.filter(|_| self.t_fine_left > 0);
+        let measure_temperature = cached.is_none();
+        i2c.write(
+            self.address,
+            &[reg::CTRL_MEAS, self.channels.ctrl_meas(measure_temperature)],
+        )
+        .map_err(Error::Bus)?;
+
+        // 等待典型转换时间后连续读取状态和测量数据，通常一次即可读到结果
+        delay.delay_us(self.channels.measure_time_us(measure_temperature));
+        let mut burst = [0u8; DATA_BURST];
+        let mut ready = false;
+        for _ in 0..STATUS_RETRIES {
+            i2c.write_read(self.address, &[reg::STATUS], &mut burst)
+                .map_err(Error::Bus)?;
+            if burst[0] & 0x08 == 0 {
+                ready = true;
+                break;
+            }
+            delay.delay_ms(1);
+        }
+        if !ready {
+            return Err(Error::Timeout);
+        }
+
+        let mut data = [0u8; 8];
+        data.copy_from_slice(&burst[(reg::DATA - reg::STATUS) as usize..]);
+        let raw = RawData::parse(&data);
+        let (temperature, t_fine) = match cached {
+            Some(cached) => {
+                self.t_fine_left -= 1;
+                cached
+            }
+            None => {
+                let compensated = self
+                    .calibration
+                    .compensate_temperature_with(raw.temperature, self.compensation);
+                self.t_fine_cache = Some(compensated);
+                self.t_fine_left = self.t_fine_reuse;
+                compensated
+            }
+        };
+        reading.temperature = temperature;
+        reading.pressure = self.channels.pressure.then(|| {
+            self.calibration
+                .compensate_pressure_with(raw.pressure, t_fine, self.compensation)
+        });
+        reading.humidity = self.channels.humidity.then(|| {
+            self.calibration
+                .compensate_humidity_with(raw.humidity, t_fine, self.compensation)
+        });
+        // OK
+        Ok(())
+    }
+}
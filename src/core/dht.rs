@@ -0,0 +1,63 @@
+use super::Error;
+
+/// 每帧的数据位数（湿度2字节、温度2字节、校验和1字节）
+pub const FRAME_BITS: usize = 40;
+
+/// 高电平超过该宽度（微秒）时为1（0约27us，1约70us）
+pub const ONE_THRESHOLD_US: u32 = 48;
+
+/// 传感器型号
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DhtModel {
+    /// DHT11（整数精度，0~50°C）
+    Dht11,
+    /// DHT22/AM2302（0.1精度，-40~80°C）
+    Dht22,
+}
+
+impl DhtModel {
+    /// 起始信号的低电平时长（微秒）
+    pub fn start_signal_us(self) -> u32 {
+        match self {
+            DhtModel::Dht11 => 18_000,
+            DhtModel::Dht22 => 1_100,
+        }
+    }
+}
+
+/// 按40个数据位的高电平宽度（微秒）组装一帧数据，高位在前
+pub fn frame_from_pulses(widths_us: &[u32; FRAME_BITS]) -> [u8; 5] {
+    let mut frame = [0u8; 5];
+    for (i, width) in widths_us.iter().enumerate() {
+        if *width > ONE_THRESHOLD_US {
+            frame[i / 8] |= 0x80 >> (i % 8);
+        }
+    }
+    frame
+}
+
+/// 解析一帧数据，返回温度（℃）和湿度（%）
+pub fn parse_frame(frame: &[u8; 5], model: DhtModel) -> Result<(f64, f64), Error<()>> {
+    let sum = frame[..4]
+        .iter()
+        .fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    if sum != frame[4] {
+        return Err(Error::Checksum);
+    }
+    let (temperature, humidity) = match model {
+        DhtModel::Dht11 => {
+            let humidity = frame[0] as f64 + frame[1] as f64 * 0.1;
+            let temperature = frame[2] as f64 + (frame[3] & 0x7F) as f64 * 0.1;
+            let sign = if frame[3] & 0x80 != 0 { -1.0 } else { 1.0 };
+            (sign * temperature, humidity)
+        }
+        DhtModel::Dht22 => {
+            let humidity = u16::from_be_bytes([frame[0], frame[1]]) as f64 / 10.0;
+            let temperature = u16::from_be_bytes([frame[2] & 0x7F, frame[3]]) as f64 / 10.0;
+            let sign = if frame[2] & 0x80 != 0 { -1.0 } else { 1.0 };
+            (sign * temperature, humidity)
+        }
+    };
+    // OK
+    Ok((temperature, humidity))
+}
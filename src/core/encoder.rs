@@ -0,0 +1,42 @@
+//! 旋转编码器（正交信号）解码
+
+/// 状态转换表（下标为上一状态<<2 | 当前状态，状态为CLK<<1 | DT），非法跳变（同时变化两位）计为0
+const TRANSITIONS: [i8; 16] = [0, -1, 1, 0, 1, 0, 0, -1, -1, 0, 0, 1, 0, 1, -1, 0];
+
+/// 正交信号解码器
+///
+/// 每次任一引脚电平变化时调用`update`，累计的跳变数达到一个定位格时输出步数，
+/// 机械抖动产生的来回跳变相互抵消
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuadratureDecoder {
+    /// 上一状态（CLK<<1 | DT）
+    state: u8,
+    /// 未达到一个定位格的跳变数
+    accum: i8,
+    /// 每个定位格的跳变数（KY-040为4，部分编码器为2）
+    steps_per_detent: i8,
+}
+
+impl QuadratureDecoder {
+    /// 创建解码器
+    ///
+    /// - clk、dt: 当前电平
+    /// - steps_per_detent: 每个定位格的跳变数（1、2或4）
+    pub fn new(clk: bool, dt: bool, steps_per_detent: u8) -> Self {
+        Self {
+            state: (clk as u8) << 1 | dt as u8,
+            accum: 0,
+            steps_per_detent: steps_per_detent.clamp(1, 4) as i8,
+        }
+    }
+
+    /// 更新电平，返回转过的定位格数（顺时针为正，未满一格时为0）
+    pub fn update(&mut self, clk: bool, dt: bool) -> i8 {
+        let state = (clk as u8) << 1 | dt as u8;
+        self.accum += TRANSITIONS[(self.state << 2 | state) as usize];
+        self.state = state;
+        let detents = self.accum / self.steps_per_detent;
+        self.accum %= self.steps_per_detent;
+        detents
+    }
+}
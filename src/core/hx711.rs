@@ -0,0 +1,124 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{InputPin, OutputPin};
+
+use super::Error;
+
+/// 通道和增益（决定每次读取后额外输出的时钟脉冲数）
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChannelGain {
+    /// A通道，增益128
+    ChannelA128,
+    /// B通道，增益32
+    ChannelB32,
+    /// A通道，增益64
+    ChannelA64,
+}
+
+impl ChannelGain {
+    /// 24位数据之后的额外脉冲数
+    pub fn extra_pulses(&self) -> usize {
+        match self {
+            ChannelGain::ChannelA128 => 1,
+            ChannelGain::ChannelB32 => 2,
+            ChannelGain::ChannelA64 => 3,
+        }
+    }
+}
+
+/// 24位补码扩展为32位
+#[inline(always)]
+pub fn sign_extend(value: i32) -> i32 {
+    (value << 8) >> 8
+}
+
+/// HX711称重ADC驱动（任意embedded-hal引脚和延时实现）
+///
+/// 时钟高电平超过60µs会进入掉电模式，读取期间不应被中断打断
+pub struct Hx711<CLK, DOUT, D> {
+    /// 时钟引脚（PD_SCK）
+    clock: CLK,
+    /// 数据引脚（DOUT）
+    data: DOUT,
+    /// 延时
+    delay: D,
+    /// 通道和增益
+    gain: ChannelGain,
+}
+
+impl<CLK, DOUT, D> Hx711<CLK, DOUT, D>
+where
+    CLK: OutputPin,
+    DOUT: InputPin,
+    D: DelayNs,
+{
+    /// 创建实例（不读取，第一次读取后通道和增益设置才生效）
+    pub fn new(mut clock: CLK, data: DOUT, delay: D, gain: ChannelGain) -> Result<Self, Error<()>> {
+        clock.set_low().map_err(|_| Error::Pin)?;
+        // OK
+        Ok(Self {
+            clock,
+            data,
+            delay,
+            gain,
+        })
+    }
+
+    /// 数据是否就绪（DOUT为低电平）
+    pub fn is_ready(&mut self) -> Result<bool, Error<()>> {
+        self.data.is_low().map_err(|_| Error::Pin)
+    }
+
+    /// 输出一个时钟脉冲并在高电平期间读取数据引脚
+    fn pulse(&mut self) -> Result<bool, Error<()>> {
+        self.clock.set_high().map_err(|_| Error::Pin)?;
+        self.delay.delay_us(1);
+        let bit = self.data.is_high().map_err(|_| Error::Pin)?;
+        self.clock.set_low().map_err(|_| Error::Pin)?;
+        self.delay.delay_us(1);
+        Ok(bit)
+    }
+
+    /// 读取一次ADC读数
+    ///
+    /// - timeout_ms: 等待数据就绪的最长时间（10SPS时不小于200毫秒）
+    pub fn read(&mut self, timeout_ms: u32) -> Result<i32, Error<()>> {
+        let mut waited = 0;
+        while !self.is_ready()? {
+            if waited >= timeout_ms {
+                return Err(Error::Timeout);
+            }
+            self.delay.delay_ms(1);
+            waited += 1;
+        }
+
+        let mut value = 0i32;
+        for _ in 0..24 {
+            value = (value << 1) | self.pulse()? as i32;
+        }
+        // 额外的脉冲用于选择下一次转换的通道和增益
+        for _ in 0..self.gain.extra_pulses() {
+            self.pulse()?;
+        }
+        // OK
+        Ok(sign_extend(value))
+    }
+
+    /// 掉电（时钟保持高电平超过60µs）
+    pub fn power_down(&mut self) -> Result<(), Error<()>> {
+        self.clock.set_low().map_err(|_| Error::Pin)?;
+        self.clock.set_high().map_err(|_| Error::Pin)?;
+        self.delay.delay_us(100);
+        Ok(())
+    }
+
+    /// 上电（上电后默认为A通道增益128，需重新读取一次以设置增益）
+    pub fn power_up(&mut self) -> Result<(), Error<()>> {
+        self.clock.set_low().map_err(|_| Error::Pin)
+    }
+
+    /// 释放引脚和延时
+    pub fn release(self) -> (CLK, DOUT, D) {
+        (self.clock, self.data, self.delay)
+    }
+}
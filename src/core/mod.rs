@@ -0,0 +1,58 @@
+//! 与平台无关的驱动核心
+//!
+//! 只依赖`core`和embedded-hal接口，不使用标准库和rppal，可以直接用于单片机；
+//! 树莓派上的驱动（`sensor`、`adapter`）复用这里的协议解析、补偿计算和步进序列，
+//! 并提供按引脚编号创建实例的便捷构造函数
+
+pub mod aht30;
+pub mod bme280;
+pub mod dht;
+pub mod encoder;
+pub mod hx711;
+pub mod stepper;
+
+use ::core::fmt;
+
+/// 核心驱动错误
+///
+/// - E: 总线错误类型（如I2C实现的错误）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Error<E> {
+    /// 总线通信错误
+    Bus(E),
+    /// 引脚读写错误
+    Pin,
+    /// 数据校验失败
+    Checksum,
+    /// 等待超时
+    Timeout,
+    /// 芯片ID不符
+    ChipId(u8),
+}
+
+impl<E: fmt::Debug> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Bus(err) => write!(f, "总线通信错误: {:?}", err),
+            Error::Pin => write!(f, "引脚读写错误"),
+            Error::Checksum => write!(f, "数据校验失败"),
+            Error::Timeout => write!(f, "等待超时"),
+            Error::ChipId(id) => write!(f, "芯片ID不符: 0x{:02X}", id),
+        }
+    }
+}
+
+impl<E: fmt::Debug> ::core::error::Error for Error<E> {}
+
+/// 温湿度、气压传感器的一次读数（AHT30、BME280共用）
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct EnvReading {
+    /// 温度（℃）
+    pub temperature: f64,
+    /// 气压（Pa），传感器不支持或未测量时为None
+    pub pressure: Option<f64>,
+    /// 相对湿度（%），传感器不支持或未测量时为None
+    pub humidity: Option<f64>,
+    /// 读取时间（Unix毫秒时间戳），核心驱动没有时钟，由调用方填写
+    pub timestamp: Option<u64>,
+}
@@ -0,0 +1,174 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+
+use super::Error;
+
+/// 步进电机转动方向
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    /// 顺时针方向
+    Clockwise,
+    /// 逆时针方向
+    CounterClockwise,
+}
+
+/// 步进模式枚举
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StepMode {
+    WaveDrive, // 单相激励（4步）
+    FullStep,  // 双相激励（4步）
+    HalfStep,  // 单双相交替（8步）
+}
+
+/// 单相激励序列（4步）
+const WAVE_DRIVE: [[bool; 4]; 4] = [
+    [true, false, false, false], // A
+    [false, true, false, false], // B
+    [false, false, true, false], // C
+    [false, false, false, true], // D
+];
+
+/// 双相激励序列（4步）
+const FULL_STEP: [[bool; 4]; 4] = [
+    [true, true, false, false], // AB
+    [false, true, true, false], // BC
+    [false, false, true, true], // CD
+    [true, false, false, true], // DA
+];
+
+/// 单双相交替序列（8步）- 提供更平滑的运动
+const HALF_STEP: [[bool; 4]; 8] = [
+    [true, false, false, false], // A
+    [true, true, false, false],  // AB
+    [false, true, false, false], // B
+    [false, true, true, false],  // BC
+    [false, false, true, false], // C
+    [false, false, true, true],  // CD
+    [false, false, false, true], // D
+    [true, false, false, true],  // DA
+];
+
+impl StepMode {
+    /// 步进序列（每步4相线圈的通电状态）
+    pub fn sequence(&self) -> &'static [[bool; 4]] {
+        match self {
+            StepMode::WaveDrive => &WAVE_DRIVE,
+            StepMode::FullStep => &FULL_STEP,
+            StepMode::HalfStep => &HALF_STEP,
+        }
+    }
+}
+
+/// 步进序列位置
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sequencer {
+    /// 步进模式
+    mode: StepMode,
+    /// 当前步
+    index: usize,
+}
+
+impl Sequencer {
+    /// 创建实例（从序列第一步开始）
+    pub fn new(mode: StepMode) -> Self {
+        Self { mode, index: 0 }
+    }
+
+    /// 步进模式
+    pub fn mode(&self) -> StepMode {
+        self.mode
+    }
+
+    /// 切换步进模式（模式不同时回到序列第一步）
+    pub fn set_mode(&mut self, mode: StepMode) {
+        if mode != self.mode {
+            self.mode = mode;
+            self.index = 0;
+        }
+    }
+
+    /// 当前步
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// 当前步的线圈状态
+    pub fn current(&self) -> [bool; 4] {
+        self.mode.sequence()[self.index]
+    }
+
+    /// 向指定方向前进一步，返回新的线圈状态
+    pub fn step(&mut self, direction: Direction) -> [bool; 4] {
+        let len = self.mode.sequence().len();
+        self.index = match direction {
+            Direction::Clockwise => (self.index + 1) % len,
+            Direction::CounterClockwise => (self.index + len - 1) % len,
+        };
+        self.current()
+    }
+}
+
+/// 4相单极步进电机驱动（如ULN2003A + 28BYJ-48，任意embedded-hal引脚和延时实现）
+pub struct Stepper4<P, D> {
+    /// IN1~IN4引脚
+    pins: [P; 4],
+    /// 延时
+    delay: D,
+    /// 序列位置
+    sequencer: Sequencer,
+}
+
+impl<P: OutputPin, D: DelayNs> Stepper4<P, D> {
+    /// 创建实例
+    pub fn new(pins: [P; 4], delay: D, mode: StepMode) -> Self {
+        Self {
+            pins,
+            delay,
+            sequencer: Sequencer::new(mode),
+        }
+    }
+
+    /// 序列位置
+    pub fn sequencer(&mut self) -> &mut Sequencer {
+        &mut self.sequencer
+    }
+
+    /// 设置线圈状态
+    fn apply(&mut self, pattern: [bool; 4]) -> Result<(), Error<()>> {
+        for (pin, enabled) in self.pins.iter_mut().zip(pattern) {
+            if enabled {
+                pin.set_high().map_err(|_| Error::Pin)?;
+            } else {
+                pin.set_low().map_err(|_| Error::Pin)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 单步运行
+    pub fn step(&mut self, direction: Direction) -> Result<(), Error<()>> {
+        let pattern = self.sequencer.step(direction);
+        self.apply(pattern)
+    }
+
+    /// 运行指定步数（28BYJ-48每步间隔最小3毫秒）
+    pub fn run_steps(
+        &mut self,
+        steps: u32,
+        step_delay_us: u32,
+        direction: Direction,
+    ) -> Result<(), Error<()>> {
+        for _ in 0..steps {
+            self.step(direction)?;
+            self.delay.delay_us(step_delay_us);
+        }
+        Ok(())
+    }
+
+    /// 释放电机（停止所有线圈）
+    pub fn release(&mut self) -> Result<(), Error<()>> {
+        self.apply([false; 4])
+    }
+}
@@ -0,0 +1,297 @@
+//! 终端实时仪表盘
+//!
+//! 调试接线时代替测试程序滚动输出的读数：表格显示每个传感器的最新读数、读取成功率和失败次数，
+//! 下方为选中传感器各物理量的历史曲线，右侧为执行器状态。↑↓选择传感器，q或Esc退出
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::{Duration, SystemTime};
+
+use ratatui::Frame;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Sparkline, Table, TableState};
+
+use crate::actuator::ActuatorRegistry;
+use crate::health::HealthRegistry;
+use crate::reading::{Quantity, Sample};
+
+/// 刷新间隔
+const REFRESH_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 单个传感器的显示数据
+#[derive(Default)]
+struct SensorView {
+    /// 最新读数
+    latest: Option<Sample>,
+    /// 各物理量的历史值
+    history: BTreeMap<Quantity, VecDeque<f64>>,
+}
+
+/// 终端实时仪表盘
+///
+/// ```ignore
+/// let health = manager.health();
+/// let scheduler = Scheduler::new(manager)?;
+/// let samples = scheduler.subscribe();
+/// let _handle = scheduler.start();
+/// Dashboard::new().with_health(health).run(samples)?;
+/// ```
+pub struct Dashboard {
+    /// 读取统计
+    health: Option<HealthRegistry>,
+    /// 执行器
+    actuators: Option<ActuatorRegistry>,
+    /// 每个物理量保留的历史值数量
+    capacity: usize,
+    /// 各传感器的显示数据
+    sensors: BTreeMap<String, SensorView>,
+    /// 选中的传感器
+    selected: usize,
+}
+
+impl Default for Dashboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Dashboard {
+    /// 创建仪表盘（每个物理量保留最近120个值）
+    pub fn new() -> Self {
+        Self {
+            health: None,
+            actuators: None,
+            capacity: 120,
+            sensors: BTreeMap::new(),
+            selected: 0,
+        }
+    }
+
+    /// 显示读取成功率、失败次数和最近的错误
+    pub fn with_health(mut self, health: HealthRegistry) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// 显示执行器状态
+    pub fn with_actuators(mut self, actuators: ActuatorRegistry) -> Self {
+        self.actuators = Some(actuators);
+        self
+    }
+
+    /// 设置每个物理量保留的历史值数量
+    pub fn with_history(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(2);
+        self
+    }
+
+    /// 记录一个读数
+    pub fn record(&mut self, sample: Sample) {
+        let view = self.sensors.entry(sample.sensor.clone()).or_default();
+        for (quantity, value) in sample.reading.iter() {
+            let history = view.history.entry(quantity).or_default();
+            if history.len() == self.capacity {
+                history.pop_front();
+            }
+            history.push_back(value);
+        }
+        view.latest = Some(sample);
+    }
+
+    /// 在当前终端运行，直到按下q或Esc，或者读数来源关闭
+    pub fn run(mut self, samples: Receiver<Sample>) -> anyhow::Result<()> {
+        let mut terminal = ratatui::init();
+        let result = (|| -> anyhow::Result<()> {
+            loop {
+                loop {
+                    match samples.try_recv() {
+                        Ok(sample) => self.record(sample),
+                        Err(TryRecvError::Empty) => break,
+                        Err(TryRecvError::Disconnected) => return Ok(()),
+                    }
+                }
+                terminal.draw(|frame| self.draw(frame))?;
+                if event::poll(REFRESH_INTERVAL)?
+                    && let Event::Key(key) = event::read()?
+                    && key.kind == KeyEventKind::Press
+                {
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                        KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+                        KeyCode::Down => self.selected += 1,
+                        _ => {}
+                    }
+                }
+            }
+        })();
+        ratatui::restore();
+        result
+    }
+
+    /// 所有传感器名称（有读数的和只有读取统计的）
+    fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.sensors.keys().cloned().collect();
+        if let Some(health) = &self.health {
+            for name in health.snapshot().into_keys() {
+                if !self.sensors.contains_key(&name) {
+                    names.push(name);
+                }
+            }
+            names.sort();
+        }
+        names
+    }
+
+    /// 绘制一帧
+    fn draw(&mut self, frame: &mut Frame) {
+        let names = self.names();
+        self.selected = self.selected.min(names.len().saturating_sub(1));
+        let [main, footer] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let columns = if self.actuators.is_some() {
+            vec![Constraint::Min(0), Constraint::Length(28)]
+        } else {
+            vec![Constraint::Min(0)]
+        };
+        let areas = Layout::new(Direction::Horizontal, columns).split(main);
+        let [table, charts] =
+            Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(areas[0]);
+
+        self.draw_table(frame, table, &names);
+        if let Some(name) = names.get(self.selected) {
+            self.draw_history(frame, charts, name);
+        }
+        if let Some(area) = areas.get(1) {
+            self.draw_actuators(frame, *area);
+        }
+        frame.render_widget(
+            Paragraph::new("↑↓ 选择传感器  q 退出").style(Style::default().fg(Color::DarkGray)),
+            footer,
+        );
+    }
+
+    /// 传感器表格
+    fn draw_table(&self, frame: &mut Frame, area: Rect, names: &[String]) {
+        let now = SystemTime::now();
+        let health = self.health.as_ref().map(HealthRegistry::snapshot);
+        let rows = names.iter().map(|name| {
+            let latest = self.sensors.get(name).and_then(|view| view.latest.as_ref());
+            let reading = latest.map_or("-".to_string(), |sample| sample.reading.to_string());
+            let age = latest.map_or("-".to_string(), |sample| {
+                let age = now.duration_since(sample.timestamp).unwrap_or_default();
+                format!("{}s", age.as_secs())
+            });
+            let stats = health.as_ref().and_then(|health| health.get(name));
+            let rate = stats
+                .and_then(|stats| stats.success_rate())
+                .map_or("-".to_string(), |rate| format!("{:.0}%", rate * 100.0));
+            let failures = stats.map_or(0, |stats| stats.failure_count());
+            let consecutive = stats.map_or(0, |stats| stats.consecutive_failures);
+            let error = stats
+                .and_then(|stats| stats.last_error.clone())
+                .unwrap_or_default();
+            let style = if consecutive > 0 {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default()
+            };
+            Row::new(vec![
+                Cell::from(name.clone()),
+                Cell::from(reading),
+                Cell::from(age),
+                Cell::from(rate),
+                Cell::from(format!("{}/{}", failures, consecutive)),
+                Cell::from(error),
+            ])
+            .style(style)
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(14),
+                Constraint::Min(24),
+                Constraint::Length(6),
+                Constraint::Length(6),
+                Constraint::Length(8),
+                Constraint::Min(10),
+            ],
+        )
+        .header(
+            Row::new(["传感器", "读数", "更新", "成功率", "失败", "最近错误"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(Block::default().borders(Borders::ALL).title("传感器"));
+        let mut state = TableState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(table, area, &mut state);
+    }
+
+    /// 选中传感器各物理量的历史曲线
+    fn draw_history(&self, frame: &mut Frame, area: Rect, name: &str) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("{}的历史", name));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        let Some(view) = self
+            .sensors
+            .get(name)
+            .filter(|view| !view.history.is_empty())
+        else {
+            frame.render_widget(Paragraph::new("还没有读数"), inner);
+            return;
+        };
+        let areas = Layout::vertical(vec![
+            Constraint::Ratio(1, view.history.len() as u32);
+            view.history.len()
+        ])
+        .split(inner);
+        for ((quantity, history), area) in view.history.iter().zip(areas.iter()) {
+            let min = history.iter().copied().fold(f64::INFINITY, f64::min);
+            let max = history.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            // 按最小、最大值缩放到0~100
+            let span = (max - min).max(f64::EPSILON);
+            let data: Vec<u64> = history
+                .iter()
+                .map(|value| ((value - min) / span * 100.0).round() as u64 + 1)
+                .collect();
+            let [label, chart] =
+                Layout::horizontal([Constraint::Length(28), Constraint::Min(0)]).areas(*area);
+            frame.render_widget(
+                Paragraph::new(vec![
+                    Line::from(format!("{} ({})", quantity, quantity.unit())),
+                    Line::from(format!("{:.2} ~ {:.2}", min, max)),
+                ]),
+                label,
+            );
+            // 只显示能放下的最近的值
+            let skip = data.len().saturating_sub(chart.width as usize);
+            frame.render_widget(
+                Sparkline::default()
+                    .data(&data[skip..])
+                    .max(101)
+                    .style(Style::default().fg(Color::Cyan)),
+                chart,
+            );
+        }
+    }
+
+    /// 执行器状态
+    fn draw_actuators(&self, frame: &mut Frame, area: Rect) {
+        let states = self
+            .actuators
+            .as_ref()
+            .map(ActuatorRegistry::states)
+            .unwrap_or_default();
+        let rows = states
+            .into_iter()
+            .map(|(name, state)| Row::new(vec![name, state.unwrap_or_else(|| "-".to_string())]));
+        let table = Table::new(rows, [Constraint::Min(12), Constraint::Length(12)])
+            .block(Block::default().borders(Borders::ALL).title("执行器"));
+        frame.render_widget(table, area);
+    }
+}
@@ -0,0 +1,300 @@
+//! 运行时调试信息
+//!
+//! 驱动申请GPIO时调用`claim`登记使用者，中断回调中调用`record_interrupt`统计中断次数。
+//! `dump_pins`返回所有登记引脚的实际模式、电平（通过`pinctrl`或`raspi-gpio`命令读取，
+//! 两种GPIO后端都适用）和中断统计，用于排查"继电器一直吸合"、"按钮没有反应"等问题
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+/// 统计最近中断次数的时间窗口
+const INTERRUPT_WINDOW: Duration = Duration::from_secs(60);
+
+/// 每个引脚保留的中断时间上限
+const MAX_RECENT_INTERRUPTS: usize = 1000;
+
+/// 登记的引脚
+static PINS: Mutex<BTreeMap<u8, Claim>> = Mutex::new(BTreeMap::new());
+
+/// 引脚的申请模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinMode {
+    /// 输入
+    Input,
+    /// 输出
+    Output,
+    /// 运行中切换输入输出（如DHT11的单总线）
+    InputOutput,
+}
+
+impl PinMode {
+    /// 名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            PinMode::Input => "input",
+            PinMode::Output => "output",
+            PinMode::InputOutput => "io",
+        }
+    }
+}
+
+/// 登记信息
+struct Claim {
+    /// 使用者（驱动名称）
+    owner: String,
+    /// 申请模式
+    mode: PinMode,
+    /// 中断总次数
+    interrupts: u64,
+    /// 最近的中断时间
+    recent: VecDeque<Instant>,
+    /// 最后一次中断的时间
+    last_interrupt: Option<SystemTime>,
+}
+
+/// 登记引脚的使用者（同一引脚重复登记时覆盖之前的登记）
+///
+/// - pin: BCM编号
+/// - owner: 使用者（如"relay-board"、"button"）
+/// - mode: 申请模式
+pub fn claim(pin: u8, owner: &str, mode: PinMode) {
+    if let Ok(mut pins) = PINS.lock() {
+        pins.insert(
+            pin,
+            Claim {
+                owner: owner.to_string(),
+                mode,
+                interrupts: 0,
+                recent: VecDeque::new(),
+                last_interrupt: None,
+            },
+        );
+    }
+}
+
+/// 注销引脚
+pub fn release(pin: u8) {
+    if let Ok(mut pins) = PINS.lock() {
+        pins.remove(&pin);
+    }
+}
+
+/// 记录一次中断（在中断回调中调用，开销为一次加锁）
+pub fn record_interrupt(pin: u8) {
+    if let Ok(mut pins) = PINS.lock()
+        && let Some(claim) = pins.get_mut(&pin)
+    {
+        claim.interrupts += 1;
+        claim.last_interrupt = Some(SystemTime::now());
+        if claim.recent.len() >= MAX_RECENT_INTERRUPTS {
+            claim.recent.pop_front();
+        }
+        claim.recent.push_back(Instant::now());
+    }
+}
+
+/// 引脚状态
+#[derive(Debug, Clone, PartialEq)]
+pub struct PinState {
+    /// BCM编号
+    pub pin: u8,
+    /// 使用者（没有登记时为None）
+    pub owner: Option<String>,
+    /// 申请模式
+    pub mode: Option<PinMode>,
+    /// 实际功能（如"output"、"input"、"SDA1"，无法读取时为None）
+    pub function: Option<String>,
+    /// 实际电平
+    pub level: Option<bool>,
+    /// 上下拉（"up"、"down"、"none"）
+    pub pull: Option<String>,
+    /// 中断总次数
+    pub interrupts: u64,
+    /// 最近一分钟的中断次数
+    pub recent_interrupts: usize,
+    /// 最后一次中断的时间
+    pub last_interrupt: Option<SystemTime>,
+}
+
+impl PinState {
+    /// 转换为JSON对象
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> serde_json::Value {
+        let timestamp = |time: &SystemTime| {
+            time.duration_since(std::time::UNIX_EPOCH)
+                .map(|t| t.as_secs_f64())
+                .unwrap_or_default()
+        };
+        serde_json::json!({
+            "pin": self.pin,
+            "owner": self.owner,
+            "mode": self.mode.map(|mode| mode.name()),
+            "function": self.function,
+            "level": self.level.map(u8::from),
+            "pull": self.pull,
+            "interrupts": self.interrupts,
+            "recent_interrupts": self.recent_interrupts,
+            "last_interrupt": self.last_interrupt.as_ref().map(timestamp),
+        })
+    }
+}
+
+impl fmt::Display for PinState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unknown = "?";
+        write!(
+            f,
+            "GPIO{:<2} {:<8} {:<3} 上下拉:{:<4} 使用者:{}",
+            self.pin,
+            self.function.as_deref().unwrap_or(unknown),
+            match self.level {
+                Some(true) => "高",
+                Some(false) => "低",
+                None => unknown,
+            },
+            self.pull.as_deref().unwrap_or(unknown),
+            self.owner.as_deref().unwrap_or("-"),
+        )?;
+        if let Some(mode) = self.mode {
+            write!(f, "（{}）", mode.name())?;
+        }
+        if self.interrupts > 0 {
+            write!(
+                f,
+                " 中断:{}次（最近1分钟{}次）",
+                self.interrupts, self.recent_interrupts
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// 所有登记引脚的状态（按编号排序）
+pub fn dump_pins() -> Vec<PinState> {
+    let pins: Vec<u8> = PINS
+        .lock()
+        .map(|pins| pins.keys().copied().collect())
+        .unwrap_or_default();
+    snapshot(pins)
+}
+
+/// 指定引脚的状态（包括没有登记的引脚）
+pub fn snapshot<I: IntoIterator<Item = u8>>(pins: I) -> Vec<PinState> {
+    let pins: Vec<u8> = pins.into_iter().collect();
+    let mut probes = probe(&pins);
+    let now = Instant::now();
+    let claims = PINS.lock().ok();
+    pins.iter()
+        .map(|&pin| {
+            let claim = claims.as_ref().and_then(|claims| claims.get(&pin));
+            let probe = probes.remove(&pin).unwrap_or_default();
+            PinState {
+                pin,
+                owner: claim.map(|claim| claim.owner.clone()),
+                mode: claim.map(|claim| claim.mode),
+                function: probe.function,
+                level: probe.level,
+                pull: probe.pull,
+                interrupts: claim.map_or(0, |claim| claim.interrupts),
+                recent_interrupts: claim.map_or(0, |claim| {
+                    claim
+                        .recent
+                        .iter()
+                        .filter(|time| now.duration_since(**time) <= INTERRUPT_WINDOW)
+                        .count()
+                }),
+                last_interrupt: claim.and_then(|claim| claim.last_interrupt),
+            }
+        })
+        .collect()
+}
+
+/// pinctrl读取到的引脚状态
+#[derive(Debug, Default)]
+struct Probe {
+    /// 功能
+    function: Option<String>,
+    /// 电平
+    level: Option<bool>,
+    /// 上下拉
+    pull: Option<String>,
+}
+
+/// 读取引脚的实际状态（优先使用pinctrl，旧系统使用raspi-gpio）
+fn probe(pins: &[u8]) -> BTreeMap<u8, Probe> {
+    if pins.is_empty() {
+        return BTreeMap::new();
+    }
+    let list: Vec<String> = pins.iter().map(u8::to_string).collect();
+    let run = |program: &str| {
+        Command::new(program)
+            .arg("get")
+            .arg(list.join(","))
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+    };
+    if let Some(text) = run("pinctrl") {
+        return text.lines().filter_map(parse_pinctrl).collect();
+    }
+    if let Some(text) = run("raspi-gpio") {
+        return text.lines().filter_map(parse_raspi_gpio).collect();
+    }
+    trace_event!(debug, "没有找到pinctrl或raspi-gpio命令，无法读取引脚状态");
+    BTreeMap::new()
+}
+
+/// 上下拉名称
+fn pull_name(pull: &str) -> Option<String> {
+    match pull.to_ascii_lowercase().as_str() {
+        "pu" | "up" => Some("up".to_string()),
+        "pd" | "down" => Some("down".to_string()),
+        "pn" | "none" | "off" => Some("none".to_string()),
+        _ => None,
+    }
+}
+
+/// 解析pinctrl的输出（如"17: op dh pd | hi // GPIO17 = output"）
+fn parse_pinctrl(line: &str) -> Option<(u8, Probe)> {
+    let (left, right) = line.split_once('|')?;
+    let (pin, fields) = left.split_once(':')?;
+    let pin = pin.trim().parse().ok()?;
+    let pull = fields.split_whitespace().find_map(pull_name);
+    let level = match right.split_whitespace().next() {
+        Some("hi") => Some(true),
+        Some("lo") => Some(false),
+        _ => None,
+    };
+    let function = right
+        .split_once('=')
+        .map(|(_, function)| function.trim().to_string())
+        .or_else(|| fields.split_whitespace().next().map(str::to_string));
+    Some((
+        pin,
+        Probe {
+            function,
+            level,
+            pull,
+        },
+    ))
+}
+
+/// 解析raspi-gpio的输出（如"GPIO 17: level=1 fsel=1 func=OUTPUT pull=DOWN"）
+fn parse_raspi_gpio(line: &str) -> Option<(u8, Probe)> {
+    let (pin, fields) = line.strip_prefix("GPIO")?.split_once(':')?;
+    let pin = pin.trim().parse().ok()?;
+    let mut probe = Probe::default();
+    for field in fields.split_whitespace() {
+        match field.split_once('=') {
+            Some(("level", level)) => probe.level = Some(level == "1"),
+            Some(("func", function)) => probe.function = Some(function.to_ascii_lowercase()),
+            Some(("pull", pull)) => probe.pull = pull_name(pull),
+            _ => {}
+        }
+    }
+    Some((pin, probe))
+}
@@ -0,0 +1,129 @@
+//! 驱动自检
+//!
+//! 传感器读取失败时，原因通常是接线（数据线接错、缺少上拉电阻、SDA/SCL接反）或供电。
+//! 各驱动的`self_test`检查芯片ID、状态位、响应信号、噪声等，生成带接线提示的诊断报告，
+//! 命令行工具的`doctor`子命令依次运行所有传感器的自检
+
+use std::fmt;
+
+/// 检查结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CheckStatus {
+    /// 通过
+    Pass,
+    /// 警告（可以工作但需要注意）
+    Warn,
+    /// 失败
+    Fail,
+}
+
+impl CheckStatus {
+    /// 名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "通过",
+            CheckStatus::Warn => "警告",
+            CheckStatus::Fail => "失败",
+        }
+    }
+}
+
+/// 单项检查
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Check {
+    /// 检查项
+    pub name: String,
+    /// 结果
+    pub status: CheckStatus,
+    /// 详细信息（测量值、寄存器值等）
+    pub detail: String,
+    /// 接线或配置提示
+    pub hint: Option<String>,
+}
+
+/// 诊断报告
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiagnosticsReport {
+    /// 设备型号
+    pub device: String,
+    /// 检查项
+    pub checks: Vec<Check>,
+}
+
+impl DiagnosticsReport {
+    /// 创建空报告
+    pub fn new(device: &str) -> Self {
+        Self {
+            device: device.to_string(),
+            checks: Vec::new(),
+        }
+    }
+
+    /// 添加检查项
+    pub fn check(
+        &mut self,
+        name: &str,
+        status: CheckStatus,
+        detail: impl fmt::Display,
+        hint: Option<&str>,
+    ) -> &mut Self {
+        self.checks.push(Check {
+            name: name.to_string(),
+            status,
+            detail: detail.to_string(),
+            hint: hint.map(str::to_string),
+        });
+        self
+    }
+
+    /// 添加通过的检查项
+    pub fn pass(&mut self, name: &str, detail: impl fmt::Display) -> &mut Self {
+        self.check(name, CheckStatus::Pass, detail, None)
+    }
+
+    /// 添加警告
+    pub fn warn(&mut self, name: &str, detail: impl fmt::Display, hint: &str) -> &mut Self {
+        self.check(name, CheckStatus::Warn, detail, Some(hint))
+    }
+
+    /// 添加失败的检查项
+    pub fn fail(&mut self, name: &str, detail: impl fmt::Display, hint: &str) -> &mut Self {
+        self.check(name, CheckStatus::Fail, detail, Some(hint))
+    }
+
+    /// 总体结果（最差的检查项，没有检查项时为通过）
+    pub fn status(&self) -> CheckStatus {
+        self.checks
+            .iter()
+            .map(|check| check.status)
+            .max()
+            .unwrap_or(CheckStatus::Pass)
+    }
+
+    /// 是否没有失败的检查项
+    pub fn is_ok(&self) -> bool {
+        self.status() != CheckStatus::Fail
+    }
+}
+
+impl fmt::Display for DiagnosticsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{}]", self.device, self.status().name())?;
+        for check in &self.checks {
+            write!(
+                f,
+                "\n  [{}] {}: {}",
+                check.status.name(),
+                check.name,
+                check.detail
+            )?;
+            if let Some(hint) = &check.hint {
+                write!(f, "\n         提示: {}", hint)?;
+            }
+        }
+        Ok(())
+    }
+}
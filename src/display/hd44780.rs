@@ -0,0 +1,360 @@
+use rppal::gpio::{Gpio, OutputPin};
+use rppal::i2c::I2c;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::units::weight::WeightFormat;
+
+/// 指令
+mod cmd {
+    pub const CLEAR: u8 = 0x01;
+    pub const HOME: u8 = 0x02;
+    pub const ENTRY_MODE: u8 = 0x04;
+    pub const DISPLAY_CONTROL: u8 = 0x08;
+    pub const SHIFT: u8 = 0x10;
+    pub const FUNCTION_SET: u8 = 0x20;
+    pub const SET_CGRAM: u8 = 0x40;
+    pub const SET_DDRAM: u8 = 0x80;
+}
+
+/// 每行起始DDRAM地址
+const ROW_OFFSETS: [u8; 4] = [0x00, 0x40, 0x14, 0x54];
+
+/// PCF8574转接板引脚定义
+mod backpack {
+    pub const RS: u8 = 0x01;
+    pub const EN: u8 = 0x04;
+    pub const BACKLIGHT: u8 = 0x08;
+}
+
+/// 接线方式
+enum Interface {
+    /// 直接使用GPIO的4位数据线模式
+    Gpio {
+        rs: OutputPin,
+        en: OutputPin,
+        data: [OutputPin; 4],
+        backlight: Option<OutputPin>,
+    },
+    /// PCF8574 I2C转接板
+    I2c {
+        i2c_bus: Arc<Mutex<I2c>>,
+        address: u16,
+    },
+}
+
+/// HD44780字符液晶屏封装对象（1602/2004）
+pub struct HD44780 {
+    /// 接线方式
+    interface: Interface,
+    /// 列数
+    cols: u8,
+    /// 行数
+    rows: u8,
+    /// 背光状态
+    backlight: bool,
+    /// 显示控制位（显示、光标、闪烁）
+    display_control: u8,
+}
+
+impl HD44780 {
+    /// 创建直接使用GPIO接线的实例（4位数据线模式，RW接地）
+    ///
+    /// - rs_pin、en_pin: 寄存器选择、使能引脚
+    /// - data_pins: D4~D7数据线引脚
+    /// - backlight_pin: 背光控制引脚（未接线时传None）
+    pub fn new_gpio(
+        rs_pin: u8,
+        en_pin: u8,
+        data_pins: [u8; 4],
+        backlight_pin: Option<u8>,
+        cols: u8,
+        rows: u8,
+    ) -> anyhow::Result<Self> {
+        let gpio = Gpio::new()?;
+        let interface = Interface::Gpio {
+            rs: gpio.get(rs_pin)?.into_output_low(),
+            en: gpio.get(en_pin)?.into_output_low(),
+            data: [
+                gpio.get(data_pins[0])?.into_output_low(),
+                gpio.get(data_pins[1])?.into_output_low(),
+                gpio.get(data_pins[2])?.into_output_low(),
+                gpio.get(data_pins[3])?.into_output_low(),
+            ],
+            backlight: match backlight_pin {
+                Some(pin) => Some(gpio.get(pin)?.into_output_high()),
+                None => None,
+            },
+        };
+        Self::with_interface(interface, cols, rows)
+    }
+
+    /// 创建使用PCF8574 I2C转接板的实例
+    ///
+    /// - i2c_bus: 共享的I2C通信总线
+    /// - address: 设备地址，为None时使用默认地址0x27
+    pub fn new_i2c(
+        i2c_bus: Arc<Mutex<I2c>>,
+        address: Option<u16>,
+        cols: u8,
+        rows: u8,
+    ) -> anyhow::Result<Self> {
+        let interface = Interface::I2c {
+            i2c_bus,
+            address: address.unwrap_or(0x27),
+        };
+        Self::with_interface(interface, cols, rows)
+    }
+
+    /// 初始化
+    fn with_interface(interface: Interface, cols: u8, rows: u8) -> anyhow::Result<Self> {
+        if rows == 0 || rows as usize > ROW_OFFSETS.len() {
+            return Err(anyhow::anyhow!("HD44780不支持的行数: {}", rows));
+        }
+
+        let mut this = Self {
+            interface,
+            cols,
+            rows,
+            backlight: true,
+            display_control: 0x04,
+        };
+
+        // 上电后至少等待40ms
+        thread::sleep(Duration::from_millis(50));
+        // 按数据手册的软件复位流程切换到4位模式
+        this.write_nibble(0x03, false)?;
+        thread::sleep(Duration::from_micros(4500));
+        this.write_nibble(0x03, false)?;
+        thread::sleep(Duration::from_micros(4500));
+        this.write_nibble(0x03, false)?;
+        thread::sleep(Duration::from_micros(150));
+        this.write_nibble(0x02, false)?;
+
+        // 4位数据线、多行、5×8点阵
+        let lines = if rows > 1 { 0x08 } else { 0x00 };
+        this.command(cmd::FUNCTION_SET | lines)?;
+        this.command(cmd::DISPLAY_CONTROL | this.display_control)?;
+        this.clear()?;
+        // 写入后光标右移，不移动画面
+        this.command(cmd::ENTRY_MODE | 0x02)?;
+        // OK
+        Ok(this)
+    }
+
+    /// 写入4位数据
+    fn write_nibble(&mut self, nibble: u8, rs: bool) -> anyhow::Result<()> {
+        match &mut self.interface {
+            Interface::Gpio {
+                rs: rs_pin,
+                en,
+                data,
+                ..
+            } => {
+                if rs {
+                    rs_pin.set_high();
+                } else {
+                    rs_pin.set_low();
+                }
+                for (i, pin) in data.iter_mut().enumerate() {
+                    if nibble & (1 << i) != 0 {
+                        pin.set_high();
+                    } else {
+                        pin.set_low();
+                    }
+                }
+                // 使能脉冲，下降沿锁存数据
+                en.set_high();
+                thread::sleep(Duration::from_micros(1));
+                en.set_low();
+            }
+            Interface::I2c { i2c_bus, address } => {
+                let mut byte = (nibble << 4) & 0xF0;
+                if rs {
+                    byte |= backpack::RS;
+                }
+                if self.backlight {
+                    byte |= backpack::BACKLIGHT;
+                }
+
+                let mut i2c = i2c_bus
+                    .lock()
+                    .map_err(|_| anyhow::anyhow!("I2C通信总线繁忙"))?;
+                i2c.set_slave_address(*address)?;
+                i2c.write(&[byte | backpack::EN])?;
+                i2c.write(&[byte])?;
+            }
+        }
+        // 大部分指令执行时间为37µs
+        thread::sleep(Duration::from_micros(50));
+        Ok(())
+    }
+
+    /// 写入一个字节
+    fn write_byte(&mut self, byte: u8, rs: bool) -> anyhow::Result<()> {
+        self.write_nibble(byte >> 4, rs)?;
+        self.write_nibble(byte & 0x0F, rs)
+    }
+
+    /// 发送指令
+    fn command(&mut self, command: u8) -> anyhow::Result<()> {
+        self.write_byte(command, false)
+    }
+
+    /// 列数
+    pub fn cols(&self) -> u8 {
+        self.cols
+    }
+
+    /// 行数
+    pub fn rows(&self) -> u8 {
+        self.rows
+    }
+
+    /// 清屏并将光标移回原点
+    pub fn clear(&mut self) -> anyhow::Result<()> {
+        self.command(cmd::CLEAR)?;
+        // 清屏指令需要1.52ms
+        thread::sleep(Duration::from_millis(2));
+        Ok(())
+    }
+
+    /// 光标移回原点
+    pub fn home(&mut self) -> anyhow::Result<()> {
+        self.command(cmd::HOME)?;
+        thread::sleep(Duration::from_millis(2));
+        Ok(())
+    }
+
+    /// 设置光标位置
+    pub fn set_cursor(&mut self, col: u8, row: u8) -> anyhow::Result<()> {
+        if col >= self.cols || row >= self.rows {
+            return Err(anyhow::anyhow!(
+                "HD44780光标位置超出范围: ({}, {})",
+                col,
+                row
+            ));
+        }
+        self.command(cmd::SET_DDRAM | (ROW_OFFSETS[row as usize] + col))
+    }
+
+    /// 在当前光标位置写入文本
+    ///
+    /// - 只支持ASCII字符，其他字符显示为'?'
+    /// - '\u{0}'~'\u{7}'显示自定义字符
+    pub fn write_str(&mut self, text: &str) -> anyhow::Result<()> {
+        for c in text.chars() {
+            let byte = match c {
+                '\u{0}'..='\u{7}' | ' '..='}' => c as u8,
+                _ => b'?',
+            };
+            self.write_byte(byte, true)?;
+        }
+        Ok(())
+    }
+
+    /// 在指定行写入文本，不足一行的部分用空格填充
+    pub fn write_line(&mut self, row: u8, text: &str) -> anyhow::Result<()> {
+        self.set_cursor(0, row)?;
+        let line: String = text
+            .chars()
+            .chain(std::iter::repeat(' '))
+            .take(self.cols as usize)
+            .collect();
+        self.write_str(&line)
+    }
+
+    /// 在指定行右对齐显示重量（按显示格式换算单位并取整到分度值）
+    ///
+    /// - grams: 重量（克）
+    pub fn write_weight(
+        &mut self,
+        row: u8,
+        grams: f64,
+        format: &WeightFormat,
+    ) -> anyhow::Result<()> {
+        let text = format.format(grams);
+        let line = format!("{:>width$}", text, width = self.cols as usize);
+        self.write_line(row, &line)
+    }
+
+    /// 创建自定义字符
+    ///
+    /// - location: 字符编号（0~7）
+    /// - pattern: 8行点阵，每行低5位有效
+    pub fn create_char(&mut self, location: u8, pattern: &[u8; 8]) -> anyhow::Result<()> {
+        if location > 7 {
+            return Err(anyhow::anyhow!(
+                "HD44780自定义字符编号超出范围: {}",
+                location
+            ));
+        }
+        self.command(cmd::SET_CGRAM | (location << 3))?;
+        for &row in pattern {
+            self.write_byte(row & 0x1F, true)?;
+        }
+        // 切换回DDRAM
+        self.command(cmd::SET_DDRAM)
+    }
+
+    /// 更新显示控制位
+    fn update_display_control(&mut self, mask: u8, enable: bool) -> anyhow::Result<()> {
+        if enable {
+            self.display_control |= mask;
+        } else {
+            self.display_control &= !mask;
+        }
+        self.command(cmd::DISPLAY_CONTROL | self.display_control)
+    }
+
+    /// 打开/关闭显示
+    pub fn set_display_on(&mut self, on: bool) -> anyhow::Result<()> {
+        self.update_display_control(0x04, on)
+    }
+
+    /// 显示/隐藏光标
+    pub fn set_cursor_visible(&mut self, visible: bool) -> anyhow::Result<()> {
+        self.update_display_control(0x02, visible)
+    }
+
+    /// 打开/关闭光标闪烁
+    pub fn set_blink(&mut self, blink: bool) -> anyhow::Result<()> {
+        self.update_display_control(0x01, blink)
+    }
+
+    /// 画面整体左移一格
+    pub fn scroll_left(&mut self) -> anyhow::Result<()> {
+        self.command(cmd::SHIFT | 0x08)
+    }
+
+    /// 画面整体右移一格
+    pub fn scroll_right(&mut self) -> anyhow::Result<()> {
+        self.command(cmd::SHIFT | 0x0C)
+    }
+
+    /// 打开/关闭背光
+    pub fn set_backlight(&mut self, on: bool) -> anyhow::Result<()> {
+        self.backlight = on;
+        match &mut self.interface {
+            Interface::Gpio { backlight, .. } => {
+                if let Some(pin) = backlight {
+                    if on {
+                        pin.set_high();
+                    } else {
+                        pin.set_low();
+                    }
+                }
+            }
+            Interface::I2c { i2c_bus, address } => {
+                // 背光位随每次写入一起输出，这里单独刷新一次
+                let mut i2c = i2c_bus
+                    .lock()
+                    .map_err(|_| anyhow::anyhow!("I2C通信总线繁忙"))?;
+                i2c.set_slave_address(*address)?;
+                i2c.write(&[if on { backpack::BACKLIGHT } else { 0 }])?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,275 @@
+use rppal::spi::{Bus, Mode, SlaveSelect};
+use std::thread;
+use std::time::Duration;
+
+use crate::display::font;
+use crate::spi_bus::SpiDeviceHandle;
+use crate::units::weight::WeightFormat;
+
+/// 寄存器地址
+mod reg {
+    pub const DIGIT0: u8 = 0x01;
+    pub const DECODE_MODE: u8 = 0x09;
+    pub const INTENSITY: u8 = 0x0A;
+    pub const SCAN_LIMIT: u8 = 0x0B;
+    pub const SHUTDOWN: u8 = 0x0C;
+    pub const DISPLAY_TEST: u8 = 0x0F;
+}
+
+/// Code B译码：小数点位
+pub const DECIMAL_POINT: u8 = 0x80;
+
+/// MAX7219 LED点阵/数码管驱动封装对象（SPI，支持级联）
+///
+/// - 设备编号0为最靠近树莓派DIN的模块
+/// - 点阵模式下x=0为离树莓派最远模块的最左一列（常见的FC-16模块DIN在右侧）
+pub struct MAX7219 {
+    /// SPI设备
+    spi: SpiDeviceHandle,
+    /// 级联的模块数量
+    devices: usize,
+    /// 点阵显存（每个模块8行，每行1字节，最高位为最左一列）
+    buffer: Vec<[u8; 8]>,
+}
+
+impl MAX7219 {
+    /// 创建实例并初始化
+    ///
+    /// - bus: SPI总线
+    /// - slave_select: 片选
+    /// - devices: 级联的模块数量
+    pub fn new(bus: Bus, slave_select: SlaveSelect, devices: usize) -> anyhow::Result<Self> {
+        // MAX7219最高支持10MHz
+        let spi = SpiDeviceHandle::hardware(bus, slave_select, 1_000_000, Mode::Mode0)?;
+        Self::with_device(spi, devices)
+    }
+
+    /// 使用共享SPI总线上的设备创建实例并初始化
+    ///
+    /// - spi: SPI设备（通过`SpiBus::device`创建，建议1MHz、Mode0）
+    /// - devices: 级联的模块数量
+    pub fn with_device(spi: SpiDeviceHandle, devices: usize) -> anyhow::Result<Self> {
+        if devices == 0 {
+            return Err(anyhow::anyhow!("MAX7219级联模块数量不能为0"));
+        }
+
+        let mut this = Self {
+            spi,
+            devices,
+            buffer: vec![[0; 8]; devices],
+        };
+        this.write_all(reg::DISPLAY_TEST, 0)?;
+        // 扫描全部8位
+        this.write_all(reg::SCAN_LIMIT, 7)?;
+        // 默认不译码（点阵模式）
+        this.write_all(reg::DECODE_MODE, 0)?;
+        this.write_all(reg::INTENSITY, 4)?;
+        this.clear()?;
+        // 退出关断模式
+        this.write_all(reg::SHUTDOWN, 1)?;
+        // OK
+        Ok(this)
+    }
+
+    /// 级联的模块数量
+    pub fn devices(&self) -> usize {
+        self.devices
+    }
+
+    /// 点阵宽度（像素）
+    pub fn width(&self) -> usize {
+        self.devices * 8
+    }
+
+    /// 发送一帧数据，每个模块一个(寄存器, 数据)
+    ///
+    /// 级联时先发送的数据会被移位到最远的模块
+    fn write_frame(&mut self, frame: &[(u8, u8)]) -> anyhow::Result<()> {
+        let data: Vec<u8> = frame
+            .iter()
+            .rev()
+            .flat_map(|&(reg, value)| [reg, value])
+            .collect();
+        self.spi.write(&data)
+    }
+
+    /// 向所有模块写同一个寄存器
+    fn write_all(&mut self, reg: u8, value: u8) -> anyhow::Result<()> {
+        let frame = vec![(reg, value); self.devices];
+        self.write_frame(&frame)
+    }
+
+    /// 向单个模块写寄存器，其他模块发送空操作
+    fn write_device(&mut self, device: usize, reg: u8, value: u8) -> anyhow::Result<()> {
+        if device >= self.devices {
+            return Err(anyhow::anyhow!("MAX7219模块编号超出范围: {}", device));
+        }
+        let mut frame = vec![(0u8, 0u8); self.devices];
+        frame[device] = (reg, value);
+        self.write_frame(&frame)
+    }
+
+    /// 设置所有模块的亮度（0~15）
+    pub fn set_intensity(&mut self, intensity: u8) -> anyhow::Result<()> {
+        self.write_all(reg::INTENSITY, intensity.min(15))
+    }
+
+    /// 设置单个模块的亮度（0~15）
+    pub fn set_device_intensity(&mut self, device: usize, intensity: u8) -> anyhow::Result<()> {
+        self.write_device(device, reg::INTENSITY, intensity.min(15))
+    }
+
+    /// 进入/退出关断模式（关断时显示熄灭，数据保留）
+    pub fn set_shutdown(&mut self, shutdown: bool) -> anyhow::Result<()> {
+        self.write_all(reg::SHUTDOWN, if shutdown { 0 } else { 1 })
+    }
+
+    /// 设置单个模块的译码模式
+    ///
+    /// - mask: 每一位对应一个数码位，1为Code B译码，0为直接控制段
+    pub fn set_decode_mode(&mut self, device: usize, mask: u8) -> anyhow::Result<()> {
+        self.write_device(device, reg::DECODE_MODE, mask)
+    }
+
+    /// 写入单个数码位（0~7）
+    ///
+    /// 译码模式下value为Code B字符（0~9、0x0A:'-'、0x0F:空白），可或上`DECIMAL_POINT`
+    pub fn write_digit(&mut self, device: usize, digit: u8, value: u8) -> anyhow::Result<()> {
+        if digit > 7 {
+            return Err(anyhow::anyhow!("MAX7219数码位超出范围: {}", digit));
+        }
+        self.write_device(device, reg::DIGIT0 + digit, value)
+    }
+
+    /// 在8位数码管上右对齐显示文本（需开启全部位译码）
+    ///
+    /// 支持0~9、'-'、'E'、'H'、'L'、'P'、空格和小数点
+    pub fn display_number(&mut self, device: usize, text: &str) -> anyhow::Result<()> {
+        let mut digits: Vec<u8> = Vec::with_capacity(8);
+        for c in text.chars().rev() {
+            // 小数点合并到前一个字符上
+            if c == '.' {
+                digits.push(0x0F | DECIMAL_POINT);
+                continue;
+            }
+            let code = match c {
+                '0'..='9' => c as u8 - b'0',
+                '-' => 0x0A,
+                'E' => 0x0B,
+                'H' => 0x0C,
+                'L' => 0x0D,
+                'P' => 0x0E,
+                _ => 0x0F,
+            };
+            match digits.last_mut() {
+                Some(last) if *last == 0x0F | DECIMAL_POINT => *last = code | DECIMAL_POINT,
+                _ => digits.push(code),
+            }
+        }
+        if digits.len() > 8 {
+            return Err(anyhow::anyhow!("MAX7219数码管显示内容过长: {}", text));
+        }
+
+        // 最右侧为第0位，不足的位显示空白
+        for digit in 0..8u8 {
+            let code = digits.get(digit as usize).copied().unwrap_or(0x0F);
+            self.write_digit(device, digit, code)?;
+        }
+        Ok(())
+    }
+
+    /// 在8位数码管上显示重量（不显示单位，按显示格式换算单位并取整到分度值）
+    ///
+    /// - grams: 重量（克）
+    pub fn display_weight(
+        &mut self,
+        device: usize,
+        grams: f64,
+        format: &WeightFormat,
+    ) -> anyhow::Result<()> {
+        self.display_number(device, &format.format_value(grams))
+    }
+
+    /// 清空显示
+    pub fn clear(&mut self) -> anyhow::Result<()> {
+        for row in self.buffer.iter_mut() {
+            *row = [0; 8];
+        }
+        self.flush()
+    }
+
+    /// 设置点阵像素（需调用`flush`刷新）
+    pub fn set_pixel(&mut self, x: usize, y: usize, on: bool) {
+        if x >= self.width() || y >= 8 {
+            return;
+        }
+        // 最左侧的模块是离树莓派最远的模块
+        let device = self.devices - 1 - x / 8;
+        let mask = 0x80 >> (x % 8);
+        if on {
+            self.buffer[device][y] |= mask;
+        } else {
+            self.buffer[device][y] &= !mask;
+        }
+    }
+
+    /// 将点阵显存刷新到模块
+    pub fn flush(&mut self) -> anyhow::Result<()> {
+        for row in 0..8 {
+            let frame: Vec<(u8, u8)> = self
+                .buffer
+                .iter()
+                .map(|rows| (reg::DIGIT0 + row as u8, rows[row]))
+                .collect();
+            self.write_frame(&frame)?;
+        }
+        Ok(())
+    }
+
+    /// 将文本转换为点阵列数据（每个字符5列加1列间距）
+    fn text_columns(text: &str) -> Vec<u8> {
+        let mut columns = Vec::with_capacity(text.len() * 6);
+        for c in text.chars() {
+            columns.extend_from_slice(font::glyph(c));
+            columns.push(0);
+        }
+        columns
+    }
+
+    /// 从指定列开始绘制点阵列数据
+    fn draw_columns(&mut self, columns: &[u8], offset: isize) {
+        for x in 0..self.width() {
+            let index = x as isize + offset;
+            let bits = if index >= 0 {
+                columns.get(index as usize).copied().unwrap_or(0)
+            } else {
+                0
+            };
+            for y in 0..8 {
+                self.set_pixel(x, y, bits & (1 << y) != 0);
+            }
+        }
+    }
+
+    /// 静态显示文本（超出宽度的部分被截断）
+    pub fn draw_text(&mut self, text: &str) -> anyhow::Result<()> {
+        let columns = Self::text_columns(text);
+        self.draw_columns(&columns, 0);
+        self.flush()
+    }
+
+    /// 水平滚动显示文本（阻塞直到文本完全移出屏幕）
+    ///
+    /// - step_delay: 每移动一列的间隔时间
+    pub fn scroll_text(&mut self, text: &str, step_delay: Duration) -> anyhow::Result<()> {
+        let columns = Self::text_columns(text);
+        // 从屏幕右侧移入，直到完全从左侧移出
+        let width = self.width() as isize;
+        for offset in -width..=columns.len() as isize {
+            self.draw_columns(&columns, offset);
+            self.flush()?;
+            thread::sleep(step_delay);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,8 @@
+#[cfg(any(feature = "ssd1306", feature = "max7219"))]
+pub mod font;
+#[cfg(feature = "hd44780")]
+pub mod hd44780;
+#[cfg(feature = "max7219")]
+pub mod max7219;
+#[cfg(feature = "ssd1306")]
+pub mod ssd1306;
@@ -0,0 +1,283 @@
+use rppal::i2c::I2c;
+use std::sync::{Arc, Mutex};
+
+use crate::display::font;
+
+/// 屏幕尺寸
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DisplaySize {
+    /// 128×64
+    W128H64,
+    /// 128×32
+    W128H32,
+}
+
+impl DisplaySize {
+    /// 宽度（像素）
+    pub fn width(&self) -> u32 {
+        128
+    }
+
+    /// 高度（像素）
+    pub fn height(&self) -> u32 {
+        match self {
+            DisplaySize::W128H64 => 64,
+            DisplaySize::W128H32 => 32,
+        }
+    }
+}
+
+/// 控制字节：命令
+const CONTROL_COMMAND: u8 = 0x00;
+/// 控制字节：显存数据
+const CONTROL_DATA: u8 = 0x40;
+
+/// 字符宽度（含1列间距）
+pub const CHAR_WIDTH: u32 = 6;
+/// 字符高度（含1行间距）
+pub const CHAR_HEIGHT: u32 = 8;
+
+/// SSD1306 OLED显示屏封装对象（I2C）
+pub struct SSD1306 {
+    /// I2C通信总线
+    i2c_bus: Arc<Mutex<I2c>>,
+    /// 设备地址
+    address: u16,
+    /// 屏幕尺寸
+    size: DisplaySize,
+    /// 显存缓冲区（按页排列，每字节表示竖直方向8个像素）
+    buffer: Vec<u8>,
+}
+
+impl SSD1306 {
+    /// 创建显示屏实例并初始化
+    ///
+    /// - i2c_bus: 共享的I2C通信总线
+    /// - address: 设备地址，为None时使用默认地址0x3C
+    /// - size: 屏幕尺寸
+    pub fn new(
+        i2c_bus: Arc<Mutex<I2c>>,
+        address: Option<u16>,
+        size: DisplaySize,
+    ) -> anyhow::Result<Self> {
+        let mut this = Self {
+            i2c_bus,
+            address: address.unwrap_or(0x3C),
+            size,
+            buffer: vec![0; (size.width() * size.height() / 8) as usize],
+        };
+        this.init()?;
+        // OK
+        Ok(this)
+    }
+
+    /// 屏幕尺寸
+    pub fn size(&self) -> DisplaySize {
+        self.size
+    }
+
+    /// 发送命令
+    fn commands(&mut self, commands: &[u8]) -> anyhow::Result<()> {
+        let mut frame = Vec::with_capacity(commands.len() + 1);
+        frame.push(CONTROL_COMMAND);
+        frame.extend_from_slice(commands);
+
+        let mut i2c = self
+            .i2c_bus
+            .lock()
+            .map_err(|_| anyhow::anyhow!("I2C通信总线繁忙"))?;
+        i2c.set_slave_address(self.address)?;
+        i2c.write(&frame)?;
+        Ok(())
+    }
+
+    /// 初始化序列
+    fn init(&mut self) -> anyhow::Result<()> {
+        let height = self.size.height() as u8;
+        // COM引脚硬件配置：64行为交替模式，32行为顺序模式
+        let com_pins = if height == 64 { 0x12 } else { 0x02 };
+        // 关闭显示
+        self.commands(&[0xAE])?;
+        // 时钟分频
+        self.commands(&[0xD5, 0x80])?;
+        // 复用率
+        self.commands(&[0xA8, height - 1])?;
+        // 显示偏移、起始行
+        self.commands(&[0xD3, 0x00, 0x40])?;
+        // 打开电荷泵
+        self.commands(&[0x8D, 0x14])?;
+        // 水平寻址模式
+        self.commands(&[0x20, 0x00])?;
+        // 列地址重映射、COM扫描方向（屏幕不倒置）
+        self.commands(&[0xA1, 0xC8])?;
+        // COM引脚配置
+        self.commands(&[0xDA, com_pins])?;
+        // 对比度
+        self.commands(&[0x81, 0xCF])?;
+        // 预充电周期
+        self.commands(&[0xD9, 0xF1])?;
+        // VCOMH电压
+        self.commands(&[0xDB, 0x40])?;
+        // 显示显存内容、正常显示、打开显示
+        self.commands(&[0xA4, 0xA6, 0xAF])?;
+        self.clear();
+        self.flush()
+    }
+
+    /// 设置对比度
+    pub fn set_contrast(&mut self, contrast: u8) -> anyhow::Result<()> {
+        self.commands(&[0x81, contrast])
+    }
+
+    /// 设置反色显示
+    pub fn set_invert(&mut self, invert: bool) -> anyhow::Result<()> {
+        self.commands(&[if invert { 0xA7 } else { 0xA6 }])
+    }
+
+    /// 打开/关闭显示（关闭后进入休眠，显存内容保留）
+    pub fn set_display_on(&mut self, on: bool) -> anyhow::Result<()> {
+        self.commands(&[if on { 0xAF } else { 0xAE }])
+    }
+
+    /// 清空显存缓冲区
+    pub fn clear(&mut self) {
+        self.buffer.fill(0);
+    }
+
+    /// 设置像素（超出屏幕的坐标忽略）
+    pub fn set_pixel(&mut self, x: u32, y: u32, on: bool) {
+        if x >= self.size.width() || y >= self.size.height() {
+            return;
+        }
+        let index = (x + (y / 8) * self.size.width()) as usize;
+        let mask = 1 << (y % 8);
+        if on {
+            self.buffer[index] |= mask;
+        } else {
+            self.buffer[index] &= !mask;
+        }
+    }
+
+    /// 读取像素
+    pub fn get_pixel(&self, x: u32, y: u32) -> bool {
+        if x >= self.size.width() || y >= self.size.height() {
+            return false;
+        }
+        let index = (x + (y / 8) * self.size.width()) as usize;
+        self.buffer[index] & (1 << (y % 8)) != 0
+    }
+
+    /// 画水平线
+    pub fn draw_hline(&mut self, x: u32, y: u32, width: u32, on: bool) {
+        for i in 0..width {
+            self.set_pixel(x + i, y, on);
+        }
+    }
+
+    /// 画竖直线
+    pub fn draw_vline(&mut self, x: u32, y: u32, height: u32, on: bool) {
+        for i in 0..height {
+            self.set_pixel(x, y + i, on);
+        }
+    }
+
+    /// 画矩形
+    pub fn draw_rect(&mut self, x: u32, y: u32, width: u32, height: u32, fill: bool) {
+        if fill {
+            for i in 0..height {
+                self.draw_hline(x, y + i, width, true);
+            }
+        } else if width > 0 && height > 0 {
+            self.draw_hline(x, y, width, true);
+            self.draw_hline(x, y + height - 1, width, true);
+            self.draw_vline(x, y, height, true);
+            self.draw_vline(x + width - 1, y, height, true);
+        }
+    }
+
+    /// 绘制单个字符（5×7点阵）
+    pub fn draw_char(&mut self, x: u32, y: u32, c: char) {
+        for (col, bits) in font::glyph(c).iter().enumerate() {
+            for row in 0..7 {
+                self.set_pixel(x + col as u32, y + row, bits & (1 << row) != 0);
+            }
+        }
+    }
+
+    /// 绘制文本，遇到换行符或超出屏幕宽度时自动换行
+    pub fn draw_text(&mut self, x: u32, y: u32, text: &str) {
+        let (mut cx, mut cy) = (x, y);
+        for c in text.chars() {
+            if c == '\n' || cx + CHAR_WIDTH > self.size.width() {
+                cx = x;
+                cy += CHAR_HEIGHT;
+                if c == '\n' {
+                    continue;
+                }
+            }
+            self.draw_char(cx, cy, c);
+            cx += CHAR_WIDTH;
+        }
+    }
+
+    /// 将显存缓冲区刷新到屏幕
+    pub fn flush(&mut self) -> anyhow::Result<()> {
+        let pages = (self.size.height() / 8) as u8;
+        // 列地址范围、页地址范围
+        let last_col = (self.size.width() - 1) as u8;
+        self.commands(&[0x21, 0, last_col, 0x22, 0, pages - 1])?;
+
+        let mut i2c = self
+            .i2c_bus
+            .lock()
+            .map_err(|_| anyhow::anyhow!("I2C通信总线繁忙"))?;
+        i2c.set_slave_address(self.address)?;
+        // 分块写入，避免单次I2C传输过长
+        let mut frame = [0u8; 33];
+        frame[0] = CONTROL_DATA;
+        for chunk in self.buffer.chunks(32) {
+            frame[1..=chunk.len()].copy_from_slice(chunk);
+            i2c.write(&frame[..=chunk.len()])?;
+        }
+        // OK
+        Ok(())
+    }
+}
+
+/// embedded-graphics绘图支持
+#[cfg(feature = "embedded-graphics")]
+mod graphics {
+    use embedded_graphics::Pixel;
+    use embedded_graphics::pixelcolor::BinaryColor;
+    use embedded_graphics::prelude::{DrawTarget, OriginDimensions, Size};
+
+    use super::SSD1306;
+
+    impl OriginDimensions for SSD1306 {
+        fn size(&self) -> Size {
+            Size::new(self.size.width(), self.size.height())
+        }
+    }
+
+    impl DrawTarget for SSD1306 {
+        type Color = BinaryColor;
+        type Error = core::convert::Infallible;
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Pixel<Self::Color>>,
+        {
+            for Pixel(point, color) in pixels {
+                if point.x >= 0 && point.y >= 0 {
+                    self.set_pixel(point.x as u32, point.y as u32, color.is_on());
+                }
+            }
+            Ok(())
+        }
+
+        fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+            self.buffer.fill(if color.is_on() { 0xFF } else { 0x00 });
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,208 @@
+//! 外壳内温湿度补偿
+//!
+//! DHT11、AHT30等与树莓派装在同一个外壳内时，测得的温度高于环境温度：一部分是固定偏差
+//! （安装位置靠近稳压器等），另一部分随开机时间逐渐增加（CPU和外壳慢慢热起来）直到稳定。
+//! 补偿模型为 偏差 = offset + slope × min(开机时间, settle)，温度减去偏差后，按水汽压不变
+//! 把相对湿度换算到环境温度下（外壳内温度高，测得的相对湿度偏低），酷热指数也按补偿后的
+//! 温湿度计算。系数与实际温度计对比得到，保存在校准数据中
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+use crate::calibration::Calibration;
+use crate::capture::SignalCapture;
+use crate::diagnostics::DiagnosticsReport;
+use crate::manager::Sensor;
+use crate::reading::{Quantity, Reading};
+use crate::station::{heat_index, saturation_vapor_pressure};
+
+/// 外壳补偿模型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EnclosureModel {
+    /// 固定偏差（℃）
+    pub offset: f64,
+    /// 开机后的升温速率（℃/h）
+    pub slope: f64,
+    /// 升温持续的时间，之后偏差不再增加
+    pub settle: Duration,
+}
+
+impl EnclosureModel {
+    /// 创建实例
+    ///
+    /// - offset: 固定偏差（℃）
+    /// - slope: 开机后的升温速率（℃/h）
+    /// - settle: 升温持续的时间
+    pub fn new(offset: f64, slope: f64, settle: Duration) -> Self {
+        Self {
+            offset,
+            slope,
+            settle,
+        }
+    }
+
+    /// 只有固定偏差的模型
+    pub fn fixed(offset: f64) -> Self {
+        Self::new(offset, 0.0, Duration::ZERO)
+    }
+
+    /// 开机`uptime`后的温度偏差（℃）
+    pub fn bias(&self, uptime: Duration) -> f64 {
+        self.offset + self.slope * uptime.min(self.settle).as_secs_f64() / 3600.0
+    }
+
+    /// 补偿读数中的温度和相对湿度（其他物理量不变）
+    pub fn correct(&self, reading: &Reading, uptime: Duration) -> Reading {
+        let mut corrected = reading.clone();
+        let Some(measured) = reading.get(Quantity::Temperature) else {
+            return corrected;
+        };
+        let ambient = measured - self.bias(uptime);
+        corrected.set(Quantity::Temperature, ambient);
+        if let Some(humidity) = reading.get(Quantity::Humidity) {
+            let humidity =
+                humidity * saturation_vapor_pressure(measured) / saturation_vapor_pressure(ambient);
+            corrected.set(Quantity::Humidity, humidity.clamp(0.0, 100.0));
+        }
+        corrected
+    }
+
+    /// 按补偿后的温度和相对湿度计算酷热指数（℃，读数中没有温度或相对湿度时为None）
+    pub fn heat_index(&self, reading: &Reading, uptime: Duration) -> Option<f64> {
+        let corrected = self.correct(reading, uptime);
+        let temperature = corrected.get(Quantity::Temperature)?;
+        let humidity = corrected.get(Quantity::Humidity)?;
+        Some(heat_index(temperature, humidity))
+    }
+
+    /// 从校准数据读取（offset和slope都没有保存过时为None）
+    ///
+    /// 键为`<prefix>.enclosure_offset`、`<prefix>.enclosure_slope`和`<prefix>.enclosure_settle`（秒），
+    /// 升温持续时间为负数、非有限值或超出范围时返回错误
+    pub fn load(calibration: &Calibration, prefix: &str) -> anyhow::Result<Option<Self>> {
+        let offset = calibration.get(&format!("{}.enclosure_offset", prefix));
+        let slope = calibration.get(&format!("{}.enclosure_slope", prefix));
+        if offset.is_none() && slope.is_none() {
+            return Ok(None);
+        }
+        let settle = calibration
+            .get(&format!("{}.enclosure_settle", prefix))
+            .unwrap_or_default();
+        let settle = Duration::try_from_secs_f64(settle)
+            .map_err(|_| anyhow::anyhow!("外壳补偿的升温持续时间无效: {}", settle))?;
+        // OK
+        Ok(Some(Self::new(
+            offset.unwrap_or_default(),
+            slope.unwrap_or_default(),
+            settle,
+        )))
+    }
+
+    /// 保存到校准数据
+    pub fn save(&self, calibration: &mut Calibration, prefix: &str) {
+        calibration.set(&format!("{}.enclosure_offset", prefix), self.offset);
+        calibration.set(&format!("{}.enclosure_slope", prefix), self.slope);
+        calibration.set(
+            &format!("{}.enclosure_settle", prefix),
+            self.settle.as_secs_f64(),
+        );
+    }
+}
+
+/// 系统开机时间（读取/proc/uptime）
+pub fn system_uptime() -> Option<Duration> {
+    let text = fs::read_to_string("/proc/uptime").ok()?;
+    let seconds: f64 = text.split_whitespace().next()?.parse().ok()?;
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// 外壳补偿
+///
+/// 包装温湿度传感器，每次读取后按开机时间补偿温度和相对湿度
+///
+/// ```ignore
+/// let mut store = FileStore::new("sensor.cal");
+/// let model = EnclosureModel::load(&store.load()?, "dht11")?.unwrap_or(EnclosureModel::fixed(0.0));
+/// let sensor = Enclosure::new(Dht11Sensor::new(4)?, model);
+/// manager.register("dht11", sensor)?;
+/// ```
+pub struct Enclosure<S: Sensor> {
+    /// 被包装的传感器
+    inner: S,
+    /// 补偿模型
+    model: EnclosureModel,
+    /// 开机时间的起点
+    started: Instant,
+}
+
+impl<S: Sensor> Enclosure<S> {
+    /// 包装传感器（开机时间为系统开机时间，读取失败时从现在开始计算）
+    pub fn new(inner: S, model: EnclosureModel) -> Self {
+        let now = Instant::now();
+        let started = system_uptime()
+            .and_then(|uptime| now.checked_sub(uptime))
+            .unwrap_or(now);
+        Self {
+            inner,
+            model,
+            started,
+        }
+    }
+
+    /// 设置开机时间的起点（外壳内的发热设备与树莓派不同时上电时使用）
+    pub fn with_start(mut self, started: Instant) -> Self {
+        self.started = started;
+        self
+    }
+
+    /// 补偿模型
+    pub fn model(&self) -> &EnclosureModel {
+        &self.model
+    }
+
+    /// 修改补偿模型
+    pub fn set_model(&mut self, model: EnclosureModel) {
+        self.model = model;
+    }
+
+    /// 当前的温度偏差（℃）
+    pub fn bias(&self) -> f64 {
+        self.model.bias(self.started.elapsed())
+    }
+
+    /// 读取传感器并按补偿后的温湿度计算酷热指数（℃，传感器没有温度或相对湿度输出时为None）
+    pub fn heat_index(&mut self) -> anyhow::Result<Option<f64>> {
+        let reading = self.inner.read()?;
+        // OK
+        Ok(self.model.heat_index(&reading, self.started.elapsed()))
+    }
+
+    /// 取回被包装的传感器
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: Sensor> Sensor for Enclosure<S> {
+    fn kind(&self) -> &'static str {
+        self.inner.kind()
+    }
+
+    fn min_interval(&self) -> Duration {
+        self.inner.min_interval()
+    }
+
+    fn self_test(&mut self) -> DiagnosticsReport {
+        self.inner.self_test()
+    }
+
+    fn signal_capture(&self) -> Option<SignalCapture> {
+        self.inner.signal_capture()
+    }
+
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        let reading = self.inner.read()?;
+        // OK
+        Ok(self.model.correct(&reading, self.started.elapsed()))
+    }
+}
@@ -0,0 +1,267 @@
+//! 事件总线
+//!
+//! 称重→显示→MQTT→继电器这类流程原本需要在程序中手动创建mpsc通道逐个连接（见
+//! `weight_sensor_test.rs`）。`EventBus`按主题（读数、读取失败、按钮、告警、执行器状态）
+//! 分发事件：各模块只负责发布，显示、上报、控制等消费者各自订阅需要的主题
+
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::SystemTime;
+
+use crate::alerts::AlertEvent;
+use crate::reading::Sample;
+use crate::scheduler::ReadError;
+#[cfg(feature = "button")]
+use crate::sensor::button::ButtonEvent;
+use crate::sink::Sink;
+use crate::switch::Switch;
+
+/// 事件主题
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Topic {
+    /// 传感器读数
+    Reading,
+    /// 传感器读取失败
+    ReadError,
+    /// 按钮事件
+    Button,
+    /// 告警
+    Alert,
+    /// 执行器状态变化
+    Actuator,
+}
+
+/// 事件
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Event {
+    /// 传感器读数
+    Reading(Sample),
+    /// 传感器读取失败
+    ReadError {
+        /// 传感器名称
+        sensor: String,
+        /// 错误信息
+        error: String,
+    },
+    /// 按钮事件
+    #[cfg(feature = "button")]
+    Button {
+        /// 按钮名称
+        source: String,
+        /// 事件
+        event: ButtonEvent,
+    },
+    /// 告警
+    Alert(AlertEvent),
+    /// 执行器状态变化
+    Actuator {
+        /// 执行器名称
+        name: String,
+        /// 是否打开
+        on: bool,
+        /// 变化时间
+        #[cfg_attr(feature = "serde", serde(with = "crate::reading::unix_seconds"))]
+        timestamp: SystemTime,
+    },
+}
+
+impl Event {
+    /// 事件所属的主题
+    pub fn topic(&self) -> Topic {
+        match self {
+            Self::Reading(_) => Topic::Reading,
+            Self::ReadError { .. } => Topic::ReadError,
+            #[cfg(feature = "button")]
+            Self::Button { .. } => Topic::Button,
+            Self::Alert(_) => Topic::Alert,
+            Self::Actuator { .. } => Topic::Actuator,
+        }
+    }
+}
+
+impl From<Sample> for Event {
+    fn from(sample: Sample) -> Self {
+        Self::Reading(sample)
+    }
+}
+
+impl From<ReadError> for Event {
+    fn from((sensor, error): ReadError) -> Self {
+        Self::ReadError { sensor, error }
+    }
+}
+
+impl From<AlertEvent> for Event {
+    fn from(event: AlertEvent) -> Self {
+        Self::Alert(event)
+    }
+}
+
+/// 添加一个订阅者（调度器、气象站等按类型分组的订阅者列表共用）
+pub(crate) fn subscribe<T>(subscribers: &Mutex<Vec<Sender<T>>>) -> Receiver<T> {
+    let (tx, rx) = mpsc::channel();
+    if let Ok(mut subscribers) = subscribers.lock() {
+        subscribers.push(tx);
+    }
+    rx
+}
+
+/// 向所有订阅者发送，移除已断开的订阅者
+pub(crate) fn broadcast<T: Clone>(subscribers: &Mutex<Vec<Sender<T>>>, value: T) {
+    if let Ok(mut subscribers) = subscribers.lock() {
+        subscribers.retain(|tx| tx.send(value.clone()).is_ok());
+    }
+}
+
+/// 订阅者
+struct Subscriber {
+    /// 订阅的主题（为空时订阅全部主题）
+    topics: Vec<Topic>,
+    /// 事件通道
+    tx: Sender<Event>,
+}
+
+/// 事件总线（可克隆，克隆后共享同一组订阅者）
+#[derive(Clone, Default)]
+pub struct EventBus {
+    subscribers: Arc<Mutex<Vec<Subscriber>>>,
+}
+
+impl EventBus {
+    /// 创建实例
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 订阅指定主题（为空时订阅全部主题）
+    pub fn subscribe(&self, topics: &[Topic]) -> Receiver<Event> {
+        let (tx, rx) = mpsc::channel();
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push(Subscriber {
+                topics: topics.to_vec(),
+                tx,
+            });
+        }
+        rx
+    }
+
+    /// 订阅全部主题
+    pub fn subscribe_all(&self) -> Receiver<Event> {
+        self.subscribe(&[])
+    }
+
+    /// 订阅者数量
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers
+            .lock()
+            .map(|subscribers| subscribers.len())
+            .unwrap_or(0)
+    }
+
+    /// 发布事件，移除已断开的订阅者
+    pub fn publish<E: Into<Event>>(&self, event: E) {
+        let event = event.into();
+        let topic = event.topic();
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.retain(|subscriber| {
+                if !subscriber.topics.is_empty() && !subscriber.topics.contains(&topic) {
+                    return true;
+                }
+                subscriber.tx.send(event.clone()).is_ok()
+            });
+        }
+    }
+
+    /// 发布按钮事件
+    #[cfg(feature = "button")]
+    pub fn publish_button(&self, source: &str, event: ButtonEvent) {
+        self.publish(Event::Button {
+            source: source.to_string(),
+            event,
+        });
+    }
+
+    /// 发布执行器状态变化
+    pub fn publish_actuator(&self, name: &str, on: bool) {
+        self.publish(Event::Actuator {
+            name: name.to_string(),
+            on,
+            timestamp: SystemTime::now(),
+        });
+    }
+
+    /// 生成按钮事件回调（传给`Button::on_event`等）
+    #[cfg(feature = "button")]
+    pub fn button_callback(&self, source: &str) -> impl FnMut(ButtonEvent) + Send + 'static {
+        let bus = self.clone();
+        let source = source.to_string();
+        move |event| bus.publish_button(&source, event)
+    }
+
+    /// 在后台线程中将通道中的数据转发到总线（调度器的读数和读取失败、告警引擎的告警等），
+    /// 通道关闭时退出
+    pub fn forward<T>(&self, rx: Receiver<T>) -> JoinHandle<()>
+    where
+        T: Into<Event> + Send + 'static,
+    {
+        let bus = self.clone();
+        thread::spawn(move || {
+            for value in rx {
+                bus.publish(value);
+            }
+        })
+    }
+
+    /// 包装开关，状态变化时发布执行器事件
+    pub fn switch<W: Switch>(&self, name: &str, switch: W) -> BusSwitch<W> {
+        BusSwitch {
+            name: name.to_string(),
+            switch,
+            bus: self.clone(),
+        }
+    }
+}
+
+/// 作为管道输出时发布读数
+impl Sink for EventBus {
+    fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        self.publish(sample.clone());
+        Ok(())
+    }
+}
+
+/// 状态变化时发布执行器事件的开关
+pub struct BusSwitch<W: Switch> {
+    /// 执行器名称
+    name: String,
+    /// 开关
+    switch: W,
+    /// 事件总线
+    bus: EventBus,
+}
+
+impl<W: Switch> BusSwitch<W> {
+    /// 取回开关
+    pub fn into_inner(self) -> W {
+        self.switch
+    }
+}
+
+impl<W: Switch> Switch for BusSwitch<W> {
+    fn set(&mut self, on: bool) -> anyhow::Result<()> {
+        let changed = self.switch.is_on() != on;
+        self.switch.set(on)?;
+        if changed {
+            self.bus.publish_actuator(&self.name, on);
+        }
+        // OK
+        Ok(())
+    }
+
+    fn is_on(&self) -> bool {
+        self.switch.is_on()
+    }
+}
@@ -0,0 +1,315 @@
+//! 冗余传感器融合
+//!
+//! 同一位置安装多个温湿度传感器（如同一I2C总线上的BME280和AHT30）时，`Fusion`将它们组合
+//! 为一个传感器：按权重对每个物理量加权平均，读数相差超过允许范围时标记为不一致，
+//! 连续读取失败的传感器自动排除，一段时间后再尝试恢复
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::diagnostics::{Check, DiagnosticsReport};
+use crate::manager::Sensor;
+use crate::reading::{Quantity, Reading};
+
+/// 参与融合的传感器
+struct Source {
+    /// 名称
+    name: String,
+    /// 传感器
+    sensor: Box<dyn Sensor>,
+    /// 默认权重
+    weight: f64,
+    /// 各物理量的权重（覆盖默认权重）
+    quantity_weights: BTreeMap<Quantity, f64>,
+    /// 连续读取失败次数
+    failures: u32,
+    /// 被排除时的重试时间
+    retry_at: Option<Instant>,
+}
+
+impl Source {
+    /// 物理量的权重
+    fn weight(&self, quantity: Quantity) -> f64 {
+        self.quantity_weights
+            .get(&quantity)
+            .copied()
+            .unwrap_or(self.weight)
+    }
+}
+
+/// 单个传感器的状态
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SourceState {
+    /// 名称
+    pub name: String,
+    /// 连续读取失败次数
+    pub failures: u32,
+    /// 是否已被排除
+    pub excluded: bool,
+    /// 最近一次读取失败的错误信息（读取成功后清空）
+    pub last_error: Option<String>,
+}
+
+/// 读数不一致
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Disagreement {
+    /// 物理量
+    pub quantity: Quantity,
+    /// 最大值与最小值之差
+    pub spread: f64,
+    /// 各传感器的读数（名称, 读数）
+    pub values: Vec<(String, f64)>,
+}
+
+/// 最近一次读取的融合状态
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FusionStatus {
+    /// 各传感器的状态
+    pub sources: Vec<SourceState>,
+    /// 超过允许范围的不一致
+    pub disagreements: Vec<Disagreement>,
+}
+
+impl FusionStatus {
+    /// 是否所有传感器正常且读数一致
+    pub fn is_healthy(&self) -> bool {
+        self.disagreements.is_empty() && self.sources.iter().all(|source| !source.excluded)
+    }
+}
+
+/// 融合状态（可克隆，传感器注册到管理器后仍然可以查看）
+#[derive(Debug, Clone, Default)]
+pub struct FusionStatusHandle {
+    status: Arc<Mutex<FusionStatus>>,
+}
+
+impl FusionStatusHandle {
+    /// 最近一次读取的融合状态
+    pub fn snapshot(&self) -> FusionStatus {
+        self.status
+            .lock()
+            .map(|status| status.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// 冗余传感器融合
+///
+/// ```ignore
+/// let bus = SharedBus::open(1)?;
+/// let fused = Fusion::new()
+///     .with_source("bme280", Bme280Sensor::new(bus.clone(), Some(0x76))?, 1.0)
+///     .with_source("aht30", Aht30Sensor::new(bus, None)?, 1.0)
+///     // BME280的湿度精度较低
+///     .with_quantity_weight("bme280", Quantity::Humidity, 0.3)
+///     .with_tolerance(Quantity::Temperature, 1.0)
+///     .with_tolerance(Quantity::Humidity, 5.0);
+/// let status = fused.status();
+/// manager.register("indoor", fused)?;
+/// ```
+pub struct Fusion {
+    /// 参与融合的传感器
+    sources: Vec<Source>,
+    /// 各物理量的允许差值
+    tolerances: BTreeMap<Quantity, f64>,
+    /// 连续读取失败多少次后排除
+    failure_limit: u32,
+    /// 排除后多久重试
+    retry_after: Duration,
+    /// 融合状态
+    status: FusionStatusHandle,
+}
+
+impl Default for Fusion {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Fusion {
+    /// 创建实例（连续失败3次后排除，60秒后重试）
+    pub fn new() -> Self {
+        Self {
+            sources: Vec::new(),
+            tolerances: BTreeMap::new(),
+            failure_limit: 3,
+            retry_after: Duration::from_secs(60),
+            status: FusionStatusHandle::default(),
+        }
+    }
+
+    /// 添加传感器
+    ///
+    /// - weight: 权重（精度越高权重越大，为0时只参与一致性检查）
+    pub fn with_source<S: Sensor + 'static>(mut self, name: &str, sensor: S, weight: f64) -> Self {
+        self.sources.push(Source {
+            name: name.to_string(),
+            sensor: Box::new(sensor),
+            weight: weight.max(0.0),
+            quantity_weights: BTreeMap::new(),
+            failures: 0,
+            retry_at: None,
+        });
+        self
+    }
+
+    /// 设置传感器某个物理量的权重
+    pub fn with_quantity_weight(mut self, name: &str, quantity: Quantity, weight: f64) -> Self {
+        if let Some(source) = self.sources.iter_mut().find(|source| source.name == name) {
+            source.quantity_weights.insert(quantity, weight.max(0.0));
+        }
+        self
+    }
+
+    /// 设置物理量的允许差值（超过时标记为不一致）
+    pub fn with_tolerance(mut self, quantity: Quantity, tolerance: f64) -> Self {
+        self.tolerances.insert(quantity, tolerance);
+        self
+    }
+
+    /// 设置连续读取失败多少次后排除
+    pub fn with_failure_limit(mut self, failure_limit: u32) -> Self {
+        self.failure_limit = failure_limit.max(1);
+        self
+    }
+
+    /// 设置排除后多久重试
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = retry_after;
+        self
+    }
+
+    /// 融合状态
+    pub fn status(&self) -> FusionStatusHandle {
+        self.status.clone()
+    }
+}
+
+impl Sensor for Fusion {
+    fn kind(&self) -> &'static str {
+        "fusion"
+    }
+
+    fn min_interval(&self) -> Duration {
+        self.sources
+            .iter()
+            .map(|source| source.sensor.min_interval())
+            .max()
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// 依次自检所有传感器，合并为一份报告
+    fn self_test(&mut self) -> DiagnosticsReport {
+        let mut report = DiagnosticsReport::new("fusion");
+        for source in &mut self.sources {
+            for check in source.sensor.self_test().checks {
+                report.checks.push(Check {
+                    name: format!("{}.{}", source.name, check.name),
+                    ..check
+                });
+            }
+        }
+        report
+    }
+
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        let now = Instant::now();
+        let mut values: BTreeMap<Quantity, Vec<(usize, f64)>> = BTreeMap::new();
+        let mut states = Vec::with_capacity(self.sources.len());
+        let mut errors = Vec::new();
+        for (index, source) in self.sources.iter_mut().enumerate() {
+            let mut last_error = None;
+            // 被排除的传感器到达重试时间前跳过
+            if source.retry_at.is_none_or(|at| now >= at) {
+                match source.sensor.read() {
+                    Ok(reading) => {
+                        if source.retry_at.take().is_some() {
+                            trace_event!(info, sensor = %source.name, "传感器恢复，重新参与融合");
+                        }
+                        source.failures = 0;
+                        for (quantity, value) in reading.iter() {
+                            values.entry(quantity).or_default().push((index, value));
+                        }
+                    }
+                    Err(err) => {
+                        source.failures += 1;
+                        if source.failures >= self.failure_limit {
+                            if source.retry_at.is_none() {
+                                trace_event!(warn, sensor = %source.name, error = %err, "传感器连续读取失败，已排除");
+                                eprintln!("传感器{}连续读取失败，已排除: {}", source.name, err);
+                            }
+                            source.retry_at = Some(now + self.retry_after);
+                        }
+                        errors.push(format!("{}: {}", source.name, err));
+                        last_error = Some(err.to_string());
+                    }
+                }
+            }
+            states.push(SourceState {
+                name: source.name.clone(),
+                failures: source.failures,
+                excluded: source.retry_at.is_some(),
+                last_error,
+            });
+        }
+
+        let mut reading = Reading::new();
+        let mut disagreements = Vec::new();
+        for (quantity, values) in &values {
+            // 加权平均，所有权重为0时取算术平均
+            let total_weight: f64 = values
+                .iter()
+                .map(|(index, _)| self.sources[*index].weight(*quantity))
+                .sum();
+            let value = if total_weight > 0.0 {
+                values
+                    .iter()
+                    .map(|(index, value)| value * self.sources[*index].weight(*quantity))
+                    .sum::<f64>()
+                    / total_weight
+            } else {
+                values.iter().map(|(_, value)| value).sum::<f64>() / values.len() as f64
+            };
+            reading.set(*quantity, value);
+
+            if let Some(tolerance) = self.tolerances.get(quantity) {
+                let max = values.iter().map(|(_, v)| *v).fold(f64::MIN, f64::max);
+                let min = values.iter().map(|(_, v)| *v).fold(f64::MAX, f64::min);
+                if max - min > *tolerance {
+                    trace_event!(warn, quantity = %quantity, spread = max - min, "冗余传感器读数不一致");
+                    disagreements.push(Disagreement {
+                        quantity: *quantity,
+                        spread: max - min,
+                        values: values
+                            .iter()
+                            .map(|(index, value)| (self.sources[*index].name.clone(), *value))
+                            .collect(),
+                    });
+                }
+            }
+        }
+
+        if let Ok(mut status) = self.status.status.lock() {
+            *status = FusionStatus {
+                sources: states,
+                disagreements,
+            };
+        }
+        if reading.is_empty() {
+            return Err(anyhow::anyhow!(
+                "没有可用的传感器: {}",
+                if errors.is_empty() {
+                    "全部已排除".to_string()
+                } else {
+                    errors.join("; ")
+                }
+            ));
+        }
+        // OK
+        Ok(reading)
+    }
+}
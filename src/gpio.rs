@@ -0,0 +1,364 @@
+//! GPIO后端
+//!
+//! 默认使用rppal（通过/dev/gpiomem直接访问寄存器）；启用`gpiocdev`功能后改为使用
+//! Linux GPIO字符设备（/dev/gpiochipN），适用于容器中没有/dev/gpiomem、树莓派5或其他开发板。
+//! 两种后端的引脚都实现了embedded-hal的`OutputPin`、`InputPin`，驱动只依赖这两个接口
+
+use embedded_hal::digital::PinState;
+
+/// 输入引脚内部上下拉
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pull {
+    /// 浮空
+    None,
+    /// 上拉
+    Up,
+    /// 下拉
+    Down,
+}
+
+impl Pull {
+    /// 按名称解析（none、up、down）
+    pub fn parse(name: &str) -> anyhow::Result<Self> {
+        match name {
+            "none" | "off" => Ok(Pull::None),
+            "up" => Ok(Pull::Up),
+            "down" => Ok(Pull::Down),
+            _ => Err(anyhow::anyhow!("上下拉设置无效: {}", name)),
+        }
+    }
+
+    /// 对应的rppal上下拉设置
+    pub fn bias(self) -> rppal::gpio::Bias {
+        match self {
+            Pull::None => rppal::gpio::Bias::Off,
+            Pull::Up => rppal::gpio::Bias::PullUp,
+            Pull::Down => rppal::gpio::Bias::PullDown,
+        }
+    }
+}
+
+/// 引脚驱动能力（电流和边沿速率）
+///
+/// BCM283x/2711的驱动能力按引脚组设置（GPIO0~27为一组），修改一个引脚会影响同组所有引脚；
+/// 默认值与上电时相同。长线缆上的时钟信号（如HX711的PD_SCK）可以限制边沿速率减少振铃，
+/// 负载较重时提高驱动电流
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Drive {
+    /// 驱动电流（mA，2~16之间的偶数）
+    pub strength_ma: u8,
+    /// 是否限制边沿速率
+    pub slew_limited: bool,
+    /// 输入是否启用施密特触发（迟滞）
+    pub hysteresis: bool,
+}
+
+impl Default for Drive {
+    fn default() -> Self {
+        Self {
+            strength_ma: 8,
+            slew_limited: false,
+            hysteresis: true,
+        }
+    }
+}
+
+impl Drive {
+    /// 检查驱动电流是否有效
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !(2..=16).contains(&self.strength_ma) || !self.strength_ma.is_multiple_of(2) {
+            return Err(anyhow::anyhow!(
+                "驱动电流无效: {}mA（应为2~16之间的偶数）",
+                self.strength_ma
+            ));
+        }
+        // OK
+        Ok(())
+    }
+}
+
+/// 设置引脚所在组的驱动能力（需要root权限访问/dev/mem，树莓派5不支持）
+///
+/// - pin: BCM编号
+/// - drive: 驱动能力
+pub fn set_drive(pin: u8, drive: Drive) -> anyhow::Result<()> {
+    drive.validate()?;
+    pads::write(pin, drive)?;
+    trace_event!(
+        debug,
+        pin,
+        strength_ma = drive.strength_ma,
+        slew_limited = drive.slew_limited,
+        "设置GPIO驱动能力"
+    );
+    // OK
+    Ok(())
+}
+
+/// 输出引脚（具体类型由GPIO后端决定）
+#[cfg(not(feature = "gpiocdev"))]
+pub type OutputPin = rppal::gpio::OutputPin;
+/// 输入引脚（具体类型由GPIO后端决定）
+#[cfg(not(feature = "gpiocdev"))]
+pub type InputPin = rppal::gpio::InputPin;
+
+#[cfg(feature = "gpiocdev")]
+pub use cdev::{CdevInputPin as InputPin, CdevOutputPin as OutputPin, set_chip};
+
+/// 当前使用的GPIO后端名称
+pub fn backend() -> &'static str {
+    if cfg!(feature = "gpiocdev") {
+        "gpiocdev"
+    } else {
+        "rppal"
+    }
+}
+
+/// 获取输出引脚
+///
+/// - pin: BCM编号
+/// - high: 初始电平
+#[cfg(not(feature = "gpiocdev"))]
+pub fn output(pin: u8, high: bool) -> anyhow::Result<OutputPin> {
+    let pin = rppal::gpio::Gpio::new()?.get(pin)?;
+    Ok(if high {
+        pin.into_output_high()
+    } else {
+        pin.into_output_low()
+    })
+}
+
+/// 获取输入引脚
+///
+/// - pin: BCM编号
+/// - pull: 内部上下拉
+#[cfg(not(feature = "gpiocdev"))]
+pub fn input(pin: u8, pull: Pull) -> anyhow::Result<InputPin> {
+    let pin = rppal::gpio::Gpio::new()?.get(pin)?;
+    Ok(match pull {
+        Pull::None => pin.into_input(),
+        Pull::Up => pin.into_input_pullup(),
+        Pull::Down => pin.into_input_pulldown(),
+    })
+}
+
+#[cfg(feature = "gpiocdev")]
+pub use cdev::{input, output};
+
+/// 设置输出电平
+pub fn write(pin: &mut OutputPin, high: bool) -> anyhow::Result<()> {
+    // rppal的引脚有同名的固有方法，需要指定通过embedded-hal接口调用
+    embedded_hal::digital::OutputPin::set_state(pin, PinState::from(high))
+        .map_err(|err| anyhow::anyhow!("设置GPIO输出电平失败: {:?}", err))
+}
+
+/// 读取输入电平
+pub fn read(pin: &mut InputPin) -> anyhow::Result<bool> {
+    embedded_hal::digital::InputPin::is_high(pin)
+        .map_err(|err| anyhow::anyhow!("读取GPIO输入电平失败: {:?}", err))
+}
+
+#[cfg(feature = "gpiocdev")]
+mod cdev {
+    use gpio_cdev::{Chip, LineHandle, LineRequestFlags};
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    use super::Pull;
+
+    /// 申请引脚时的使用者名称（显示在gpioinfo中）
+    const CONSUMER: &str = "raspi-sensor";
+
+    /// 树莓派排针所在GPIO控制器的标签（树莓派5为RP1，其他型号为BCM283x/2711）
+    const HEADER_LABELS: [&str; 3] = ["pinctrl-rp1", "pinctrl-bcm2711", "pinctrl-bcm2835"];
+
+    /// 手动指定的GPIO控制器
+    static CHIP_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+    /// 指定GPIO控制器（如/dev/gpiochip4），不指定时按标签查找树莓派排针所在的控制器
+    pub fn set_chip<P: Into<PathBuf>>(path: P) {
+        if let Ok(mut chip) = CHIP_PATH.lock() {
+            *chip = Some(path.into());
+        }
+    }
+
+    /// 打开GPIO控制器
+    fn open_chip() -> anyhow::Result<Chip> {
+        let path = CHIP_PATH.lock().ok().and_then(|chip| chip.clone());
+        if let Some(path) = path {
+            return Chip::new(&path)
+                .map_err(|err| anyhow::anyhow!("打开GPIO控制器{}失败: {}", path.display(), err));
+        }
+        for chip in gpio_cdev::chips()?.flatten() {
+            if HEADER_LABELS.contains(&chip.label()) {
+                return Ok(chip);
+            }
+        }
+        // 其他开发板使用第一个控制器
+        Chip::new("/dev/gpiochip0").map_err(|err| anyhow::anyhow!("打开GPIO控制器失败: {}", err))
+    }
+
+    /// 申请引脚
+    fn request(pin: u8, flags: LineRequestFlags, default: u8) -> anyhow::Result<LineHandle> {
+        open_chip()?
+            .get_line(pin as u32)?
+            .request(flags, default, CONSUMER)
+            .map_err(|err| anyhow::anyhow!("申请GPIO{}失败: {}", pin, err))
+    }
+
+    /// GPIO字符设备错误
+    #[derive(Debug)]
+    pub struct CdevError(gpio_cdev::Error);
+
+    impl std::fmt::Display for CdevError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            self.0.fmt(f)
+        }
+    }
+
+    impl std::error::Error for CdevError {}
+
+    impl embedded_hal::digital::Error for CdevError {
+        fn kind(&self) -> embedded_hal::digital::ErrorKind {
+            embedded_hal::digital::ErrorKind::Other
+        }
+    }
+
+    /// 字符设备输出引脚
+    pub struct CdevOutputPin {
+        handle: LineHandle,
+    }
+
+    /// 获取输出引脚
+    ///
+    /// - pin: 控制器内的引脚编号（树莓派上与BCM编号相同）
+    /// - high: 初始电平
+    pub fn output(pin: u8, high: bool) -> anyhow::Result<CdevOutputPin> {
+        let handle = request(pin, LineRequestFlags::OUTPUT, high as u8)?;
+        // OK
+        Ok(CdevOutputPin { handle })
+    }
+
+    impl embedded_hal::digital::ErrorType for CdevOutputPin {
+        type Error = CdevError;
+    }
+
+    impl embedded_hal::digital::OutputPin for CdevOutputPin {
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            self.handle.set_value(0).map_err(CdevError)
+        }
+
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            self.handle.set_value(1).map_err(CdevError)
+        }
+    }
+
+    impl embedded_hal::digital::StatefulOutputPin for CdevOutputPin {
+        fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+            Ok(self.handle.get_value().map_err(CdevError)? != 0)
+        }
+
+        fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+            Ok(self.handle.get_value().map_err(CdevError)? == 0)
+        }
+    }
+
+    /// 字符设备输入引脚
+    pub struct CdevInputPin {
+        handle: LineHandle,
+    }
+
+    /// 获取输入引脚
+    ///
+    /// 字符设备v1接口不支持设置内部上下拉，需要上下拉时通过设备树配置（如config.txt中的`gpio=17=ip,pu`）或外接电阻
+    pub fn input(pin: u8, pull: Pull) -> anyhow::Result<CdevInputPin> {
+        if pull != Pull::None {
+            trace_event!(warn, pin, "gpiocdev后端忽略内部上下拉设置");
+        }
+        let handle = request(pin, LineRequestFlags::INPUT, 0)?;
+        // OK
+        Ok(CdevInputPin { handle })
+    }
+
+    impl embedded_hal::digital::ErrorType for CdevInputPin {
+        type Error = CdevError;
+    }
+
+    impl embedded_hal::digital::InputPin for CdevInputPin {
+        fn is_high(&mut self) -> Result<bool, Self::Error> {
+            Ok(self.handle.get_value().map_err(CdevError)? != 0)
+        }
+
+        fn is_low(&mut self) -> Result<bool, Self::Error> {
+            Ok(self.handle.get_value().map_err(CdevError)? == 0)
+        }
+    }
+}
+
+/// BCM283x/2711的PADS寄存器（驱动能力、边沿速率、迟滞）
+mod pads {
+    use std::fs::{self, OpenOptions};
+    use std::os::unix::fs::FileExt;
+
+    use super::Drive;
+    use crate::board::{Board, Soc};
+
+    /// PADS控制寄存器相对外设基地址的偏移（GPIO0~27、28~45、46~53各一个）
+    const PADS_OFFSET: u64 = 0x10_002C;
+
+    /// 写入寄存器时必须带上的密码
+    const PASSWORD: u32 = 0x5A00_0000;
+
+    /// 引脚所在的组
+    fn bank(pin: u8) -> anyhow::Result<u64> {
+        match pin {
+            0..=27 => Ok(0),
+            28..=45 => Ok(1),
+            46..=53 => Ok(2),
+            _ => Err(anyhow::anyhow!("GPIO编号无效: {}", pin)),
+        }
+    }
+
+    /// 外设物理基地址（设备树soc/ranges中的父地址，BCM2711为64位地址的低32位所在的第三个单元）
+    fn peripheral_base() -> anyhow::Result<u64> {
+        let ranges = fs::read("/proc/device-tree/soc/ranges")
+            .map_err(|err| anyhow::anyhow!("读取设备树soc/ranges失败: {}", err))?;
+        let cell = |index: usize| {
+            ranges
+                .get(index * 4..index * 4 + 4)
+                .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        };
+        match (cell(1), cell(2)) {
+            (Some(base), _) if base != 0 => Ok(base as u64),
+            (_, Some(base)) if base != 0 => Ok(base as u64),
+            _ => Err(anyhow::anyhow!("无法从设备树获取外设基地址")),
+        }
+    }
+
+    /// 寄存器的值
+    fn encode(drive: Drive) -> u32 {
+        PASSWORD
+            | (!drive.slew_limited as u32) << 4
+            | (drive.hysteresis as u32) << 3
+            | (drive.strength_ma / 2 - 1) as u32
+    }
+
+    /// 写入引脚所在组的PADS寄存器
+    pub fn write(pin: u8, drive: Drive) -> anyhow::Result<()> {
+        match Board::detect().soc() {
+            Soc::Bcm2835 | Soc::Bcm2711 => {}
+            Soc::Rp1 => return Err(anyhow::anyhow!("树莓派5不支持设置GPIO驱动能力")),
+            Soc::Unknown => return Err(anyhow::anyhow!("非树莓派开发板不支持设置GPIO驱动能力")),
+        }
+        let address = peripheral_base()? + PADS_OFFSET + bank(pin)? * 4;
+        let mem = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/mem")
+            .map_err(|err| anyhow::anyhow!("打开/dev/mem失败（需要root权限）: {}", err))?;
+        mem.write_at(&encode(drive).to_le_bytes(), address)
+            .map_err(|err| anyhow::anyhow!("写入GPIO{}的PADS寄存器失败: {}", pin, err))?;
+        // OK
+        Ok(())
+    }
+}
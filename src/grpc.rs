@@ -0,0 +1,223 @@
+//! 传感器节点gRPC服务
+//!
+//! 接口定义见`proto/raspi_sensor.proto`，构建时由tonic生成代码。适合把树莓派节点接入
+//! 已有的微服务集群（不使用MQTT时），读数来源与HTTP服务器相同（调度器订阅或读数分发管道）
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status};
+
+use crate::actuator::ActuatorRegistry;
+use crate::reading::Sample;
+use crate::sink::Sink;
+
+/// 由proto生成的消息和服务
+pub mod proto {
+    tonic::include_proto!("raspi_sensor.v1");
+}
+
+use proto::sensor_service_server::{SensorService, SensorServiceServer};
+
+/// 实时推送的缓冲读数数量（客户端处理不及时时丢弃最早的读数）
+const STREAM_CAPACITY: usize = 256;
+
+impl From<&Sample> for proto::Sample {
+    fn from(sample: &Sample) -> Self {
+        Self {
+            sensor: sample.sensor.clone(),
+            timestamp: sample.unix_timestamp(),
+            values: sample
+                .reading
+                .iter()
+                .map(|(quantity, value)| (quantity.name().to_string(), value))
+                .collect(),
+        }
+    }
+}
+
+/// 传感器gRPC服务（可克隆，克隆后共享同一份最新读数）
+///
+/// ```ignore
+/// let actuators = ActuatorRegistry::new();
+/// actuators.add_switch("pump", GpioSwitch::new(22, false)?)?;
+/// let grpc = GrpcServer::new().with_actuators(actuators);
+/// grpc.record_from(scheduler.subscribe());
+/// grpc.serve("0.0.0.0:50051")?.join().ok();
+/// ```
+#[derive(Clone)]
+pub struct GrpcServer {
+    /// 各传感器的最新读数
+    latest: Arc<Mutex<BTreeMap<String, Sample>>>,
+    /// 实时推送
+    updates: broadcast::Sender<Sample>,
+    /// 可控制的执行器（None时InvokeActuator返回未实现）
+    actuators: Option<ActuatorRegistry>,
+}
+
+impl Default for GrpcServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GrpcServer {
+    /// 创建实例
+    pub fn new() -> Self {
+        let (updates, _) = broadcast::channel(STREAM_CAPACITY);
+        Self {
+            latest: Arc::new(Mutex::new(BTreeMap::new())),
+            updates,
+            actuators: None,
+        }
+    }
+
+    /// 允许通过InvokeActuator控制注册表中的执行器
+    pub fn with_actuators(mut self, actuators: ActuatorRegistry) -> Self {
+        self.actuators = Some(actuators);
+        self
+    }
+
+    /// 记录一个新读数并推送给订阅的客户端
+    pub fn record(&self, sample: Sample) {
+        // 没有客户端订阅时发送失败，忽略
+        let _ = self.updates.send(sample.clone());
+        if let Ok(mut latest) = self.latest.lock() {
+            latest.insert(sample.sensor.clone(), sample);
+        }
+    }
+
+    /// 在后台线程中记录收到的所有读数（如调度器的订阅），通道关闭后线程退出
+    pub fn record_from(&self, samples: Receiver<Sample>) -> JoinHandle<()> {
+        let this = self.clone();
+        thread::spawn(move || {
+            for sample in samples {
+                this.record(sample);
+            }
+        })
+    }
+
+    /// 在后台线程中启动gRPC服务
+    ///
+    /// - addr: 监听地址（如"0.0.0.0:50051"）
+    pub fn serve(&self, addr: &str) -> anyhow::Result<JoinHandle<()>> {
+        let addr: SocketAddr = addr
+            .parse()
+            .map_err(|err| anyhow::anyhow!("gRPC监听地址无效: {}: {}", addr, err))?;
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let service = SensorServiceServer::new(self.clone());
+        // OK
+        Ok(thread::spawn(move || {
+            let result = runtime.block_on(
+                tonic::transport::Server::builder()
+                    .add_service(service)
+                    .serve(addr),
+            );
+            if let Err(err) = result {
+                eprintln!("gRPC服务异常退出: {}: {}", addr, err);
+            }
+        }))
+    }
+}
+
+impl Sink for GrpcServer {
+    fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        self.record(sample.clone());
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl SensorService for GrpcServer {
+    type StreamSamplesStream =
+        Pin<Box<dyn Stream<Item = Result<proto::Sample, Status>> + Send + 'static>>;
+
+    async fn list_sensors(
+        &self,
+        _request: Request<proto::ListSensorsRequest>,
+    ) -> Result<Response<proto::ListSensorsResponse>, Status> {
+        let sensors = self
+            .latest
+            .lock()
+            .map_err(|_| Status::internal("读数状态锁已损坏"))?
+            .iter()
+            .map(|(name, sample)| proto::Sensor {
+                name: name.clone(),
+                latest: Some(sample.into()),
+            })
+            .collect();
+        let actuators = self
+            .actuators
+            .as_ref()
+            .map(|actuators| actuators.list())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, kind)| proto::Actuator {
+                name,
+                kind: kind.name().to_string(),
+            })
+            .collect();
+        Ok(Response::new(proto::ListSensorsResponse {
+            sensors,
+            actuators,
+        }))
+    }
+
+    async fn get_latest(
+        &self,
+        request: Request<proto::GetLatestRequest>,
+    ) -> Result<Response<proto::Sample>, Status> {
+        let sensor = request.into_inner().sensor;
+        let latest = self
+            .latest
+            .lock()
+            .map_err(|_| Status::internal("读数状态锁已损坏"))?;
+        let sample = latest
+            .get(&sensor)
+            .ok_or_else(|| Status::not_found(format!("传感器不存在或还没有读数: {}", sensor)))?;
+        Ok(Response::new(sample.into()))
+    }
+
+    async fn stream_samples(
+        &self,
+        request: Request<proto::StreamSamplesRequest>,
+    ) -> Result<Response<Self::StreamSamplesStream>, Status> {
+        let sensor = request.into_inner().sensor;
+        let stream = BroadcastStream::new(self.updates.subscribe()).filter_map(move |sample| {
+            // 客户端处理不及时时跳过丢弃的读数
+            let sample = sample.ok()?;
+            if !sensor.is_empty() && sample.sensor != sensor {
+                return None;
+            }
+            Some(Ok(proto::Sample::from(&sample)))
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn invoke_actuator(
+        &self,
+        request: Request<proto::InvokeActuatorRequest>,
+    ) -> Result<Response<proto::InvokeActuatorResponse>, Status> {
+        let Some(actuators) = self.actuators.clone() else {
+            return Err(Status::unimplemented("没有可控制的执行器"));
+        };
+        let proto::InvokeActuatorRequest { name, command } = request.into_inner();
+        // 步进电机运行时间较长，在阻塞线程中执行
+        let result = tokio::task::spawn_blocking(move || actuators.invoke(&name, &command))
+            .await
+            .map_err(|err| Status::internal(format!("执行器线程异常退出: {}", err)))?;
+        match result {
+            Ok(state) => Ok(Response::new(proto::InvokeActuatorResponse { state })),
+            Err(err) => Err(Status::failed_precondition(err.to_string())),
+        }
+    }
+}
@@ -0,0 +1,346 @@
+//! 传感器健康统计
+//!
+//! 管理器每次实际读取传感器（不包括返回缓存的读数）后更新`HealthRegistry`中的计数：
+//! 读取次数、按类型分类的失败次数、连续失败次数和最近一次读取成功的时间。
+//! 注册表可以克隆，HTTP服务、看门狗等通过它查看传感器的可靠性，
+//! 或找出长时间没有读取成功的传感器（如"10分钟没有读数"告警）
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+use crate::alerts::AlertState;
+use crate::rate_limit::TooSoon;
+
+/// 读取失败的类型
+///
+/// 驱动的错误在转换为`anyhow::Error`时已格式化为文本，按错误信息中的关键字分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum FailureKind {
+    /// 等待响应或数据就绪超时
+    Timeout,
+    /// 数据校验失败（校验和、CRC）
+    Checksum,
+    /// 总线通信错误（I2C无应答、SPI错误等）
+    Bus,
+    /// 设备不存在或芯片ID不符
+    Missing,
+    /// 其他错误
+    Other,
+}
+
+impl FailureKind {
+    /// 所有类型
+    pub const ALL: [FailureKind; 5] = [
+        FailureKind::Timeout,
+        FailureKind::Checksum,
+        FailureKind::Bus,
+        FailureKind::Missing,
+        FailureKind::Other,
+    ];
+
+    /// 根据错误信息分类
+    pub fn classify(error: &anyhow::Error) -> Self {
+        let text = format!("{:#}", error);
+        let has = |keywords: &[&str]| keywords.iter().any(|keyword| text.contains(keyword));
+        if has(&["Timeout", "超时", "timed out"]) {
+            FailureKind::Timeout
+        } else if has(&["Checksum", "校验", "CRC"]) {
+            FailureKind::Checksum
+        } else if has(&["ChipId", "不存在", "没有找到", "No such"]) {
+            FailureKind::Missing
+        } else if has(&["Bus(", "总线", "I2C", "SPI", "Nack"]) {
+            FailureKind::Bus
+        } else {
+            FailureKind::Other
+        }
+    }
+
+    /// 名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            FailureKind::Timeout => "timeout",
+            FailureKind::Checksum => "checksum",
+            FailureKind::Bus => "bus",
+            FailureKind::Missing => "missing",
+            FailureKind::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for FailureKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// 单个传感器的健康统计
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SensorHealth {
+    /// 实际读取次数
+    pub reads: u64,
+    /// 读取成功次数
+    pub successes: u64,
+    /// 各类型的失败次数
+    pub failures: BTreeMap<FailureKind, u64>,
+    /// 连续失败次数
+    pub consecutive_failures: u32,
+    /// 开始统计的时间
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub since: SystemTime,
+    /// 最近一次读取成功的时间
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub last_success: Option<SystemTime>,
+    /// 最近一次读取失败的时间
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub last_failure: Option<SystemTime>,
+    /// 最近一次读取失败的错误信息
+    pub last_error: Option<String>,
+}
+
+impl SensorHealth {
+    /// 创建空的统计
+    fn new(since: SystemTime) -> Self {
+        Self {
+            reads: 0,
+            successes: 0,
+            failures: BTreeMap::new(),
+            consecutive_failures: 0,
+            since,
+            last_success: None,
+            last_failure: None,
+            last_error: None,
+        }
+    }
+
+    /// 失败总次数
+    pub fn failure_count(&self) -> u64 {
+        self.failures.values().sum()
+    }
+
+    /// 读取成功率（还没有读取时为None）
+    pub fn success_rate(&self) -> Option<f64> {
+        (self.reads > 0).then(|| self.successes as f64 / self.reads as f64)
+    }
+
+    /// 距最近一次读取成功的时长（从未成功时从开始统计算起）
+    pub fn silent_for(&self, now: SystemTime) -> Duration {
+        now.duration_since(self.last_success.unwrap_or(self.since))
+            .unwrap_or_default()
+    }
+}
+
+/// 传感器健康统计注册表（可克隆，克隆后共享同一份统计）
+#[derive(Clone, Default)]
+pub struct HealthRegistry {
+    sensors: Arc<Mutex<BTreeMap<String, SensorHealth>>>,
+}
+
+impl HealthRegistry {
+    /// 创建空的注册表
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 更新指定传感器的统计（不存在时从现在开始统计）
+    fn update<R, F: FnOnce(&mut SensorHealth) -> R>(&self, name: &str, f: F) -> R {
+        let mut sensors = match self.sensors.lock() {
+            Ok(sensors) => sensors,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let health = sensors
+            .entry(name.to_string())
+            .or_insert_with(|| SensorHealth::new(SystemTime::now()));
+        f(health)
+    }
+
+    /// 开始统计指定传感器（重复调用不会清空已有统计）
+    pub fn register(&self, name: &str) {
+        self.update(name, |_| {});
+    }
+
+    /// 移除指定传感器的统计
+    pub fn remove(&self, name: &str) {
+        if let Ok(mut sensors) = self.sensors.lock() {
+            sensors.remove(name);
+        }
+    }
+
+    /// 记录一次读取成功
+    pub fn record_success(&self, name: &str) {
+        self.update(name, |health| {
+            health.reads += 1;
+            health.successes += 1;
+            health.consecutive_failures = 0;
+            health.last_success = Some(SystemTime::now());
+        });
+    }
+
+    /// 记录一次读取失败（读取过快的`TooSoon`错误不计入）
+    pub fn record_failure(&self, name: &str, error: &anyhow::Error) {
+        if error.downcast_ref::<TooSoon>().is_some() {
+            return;
+        }
+        let kind = FailureKind::classify(error);
+        self.update(name, |health| {
+            health.reads += 1;
+            *health.failures.entry(kind).or_default() += 1;
+            health.consecutive_failures += 1;
+            health.last_failure = Some(SystemTime::now());
+            health.last_error = Some(error.to_string());
+        });
+    }
+
+    /// 记录一次读取结果
+    pub fn record<T>(&self, name: &str, result: &anyhow::Result<T>) {
+        match result {
+            Ok(_) => self.record_success(name),
+            Err(err) => self.record_failure(name, err),
+        }
+    }
+
+    /// 指定传感器的统计
+    pub fn get(&self, name: &str) -> Option<SensorHealth> {
+        self.sensors.lock().ok()?.get(name).cloned()
+    }
+
+    /// 所有传感器的统计
+    pub fn snapshot(&self) -> BTreeMap<String, SensorHealth> {
+        match self.sensors.lock() {
+            Ok(sensors) => sensors.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// 超过指定时长没有读取成功的传感器及其静默时长
+    pub fn silent(&self, threshold: Duration) -> Vec<(String, Duration)> {
+        let now = SystemTime::now();
+        self.snapshot()
+            .into_iter()
+            .map(|(name, health)| (name, health.silent_for(now)))
+            .filter(|(_, silent)| *silent > threshold)
+            .collect()
+    }
+
+    /// 清空统计（保留已注册的传感器）
+    pub fn reset(&self) {
+        let now = SystemTime::now();
+        if let Ok(mut sensors) = self.sensors.lock() {
+            for health in sensors.values_mut() {
+                *health = SensorHealth::new(now);
+            }
+        }
+    }
+
+    /// 在后台线程中定期检查，传感器超过指定时长没有读取成功时触发静默告警，
+    /// 重新读取成功后解除
+    ///
+    /// ```ignore
+    /// let monitor = manager.health().monitor(Duration::from_secs(600), Duration::from_secs(30));
+    /// for event in monitor.subscribe() {
+    ///     println!("{}: {:?}", event.sensor, event.state);
+    /// }
+    /// ```
+    pub fn monitor(&self, threshold: Duration, interval: Duration) -> SilenceMonitor {
+        let registry = self.clone();
+        let subscribers: Arc<Mutex<Vec<Sender<SilenceEvent>>>> = Arc::default();
+        let shared = subscribers.clone();
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let thread = thread::spawn(move || {
+            let mut silent: BTreeMap<String, bool> = BTreeMap::new();
+            loop {
+                let now = SystemTime::now();
+                let sensors = registry.snapshot();
+                // 已移除的传感器不再跟踪
+                silent.retain(|name, _| sensors.contains_key(name));
+                for (name, health) in sensors {
+                    let silent_for = health.silent_for(now);
+                    let is_silent = silent_for > threshold;
+                    let was_silent = silent.insert(name.clone(), is_silent).unwrap_or(false);
+                    if is_silent == was_silent {
+                        continue;
+                    }
+                    let state = if is_silent {
+                        trace_event!(warn, sensor = %name, silent_secs = silent_for.as_secs(), "传感器长时间没有读取成功");
+                        AlertState::Raised
+                    } else {
+                        trace_event!(info, sensor = %name, "传感器恢复读取");
+                        AlertState::Cleared
+                    };
+                    let event = SilenceEvent {
+                        sensor: name,
+                        silent_for,
+                        state,
+                        timestamp: now,
+                    };
+                    if let Ok(mut subscribers) = shared.lock() {
+                        subscribers.retain(|tx| tx.send(event.clone()).is_ok());
+                    }
+                }
+                match stop_rx.recv_timeout(interval) {
+                    Err(RecvTimeoutError::Timeout) => {}
+                    _ => break,
+                }
+            }
+        });
+        SilenceMonitor {
+            subscribers,
+            stop_tx,
+            thread,
+        }
+    }
+}
+
+impl fmt::Debug for HealthRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.snapshot()).finish()
+    }
+}
+
+/// 静默状态变化
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SilenceEvent {
+    /// 传感器名称
+    pub sensor: String,
+    /// 距最近一次读取成功的时长
+    pub silent_for: Duration,
+    /// 触发（超过时长没有读取成功）或解除（重新读取成功）
+    pub state: AlertState,
+    /// 检查时间
+    #[cfg_attr(feature = "serde", serde(with = "crate::reading::unix_seconds"))]
+    pub timestamp: SystemTime,
+}
+
+/// 运行中的静默检查
+pub struct SilenceMonitor {
+    /// 事件订阅者
+    subscribers: Arc<Mutex<Vec<Sender<SilenceEvent>>>>,
+    /// 停止信号
+    stop_tx: Sender<()>,
+    /// 检查线程
+    thread: JoinHandle<()>,
+}
+
+impl SilenceMonitor {
+    /// 订阅静默状态变化
+    pub fn subscribe(&self) -> Receiver<SilenceEvent> {
+        let (tx, rx) = mpsc::channel();
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push(tx);
+        }
+        rx
+    }
+
+    /// 停止检查并等待检查线程退出
+    pub fn stop(self) {
+        let _ = self.stop_tx.send(());
+        let _ = self.thread.join();
+    }
+}
@@ -0,0 +1,430 @@
+//! 共享I2C总线、总线健康监测和故障恢复
+//!
+//! `SharedBus`由同一总线上的多个设备共享，持有锁的线程panic后自动恢复，不会让其他设备
+//! 一直返回"I2C通信总线繁忙"。
+//!
+//! 从机在传输中途复位或受到干扰时可能一直拉低SDA，此后总线上所有设备都返回无应答或超时，
+//! 共享同一个`Arc<Mutex<I2c>>`的传感器全部读取失败，直到重启。`I2cBusMonitor`统计
+//! 调度器报告的读取结果，连续出现总线错误时自动恢复总线：手动输出SCL时钟让从机释放SDA、
+//! 发出STOP条件，然后重新打开I2C设备
+
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+use rppal::gpio::{Gpio, Mode};
+use rppal::i2c::I2c;
+
+use crate::reading::Sample;
+use crate::scheduler::ReadError;
+
+/// 默认连续总线错误次数阈值
+const DEFAULT_THRESHOLD: u32 = 3;
+
+/// 恢复时输出的最大时钟数（从机最多还需要发送8位数据和1位应答）
+const RECOVERY_CLOCKS: u32 = 9;
+
+/// 恢复时的时钟半周期（约50kHz）
+const HALF_PERIOD: Duration = Duration::from_micros(10);
+
+/// 共享的I2C通信总线
+///
+/// 默认为rppal的I2C总线，也可以是其他实现了embedded-hal I2C接口的总线（如回放记录的总线）
+pub struct SharedBus<I = I2c> {
+    bus: Arc<Mutex<I>>,
+}
+
+impl<I> Clone for SharedBus<I> {
+    fn clone(&self) -> Self {
+        Self {
+            bus: self.bus.clone(),
+        }
+    }
+}
+
+impl<I> From<Arc<Mutex<I>>> for SharedBus<I> {
+    fn from(bus: Arc<Mutex<I>>) -> Self {
+        Self { bus }
+    }
+}
+
+impl<I> SharedBus<I> {
+    /// 包装总线
+    pub fn new(i2c: I) -> Self {
+        Self {
+            bus: Arc::new(Mutex::new(i2c)),
+        }
+    }
+
+    /// 锁定总线（锁已损坏时清除损坏状态后继续使用）
+    pub fn lock(&self) -> MutexGuard<'_, I> {
+        self.bus.lock().unwrap_or_else(|poisoned| {
+            trace_event!(warn, "I2C总线锁已损坏，已恢复");
+            self.bus.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+
+    /// 锁定总线后执行操作
+    pub fn transaction<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut I) -> R,
+    {
+        f(&mut self.lock())
+    }
+
+    /// 底层的`Arc<Mutex<I>>`（供尚未迁移的驱动使用）
+    pub fn inner(&self) -> &Arc<Mutex<I>> {
+        &self.bus
+    }
+}
+
+impl<I: embedded_hal::i2c::I2c> SharedBus<I> {
+    /// 从指定寄存器开始连续读取（写入寄存器地址后读取，数据直接写入`buffer`）
+    ///
+    /// - address: 设备地址
+    /// - start: 起始寄存器
+    /// - buffer: 读取的数据
+    pub fn read_registers(&self, address: u8, start: u8, buffer: &mut [u8]) -> anyhow::Result<()> {
+        self.lock()
+            .write_read(address, &[start], buffer)
+            .map_err(|err| {
+                anyhow::anyhow!(
+                    "读取0x{:02X}的寄存器0x{:02X}失败: {:?}",
+                    address,
+                    start,
+                    err
+                )
+            })
+    }
+
+    /// 写入单个寄存器
+    ///
+    /// - address: 设备地址
+    /// - register: 寄存器
+    /// - value: 写入的值
+    pub fn write_register(&self, address: u8, register: u8, value: u8) -> anyhow::Result<()> {
+        self.lock()
+            .write(address, &[register, value])
+            .map_err(|err| {
+                anyhow::anyhow!(
+                    "写入0x{:02X}的寄存器0x{:02X}失败: {:?}",
+                    address,
+                    register,
+                    err
+                )
+            })
+    }
+}
+
+impl SharedBus<I2c> {
+    /// 打开I2C总线
+    pub fn open(bus: u8) -> anyhow::Result<Self> {
+        let i2c =
+            I2c::with_bus(bus).map_err(|err| anyhow::anyhow!("打开I2C总线{}失败: {}", bus, err))?;
+        // OK
+        Ok(Self::new(i2c))
+    }
+
+    /// 锁定总线并切换到指定设备地址后执行操作
+    ///
+    /// 其他设备可能在两次操作之间修改了从机地址，每次操作前都重新设置
+    pub fn transaction_at<R, F>(&self, address: u16, f: F) -> anyhow::Result<R>
+    where
+        F: FnOnce(&mut I2c) -> anyhow::Result<R>,
+    {
+        let mut i2c = self.lock();
+        i2c.set_slave_address(address)?;
+        f(&mut i2c)
+    }
+}
+
+/// 总线错误类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BusError {
+    /// 从机无应答（EREMOTEIO）
+    Nack,
+    /// 传输超时（ETIMEDOUT）
+    Timeout,
+    /// 总线锁已损坏（持有锁的线程异常退出）
+    Poisoned,
+}
+
+impl BusError {
+    /// 根据错误信息判断是否为总线错误（校验失败等设备层面的错误返回None）
+    pub fn classify(message: &str) -> Option<Self> {
+        let message = message.to_lowercase();
+        if message.contains("remote i/o")
+            || message.contains("os error 121")
+            || message.contains("nack")
+            || message.contains("无应答")
+        {
+            Some(BusError::Nack)
+        } else if message.contains("timed out")
+            || message.contains("os error 110")
+            || message.contains("超时")
+        {
+            Some(BusError::Timeout)
+        } else if message.contains("poison") || message.contains("总线繁忙") {
+            Some(BusError::Poisoned)
+        } else {
+            None
+        }
+    }
+}
+
+/// 总线状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum BusState {
+    /// 正常
+    Healthy,
+    /// 出现总线错误，未达到恢复阈值
+    Degraded,
+    /// 恢复失败（下次达到阈值时重试）
+    Failed,
+}
+
+/// 总线健康报告
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BusHealth {
+    /// 总线编号
+    pub bus: u8,
+    /// 状态
+    pub state: BusState,
+    /// 连续总线错误次数
+    pub consecutive_errors: u32,
+    /// 无应答次数
+    pub nacks: u64,
+    /// 超时次数
+    pub timeouts: u64,
+    /// 总线锁损坏次数
+    pub poisoned: u64,
+    /// 恢复次数
+    pub recoveries: u64,
+    /// 最近一次总线错误
+    pub last_error: Option<String>,
+    /// 最近一次恢复时间
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub last_recovery: Option<SystemTime>,
+}
+
+/// 监测器共享状态
+struct Inner {
+    /// 共享的I2C通信总线
+    i2c: SharedBus,
+    /// SDA、SCL引脚（BCM编号，为None时不输出恢复时钟）
+    pins: Option<(u8, u8)>,
+    /// 连续总线错误次数阈值
+    threshold: u32,
+    /// 健康报告
+    health: Mutex<BusHealth>,
+}
+
+/// I2C总线监测器（可克隆，克隆后共享同一份统计）
+#[derive(Clone)]
+pub struct I2cBusMonitor {
+    inner: Arc<Inner>,
+}
+
+impl I2cBusMonitor {
+    /// 创建实例
+    ///
+    /// - bus: 总线编号（总线0为GPIO0/1，总线1为GPIO2/3，其他总线需要通过`with_pins`指定引脚）
+    /// - i2c: 传感器共享的I2C通信总线
+    pub fn new<B: Into<SharedBus>>(bus: u8, i2c: B) -> Self {
+        let pins = match bus {
+            0 => Some((0, 1)),
+            1 => Some((2, 3)),
+            _ => None,
+        };
+        Self {
+            inner: Arc::new(Inner {
+                i2c: i2c.into(),
+                pins,
+                threshold: DEFAULT_THRESHOLD,
+                health: Mutex::new(BusHealth {
+                    bus,
+                    state: BusState::Healthy,
+                    consecutive_errors: 0,
+                    nacks: 0,
+                    timeouts: 0,
+                    poisoned: 0,
+                    recoveries: 0,
+                    last_error: None,
+                    last_recovery: None,
+                }),
+            }),
+        }
+    }
+
+    /// 修改内部配置（只能在克隆之前调用）
+    fn configure<F: FnOnce(&mut Inner)>(mut self, f: F) -> Self {
+        if let Some(inner) = Arc::get_mut(&mut self.inner) {
+            f(inner);
+        }
+        self
+    }
+
+    /// 指定SDA、SCL引脚（BCM编号）
+    pub fn with_pins(self, sda: u8, scl: u8) -> Self {
+        self.configure(|inner| inner.pins = Some((sda, scl)))
+    }
+
+    /// 设置触发恢复的连续总线错误次数
+    pub fn with_threshold(self, threshold: u32) -> Self {
+        self.configure(|inner| inner.threshold = threshold.max(1))
+    }
+
+    /// 健康报告
+    pub fn health(&self) -> BusHealth {
+        match self.inner.health.lock() {
+            Ok(health) => health.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// 更新健康报告
+    fn update<R, F: FnOnce(&mut BusHealth) -> R>(&self, f: F) -> R {
+        match self.inner.health.lock() {
+            Ok(mut health) => f(&mut health),
+            Err(poisoned) => f(&mut poisoned.into_inner()),
+        }
+    }
+
+    /// 记录一次成功的传输
+    pub fn record_success(&self) {
+        self.update(|health| {
+            health.consecutive_errors = 0;
+            health.state = BusState::Healthy;
+        });
+    }
+
+    /// 记录一次失败的传输，连续总线错误达到阈值时恢复总线
+    ///
+    /// 返回是否为总线错误
+    pub fn record_error(&self, message: &str) -> bool {
+        let Some(error) = BusError::classify(message) else {
+            return false;
+        };
+        let recover = self.update(|health| {
+            match error {
+                BusError::Nack => health.nacks += 1,
+                BusError::Timeout => health.timeouts += 1,
+                BusError::Poisoned => health.poisoned += 1,
+            }
+            health.consecutive_errors += 1;
+            health.last_error = Some(message.to_string());
+            if health.state == BusState::Healthy {
+                health.state = BusState::Degraded;
+            }
+            health.consecutive_errors >= self.inner.threshold
+        });
+        if recover {
+            trace_event!(warn, bus = self.health().bus, "I2C总线连续出错，开始恢复");
+            if let Err(err) = self.recover() {
+                eprintln!("{}", err);
+            }
+        }
+        true
+    }
+
+    /// 恢复总线
+    ///
+    /// 持有总线锁期间输出最多9个SCL时钟直到SDA释放，发出STOP条件后重新打开I2C设备
+    pub fn recover(&self) -> anyhow::Result<()> {
+        let bus = self.health().bus;
+        let result = (|| -> anyhow::Result<()> {
+            let mut i2c = self.inner.i2c.lock();
+            if let Some((sda, scl)) = self.inner.pins {
+                Self::clock_out(sda, scl)?;
+            }
+            *i2c = I2c::with_bus(bus)
+                .map_err(|err| anyhow::anyhow!("重新打开I2C总线{}失败: {}", bus, err))?;
+            Ok(())
+        })();
+        self.update(|health| match &result {
+            Ok(()) => {
+                health.consecutive_errors = 0;
+                health.state = BusState::Healthy;
+                health.recoveries += 1;
+                health.last_recovery = Some(SystemTime::now());
+            }
+            Err(err) => {
+                health.consecutive_errors = 0;
+                health.state = BusState::Failed;
+                health.last_error = Some(err.to_string());
+            }
+        });
+        result
+    }
+
+    /// 输出SCL时钟直到从机释放SDA，然后发出STOP条件
+    ///
+    /// 引脚在释放时自动恢复为I2C功能（ALT0）
+    fn clock_out(sda: u8, scl: u8) -> anyhow::Result<()> {
+        let gpio = Gpio::new()?;
+        let mut sda = gpio.get(sda)?.into_io(Mode::Input);
+        let mut scl = gpio.get(scl)?.into_io(Mode::Output);
+        scl.set_high();
+        thread::sleep(HALF_PERIOD);
+        for _ in 0..RECOVERY_CLOCKS {
+            if sda.is_high() {
+                break;
+            }
+            scl.set_low();
+            thread::sleep(HALF_PERIOD);
+            scl.set_high();
+            thread::sleep(HALF_PERIOD);
+        }
+        // STOP条件：SCL为高时SDA由低变高
+        sda.set_mode(Mode::Output);
+        scl.set_low();
+        sda.set_low();
+        thread::sleep(HALF_PERIOD);
+        scl.set_high();
+        thread::sleep(HALF_PERIOD);
+        sda.set_high();
+        thread::sleep(HALF_PERIOD);
+        sda.set_mode(Mode::Input);
+        if sda.is_low() {
+            return Err(anyhow::anyhow!("I2C总线SDA仍被拉低，请检查接线或从机供电"));
+        }
+        // OK
+        Ok(())
+    }
+
+    /// 在后台线程中统计调度器报告的读取结果
+    ///
+    /// - sensors: 挂载在该总线上的传感器名称
+    /// - samples: 调度器的读数订阅
+    /// - errors: 调度器的读取失败订阅
+    pub fn watch(
+        &self,
+        sensors: &[&str],
+        samples: Receiver<Sample>,
+        errors: Receiver<ReadError>,
+    ) -> [JoinHandle<()>; 2] {
+        let sensors: Vec<String> = sensors.iter().map(|s| s.to_string()).collect();
+        let monitor = self.clone();
+        let names = sensors.clone();
+        let success = thread::spawn(move || {
+            for sample in samples {
+                if names.contains(&sample.sensor) {
+                    monitor.record_success();
+                }
+            }
+        });
+        let monitor = self.clone();
+        let failure = thread::spawn(move || {
+            for (sensor, message) in errors {
+                if sensors.contains(&sensor) {
+                    monitor.record_error(&message);
+                }
+            }
+        });
+        [success, failure]
+    }
+}
@@ -0,0 +1,293 @@
+//! 内核驱动的传感器
+//!
+//! 通过dtoverlay启用内核驱动后（如`dtoverlay=i2c-sensor,bmp280`、`dtoverlay=i2c-sensor,sht3x`、
+//! `dtoverlay=w1-gpio`），读数由内核从sysfs导出，不需要在用户态访问总线。
+//! 这里的传感器同样实现了`Sensor`，可以和用户态驱动的传感器注册到同一个`SensorManager`
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::manager::Sensor;
+use crate::reading::{Quantity, Reading};
+
+/// IIO设备目录
+const IIO_ROOT: &str = "/sys/bus/iio/devices";
+
+/// hwmon设备目录
+const HWMON_ROOT: &str = "/sys/class/hwmon";
+
+/// 1-Wire设备目录
+const W1_ROOT: &str = "/sys/bus/w1/devices";
+
+/// 读取sysfs属性文件并解析为数值
+fn read_value(path: &Path) -> anyhow::Result<f64> {
+    let text = fs::read_to_string(path)
+        .map_err(|err| anyhow::anyhow!("读取{}失败: {}", path.display(), err))?;
+    text.trim()
+        .parse()
+        .map_err(|_| anyhow::anyhow!("{}的内容无效: {}", path.display(), text.trim()))
+}
+
+/// 读取设备名称（name文件）
+fn read_name(dir: &Path) -> Option<String> {
+    fs::read_to_string(dir.join("name"))
+        .ok()
+        .map(|name| name.trim().to_string())
+}
+
+/// 按名称或路径查找设备目录
+///
+/// - root: 设备类别目录
+/// - device: 设备名称（name文件的内容，如bmp280）、目录名（如iio:device0）或完整路径
+fn find_device(root: &str, device: &str) -> anyhow::Result<PathBuf> {
+    let path = Path::new(device);
+    if path.is_absolute() {
+        return Ok(path.to_path_buf());
+    }
+    let path = Path::new(root).join(device);
+    if path.is_dir() {
+        return Ok(path);
+    }
+    let entries = fs::read_dir(root).map_err(|err| anyhow::anyhow!("读取{}失败: {}", root, err))?;
+    for entry in entries.flatten() {
+        if read_name(&entry.path()).as_deref() == Some(device) {
+            return Ok(entry.path());
+        }
+    }
+    Err(anyhow::anyhow!("{}中没有找到设备: {}", root, device))
+}
+
+/// IIO通道
+struct IioChannel {
+    /// 物理量
+    quantity: Quantity,
+    /// 处理后的读数文件（*_input），不存在时使用原始值换算
+    input: Option<PathBuf>,
+    /// 原始读数文件（*_raw）
+    raw: PathBuf,
+    /// 原始读数的比例（*_scale）
+    scale: f64,
+    /// 原始读数的偏移（*_offset）
+    offset: f64,
+    /// IIO单位换算为`Quantity`单位的系数
+    factor: f64,
+}
+
+impl IioChannel {
+    /// 读取通道值
+    fn read(&self) -> anyhow::Result<f64> {
+        let value = match &self.input {
+            Some(input) => read_value(input)?,
+            None => (read_value(&self.raw)? + self.offset) * self.scale,
+        };
+        Ok(value * self.factor)
+    }
+}
+
+/// IIO子系统中的传感器（/sys/bus/iio/devices/iio:deviceN）
+///
+/// 支持温度（毫摄氏度）、相对湿度（千分之一百分比）、气压（kPa）和电压（mV）通道，
+/// 读数统一换算为`Quantity`的单位
+pub struct IioSensor {
+    /// 设备目录
+    path: PathBuf,
+    /// 通道列表
+    channels: Vec<IioChannel>,
+}
+
+impl IioSensor {
+    /// 打开设备
+    ///
+    /// - device: 设备名称（如bmp280）、目录名（如iio:device0）或完整路径
+    pub fn open(device: &str) -> anyhow::Result<Self> {
+        let path = find_device(IIO_ROOT, device)?;
+        // (通道前缀, 物理量, 换算系数)
+        let candidates = [
+            ("in_temp", Quantity::Temperature, 0.001),
+            ("in_humidityrelative", Quantity::Humidity, 0.001),
+            ("in_pressure", Quantity::Pressure, 1000.0),
+            ("in_voltage0", Quantity::Voltage, 0.001),
+        ];
+        let mut channels = Vec::new();
+        for (prefix, quantity, factor) in candidates {
+            let input = path.join(format!("{}_input", prefix));
+            let raw = path.join(format!("{}_raw", prefix));
+            if !input.exists() && !raw.exists() {
+                continue;
+            }
+            let scale = read_value(&path.join(format!("{}_scale", prefix))).unwrap_or(1.0);
+            let offset = read_value(&path.join(format!("{}_offset", prefix))).unwrap_or(0.0);
+            channels.push(IioChannel {
+                quantity,
+                input: input.exists().then_some(input),
+                raw,
+                scale,
+                offset,
+                factor,
+            });
+        }
+        if channels.is_empty() {
+            return Err(anyhow::anyhow!("IIO设备{}没有支持的通道", path.display()));
+        }
+        // OK
+        Ok(Self { path, channels })
+    }
+
+    /// 设备目录
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 支持的物理量
+    pub fn quantities(&self) -> Vec<Quantity> {
+        self.channels.iter().map(|c| c.quantity).collect()
+    }
+}
+
+impl Sensor for IioSensor {
+    fn kind(&self) -> &'static str {
+        "iio"
+    }
+
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        let mut reading = Reading::new();
+        for channel in &self.channels {
+            reading = reading.with(channel.quantity, channel.read()?);
+        }
+        // OK
+        Ok(reading)
+    }
+}
+
+/// hwmon子系统中的传感器（/sys/class/hwmon/hwmonN，如sht3x、CPU温度）
+///
+/// 读取temp1_input（毫摄氏度）和humidity1_input（千分之一百分比）
+pub struct HwmonSensor {
+    /// 设备目录
+    path: PathBuf,
+    /// 是否有湿度通道
+    humidity: bool,
+}
+
+impl HwmonSensor {
+    /// 打开设备
+    ///
+    /// - device: 设备名称（如sht3x、cpu_thermal）、目录名（如hwmon2）或完整路径
+    pub fn open(device: &str) -> anyhow::Result<Self> {
+        let path = find_device(HWMON_ROOT, device)?;
+        if !path.join("temp1_input").exists() {
+            return Err(anyhow::anyhow!("hwmon设备{}没有温度通道", path.display()));
+        }
+        let humidity = path.join("humidity1_input").exists();
+        // OK
+        Ok(Self { path, humidity })
+    }
+}
+
+impl Sensor for HwmonSensor {
+    fn kind(&self) -> &'static str {
+        "hwmon"
+    }
+
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        let mut reading = Reading::new().with(
+            Quantity::Temperature,
+            read_value(&self.path.join("temp1_input"))? / 1000.0,
+        );
+        if self.humidity {
+            reading = reading.with(
+                Quantity::Humidity,
+                read_value(&self.path.join("humidity1_input"))? / 1000.0,
+            );
+        }
+        // OK
+        Ok(reading)
+    }
+}
+
+/// w1-therm驱动的1-Wire温度传感器（DS18B20等）
+pub struct W1Therm {
+    /// 设备目录
+    path: PathBuf,
+}
+
+impl W1Therm {
+    /// 总线上所有温度传感器的ID（DS18S20、DS1822、DS18B20等，按ID排序）
+    pub fn discover() -> anyhow::Result<Vec<String>> {
+        let entries =
+            fs::read_dir(W1_ROOT).map_err(|err| anyhow::anyhow!("读取{}失败: {}", W1_ROOT, err))?;
+        let mut ids: Vec<String> = entries
+            .flatten()
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|id| {
+                ["10-", "22-", "28-", "3b-", "42-"]
+                    .iter()
+                    .any(|family| id.starts_with(family))
+            })
+            .collect();
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// 打开设备
+    ///
+    /// - id: 设备ID（如28-0316a2799aff），为None时使用找到的第一个
+    pub fn open(id: Option<&str>) -> anyhow::Result<Self> {
+        let id = match id {
+            Some(id) => id.to_string(),
+            None => Self::discover()?
+                .into_iter()
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("1-Wire总线上没有温度传感器"))?,
+        };
+        let path = Path::new(W1_ROOT).join(&id);
+        if !path.is_dir() {
+            return Err(anyhow::anyhow!("1-Wire设备不存在: {}", id));
+        }
+        // OK
+        Ok(Self { path })
+    }
+
+    /// 解析w1_slave文件（第一行以YES结尾表示CRC正确，第二行t=后为毫摄氏度）
+    fn parse_w1_slave(text: &str) -> anyhow::Result<f64> {
+        let mut lines = text.lines();
+        if !lines
+            .next()
+            .is_some_and(|line| line.trim_end().ends_with("YES"))
+        {
+            return Err(anyhow::anyhow!("1-Wire温度数据CRC校验失败"));
+        }
+        lines
+            .next()
+            .and_then(|line| line.split("t=").nth(1))
+            .and_then(|value| value.trim().parse::<f64>().ok())
+            .map(|value| value / 1000.0)
+            .ok_or_else(|| anyhow::anyhow!("1-Wire温度数据格式错误"))
+    }
+}
+
+impl Sensor for W1Therm {
+    fn kind(&self) -> &'static str {
+        "w1-therm"
+    }
+
+    fn min_interval(&self) -> Duration {
+        // 12位精度转换时间为750毫秒
+        Duration::from_millis(750)
+    }
+
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        // 新内核直接提供temperature属性
+        let temperature = match read_value(&self.path.join("temperature")) {
+            Ok(value) => value / 1000.0,
+            Err(_) => {
+                let path = self.path.join("w1_slave");
+                let text = fs::read_to_string(&path)
+                    .map_err(|err| anyhow::anyhow!("读取{}失败: {}", path.display(), err))?;
+                Self::parse_w1_slave(&text)?
+            }
+        };
+        Ok(Reading::new().with(Quantity::Temperature, temperature))
+    }
+}
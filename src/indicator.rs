@@ -0,0 +1,382 @@
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::alerts::{AlertEvent, AlertState};
+use crate::reading::Sample;
+use crate::scheduler::ReadError;
+use crate::sink::Sink;
+use crate::switch::Switch;
+
+/// 输出模式
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    /// 关闭
+    Off,
+    /// 常亮（继电器吸合、蜂鸣器长鸣）
+    On,
+    /// 闪烁count次，之后间隔pause重复（pause为None时只闪烁一轮）
+    Blink {
+        /// 次数
+        count: u32,
+        /// 每次打开的时间
+        on: Duration,
+        /// 每次关闭的时间
+        off: Duration,
+        /// 两轮之间的间隔
+        pause: Option<Duration>,
+    },
+}
+
+impl Pattern {
+    /// 快闪count次，每秒重复（错误码）
+    pub fn fast_blinks(count: u32) -> Self {
+        Pattern::Blink {
+            count,
+            on: Duration::from_millis(100),
+            off: Duration::from_millis(150),
+            pause: Some(Duration::from_secs(1)),
+        }
+    }
+
+    /// 慢闪count次，每2秒重复（心跳、等待状态）
+    pub fn slow_blinks(count: u32) -> Self {
+        Pattern::Blink {
+            count,
+            on: Duration::from_millis(500),
+            off: Duration::from_millis(500),
+            pause: Some(Duration::from_secs(2)),
+        }
+    }
+
+    /// 短鸣count声（蜂鸣器提示，不重复）
+    pub fn chirp(count: u32) -> Self {
+        Pattern::Blink {
+            count,
+            on: Duration::from_millis(50),
+            off: Duration::from_millis(100),
+            pause: None,
+        }
+    }
+}
+
+/// 触发条件
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trigger {
+    /// 传感器读取失败，下一次读取成功后解除
+    ReadError {
+        /// 传感器名称（为None时匹配任意传感器）
+        sensor: Option<String>,
+        /// 错误信息需包含的文本（为None时匹配任意错误）
+        contains: Option<String>,
+    },
+    /// 告警规则处于告警中
+    Alert(String),
+}
+
+impl Trigger {
+    /// 任意传感器读取失败
+    pub fn any_error() -> Self {
+        Trigger::ReadError {
+            sensor: None,
+            contains: None,
+        }
+    }
+
+    /// 错误信息包含指定文本（如"校验"匹配各驱动的校验和错误）
+    pub fn error_containing(text: &str) -> Self {
+        Trigger::ReadError {
+            sensor: None,
+            contains: Some(text.to_string()),
+        }
+    }
+
+    /// 指定传感器读取失败
+    pub fn sensor_error(sensor: &str) -> Self {
+        Trigger::ReadError {
+            sensor: Some(sensor.to_string()),
+            contains: None,
+        }
+    }
+
+    /// 告警规则处于告警中
+    pub fn alert(rule: &str) -> Self {
+        Trigger::Alert(rule.to_string())
+    }
+}
+
+/// 规则及其状态
+struct Binding {
+    /// 触发条件
+    trigger: Trigger,
+    /// 输出模式
+    pattern: Pattern,
+    /// 处于触发状态的传感器（读取失败）或规则（告警）
+    active: Vec<String>,
+}
+
+/// 指示器状态
+struct State {
+    /// 规则（按添加顺序，靠前的优先）
+    bindings: Vec<Binding>,
+    /// 没有规则触发时的输出模式
+    idle: Pattern,
+    /// 当前输出模式
+    current: Pattern,
+    /// 输出线程的模式切换通道
+    tx: Sender<Pattern>,
+}
+
+impl State {
+    /// 重新选择输出模式，变化时通知输出线程
+    fn refresh(&mut self) {
+        let pattern = self
+            .bindings
+            .iter()
+            .find(|binding| !binding.active.is_empty())
+            .map(|binding| binding.pattern.clone())
+            .unwrap_or_else(|| self.idle.clone());
+        if pattern != self.current {
+            self.current = pattern.clone();
+            let _ = self.tx.send(pattern);
+        }
+    }
+
+    /// 设置规则的触发状态
+    fn set_active(binding: &mut Binding, key: &str, active: bool) {
+        let index = binding.active.iter().position(|k| k == key);
+        match (index, active) {
+            (None, true) => binding.active.push(key.to_string()),
+            (Some(index), false) => {
+                binding.active.remove(index);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 将传感器和告警状态映射为LED、蜂鸣器、继电器输出模式的指示器
+///
+/// ```ignore
+/// let led = Indicator::new(GpioSwitch::new(27, true)?)
+///     .when(Trigger::error_containing("校验"), Pattern::fast_blinks(3))
+///     .when(Trigger::any_error(), Pattern::On)
+///     .idle(Pattern::slow_blinks(1))
+///     .start();
+/// ```
+pub struct Indicator {
+    /// 输出
+    output: Box<dyn Switch>,
+    /// 规则
+    bindings: Vec<Binding>,
+    /// 没有规则触发时的输出模式
+    idle: Pattern,
+}
+
+impl Indicator {
+    /// 创建指示器（没有规则触发时关闭）
+    pub fn new<W: Switch + 'static>(output: W) -> Self {
+        Self {
+            output: Box::new(output),
+            bindings: Vec::new(),
+            idle: Pattern::Off,
+        }
+    }
+
+    /// 添加规则（同时触发多条规则时先添加的优先）
+    pub fn when(mut self, trigger: Trigger, pattern: Pattern) -> Self {
+        self.bindings.push(Binding {
+            trigger,
+            pattern,
+            active: Vec::new(),
+        });
+        self
+    }
+
+    /// 设置没有规则触发时的输出模式
+    pub fn idle(mut self, pattern: Pattern) -> Self {
+        self.idle = pattern;
+        self
+    }
+
+    /// 启动输出线程
+    ///
+    /// 所有句柄释放后线程退出并关闭输出
+    pub fn start(self) -> IndicatorHandle {
+        let (tx, rx) = mpsc::channel();
+        let mut output = self.output;
+        let idle = self.idle.clone();
+        let thread = thread::spawn(move || {
+            drive(&mut *output, idle, rx);
+            let _ = output.off();
+        });
+        IndicatorHandle {
+            state: Arc::new(Mutex::new(State {
+                bindings: self.bindings,
+                idle: self.idle.clone(),
+                current: self.idle,
+                tx,
+            })),
+            thread: Arc::new(Mutex::new(Some(thread))),
+        }
+    }
+}
+
+/// 按输出模式驱动输出，收到新模式时立即切换，通道关闭时返回
+fn drive(output: &mut dyn Switch, mut pattern: Pattern, rx: Receiver<Pattern>) {
+    loop {
+        let next = match &pattern {
+            Pattern::Off | Pattern::On => {
+                let _ = output.set(pattern == Pattern::On);
+                rx.recv().ok()
+            }
+            Pattern::Blink {
+                count,
+                on,
+                off,
+                pause,
+            } => {
+                // 等待指定时间，期间收到新模式时提前返回
+                let wait = |duration: Duration| match rx.recv_timeout(duration) {
+                    Ok(next) => Some(Some(next)),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => Some(None),
+                };
+                let mut next = None;
+                for _ in 0..*count {
+                    let _ = output.on();
+                    if let Some(n) = wait(*on) {
+                        next = Some(n);
+                        break;
+                    }
+                    let _ = output.off();
+                    if let Some(n) = wait(*off) {
+                        next = Some(n);
+                        break;
+                    }
+                }
+                match (next, pause) {
+                    (Some(next), _) => next,
+                    (None, Some(pause)) => match wait(*pause) {
+                        Some(next) => next,
+                        // 重复当前模式
+                        None => continue,
+                    },
+                    (None, None) => {
+                        let _ = output.off();
+                        rx.recv().ok()
+                    }
+                }
+            }
+        };
+        match next {
+            Some(next) => pattern = next,
+            None => break,
+        }
+    }
+}
+
+/// 指示器句柄（可克隆，传给调度器、告警引擎的回调）
+#[derive(Clone)]
+pub struct IndicatorHandle {
+    /// 规则状态
+    state: Arc<Mutex<State>>,
+    /// 输出线程
+    thread: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl IndicatorHandle {
+    /// 更新状态
+    fn update<F>(&self, f: F)
+    where
+        F: FnOnce(&mut State),
+    {
+        if let Ok(mut state) = self.state.lock() {
+            f(&mut state);
+            state.refresh();
+        }
+    }
+
+    /// 传感器读取失败
+    pub fn error(&self, sensor: &str, message: &str) {
+        self.update(|state| {
+            for binding in &mut state.bindings {
+                let Trigger::ReadError {
+                    sensor: filter,
+                    contains,
+                } = &binding.trigger
+                else {
+                    continue;
+                };
+                if filter.as_deref().is_none_or(|s| s == sensor)
+                    && contains
+                        .as_deref()
+                        .is_none_or(|text| message.contains(text))
+                {
+                    State::set_active(binding, sensor, true);
+                }
+            }
+        });
+    }
+
+    /// 传感器读取成功（解除该传感器的读取失败状态）
+    pub fn sample(&self, sample: &Sample) {
+        self.update(|state| {
+            for binding in &mut state.bindings {
+                if matches!(binding.trigger, Trigger::ReadError { .. }) {
+                    State::set_active(binding, &sample.sensor, false);
+                }
+            }
+        });
+    }
+
+    /// 告警状态变化
+    pub fn alert(&self, event: &AlertEvent) {
+        self.update(|state| {
+            for binding in &mut state.bindings {
+                if binding.trigger == Trigger::Alert(event.rule.clone()) {
+                    State::set_active(binding, &event.rule, event.state == AlertState::Raised);
+                }
+            }
+        });
+    }
+
+    /// 在后台线程中接收调度器的读取失败通知
+    pub fn watch_errors(&self, errors: Receiver<ReadError>) -> JoinHandle<()> {
+        let handle = self.clone();
+        thread::spawn(move || {
+            for (sensor, message) in errors {
+                handle.error(&sensor, &message);
+            }
+        })
+    }
+
+    /// 当前输出模式
+    pub fn current(&self) -> Pattern {
+        self.state
+            .lock()
+            .map(|state| state.current.clone())
+            .unwrap_or(Pattern::Off)
+    }
+
+    /// 关闭输出并等待输出线程退出（其他句柄随之失效）
+    pub fn stop(self) {
+        if let Ok(mut state) = self.state.lock() {
+            // 替换发送端，使输出线程的通道关闭
+            state.tx = mpsc::channel().0;
+        }
+        let thread = self.thread.lock().ok().and_then(|mut thread| thread.take());
+        if let Some(thread) = thread {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// 加入读数分发管道后，读取成功会自动解除读取失败状态
+impl Sink for IndicatorHandle {
+    fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        self.sample(sample);
+        Ok(())
+    }
+}
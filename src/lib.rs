@@ -1,2 +1,80 @@
+#[macro_use]
+mod trace;
+
+pub mod actions;
+pub mod actuator;
+pub mod adapter;
+pub mod alerts;
+pub mod analog;
+pub mod apps;
+pub mod array;
+#[cfg(feature = "async")]
+pub mod async_sensor;
+#[cfg(feature = "ble")]
+pub mod ble;
+pub mod board;
+pub mod calibration;
+pub mod capture;
+pub mod codec;
+#[cfg(feature = "config")]
+pub mod config;
+pub mod control;
+pub mod core;
+#[cfg(feature = "dashboard")]
+pub mod dashboard;
+pub mod debug;
+pub mod diagnostics;
+pub mod display;
+pub mod enclosure;
+pub mod event_bus;
+pub mod fusion;
+pub mod gpio;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod health;
+pub mod i2c_bus;
+pub mod indicator;
+#[cfg(feature = "iio")]
+pub mod iio;
+pub mod manager;
+pub mod metrics;
+#[cfg(feature = "modbus")]
+pub mod modbus;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+pub mod outlier;
+#[cfg(feature = "privdrop")]
+pub mod privilege;
+pub mod profiles;
+pub mod pwm_wapper;
+pub mod radio;
+pub mod rate_limit;
+pub mod reading;
+#[cfg(feature = "reload")]
+pub mod reload;
+pub mod scale;
+pub mod scale_service;
+pub mod schedule;
+pub mod scheduler;
 pub mod sensor;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod service;
+#[cfg(feature = "shutdown")]
+pub mod shutdown;
+#[cfg(feature = "sim")]
+pub mod sim;
+pub mod sink;
+pub mod softpwm;
+pub mod spi_bus;
+pub mod station;
+#[cfg(feature = "sensor-hal")]
 pub mod std_clock;
+pub mod stream;
+pub mod switch;
+pub mod time_sync;
+pub mod timing;
+pub mod trend;
+pub mod ui;
+pub mod units;
+pub mod watchdog;
@@ -0,0 +1,312 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::capture::SignalCapture;
+use crate::diagnostics::DiagnosticsReport;
+use crate::health::HealthRegistry;
+use crate::reading::{Reading, Sample};
+
+/// 统一的传感器接口
+pub trait Sensor: Send {
+    /// 传感器型号（如"dht11"）
+    fn kind(&self) -> &'static str;
+
+    /// 两次读取之间的最小间隔（如DHT11为2秒）
+    fn min_interval(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    /// 读取一次数据
+    fn read(&mut self) -> anyhow::Result<Reading>;
+
+    /// 自检（默认读取一次，驱动可以检查芯片ID、状态位等并给出接线提示）
+    fn self_test(&mut self) -> DiagnosticsReport {
+        let mut report = DiagnosticsReport::new(self.kind());
+        match self.read() {
+            Ok(reading) => report.pass("读取", reading),
+            Err(err) => report.fail("读取", err, "检查接线、供电和配置的引脚或地址"),
+        };
+        report
+    }
+
+    /// 软件时序协议的信号捕获（DHT11、HX711等），不支持时返回None
+    fn signal_capture(&self) -> Option<SignalCapture> {
+        None
+    }
+}
+
+/// 已装箱的传感器（按配置创建的传感器可以直接传给接受泛型传感器的组合应用）
+impl Sensor for Box<dyn Sensor> {
+    fn kind(&self) -> &'static str {
+        (**self).kind()
+    }
+
+    fn min_interval(&self) -> Duration {
+        (**self).min_interval()
+    }
+
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        (**self).read()
+    }
+
+    fn self_test(&mut self) -> DiagnosticsReport {
+        (**self).self_test()
+    }
+
+    fn signal_capture(&self) -> Option<SignalCapture> {
+        (**self).signal_capture()
+    }
+}
+
+/// 已注册的传感器
+struct Entry {
+    /// 传感器对象
+    sensor: Box<dyn Sensor>,
+    /// 两次读取之间的最小间隔
+    min_interval: Duration,
+    /// 上一次读取成功的时间和读数
+    last: Option<(Instant, Sample)>,
+    /// 是否启用（停用时读取返回错误，调度器跳过该传感器）
+    enabled: bool,
+}
+
+impl Entry {
+    /// 创建实例（最小间隔不能小于传感器自身的最小间隔）
+    fn new(sensor: Box<dyn Sensor>, min_interval: Duration) -> Self {
+        Self {
+            min_interval: min_interval.max(sensor.min_interval()),
+            sensor,
+            last: None,
+            enabled: true,
+        }
+    }
+
+    /// 读取数据，未超过最小间隔时返回上一次的读数
+    fn read(&mut self, name: &str, health: &HealthRegistry) -> anyhow::Result<Sample> {
+        if !self.enabled {
+            return Err(anyhow::anyhow!("传感器已停用: {}", name));
+        }
+        if let Some((at, sample)) = &self.last
+            && at.elapsed() < self.min_interval
+        {
+            trace_event!(debug, sensor = name, "未超过最小读取间隔，返回缓存的读数");
+            return Ok(sample.clone());
+        }
+        let result = self.sensor.read();
+        health.record(name, &result);
+        let sample = Sample::new(name, result?);
+        self.last = Some((Instant::now(), sample.clone()));
+        // OK
+        Ok(sample)
+    }
+}
+
+/// 共享的已注册传感器
+type SharedEntry = Arc<Mutex<Entry>>;
+
+/// 传感器管理器
+///
+/// - 传感器按名称注册，通过`Sensor`接口统一读取
+/// - 两次读取间隔小于传感器的最小间隔时直接返回缓存的读数，避免DHT11等传感器读取过快
+/// - 同一个传感器的读取互斥，共享I2C/SPI总线的传感器由各自持有的总线锁仲裁
+/// - 每次实际读取的结果计入健康统计（`health()`）
+/// - 运行期间（包括交给调度器之后）可以添加、移除、替换和停用传感器，长时间运行的网关
+///   不需要为硬件变动重启
+#[derive(Default)]
+pub struct SensorManager {
+    sensors: RwLock<BTreeMap<String, SharedEntry>>,
+    /// 健康统计
+    health: HealthRegistry,
+}
+
+impl SensorManager {
+    /// 创建空的管理器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册传感器（使用传感器自身的最小读取间隔）
+    pub fn register<S>(&self, name: &str, sensor: S) -> anyhow::Result<()>
+    where
+        S: Sensor + 'static,
+    {
+        let min_interval = sensor.min_interval();
+        self.register_with_interval(name, sensor, min_interval)
+    }
+
+    /// 注册传感器并指定最小读取间隔（不能小于传感器自身的最小间隔）
+    pub fn register_with_interval<S>(
+        &self,
+        name: &str,
+        sensor: S,
+        min_interval: Duration,
+    ) -> anyhow::Result<()>
+    where
+        S: Sensor + 'static,
+    {
+        self.register_boxed(name, Box::new(sensor), min_interval)
+    }
+
+    /// 注册已装箱的传感器
+    pub fn register_boxed(
+        &self,
+        name: &str,
+        sensor: Box<dyn Sensor>,
+        min_interval: Duration,
+    ) -> anyhow::Result<()> {
+        let mut sensors = self
+            .sensors
+            .write()
+            .map_err(|_| anyhow::anyhow!("传感器列表状态异常"))?;
+        if sensors.contains_key(name) {
+            return Err(anyhow::anyhow!("传感器名称重复: {}", name));
+        }
+        let entry = Entry::new(sensor, min_interval);
+        sensors.insert(name.to_string(), Arc::new(Mutex::new(entry)));
+        self.health.register(name);
+        Ok(())
+    }
+
+    /// 替换已注册的传感器（如修改了I2C地址），保留健康统计和启用状态
+    ///
+    /// 正在进行的读取完成后才会替换，旧的传感器对象随后释放
+    pub fn replace(
+        &self,
+        name: &str,
+        sensor: Box<dyn Sensor>,
+        min_interval: Duration,
+    ) -> anyhow::Result<()> {
+        self.with_entry(name, |entry| {
+            let enabled = entry.enabled;
+            *entry = Entry::new(sensor, min_interval);
+            entry.enabled = enabled;
+        })?;
+        trace_event!(info, sensor = name, "已替换传感器");
+        Ok(())
+    }
+
+    /// 移除传感器
+    pub fn remove(&self, name: &str) -> bool {
+        self.health.remove(name);
+        match self.sensors.write() {
+            Ok(mut sensors) => sensors.remove(name).is_some(),
+            Err(_) => false,
+        }
+    }
+
+    /// 修改传感器的最小读取间隔（不能小于传感器自身的最小间隔）
+    pub fn set_min_interval(&self, name: &str, min_interval: Duration) -> anyhow::Result<()> {
+        self.with_entry(name, |entry| {
+            entry.min_interval = min_interval.max(entry.sensor.min_interval());
+        })
+    }
+
+    /// 启用或停用传感器（停用后保留注册和健康统计，读取返回错误）
+    pub fn set_enabled(&self, name: &str, enabled: bool) -> anyhow::Result<()> {
+        self.with_entry(name, |entry| {
+            if entry.enabled != enabled {
+                trace_event!(info, sensor = name, enabled, "修改传感器启用状态");
+            }
+            entry.enabled = enabled;
+        })
+    }
+
+    /// 传感器是否启用
+    pub fn is_enabled(&self, name: &str) -> anyhow::Result<bool> {
+        self.with_entry(name, |entry| entry.enabled)
+    }
+
+    /// 健康统计（可克隆，交给HTTP服务、看门狗等查看）
+    pub fn health(&self) -> HealthRegistry {
+        self.health.clone()
+    }
+
+    /// 已注册的传感器名称
+    pub fn names(&self) -> Vec<String> {
+        match self.sensors.read() {
+            Ok(sensors) => sensors.keys().cloned().collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// 是否已注册指定名称的传感器
+    pub fn contains(&self, name: &str) -> bool {
+        self.sensors
+            .read()
+            .is_ok_and(|sensors| sensors.contains_key(name))
+    }
+
+    /// 传感器型号
+    pub fn kind(&self, name: &str) -> anyhow::Result<&'static str> {
+        self.with_entry(name, |entry| entry.sensor.kind())
+    }
+
+    /// 传感器的最小读取间隔
+    pub fn min_interval(&self, name: &str) -> anyhow::Result<Duration> {
+        self.with_entry(name, |entry| entry.min_interval)
+    }
+
+    /// 取出已注册的传感器
+    fn entry(&self, name: &str) -> anyhow::Result<SharedEntry> {
+        self.sensors
+            .read()
+            .map_err(|_| anyhow::anyhow!("传感器列表状态异常"))?
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("未注册的传感器: {}", name))
+    }
+
+    /// 锁定已注册的传感器后执行操作
+    ///
+    /// 只在取出传感器时锁定传感器列表，读取期间不阻塞其他传感器的添加和移除
+    fn with_entry<R>(&self, name: &str, f: impl FnOnce(&mut Entry) -> R) -> anyhow::Result<R> {
+        let entry = self.entry(name)?;
+        let mut entry = entry
+            .lock()
+            .map_err(|_| anyhow::anyhow!("传感器状态异常: {}", name))?;
+        Ok(f(&mut entry))
+    }
+
+    /// 读取指定传感器
+    pub fn read(&self, name: &str) -> anyhow::Result<Reading> {
+        Ok(self.read_sample(name)?.reading)
+    }
+
+    /// 读取指定传感器（带时间戳）
+    pub fn read_sample(&self, name: &str) -> anyhow::Result<Sample> {
+        self.with_entry(name, |entry| entry.read(name, &self.health))?
+    }
+
+    /// 依次读取所有传感器
+    pub fn read_all(&self) -> Vec<(String, anyhow::Result<Sample>)> {
+        self.names()
+            .into_iter()
+            .map(|name| {
+                let sample = self.read_sample(&name);
+                (name, sample)
+            })
+            .collect()
+    }
+
+    /// 指定传感器的信号捕获（不支持时返回None）
+    pub fn signal_capture(&self, name: &str) -> anyhow::Result<Option<SignalCapture>> {
+        self.with_entry(name, |entry| entry.sensor.signal_capture())
+    }
+
+    /// 运行指定传感器的自检
+    pub fn self_test(&self, name: &str) -> anyhow::Result<DiagnosticsReport> {
+        self.with_entry(name, |entry| entry.sensor.self_test())
+    }
+
+    /// 依次运行所有传感器的自检
+    pub fn self_test_all(&self) -> Vec<(String, anyhow::Result<DiagnosticsReport>)> {
+        self.names()
+            .into_iter()
+            .map(|name| {
+                let report = self.self_test(&name);
+                (name, report)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,258 @@
+//! 气象指标
+//!
+//! 气压趋势和Zambretti天气预报：按3小时气压趋势、海平面气压、风向和季节查表得到A~Z共26种
+//! 本地预报，适合没有网络的气象站在显示屏上给出“晴转阴”一类的提示
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::reading::{Quantity, Sample};
+use crate::schedule::civil_from_days;
+use crate::sink::Sink;
+use crate::trend::{RateEstimator, Trend};
+
+/// 计算气压趋势的时间窗口
+pub const TENDENCY_WINDOW: Duration = Duration::from_secs(3 * 3600);
+
+/// 3小时气压变化不超过该值时为平稳（Pa）
+pub const TENDENCY_THRESHOLD: f64 = 160.0;
+
+/// 查表的气压范围（hPa）
+const BARO_TOP: f64 = 1050.0;
+const BARO_BOTTOM: f64 = 950.0;
+
+/// 预报（A~Z）
+const FORECASTS: [&str; 26] = [
+    "持续晴好",
+    "晴",
+    "转晴",
+    "晴，转不稳定",
+    "晴，可能有阵雨",
+    "大致晴，好转",
+    "大致晴，早些时候可能有阵雨",
+    "大致晴，稍后有阵雨",
+    "早些时候有阵雨，好转",
+    "多变，好转",
+    "大致晴，可能有阵雨",
+    "较不稳定，稍后转晴",
+    "不稳定，可能好转",
+    "阵雨，间有晴朗",
+    "阵雨，转不稳定",
+    "多变，有雨",
+    "不稳定，短暂晴朗",
+    "不稳定，稍后有雨",
+    "不稳定，有雨",
+    "大多很不稳定",
+    "间有雨，转差",
+    "时有雨，很不稳定",
+    "频繁降雨",
+    "有雨，很不稳定",
+    "暴风雨，可能好转",
+    "暴风雨，大量降雨",
+];
+
+/// 各气压档位对应的预报序号
+const RISING_OPTIONS: [usize; 22] = [
+    25, 25, 25, 24, 24, 19, 16, 12, 11, 9, 8, 6, 5, 2, 1, 1, 0, 0, 0, 0, 0, 0,
+];
+const STEADY_OPTIONS: [usize; 22] = [
+    25, 25, 25, 25, 25, 25, 23, 23, 22, 18, 15, 13, 10, 4, 1, 1, 0, 0, 0, 0, 0, 0,
+];
+const FALLING_OPTIONS: [usize; 22] = [
+    25, 25, 25, 25, 25, 25, 25, 25, 23, 23, 21, 20, 17, 14, 7, 3, 1, 1, 1, 0, 0, 0,
+];
+
+/// 风向对气压的修正（占气压范围的百分比），北半球从北风开始按顺时针排列的16个方位
+const WIND_ADJUSTMENTS: [f64; 16] = [
+    6.0, 5.0, 5.0, 2.0, -0.5, -2.0, -5.0, -8.5, -12.0, -10.0, -6.0, -4.5, -3.0, -0.5, 1.5, 3.0,
+];
+
+/// 夏季按气压趋势的修正（占气压范围的百分比）
+const SUMMER_ADJUSTMENT: f64 = 7.0;
+
+/// 半球
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Hemisphere {
+    /// 北半球
+    North,
+    /// 南半球
+    South,
+}
+
+/// Zambretti预报
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Forecast {
+    /// 预报代码（A~Z，A为持续晴好，Z为暴风雨）
+    pub code: char,
+    /// 预报文本
+    pub text: &'static str,
+    /// 气压超出查表范围（950~1050hPa）
+    pub exceptional: bool,
+    /// 3小时气压趋势
+    pub tendency: Trend,
+    /// 3小时气压变化（Pa）
+    pub change: f64,
+    /// 最新的海平面气压（Pa）
+    pub pressure: f64,
+}
+
+impl fmt::Display for Forecast {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.exceptional {
+            f.write_str("极端天气，")?;
+        }
+        write!(f, "{}（{}）", self.text, self.code)
+    }
+}
+
+/// 3小时气压趋势和变化量（Pa），读数覆盖的时间不足1.5小时时为None
+///
+/// - history: 海平面气压的历史（时间, Pa），按时间顺序
+pub fn pressure_tendency(history: &[(SystemTime, f64)]) -> Option<(Trend, f64)> {
+    let mut estimator = RateEstimator::new(TENDENCY_WINDOW);
+    for (timestamp, pressure) in history {
+        estimator.push(*timestamp, *pressure);
+    }
+    let change = estimator.rate(TENDENCY_WINDOW)?;
+    Some((Trend::classify(change, TENDENCY_THRESHOLD), change))
+}
+
+/// Zambretti天气预报
+///
+/// - pressure_history: 海平面气压的历史（时间, Pa），按时间顺序，至少覆盖1.5小时
+/// - wind_dir: 风向（度，风的来向，0为北风），没有风向传感器时为None
+/// - hemisphere: 所在半球（决定风向修正和季节）
+pub fn zambretti(
+    pressure_history: &[(SystemTime, f64)],
+    wind_dir: Option<f64>,
+    hemisphere: Hemisphere,
+) -> Option<Forecast> {
+    let (tendency, change) = pressure_tendency(pressure_history)?;
+    let &(timestamp, pressure) = pressure_history.last()?;
+    let range = BARO_TOP - BARO_BOTTOM;
+    let mut hpa = pressure / 100.0;
+
+    if let Some(degrees) = wind_dir {
+        // 16个方位，南半球从南风开始
+        let mut point = (degrees.rem_euclid(360.0) / 22.5).round() as usize % 16;
+        if hemisphere == Hemisphere::South {
+            point = (point + 8) % 16;
+        }
+        hpa += WIND_ADJUSTMENTS[point] / 100.0 * range;
+    }
+
+    // 北半球4~9月为夏季，南半球相反
+    let days = timestamp
+        .duration_since(UNIX_EPOCH)
+        .map(|t| t.as_secs() as i64 / 86_400)
+        .unwrap_or_default();
+    let (_, month, _) = civil_from_days(days);
+    let summer = (4..=9).contains(&month) == (hemisphere == Hemisphere::North);
+    if summer {
+        match tendency {
+            Trend::Rising => hpa += SUMMER_ADJUSTMENT / 100.0 * range,
+            Trend::Falling => hpa -= SUMMER_ADJUSTMENT / 100.0 * range,
+            Trend::Steady => {}
+        }
+    }
+
+    // 超出查表范围时标记为极端天气，查表时再按最近的档位取预报
+    let option = ((hpa - BARO_BOTTOM) / (range / 22.0)).floor();
+    let exceptional = !(0.0..=21.0).contains(&option);
+    let option = option.clamp(0.0, 21.0) as usize;
+    let index = match tendency {
+        Trend::Rising => RISING_OPTIONS[option],
+        Trend::Falling => FALLING_OPTIONS[option],
+        Trend::Steady => STEADY_OPTIONS[option],
+    };
+    Some(Forecast {
+        code: (b'A' + index as u8) as char,
+        text: FORECASTS[index],
+        exceptional,
+        tendency,
+        change,
+        pressure,
+    })
+}
+
+/// 最近3小时的海平面气压
+///
+/// 实现了`Sink`，加入读数分发管道后记录指定传感器的气压（有海平面气压时优先使用），可以克隆
+///
+/// ```ignore
+/// let history = PressureHistory::new("station");
+/// pipeline.add("pressure", history.clone());
+/// // 之后
+/// if let Some(forecast) = history.forecast(None, Hemisphere::North) {
+///     println!("{}", forecast);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct PressureHistory {
+    /// 传感器名称
+    sensor: String,
+    /// 海平面气压（时间, Pa）
+    history: Arc<Mutex<VecDeque<(SystemTime, f64)>>>,
+}
+
+impl PressureHistory {
+    /// 创建实例
+    ///
+    /// - sensor: 气压传感器或气象站的名称（读数中没有海平面气压时，测站气压需先换算）
+    pub fn new(sensor: &str) -> Self {
+        Self {
+            sensor: sensor.to_string(),
+            history: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    /// 记录一个海平面气压（Pa）
+    pub fn push(&self, timestamp: SystemTime, pressure: f64) {
+        if let Ok(mut history) = self.history.lock() {
+            history.push_back((timestamp, pressure));
+            while let Some((t, _)) = history.front()
+                && timestamp.duration_since(*t).unwrap_or_default() > TENDENCY_WINDOW
+            {
+                history.pop_front();
+            }
+        }
+    }
+
+    /// 记录的气压
+    pub fn snapshot(&self) -> Vec<(SystemTime, f64)> {
+        self.history
+            .lock()
+            .map(|history| history.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// 3小时气压趋势和变化量（Pa）
+    pub fn tendency(&self) -> Option<(Trend, f64)> {
+        pressure_tendency(&self.snapshot())
+    }
+
+    /// Zambretti天气预报
+    pub fn forecast(&self, wind_dir: Option<f64>, hemisphere: Hemisphere) -> Option<Forecast> {
+        zambretti(&self.snapshot(), wind_dir, hemisphere)
+    }
+}
+
+impl Sink for PressureHistory {
+    fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        if sample.sensor != self.sensor {
+            return Ok(());
+        }
+        let pressure = sample
+            .reading
+            .get(Quantity::SeaLevelPressure)
+            .or(sample.reading.get(Quantity::Pressure));
+        if let Some(pressure) = pressure {
+            self.push(sample.timestamp, pressure);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,549 @@
+//! Modbus从站（TCP和RS-485 RTU）
+//!
+//! 按寄存器映射表把传感器读数映射为输入寄存器（功能码04），把执行器映射为保持寄存器
+//! （功能码03读取、06/16写入），PLC和SCADA系统可以像轮询普通工业设备一样读取树莓派。
+//! 读数来源与HTTP服务器相同（调度器订阅或读数分发管道）
+//!
+//! 传感器还没有读数时，I16寄存器为0x8000，U16寄存器为0xFFFF，F32寄存器为NaN
+
+use rppal::gpio::{Gpio, OutputPin};
+use rppal::uart::{Parity, Queue, Uart};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::actuator::{ActuatorCommand, ActuatorKind, ActuatorRegistry};
+use crate::reading::{Quantity, Sample};
+use crate::sink::Sink;
+
+/// 功能码
+mod function {
+    pub const READ_HOLDING: u8 = 0x03;
+    pub const READ_INPUT: u8 = 0x04;
+    pub const WRITE_SINGLE: u8 = 0x06;
+    pub const WRITE_MULTIPLE: u8 = 0x10;
+}
+
+/// 异常码
+mod exception {
+    pub const ILLEGAL_FUNCTION: u8 = 0x01;
+    pub const ILLEGAL_ADDRESS: u8 = 0x02;
+    pub const ILLEGAL_VALUE: u8 = 0x03;
+    pub const DEVICE_FAILURE: u8 = 0x04;
+}
+
+/// 一次最多读取的寄存器数量（协议规定）
+const MAX_READ: u16 = 125;
+
+/// 一次最多写入的寄存器数量（协议规定）
+const MAX_WRITE: u16 = 123;
+
+/// 输入寄存器的数据格式（寄存器值 = 物理量 × 倍率）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RegisterFormat {
+    /// 有符号16位整数（如温度×10）
+    I16(f64),
+    /// 无符号16位整数（如湿度×10）
+    U16(f64),
+    /// 32位浮点数（占两个寄存器，高位字在前）
+    F32,
+}
+
+impl RegisterFormat {
+    /// 占用的寄存器数量
+    fn width(&self) -> u16 {
+        match self {
+            Self::I16(_) | Self::U16(_) => 1,
+            Self::F32 => 2,
+        }
+    }
+
+    /// 物理量编码为寄存器值（None为还没有读数）
+    fn encode(&self, value: Option<f64>) -> [u16; 2] {
+        match (self, value) {
+            (Self::I16(scale), Some(value)) => [
+                (value * scale).round().clamp(-32767.0, 32767.0) as i16 as u16,
+                0,
+            ],
+            (Self::U16(scale), Some(value)) => {
+                [(value * scale).round().clamp(0.0, 65534.0) as u16, 0]
+            }
+            (Self::I16(_), None) => [0x8000, 0],
+            (Self::U16(_), None) => [0xFFFF, 0],
+            (Self::F32, value) => {
+                let bits = (value.unwrap_or(f64::NAN) as f32).to_bits();
+                [(bits >> 16) as u16, bits as u16]
+            }
+        }
+    }
+}
+
+/// 输入寄存器（传感器读数）
+#[derive(Debug, Clone, PartialEq)]
+struct InputRegister {
+    /// 传感器名称
+    sensor: String,
+    /// 物理量
+    quantity: Quantity,
+    /// 数据格式
+    format: RegisterFormat,
+    /// 多寄存器格式中的第几个寄存器
+    word: usize,
+}
+
+/// 保持寄存器（执行器）
+#[derive(Debug, Clone, PartialEq)]
+struct HoldingRegister {
+    /// 执行器名称
+    actuator: String,
+    /// 倍率（PWM的占空比 = 寄存器值 / 倍率）
+    scale: f64,
+}
+
+/// 寄存器映射表
+///
+/// ```ignore
+/// let mut map = RegisterMap::new();
+/// // 30001：温度×10，30002：湿度×10，30003~30004：气压（浮点数）
+/// map.input(0, "greenhouse", Quantity::Temperature, RegisterFormat::I16(10.0))?;
+/// map.input(1, "greenhouse", Quantity::Humidity, RegisterFormat::U16(10.0))?;
+/// map.input(2, "greenhouse", Quantity::Pressure, RegisterFormat::F32)?;
+/// // 40001：水泵（0关闭，非0打开），40002：风扇占空比（0~1000）
+/// map.holding(0, "pump", 1.0)?;
+/// map.holding(1, "fan", 1000.0)?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RegisterMap {
+    /// 输入寄存器
+    inputs: BTreeMap<u16, InputRegister>,
+    /// 保持寄存器
+    holdings: BTreeMap<u16, HoldingRegister>,
+}
+
+impl RegisterMap {
+    /// 创建空的映射表
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 映射输入寄存器（地址从0开始，即30001对应0）
+    pub fn input(
+        &mut self,
+        address: u16,
+        sensor: &str,
+        quantity: Quantity,
+        format: RegisterFormat,
+    ) -> anyhow::Result<()> {
+        let width = format.width();
+        let addresses: Vec<u16> = (0..width)
+            .map(|offset| address.checked_add(offset))
+            .collect::<Option<_>>()
+            .ok_or_else(|| anyhow::anyhow!("输入寄存器地址超出范围: {}", address))?;
+        if let Some(used) = addresses.iter().find(|at| self.inputs.contains_key(at)) {
+            return Err(anyhow::anyhow!("输入寄存器地址重复: {}", used));
+        }
+        for (word, at) in addresses.into_iter().enumerate() {
+            self.inputs.insert(
+                at,
+                InputRegister {
+                    sensor: sensor.to_string(),
+                    quantity,
+                    format,
+                    word,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// 映射保持寄存器（地址从0开始，即40001对应0）
+    ///
+    /// 写入时按执行器类型转换为命令：开关为非0打开，PWM为寄存器值除以倍率，
+    /// 步进电机为有符号步数，称重服务为任意值去皮
+    pub fn holding(&mut self, address: u16, actuator: &str, scale: f64) -> anyhow::Result<()> {
+        if self.holdings.contains_key(&address) {
+            return Err(anyhow::anyhow!("保持寄存器地址重复: {}", address));
+        }
+        if !scale.is_finite() || scale <= 0.0 {
+            return Err(anyhow::anyhow!("保持寄存器倍率必须大于0: {}", scale));
+        }
+        self.holdings.insert(
+            address,
+            HoldingRegister {
+                actuator: actuator.to_string(),
+                scale,
+            },
+        );
+        Ok(())
+    }
+}
+
+/// Modbus RTU的CRC16（多项式0xA001，初始值0xFFFF，低字节在前发送）
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for byte in data {
+        crc ^= *byte as u16;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xA001
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+/// RTU请求帧的长度（数据不足以判断时为None）
+fn rtu_frame_len(buffer: &[u8]) -> Option<usize> {
+    match *buffer.get(1)? {
+        function::READ_HOLDING | function::READ_INPUT | function::WRITE_SINGLE => Some(8),
+        function::WRITE_MULTIPLE => Some(9 + *buffer.get(6)? as usize),
+        // 不支持的功能码按最短请求处理，由PDU处理返回异常
+        _ => Some(8),
+    }
+}
+
+/// 异常响应
+fn exception(function: u8, code: u8) -> Vec<u8> {
+    vec![function | 0x80, code]
+}
+
+/// 读取PDU中的16位整数（大端）
+fn word(pdu: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([*pdu.get(at)?, *pdu.get(at + 1)?]))
+}
+
+/// Modbus从站（可克隆，克隆后共享同一份读数和保持寄存器）
+///
+/// ```ignore
+/// let modbus = ModbusServer::new(map).with_actuators(actuators);
+/// modbus.record_from(scheduler.subscribe());
+/// modbus.serve_tcp("0.0.0.0:502")?;
+/// modbus.serve_rtu("/dev/ttyAMA0", 9600, Some(17))?;
+/// ```
+#[derive(Clone)]
+pub struct ModbusServer {
+    /// 寄存器映射表
+    map: Arc<RegisterMap>,
+    /// 各传感器的最新读数
+    latest: Arc<Mutex<BTreeMap<String, Sample>>>,
+    /// 保持寄存器的当前值（最后一次写入成功的值）
+    holdings: Arc<Mutex<BTreeMap<u16, u16>>>,
+    /// 可控制的执行器（None时写入保持寄存器返回异常）
+    actuators: Option<ActuatorRegistry>,
+    /// RTU从站地址
+    unit_id: u8,
+}
+
+impl ModbusServer {
+    /// 创建实例（RTU从站地址为1）
+    pub fn new(map: RegisterMap) -> Self {
+        Self {
+            map: Arc::new(map),
+            latest: Arc::new(Mutex::new(BTreeMap::new())),
+            holdings: Arc::new(Mutex::new(BTreeMap::new())),
+            actuators: None,
+            unit_id: 1,
+        }
+    }
+
+    /// 允许通过保持寄存器控制注册表中的执行器
+    pub fn with_actuators(mut self, actuators: ActuatorRegistry) -> Self {
+        self.actuators = Some(actuators);
+        self
+    }
+
+    /// 设置RTU从站地址（1~247）
+    pub fn with_unit_id(mut self, unit_id: u8) -> anyhow::Result<Self> {
+        if !(1..=247).contains(&unit_id) {
+            return Err(anyhow::anyhow!(
+                "Modbus从站地址需要在1~247之间: {}",
+                unit_id
+            ));
+        }
+        self.unit_id = unit_id;
+        // OK
+        Ok(self)
+    }
+
+    /// 记录一个新读数
+    pub fn record(&self, sample: Sample) {
+        if let Ok(mut latest) = self.latest.lock() {
+            latest.insert(sample.sensor.clone(), sample);
+        }
+    }
+
+    /// 在后台线程中记录收到的所有读数（如调度器的订阅），通道关闭后线程退出
+    pub fn record_from(&self, samples: Receiver<Sample>) -> JoinHandle<()> {
+        let this = self.clone();
+        thread::spawn(move || {
+            for sample in samples {
+                this.record(sample);
+            }
+        })
+    }
+
+    /// 读取输入寄存器
+    fn read_inputs(&self, start: u16, count: u16) -> Result<Vec<u16>, u8> {
+        let latest = self.latest.lock().map_err(|_| exception::DEVICE_FAILURE)?;
+        (start..start.saturating_add(count))
+            .map(|address| {
+                let register = self
+                    .map
+                    .inputs
+                    .get(&address)
+                    .ok_or(exception::ILLEGAL_ADDRESS)?;
+                let value = latest
+                    .get(&register.sensor)
+                    .and_then(|sample| sample.reading.get(register.quantity));
+                Ok(register.format.encode(value)[register.word])
+            })
+            .collect()
+    }
+
+    /// 读取保持寄存器
+    fn read_holdings(&self, start: u16, count: u16) -> Result<Vec<u16>, u8> {
+        let holdings = self
+            .holdings
+            .lock()
+            .map_err(|_| exception::DEVICE_FAILURE)?;
+        (start..start.saturating_add(count))
+            .map(|address| {
+                if !self.map.holdings.contains_key(&address) {
+                    return Err(exception::ILLEGAL_ADDRESS);
+                }
+                Ok(holdings.get(&address).copied().unwrap_or(0))
+            })
+            .collect()
+    }
+
+    /// 写入保持寄存器（执行对应的执行器命令）
+    fn write_holding(&self, address: u16, value: u16) -> Result<(), u8> {
+        let register = self
+            .map
+            .holdings
+            .get(&address)
+            .ok_or(exception::ILLEGAL_ADDRESS)?;
+        let actuators = self.actuators.as_ref().ok_or(exception::DEVICE_FAILURE)?;
+        let kind = actuators
+            .kind(&register.actuator)
+            .map_err(|_| exception::DEVICE_FAILURE)?;
+        let command = match kind {
+            ActuatorKind::Switch => ActuatorCommand::Switch(value != 0),
+            ActuatorKind::Pwm => {
+                let duty = value as f64 / register.scale;
+                if duty > 1.0 {
+                    return Err(exception::ILLEGAL_VALUE);
+                }
+                ActuatorCommand::Duty(duty)
+            }
+            ActuatorKind::Stepper => ActuatorCommand::Move(value as i16 as i64),
+            ActuatorKind::Scale => ActuatorCommand::Tare,
+        };
+        if let Err(err) = actuators.execute(&register.actuator, command) {
+            eprintln!("Modbus写入保持寄存器{}失败: {}", address, err);
+            return Err(exception::DEVICE_FAILURE);
+        }
+        if let Ok(mut holdings) = self.holdings.lock() {
+            holdings.insert(address, value);
+        }
+        Ok(())
+    }
+
+    /// 处理一个请求PDU（功能码+数据），返回响应PDU
+    pub fn handle_pdu(&self, pdu: &[u8]) -> Vec<u8> {
+        let Some(&code) = pdu.first() else {
+            return exception(0, exception::ILLEGAL_FUNCTION);
+        };
+        let (Some(address), Some(value)) = (word(pdu, 1), word(pdu, 3)) else {
+            return exception(code, exception::ILLEGAL_VALUE);
+        };
+        let result = match code {
+            function::READ_HOLDING | function::READ_INPUT => {
+                if value == 0 || value > MAX_READ {
+                    return exception(code, exception::ILLEGAL_VALUE);
+                }
+                let registers = if code == function::READ_INPUT {
+                    self.read_inputs(address, value)
+                } else {
+                    self.read_holdings(address, value)
+                };
+                registers.map(|registers| {
+                    let mut response = vec![code, (registers.len() * 2) as u8];
+                    for register in registers {
+                        response.extend_from_slice(&register.to_be_bytes());
+                    }
+                    response
+                })
+            }
+            function::WRITE_SINGLE => self
+                .write_holding(address, value)
+                .map(|_| pdu[..5].to_vec()),
+            function::WRITE_MULTIPLE => {
+                let count = value;
+                let bytes = pdu.get(5).copied().unwrap_or(0) as usize;
+                if count == 0 || count > MAX_WRITE || bytes != count as usize * 2 {
+                    return exception(code, exception::ILLEGAL_VALUE);
+                }
+                let Some(values) = (0..count as usize)
+                    .map(|i| word(pdu, 6 + i * 2))
+                    .collect::<Option<Vec<u16>>>()
+                else {
+                    return exception(code, exception::ILLEGAL_VALUE);
+                };
+                values
+                    .into_iter()
+                    .zip(address..)
+                    .try_for_each(|(value, at)| self.write_holding(at, value))
+                    .map(|_| pdu[..5].to_vec())
+            }
+            _ => Err(exception::ILLEGAL_FUNCTION),
+        };
+        result.unwrap_or_else(|code_| exception(code, code_))
+    }
+
+    /// 在后台线程中启动Modbus TCP服务（每个连接一个线程）
+    ///
+    /// - addr: 监听地址（如"0.0.0.0:502"，502端口需要root权限）
+    pub fn serve_tcp(&self, addr: &str) -> anyhow::Result<JoinHandle<()>> {
+        let listener = TcpListener::bind(addr)
+            .map_err(|err| anyhow::anyhow!("启动Modbus TCP服务失败: {}: {}", addr, err))?;
+        let this = self.clone();
+        // OK
+        Ok(thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                let this = this.clone();
+                thread::spawn(move || {
+                    if let Err(err) = this.handle_tcp(stream) {
+                        trace_event!(debug, error = %err, "Modbus TCP连接断开");
+                        eprintln!("Modbus TCP连接断开: {}", err);
+                    }
+                });
+            }
+        }))
+    }
+
+    /// 处理一个TCP连接（MBAP头 + PDU）
+    fn handle_tcp(&self, mut stream: TcpStream) -> anyhow::Result<()> {
+        loop {
+            let mut header = [0u8; 7];
+            if let Err(err) = stream.read_exact(&mut header) {
+                // 客户端关闭连接
+                if err.kind() == std::io::ErrorKind::UnexpectedEof {
+                    return Ok(());
+                }
+                return Err(err.into());
+            }
+            let protocol = u16::from_be_bytes([header[2], header[3]]);
+            let length = u16::from_be_bytes([header[4], header[5]]) as usize;
+            if protocol != 0 || !(2..=254).contains(&length) {
+                return Err(anyhow::anyhow!("Modbus TCP报文头无效: {:02X?}", header));
+            }
+            let mut pdu = vec![0u8; length - 1];
+            stream.read_exact(&mut pdu)?;
+
+            let response = self.handle_pdu(&pdu);
+            let mut frame = Vec::with_capacity(7 + response.len());
+            frame.extend_from_slice(&header[..4]);
+            frame.extend_from_slice(&(response.len() as u16 + 1).to_be_bytes());
+            frame.push(header[6]);
+            frame.extend_from_slice(&response);
+            stream.write_all(&frame)?;
+        }
+    }
+
+    /// 在后台线程中启动Modbus RTU服务（RS-485）
+    ///
+    /// - path: 串口设备路径，如"/dev/ttyAMA0"
+    /// - baud_rate: 波特率（8位数据、无校验、1位停止位）
+    /// - direction_pin: RS-485收发器DE/RE引脚（高电平发送），自动收发的模块为None
+    pub fn serve_rtu(
+        &self,
+        path: &str,
+        baud_rate: u32,
+        direction_pin: Option<u8>,
+    ) -> anyhow::Result<JoinHandle<()>> {
+        let mut uart = Uart::with_path(path, baud_rate, Parity::None, 8, 1)?;
+        uart.set_read_mode(0, Duration::from_millis(100))?;
+        uart.flush(Queue::Both)?;
+        let direction = match direction_pin {
+            Some(pin) => Some(Gpio::new()?.get(pin)?.into_output_low()),
+            None => None,
+        };
+        let this = self.clone();
+        // OK
+        Ok(thread::spawn(move || this.run_rtu(uart, direction)))
+    }
+
+    /// RTU接收循环：按功能码确定帧长度，CRC错误时丢弃一个字节重新同步
+    fn run_rtu(&self, mut uart: Uart, mut direction: Option<OutputPin>) {
+        let mut frame: Vec<u8> = Vec::with_capacity(256);
+        let mut buffer = [0u8; 256];
+        loop {
+            let n = match uart.read(&mut buffer) {
+                Ok(n) => n,
+                Err(err) => {
+                    eprintln!("读取Modbus RTU串口数据失败: {}", err);
+                    thread::sleep(Duration::from_secs(1));
+                    continue;
+                }
+            };
+            // 超时没有数据时丢弃不完整的帧（帧间隔远小于100毫秒）
+            if n == 0 {
+                frame.clear();
+                continue;
+            }
+            frame.extend_from_slice(&buffer[..n]);
+
+            while let Some(len) = rtu_frame_len(&frame) {
+                if frame.len() < len {
+                    break;
+                }
+                let crc = u16::from_le_bytes([frame[len - 2], frame[len - 1]]);
+                if crc16(&frame[..len - 2]) != crc {
+                    frame.remove(0);
+                    continue;
+                }
+                let request: Vec<u8> = frame.drain(..len).collect();
+                let unit = request[0];
+                // 0为广播地址：执行写入但不响应
+                if unit != self.unit_id && unit != 0 {
+                    continue;
+                }
+                let response = self.handle_pdu(&request[1..len - 2]);
+                if unit == 0 {
+                    continue;
+                }
+                let mut reply = Vec::with_capacity(response.len() + 3);
+                reply.push(unit);
+                reply.extend_from_slice(&response);
+                reply.extend_from_slice(&crc16(&reply).to_le_bytes());
+                if let Some(pin) = direction.as_mut() {
+                    pin.set_high();
+                }
+                let result = uart.write(&reply).and_then(|_| uart.drain());
+                if let Some(pin) = direction.as_mut() {
+                    pin.set_low();
+                }
+                if let Err(err) = result {
+                    eprintln!("发送Modbus RTU响应失败: {}", err);
+                }
+            }
+        }
+    }
+}
+
+impl Sink for ModbusServer {
+    fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        self.record(sample.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,400 @@
+use rumqttc::{
+    Client, Connection, Event, LastWill, MqttOptions, Packet, QoS, TlsConfiguration, Transport,
+};
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::actuator::ActuatorRegistry;
+use crate::reading::{Quantity, Sample};
+use crate::sink::Sink;
+
+/// 在线状态消息
+const PAYLOAD_ONLINE: &str = "online";
+/// 离线状态消息（遗嘱）
+const PAYLOAD_OFFLINE: &str = "offline";
+
+/// TLS配置
+#[derive(Debug, Clone)]
+pub struct MqttTls {
+    /// CA证书（PEM）
+    pub ca: Vec<u8>,
+    /// 客户端证书和私钥（PEM，双向认证时使用）
+    pub client_auth: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+/// MQTT连接和发布配置
+#[derive(Debug, Clone)]
+pub struct MqttConfig {
+    /// 服务器地址
+    pub host: String,
+    /// 服务器端口
+    pub port: u16,
+    /// 客户端ID（同时作为Home Assistant中的设备ID，只能包含字母、数字、`_`和`-`）
+    pub client_id: String,
+    /// 用户名和密码
+    pub credentials: Option<(String, String)>,
+    /// TLS配置（None为明文连接）
+    pub tls: Option<MqttTls>,
+    /// 心跳间隔
+    pub keep_alive: Duration,
+    /// 主题前缀，读数发布到`{base_topic}/{传感器名称}/state`
+    pub base_topic: String,
+    /// 读数的服务质量
+    pub qos: QoS,
+    /// 读数是否保留
+    pub retain: bool,
+    /// Home Assistant自动发现主题前缀（None为不发送自动发现配置）
+    pub discovery_prefix: Option<String>,
+}
+
+impl MqttConfig {
+    /// 创建默认配置
+    ///
+    /// - host: 服务器地址
+    /// - client_id: 客户端ID
+    pub fn new(host: &str, client_id: &str) -> Self {
+        Self {
+            host: host.to_string(),
+            port: 1883,
+            client_id: client_id.to_string(),
+            credentials: None,
+            tls: None,
+            keep_alive: Duration::from_secs(30),
+            base_topic: format!("raspi-sensor/{}", client_id),
+            qos: QoS::AtLeastOnce,
+            retain: false,
+            discovery_prefix: Some("homeassistant".to_string()),
+        }
+    }
+
+    /// 在线状态主题（连接时发布online，断开时由服务器发布遗嘱offline）
+    pub fn availability_topic(&self) -> String {
+        format!("{}/status", self.base_topic)
+    }
+
+    /// 传感器读数主题（执行器为执行命令后的状态）
+    pub fn state_topic(&self, sensor: &str) -> String {
+        format!("{}/{}/state", self.base_topic, sensor)
+    }
+
+    /// 执行器命令主题
+    pub fn command_topic(&self, actuator: &str) -> String {
+        format!("{}/{}/set", self.base_topic, actuator)
+    }
+
+    /// 连接参数（检查客户端ID）
+    fn options(&self, client_id: &str) -> anyhow::Result<MqttOptions> {
+        if client_id.is_empty()
+            || !client_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(anyhow::anyhow!("MQTT客户端ID无效: {}", client_id));
+        }
+        let mut options = MqttOptions::new(client_id, &self.host, self.port);
+        options.set_keep_alive(self.keep_alive);
+        if let Some((username, password)) = &self.credentials {
+            options.set_credentials(username, password);
+        }
+        if let Some(tls) = &self.tls {
+            options.set_transport(Transport::tls_with_config(TlsConfiguration::Simple {
+                ca: tls.ca.clone(),
+                alpn: None,
+                client_auth: tls.client_auth.clone(),
+            }));
+        }
+        // OK
+        Ok(options)
+    }
+}
+
+/// Home Assistant中的设备类型
+fn device_class(quantity: Quantity) -> Option<&'static str> {
+    match quantity {
+        Quantity::Temperature => Some("temperature"),
+        Quantity::Humidity => Some("humidity"),
+        Quantity::Pressure => Some("pressure"),
+        Quantity::Weight => Some("weight"),
+        Quantity::Voltage => Some("voltage"),
+        Quantity::Raw => None,
+        Quantity::DewPoint => Some("temperature"),
+        Quantity::SeaLevelPressure => Some("atmospheric_pressure"),
+        Quantity::WindSpeed | Quantity::WindGust => Some("wind_speed"),
+        Quantity::Rainfall => Some("precipitation"),
+        Quantity::Illuminance => Some("illuminance"),
+        Quantity::SoilMoisture => Some("moisture"),
+    }
+}
+
+/// MQTT读数发布器
+///
+/// - 连接时设置遗嘱，异常断开后Home Assistant中的实体显示为不可用
+/// - 每个传感器的每种物理量第一次发布前自动发送Home Assistant自动发现配置
+/// - 断线后后台线程自动重连
+pub struct MqttPublisher {
+    /// MQTT客户端
+    client: Client,
+    /// 发布配置
+    config: MqttConfig,
+    /// 已发送自动发现配置的（传感器名称、物理量）
+    announced: Arc<Mutex<BTreeSet<(String, Quantity)>>>,
+}
+
+impl MqttPublisher {
+    /// 连接服务器
+    pub fn connect(config: MqttConfig) -> anyhow::Result<Self> {
+        let mut options = config.options(&config.client_id)?;
+        options.set_last_will(LastWill::new(
+            config.availability_topic(),
+            PAYLOAD_OFFLINE,
+            QoS::AtLeastOnce,
+            true,
+        ));
+
+        let (client, connection) = Client::new(options, 64);
+        let announced = Arc::new(Mutex::new(BTreeSet::new()));
+        Self::drive(
+            connection,
+            client.clone(),
+            config.availability_topic(),
+            announced.clone(),
+        );
+        // OK
+        Ok(Self {
+            client,
+            config,
+            announced,
+        })
+    }
+
+    /// 后台线程驱动连接（收发数据包、断线重连）
+    fn drive(
+        mut connection: Connection,
+        client: Client,
+        availability_topic: String,
+        announced: Arc<Mutex<BTreeSet<(String, Quantity)>>>,
+    ) -> JoinHandle<()> {
+        thread::spawn(move || {
+            for notification in connection.iter() {
+                match notification {
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        // 每次(重新)连接后更新在线状态，并重新发送自动发现配置
+                        let _ = client.try_publish(
+                            &availability_topic,
+                            QoS::AtLeastOnce,
+                            true,
+                            PAYLOAD_ONLINE,
+                        );
+                        if let Ok(mut announced) = announced.lock() {
+                            announced.clear();
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(_) => {
+                        // 连接断开，稍后由迭代器自动重连
+                        thread::sleep(Duration::from_secs(3));
+                    }
+                }
+            }
+        })
+    }
+
+    /// 发布配置
+    pub fn config(&self) -> &MqttConfig {
+        &self.config
+    }
+
+    /// 发送Home Assistant自动发现配置
+    fn announce(&self, sensor: &str, quantity: Quantity) -> anyhow::Result<()> {
+        let Some(prefix) = &self.config.discovery_prefix else {
+            return Ok(());
+        };
+        let object_id = format!("{}_{}", sensor, quantity.name());
+        let unique_id = format!("{}_{}", self.config.client_id, object_id);
+        let mut payload = json!({
+            "name": format!("{} {}", sensor, quantity.name()),
+            "unique_id": unique_id,
+            "object_id": unique_id,
+            "state_topic": self.config.state_topic(sensor),
+            "value_template": format!("{{{{ value_json.{} }}}}", quantity.name()),
+            "availability_topic": self.config.availability_topic(),
+            "payload_available": PAYLOAD_ONLINE,
+            "payload_not_available": PAYLOAD_OFFLINE,
+            "state_class": "measurement",
+            "device": {
+                "identifiers": [self.config.client_id],
+                "name": self.config.client_id,
+                "manufacturer": "raspi-sensor",
+            },
+        });
+        if let Some(class) = device_class(quantity) {
+            payload["device_class"] = json!(class);
+            payload["unit_of_measurement"] = json!(quantity.unit());
+        }
+        let topic = format!(
+            "{}/sensor/{}/{}/config",
+            prefix, self.config.client_id, object_id
+        );
+        self.client
+            .publish(topic, QoS::AtLeastOnce, true, payload.to_string())
+            .map_err(|err| anyhow::anyhow!("发送自动发现配置失败: {}", err))?;
+        Ok(())
+    }
+
+    /// 发布一个读数
+    pub fn publish(&self, sample: &Sample) -> anyhow::Result<()> {
+        for (quantity, _) in sample.reading.iter() {
+            let key = (sample.sensor.clone(), quantity);
+            let first = self
+                .announced
+                .lock()
+                .map_err(|_| anyhow::anyhow!("MQTT发布器状态异常"))?
+                .insert(key);
+            if first {
+                self.announce(&sample.sensor, quantity)?;
+            }
+        }
+        self.client
+            .publish(
+                self.config.state_topic(&sample.sensor),
+                self.config.qos,
+                self.config.retain,
+                sample.to_json().to_string(),
+            )
+            .map_err(|err| anyhow::anyhow!("发布读数失败: {}", err))?;
+        Ok(())
+    }
+
+    /// 在后台线程中发布收到的所有读数（如调度器的订阅），通道关闭后线程退出
+    pub fn publish_from(self, samples: Receiver<Sample>) -> JoinHandle<()> {
+        thread::spawn(move || {
+            for sample in samples {
+                if let Err(err) = self.publish(&sample) {
+                    eprintln!("MQTT发布传感器{}的读数失败: {}", sample.sensor, err);
+                }
+            }
+        })
+    }
+}
+
+impl Sink for MqttPublisher {
+    fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        self.publish(sample)
+    }
+}
+
+/// MQTT执行器命令通道
+///
+/// 订阅`{base_topic}/{执行器名称}/set`，只有在执行器注册表中注册过的执行器可以被控制
+/// （允许列表），命令频率由注册表限制。网络线程收到命令后交给执行线程，
+/// 步进电机运行期间不影响心跳，之后的命令排队执行。
+/// 执行成功后向`{base_topic}/{执行器名称}/state`发布状态（开关为ON/OFF，
+/// PWM为占空比，步进电机为步数），失败时向`{base_topic}/{执行器名称}/error`发布错误信息
+///
+/// 使用单独的连接，客户端ID为发布器的客户端ID加`_cmd`后缀
+///
+/// ```ignore
+/// let actuators = ActuatorRegistry::new();
+/// actuators.add_switch("pump", GpioSwitch::new(22, false)?)?;
+/// actuators.add_stepper("vent", Arc::new(Mutex::new(motor)))?;
+/// actuators.add_scale("scale", scale.clone())?;
+/// let config = MqttConfig::new("192.168.1.10", "raspi_greenhouse");
+/// let _handle = MqttCommands::new(config, actuators).start()?;
+/// // mosquitto_pub -t raspi-sensor/raspi_greenhouse/pump/set -m ON
+/// ```
+pub struct MqttCommands {
+    /// 连接配置（使用其中的服务器、认证和主题前缀）
+    config: MqttConfig,
+    /// 执行器注册表
+    actuators: ActuatorRegistry,
+}
+
+impl MqttCommands {
+    /// 创建命令通道
+    pub fn new(config: MqttConfig, actuators: ActuatorRegistry) -> Self {
+        Self { config, actuators }
+    }
+
+    /// 连接配置
+    pub fn config(&self) -> &MqttConfig {
+        &self.config
+    }
+
+    /// 执行器注册表
+    pub fn actuators(&self) -> &ActuatorRegistry {
+        &self.actuators
+    }
+
+    /// 连接服务器并开始接收命令，返回执行线程（连接断开后自动重连）
+    pub fn start(self) -> anyhow::Result<JoinHandle<()>> {
+        let Self { config, actuators } = self;
+        let options = config.options(&format!("{}_cmd", config.client_id))?;
+        let (client, mut connection) = Client::new(options, 64);
+        let filter = config.command_topic("+");
+        let prefix = format!("{}/", config.base_topic);
+
+        // 网络线程：收发数据包，把命令交给执行线程
+        let (tx, rx) = mpsc::channel::<(String, String)>();
+        let subscriber = client.clone();
+        thread::spawn(move || {
+            for notification in connection.iter() {
+                match notification {
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        // 每次(重新)连接后重新订阅
+                        let _ = subscriber.try_subscribe(&filter, QoS::AtLeastOnce);
+                    }
+                    Ok(Event::Incoming(Packet::Publish(publish))) => {
+                        let name = publish
+                            .topic
+                            .strip_prefix(&prefix)
+                            .and_then(|rest| rest.strip_suffix("/set"))
+                            .map(str::to_string);
+                        let Some(name) = name else {
+                            continue;
+                        };
+                        let payload = String::from_utf8_lossy(&publish.payload).into_owned();
+                        if tx.send((name, payload)).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(_) => {
+                        // 连接断开，稍后由迭代器自动重连
+                        thread::sleep(Duration::from_secs(3));
+                    }
+                }
+            }
+        });
+
+        // 执行线程
+        let handle = thread::spawn(move || {
+            for (name, payload) in rx {
+                match actuators.invoke(&name, &payload) {
+                    Ok(state) => {
+                        let _ = client.try_publish(
+                            config.state_topic(&name),
+                            QoS::AtLeastOnce,
+                            true,
+                            state,
+                        );
+                    }
+                    Err(err) => {
+                        eprintln!("执行器{}执行命令失败: {}", name, err);
+                        let _ = client.try_publish(
+                            format!("{}/{}/error", config.base_topic, name),
+                            QoS::AtLeastOnce,
+                            false,
+                            err.to_string(),
+                        );
+                    }
+                }
+            }
+        });
+        // OK
+        Ok(handle)
+    }
+}
@@ -0,0 +1,333 @@
+//! 异常读数过滤
+//!
+//! DHT11等传感器偶尔会出现校验通过但数值明显错误的读数（如湿度连续三次为95→10→95，
+//! 原因是多位同时翻转使校验和恰好相同）。`SpikeFilter`包装任意传感器，按物理量检查
+//! 每次读数与近期读数的偏差（z-score）或变化速率，丢弃或标记物理上不可能的跳变，
+//! 并统计被拒绝的读数数量用于诊断
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::capture::SignalCapture;
+use crate::diagnostics::DiagnosticsReport;
+use crate::manager::Sensor;
+use crate::reading::{Quantity, Reading};
+
+/// 跳变判断方法
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Method {
+    /// 与最近若干个读数的均值相差超过若干倍标准差
+    ZScore {
+        /// 参与统计的最近读数数量（不足时不判断）
+        window: usize,
+        /// 标准差倍数
+        threshold: f64,
+        /// 最小允许偏差（读数长时间不变时标准差接近0，小于该偏差的变化不视为跳变）
+        min_deviation: f64,
+    },
+    /// 与上一次接受的读数相比变化速率超过上限
+    RateOfChange {
+        /// 每秒最大变化量
+        max_per_second: f64,
+        /// 与时间无关的最小允许变化量（读取间隔很短时避免误判）
+        min_change: f64,
+    },
+}
+
+impl Method {
+    /// z-score判断（最近10个读数，超过4倍标准差）
+    pub fn z_score(min_deviation: f64) -> Self {
+        Self::ZScore {
+            window: 10,
+            threshold: 4.0,
+            min_deviation,
+        }
+    }
+
+    /// 变化速率判断
+    pub fn rate_of_change(max_per_second: f64, min_change: f64) -> Self {
+        Self::RateOfChange {
+            max_per_second,
+            min_change,
+        }
+    }
+}
+
+/// 发现跳变时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    /// 从读数中移除该物理量（所有物理量都被移除时读取失败）
+    Drop,
+    /// 保留读数，只计数并输出警告
+    Flag,
+}
+
+/// 跳变检查结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    /// 正常
+    Accepted,
+    /// 跳变
+    Rejected,
+}
+
+/// 单个物理量的跳变检测
+///
+/// 连续被拒绝的读数达到上限时认为是真实的阶跃变化（如开门、加湿器启动），
+/// 接受该读数并以它为新的基准
+#[derive(Debug, Clone)]
+pub struct SpikeDetector {
+    /// 判断方法
+    method: Method,
+    /// 连续拒绝次数上限
+    max_consecutive: u32,
+    /// 最近接受的读数
+    history: VecDeque<f64>,
+    /// 上一次接受的读数和时间
+    last: Option<(Instant, f64)>,
+    /// 当前连续拒绝次数
+    consecutive: u32,
+}
+
+impl SpikeDetector {
+    /// 创建实例（连续拒绝3次后接受）
+    pub fn new(method: Method) -> Self {
+        Self {
+            method,
+            max_consecutive: 3,
+            history: VecDeque::new(),
+            last: None,
+            consecutive: 0,
+        }
+    }
+
+    /// 设置连续拒绝次数上限
+    pub fn with_max_consecutive(mut self, max_consecutive: u32) -> Self {
+        self.max_consecutive = max_consecutive.max(1);
+        self
+    }
+
+    /// 检查读数是否为跳变
+    pub fn check(&mut self, value: f64, at: Instant) -> Verdict {
+        if !value.is_finite() {
+            return Verdict::Rejected;
+        }
+        let spike = match self.method {
+            Method::ZScore {
+                window,
+                threshold,
+                min_deviation,
+            } => {
+                if self.history.len() < window.max(2) {
+                    false
+                } else {
+                    let n = self.history.len() as f64;
+                    let mean = self.history.iter().sum::<f64>() / n;
+                    let variance = self.history.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+                    let deviation = (value - mean).abs();
+                    deviation > min_deviation && deviation > threshold * variance.sqrt()
+                }
+            }
+            Method::RateOfChange {
+                max_per_second,
+                min_change,
+            } => match self.last {
+                Some((last_at, last)) => {
+                    let elapsed = at.saturating_duration_since(last_at).as_secs_f64();
+                    (value - last).abs() > min_change + max_per_second * elapsed
+                }
+                None => false,
+            },
+        };
+        if spike && self.consecutive + 1 < self.max_consecutive {
+            self.consecutive += 1;
+            return Verdict::Rejected;
+        }
+        if spike {
+            // 连续跳变视为真实的阶跃变化，重新建立基准
+            self.history.clear();
+        }
+        self.consecutive = 0;
+        self.last = Some((at, value));
+        if let Method::ZScore { window, .. } = self.method {
+            self.history.push_back(value);
+            while self.history.len() > window.max(2) {
+                self.history.pop_front();
+            }
+        }
+        Verdict::Accepted
+    }
+}
+
+/// 单个物理量的统计
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpikeStats {
+    /// 检查过的读数数量
+    pub checked: u64,
+    /// 被丢弃的读数数量
+    pub rejected: u64,
+    /// 被标记（保留）的读数数量
+    pub flagged: u64,
+}
+
+/// 过滤统计（可克隆，传感器注册到管理器后仍然可以查看）
+#[derive(Clone, Default)]
+pub struct SpikeStatsHandle {
+    stats: Arc<Mutex<BTreeMap<Quantity, SpikeStats>>>,
+}
+
+impl SpikeStatsHandle {
+    /// 各物理量的统计
+    pub fn snapshot(&self) -> BTreeMap<Quantity, SpikeStats> {
+        self.stats
+            .lock()
+            .map(|stats| stats.clone())
+            .unwrap_or_default()
+    }
+
+    /// 所有物理量的合计
+    pub fn total(&self) -> SpikeStats {
+        self.snapshot()
+            .values()
+            .fold(SpikeStats::default(), |total, stats| SpikeStats {
+                checked: total.checked + stats.checked,
+                rejected: total.rejected + stats.rejected,
+                flagged: total.flagged + stats.flagged,
+            })
+    }
+
+    /// 更新统计
+    fn record(&self, quantity: Quantity, verdict: Verdict, action: Action) {
+        if let Ok(mut stats) = self.stats.lock() {
+            let stats = stats.entry(quantity).or_default();
+            stats.checked += 1;
+            match (verdict, action) {
+                (Verdict::Accepted, _) => {}
+                (Verdict::Rejected, Action::Drop) => stats.rejected += 1,
+                (Verdict::Rejected, Action::Flag) => stats.flagged += 1,
+            }
+        }
+    }
+}
+
+impl fmt::Debug for SpikeStatsHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.snapshot()).finish()
+    }
+}
+
+/// 过滤跳变读数的传感器
+///
+/// ```ignore
+/// let dht11 = SpikeFilter::new(Dht11Sensor::new(4)?)
+///     .with_rule(Quantity::Humidity, Method::rate_of_change(1.0, 5.0))
+///     .with_rule(Quantity::Temperature, Method::z_score(2.0));
+/// let stats = dht11.stats();
+/// manager.register("greenhouse", dht11)?;
+/// ```
+pub struct SpikeFilter<S: Sensor> {
+    /// 被包装的传感器
+    inner: S,
+    /// 各物理量的跳变检测
+    detectors: BTreeMap<Quantity, SpikeDetector>,
+    /// 处理方式
+    action: Action,
+    /// 统计
+    stats: SpikeStatsHandle,
+}
+
+impl<S: Sensor> SpikeFilter<S> {
+    /// 包装传感器（默认丢弃跳变读数）
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            detectors: BTreeMap::new(),
+            action: Action::Drop,
+            stats: SpikeStatsHandle::default(),
+        }
+    }
+
+    /// 检查指定物理量
+    pub fn with_rule(self, quantity: Quantity, method: Method) -> Self {
+        self.with_detector(quantity, SpikeDetector::new(method))
+    }
+
+    /// 使用自定义的跳变检测检查指定物理量
+    pub fn with_detector(mut self, quantity: Quantity, detector: SpikeDetector) -> Self {
+        self.detectors.insert(quantity, detector);
+        self
+    }
+
+    /// 设置处理方式
+    pub fn with_action(mut self, action: Action) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// 过滤统计
+    pub fn stats(&self) -> SpikeStatsHandle {
+        self.stats.clone()
+    }
+
+    /// 取回被包装的传感器
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: Sensor> Sensor for SpikeFilter<S> {
+    fn kind(&self) -> &'static str {
+        self.inner.kind()
+    }
+
+    fn min_interval(&self) -> Duration {
+        self.inner.min_interval()
+    }
+
+    fn self_test(&mut self) -> DiagnosticsReport {
+        self.inner.self_test()
+    }
+
+    fn signal_capture(&self) -> Option<SignalCapture> {
+        self.inner.signal_capture()
+    }
+
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        let reading = self.inner.read()?;
+        let now = Instant::now();
+        let mut filtered = Reading::new();
+        let mut rejected = Vec::new();
+        for (quantity, value) in reading.iter() {
+            let verdict = match self.detectors.get_mut(&quantity) {
+                Some(detector) => detector.check(value, now),
+                None => Verdict::Accepted,
+            };
+            if self.detectors.contains_key(&quantity) {
+                self.stats.record(quantity, verdict, self.action);
+            }
+            if verdict == Verdict::Rejected {
+                trace_event!(warn, sensor = self.inner.kind(), quantity = %quantity, value, "读数跳变");
+                rejected.push(format!("{}={}", quantity, value));
+                if self.action == Action::Drop {
+                    continue;
+                }
+            }
+            filtered.set(quantity, value);
+        }
+        if !rejected.is_empty() && self.action == Action::Flag {
+            eprintln!(
+                "传感器{}的读数跳变: {}",
+                self.inner.kind(),
+                rejected.join(", ")
+            );
+        }
+        if filtered.is_empty() && !rejected.is_empty() {
+            return Err(anyhow::anyhow!("读数跳变，已丢弃: {}", rejected.join(", ")));
+        }
+        // OK
+        Ok(filtered)
+    }
+}
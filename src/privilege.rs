@@ -0,0 +1,258 @@
+//! 降低进程权限
+//!
+//! 访问/dev/gpiomem、/dev/i2c-*等设备时常以root启动，但设备打开后文件描述符和内存映射
+//! 在切换用户后仍然有效。长期运行的采集服务在打开所有设备后调用`PrivilegeDrop::apply`，
+//! 切换到普通用户运行，只保留显式指定的能力（如监听80端口需要的CAP_NET_BIND_SERVICE）
+//!
+//! ```ignore
+//! let manager = HardwareConfig::load("sensors.toml")?.build()?;
+//! privilege::preopen()?;
+//! PrivilegeDrop::new("sensor").keep(Capability::SysTime).apply()?;
+//! let handle = Scheduler::new(manager)?.start();
+//! ```
+
+use std::ffi::CString;
+use std::fs;
+use std::io;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use rppal::gpio::Gpio;
+
+/// 启动时打开的GPIO（保持内存映射，切换用户后再次`Gpio::new()`时复用）
+static GPIO: OnceLock<Gpio> = OnceLock::new();
+
+/// _LINUX_CAPABILITY_VERSION_3（64位能力集，分两个32位字保存）
+const CAPABILITY_VERSION_3: u32 = 0x2008_0522;
+
+/// 可以保留的能力
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    /// 监听1024以下的端口（CAP_NET_BIND_SERVICE）
+    NetBindService,
+    /// 使用原始套接字（CAP_NET_RAW）
+    NetRaw,
+    /// 访问/dev/mem等原始I/O（CAP_SYS_RAWIO）
+    SysRawio,
+    /// 设置系统时钟（CAP_SYS_TIME，用RTC校准时间时需要）
+    SysTime,
+    /// 提高线程优先级（CAP_SYS_NICE，实时调度读取单总线传感器时需要）
+    SysNice,
+}
+
+impl Capability {
+    /// 能力编号（linux/capability.h）
+    pub fn number(&self) -> u32 {
+        match self {
+            Capability::NetBindService => 10,
+            Capability::NetRaw => 13,
+            Capability::SysRawio => 17,
+            Capability::SysNice => 23,
+            Capability::SysTime => 25,
+        }
+    }
+}
+
+impl FromStr for Capability {
+    type Err = anyhow::Error;
+
+    /// 解析能力名称（如"CAP_NET_BIND_SERVICE"、"net_bind_service"）
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let name = text.to_ascii_lowercase();
+        match name.strip_prefix("cap_").unwrap_or(&name) {
+            "net_bind_service" => Ok(Capability::NetBindService),
+            "net_raw" => Ok(Capability::NetRaw),
+            "sys_rawio" => Ok(Capability::SysRawio),
+            "sys_time" => Ok(Capability::SysTime),
+            "sys_nice" => Ok(Capability::SysNice),
+            _ => Err(anyhow::anyhow!("不支持的能力: {}", text)),
+        }
+    }
+}
+
+/// capset的参数头（struct __user_cap_header_struct）
+#[repr(C)]
+struct CapHeader {
+    version: u32,
+    pid: libc::c_int,
+}
+
+/// capset的能力集（struct __user_cap_data_struct）
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct CapData {
+    effective: u32,
+    permitted: u32,
+    inheritable: u32,
+}
+
+/// 降低进程权限（切换用户并丢弃能力）
+pub struct PrivilegeDrop {
+    /// 切换到的用户
+    user: String,
+    /// 切换到的用户组（None为用户的主组）
+    group: Option<String>,
+    /// 保留的能力
+    capabilities: Vec<Capability>,
+}
+
+impl PrivilegeDrop {
+    /// 创建实例
+    ///
+    /// - user: 切换到的用户（需要在/etc/passwd中，附加组按/etc/group设置）
+    pub fn new(user: &str) -> Self {
+        Self {
+            user: user.to_string(),
+            group: None,
+            capabilities: Vec::new(),
+        }
+    }
+
+    /// 切换到指定用户组（默认为用户的主组）
+    pub fn with_group(mut self, group: &str) -> Self {
+        self.group = Some(group.to_string());
+        self
+    }
+
+    /// 切换用户后保留指定能力
+    pub fn keep(mut self, capability: Capability) -> Self {
+        if !self.capabilities.contains(&capability) {
+            self.capabilities.push(capability);
+        }
+        self
+    }
+
+    /// 切换用户并丢弃未保留的能力
+    ///
+    /// 能力只对调用线程生效，需要在启动调度器、HTTP服务等后台线程之前调用；
+    /// 切换后设置no_new_privs，之后无法再通过setuid程序重新获得root权限
+    pub fn apply(&self) -> anyhow::Result<()> {
+        // SAFETY: geteuid没有前置条件
+        if unsafe { libc::geteuid() } != 0 {
+            return Err(anyhow::anyhow!("当前进程不是以root运行，无法切换用户"));
+        }
+        let (uid, primary_gid) = lookup_user(&self.user)?;
+        let gid = match &self.group {
+            Some(group) => lookup_group(group)?,
+            None => primary_gid,
+        };
+        let name = CString::new(self.user.as_str())
+            .map_err(|_| anyhow::anyhow!("用户名无效: {}", self.user))?;
+        let keep_caps = !self.capabilities.is_empty();
+
+        // SAFETY: 以下均为参数为整数或有效C字符串的系统调用，失败时检查返回值
+        unsafe {
+            // 切换用户后保留能力，之后再用capset缩减到指定的能力
+            if keep_caps {
+                check(
+                    libc::prctl(libc::PR_SET_KEEPCAPS, 1, 0, 0, 0),
+                    "PR_SET_KEEPCAPS",
+                )?;
+            }
+            // 附加组（gpio、i2c、spi等）需要在放弃root之前设置
+            check(libc::initgroups(name.as_ptr(), gid), "设置附加用户组")?;
+            check(libc::setresgid(gid, gid, gid), "切换用户组")?;
+            check(libc::setresuid(uid, uid, uid), "切换用户")?;
+            if keep_caps {
+                let mask = self
+                    .capabilities
+                    .iter()
+                    .fold(0u64, |mask, capability| mask | 1 << capability.number());
+                set_capabilities(mask)?;
+                check(
+                    libc::prctl(libc::PR_SET_KEEPCAPS, 0, 0, 0, 0),
+                    "PR_SET_KEEPCAPS",
+                )?;
+            }
+            check(
+                libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0),
+                "PR_SET_NO_NEW_PRIVS",
+            )?;
+            // 确认无法恢复root权限
+            if libc::setuid(0) == 0 {
+                return Err(anyhow::anyhow!("降低权限后仍然可以恢复root权限"));
+            }
+        }
+        trace_event!(info, user = %self.user, uid, gid, "已降低进程权限");
+        // OK
+        Ok(())
+    }
+}
+
+/// 打开GPIO并在进程退出前保持打开
+///
+/// rppal在所有`Gpio`实例释放后会关闭/dev/gpiomem，之后再次打开需要权限。
+/// I2C、SPI、串口由传感器在创建时打开，切换用户前创建所有传感器即可
+pub fn preopen() -> anyhow::Result<()> {
+    if GPIO.get().is_none() {
+        let gpio = Gpio::new().map_err(|err| anyhow::anyhow!("打开GPIO失败: {}", err))?;
+        let _ = GPIO.set(gpio);
+    }
+    Ok(())
+}
+
+/// 检查系统调用的返回值
+fn check(result: libc::c_int, operation: &str) -> anyhow::Result<()> {
+    if result < 0 {
+        return Err(anyhow::anyhow!(
+            "{}失败: {}",
+            operation,
+            io::Error::last_os_error()
+        ));
+    }
+    Ok(())
+}
+
+/// 设置调用线程的有效能力和允许能力，继承能力清空
+fn set_capabilities(mask: u64) -> anyhow::Result<()> {
+    let header = CapHeader {
+        version: CAPABILITY_VERSION_3,
+        pid: 0,
+    };
+    let mut data = [CapData::default(); 2];
+    for (i, data) in data.iter_mut().enumerate() {
+        let word = (mask >> (i * 32)) as u32;
+        data.effective = word;
+        data.permitted = word;
+    }
+    // SAFETY: header和data的布局与内核的定义相同，版本3需要两个能力集
+    let result = unsafe { libc::syscall(libc::SYS_capset, &header, data.as_ptr()) };
+    check(result as libc::c_int, "设置能力")
+}
+
+/// 按用户名查找uid和主组gid（/etc/passwd）
+fn lookup_user(name: &str) -> anyhow::Result<(libc::uid_t, libc::gid_t)> {
+    let passwd = fs::read_to_string("/etc/passwd")
+        .map_err(|err| anyhow::anyhow!("读取/etc/passwd失败: {}", err))?;
+    // name:password:uid:gid:gecos:home:shell
+    for line in passwd.lines() {
+        let fields: Vec<&str> = line.split(':').collect();
+        if fields.len() >= 4
+            && fields[0] == name
+            && let (Ok(uid), Ok(gid)) = (fields[2].parse(), fields[3].parse())
+        {
+            return Ok((uid, gid));
+        }
+    }
+    Err(anyhow::anyhow!("用户不存在: {}", name))
+}
+
+/// 按组名查找gid（/etc/group），也可以直接使用数字gid
+fn lookup_group(name: &str) -> anyhow::Result<libc::gid_t> {
+    if let Ok(gid) = name.parse() {
+        return Ok(gid);
+    }
+    let group = fs::read_to_string("/etc/group")
+        .map_err(|err| anyhow::anyhow!("读取/etc/group失败: {}", err))?;
+    // name:password:gid:members
+    for line in group.lines() {
+        let fields: Vec<&str> = line.split(':').collect();
+        if fields.len() >= 3
+            && fields[0] == name
+            && let Ok(gid) = fields[2].parse()
+        {
+            return Ok(gid);
+        }
+    }
+    Err(anyhow::anyhow!("用户组不存在: {}", name))
+}
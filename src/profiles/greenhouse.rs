@@ -0,0 +1,465 @@
+//! 温室
+//!
+//! 温湿度、土壤湿度和光照传感器驱动加热、通风、喷雾和灌溉四路继电器：
+//! - 加热：温度低于设定值时打开，防止霜冻
+//! - 通风：温度高于设定值时打开
+//! - 喷雾：白天饱和水汽压差（VPD）过高时打开，植物蒸腾过强时加湿降温
+//! - 灌溉：土壤湿度低于下限时打开，达到上限或超过最长运行时间时关闭
+//!
+//! 默认生成霜冻（低于2℃）、热害（高于35℃）和土壤过干三条告警规则，
+//! 所有参数可以在配置文件的`[greenhouses.<名称>]`中修改
+
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+use crate::alerts::{AlertEngine, Condition, Rule};
+use crate::apps::climate_chamber::{HysteresisConfig, RelayLoop};
+use crate::manager::Sensor;
+use crate::reading::{Quantity, Reading, Sample};
+use crate::sink::Sink;
+use crate::station::vapor_pressure_deficit;
+use crate::switch::Switch;
+
+/// 温室参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct GreenhouseSettings {
+    /// 加热控制（温度℃，默认3℃打开、5℃关闭）
+    pub heater: HysteresisConfig,
+    /// 通风控制（温度℃，默认29℃打开、27℃关闭）
+    pub vent: HysteresisConfig,
+    /// 喷雾控制（VPD kPa，默认1.6kPa打开、1.0kPa关闭）
+    pub mister: HysteresisConfig,
+    /// 灌溉控制（土壤湿度%，默认30%打开、45%关闭）
+    pub irrigation: HysteresisConfig,
+    /// 单次灌溉的最长运行时间（土壤湿度传感器脱出或损坏时避免一直浇水）
+    pub irrigation_max_run: Duration,
+    /// 光照度低于该值时不喷雾（lux，夜间喷雾容易引起病害），没有光照传感器时不限制
+    pub mist_min_lux: Option<f64>,
+    /// 霜冻报警温度（℃）
+    pub frost_alarm: Option<f64>,
+    /// 热害报警温度（℃）
+    pub heat_stress_alarm: Option<f64>,
+    /// 土壤过干报警湿度（%）
+    pub soil_dry_alarm: Option<f64>,
+    /// 超出报警范围持续多久后报警
+    pub alarm_delay: Duration,
+    /// 传感器连续读取失败多少次后关闭依赖它的执行器
+    pub max_failures: u32,
+}
+
+impl Default for GreenhouseSettings {
+    fn default() -> Self {
+        Self {
+            heater: HysteresisConfig {
+                setpoint: 4.0,
+                deadband: 2.0,
+                min_on: Duration::from_secs(60),
+                min_off: Duration::from_secs(60),
+            },
+            vent: HysteresisConfig {
+                setpoint: 28.0,
+                deadband: 2.0,
+                min_on: Duration::from_secs(120),
+                min_off: Duration::from_secs(120),
+            },
+            mister: HysteresisConfig {
+                setpoint: 1.3,
+                deadband: 0.6,
+                min_on: Duration::from_secs(10),
+                min_off: Duration::from_secs(60),
+            },
+            irrigation: HysteresisConfig {
+                setpoint: 37.5,
+                deadband: 15.0,
+                min_on: Duration::ZERO,
+                min_off: Duration::from_secs(1800),
+            },
+            irrigation_max_run: Duration::from_secs(600),
+            mist_min_lux: Some(1000.0),
+            frost_alarm: Some(2.0),
+            heat_stress_alarm: Some(35.0),
+            soil_dry_alarm: Some(15.0),
+            alarm_delay: Duration::from_secs(300),
+            max_failures: 3,
+        }
+    }
+}
+
+/// 温室状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GreenhouseStatus {
+    /// 温度（℃）
+    pub temperature: Option<f64>,
+    /// 相对湿度（%）
+    pub humidity: Option<f64>,
+    /// 饱和水汽压差（kPa）
+    pub vpd: Option<f64>,
+    /// 土壤湿度（%）
+    pub soil_moisture: Option<f64>,
+    /// 光照度（lux）
+    pub illuminance: Option<f64>,
+    /// 加热是否打开
+    pub heater: bool,
+    /// 通风是否打开
+    pub vent: bool,
+    /// 喷雾是否打开
+    pub mister: bool,
+    /// 灌溉是否打开
+    pub irrigation: bool,
+    /// 是否有传感器处于故障保护中
+    pub failsafe: bool,
+}
+
+/// 温室使用的传感器
+struct Input {
+    /// 传感器
+    sensor: Box<dyn Sensor>,
+    /// 连续读取失败次数
+    failures: u32,
+}
+
+impl Input {
+    /// 读取一次并合并到`reading`，返回是否处于故障保护中
+    fn read_into(&mut self, reading: &mut Reading, max_failures: u32) -> bool {
+        match self.sensor.read() {
+            Ok(value) => {
+                self.failures = 0;
+                for (quantity, value) in value.iter() {
+                    reading.set(quantity, value);
+                }
+                false
+            }
+            Err(err) => {
+                trace_event!(warn, kind = self.sensor.kind(), error = %err, "温室读取传感器失败");
+                eprintln!("温室读取传感器{}失败: {}", self.sensor.kind(), err);
+                self.failures += 1;
+                self.failures >= max_failures
+            }
+        }
+    }
+}
+
+/// 按测量值更新执行器，没有测量值且传感器处于故障保护中时关闭
+fn drive(
+    relay: Option<&mut RelayLoop>,
+    value: Option<f64>,
+    config: &HysteresisConfig,
+    failsafe: bool,
+) -> anyhow::Result<()> {
+    let Some(relay) = relay else {
+        return Ok(());
+    };
+    match value {
+        Some(value) => {
+            relay.update(value, config)?;
+        }
+        None if failsafe => relay.switch_to(false)?,
+        None => {}
+    }
+    Ok(())
+}
+
+/// 温室
+///
+/// ```ignore
+/// let mcp3008 = MCP3008::new(Bus::Spi0, SlaveSelect::Ss0)?;
+/// let mut greenhouse = Greenhouse::new("greenhouse", GreenhouseSettings::default())?
+///     .with_climate_sensor(Bme280Sensor::new(bus.clone(), None)?)
+///     .with_soil_sensor(SoilMoisture::new(mcp3008.channel(0)?, 820, 360)?)
+///     .with_light_sensor(BH1750::new(bus, 0x23)?)
+///     .with_heater(GpioSwitch::new(22, false)?)
+///     .with_vent(GpioSwitch::new(23, false)?)
+///     .with_mister(GpioSwitch::new(24, false)?)
+///     .with_irrigation(GpioSwitch::new(25, false)?);
+/// let _notify = Webhook::new("https://example.com/hook").attach(greenhouse.alerts());
+/// greenhouse.run(Duration::from_secs(30), |status| println!("{:?}", status));
+/// ```
+pub struct Greenhouse {
+    /// 名称（数据记录和告警中的传感器名称）
+    name: String,
+    /// 参数
+    settings: GreenhouseSettings,
+    /// 温湿度传感器
+    climate: Option<Input>,
+    /// 土壤湿度传感器
+    soil: Option<Input>,
+    /// 光照传感器
+    light: Option<Input>,
+    /// 加热
+    heater: Option<RelayLoop>,
+    /// 通风
+    vent: Option<RelayLoop>,
+    /// 喷雾
+    mister: Option<RelayLoop>,
+    /// 灌溉
+    irrigation: Option<RelayLoop>,
+    /// 报警
+    alerts: AlertEngine,
+    /// 数据记录
+    sinks: Vec<Box<dyn Sink>>,
+}
+
+impl Greenhouse {
+    /// 创建实例（按参数生成告警规则）
+    ///
+    /// 告警规则名称为frost、heat_stress和soil_dry
+    pub fn new(name: &str, settings: GreenhouseSettings) -> anyhow::Result<Self> {
+        let mut alerts = AlertEngine::new();
+        let rules = [
+            (
+                "frost",
+                Quantity::Temperature,
+                settings.frost_alarm.map(Condition::Below),
+                0.5,
+            ),
+            (
+                "heat_stress",
+                Quantity::Temperature,
+                settings.heat_stress_alarm.map(Condition::Above),
+                1.0,
+            ),
+            (
+                "soil_dry",
+                Quantity::SoilMoisture,
+                settings.soil_dry_alarm.map(Condition::Below),
+                3.0,
+            ),
+        ];
+        for (rule, quantity, condition, hysteresis) in rules {
+            let Some(condition) = condition else {
+                continue;
+            };
+            alerts.add_rule(
+                Rule::new(rule, name, quantity, condition)
+                    .with_hysteresis(hysteresis)
+                    .for_duration(settings.alarm_delay),
+            )?;
+        }
+        // OK
+        Ok(Self {
+            name: name.to_string(),
+            settings,
+            climate: None,
+            soil: None,
+            light: None,
+            heater: None,
+            vent: None,
+            mister: None,
+            irrigation: None,
+            alerts,
+            sinks: Vec::new(),
+        })
+    }
+
+    /// 设置温湿度传感器（加热、通风和喷雾需要）
+    pub fn with_climate_sensor<S: Sensor + 'static>(mut self, sensor: S) -> Self {
+        self.climate = Some(Input {
+            sensor: Box::new(sensor),
+            failures: 0,
+        });
+        self
+    }
+
+    /// 设置土壤湿度传感器（灌溉需要）
+    pub fn with_soil_sensor<S: Sensor + 'static>(mut self, sensor: S) -> Self {
+        self.soil = Some(Input {
+            sensor: Box::new(sensor),
+            failures: 0,
+        });
+        self
+    }
+
+    /// 设置光照传感器（只在白天喷雾）
+    pub fn with_light_sensor<S: Sensor + 'static>(mut self, sensor: S) -> Self {
+        self.light = Some(Input {
+            sensor: Box::new(sensor),
+            failures: 0,
+        });
+        self
+    }
+
+    /// 设置加热继电器
+    pub fn with_heater<W: Switch + 'static>(mut self, switch: W) -> Self {
+        self.heater = Some(RelayLoop::new(Box::new(switch)));
+        self
+    }
+
+    /// 设置通风继电器（风机或天窗电机）
+    pub fn with_vent<W: Switch + 'static>(mut self, switch: W) -> Self {
+        self.vent = Some(RelayLoop::reversed(Box::new(switch)));
+        self
+    }
+
+    /// 设置喷雾继电器
+    pub fn with_mister<W: Switch + 'static>(mut self, switch: W) -> Self {
+        self.mister = Some(RelayLoop::reversed(Box::new(switch)));
+        self
+    }
+
+    /// 设置灌溉继电器（水泵或电磁阀）
+    pub fn with_irrigation<W: Switch + 'static>(mut self, switch: W) -> Self {
+        self.irrigation = Some(RelayLoop::new(Box::new(switch)));
+        self
+    }
+
+    /// 添加数据记录
+    pub fn with_sink<S: Sink + 'static>(mut self, sink: S) -> Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    /// 参数
+    pub fn settings(&self) -> &GreenhouseSettings {
+        &self.settings
+    }
+
+    /// 报警规则引擎（订阅告警、绑定蜂鸣器输出、发送通知等）
+    pub fn alerts(&mut self) -> &mut AlertEngine {
+        &mut self.alerts
+    }
+
+    /// 所有传感器中最大的最小读取间隔
+    fn min_interval(&self) -> Duration {
+        [&self.climate, &self.soil, &self.light]
+            .into_iter()
+            .flatten()
+            .map(|input| input.sensor.min_interval())
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// 所有执行器
+    fn relays(&mut self) -> impl Iterator<Item = &mut RelayLoop> {
+        [
+            self.heater.as_mut(),
+            self.vent.as_mut(),
+            self.mister.as_mut(),
+            self.irrigation.as_mut(),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// 读取所有传感器，更新所有执行器，检查报警并写入数据记录
+    pub fn step(&mut self) -> anyhow::Result<GreenhouseStatus> {
+        let max_failures = self.settings.max_failures;
+        let mut reading = Reading::new();
+        let mut read = |input: Option<&mut Input>| {
+            input.is_some_and(|input| input.read_into(&mut reading, max_failures))
+        };
+        let climate_failsafe = read(self.climate.as_mut());
+        let soil_failsafe = read(self.soil.as_mut());
+        let light_failsafe = read(self.light.as_mut());
+
+        let temperature = reading.get(Quantity::Temperature);
+        let humidity = reading.get(Quantity::Humidity);
+        let vpd = temperature
+            .zip(humidity)
+            .map(|(temperature, humidity)| vapor_pressure_deficit(temperature, humidity));
+        let soil_moisture = reading.get(Quantity::SoilMoisture);
+        let illuminance = reading.get(Quantity::Illuminance);
+
+        let settings = &self.settings;
+        drive(
+            self.heater.as_mut(),
+            temperature,
+            &settings.heater,
+            climate_failsafe,
+        )?;
+        drive(
+            self.vent.as_mut(),
+            temperature,
+            &settings.vent,
+            climate_failsafe,
+        )?;
+
+        // 夜间或光照传感器故障时不喷雾
+        let dark = match (settings.mist_min_lux, &self.light) {
+            (Some(min_lux), Some(_)) => match illuminance {
+                Some(illuminance) => illuminance < min_lux,
+                None => light_failsafe,
+            },
+            _ => false,
+        };
+        if dark {
+            drive(self.mister.as_mut(), None, &settings.mister, true)?;
+        } else {
+            drive(
+                self.mister.as_mut(),
+                vpd,
+                &settings.mister,
+                climate_failsafe,
+            )?;
+        }
+
+        drive(
+            self.irrigation.as_mut(),
+            soil_moisture,
+            &settings.irrigation,
+            soil_failsafe,
+        )?;
+        if let Some(irrigation) = self.irrigation.as_mut()
+            && irrigation.is_on()
+            && irrigation
+                .since_change()
+                .is_some_and(|elapsed| elapsed >= settings.irrigation_max_run)
+        {
+            eprintln!("温室{}灌溉超过最长运行时间，已关闭", self.name);
+            irrigation.switch_to(false)?;
+        }
+
+        let sample = Sample {
+            sensor: self.name.clone(),
+            timestamp: SystemTime::now(),
+            reading,
+        };
+        if !sample.reading.is_empty() {
+            self.alerts.evaluate(&sample);
+            for sink in &mut self.sinks {
+                if let Err(err) = sink.write(&sample) {
+                    eprintln!("温室数据记录失败: {}", err);
+                }
+            }
+        }
+        let is_on = |relay: &Option<RelayLoop>| relay.as_ref().is_some_and(|relay| relay.is_on());
+        // OK
+        Ok(GreenhouseStatus {
+            temperature,
+            humidity,
+            vpd,
+            soil_moisture,
+            illuminance,
+            heater: is_on(&self.heater),
+            vent: is_on(&self.vent),
+            mister: is_on(&self.mister),
+            irrigation: is_on(&self.irrigation),
+            failsafe: climate_failsafe || soil_failsafe || light_failsafe,
+        })
+    }
+
+    /// 在后台线程中按固定间隔运行（间隔不小于传感器的最小读取间隔）
+    ///
+    /// 每次更新后回调状态，执行器切换失败时线程退出并关闭所有执行器
+    pub fn run<F>(mut self, interval: Duration, mut cb: F) -> JoinHandle<()>
+    where
+        F: FnMut(&GreenhouseStatus) + Send + 'static,
+    {
+        let interval = interval.max(self.min_interval());
+        thread::spawn(move || {
+            loop {
+                match self.step() {
+                    Ok(status) => cb(&status),
+                    Err(err) => {
+                        eprintln!("温室执行器切换失败: {}", err);
+                        for relay in self.relays() {
+                            let _ = relay.switch_to(false);
+                        }
+                        break;
+                    }
+                }
+                thread::sleep(interval);
+            }
+        })
+    }
+}
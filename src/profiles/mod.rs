@@ -0,0 +1,3 @@
+//! 按使用场景预置的配置（传感器、执行器和告警规则的默认组合）
+
+pub mod greenhouse;
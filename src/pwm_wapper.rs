@@ -0,0 +1,157 @@
+use rppal::gpio::{Gpio, OutputPin};
+use rppal::pwm::{Channel, Polarity, Pwm};
+
+use crate::board::{Board, PwmChannel};
+use crate::debug::{self, PinMode};
+use crate::softpwm::{SoftPwm, SoftPwmChannel};
+
+/// PWM输出封装（硬件PWM或软件PWM）
+///
+/// - 硬件PWM：占用PWM通道，波形稳定，适合电机调速
+/// - 软件PWM：任意GPIO针脚均可使用，波形存在抖动
+/// - 软件PWM引擎：多个针脚共用一个计时线程，频率由引擎统一设置
+pub enum PwmWapper {
+    /// 硬件PWM
+    Hardware(Pwm),
+    /// 软件PWM
+    Software {
+        /// 输出针脚
+        pin: OutputPin,
+        /// 频率（Hz）
+        frequency: f64,
+        /// 当前占空比（0.0~1.0）
+        duty_cycle: f64,
+    },
+    /// 软件PWM引擎的通道
+    Engine(SoftPwmChannel),
+}
+
+impl PwmWapper {
+    /// 创建硬件PWM实例（初始占空比为0）
+    ///
+    /// 树莓派5上通道按RP1的编号（GPIO12、13、18、19分别为通道0~3），并自动查找RP1对应的pwmchip
+    ///
+    /// - channel: PWM通道
+    /// - frequency: 频率（Hz）
+    pub fn hardware(channel: Channel, frequency: f64) -> anyhow::Result<Self> {
+        let board = Board::detect();
+        if board.is_pi5() {
+            let index = match channel {
+                Channel::Pwm0 => 0,
+                Channel::Pwm1 => 1,
+                Channel::Pwm2 => 2,
+                Channel::Pwm3 => 3,
+            };
+            return Self::with_pwmchip(board.pwm_chip(), index, frequency);
+        }
+        let pwm = Pwm::with_frequency(channel, frequency, 0.0, Polarity::Normal, true)?;
+        // OK
+        Ok(PwmWapper::Hardware(pwm))
+    }
+
+    /// 按GPIO针脚创建硬件PWM实例（初始占空比为0）
+    ///
+    /// 根据开发板型号确定pwmchip和通道，不需要关心不同型号的通道编号差异
+    ///
+    /// - pin: 支持硬件PWM的GPIO针脚（12、13、18、19）
+    /// - frequency: 频率（Hz）
+    pub fn hardware_pin(pin: u8, frequency: f64) -> anyhow::Result<Self> {
+        let PwmChannel { chip, channel } = Board::detect().hardware_pwm(pin)?;
+        Self::with_pwmchip(chip, channel, frequency)
+    }
+
+    /// 使用指定pwmchip的通道
+    fn with_pwmchip(chip: u8, channel: u8, frequency: f64) -> anyhow::Result<Self> {
+        let pwm = Pwm::with_pwmchip(chip, channel)
+            .map_err(|err| anyhow::anyhow!("打开pwmchip{}的通道{}失败: {}", chip, channel, err))?;
+        pwm.set_frequency(frequency, 0.0)?;
+        pwm.enable()?;
+        // OK
+        Ok(PwmWapper::Hardware(pwm))
+    }
+
+    /// 创建软件PWM实例（初始占空比为0）
+    ///
+    /// - pin: GPIO针脚
+    /// - frequency: 频率（Hz）
+    pub fn software(pin: u8, frequency: f64) -> anyhow::Result<Self> {
+        let pin = Gpio::new()?.get(pin)?.into_output_low();
+        debug::claim(pin.pin(), "pwm", PinMode::Output);
+        // OK
+        Ok(PwmWapper::Software {
+            pin,
+            frequency,
+            duty_cycle: 0.0,
+        })
+    }
+
+    /// 使用软件PWM引擎的通道（初始占空比为0，频率由引擎决定）
+    ///
+    /// - engine: 软件PWM引擎
+    /// - pin: GPIO针脚
+    pub fn engine(engine: &SoftPwm, pin: u8) -> anyhow::Result<Self> {
+        // OK
+        Ok(PwmWapper::Engine(engine.channel(pin)?))
+    }
+
+    /// 优先使用硬件PWM，针脚不支持或硬件PWM通道已被占用时使用软件PWM引擎
+    ///
+    /// - pin: GPIO针脚
+    /// - frequency: 硬件PWM的频率（Hz），使用引擎时为引擎的频率
+    /// - engine: 后备的软件PWM引擎
+    pub fn hardware_or(pin: u8, frequency: f64, engine: &SoftPwm) -> anyhow::Result<Self> {
+        Self::hardware_pin(pin, frequency).or_else(|_| Self::engine(engine, pin))
+    }
+
+    /// 设置占空比（0.0~1.0，超出范围时截断）
+    pub fn set_duty_cycle(&mut self, duty_cycle: f64) -> anyhow::Result<()> {
+        let duty_cycle = duty_cycle.clamp(0.0, 1.0);
+        match self {
+            PwmWapper::Hardware(pwm) => pwm.set_duty_cycle(duty_cycle)?,
+            PwmWapper::Software {
+                pin,
+                frequency,
+                duty_cycle: current,
+            } => {
+                // 0和100%时直接输出电平，避免软件PWM线程空转
+                if duty_cycle <= 0.0 {
+                    pin.clear_pwm()?;
+                    pin.set_low();
+                } else if duty_cycle >= 1.0 {
+                    pin.clear_pwm()?;
+                    pin.set_high();
+                } else {
+                    pin.set_pwm_frequency(*frequency, duty_cycle)?;
+                }
+                *current = duty_cycle;
+            }
+            PwmWapper::Engine(channel) => channel.set_duty_cycle(duty_cycle)?,
+        }
+        Ok(())
+    }
+
+    /// 读取当前占空比（0.0~1.0）
+    pub fn duty_cycle(&self) -> anyhow::Result<f64> {
+        match self {
+            PwmWapper::Hardware(pwm) => Ok(pwm.duty_cycle()?),
+            PwmWapper::Software { duty_cycle, .. } => Ok(*duty_cycle),
+            PwmWapper::Engine(channel) => Ok(channel.duty_cycle()),
+        }
+    }
+}
+
+impl embedded_hal::pwm::ErrorType for PwmWapper {
+    type Error = embedded_hal::pwm::ErrorKind;
+}
+
+/// 兼容embedded-hal的PWM接口，可直接传给sensor-hal的驱动使用
+impl embedded_hal::pwm::SetDutyCycle for PwmWapper {
+    fn max_duty_cycle(&self) -> u16 {
+        u16::MAX
+    }
+
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        PwmWapper::set_duty_cycle(self, duty as f64 / u16::MAX as f64)
+            .map_err(|_| embedded_hal::pwm::ErrorKind::Other)
+    }
+}
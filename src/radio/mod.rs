@@ -0,0 +1,8 @@
+//! 无线电模块和远程节点
+//!
+//! 田间节点（土壤、气象站）通过LoRa把读数以紧凑的二进制格式（见`codec::binary`）发送到网关，
+//! 网关解码后得到与本地传感器相同的`Sample`，可以继续交给分发管道或各种输出
+
+pub mod payload;
+#[cfg(feature = "sx127x")]
+pub mod sx127x;
@@ -0,0 +1,58 @@
+//! 上行数据包
+//!
+//! 格式：节点ID（1字节）+ 包序号（1字节，每发送一次加1，网关据此统计丢包）+
+//! 二进制编码的读数帧（见`codec::binary`）。节点通常没有可靠的时钟，
+//! 网关以收到的时间减去时间差作为读数时间
+
+use std::time::SystemTime;
+
+use crate::codec::binary::BinaryCodec;
+use crate::reading::Sample;
+
+/// 上行数据包
+#[derive(Debug, Clone, PartialEq)]
+pub struct Uplink {
+    /// 节点ID
+    pub node: u8,
+    /// 包序号
+    pub sequence: u8,
+    /// 读数
+    pub samples: Vec<Sample>,
+}
+
+/// 编码读数
+///
+/// - node: 节点ID
+/// - sequence: 包序号
+/// - codec: 与网关共享传感器列表的编解码器
+pub fn encode(
+    node: u8,
+    sequence: u8,
+    codec: &BinaryCodec,
+    samples: &[Sample],
+) -> anyhow::Result<Vec<u8>> {
+    let mut payload = vec![node, sequence];
+    payload.extend_from_slice(&codec.encode(samples)?);
+    // OK
+    Ok(payload)
+}
+
+/// 解码读数
+///
+/// - codec: 与节点共享传感器列表的编解码器
+/// - received_at: 收到数据包的时间
+pub fn decode(
+    codec: &BinaryCodec,
+    payload: &[u8],
+    received_at: SystemTime,
+) -> anyhow::Result<Uplink> {
+    let [node, sequence, frame @ ..] = payload else {
+        return Err(anyhow::anyhow!("上行数据包过短: {}字节", payload.len()));
+    };
+    // OK
+    Ok(Uplink {
+        node: *node,
+        sequence: *sequence,
+        samples: codec.decode(frame, Some(received_at))?,
+    })
+}
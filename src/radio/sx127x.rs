@@ -0,0 +1,594 @@
+//! SX1276/SX1277/SX1278/SX1279 LoRa射频芯片驱动（SPI）
+//!
+//! 常见模块为Ra-01/Ra-02（433MHz）和RFM95W（868/915MHz），发射使用PA_BOOST输出。
+//! 收发完成通过轮询中断标志寄存器判断，DIO0不需要接线
+
+use rppal::gpio::{Gpio, OutputPin};
+use rppal::spi::{Bus, Mode, SlaveSelect};
+use std::collections::BTreeMap;
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::codec::binary::{self, BinaryCodec};
+use crate::radio::payload;
+use crate::reading::Sample;
+use crate::sink::Sink;
+use crate::spi_bus::SpiDeviceHandle;
+
+/// SX127x寄存器地址（LoRa模式）
+mod reg {
+    pub const FIFO: u8 = 0x00;
+    pub const OP_MODE: u8 = 0x01;
+    pub const FRF_MSB: u8 = 0x06;
+    pub const PA_CONFIG: u8 = 0x09;
+    pub const OCP: u8 = 0x0B;
+    pub const LNA: u8 = 0x0C;
+    pub const FIFO_ADDR_PTR: u8 = 0x0D;
+    pub const FIFO_TX_BASE_ADDR: u8 = 0x0E;
+    pub const FIFO_RX_BASE_ADDR: u8 = 0x0F;
+    pub const FIFO_RX_CURRENT_ADDR: u8 = 0x10;
+    pub const IRQ_FLAGS: u8 = 0x12;
+    pub const RX_NB_BYTES: u8 = 0x13;
+    pub const PKT_SNR_VALUE: u8 = 0x19;
+    pub const PKT_RSSI_VALUE: u8 = 0x1A;
+    pub const MODEM_CONFIG_1: u8 = 0x1D;
+    pub const MODEM_CONFIG_2: u8 = 0x1E;
+    pub const PREAMBLE_MSB: u8 = 0x20;
+    pub const PAYLOAD_LENGTH: u8 = 0x22;
+    pub const MODEM_CONFIG_3: u8 = 0x26;
+    pub const SYNC_WORD: u8 = 0x39;
+    pub const VERSION: u8 = 0x42;
+    pub const PA_DAC: u8 = 0x4D;
+}
+
+/// 工作模式（OP_MODE低3位，最高位为LoRa模式）
+mod mode {
+    pub const LONG_RANGE: u8 = 0x80;
+    pub const SLEEP: u8 = 0x00;
+    pub const STANDBY: u8 = 0x01;
+    pub const TX: u8 = 0x03;
+    pub const RX_CONTINUOUS: u8 = 0x05;
+}
+
+/// 中断标志
+mod irq {
+    pub const TX_DONE: u8 = 0x08;
+    pub const PAYLOAD_CRC_ERROR: u8 = 0x20;
+    pub const RX_DONE: u8 = 0x40;
+}
+
+/// 芯片版本号
+const CHIP_VERSION: u8 = 0x12;
+
+/// 晶振频率（Hz）
+const OSCILLATOR: u64 = 32_000_000;
+
+/// 单个数据包的最大长度
+pub const MAX_PAYLOAD: usize = 255;
+
+/// 带宽
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bandwidth {
+    /// 62.5kHz
+    Khz62_5 = 6,
+    /// 125kHz
+    Khz125 = 7,
+    /// 250kHz
+    Khz250 = 8,
+    /// 500kHz
+    Khz500 = 9,
+}
+
+impl Bandwidth {
+    /// 带宽（Hz）
+    pub fn hz(&self) -> f64 {
+        match self {
+            Self::Khz62_5 => 62_500.0,
+            Self::Khz125 => 125_000.0,
+            Self::Khz250 => 250_000.0,
+            Self::Khz500 => 500_000.0,
+        }
+    }
+}
+
+/// 编码率
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodingRate {
+    /// 4/5
+    Cr4_5 = 1,
+    /// 4/6
+    Cr4_6 = 2,
+    /// 4/7
+    Cr4_7 = 3,
+    /// 4/8
+    Cr4_8 = 4,
+}
+
+/// LoRa调制和发射配置（收发双方的频率、扩频因子、带宽、编码率和同步字需要一致）
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoRaConfig {
+    /// 载波频率（Hz）
+    pub frequency: u64,
+    /// 扩频因子（7~12，越大距离越远、速率越低）
+    pub spreading_factor: u8,
+    /// 带宽
+    pub bandwidth: Bandwidth,
+    /// 编码率
+    pub coding_rate: CodingRate,
+    /// 发射功率（dBm，2~17或20）
+    pub tx_power: i8,
+    /// 同步字（0x12为私有网络，0x34保留给LoRaWAN）
+    pub sync_word: u8,
+    /// 前导码长度（符号数）
+    pub preamble_length: u16,
+    /// 是否附带CRC
+    pub crc: bool,
+}
+
+impl LoRaConfig {
+    /// 创建默认配置（SF9、125kHz、4/5、17dBm）
+    ///
+    /// - frequency: 载波频率（Hz），如433_000_000、868_100_000
+    pub fn new(frequency: u64) -> Self {
+        Self {
+            frequency,
+            spreading_factor: 9,
+            bandwidth: Bandwidth::Khz125,
+            coding_rate: CodingRate::Cr4_5,
+            tx_power: 17,
+            sync_word: 0x12,
+            preamble_length: 8,
+            crc: true,
+        }
+    }
+
+    /// 符号时间（秒）
+    fn symbol_time(&self) -> f64 {
+        (1u32 << self.spreading_factor) as f64 / self.bandwidth.hz()
+    }
+
+    /// 是否需要低速率优化（符号时间超过16毫秒）
+    fn low_data_rate_optimize(&self) -> bool {
+        self.symbol_time() > 0.016
+    }
+
+    /// 发送指定长度的数据包需要的空中时间（按数据手册的公式计算，显式报头）
+    ///
+    /// 部分地区对占空比有要求（如EU868为1%），发送间隔需要大于空中时间的100倍
+    pub fn time_on_air(&self, length: usize) -> Duration {
+        let sf = self.spreading_factor as f64;
+        let de = if self.low_data_rate_optimize() {
+            1.0
+        } else {
+            0.0
+        };
+        let crc = if self.crc { 1.0 } else { 0.0 };
+        let numerator = 8.0 * length as f64 - 4.0 * sf + 28.0 + 16.0 * crc;
+        let symbols = (numerator / (4.0 * (sf - 2.0 * de))).ceil().max(0.0)
+            * (self.coding_rate as u8 as f64 + 4.0);
+        let preamble = self.preamble_length as f64 + 4.25;
+        Duration::from_secs_f64((preamble + 8.0 + symbols) * self.symbol_time())
+    }
+}
+
+/// 收到的数据包
+#[derive(Debug, Clone, PartialEq)]
+pub struct Packet {
+    /// 数据
+    pub data: Vec<u8>,
+    /// 接收信号强度（dBm）
+    pub rssi: i16,
+    /// 信噪比（dB）
+    pub snr: f64,
+}
+
+/// SX127x LoRa射频芯片封装对象
+///
+/// ```ignore
+/// let mut radio = Sx127x::new(Bus::Spi0, SlaveSelect::Ss1, Some(22), LoRaConfig::new(433_000_000))?;
+/// radio.send(b"hello")?;
+/// if let Some(packet) = radio.receive(Duration::from_secs(10))? {
+///     println!("{:02X?} {}dBm", packet.data, packet.rssi);
+/// }
+/// ```
+pub struct Sx127x {
+    /// SPI设备
+    spi: SpiDeviceHandle,
+    /// 复位引脚（可选）
+    #[allow(unused)]
+    reset: Option<OutputPin>,
+    /// 当前配置
+    config: LoRaConfig,
+}
+
+impl Sx127x {
+    /// 创建实例
+    ///
+    /// - bus: SPI总线
+    /// - slave_select: 片选
+    /// - reset_pin: 复位引脚（未接线时传None）
+    /// - config: 调制和发射配置
+    pub fn new(
+        bus: Bus,
+        slave_select: SlaveSelect,
+        reset_pin: Option<u8>,
+        config: LoRaConfig,
+    ) -> anyhow::Result<Self> {
+        // SX127x最高支持10MHz，杜邦线接线时使用1MHz
+        let spi = SpiDeviceHandle::hardware(bus, slave_select, 1_000_000, Mode::Mode0)?;
+        Self::with_device(spi, reset_pin, config)
+    }
+
+    /// 使用共享SPI总线上的设备创建实例
+    ///
+    /// - spi: SPI设备（通过`SpiBus::device`创建，建议1MHz、Mode0）
+    /// - reset_pin: 复位引脚（未接线时传None）
+    /// - config: 调制和发射配置
+    pub fn with_device(
+        spi: SpiDeviceHandle,
+        reset_pin: Option<u8>,
+        config: LoRaConfig,
+    ) -> anyhow::Result<Self> {
+        // 复位：拉低100us以上，然后等待5ms
+        let reset = match reset_pin {
+            Some(pin) => {
+                let mut reset = Gpio::new()?.get(pin)?.into_output_low();
+                thread::sleep(Duration::from_millis(1));
+                reset.set_high();
+                thread::sleep(Duration::from_millis(10));
+                Some(reset)
+            }
+            None => None,
+        };
+
+        let mut this = Self {
+            spi,
+            reset,
+            config: config.clone(),
+        };
+        let version = this.version()?;
+        if version != CHIP_VERSION {
+            return Err(anyhow::anyhow!(
+                "SX127x芯片版本号错误: 0x{:02X}（应为0x{:02X}）",
+                version,
+                CHIP_VERSION
+            ));
+        }
+        // LoRa模式只能在睡眠模式下切换
+        this.write_reg(reg::OP_MODE, mode::LONG_RANGE | mode::SLEEP)?;
+        thread::sleep(Duration::from_millis(10));
+        // 收发各使用整个FIFO
+        this.write_reg(reg::FIFO_TX_BASE_ADDR, 0)?;
+        this.write_reg(reg::FIFO_RX_BASE_ADDR, 0)?;
+        // LNA最大增益、高频端口电流增强
+        this.write_reg(reg::LNA, 0x23)?;
+        this.configure(config)?;
+        this.set_mode(mode::STANDBY)?;
+        // OK
+        Ok(this)
+    }
+
+    /// 读取芯片版本号（SX1276~SX1279为0x12）
+    pub fn version(&mut self) -> anyhow::Result<u8> {
+        self.read_reg(reg::VERSION)
+    }
+
+    /// 当前配置
+    pub fn config(&self) -> &LoRaConfig {
+        &self.config
+    }
+
+    /// 修改调制和发射配置
+    pub fn configure(&mut self, config: LoRaConfig) -> anyhow::Result<()> {
+        if !(7..=12).contains(&config.spreading_factor) {
+            return Err(anyhow::anyhow!(
+                "SX127x扩频因子需要在7~12之间: {}",
+                config.spreading_factor
+            ));
+        }
+        if !(2..=17).contains(&config.tx_power) && config.tx_power != 20 {
+            return Err(anyhow::anyhow!(
+                "SX127x发射功率需要在2~17dBm之间或为20dBm: {}",
+                config.tx_power
+            ));
+        }
+        if !(137_000_000..=1_020_000_000).contains(&config.frequency) {
+            return Err(anyhow::anyhow!(
+                "SX127x频率超出范围: {}Hz",
+                config.frequency
+            ));
+        }
+        self.set_mode(mode::STANDBY)?;
+
+        // 频率：FRF = 频率 * 2^19 / 晶振频率
+        let frf = (config.frequency << 19) / OSCILLATOR;
+        self.write_burst(
+            reg::FRF_MSB,
+            &[(frf >> 16) as u8, (frf >> 8) as u8, frf as u8],
+        )?;
+
+        // 发射功率（PA_BOOST输出），20dBm需要打开高功率模式并提高过流保护
+        if config.tx_power == 20 {
+            self.write_reg(reg::PA_DAC, 0x87)?;
+            self.write_reg(reg::OCP, 0x3B)?;
+            self.write_reg(reg::PA_CONFIG, 0x80 | 15)?;
+        } else {
+            self.write_reg(reg::PA_DAC, 0x84)?;
+            self.write_reg(reg::OCP, 0x2B)?;
+            self.write_reg(reg::PA_CONFIG, 0x80 | (config.tx_power - 2) as u8)?;
+        }
+
+        // 带宽、编码率、显式报头
+        self.write_reg(
+            reg::MODEM_CONFIG_1,
+            (config.bandwidth as u8) << 4 | (config.coding_rate as u8) << 1,
+        )?;
+        // 扩频因子、CRC
+        self.write_reg(
+            reg::MODEM_CONFIG_2,
+            config.spreading_factor << 4 | if config.crc { 0x04 } else { 0x00 },
+        )?;
+        // 低速率优化、自动增益
+        let ldro = if config.low_data_rate_optimize() {
+            0x08
+        } else {
+            0x00
+        };
+        self.write_reg(reg::MODEM_CONFIG_3, ldro | 0x04)?;
+        self.write_burst(reg::PREAMBLE_MSB, &config.preamble_length.to_be_bytes())?;
+        self.write_reg(reg::SYNC_WORD, config.sync_word)?;
+
+        self.config = config;
+        trace_event!(
+            debug,
+            frequency = self.config.frequency,
+            spreading_factor = self.config.spreading_factor,
+            "SX127x配置完成"
+        );
+        // OK
+        Ok(())
+    }
+
+    /// 发送一个数据包（阻塞到发送完成）
+    pub fn send(&mut self, data: &[u8]) -> anyhow::Result<()> {
+        if data.is_empty() || data.len() > MAX_PAYLOAD {
+            return Err(anyhow::anyhow!(
+                "SX127x数据包长度需要在1~{}字节之间: {}",
+                MAX_PAYLOAD,
+                data.len()
+            ));
+        }
+        self.set_mode(mode::STANDBY)?;
+        self.write_reg(reg::FIFO_ADDR_PTR, 0)?;
+        self.write_burst(reg::FIFO, data)?;
+        self.write_reg(reg::PAYLOAD_LENGTH, data.len() as u8)?;
+        self.write_reg(reg::IRQ_FLAGS, 0xFF)?;
+        self.set_mode(mode::TX)?;
+
+        // 等待发送完成（超时为空中时间的2倍）
+        let timeout = self.config.time_on_air(data.len()) * 2 + Duration::from_millis(100);
+        let start = Instant::now();
+        loop {
+            if self.read_reg(reg::IRQ_FLAGS)? & irq::TX_DONE != 0 {
+                break;
+            }
+            if start.elapsed() > timeout {
+                self.set_mode(mode::STANDBY)?;
+                return Err(anyhow::anyhow!("SX127x发送超时"));
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        self.write_reg(reg::IRQ_FLAGS, 0xFF)?;
+        // OK
+        Ok(())
+    }
+
+    /// 接收一个数据包，超时返回None
+    pub fn receive(&mut self, timeout: Duration) -> anyhow::Result<Option<Packet>> {
+        self.write_reg(reg::IRQ_FLAGS, 0xFF)?;
+        self.set_mode(mode::RX_CONTINUOUS)?;
+
+        let start = Instant::now();
+        let flags = loop {
+            let flags = self.read_reg(reg::IRQ_FLAGS)?;
+            if flags & irq::RX_DONE != 0 {
+                break flags;
+            }
+            if start.elapsed() > timeout {
+                self.set_mode(mode::STANDBY)?;
+                return Ok(None);
+            }
+            thread::sleep(Duration::from_millis(5));
+        };
+        self.write_reg(reg::IRQ_FLAGS, 0xFF)?;
+        if flags & irq::PAYLOAD_CRC_ERROR != 0 {
+            self.set_mode(mode::STANDBY)?;
+            return Err(anyhow::anyhow!("SX127x接收的数据包CRC错误"));
+        }
+
+        let length = self.read_reg(reg::RX_NB_BYTES)? as usize;
+        let current = self.read_reg(reg::FIFO_RX_CURRENT_ADDR)?;
+        self.write_reg(reg::FIFO_ADDR_PTR, current)?;
+        let mut data = vec![0u8; length];
+        self.read_burst(reg::FIFO, &mut data)?;
+
+        // 信噪比为有符号数（单位0.25dB），信号强度按端口计算（高频端口为779MHz以上）
+        let snr = self.read_reg(reg::PKT_SNR_VALUE)? as i8 as f64 / 4.0;
+        let offset = if self.config.frequency > 779_000_000 {
+            -157
+        } else {
+            -164
+        };
+        let rssi = offset + self.read_reg(reg::PKT_RSSI_VALUE)? as i16;
+        self.set_mode(mode::STANDBY)?;
+        trace_event!(debug, length, rssi, snr, "SX127x收到数据包");
+        // OK
+        Ok(Some(Packet { data, rssi, snr }))
+    }
+
+    /// 进入睡眠模式（电流约0.2uA，下次收发时自动唤醒）
+    pub fn sleep(&mut self) -> anyhow::Result<()> {
+        self.set_mode(mode::SLEEP)
+    }
+
+    /// 切换工作模式
+    fn set_mode(&mut self, mode: u8) -> anyhow::Result<()> {
+        self.write_reg(reg::OP_MODE, mode::LONG_RANGE | mode)
+    }
+
+    /// 读寄存器
+    fn read_reg(&mut self, reg: u8) -> anyhow::Result<u8> {
+        let mut read = [0u8; 2];
+        self.spi.transfer(&mut read, &[reg & 0x7F, 0])?;
+        Ok(read[1])
+    }
+
+    /// 写寄存器
+    fn write_reg(&mut self, reg: u8, value: u8) -> anyhow::Result<()> {
+        self.spi.write(&[reg | 0x80, value])
+    }
+
+    /// 连续读取（地址自动递增，FIFO地址不递增）
+    fn read_burst(&mut self, reg: u8, buffer: &mut [u8]) -> anyhow::Result<()> {
+        let mut write = vec![0u8; buffer.len() + 1];
+        write[0] = reg & 0x7F;
+        let mut read = vec![0u8; buffer.len() + 1];
+        self.spi.transfer(&mut read, &write)?;
+        buffer.copy_from_slice(&read[1..]);
+        Ok(())
+    }
+
+    /// 连续写入（地址自动递增，FIFO地址不递增）
+    fn write_burst(&mut self, reg: u8, data: &[u8]) -> anyhow::Result<()> {
+        let mut write = Vec::with_capacity(data.len() + 1);
+        write.push(reg | 0x80);
+        write.extend_from_slice(data);
+        self.spi.write(&write)
+    }
+}
+
+/// LoRa定时上行（可克隆，克隆后共享同一个射频芯片）
+///
+/// 记录每个传感器的最新读数，每个周期把上次发送后有更新的读数编码到尽量少的数据包中发送，
+/// 发送完成后芯片进入睡眠模式。周期需要满足所在地区的占空比限制
+///
+/// ```ignore
+/// let codec = BinaryCodec::new(&["soil", "weather"])?;
+/// let uplink = LoRaUplink::new(radio, 1, codec).with_interval(Duration::from_secs(300));
+/// uplink.record_from(scheduler.subscribe());
+/// uplink.start().join();
+/// ```
+#[derive(Clone)]
+pub struct LoRaUplink {
+    /// 射频芯片
+    radio: Arc<Mutex<Sx127x>>,
+    /// 节点ID
+    node: u8,
+    /// 读数编解码器
+    codec: Arc<BinaryCodec>,
+    /// 发送周期
+    interval: Duration,
+    /// 等待发送的读数（每个传感器一个）
+    pending: Arc<Mutex<BTreeMap<String, Sample>>>,
+}
+
+impl LoRaUplink {
+    /// 创建实例（发送周期为60秒）
+    ///
+    /// - radio: 射频芯片
+    /// - node: 节点ID（网关据此区分节点）
+    /// - codec: 与网关共享传感器列表的编解码器
+    pub fn new(radio: Sx127x, node: u8, codec: BinaryCodec) -> Self {
+        Self {
+            radio: Arc::new(Mutex::new(radio)),
+            node,
+            codec: Arc::new(codec),
+            interval: Duration::from_secs(60),
+            pending: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    /// 设置发送周期
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// 射频芯片（发送间隙可以用来接收下行数据）
+    pub fn radio(&self) -> Arc<Mutex<Sx127x>> {
+        self.radio.clone()
+    }
+
+    /// 记录一个新读数（覆盖同一传感器还没有发送的读数）
+    pub fn record(&self, sample: Sample) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(sample.sensor.clone(), sample);
+        }
+    }
+
+    /// 在后台线程中记录收到的所有读数（如调度器的订阅），通道关闭后线程退出
+    pub fn record_from(&self, samples: Receiver<Sample>) -> JoinHandle<()> {
+        let this = self.clone();
+        thread::spawn(move || {
+            for sample in samples {
+                this.record(sample);
+            }
+        })
+    }
+
+    /// 按数据包长度上限分批（节点ID和包序号占2字节）
+    fn batches(samples: impl Iterator<Item = Sample>) -> Vec<Vec<Sample>> {
+        let mut batches: Vec<Vec<Sample>> = Vec::new();
+        let mut len = 0;
+        for sample in samples {
+            let sample_len = binary::encoded_len(&sample);
+            if batches.is_empty() || len + sample_len > MAX_PAYLOAD {
+                batches.push(Vec::new());
+                len = 2 + binary::HEADER_LEN;
+            }
+            len += sample_len;
+            if let Some(batch) = batches.last_mut() {
+                batch.push(sample);
+            }
+        }
+        batches
+    }
+
+    /// 在后台线程中按周期发送
+    pub fn start(&self) -> JoinHandle<()> {
+        let this = self.clone();
+        thread::spawn(move || {
+            let mut sequence: u8 = 0;
+            loop {
+                thread::sleep(this.interval);
+                let samples = match this.pending.lock() {
+                    Ok(mut pending) => std::mem::take(&mut *pending),
+                    Err(_) => return,
+                };
+                let Ok(mut radio) = this.radio.lock() else {
+                    return;
+                };
+                for batch in Self::batches(samples.into_values()) {
+                    let result = payload::encode(this.node, sequence, &this.codec, &batch)
+                        .and_then(|data| radio.send(&data));
+                    if let Err(err) = result {
+                        eprintln!("LoRa发送{}个读数失败: {}", batch.len(), err);
+                    }
+                    sequence = sequence.wrapping_add(1);
+                }
+                if let Err(err) = radio.sleep() {
+                    eprintln!("SX127x进入睡眠模式失败: {}", err);
+                }
+            }
+        })
+    }
+}
+
+impl Sink for LoRaUplink {
+    fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        self.record(sample.clone());
+        Ok(())
+    }
+}
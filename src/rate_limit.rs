@@ -0,0 +1,102 @@
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 读取过快错误（可以通过`anyhow::Error::downcast_ref`取出）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TooSoon {
+    /// 还需要等待的时间
+    pub retry_after: Duration,
+}
+
+impl fmt::Display for TooSoon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "读取过快，{}毫秒后重试", self.retry_after.as_millis())
+    }
+}
+
+impl std::error::Error for TooSoon {}
+
+/// 读取间隔不足时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Policy {
+    /// 等待到允许读取的时间
+    Wait,
+    /// 返回`TooSoon`错误
+    Error,
+}
+
+/// 驱动内部的读取频率限制
+///
+/// DHT11连续读取会使芯片自热、HX711在转换周期内读取会得到上一次的数据，
+/// 驱动在每次读取前检查与上一次读取的间隔
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    /// 两次读取之间的最小间隔
+    min_interval: Duration,
+    /// 间隔不足时的处理方式
+    policy: Policy,
+    /// 上一次读取的时间
+    last: Option<Instant>,
+}
+
+impl RateLimit {
+    /// 创建实例
+    pub fn new(min_interval: Duration, policy: Policy) -> Self {
+        Self {
+            min_interval,
+            policy,
+            last: None,
+        }
+    }
+
+    /// 不限制读取频率
+    pub fn disabled() -> Self {
+        Self::new(Duration::ZERO, Policy::Wait)
+    }
+
+    /// 两次读取之间的最小间隔
+    pub fn min_interval(&self) -> Duration {
+        self.min_interval
+    }
+
+    /// 设置两次读取之间的最小间隔
+    pub fn set_min_interval(&mut self, min_interval: Duration) {
+        self.min_interval = min_interval;
+    }
+
+    /// 间隔不足时的处理方式
+    pub fn policy(&self) -> Policy {
+        self.policy
+    }
+
+    /// 设置间隔不足时的处理方式
+    pub fn set_policy(&mut self, policy: Policy) {
+        self.policy = policy;
+    }
+
+    /// 还需要等待的时间（可以立即读取时为0）
+    pub fn remaining(&self) -> Duration {
+        self.last
+            .map(|last| (last + self.min_interval).saturating_duration_since(Instant::now()))
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// 读取前调用：间隔足够时记录本次读取时间，否则按处理方式等待或返回错误
+    pub fn acquire(&mut self) -> Result<(), TooSoon> {
+        let remaining = self.remaining();
+        if !remaining.is_zero() {
+            match self.policy {
+                Policy::Wait => thread::sleep(remaining),
+                Policy::Error => {
+                    return Err(TooSoon {
+                        retry_after: remaining,
+                    });
+                }
+            }
+        }
+        self.last = Some(Instant::now());
+        Ok(())
+    }
+}
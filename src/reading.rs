@@ -0,0 +1,339 @@
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::core::EnvReading;
+
+/// 物理量
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Quantity {
+    /// 温度（℃）
+    Temperature,
+    /// 相对湿度（%）
+    Humidity,
+    /// 气压（Pa）
+    Pressure,
+    /// 重量（单位由电子秤矫正时决定）
+    Weight,
+    /// 电压（V）
+    Voltage,
+    /// ADC原始读数
+    Raw,
+    /// 露点（℃）
+    DewPoint,
+    /// 海平面气压（Pa）
+    SeaLevelPressure,
+    /// 风速（m/s）
+    WindSpeed,
+    /// 阵风风速（m/s）
+    WindGust,
+    /// 降雨量（mm）
+    Rainfall,
+    /// 光照度（lx）
+    Illuminance,
+    /// 土壤湿度（%）
+    SoilMoisture,
+}
+
+impl Quantity {
+    /// 所有物理量
+    pub const ALL: [Quantity; 13] = [
+        Quantity::Temperature,
+        Quantity::Humidity,
+        Quantity::Pressure,
+        Quantity::Weight,
+        Quantity::Voltage,
+        Quantity::Raw,
+        Quantity::DewPoint,
+        Quantity::SeaLevelPressure,
+        Quantity::WindSpeed,
+        Quantity::WindGust,
+        Quantity::Rainfall,
+        Quantity::Illuminance,
+        Quantity::SoilMoisture,
+    ];
+
+    /// 按名称查找物理量
+    pub fn from_name(name: &str) -> Option<Quantity> {
+        Self::ALL.into_iter().find(|q| q.name() == name)
+    }
+
+    /// 名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Quantity::Temperature => "temperature",
+            Quantity::Humidity => "humidity",
+            Quantity::Pressure => "pressure",
+            Quantity::Weight => "weight",
+            Quantity::Voltage => "voltage",
+            Quantity::Raw => "raw",
+            Quantity::DewPoint => "dew_point",
+            Quantity::SeaLevelPressure => "sea_level_pressure",
+            Quantity::WindSpeed => "wind_speed",
+            Quantity::WindGust => "wind_gust",
+            Quantity::Rainfall => "rainfall",
+            Quantity::Illuminance => "illuminance",
+            Quantity::SoilMoisture => "soil_moisture",
+        }
+    }
+
+    /// 单位
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Quantity::Temperature => "°C",
+            Quantity::Humidity => "%",
+            Quantity::Pressure => "Pa",
+            Quantity::Weight => "g",
+            Quantity::Voltage => "V",
+            Quantity::Raw => "",
+            Quantity::DewPoint => "°C",
+            Quantity::SeaLevelPressure => "Pa",
+            Quantity::WindSpeed | Quantity::WindGust => "m/s",
+            Quantity::Rainfall => "mm",
+            Quantity::Illuminance => "lx",
+            Quantity::SoilMoisture => "%",
+        }
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// 一次读数（一个传感器可能同时测量多个物理量）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Reading {
+    values: Vec<(Quantity, f64)>,
+}
+
+impl Reading {
+    /// 创建空读数
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加物理量（链式调用）
+    pub fn with(mut self, quantity: Quantity, value: f64) -> Self {
+        self.set(quantity, value);
+        self
+    }
+
+    /// 设置物理量（已存在时覆盖）
+    pub fn set(&mut self, quantity: Quantity, value: f64) {
+        match self.values.iter_mut().find(|(q, _)| *q == quantity) {
+            Some((_, v)) => *v = value,
+            None => self.values.push((quantity, value)),
+        }
+    }
+
+    /// 读取物理量
+    pub fn get(&self, quantity: Quantity) -> Option<f64> {
+        self.values
+            .iter()
+            .find(|(q, _)| *q == quantity)
+            .map(|(_, v)| *v)
+    }
+
+    /// 温度（℃）
+    pub fn temperature(&self) -> Option<f64> {
+        self.get(Quantity::Temperature)
+    }
+
+    /// 相对湿度（%）
+    pub fn humidity(&self) -> Option<f64> {
+        self.get(Quantity::Humidity)
+    }
+
+    /// 气压（Pa）
+    pub fn pressure(&self) -> Option<f64> {
+        self.get(Quantity::Pressure)
+    }
+
+    /// 遍历所有物理量
+    pub fn iter(&self) -> impl Iterator<Item = (Quantity, f64)> + '_ {
+        self.values.iter().copied()
+    }
+
+    /// 是否为空
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl From<EnvReading> for Reading {
+    fn from(env: EnvReading) -> Self {
+        let mut reading = Reading::new().with(Quantity::Temperature, env.temperature);
+        if let Some(humidity) = env.humidity {
+            reading.set(Quantity::Humidity, humidity);
+        }
+        if let Some(pressure) = env.pressure {
+            reading.set(Quantity::Pressure, pressure);
+        }
+        reading
+    }
+}
+
+impl fmt::Display for Reading {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (quantity, value)) in self.values.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}: {:.2}{}", quantity, value, quantity.unit())?;
+        }
+        Ok(())
+    }
+}
+
+/// 序列化为物理量名称到数值的映射，如`{"temperature": 23.5, "humidity": 41.0}`
+#[cfg(feature = "serde")]
+impl serde::Serialize for Reading {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(self.values.len()))?;
+        for (quantity, value) in &self.values {
+            map.serialize_entry(quantity.name(), value)?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Reading {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ReadingVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for ReadingVisitor {
+            type Value = Reading;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("物理量名称到数值的映射")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> Result<Reading, A::Error> {
+                let mut reading = Reading::new();
+                while let Some((name, value)) = map.next_entry::<String, f64>()? {
+                    let quantity = Quantity::from_name(&name).ok_or_else(|| {
+                        serde::de::Error::custom(format!("未知的物理量: {}", name))
+                    })?;
+                    reading.set(quantity, value);
+                }
+                Ok(reading)
+            }
+        }
+
+        deserializer.deserialize_map(ReadingVisitor)
+    }
+}
+
+/// 时间序列化为Unix时间戳（秒），用法：`#[serde(with = "crate::reading::unix_seconds")]`
+#[cfg(feature = "serde")]
+pub(crate) mod unix_seconds {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    pub fn serialize<S: Serializer>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+        let seconds = time
+            .duration_since(UNIX_EPOCH)
+            .map(|t| t.as_secs_f64())
+            .unwrap_or_default();
+        serializer.serialize_f64(seconds)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SystemTime, D::Error> {
+        let seconds = f64::deserialize(deserializer)?;
+        super::from_unix_seconds(seconds).map_err(D::Error::custom)
+    }
+}
+
+/// Unix时间戳（秒）转换为时间
+///
+/// 早于1970年的按1970年计算，非有限值或超出时间范围时返回错误
+pub(crate) fn from_unix_seconds(seconds: f64) -> anyhow::Result<SystemTime> {
+    if !seconds.is_finite() {
+        return Err(anyhow::anyhow!("时间戳无效: {}", seconds));
+    }
+    let duration = Duration::try_from_secs_f64(seconds.max(0.0))
+        .map_err(|err| anyhow::anyhow!("时间戳无效: {}: {}", seconds, err))?;
+    let time = UNIX_EPOCH
+        .checked_add(duration)
+        .ok_or_else(|| anyhow::anyhow!("时间戳超出范围: {}", seconds))?;
+    // OK
+    Ok(time)
+}
+
+/// 带时间戳的传感器读数
+///
+/// 序列化格式与`to_json`相同：`{"sensor": "outdoor", "timestamp": 1700000000.123, "temperature": 23.5}`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sample {
+    /// 传感器名称
+    pub sensor: String,
+    /// 读取时间
+    #[cfg_attr(feature = "serde", serde(with = "unix_seconds"))]
+    pub timestamp: SystemTime,
+    /// 读数
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub reading: Reading,
+}
+
+impl Sample {
+    /// 创建读数样本（时间戳为当前时间）
+    pub fn new(sensor: &str, reading: Reading) -> Self {
+        Self {
+            sensor: sensor.to_string(),
+            timestamp: SystemTime::now(),
+            reading,
+        }
+    }
+
+    /// 创建指定时间的读数样本（Unix时间戳，秒）
+    ///
+    /// 时间戳为非有限值或超出时间范围时返回错误
+    pub fn at_unix_timestamp(
+        sensor: &str,
+        timestamp: f64,
+        reading: Reading,
+    ) -> anyhow::Result<Self> {
+        let timestamp = from_unix_seconds(timestamp)?;
+        // OK
+        Ok(Self {
+            sensor: sensor.to_string(),
+            timestamp,
+            reading,
+        })
+    }
+
+    /// 读取时间（Unix时间戳，秒）
+    pub fn unix_timestamp(&self) -> f64 {
+        self.timestamp
+            .duration_since(UNIX_EPOCH)
+            .map(|t| t.as_secs_f64())
+            .unwrap_or_default()
+    }
+
+    /// 转换为JSON对象
+    ///
+    /// 格式：`{"sensor": "outdoor", "timestamp": 1700000000.123, "temperature": 23.5, "humidity": 41.0}`
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut object = serde_json::Map::new();
+        object.insert("sensor".to_string(), self.sensor.clone().into());
+        object.insert("timestamp".to_string(), self.unix_timestamp().into());
+        for (quantity, value) in self.reading.iter() {
+            object.insert(quantity.name().to_string(), value.into());
+        }
+        serde_json::Value::Object(object)
+    }
+}
@@ -0,0 +1,226 @@
+//! 配置热重载
+//!
+//! 长时间运行的网关修改硬件（更换I2C地址、新增传感器、停用故障传感器）时不需要重启：
+//! `ConfigReloader`重新读取配置文件，与当前配置比较后只处理有变化的传感器——
+//! 新增的注册并开始调度，删除或停用的移除，只修改读取间隔的直接更新，
+//! 其他修改重新创建传感器。可以手动调用`reload`，也可以在收到SIGHUP时自动重载
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+
+use signal_hook::consts::SIGHUP;
+use signal_hook::iterator::Signals;
+
+use crate::config::{HardwareConfig, I2cBuses, SensorConfig};
+use crate::scheduler::SchedulerControl;
+
+/// 重载结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReloadReport {
+    /// 新增的传感器
+    pub added: Vec<String>,
+    /// 移除的传感器
+    pub removed: Vec<String>,
+    /// 重新创建的传感器
+    pub replaced: Vec<String>,
+    /// 只修改了读取间隔的传感器
+    pub updated: Vec<String>,
+    /// 处理失败的传感器（名称, 错误信息），下一次重载时重试
+    pub errors: Vec<(String, String)>,
+}
+
+impl ReloadReport {
+    /// 配置没有变化
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.replaced.is_empty()
+            && self.updated.is_empty()
+            && self.errors.is_empty()
+    }
+}
+
+impl fmt::Display for ReloadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "配置没有变化");
+        }
+        let mut parts = Vec::new();
+        for (label, names) in [
+            ("新增", &self.added),
+            ("移除", &self.removed),
+            ("重建", &self.replaced),
+            ("更新", &self.updated),
+        ] {
+            if !names.is_empty() {
+                parts.push(format!("{}: {}", label, names.join(", ")));
+            }
+        }
+        for (name, err) in &self.errors {
+            parts.push(format!("{}失败: {}", name, err));
+        }
+        write!(f, "{}", parts.join("; "))
+    }
+}
+
+/// 配置热重载
+///
+/// ```ignore
+/// let config = HardwareConfig::load("sensors.toml")?;
+/// let mut buses = I2cBuses::default();
+/// let scheduler = Scheduler::new(config.build_with(&mut buses)?)?.start();
+/// ConfigReloader::new("sensors.toml", config, buses, scheduler.control()).on_sighup()?;
+/// ```
+pub struct ConfigReloader {
+    /// 配置文件
+    path: PathBuf,
+    /// 当前生效的配置
+    config: HardwareConfig,
+    /// 已打开的I2C总线（新增的传感器与已有的传感器共享总线）
+    buses: I2cBuses,
+    /// 调度器控制接口
+    control: SchedulerControl,
+}
+
+impl ConfigReloader {
+    /// 创建实例
+    ///
+    /// - path: 配置文件
+    /// - config: 当前生效的配置（创建管理器时使用的配置）
+    /// - buses: 创建管理器时打开的I2C总线
+    /// - control: 调度器控制接口
+    pub fn new<P: AsRef<Path>>(
+        path: P,
+        config: HardwareConfig,
+        buses: I2cBuses,
+        control: SchedulerControl,
+    ) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            config,
+            buses,
+            control,
+        }
+    }
+
+    /// 当前生效的配置
+    pub fn config(&self) -> &HardwareConfig {
+        &self.config
+    }
+
+    /// 重新读取配置文件并应用（配置文件无效时保持当前配置）
+    pub fn reload(&mut self) -> anyhow::Result<ReloadReport> {
+        let config = HardwareConfig::load(&self.path)?;
+        // OK
+        Ok(self.apply(config))
+    }
+
+    /// 应用新的配置
+    ///
+    /// 先移除删除或停用的传感器（释放引脚），再处理新增和修改的传感器；
+    /// 处理失败的传感器不计入当前配置，下一次重载时重试
+    pub fn apply(&mut self, mut config: HardwareConfig) -> ReloadReport {
+        let mut report = ReloadReport::default();
+        let enabled = |config: &HardwareConfig, name: &str| {
+            config
+                .sensors
+                .get(name)
+                .filter(|sensor| sensor.is_enabled())
+                .cloned()
+        };
+
+        for name in self.config.sensors.keys() {
+            if enabled(&self.config, name).is_some()
+                && enabled(&config, name).is_none()
+                && self.control.remove(name)
+            {
+                report.removed.push(name.clone());
+            }
+        }
+
+        let mut failed = Vec::new();
+        for (name, new) in &config.sensors {
+            if !new.is_enabled() {
+                continue;
+            }
+            let result = match enabled(&self.config, name) {
+                None => self.add(name, new).map(|_| &mut report.added),
+                Some(old) if old == *new => continue,
+                Some(old) if old.same_hardware(new) => {
+                    self.update(name, new).map(|_| &mut report.updated)
+                }
+                Some(_) => self.replace(name, new).map(|_| &mut report.replaced),
+            };
+            match result {
+                Ok(list) => list.push(name.clone()),
+                Err(err) => {
+                    trace_event!(warn, sensor = %name, error = %err, "重载传感器配置失败");
+                    report.errors.push((name.clone(), err.to_string()));
+                    failed.push(name.clone());
+                }
+            }
+        }
+
+        // 失败的传感器保持原状：仍在运行的使用旧配置，未运行的视为不存在
+        for name in failed {
+            let running = self.control.manager().contains(&name);
+            match self.config.sensors.get(&name) {
+                Some(old) if running => config.sensors.insert(name, old.clone()),
+                _ => config.sensors.remove(&name),
+            };
+        }
+        self.config = config;
+        trace_event!(info, report = %report, "已重载配置");
+        report
+    }
+
+    /// 新增传感器
+    fn add(&mut self, name: &str, config: &SensorConfig) -> anyhow::Result<()> {
+        let sensor = config.build(name, &mut self.buses)?;
+        self.control.add(name, sensor, config.min_interval(), None)
+    }
+
+    /// 只修改读取间隔
+    fn update(&mut self, name: &str, config: &SensorConfig) -> anyhow::Result<()> {
+        self.control
+            .manager()
+            .set_min_interval(name, config.min_interval())?;
+        self.control.schedule(name, None)
+    }
+
+    /// 重新创建传感器
+    ///
+    /// 新旧配置使用相同的引脚时，旧的传感器仍占用引脚会使创建失败，
+    /// 此时先移除旧的传感器再创建
+    fn replace(&mut self, name: &str, config: &SensorConfig) -> anyhow::Result<()> {
+        match config.build(name, &mut self.buses) {
+            Ok(sensor) => {
+                self.control
+                    .manager()
+                    .replace(name, sensor, config.min_interval())?;
+                self.control.schedule(name, None)
+            }
+            Err(_) => {
+                trace_event!(debug, sensor = name, "创建失败，移除旧的传感器后重试");
+                self.control.remove(name);
+                self.add(name, config)
+            }
+        }
+    }
+
+    /// 在后台线程中监听SIGHUP，收到信号后重新读取配置文件
+    pub fn on_sighup(mut self) -> anyhow::Result<JoinHandle<()>> {
+        let mut signals =
+            Signals::new([SIGHUP]).map_err(|err| anyhow::anyhow!("注册SIGHUP处理失败: {}", err))?;
+        // OK
+        Ok(thread::spawn(move || {
+            for _ in signals.forever() {
+                match self.reload() {
+                    Ok(report) => eprintln!("已重载配置{}: {}", self.path.display(), report),
+                    Err(err) => eprintln!("重载配置失败，保持当前配置: {}", err),
+                }
+            }
+        }))
+    }
+}
@@ -0,0 +1,140 @@
+use crate::calibration::Calibration;
+
+/// 称重ADC（HX711、NAU7802等）
+pub trait WeightAdc: Send {
+    /// 读取一次ADC读数（阻塞直到数据就绪）
+    fn read_raw(&mut self) -> anyhow::Result<i32>;
+}
+
+/// 电子秤（ADC读数滤波、去皮和重量换算）
+///
+/// 重量 = (ADC读数 - 0点偏移值) / 矫正因子，重量单位由矫正时传入的砝码重量决定
+pub struct Scale<A: WeightAdc> {
+    /// 称重ADC
+    adc: A,
+    /// ADC读数0点偏移值（俗称皮重）
+    zero_offset: i32,
+    /// ADC读数转换为实物重量时的矫正因子
+    transform_factor: f32,
+    /// 每次读取重量时的平均采样次数
+    samples: usize,
+}
+
+impl<A: WeightAdc> Scale<A> {
+    /// 创建电子秤实例
+    ///
+    /// - transform_factor: 矫正因子（未矫正过时可先传1.0，再调用`calibrate`）
+    pub fn new(adc: A, transform_factor: f32) -> Self {
+        Self {
+            adc,
+            zero_offset: 0,
+            transform_factor,
+            samples: 5,
+        }
+    }
+
+    /// 获取称重ADC
+    pub fn adc(&mut self) -> &mut A {
+        &mut self.adc
+    }
+
+    /// 设置每次读取重量时的平均采样次数（默认5次）
+    pub fn set_samples(&mut self, samples: usize) {
+        self.samples = samples.max(1);
+    }
+
+    /// 每次读取重量时的平均采样次数
+    pub fn samples(&self) -> usize {
+        self.samples
+    }
+
+    /// 读取多次ADC读数并计算平均值
+    pub fn read_average(&mut self, samples: usize) -> anyhow::Result<i32> {
+        let samples = samples.max(1);
+        let mut sum: i64 = 0;
+        for _ in 0..samples {
+            sum += self.adc.read_raw()? as i64;
+        }
+        Ok((sum / samples as i64) as i32)
+    }
+
+    /// 去皮（以当前读数作为0点）
+    pub fn tare(&mut self) -> anyhow::Result<()> {
+        self.zero_offset = self.read_average(self.samples * 2)?;
+        Ok(())
+    }
+
+    /// 矫正（秤盘上放置已知重量的砝码后调用）
+    ///
+    /// - actual_weight: 砝码的实际重量
+    ///
+    /// 返回计算得到的矫正因子
+    pub fn calibrate(&mut self, actual_weight: f32) -> anyhow::Result<f32> {
+        // 实际重量不能为0，否则无法计算矫正因子
+        if actual_weight == 0.0 {
+            return Err(anyhow::anyhow!("实际重量不能为0"));
+        }
+        let valid_adc_data = self.read_average(self.samples * 2)? - self.zero_offset;
+        if valid_adc_data == 0 {
+            return Err(anyhow::anyhow!("有效ADC读数为0，请检查砝码是否放置"));
+        }
+        self.transform_factor = valid_adc_data as f32 / actual_weight;
+        // OK
+        Ok(self.transform_factor)
+    }
+
+    /// 读取重量
+    pub fn read_weight(&mut self) -> anyhow::Result<f32> {
+        let adc_data = self.read_average(self.samples)?;
+        self.transform(adc_data)
+    }
+
+    /// ADC读数换算为重量
+    pub fn transform(&self, adc_data: i32) -> anyhow::Result<f32> {
+        if self.transform_factor == 0.0 {
+            return Err(anyhow::anyhow!("矫正因子为0时无法转换重量，请设置矫正因子"));
+        }
+        Ok((adc_data - self.zero_offset) as f32 / self.transform_factor)
+    }
+
+    /// ADC读数0点偏移值
+    pub fn zero_offset(&self) -> i32 {
+        self.zero_offset
+    }
+
+    /// 设置ADC读数0点偏移值
+    pub fn set_zero_offset(&mut self, zero_offset: i32) {
+        self.zero_offset = zero_offset;
+    }
+
+    /// 矫正因子
+    pub fn transform_factor(&self) -> f32 {
+        self.transform_factor
+    }
+
+    /// 设置矫正因子
+    pub fn set_transform_factor(&mut self, transform_factor: f32) {
+        self.transform_factor = transform_factor;
+    }
+
+    /// 从校准数据中恢复0点偏移值和矫正因子
+    ///
+    /// - prefix: 校准数据名称前缀（多台秤共用一份校准数据时区分）
+    pub fn load_calibration(&mut self, calibration: &Calibration, prefix: &str) {
+        if let Some(zero_offset) = calibration.get(&format!("{}.zero_offset", prefix)) {
+            self.zero_offset = zero_offset as i32;
+        }
+        if let Some(transform_factor) = calibration.get(&format!("{}.transform_factor", prefix)) {
+            self.transform_factor = transform_factor as f32;
+        }
+    }
+
+    /// 将0点偏移值和矫正因子写入校准数据
+    pub fn save_calibration(&self, calibration: &mut Calibration, prefix: &str) {
+        calibration.set(&format!("{}.zero_offset", prefix), self.zero_offset as f64);
+        calibration.set(
+            &format!("{}.transform_factor", prefix),
+            self.transform_factor as f64,
+        );
+    }
+}
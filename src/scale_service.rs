@@ -0,0 +1,761 @@
+//! 称重服务
+//!
+//! `Scale`只负责一次读取的换算，实际的电子秤还需要后台持续采集、滤波、稳定判断、
+//! 去皮和矫正（通常由按钮触发）以及重启后恢复皮重和矫正因子。`ScaleService`在后台线程中
+//! 持续读取称重ADC，将每次的重量和状态发送给订阅者，去皮和矫正结果写入校准数据存储
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::calibration::CalibrationStore;
+use crate::event_bus::{broadcast, subscribe};
+use crate::reading::{Quantity, Reading, Sample};
+use crate::scale::WeightAdc;
+use crate::units::weight::{Division, WeightFormat, ZeroTracker};
+
+pub use crate::units::weight::WeightUnit;
+
+/// 重量状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WeightStatus {
+    /// 不稳定
+    Unstable,
+    /// 稳定
+    Stable,
+    /// 欠载
+    Underload,
+    /// 超载
+    Overload,
+    /// 错误
+    Error,
+}
+
+/// 称重读数
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightReading {
+    /// 重量（克，读取失败时为上一次的重量）
+    pub grams: f64,
+    /// 显示单位
+    pub unit: WeightUnit,
+    /// 显示分度值
+    pub division: Option<Division>,
+    /// 计数模式下的件数
+    pub pieces: Option<u32>,
+    /// 状态
+    pub status: WeightStatus,
+    /// 读取时间
+    pub timestamp: SystemTime,
+}
+
+impl WeightReading {
+    /// 按显示单位换算的重量
+    pub fn value(&self) -> f64 {
+        self.unit.from_grams(self.grams)
+    }
+
+    /// 显示格式
+    pub fn format(&self) -> WeightFormat {
+        WeightFormat {
+            unit: self.unit,
+            division: self.division,
+        }
+    }
+
+    /// 按显示单位换算并取整到分度值的重量
+    pub fn display_value(&self) -> f64 {
+        self.format().round(self.grams)
+    }
+
+    /// 转换为读数样本（重量单位为克）
+    pub fn to_sample(&self, sensor: &str) -> Sample {
+        Sample {
+            sensor: sensor.to_string(),
+            timestamp: self.timestamp,
+            reading: Reading::new().with(Quantity::Weight, self.grams),
+        }
+    }
+}
+
+impl fmt::Display for WeightReading {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(pieces) = self.pieces {
+            write!(f, "{}pcs ", pieces)?;
+        }
+        write!(
+            f,
+            "{} ({:?})",
+            self.format().format(self.grams),
+            self.status
+        )
+    }
+}
+
+/// 计数秤的件数换算
+///
+/// 放上已知数量的样品后得到单件重量，之后按重量换算件数。继续加入样品且稳定后，
+/// 如果件数接近整数且不超过已学习件数的2倍，以更多的件数重新计算单件重量，
+/// 减小样品个体差异和漂移带来的误差
+#[derive(Debug, Clone, PartialEq)]
+pub struct PieceCounter {
+    /// 单件重量（克）
+    unit_weight: f64,
+    /// 计算单件重量使用的件数
+    learned_count: u32,
+}
+
+impl PieceCounter {
+    /// 重新学习时件数与整数的最大偏差
+    const RELEARN_TOLERANCE: f64 = 0.2;
+
+    /// 以样品重量创建
+    ///
+    /// - grams: 样品总重量（克）
+    /// - reference_count: 样品件数
+    pub fn new(grams: f64, reference_count: u32) -> anyhow::Result<Self> {
+        if reference_count == 0 {
+            return Err(anyhow::anyhow!("样品件数不能为0"));
+        }
+        if !grams.is_finite() || grams <= 0.0 {
+            return Err(anyhow::anyhow!("样品重量必须大于0: {}", grams));
+        }
+        // OK
+        Ok(Self {
+            unit_weight: grams / reference_count as f64,
+            learned_count: reference_count,
+        })
+    }
+
+    /// 单件重量（克）
+    pub fn unit_weight(&self) -> f64 {
+        self.unit_weight
+    }
+
+    /// 计算单件重量使用的件数
+    pub fn learned_count(&self) -> u32 {
+        self.learned_count
+    }
+
+    /// 重量换算为件数
+    pub fn count(&self, grams: f64) -> u32 {
+        (grams / self.unit_weight).round().max(0.0) as u32
+    }
+
+    /// 换算件数，读数稳定时按条件重新学习单件重量
+    pub fn update(&mut self, grams: f64, stable: bool) -> u32 {
+        let exact = grams / self.unit_weight;
+        let count = self.count(grams);
+        if stable
+            && count > self.learned_count
+            && count <= self.learned_count.saturating_mul(2)
+            && (exact - count as f64).abs() <= Self::RELEARN_TOLERANCE
+        {
+            self.unit_weight = grams / count as f64;
+            self.learned_count = count;
+            trace_event!(
+                debug,
+                unit_weight = self.unit_weight,
+                count,
+                "计数秤重新学习单件重量"
+            );
+        }
+        count
+    }
+}
+
+/// 重量变化事件
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WeightEvent {
+    /// 放入物品（增加的重量，克）
+    ItemAdded(f64),
+    /// 取走物品（减少的重量，克）
+    ItemRemoved(f64),
+}
+
+/// 重量平台变化检测（智能货架、宠物喂食器等）
+///
+/// 读数稳定时的重量为一个平台，与上一个平台相差超过阈值时产生放入或取走事件，
+/// 不稳定、超载、欠载和错误的读数不参与比较
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlateauDetector {
+    /// 阈值（克）
+    threshold: f64,
+    /// 上一个平台的重量（克）
+    plateau: Option<f64>,
+}
+
+impl PlateauDetector {
+    /// 创建实例
+    ///
+    /// - threshold: 阈值（克）
+    pub fn new(threshold: f64) -> Self {
+        Self {
+            threshold: threshold.abs(),
+            plateau: None,
+        }
+    }
+
+    /// 上一个平台的重量（克）
+    pub fn plateau(&self) -> Option<f64> {
+        self.plateau
+    }
+
+    /// 清除平台（去皮后调用，下一个稳定读数作为新的平台）
+    pub fn reset(&mut self) {
+        self.plateau = None;
+    }
+
+    /// 输入读数，平台变化超过阈值时返回事件
+    pub fn update(&mut self, reading: &WeightReading) -> Option<WeightEvent> {
+        if reading.status != WeightStatus::Stable {
+            return None;
+        }
+        let Some(plateau) = self.plateau else {
+            self.plateau = Some(reading.grams);
+            return None;
+        };
+        let delta = reading.grams - plateau;
+        if delta.abs() <= self.threshold {
+            return None;
+        }
+        self.plateau = Some(reading.grams);
+        if delta > 0.0 {
+            Some(WeightEvent::ItemAdded(delta))
+        } else {
+            Some(WeightEvent::ItemRemoved(-delta))
+        }
+    }
+}
+
+/// 称重服务参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaleSettings {
+    /// 显示单位
+    pub unit: WeightUnit,
+    /// 显示分度值（显示单位，修改单位时需要同时修改），为None时不取整
+    pub division: Option<Division>,
+    /// 零点跟踪范围（克，通常为0.5个分度值），稳定且偏离零点不超过该值时自动归零，
+    /// 累计修正不超过最大称量的4%，为0时关闭
+    pub zero_tracking: f64,
+    /// 最大称量（克），超过时为超载
+    pub capacity: f64,
+    /// 最小重量（克），低于时为欠载（去皮后的小幅负漂移不视为欠载）
+    pub min_weight: f64,
+    /// 滑动平均滤波的ADC读数数量
+    pub filter_window: usize,
+    /// 稳定判断的滤波结果数量
+    pub stable_window: usize,
+    /// 稳定判断的允许波动（克）
+    pub stable_tolerance: f64,
+    /// 读取间隔
+    pub interval: Duration,
+    /// 没有保存过皮重时，启动后以第一次滤波结果去皮
+    pub tare_on_start: bool,
+}
+
+impl Default for ScaleSettings {
+    fn default() -> Self {
+        Self {
+            unit: WeightUnit::Gram,
+            division: None,
+            zero_tracking: 0.0,
+            capacity: 5000.0,
+            min_weight: -1.0,
+            filter_window: 5,
+            stable_window: 3,
+            stable_tolerance: 1.0,
+            interval: Duration::from_millis(100),
+            tare_on_start: true,
+        }
+    }
+}
+
+/// 校准数据存储和名称前缀
+struct Persistence {
+    store: Box<dyn CalibrationStore>,
+    prefix: String,
+}
+
+impl Persistence {
+    /// 写入0点偏移值和矫正因子（与`Scale::save_calibration`使用相同的名称）
+    fn save(&mut self, zero_offset: i32, transform_factor: f32) -> anyhow::Result<()> {
+        let mut calibration = self.store.load()?;
+        calibration.set(&format!("{}.zero_offset", self.prefix), zero_offset as f64);
+        calibration.set(
+            &format!("{}.transform_factor", self.prefix),
+            transform_factor as f64,
+        );
+        self.store.save(&calibration)
+    }
+}
+
+/// 稳定判断队列已满且波动不超过允许值时为稳定
+fn is_stable(values: &VecDeque<f64>, settings: &ScaleSettings) -> bool {
+    if values.len() < settings.stable_window.max(1) {
+        return false;
+    }
+    let max = values.iter().cloned().fold(f64::MIN, f64::max);
+    let min = values.iter().cloned().fold(f64::MAX, f64::min);
+    max - min <= settings.stable_tolerance
+}
+
+/// 采集线程和控制端共享的状态
+struct State {
+    /// 参数
+    settings: ScaleSettings,
+    /// ADC读数0点偏移值（俗称皮重），未去皮时为None
+    zero_offset: Option<i32>,
+    /// 矫正因子（每克的ADC读数）
+    transform_factor: f32,
+    /// 零点跟踪
+    zero_tracker: ZeroTracker,
+    /// 计数模式
+    counter: Option<PieceCounter>,
+    /// 最新的滤波结果
+    average: Option<i32>,
+    /// 最新的读数
+    latest: Option<WeightReading>,
+}
+
+impl State {
+    /// ADC读数换算为重量（克）
+    fn grams(&self, adc_data: i32) -> Option<f64> {
+        if self.transform_factor == 0.0 {
+            return None;
+        }
+        let zero_offset = self.zero_offset?;
+        Some((adc_data - zero_offset) as f64 / self.transform_factor as f64)
+    }
+}
+
+/// 共享状态
+struct Shared {
+    state: Mutex<State>,
+    /// 读数订阅者
+    subscribers: Mutex<Vec<Sender<WeightReading>>>,
+    /// 状态变化订阅者
+    status_subscribers: Mutex<Vec<Sender<WeightReading>>>,
+    /// 重量变化事件订阅者（每个订阅者有各自的阈值）
+    event_subscribers: Mutex<Vec<(PlateauDetector, Sender<WeightEvent>)>>,
+    /// 校准数据存储
+    persistence: Mutex<Option<Persistence>>,
+    /// 是否停止
+    stopped: AtomicBool,
+}
+
+impl Shared {
+    /// 检测平台变化并发送事件，移除已断开的订阅者
+    fn broadcast_events(&self, reading: &WeightReading) {
+        if let Ok(mut subscribers) = self.event_subscribers.lock() {
+            subscribers.retain_mut(|(detector, tx)| match detector.update(reading) {
+                Some(event) => tx.send(event).is_ok(),
+                None => true,
+            });
+        }
+    }
+
+    /// 清除所有订阅者的平台（去皮、矫正后重量的跳变不产生事件）
+    fn reset_events(&self) {
+        if let Ok(mut subscribers) = self.event_subscribers.lock() {
+            for (detector, _) in subscribers.iter_mut() {
+                detector.reset();
+            }
+        }
+    }
+
+    /// 锁定状态（采集线程异常退出时仍然可以读取）
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 保存0点偏移值和矫正因子（没有设置存储时忽略）
+    fn persist(&self) -> anyhow::Result<()> {
+        let (zero_offset, transform_factor) = {
+            let state = self.state();
+            (state.zero_offset.unwrap_or(0), state.transform_factor)
+        };
+        let mut persistence = self
+            .persistence
+            .lock()
+            .map_err(|_| anyhow::anyhow!("校准数据存储不可用"))?;
+        match persistence.as_mut() {
+            Some(persistence) => persistence.save(zero_offset, transform_factor),
+            None => Ok(()),
+        }
+    }
+}
+
+/// 称重服务
+///
+/// 矫正因子为每克的ADC读数，与显示单位无关：
+///
+/// ```ignore
+/// let adc = HX711::new(24, 23, ChannelGain::ChannelA128, None)?;
+/// let scale = ScaleService::new(adc, ScaleSettings::default())
+///     .with_store(FileStore::new("/var/lib/scale.cal"), "kitchen")?
+///     .start();
+/// for reading in scale.subscribe_status() {
+///     println!("{}", reading);
+/// }
+/// ```
+pub struct ScaleService<A: WeightAdc> {
+    /// 称重ADC
+    adc: A,
+    /// 共享状态
+    shared: Arc<Shared>,
+}
+
+impl<A: WeightAdc + 'static> ScaleService<A> {
+    /// 创建实例（未矫正时矫正因子为1.0，需要调用`ScaleHandle::calibrate`）
+    pub fn new(adc: A, settings: ScaleSettings) -> Self {
+        Self {
+            adc,
+            shared: Arc::new(Shared {
+                state: Mutex::new(State {
+                    settings,
+                    zero_offset: None,
+                    transform_factor: 1.0,
+                    zero_tracker: ZeroTracker::new(0.0, 0.0),
+                    counter: None,
+                    average: None,
+                    latest: None,
+                }),
+                subscribers: Mutex::new(Vec::new()),
+                status_subscribers: Mutex::new(Vec::new()),
+                event_subscribers: Mutex::new(Vec::new()),
+                persistence: Mutex::new(None),
+                stopped: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// 设置矫正因子（每克的ADC读数）
+    pub fn with_transform_factor(self, transform_factor: f32) -> Self {
+        self.shared.state().transform_factor = transform_factor;
+        self
+    }
+
+    /// 设置0点偏移值（设置后启动时不再自动去皮）
+    pub fn with_zero_offset(self, zero_offset: i32) -> Self {
+        self.shared.state().zero_offset = Some(zero_offset);
+        self
+    }
+
+    /// 设置校准数据存储，并恢复保存过的0点偏移值和矫正因子
+    ///
+    /// - prefix: 校准数据名称前缀（多台秤共用一份校准数据时区分）
+    pub fn with_store<S: CalibrationStore + 'static>(
+        self,
+        mut store: S,
+        prefix: &str,
+    ) -> anyhow::Result<Self> {
+        let calibration = store.load()?;
+        {
+            let mut state = self.shared.state();
+            if let Some(zero_offset) = calibration.get(&format!("{}.zero_offset", prefix)) {
+                state.zero_offset = Some(zero_offset as i32);
+            }
+            if let Some(transform_factor) = calibration.get(&format!("{}.transform_factor", prefix))
+            {
+                state.transform_factor = transform_factor as f32;
+            }
+        }
+        if let Ok(mut persistence) = self.shared.persistence.lock() {
+            *persistence = Some(Persistence {
+                store: Box::new(store),
+                prefix: prefix.to_string(),
+            });
+        }
+        // OK
+        Ok(self)
+    }
+
+    /// 在后台线程中开始采集
+    pub fn start(self) -> ScaleHandle {
+        let Self { mut adc, shared } = self;
+        let worker = shared.clone();
+        let thread = thread::spawn(move || {
+            let mut filter: VecDeque<i32> = VecDeque::new();
+            let mut stable: VecDeque<f64> = VecDeque::new();
+            let mut status: Option<WeightStatus> = None;
+            let mut next = Instant::now();
+            while !worker.stopped.load(Ordering::SeqCst) {
+                let result = adc.read_raw();
+                let reading = {
+                    let mut state = worker.state();
+                    let settings = state.settings.clone();
+                    let (grams, new_status) = match result {
+                        Ok(adc_data) => {
+                            // 滑动平均滤波
+                            filter.push_back(adc_data);
+                            while filter.len() > settings.filter_window.max(1) {
+                                filter.pop_front();
+                            }
+                            let average = (filter.iter().map(|v| *v as i64).sum::<i64>()
+                                / filter.len() as i64)
+                                as i32;
+                            state.average = Some(average);
+                            if state.zero_offset.is_none() && settings.tare_on_start {
+                                state.zero_offset = Some(average);
+                            }
+                            match state.grams(average) {
+                                Some(grams) => {
+                                    stable.push_back(grams);
+                                    while stable.len() > settings.stable_window.max(1) {
+                                        stable.pop_front();
+                                    }
+                                    let steady = is_stable(&stable, &settings);
+                                    // 零点跟踪（OIML R76：累计修正不超过最大称量的4%）
+                                    state.zero_tracker.set_range(
+                                        settings.zero_tracking,
+                                        settings.capacity * 0.04,
+                                    );
+                                    let grams = state.zero_tracker.apply(grams, steady);
+                                    let status = if grams < settings.min_weight {
+                                        WeightStatus::Underload
+                                    } else if grams > settings.capacity {
+                                        WeightStatus::Overload
+                                    } else if steady {
+                                        WeightStatus::Stable
+                                    } else {
+                                        WeightStatus::Unstable
+                                    };
+                                    (grams, status)
+                                }
+                                // 矫正因子为0或未去皮时无法换算
+                                None => (0.0, WeightStatus::Error),
+                            }
+                        }
+                        Err(err) => {
+                            trace_event!(warn, error = %err, "读取称重ADC失败");
+                            eprintln!("读取称重ADC失败: {}", err);
+                            let grams = state.latest.as_ref().map_or(0.0, |latest| latest.grams);
+                            (grams, WeightStatus::Error)
+                        }
+                    };
+                    let stable = new_status == WeightStatus::Stable;
+                    let pieces = state
+                        .counter
+                        .as_mut()
+                        .map(|counter| counter.update(grams, stable));
+                    let reading = WeightReading {
+                        grams,
+                        unit: settings.unit,
+                        division: settings.division,
+                        pieces,
+                        status: new_status,
+                        timestamp: SystemTime::now(),
+                    };
+                    state.latest = Some(reading.clone());
+                    reading
+                };
+                broadcast(&worker.subscribers, reading.clone());
+                if status != Some(reading.status) {
+                    status = Some(reading.status);
+                    broadcast(&worker.status_subscribers, reading.clone());
+                }
+                worker.broadcast_events(&reading);
+
+                // 按固定节拍读取
+                let interval = worker.state().settings.interval;
+                next += interval;
+                let now = Instant::now();
+                if next > now {
+                    thread::sleep(next - now);
+                } else {
+                    next = now;
+                }
+            }
+        });
+        ScaleHandle {
+            shared,
+            thread: Arc::new(Mutex::new(Some(thread))),
+        }
+    }
+}
+
+/// 运行中的称重服务（可克隆，供按钮回调、显示线程等共享）
+#[derive(Clone)]
+pub struct ScaleHandle {
+    /// 共享状态
+    shared: Arc<Shared>,
+    /// 采集线程
+    thread: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl ScaleHandle {
+    /// 订阅每一次的读数
+    pub fn subscribe(&self) -> Receiver<WeightReading> {
+        subscribe(&self.shared.subscribers)
+    }
+
+    /// 订阅状态变化（变为稳定、超载等时发送一次）
+    pub fn subscribe_status(&self) -> Receiver<WeightReading> {
+        subscribe(&self.shared.status_subscribers)
+    }
+
+    /// 订阅重量变化事件（稳定重量与上一次相差超过阈值时发送一次）
+    ///
+    /// - threshold: 阈值（克）
+    pub fn subscribe_events(&self, threshold: f64) -> Receiver<WeightEvent> {
+        let (tx, rx) = mpsc::channel();
+        if let Ok(mut subscribers) = self.shared.event_subscribers.lock() {
+            subscribers.push((PlateauDetector::new(threshold), tx));
+        }
+        rx
+    }
+
+    /// 最新的读数
+    pub fn latest(&self) -> Option<WeightReading> {
+        self.shared.state().latest.clone()
+    }
+
+    /// 去皮（以最新的滤波结果作为0点），并保存到校准数据存储
+    pub fn tare(&self) -> anyhow::Result<()> {
+        {
+            let mut state = self.shared.state();
+            let average = state
+                .average
+                .ok_or_else(|| anyhow::anyhow!("还没有读取到ADC读数，无法去皮"))?;
+            state.zero_offset = Some(average);
+            state.zero_tracker.reset();
+        }
+        // 去皮后重量回到0，不是取走物品
+        self.shared.reset_events();
+        self.shared.persist()
+    }
+
+    /// 矫正（秤盘上放置已知重量的砝码后调用），并保存到校准数据存储
+    ///
+    /// - mass: 砝码的实际重量
+    /// - unit: 砝码重量的单位
+    ///
+    /// 返回计算得到的矫正因子
+    pub fn calibrate(&self, mass: f64, unit: WeightUnit) -> anyhow::Result<f32> {
+        let grams = unit.to_grams(mass);
+        // 实际重量不能为0，否则无法计算矫正因子
+        if grams == 0.0 {
+            return Err(anyhow::anyhow!("实际重量不能为0"));
+        }
+        let transform_factor = {
+            let mut state = self.shared.state();
+            let average = state
+                .average
+                .ok_or_else(|| anyhow::anyhow!("还没有读取到ADC读数，无法矫正"))?;
+            let zero_offset = state
+                .zero_offset
+                .ok_or_else(|| anyhow::anyhow!("矫正前需要先去皮"))?;
+            let valid_adc_data = average - zero_offset;
+            if valid_adc_data == 0 {
+                return Err(anyhow::anyhow!("有效ADC读数为0，请检查砝码是否放置"));
+            }
+            state.transform_factor = (valid_adc_data as f64 / grams) as f32;
+            state.zero_tracker.reset();
+            state.transform_factor
+        };
+        self.shared.reset_events();
+        self.shared.persist()?;
+        // OK
+        Ok(transform_factor)
+    }
+
+    /// 进入计数模式（秤盘上放置已知数量的样品且读数稳定后调用）
+    ///
+    /// - reference_count: 样品件数
+    ///
+    /// 返回单件重量（克），之后的读数带有件数
+    pub fn count_mode(&self, reference_count: u32) -> anyhow::Result<f64> {
+        let mut state = self.shared.state();
+        let latest = state
+            .latest
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("还没有读取到重量，无法进入计数模式"))?;
+        if latest.status != WeightStatus::Stable {
+            return Err(anyhow::anyhow!(
+                "读数不稳定，无法进入计数模式: {:?}",
+                latest.status
+            ));
+        }
+        let counter = PieceCounter::new(latest.grams, reference_count)?;
+        let unit_weight = counter.unit_weight();
+        state.counter = Some(counter);
+        // OK
+        Ok(unit_weight)
+    }
+
+    /// 退出计数模式
+    pub fn exit_count_mode(&self) {
+        self.shared.state().counter = None;
+    }
+
+    /// 计数模式的单件重量（克），不在计数模式时为None
+    pub fn unit_weight(&self) -> Option<f64> {
+        self.shared
+            .state()
+            .counter
+            .as_ref()
+            .map(|counter| counter.unit_weight())
+    }
+
+    /// 显示单位
+    pub fn unit(&self) -> WeightUnit {
+        self.shared.state().settings.unit
+    }
+
+    /// 设置显示单位
+    pub fn set_unit(&self, unit: WeightUnit) {
+        self.shared.state().settings.unit = unit;
+    }
+
+    /// 设置显示分度值（显示单位）
+    pub fn set_division(&self, division: Option<Division>) {
+        self.shared.state().settings.division = division;
+    }
+
+    /// 零点跟踪的累计修正量（克）
+    pub fn zero_tracking_offset(&self) -> f64 {
+        self.shared.state().zero_tracker.offset()
+    }
+
+    /// 设置最大称量和最小重量（克）
+    pub fn set_limits(&self, min_weight: f64, capacity: f64) {
+        let mut state = self.shared.state();
+        state.settings.min_weight = min_weight;
+        state.settings.capacity = capacity;
+    }
+
+    /// 当前参数
+    pub fn settings(&self) -> ScaleSettings {
+        self.shared.state().settings.clone()
+    }
+
+    /// ADC读数0点偏移值
+    pub fn zero_offset(&self) -> Option<i32> {
+        self.shared.state().zero_offset
+    }
+
+    /// 矫正因子（每克的ADC读数）
+    pub fn transform_factor(&self) -> f32 {
+        self.shared.state().transform_factor
+    }
+
+    /// 停止采集并等待采集线程退出
+    pub fn stop(&self) {
+        self.shared.stopped.store(true, Ordering::SeqCst);
+        let thread = match self.thread.lock() {
+            Ok(mut thread) => thread.take(),
+            Err(poisoned) => poisoned.into_inner().take(),
+        };
+        if let Some(thread) = thread {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,798 @@
+//! 执行器定时
+//!
+//! 按时间表控制开关型执行器（浇水电磁阀、补光灯、水泵）：规则的开始时间可以是
+//! cron表达式、固定间隔或日出/日落前后（需要经纬度），结束时间为固定时长或另一个时间点。
+//! 程序停止期间错过的定时任务在宽限时间内补执行；手动打开或关闭后保持到指定时间或
+//! 时间表下一次变化
+//!
+//! 本地时间使用固定的UTC偏移，不处理夏令时
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::calibration::CalibrationStore;
+use crate::switch::Switch;
+
+/// 一天的秒数
+const DAY: i64 = 86_400;
+
+/// 向前查找时间点的最大天数
+const MAX_LOOKBACK_DAYS: i64 = 366;
+
+/// 当前Unix时间（秒）
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64)
+}
+
+/// 自1970-01-01起的天数转换为（年, 月, 日）
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// UTC日期和时间
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct UtcDateTime {
+    pub(crate) year: i64,
+    pub(crate) month: u32,
+    pub(crate) day: u32,
+    pub(crate) hour: u32,
+    pub(crate) minute: u32,
+    pub(crate) second: u32,
+    pub(crate) millisecond: u32,
+}
+
+impl UtcDateTime {
+    /// 转换系统时间（早于1970-01-01时为1970-01-01 00:00:00）
+    pub(crate) fn from_system_time(time: SystemTime) -> Self {
+        let elapsed = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let secs = elapsed.as_secs() as i64;
+        let (year, month, day) = civil_from_days(secs.div_euclid(DAY));
+        let secs_of_day = secs.rem_euclid(DAY) as u32;
+        Self {
+            year,
+            month,
+            day,
+            hour: secs_of_day / 3600,
+            minute: secs_of_day / 60 % 60,
+            second: secs_of_day % 60,
+            millisecond: elapsed.subsec_millis(),
+        }
+    }
+}
+
+/// （年, 月, 日）转换为自1970-01-01起的天数
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// 一年中的第几天（1月1日为1）
+fn day_of_year(days: i64) -> i64 {
+    let (year, _, _) = civil_from_days(days);
+    days - days_from_civil(year, 1, 1) + 1
+}
+
+/// 星期（0为周日）
+fn weekday(days: i64) -> u32 {
+    // 1970-01-01为周四
+    (days + 4).rem_euclid(7) as u32
+}
+
+/// 地理位置（计算日出日落）
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Location {
+    /// 纬度（北纬为正）
+    pub latitude: f64,
+    /// 经度（东经为正）
+    pub longitude: f64,
+}
+
+/// 日出或日落
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SunEvent {
+    /// 日出
+    Sunrise,
+    /// 日落
+    Sunset,
+}
+
+impl Location {
+    /// 创建实例
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+        }
+    }
+
+    /// 指定日期（自1970-01-01起的天数）的日出或日落时间（UTC小时，0~24）
+    ///
+    /// 按NOAA简化算法计算，误差在几分钟以内；极昼、极夜时返回None
+    fn sun_hour(&self, days: i64, event: SunEvent) -> Option<f64> {
+        let normalize = |value: f64, range: f64| value.rem_euclid(range);
+        let lng_hour = self.longitude / 15.0;
+        let base = match event {
+            SunEvent::Sunrise => 6.0,
+            SunEvent::Sunset => 18.0,
+        };
+        let t = day_of_year(days) as f64 + (base - lng_hour) / 24.0;
+        // 太阳平近点角和真黄经
+        let m = 0.9856 * t - 3.289;
+        let l = normalize(
+            m + 1.916 * m.to_radians().sin() + 0.020 * (2.0 * m).to_radians().sin() + 282.634,
+            360.0,
+        );
+        // 赤经（与黄经在同一象限）
+        let mut ra = normalize((0.91764 * l.to_radians().tan()).atan().to_degrees(), 360.0);
+        ra += (l / 90.0).floor() * 90.0 - (ra / 90.0).floor() * 90.0;
+        let ra = ra / 15.0;
+        // 赤纬和时角（天顶角90.833°，包含大气折射和太阳视半径）
+        let sin_dec = 0.39782 * l.to_radians().sin();
+        let cos_dec = sin_dec.asin().cos();
+        let lat = self.latitude.to_radians();
+        let cos_h = (90.833f64.to_radians().cos() - sin_dec * lat.sin()) / (cos_dec * lat.cos());
+        if !(-1.0..=1.0).contains(&cos_h) {
+            return None;
+        }
+        let h = match event {
+            SunEvent::Sunrise => 360.0 - cos_h.acos().to_degrees(),
+            SunEvent::Sunset => cos_h.acos().to_degrees(),
+        } / 15.0;
+        let local_mean = h + ra - 0.06571 * t - 6.622;
+        Some(normalize(local_mean - lng_hour, 24.0))
+    }
+}
+
+/// 时区和位置
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Clock {
+    /// 本地时间相对UTC的偏移（秒）
+    utc_offset: i64,
+    /// 地理位置
+    location: Option<Location>,
+}
+
+impl Clock {
+    /// 本地时间所在的日期（自1970-01-01起的天数）和当天的秒数
+    fn local(&self, unix: i64) -> (i64, i64) {
+        let local = unix + self.utc_offset;
+        (local.div_euclid(DAY), local.rem_euclid(DAY))
+    }
+
+    /// 本地日期的日出或日落时间（Unix时间）
+    fn sun(&self, days: i64, event: SunEvent) -> Option<i64> {
+        let hour = self.location?.sun_hour(days, event)?;
+        let mut unix = days * DAY + (hour * 3600.0).round() as i64;
+        // 计算结果是UTC时间，换算到与本地日期正午最接近的一天
+        let noon = days * DAY + DAY / 2 - self.utc_offset;
+        while unix - noon > DAY / 2 {
+            unix -= DAY;
+        }
+        while noon - unix > DAY / 2 {
+            unix += DAY;
+        }
+        Some(unix)
+    }
+}
+
+/// cron表达式（分 时 日 月 周）
+///
+/// 支持`*`、数字、范围`1-5`、列表`1,3,5`和步长`*/15`、`8-18/2`；
+/// 周日可以写为0或7；日和周都不是`*`时满足其一即可（与cron一致）。
+/// 也支持`@hourly`、`@daily`、`@weekly`、`@monthly`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cron {
+    /// 原始表达式
+    text: String,
+    /// 分钟（0~59）
+    minutes: u64,
+    /// 小时（0~23）
+    hours: u64,
+    /// 日（1~31）
+    days: u64,
+    /// 月（1~12）
+    months: u64,
+    /// 星期（0~6）
+    weekdays: u64,
+    /// 日是否为`*`
+    any_day: bool,
+    /// 星期是否为`*`
+    any_weekday: bool,
+}
+
+impl Cron {
+    /// 解析单个字段为位集合
+    fn parse_field(field: &str, min: u32, max: u32) -> anyhow::Result<u64> {
+        let mut bits = 0u64;
+        for part in field.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => (
+                    range,
+                    step.parse::<u32>()
+                        .ok()
+                        .filter(|step| *step > 0)
+                        .ok_or_else(|| anyhow::anyhow!("无效的步长: {}", part))?,
+                ),
+                None => (part, 1),
+            };
+            let parse = |text: &str| {
+                text.parse::<u32>()
+                    .ok()
+                    .filter(|value| (min..=max).contains(value))
+                    .ok_or_else(|| anyhow::anyhow!("超出范围{}~{}: {}", min, max, part))
+            };
+            let (start, end) = match range {
+                "*" => (min, max),
+                _ => match range.split_once('-') {
+                    Some((start, end)) => (parse(start)?, parse(end)?),
+                    // 单个数字带步长时到最大值为止
+                    None if step > 1 => (parse(range)?, max),
+                    None => (parse(range)?, parse(range)?),
+                },
+            };
+            if start > end {
+                return Err(anyhow::anyhow!("无效的范围: {}", part));
+            }
+            for value in (start..=end).step_by(step as usize) {
+                bits |= 1 << value;
+            }
+        }
+        Ok(bits)
+    }
+
+    /// 指定日期是否匹配日、月、星期
+    fn matches_day(&self, days: i64) -> bool {
+        let (_, month, day) = civil_from_days(days);
+        if self.months & (1 << month) == 0 {
+            return false;
+        }
+        let day_ok = self.days & (1 << day) != 0;
+        let weekday_ok = self.weekdays & (1 << weekday(days)) != 0;
+        match (self.any_day, self.any_weekday) {
+            (false, false) => day_ok || weekday_ok,
+            _ => day_ok && weekday_ok,
+        }
+    }
+
+    /// 当天不晚于指定秒数的最后一个匹配时间（当天的秒数）
+    fn last_in_day(&self, limit: i64) -> Option<i64> {
+        let limit_minute = (limit / 60) as u32;
+        (0..24u32)
+            .rev()
+            .filter(|hour| self.hours & (1 << hour) != 0)
+            .flat_map(|hour| {
+                (0..60u32)
+                    .rev()
+                    .filter(|minute| self.minutes & (1 << minute) != 0)
+                    .map(move |minute| hour * 60 + minute)
+            })
+            .find(|minute| *minute <= limit_minute)
+            .map(|minute| minute as i64 * 60)
+    }
+}
+
+impl FromStr for Cron {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> anyhow::Result<Self> {
+        let expanded = match text.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(anyhow::anyhow!(
+                "cron表达式需要5个字段（分 时 日 月 周）: {}",
+                text
+            ));
+        };
+        let error = |err: anyhow::Error| anyhow::anyhow!("无效的cron表达式\"{}\": {}", text, err);
+        // 星期7与0都表示周日
+        let mut weekdays = Self::parse_field(weekday, 0, 7).map_err(error)?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+        // OK
+        Ok(Self {
+            text: text.trim().to_string(),
+            minutes: Self::parse_field(minute, 0, 59).map_err(error)?,
+            hours: Self::parse_field(hour, 0, 23).map_err(error)?,
+            days: Self::parse_field(day, 1, 31).map_err(error)?,
+            months: Self::parse_field(month, 1, 12).map_err(error)?,
+            weekdays,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        })
+    }
+}
+
+impl fmt::Display for Cron {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// 时间点
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimeSpec {
+    /// cron表达式（本地时间）
+    Cron(Cron),
+    /// 固定间隔（从本地时间0点起对齐，如每2小时为0点、2点、4点……）
+    Every(Duration),
+    /// 日出或日落前后（偏移为正数时在之后）
+    Sun {
+        /// 日出或日落
+        event: SunEvent,
+        /// 偏移（分钟）
+        offset_minutes: i64,
+    },
+}
+
+impl TimeSpec {
+    /// cron表达式
+    pub fn cron(text: &str) -> anyhow::Result<Self> {
+        Ok(TimeSpec::Cron(text.parse()?))
+    }
+
+    /// 每天的固定时间（本地时间）
+    pub fn daily(hour: u32, minute: u32) -> anyhow::Result<Self> {
+        Self::cron(&format!("{} {} * * *", minute, hour))
+    }
+
+    /// 固定间隔
+    pub fn every(period: Duration) -> Self {
+        TimeSpec::Every(period)
+    }
+
+    /// 日出前后（如-30为日出前30分钟）
+    pub fn sunrise(offset_minutes: i64) -> Self {
+        TimeSpec::Sun {
+            event: SunEvent::Sunrise,
+            offset_minutes,
+        }
+    }
+
+    /// 日落前后
+    pub fn sunset(offset_minutes: i64) -> Self {
+        TimeSpec::Sun {
+            event: SunEvent::Sunset,
+            offset_minutes,
+        }
+    }
+
+    /// 不晚于指定时间的最近一个时间点（Unix时间）
+    fn prev(&self, now: i64, clock: &Clock) -> Option<i64> {
+        let (today, seconds) = clock.local(now);
+        match self {
+            TimeSpec::Cron(cron) => (0..=MAX_LOOKBACK_DAYS).find_map(|back| {
+                let days = today - back;
+                if !cron.matches_day(days) {
+                    return None;
+                }
+                let limit = if back == 0 { seconds } else { DAY - 1 };
+                let at = cron.last_in_day(limit)?;
+                Some(days * DAY + at - clock.utc_offset)
+            }),
+            TimeSpec::Every(period) => {
+                let period = (period.as_secs() as i64).max(1);
+                let local = now + clock.utc_offset;
+                Some(local.div_euclid(period) * period - clock.utc_offset)
+            }
+            TimeSpec::Sun {
+                event,
+                offset_minutes,
+            } => (0..=MAX_LOOKBACK_DAYS).find_map(|back| {
+                // 偏移可能使时间点落到前一天或后一天，从明天开始查找
+                let at = clock.sun(today + 1 - back, *event)? + offset_minutes * 60;
+                (at <= now).then_some(at)
+            }),
+        }
+    }
+}
+
+impl fmt::Display for TimeSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeSpec::Cron(cron) => write!(f, "cron({})", cron),
+            TimeSpec::Every(period) => write!(f, "每{}秒", period.as_secs()),
+            TimeSpec::Sun {
+                event,
+                offset_minutes,
+            } => {
+                let name = match event {
+                    SunEvent::Sunrise => "日出",
+                    SunEvent::Sunset => "日落",
+                };
+                write!(f, "{}{:+}分钟", name, offset_minutes)
+            }
+        }
+    }
+}
+
+/// 规则的结束时间
+#[derive(Debug, Clone, PartialEq)]
+pub enum End {
+    /// 开始后运行固定时长（浇水、水泵）
+    After(Duration),
+    /// 到另一个时间点关闭（补光灯从日出前30分钟到22点）
+    At(TimeSpec),
+}
+
+/// 定时规则
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    /// 名称
+    pub name: String,
+    /// 开始时间
+    pub start: TimeSpec,
+    /// 结束时间
+    pub end: End,
+    /// 程序停止期间错过的开始时间在该时长内时补执行（只对固定时长的规则有效）
+    pub catch_up: Option<Duration>,
+}
+
+impl Rule {
+    /// 开始后运行固定时长
+    pub fn pulse(name: &str, start: TimeSpec, duration: Duration) -> Self {
+        Self {
+            name: name.to_string(),
+            start,
+            end: End::After(duration),
+            catch_up: None,
+        }
+    }
+
+    /// 在两个时间点之间保持打开
+    pub fn window(name: &str, start: TimeSpec, end: TimeSpec) -> Self {
+        Self {
+            name: name.to_string(),
+            start,
+            end: End::At(end),
+            catch_up: None,
+        }
+    }
+
+    /// 设置补执行的宽限时间
+    pub fn with_catch_up(mut self, grace: Duration) -> Self {
+        self.catch_up = Some(grace);
+        self
+    }
+
+    /// 规则是否用到日出日落
+    fn needs_location(&self) -> bool {
+        let sun = |spec: &TimeSpec| matches!(spec, TimeSpec::Sun { .. });
+        sun(&self.start) || matches!(&self.end, End::At(end) if sun(end))
+    }
+}
+
+/// 规则的运行状态
+#[derive(Debug, Clone, Default)]
+struct RuleState {
+    /// 最近一次已执行（或已补执行）的开始时间
+    last_start: Option<i64>,
+    /// 补执行的结束时间
+    catch_up_until: Option<i64>,
+}
+
+/// 手动控制
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Manual {
+    /// 打开或关闭
+    on: bool,
+    /// 结束时间（为None时保持到时间表下一次变化）
+    until: Option<i64>,
+    /// 手动控制开始时时间表的状态
+    scheduled: bool,
+}
+
+/// 定时状态
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ScheduleStatus {
+    /// 执行器是否打开
+    pub on: bool,
+    /// 按时间表是否应该打开
+    pub scheduled: bool,
+    /// 手动控制（打开或关闭）
+    pub manual: Option<bool>,
+    /// 当前生效的规则
+    pub active_rules: Vec<String>,
+}
+
+/// 共享状态
+struct Shared {
+    /// 手动控制
+    manual: Option<Manual>,
+    /// 状态
+    status: ScheduleStatus,
+}
+
+/// 执行器定时
+///
+/// ```ignore
+/// let valve = RelayBoard::channel(&board, 1)?;
+/// let handle = Schedule::new(valve)
+///     .with_utc_offset(8 * 60)
+///     .with_location(Location::new(31.23, 121.47))
+///     .with_rule(
+///         Rule::pulse("早晨浇水", TimeSpec::cron("0 6 * * *")?, Duration::from_secs(600))
+///             .with_catch_up(Duration::from_secs(3600)),
+///     )
+///     .with_rule(Rule::pulse("傍晚浇水", TimeSpec::sunset(-60), Duration::from_secs(300)))
+///     .with_store(FileStore::new("/var/lib/raspi-sensor/valve.schedule"))
+///     .start()?;
+/// handle.set_manual(true, Some(Duration::from_secs(120)));
+/// ```
+pub struct Schedule {
+    /// 执行器
+    output: Box<dyn Switch>,
+    /// 规则
+    rules: Vec<Rule>,
+    /// 时区和位置
+    clock: Clock,
+    /// 检查间隔
+    tick: Duration,
+    /// 保存规则执行记录（重启后判断是否需要补执行）
+    store: Option<Box<dyn CalibrationStore>>,
+}
+
+impl Schedule {
+    /// 创建实例（UTC时间，每秒检查一次）
+    pub fn new<W: Switch + 'static>(output: W) -> Self {
+        Self {
+            output: Box::new(output),
+            rules: Vec::new(),
+            clock: Clock {
+                utc_offset: 0,
+                location: None,
+            },
+            tick: Duration::from_secs(1),
+            store: None,
+        }
+    }
+
+    /// 添加规则（任意规则生效时打开执行器）
+    pub fn with_rule(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// 设置本地时间相对UTC的偏移（分钟，如北京时间为480）
+    pub fn with_utc_offset(mut self, minutes: i64) -> Self {
+        self.clock.utc_offset = minutes * 60;
+        self
+    }
+
+    /// 设置地理位置（使用日出日落时间时必须设置）
+    pub fn with_location(mut self, location: Location) -> Self {
+        self.clock.location = Some(location);
+        self
+    }
+
+    /// 设置检查间隔
+    pub fn with_tick(mut self, tick: Duration) -> Self {
+        self.tick = tick.max(Duration::from_millis(100));
+        self
+    }
+
+    /// 保存规则执行记录，重启后已执行过的定时任务不会重复补执行
+    pub fn with_store<S: CalibrationStore + 'static>(mut self, store: S) -> Self {
+        self.store = Some(Box::new(store));
+        self
+    }
+
+    /// 启动定时线程
+    pub fn start(mut self) -> anyhow::Result<ScheduleHandle> {
+        if self.clock.location.is_none()
+            && let Some(rule) = self.rules.iter().find(|rule| rule.needs_location())
+        {
+            return Err(anyhow::anyhow!(
+                "规则{}使用日出日落时间，需要设置地理位置",
+                rule.name
+            ));
+        }
+        let mut states: Vec<RuleState> = vec![RuleState::default(); self.rules.len()];
+        if let Some(store) = &mut self.store {
+            let calibration = store.load()?;
+            for (rule, state) in self.rules.iter().zip(&mut states) {
+                state.last_start = calibration
+                    .get(&format!("{}.last_start", rule.name))
+                    .map(|value| value as i64);
+            }
+        }
+
+        let shared = Arc::new(Mutex::new(Shared {
+            manual: None,
+            status: ScheduleStatus::default(),
+        }));
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let state = shared.clone();
+        let thread = thread::spawn(move || {
+            loop {
+                if let Err(err) = self.update(&mut states, &state) {
+                    eprintln!("定时执行器切换失败: {}", err);
+                }
+                match stop_rx.recv_timeout(self.tick) {
+                    Err(RecvTimeoutError::Timeout) => {}
+                    _ => break,
+                }
+            }
+            // 停止后关闭执行器
+            if let Err(err) = self.output.off() {
+                eprintln!("关闭定时执行器失败: {}", err);
+            }
+        });
+        // OK
+        Ok(ScheduleHandle {
+            shared,
+            stop_tx,
+            thread,
+        })
+    }
+
+    /// 计算规则当前是否生效
+    fn evaluate(&self, rule: &Rule, state: &mut RuleState, now: i64) -> bool {
+        let Some(start) = rule.start.prev(now, &self.clock) else {
+            return false;
+        };
+        match &rule.end {
+            End::At(end) => end.prev(now, &self.clock).is_none_or(|end| end < start),
+            End::After(duration) => {
+                let duration = duration.as_secs() as i64;
+                if now < start + duration {
+                    state.last_start = Some(start);
+                    return true;
+                }
+                if let Some(until) = state.catch_up_until {
+                    if now < until {
+                        return true;
+                    }
+                    state.catch_up_until = None;
+                }
+                let missed = state.last_start.is_none_or(|last| last < start);
+                if !missed {
+                    return false;
+                }
+                state.last_start = Some(start);
+                if rule
+                    .catch_up
+                    .is_some_and(|grace| now - start <= grace.as_secs() as i64)
+                {
+                    trace_event!(info, rule = %rule.name, "补执行错过的定时任务");
+                    state.catch_up_until = Some(now + duration);
+                    return true;
+                }
+                false
+            }
+        }
+    }
+
+    /// 检查一次时间表并切换执行器
+    fn update(&mut self, states: &mut [RuleState], shared: &Mutex<Shared>) -> anyhow::Result<()> {
+        let now = unix_now();
+        let mut active_rules = Vec::new();
+        let mut started = BTreeMap::new();
+        for (rule, state) in self.rules.iter().zip(states.iter_mut()) {
+            let last_start = state.last_start;
+            if self.evaluate(rule, state, now) {
+                active_rules.push(rule.name.clone());
+            }
+            if state.last_start != last_start
+                && let Some(start) = state.last_start
+            {
+                started.insert(format!("{}.last_start", rule.name), start as f64);
+            }
+        }
+        if !started.is_empty()
+            && let Some(store) = &mut self.store
+        {
+            let mut calibration = store.load()?;
+            for (key, value) in started {
+                calibration.set(&key, value);
+            }
+            store.save(&calibration)?;
+        }
+
+        let scheduled = !active_rules.is_empty();
+        let mut shared = shared
+            .lock()
+            .map_err(|_| anyhow::anyhow!("定时状态锁已损坏"))?;
+        // 手动控制到期或时间表变化后恢复按时间表运行
+        if let Some(manual) = shared.manual
+            && (manual.until.is_some_and(|until| now >= until)
+                || manual.until.is_none() && manual.scheduled != scheduled)
+        {
+            shared.manual = None;
+        }
+        if let Some(manual) = &mut shared.manual {
+            manual.scheduled = scheduled;
+        }
+        let want = shared.manual.map_or(scheduled, |manual| manual.on);
+        if self.output.is_on() != want {
+            trace_event!(info, on = want, rules = ?active_rules, "定时切换执行器");
+            self.output.set(want)?;
+        }
+        shared.status = ScheduleStatus {
+            on: self.output.is_on(),
+            scheduled,
+            manual: shared.manual.map(|manual| manual.on),
+            active_rules,
+        };
+        Ok(())
+    }
+}
+
+/// 运行中的定时
+pub struct ScheduleHandle {
+    /// 共享状态
+    shared: Arc<Mutex<Shared>>,
+    /// 停止信号
+    stop_tx: Sender<()>,
+    /// 定时线程
+    thread: JoinHandle<()>,
+}
+
+impl ScheduleHandle {
+    /// 当前状态（下一次检查后更新）
+    pub fn status(&self) -> ScheduleStatus {
+        self.shared
+            .lock()
+            .map(|shared| shared.status.clone())
+            .unwrap_or_default()
+    }
+
+    /// 手动打开或关闭，下一次检查时生效
+    ///
+    /// - on: 打开或关闭
+    /// - duration: 保持时长，为None时保持到时间表下一次变化
+    pub fn set_manual(&self, on: bool, duration: Option<Duration>) {
+        if let Ok(mut shared) = self.shared.lock() {
+            let scheduled = shared.status.scheduled;
+            shared.manual = Some(Manual {
+                on,
+                until: duration.map(|duration| unix_now() + duration.as_secs() as i64),
+                scheduled,
+            });
+        }
+    }
+
+    /// 取消手动控制，恢复按时间表运行
+    pub fn clear_manual(&self) {
+        if let Ok(mut shared) = self.shared.lock() {
+            shared.manual = None;
+        }
+    }
+
+    /// 停止定时（关闭执行器）并等待定时线程退出
+    pub fn stop(self) {
+        let _ = self.stop_tx.send(());
+        let _ = self.thread.join();
+    }
+}
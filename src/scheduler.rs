@@ -0,0 +1,419 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::event_bus::{broadcast, subscribe};
+use crate::manager::{Sensor, SensorManager};
+use crate::reading::Sample;
+use crate::timing;
+
+/// 传感器没有最小读取间隔时的默认轮询间隔
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 读取失败的通知（传感器名称、错误信息）
+pub type ReadError = (String, String);
+
+/// 调度器线程间共享的状态
+#[derive(Default)]
+struct Shared {
+    /// 读数订阅者
+    subscribers: Mutex<Vec<Sender<Sample>>>,
+    /// 读取失败订阅者
+    error_subscribers: Mutex<Vec<Sender<ReadError>>>,
+    /// 正在读取的传感器（上一次读取未完成时跳过本次轮询）
+    busy: Mutex<BTreeSet<String>>,
+    /// 各传感器最后一次发布的读数时间（过滤管理器返回的缓存读数）
+    published: Mutex<BTreeMap<String, SystemTime>>,
+}
+
+impl Shared {
+    /// 读取一个传感器并发布结果
+    fn poll(&self, manager: &SensorManager, name: &str) {
+        match manager.read_sample(name) {
+            Ok(sample) => {
+                // 读取过快时管理器返回上一次的读数，不重复发布
+                let fresh = match self.published.lock() {
+                    Ok(mut published) => {
+                        published.insert(name.to_string(), sample.timestamp)
+                            != Some(sample.timestamp)
+                    }
+                    Err(_) => true,
+                };
+                if fresh {
+                    broadcast(&self.subscribers, sample);
+                }
+            }
+            Err(err) => {
+                trace_event!(warn, sensor = name, error = %err, "读取传感器失败");
+                broadcast(&self.error_subscribers, (name.to_string(), err.to_string()));
+            }
+        }
+        if let Ok(mut busy) = self.busy.lock() {
+            busy.remove(name);
+        }
+    }
+}
+
+/// 周期轮询调度器
+///
+/// - 每个传感器按各自的间隔轮询，间隔不能小于传感器的最小读取间隔（DHT11为2秒等）
+/// - 轮询时刻按固定节拍计算（下一次 = 上一次计划时刻 + 间隔），读取耗时不会累积为漂移
+/// - 读取在工作线程池中执行，慢速传感器不会拖慢其他传感器
+/// - 读数通过通道发送给所有订阅者，替代各测试程序中的`loop { read; sleep }`
+pub struct Scheduler {
+    /// 传感器管理器
+    manager: Arc<SensorManager>,
+    /// 各传感器的轮询间隔
+    intervals: BTreeMap<String, Duration>,
+    /// 工作线程数量
+    workers: usize,
+    /// 相邻传感器首次轮询的错开时间
+    stagger: Duration,
+    /// 合并轮询的时间窗口（0为不合并）
+    coalesce: Duration,
+    /// 共享状态
+    shared: Arc<Shared>,
+}
+
+impl Scheduler {
+    /// 创建调度器，所有已注册的传感器默认按各自的最小读取间隔轮询
+    pub fn new(manager: SensorManager) -> anyhow::Result<Self> {
+        let mut intervals = BTreeMap::new();
+        for name in manager.names() {
+            let min_interval = manager.min_interval(&name)?;
+            let interval = if min_interval.is_zero() {
+                DEFAULT_INTERVAL
+            } else {
+                min_interval
+            };
+            intervals.insert(name, interval);
+        }
+        // OK
+        Ok(Self {
+            manager: Arc::new(manager),
+            intervals,
+            workers: 2,
+            stagger: Duration::from_millis(50),
+            coalesce: Duration::ZERO,
+            shared: Arc::new(Shared::default()),
+        })
+    }
+
+    /// 传感器管理器
+    pub fn manager(&self) -> &SensorManager {
+        &self.manager
+    }
+
+    /// 设置传感器的轮询间隔（小于最小读取间隔时按最小读取间隔轮询）
+    pub fn set_interval(&mut self, name: &str, interval: Duration) -> anyhow::Result<()> {
+        let min_interval = self.manager.min_interval(name)?;
+        let interval = interval.max(min_interval);
+        if interval.is_zero() {
+            return Err(anyhow::anyhow!("轮询间隔不能为0: {}", name));
+        }
+        self.intervals.insert(name.to_string(), interval);
+        Ok(())
+    }
+
+    /// 传感器的轮询间隔
+    pub fn interval(&self, name: &str) -> Option<Duration> {
+        self.intervals.get(name).copied()
+    }
+
+    /// 设置工作线程数量（默认2个）
+    ///
+    /// DHT11等软件模拟时序的传感器读取期间会独占一个工作线程
+    pub fn set_workers(&mut self, workers: usize) {
+        self.workers = workers.max(1);
+    }
+
+    /// 设置相邻传感器首次轮询的错开时间（默认50毫秒）
+    ///
+    /// 避免所有传感器在同一时刻读取，减少总线争用和时序干扰
+    pub fn set_stagger(&mut self, stagger: Duration) {
+        self.stagger = stagger;
+    }
+
+    /// 合并轮询（默认不合并）
+    ///
+    /// 轮询时刻向上对齐到窗口的整数倍，不同间隔的传感器在同一时刻一起轮询，调度线程每个窗口
+    /// 最多唤醒一次。轮询只会推迟不会提前（不会违反最小读取间隔），实际间隔最多增加一个窗口
+    pub fn set_coalesce(&mut self, window: Duration) {
+        self.coalesce = window;
+    }
+
+    /// 低CPU占用配置（Pi Zero等单核设备）
+    ///
+    /// 单个工作线程、不错开首次轮询、按1秒窗口合并轮询，并启用全局的低CPU占用模式
+    /// （`timing::set_low_cpu`，软件PWM等计时线程的短等待让出CPU）
+    pub fn set_low_power(&mut self) {
+        self.workers = 1;
+        self.stagger = Duration::ZERO;
+        self.coalesce = Duration::from_secs(1);
+        timing::set_low_cpu(true);
+    }
+
+    /// 订阅读数
+    pub fn subscribe(&self) -> Receiver<Sample> {
+        subscribe(&self.shared.subscribers)
+    }
+
+    /// 订阅读取失败通知
+    pub fn subscribe_errors(&self) -> Receiver<ReadError> {
+        subscribe(&self.shared.error_subscribers)
+    }
+
+    /// 启动调度
+    pub fn start(self) -> SchedulerHandle {
+        let (job_tx, job_rx) = mpsc::channel::<String>();
+        let job_rx = Arc::new(Mutex::new(job_rx));
+
+        // 工作线程：从任务队列中取出传感器名称并读取
+        let mut threads = Vec::with_capacity(self.workers + 1);
+        for _ in 0..self.workers {
+            let job_rx = job_rx.clone();
+            let manager = self.manager.clone();
+            let shared = self.shared.clone();
+            threads.push(thread::spawn(move || {
+                loop {
+                    let job = match job_rx.lock() {
+                        Ok(rx) => rx.recv(),
+                        Err(_) => break,
+                    };
+                    // 任务队列关闭时退出
+                    let Ok(name) = job else {
+                        break;
+                    };
+                    shared.poll(&manager, &name);
+                }
+            }));
+        }
+
+        // 调度线程：按计划时刻分发任务，等待期间处理控制命令
+        let (control_tx, control_rx) = mpsc::channel::<Control>();
+        let now = Instant::now();
+        let coalesce = self.coalesce;
+        // 合并轮询时计划时刻向上对齐到窗口的整数倍
+        let align = move |at: Instant| {
+            if coalesce.is_zero() {
+                return at;
+            }
+            let windows = at
+                .saturating_duration_since(now)
+                .as_nanos()
+                .div_ceil(coalesce.as_nanos());
+            now + Duration::from_nanos((windows * coalesce.as_nanos()) as u64)
+        };
+        let mut queue: BinaryHeap<Reverse<(Instant, String)>> = self
+            .intervals
+            .keys()
+            .enumerate()
+            .map(|(i, name)| Reverse((align(now + self.stagger * i as u32), name.clone())))
+            .collect();
+        let intervals = Arc::new(Mutex::new(self.intervals));
+        let schedule = intervals.clone();
+        let manager = self.manager.clone();
+        let shared = self.shared.clone();
+        threads.push(thread::spawn(move || {
+            loop {
+                // 等待下一个计划时刻（没有传感器时一直等待命令）
+                let command = match queue.peek() {
+                    Some(Reverse((due, _))) => {
+                        control_rx.recv_timeout(due.saturating_duration_since(Instant::now()))
+                    }
+                    None => control_rx
+                        .recv()
+                        .map_err(|_| RecvTimeoutError::Disconnected),
+                };
+                match command {
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Ok(Control::Schedule(name)) => {
+                        // 新加入的传感器立即轮询一次，已在队列中的传感器下一次起使用新间隔
+                        if !queue.iter().any(|Reverse((_, queued))| *queued == name) {
+                            queue.push(Reverse((Instant::now(), name)));
+                        }
+                        continue;
+                    }
+                    Ok(Control::Stop) | Err(RecvTimeoutError::Disconnected) => break,
+                }
+                let Some(Reverse((due, name))) = queue.pop() else {
+                    continue;
+                };
+
+                // 已取消调度的传感器移出队列
+                let interval = match schedule.lock() {
+                    Ok(intervals) => intervals.get(&name).copied(),
+                    Err(_) => break,
+                };
+                let Some(interval) = interval else {
+                    trace_event!(debug, sensor = %name, "传感器已取消调度");
+                    continue;
+                };
+
+                // 上一次读取未完成或传感器已停用时跳过本次
+                if manager.is_enabled(&name).unwrap_or(false) {
+                    let idle = match shared.busy.lock() {
+                        Ok(mut busy) => busy.insert(name.clone()),
+                        Err(_) => break,
+                    };
+                    if !idle {
+                        trace_event!(debug, sensor = %name, "上一次读取未完成，跳过本次");
+                    }
+                    if idle && job_tx.send(name.clone()).is_err() {
+                        break;
+                    }
+                }
+
+                // 计算下一次计划时刻，错过的节拍直接跳过
+                let mut next = due + interval;
+                let now = Instant::now();
+                while next <= now {
+                    trace_event!(debug, sensor = %name, "读取耗时超过间隔，跳过错过的节拍");
+                    next += interval;
+                }
+                queue.push(Reverse((align(next), name)));
+            }
+        }));
+
+        SchedulerHandle {
+            control: SchedulerControl {
+                manager: self.manager,
+                intervals,
+                control_tx,
+            },
+            shared: self.shared,
+            threads,
+        }
+    }
+}
+
+/// 调度线程的控制命令
+enum Control {
+    /// 开始调度传感器（已在调度中时只更新间隔）
+    Schedule(String),
+    /// 停止调度
+    Stop,
+}
+
+/// 运行中调度器的控制接口（可克隆，如交给配置重载线程）
+///
+/// 调度期间可以添加、移除传感器或修改轮询间隔，修改在下一次轮询时生效
+#[derive(Clone)]
+pub struct SchedulerControl {
+    /// 传感器管理器
+    manager: Arc<SensorManager>,
+    /// 各传感器的轮询间隔
+    intervals: Arc<Mutex<BTreeMap<String, Duration>>>,
+    /// 控制命令
+    control_tx: Sender<Control>,
+}
+
+impl SchedulerControl {
+    /// 传感器管理器
+    pub fn manager(&self) -> &SensorManager {
+        &self.manager
+    }
+
+    /// 开始调度已注册的传感器或修改轮询间隔
+    ///
+    /// - interval: 轮询间隔，为None时使用传感器的最小读取间隔（小于最小读取间隔时按最小读取间隔轮询）
+    pub fn schedule(&self, name: &str, interval: Option<Duration>) -> anyhow::Result<()> {
+        let min_interval = self.manager.min_interval(name)?;
+        let interval = match interval {
+            Some(interval) => interval.max(min_interval),
+            None if min_interval.is_zero() => DEFAULT_INTERVAL,
+            None => min_interval,
+        };
+        if interval.is_zero() {
+            return Err(anyhow::anyhow!("轮询间隔不能为0: {}", name));
+        }
+        self.intervals
+            .lock()
+            .map_err(|_| anyhow::anyhow!("调度器状态异常"))?
+            .insert(name.to_string(), interval);
+        self.control_tx
+            .send(Control::Schedule(name.to_string()))
+            .map_err(|_| anyhow::anyhow!("调度器已停止"))?;
+        trace_event!(
+            info,
+            sensor = name,
+            interval_ms = interval.as_millis() as u64,
+            "调度传感器"
+        );
+        Ok(())
+    }
+
+    /// 停止调度传感器（不从管理器中移除）
+    pub fn unschedule(&self, name: &str) -> bool {
+        match self.intervals.lock() {
+            Ok(mut intervals) => intervals.remove(name).is_some(),
+            Err(_) => false,
+        }
+    }
+
+    /// 传感器的轮询间隔（没有调度时为None）
+    pub fn interval(&self, name: &str) -> Option<Duration> {
+        self.intervals.lock().ok()?.get(name).copied()
+    }
+
+    /// 注册并开始调度新的传感器
+    pub fn add(
+        &self,
+        name: &str,
+        sensor: Box<dyn Sensor>,
+        min_interval: Duration,
+        interval: Option<Duration>,
+    ) -> anyhow::Result<()> {
+        self.manager.register_boxed(name, sensor, min_interval)?;
+        self.schedule(name, interval)
+    }
+
+    /// 停止调度并从管理器中移除传感器
+    pub fn remove(&self, name: &str) -> bool {
+        self.unschedule(name);
+        self.manager.remove(name)
+    }
+}
+
+/// 运行中的调度器
+pub struct SchedulerHandle {
+    /// 控制接口
+    control: SchedulerControl,
+    /// 共享状态
+    shared: Arc<Shared>,
+    /// 调度线程和工作线程
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl SchedulerHandle {
+    /// 传感器管理器（可以在调度期间按需读取，读取过快时返回缓存的读数）
+    pub fn manager(&self) -> &SensorManager {
+        self.control.manager()
+    }
+
+    /// 控制接口（运行期间添加、移除传感器或修改轮询间隔）
+    pub fn control(&self) -> SchedulerControl {
+        self.control.clone()
+    }
+
+    /// 订阅读数
+    pub fn subscribe(&self) -> Receiver<Sample> {
+        subscribe(&self.shared.subscribers)
+    }
+
+    /// 订阅读取失败通知
+    pub fn subscribe_errors(&self) -> Receiver<ReadError> {
+        subscribe(&self.shared.error_subscribers)
+    }
+
+    /// 停止调度并等待正在进行的读取完成
+    pub fn stop(self) {
+        let _ = self.control.control_tx.send(Control::Stop);
+        for thread in self.threads {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::time::{Duration, Instant};
+
+use crate::sensor::pulse_counter::PulseCounter;
+
+/// 常见风杯式风速计每秒一个脉冲对应的风速（2.4km/h，m/s）
+pub const DEFAULT_SPEED_PER_HZ: f64 = 2.4 / 3.6;
+
+/// 风速读数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Wind {
+    /// 平均风速（m/s）
+    pub speed: f64,
+    /// 阵风风速（m/s，时间窗口内的最大平均风速）
+    pub gust: f64,
+}
+
+/// 风杯式风速计
+///
+/// 风杯每转一圈干簧管闭合一次（或多次），风速与脉冲频率成正比。
+/// 平均风速按上一次读取以来的脉冲数计算，阵风按气象惯例取3秒滑动窗口内的最大平均风速
+pub struct Anemometer {
+    /// 脉冲计数器
+    counter: PulseCounter,
+    /// 每秒一个脉冲对应的风速（m/s）
+    speed_per_hz: f64,
+    /// 阵风的时间窗口
+    gust_window: Duration,
+    /// 上一次读取的时间
+    since: Instant,
+}
+
+impl Anemometer {
+    /// 创建实例
+    ///
+    /// - pin: 干簧管接入的GPIO针脚（另一端接地）
+    pub fn new(pin: u8) -> anyhow::Result<Self> {
+        Ok(Self {
+            // 风速30m/s时约45Hz，消抖时间需要小于脉冲间隔
+            counter: PulseCounter::new(pin, Duration::from_millis(5))?,
+            speed_per_hz: DEFAULT_SPEED_PER_HZ,
+            gust_window: Duration::from_secs(3),
+            since: Instant::now(),
+        })
+    }
+
+    /// 设置每秒一个脉冲对应的风速（m/s）
+    pub fn with_speed_per_hz(mut self, speed_per_hz: f64) -> Self {
+        self.speed_per_hz = speed_per_hz;
+        self
+    }
+
+    /// 设置阵风的时间窗口（默认3秒）
+    pub fn with_gust_window(mut self, gust_window: Duration) -> Self {
+        self.gust_window = gust_window;
+        self
+    }
+
+    /// 上一次读取以来的平均风速和阵风风速
+    pub fn read(&mut self) -> Wind {
+        let pulses = self.counter.take();
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.since).as_secs_f64();
+        self.since = now;
+        let speed = if elapsed > 0.0 {
+            pulses.len() as f64 / elapsed * self.speed_per_hz
+        } else {
+            0.0
+        };
+        // 以每个脉冲为窗口起点统计窗口内的脉冲数（脉冲按时间顺序记录）
+        let window = self.gust_window.as_secs_f64();
+        let mut most = 0;
+        let mut end = 0;
+        for (start, at) in pulses.iter().enumerate() {
+            while end < pulses.len() && pulses[end].duration_since(*at) < self.gust_window {
+                end += 1;
+            }
+            most = most.max(end - start);
+        }
+        let gust = if window > 0.0 && elapsed >= window {
+            most as f64 / window * self.speed_per_hz
+        } else {
+            // 读取间隔小于阵风窗口时阵风即为平均风速
+            speed
+        };
+        Wind {
+            speed,
+            gust: gust.max(speed),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use std::thread;
+use std::time::Duration;
+
+use crate::i2c_bus::SharedBus;
+
+/// 指令
+mod cmd {
+    /// 上电
+    pub const POWER_ON: u8 = 0x01;
+    /// 清除数据寄存器
+    pub const RESET: u8 = 0x07;
+    /// 连续高分辨率模式（1lx，转换时间最长180ms）
+    pub const CONTINUOUS_HIGH_RES: u8 = 0x10;
+}
+
+/// ADDR引脚接地时的设备地址
+pub const ADDRESS_LOW: u16 = 0x23;
+/// ADDR引脚接高电平时的设备地址
+pub const ADDRESS_HIGH: u16 = 0x5C;
+
+/// BH1750光照度传感器（I2C）
+pub struct BH1750 {
+    /// I2C通信总线
+    i2c_bus: SharedBus,
+    /// 设备地址
+    address: u16,
+}
+
+impl BH1750 {
+    /// 创建实例并开始连续测量
+    ///
+    /// - i2c_bus: 共享的I2C通信总线
+    /// - address: 设备地址（`ADDRESS_LOW`或`ADDRESS_HIGH`）
+    pub fn new<B: Into<SharedBus>>(i2c_bus: B, address: u16) -> anyhow::Result<Self> {
+        let this = Self {
+            i2c_bus: i2c_bus.into(),
+            address,
+        };
+        this.command(cmd::POWER_ON)?;
+        this.command(cmd::RESET)?;
+        this.command(cmd::CONTINUOUS_HIGH_RES)?;
+        // 等待第一次转换完成
+        thread::sleep(Duration::from_millis(180));
+        // OK
+        Ok(this)
+    }
+
+    /// 发送指令
+    fn command(&self, command: u8) -> anyhow::Result<()> {
+        self.i2c_bus.transaction_at(self.address, |i2c| {
+            i2c.write(&[command])
+                .map_err(|err| anyhow::anyhow!("向BH1750发送指令0x{:02X}失败: {}", command, err))?;
+            Ok(())
+        })
+    }
+
+    /// 读取光照度（lx）
+    pub fn read(&mut self) -> anyhow::Result<f64> {
+        let mut buf = [0u8; 2];
+        self.i2c_bus.transaction_at(self.address, |i2c| {
+            i2c.read(&mut buf)
+                .map_err(|err| anyhow::anyhow!("读取BH1750数据失败: {}", err))?;
+            Ok(())
+        })?;
+        // 高分辨率模式下测量值 / 1.2 为光照度
+        Ok(u16::from_be_bytes(buf) as f64 / 1.2)
+    }
+}
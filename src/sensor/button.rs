@@ -1,5 +1,104 @@
-use rppal::gpio::{Gpio, Trigger};
-use std::time::Duration;
+use rppal::gpio::{Gpio, InputPin, Trigger};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::debug::{self, PinMode};
+
+/// 按钮事件
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ButtonEvent {
+    /// 按下
+    Pressed,
+    /// 松开（附带按下的时长）
+    Released(Duration),
+    /// 长按（按住达到设定时长时触发一次）
+    Held(Duration),
+}
+
+/// 按钮事件时间参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EventTiming {
+    /// 消抖时间（None为不消抖）
+    pub debounce: Option<Duration>,
+    /// 长按判定时长
+    pub hold: Duration,
+}
+
+impl Default for EventTiming {
+    fn default() -> Self {
+        Self {
+            debounce: Some(Duration::from_millis(50)),
+            hold: Duration::from_secs(1),
+        }
+    }
+}
+
+/// 监听引脚电平变化并生成按钮事件
+///
+/// - 中断回调只负责转发电平，长按计时在独立线程中完成
+/// - 清除中断或引脚被释放后线程自动退出
+pub(crate) fn watch_events<F>(
+    pin: &mut InputPin,
+    active_low: bool,
+    timing: EventTiming,
+    mut cb: F,
+) -> anyhow::Result<()>
+where
+    F: FnMut(ButtonEvent) + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel::<bool>();
+    let number = pin.pin();
+    pin.set_async_interrupt(Trigger::Both, timing.debounce, move |event| {
+        debug::record_interrupt(number);
+        // 转换为是否处于按下状态
+        let _ = tx.send((event.trigger == Trigger::FallingEdge) == active_low);
+    })?;
+
+    thread::spawn(move || {
+        // 按下的时刻
+        let mut pressed_at: Option<Instant> = None;
+        // 本次按下是否已触发长按
+        let mut held = false;
+        loop {
+            let active = match pressed_at {
+                // 按住期间等待松开或长按超时
+                Some(at) if !held => {
+                    let timeout = timing.hold.saturating_sub(at.elapsed());
+                    match rx.recv_timeout(timeout) {
+                        Ok(active) => active,
+                        Err(RecvTimeoutError::Timeout) => {
+                            held = true;
+                            cb(ButtonEvent::Held(at.elapsed()));
+                            continue;
+                        }
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+                _ => match rx.recv() {
+                    Ok(active) => active,
+                    Err(_) => break,
+                },
+            };
+            match (active, pressed_at) {
+                (true, None) => {
+                    pressed_at = Some(Instant::now());
+                    held = false;
+                    cb(ButtonEvent::Pressed);
+                }
+                (false, Some(at)) => {
+                    pressed_at = None;
+                    cb(ButtonEvent::Released(at.elapsed()));
+                }
+                // 重复的电平忽略
+                _ => {}
+            }
+        }
+    });
+    // OK
+    Ok(())
+}
 
 /// 按钮封装对象
 pub struct Button {
@@ -12,6 +111,7 @@ impl Button {
         // 构建针脚GPIO对象
         let gpio = Gpio::new()?;
         let pin = gpio.get(pin)?.into_input_pullup();
+        debug::claim(pin.pin(), "button", PinMode::Input);
         // OK
         Ok(Self { pin })
     }
@@ -34,15 +134,38 @@ impl Button {
         F: FnMut(bool) + Send + 'static,
     {
         // 设置中断回调，监听电平变化（按下和松开都监听）
+        let number = self.pin.pin();
         self.pin.set_async_interrupt(
             // 同时监听上升沿和下降沿
             Trigger::Both,
             // 50ms防抖动,
             Some(Duration::from_millis(50)),
             // 下降沿为True
-            move |event| cb(event.trigger == Trigger::FallingEdge),
+            move |event| {
+                debug::record_interrupt(number);
+                cb(event.trigger == Trigger::FallingEdge)
+            },
         )?;
         // OK
         Ok(())
     }
+
+    /// 监听按钮事件（按下、松开、长按）
+    ///
+    /// - 默认50ms消抖，按住1秒触发长按
+    pub fn on_event<F>(&mut self, cb: F) -> anyhow::Result<()>
+    where
+        F: FnMut(ButtonEvent) + Send + 'static,
+    {
+        self.on_event_with_timing(EventTiming::default(), cb)
+    }
+
+    /// 使用自定义时间参数监听按钮事件
+    pub fn on_event_with_timing<F>(&mut self, timing: EventTiming, cb: F) -> anyhow::Result<()>
+    where
+        F: FnMut(ButtonEvent) + Send + 'static,
+    {
+        // 按钮为上拉输入，低电平为按下
+        watch_events(&mut self.pin, true, timing, cb)
+    }
 }
@@ -0,0 +1,253 @@
+//! 多按钮管理
+//!
+//! 每个`Button`使用rppal的异步中断时都会创建一个中断线程和一个长按计时线程，按钮较多时线程数量
+//! 随之增长。`ButtonBank`只使用一个线程，通过`poll_interrupts`同时等待所有引脚的中断，
+//! 长按计时也在同一线程中完成，所有事件按`(按钮序号, 事件)`交给同一个回调
+
+use rppal::gpio::{Gpio, InputPin, Trigger};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::debug::{self, PinMode};
+use crate::gpio::Pull;
+use crate::sensor::button::{ButtonEvent, EventTiming};
+
+/// 没有按钮按住时等待中断的超时时间（用于检查是否需要停止）
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 单个按钮的配置
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ButtonConfig {
+    /// 引脚（BCM编号）
+    pub pin: u8,
+    /// 是否低电平为按下
+    pub active_low: bool,
+    /// 内部上下拉
+    pub pull: Pull,
+    /// 消抖和长按时间
+    pub timing: EventTiming,
+}
+
+impl ButtonConfig {
+    /// 创建配置（默认上拉输入，低电平为按下，50ms消抖，按住1秒触发长按）
+    ///
+    /// - pin: 按钮接入的GPIO针脚
+    pub fn new(pin: u8) -> Self {
+        Self {
+            pin,
+            active_low: true,
+            pull: Pull::Up,
+            timing: EventTiming::default(),
+        }
+    }
+
+    /// 设置按下时的电平（高电平为按下时通常同时使用下拉）
+    pub fn with_active_low(mut self, active_low: bool) -> Self {
+        self.active_low = active_low;
+        self
+    }
+
+    /// 设置内部上下拉
+    pub fn with_pull(mut self, pull: Pull) -> Self {
+        self.pull = pull;
+        self
+    }
+
+    /// 设置消抖和长按时间
+    pub fn with_timing(mut self, timing: EventTiming) -> Self {
+        self.timing = timing;
+        self
+    }
+}
+
+/// 按钮的按下状态
+struct ButtonState {
+    config: ButtonConfig,
+    /// 按下的时刻
+    pressed_at: Option<Instant>,
+    /// 本次按下是否已触发长按
+    held: bool,
+}
+
+impl ButtonState {
+    /// 处理电平变化
+    fn update(&mut self, active: bool) -> Option<ButtonEvent> {
+        match (active, self.pressed_at) {
+            (true, None) => {
+                self.pressed_at = Some(Instant::now());
+                self.held = false;
+                Some(ButtonEvent::Pressed)
+            }
+            (false, Some(at)) => {
+                self.pressed_at = None;
+                Some(ButtonEvent::Released(at.elapsed()))
+            }
+            // 重复的电平忽略
+            _ => None,
+        }
+    }
+
+    /// 距离触发长按的剩余时间（没有按住或已触发时为None）
+    fn hold_remaining(&self) -> Option<Duration> {
+        match self.pressed_at {
+            Some(at) if !self.held => Some(self.config.timing.hold.saturating_sub(at.elapsed())),
+            _ => None,
+        }
+    }
+}
+
+/// 多按钮管理（共用一个事件线程）
+///
+/// ```ignore
+/// let mut bank = ButtonBank::new()?;
+/// let up = bank.add(ButtonConfig::new(17))?;
+/// let down = bank.add(ButtonConfig::new(27).with_timing(EventTiming {
+///     debounce: Some(Duration::from_millis(30)),
+///     hold: Duration::from_millis(500),
+/// }))?;
+/// bank.on_event(move |id, event| println!("按钮{}: {:?}", id, event))?;
+/// ```
+pub struct ButtonBank {
+    gpio: Gpio,
+    /// 尚未启动的按钮（启动后移动到事件线程）
+    buttons: Vec<(InputPin, ButtonConfig)>,
+    /// 按钮数量
+    len: usize,
+    /// 事件线程的停止标记和句柄
+    worker: Option<(Arc<AtomicBool>, JoinHandle<()>)>,
+}
+
+impl ButtonBank {
+    /// 创建实例
+    pub fn new() -> anyhow::Result<Self> {
+        // OK
+        Ok(Self {
+            gpio: Gpio::new()?,
+            buttons: Vec::new(),
+            len: 0,
+            worker: None,
+        })
+    }
+
+    /// 添加按钮，返回按钮序号（从0开始，按添加顺序递增）
+    ///
+    /// 启动事件线程之后不能再添加
+    pub fn add(&mut self, config: ButtonConfig) -> anyhow::Result<usize> {
+        if self.worker.is_some() {
+            return Err(anyhow::anyhow!("按钮组已启动，不能再添加按钮"));
+        }
+        let pin = self.gpio.get(config.pin)?;
+        let mut pin = match config.pull {
+            Pull::None => pin.into_input(),
+            Pull::Up => pin.into_input_pullup(),
+            Pull::Down => pin.into_input_pulldown(),
+        };
+        pin.set_interrupt(Trigger::Both, config.timing.debounce)?;
+        debug::claim(config.pin, "button-bank", PinMode::Input);
+        self.buttons.push((pin, config));
+        self.len += 1;
+        // OK
+        Ok(self.len - 1)
+    }
+
+    /// 按钮数量
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// 是否没有按钮
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 启动事件线程，所有按钮的事件交给同一个回调
+    ///
+    /// - cb: 事件回调（按钮序号、事件），在事件线程中调用，耗时的处理需要转发到其他线程
+    pub fn on_event<F>(&mut self, mut cb: F) -> anyhow::Result<()>
+    where
+        F: FnMut(usize, ButtonEvent) + Send + 'static,
+    {
+        if self.worker.is_some() {
+            return Err(anyhow::anyhow!("按钮组已启动"));
+        }
+        if self.buttons.is_empty() {
+            return Err(anyhow::anyhow!("按钮组没有按钮"));
+        }
+        let gpio = self.gpio.clone();
+        let (pins, configs): (Vec<InputPin>, Vec<ButtonConfig>) =
+            std::mem::take(&mut self.buttons).into_iter().unzip();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+
+        let handle = thread::Builder::new()
+            .name("button-bank".to_string())
+            .spawn(move || {
+                let mut states: Vec<ButtonState> = configs
+                    .into_iter()
+                    .map(|config| ButtonState {
+                        config,
+                        pressed_at: None,
+                        held: false,
+                    })
+                    .collect();
+                let refs: Vec<&InputPin> = pins.iter().collect();
+                while !stopped.load(Ordering::Relaxed) {
+                    // 等到最近的长按时刻
+                    let timeout = states
+                        .iter()
+                        .filter_map(ButtonState::hold_remaining)
+                        .min()
+                        .unwrap_or(POLL_INTERVAL)
+                        .min(POLL_INTERVAL);
+                    match gpio.poll_interrupts(&refs, false, Some(timeout)) {
+                        Ok(Some((pin, event))) => {
+                            let number = pin.pin();
+                            debug::record_interrupt(number);
+                            if let Some(id) = states.iter().position(|s| s.config.pin == number) {
+                                let state = &mut states[id];
+                                // 转换为是否处于按下状态
+                                let active = (event.trigger == Trigger::FallingEdge)
+                                    == state.config.active_low;
+                                if let Some(event) = state.update(active) {
+                                    cb(id, event);
+                                }
+                            }
+                        }
+                        Ok(None) => {}
+                        Err(err) => {
+                            eprintln!("按钮组等待中断失败: {}", err);
+                            break;
+                        }
+                    }
+                    // 触发到时的长按
+                    for (id, state) in states.iter_mut().enumerate() {
+                        if state.hold_remaining() == Some(Duration::ZERO)
+                            && let Some(at) = state.pressed_at
+                        {
+                            state.held = true;
+                            cb(id, ButtonEvent::Held(at.elapsed()));
+                        }
+                    }
+                }
+            })?;
+        self.worker = Some((stop, handle));
+        // OK
+        Ok(())
+    }
+
+    /// 停止事件线程（最多等待100ms），之后不能再次启动
+    pub fn stop(&mut self) {
+        if let Some((stop, handle)) = self.worker.take() {
+            stop.store(true, Ordering::Relaxed);
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for ButtonBank {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
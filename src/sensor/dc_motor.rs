@@ -0,0 +1,201 @@
+use rppal::gpio::{Gpio, OutputPin};
+
+use crate::debug::{self, PinMode};
+use crate::pwm_wapper::PwmWapper;
+
+/// H桥接线方式
+enum Bridge {
+    /// L298N：IN1/IN2控制方向，ENA/ENB输出PWM调速
+    L298N {
+        in1: OutputPin,
+        in2: OutputPin,
+        enable: PwmWapper,
+    },
+    /// DRV8833/TB6612等：IN1/IN2均输出PWM（快衰减模式）
+    DRV8833 { in1: PwmWapper, in2: PwmWapper },
+}
+
+/// 直流电机封装对象
+pub struct DcMotor {
+    /// H桥接线方式
+    bridge: Bridge,
+    /// 当前速度（-1.0~1.0，正数为正转）
+    speed: f64,
+    /// 是否反转方向（电机接线反了时无需重新接线）
+    inverted: bool,
+}
+
+impl DcMotor {
+    /// 创建L298N驱动的电机实例
+    ///
+    /// - in1_pin、in2_pin: 方向控制引脚
+    /// - enable: 使能引脚的PWM输出（拔掉ENA跳线帽后接入）
+    pub fn new_l298n(in1_pin: u8, in2_pin: u8, enable: PwmWapper) -> anyhow::Result<Self> {
+        let gpio = Gpio::new()?;
+        debug::claim(in1_pin, "dc-motor", PinMode::Output);
+        debug::claim(in2_pin, "dc-motor", PinMode::Output);
+        let bridge = Bridge::L298N {
+            in1: gpio.get(in1_pin)?.into_output_low(),
+            in2: gpio.get(in2_pin)?.into_output_low(),
+            enable,
+        };
+        let mut this = Self {
+            bridge,
+            speed: 0.0,
+            inverted: false,
+        };
+        this.coast()?;
+        // OK
+        Ok(this)
+    }
+
+    /// 创建DRV8833驱动的电机实例
+    ///
+    /// - in1、in2: 两个输入引脚的PWM输出
+    pub fn new_drv8833(in1: PwmWapper, in2: PwmWapper) -> anyhow::Result<Self> {
+        let mut this = Self {
+            bridge: Bridge::DRV8833 { in1, in2 },
+            speed: 0.0,
+            inverted: false,
+        };
+        this.coast()?;
+        // OK
+        Ok(this)
+    }
+
+    /// 设置是否反转方向
+    pub fn set_inverted(&mut self, inverted: bool) {
+        self.inverted = inverted;
+    }
+
+    /// 当前速度（-1.0~1.0，正数为正转，0为停止）
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// 设置速度
+    ///
+    /// - speed: -1.0~1.0，正数为正转，负数为反转，0为滑行停止
+    pub fn set_speed(&mut self, speed: f64) -> anyhow::Result<()> {
+        let speed = speed.clamp(-1.0, 1.0);
+        if speed == 0.0 {
+            return self.coast();
+        }
+
+        let forward = (speed > 0.0) != self.inverted;
+        let duty = speed.abs();
+        match &mut self.bridge {
+            Bridge::L298N { in1, in2, enable } => {
+                if forward {
+                    in2.set_low();
+                    in1.set_high();
+                } else {
+                    in1.set_low();
+                    in2.set_high();
+                }
+                enable.set_duty_cycle(duty)?;
+            }
+            Bridge::DRV8833 { in1, in2 } => {
+                if forward {
+                    in2.set_duty_cycle(0.0)?;
+                    in1.set_duty_cycle(duty)?;
+                } else {
+                    in1.set_duty_cycle(0.0)?;
+                    in2.set_duty_cycle(duty)?;
+                }
+            }
+        }
+        self.speed = speed;
+        Ok(())
+    }
+
+    /// 正转
+    ///
+    /// - speed: 0.0~1.0
+    pub fn forward(&mut self, speed: f64) -> anyhow::Result<()> {
+        self.set_speed(speed.abs())
+    }
+
+    /// 反转
+    ///
+    /// - speed: 0.0~1.0
+    pub fn reverse(&mut self, speed: f64) -> anyhow::Result<()> {
+        self.set_speed(-speed.abs())
+    }
+
+    /// 刹车（电机两端短接，快速停止）
+    pub fn brake(&mut self) -> anyhow::Result<()> {
+        match &mut self.bridge {
+            Bridge::L298N { in1, in2, enable } => {
+                in1.set_low();
+                in2.set_low();
+                enable.set_duty_cycle(1.0)?;
+            }
+            Bridge::DRV8833 { in1, in2 } => {
+                in1.set_duty_cycle(1.0)?;
+                in2.set_duty_cycle(1.0)?;
+            }
+        }
+        self.speed = 0.0;
+        Ok(())
+    }
+
+    /// 滑行（断开输出，电机自由停止）
+    pub fn coast(&mut self) -> anyhow::Result<()> {
+        match &mut self.bridge {
+            Bridge::L298N { in1, in2, enable } => {
+                enable.set_duty_cycle(0.0)?;
+                in1.set_low();
+                in2.set_low();
+            }
+            Bridge::DRV8833 { in1, in2 } => {
+                in1.set_duty_cycle(0.0)?;
+                in2.set_duty_cycle(0.0)?;
+            }
+        }
+        self.speed = 0.0;
+        Ok(())
+    }
+}
+
+/// 双电机差速驱动（坦克式底盘）
+pub struct DifferentialDrive {
+    /// 左侧电机
+    pub left: DcMotor,
+    /// 右侧电机
+    pub right: DcMotor,
+}
+
+impl DifferentialDrive {
+    /// 创建差速驱动实例
+    pub fn new(left: DcMotor, right: DcMotor) -> Self {
+        Self { left, right }
+    }
+
+    /// 分别设置左右电机速度（-1.0~1.0）
+    pub fn tank_drive(&mut self, left: f64, right: f64) -> anyhow::Result<()> {
+        self.left.set_speed(left)?;
+        self.right.set_speed(right)
+    }
+
+    /// 按油门和转向控制（-1.0~1.0，转向正数为右转）
+    pub fn arcade_drive(&mut self, throttle: f64, turn: f64) -> anyhow::Result<()> {
+        let left = throttle + turn;
+        let right = throttle - turn;
+        // 超出范围时等比例缩小，保持转向比例
+        let max = left.abs().max(right.abs()).max(1.0);
+        self.tank_drive(left / max, right / max)
+    }
+
+    /// 刹车
+    pub fn brake(&mut self) -> anyhow::Result<()> {
+        self.left.brake()?;
+        self.right.brake()
+    }
+
+    /// 滑行停止
+    pub fn coast(&mut self) -> anyhow::Result<()> {
+        self.left.coast()?;
+        self.right.coast()
+    }
+}
@@ -0,0 +1,140 @@
+//! DHT11/DHT22的SPI采样读取
+//!
+//! 单总线协议用高电平的宽度区分0（约27us）和1（约70us），用户态线程按时间读取GPIO时
+//! 容易被调度打断而丢位。这里把数据线同时接到SPI的MISO，发送起始信号后以1MHz时钟
+//! 连续读取MISO，得到每微秒一个采样点的波形，再按高电平宽度解码，解码结果与调度抖动无关
+//!
+//! 接线：数据线接SPI MISO（SPI0为GPIO9），同时接一个普通GPIO用于发送起始信号，
+//! 数据线需要4.7k~10k上拉；SPI的硬件片选和MOSI、SCLK不需要连接
+
+use std::thread;
+use std::time::Duration;
+
+use rppal::gpio::{Gpio, IoPin, Mode};
+use rppal::spi::{Bus, Mode as SpiMode, SlaveSelect};
+
+pub use crate::core::dht::DhtModel;
+use crate::core::dht::{self, FRAME_BITS};
+use crate::gpio::Pull;
+use crate::spi_bus::SpiDeviceHandle;
+
+/// 采样频率（Hz），每个采样点1us（解码时采样点数即为微秒数）
+const SAMPLE_RATE: u32 = 1_000_000;
+
+/// 采样间隔
+pub const SAMPLE_PERIOD: Duration = Duration::from_micros(1);
+
+/// 采样字节数（约16ms，完整的一帧约5ms，剩余部分用于容忍起始信号后的启动延迟）
+const CAPTURE_BYTES: usize = 2048;
+
+/// 使用SPI采样读取的DHT温湿度传感器
+///
+/// ```ignore
+/// let mut dht = DhtSpiCapture::new(Bus::Spi0, SlaveSelect::Ss0, 4, DhtModel::Dht22)?;
+/// let (temperature, humidity) = dht.read()?;
+/// ```
+pub struct DhtSpiCapture {
+    /// 采样用的SPI设备
+    spi: SpiDeviceHandle,
+    /// 发送起始信号的GPIO
+    pin: IoPin,
+    /// 传感器型号
+    model: DhtModel,
+}
+
+impl DhtSpiCapture {
+    /// 创建实例
+    ///
+    /// - bus: 数据线接入MISO的SPI总线
+    /// - slave_select: 占用的硬件片选（该引脚悬空）
+    /// - start_pin: 与数据线相连、用于发送起始信号的GPIO针脚
+    /// - model: 传感器型号
+    pub fn new(
+        bus: Bus,
+        slave_select: SlaveSelect,
+        start_pin: u8,
+        model: DhtModel,
+    ) -> anyhow::Result<Self> {
+        let spi = SpiDeviceHandle::hardware(bus, slave_select, SAMPLE_RATE, SpiMode::Mode0)?;
+        // 空闲时释放数据线，由上拉电阻保持高电平
+        let pin = Gpio::new()?.get(start_pin)?.into_io(Mode::Input);
+        // OK
+        Ok(Self { spi, pin, model })
+    }
+
+    /// 设置数据线的内部上下拉（没有外接上拉电阻时使用上拉）
+    pub fn set_pull(&mut self, pull: Pull) {
+        self.pin.set_bias(pull.bias());
+    }
+
+    /// 传感器型号
+    pub fn model(&self) -> DhtModel {
+        self.model
+    }
+
+    /// 读取温度（°C）和湿度（%RH）
+    ///
+    /// 两次读取需要间隔2秒以上（DHT22）或1秒以上（DHT11）
+    pub fn read(&mut self) -> anyhow::Result<(f64, f64)> {
+        let samples = self.sample()?;
+        decode(&samples, self.model)
+    }
+
+    /// 发送起始信号并采样数据线的波形（每个bit为`SAMPLE_PERIOD`的采样点，高位在前）
+    pub fn sample(&mut self) -> anyhow::Result<Vec<u8>> {
+        let mut samples = vec![0u8; CAPTURE_BYTES];
+        let write = vec![0u8; CAPTURE_BYTES];
+
+        // 起始信号：拉低数据线，然后释放并立即开始采样
+        self.pin.set_mode(Mode::Output);
+        self.pin.set_low();
+        thread::sleep(Duration::from_micros(self.model.start_signal_us() as u64));
+        self.pin.set_mode(Mode::Input);
+        self.spi.transfer(&mut samples, &write)?;
+        // OK
+        Ok(samples)
+    }
+}
+
+/// 解码采样得到的波形（每个bit为1us的采样点，高位在前）
+///
+/// 取最后40个两端都是低电平的高电平脉冲作为数据位，起始信号后的启动延迟导致
+/// 响应脉冲没有采到时不影响解码
+pub fn decode(samples: &[u8], model: DhtModel) -> anyhow::Result<(f64, f64)> {
+    // 统计连续电平的长度
+    let mut runs: Vec<(bool, u32)> = Vec::new();
+    for byte in samples {
+        for shift in (0..8).rev() {
+            let level = byte >> shift & 1 == 1;
+            match runs.last_mut() {
+                Some((last, count)) if *last == level => *count += 1,
+                _ => runs.push((level, 1)),
+            }
+        }
+    }
+
+    // 首尾的脉冲不完整（首部为释放后的空闲电平，尾部为结束后的空闲电平）
+    let inner = runs.get(1..runs.len().saturating_sub(1)).unwrap_or(&[]);
+    let pulses: Vec<u32> = inner
+        .iter()
+        .filter(|(level, _)| *level)
+        .map(|(_, count)| *count)
+        .collect();
+    let Some(widths) = pulses
+        .len()
+        .checked_sub(FRAME_BITS)
+        .and_then(|start| <&[u32; FRAME_BITS]>::try_from(&pulses[start..]).ok())
+    else {
+        return Err(anyhow::anyhow!(
+            "等待DHT传感器数据超时: 只采到{}个数据位",
+            pulses.len()
+        ));
+    };
+
+    let frame = dht::frame_from_pulses(widths);
+    let (temperature, humidity) = dht::parse_frame(&frame, model)
+        .map_err(|_| anyhow::anyhow!("DHT传感器数据校验和错误: {:02X?}", frame))?;
+    trace_event!(debug, temperature, humidity, "DHT传感器SPI采样解码完成");
+    // OK
+    Ok((temperature, humidity))
+}
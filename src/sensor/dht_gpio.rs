@@ -0,0 +1,139 @@
+//! DHT11/DHT22的GPIO读取
+//!
+//! 起始信号（DHT11为18ms）期间睡眠而不是忙等，只在约5ms的响应和数据传输期间轮询引脚，
+//! Pi Zero等单核设备上每次读取只占用几毫秒CPU。数据位按高电平宽度解码（与SPI采样读取相同），
+//! 轮询期间被调度打断时返回超时或校验和错误，由调用方重试
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rppal::gpio::{Gpio, IoPin, Mode};
+
+use crate::capture::SignalCapture;
+pub use crate::core::dht::DhtModel;
+use crate::core::dht::{self, FRAME_BITS};
+use crate::gpio::Pull;
+
+/// 等待响应脉冲每个电平的超时时间（响应脉冲约80µs）
+const RESPONSE_TIMEOUT: Duration = Duration::from_micros(200);
+
+/// 等待数据位每个电平的超时时间（低电平约50µs，高电平最长约70µs）
+const BIT_TIMEOUT: Duration = Duration::from_micros(150);
+
+/// 使用GPIO读取的DHT温湿度传感器
+///
+/// ```ignore
+/// let mut dht = DhtGpio::new(4, DhtModel::Dht11)?;
+/// let (temperature, humidity) = dht.read()?;
+/// ```
+pub struct DhtGpio {
+    /// 单总线引脚
+    pin: IoPin,
+    /// 传感器型号
+    model: DhtModel,
+    /// 数据线的信号捕获
+    capture: Option<SignalCapture>,
+}
+
+impl DhtGpio {
+    /// 创建实例
+    ///
+    /// - pin: 单总线接入的GPIO针脚
+    /// - model: 传感器型号
+    pub fn new(pin: u8, model: DhtModel) -> anyhow::Result<Self> {
+        // 空闲时释放数据线，由上拉电阻保持高电平
+        let pin = Gpio::new()?.get(pin)?.into_io(Mode::Input);
+        // OK
+        Ok(Self {
+            pin,
+            model,
+            capture: None,
+        })
+    }
+
+    /// 读取时记录数据线的电平变化（信号序号0）
+    pub fn with_capture(mut self, capture: SignalCapture) -> Self {
+        self.capture = Some(capture);
+        self
+    }
+
+    /// 设置数据线的内部上下拉（没有外接上拉电阻时使用上拉，内部上拉约50k，线缆较长时仍建议外接）
+    pub fn set_pull(&mut self, pull: Pull) {
+        self.pin.set_bias(pull.bias());
+    }
+
+    /// BCM编号
+    pub fn pin(&self) -> u8 {
+        self.pin.pin()
+    }
+
+    /// 传感器型号
+    pub fn model(&self) -> DhtModel {
+        self.model
+    }
+
+    /// 读取温度（°C）和湿度（%RH）
+    ///
+    /// 两次读取需要间隔2秒以上（DHT22）或1秒以上（DHT11）
+    pub fn read(&mut self) -> anyhow::Result<(f64, f64)> {
+        // 起始信号：拉低数据线（睡眠等待），然后释放
+        self.pin.set_mode(Mode::Output);
+        self.pin.set_low();
+        self.record(false);
+        thread::sleep(Duration::from_micros(self.model.start_signal_us() as u64));
+        self.pin.set_mode(Mode::Input);
+
+        // 响应脉冲：传感器拉低约80µs，再释放约80µs
+        self.wait_level(false, RESPONSE_TIMEOUT)
+            .and_then(|_| self.wait_level(true, RESPONSE_TIMEOUT))
+            .and_then(|_| self.wait_level(false, RESPONSE_TIMEOUT))
+            .ok_or_else(|| anyhow::anyhow!("等待DHT传感器响应超时"))?;
+
+        // 数据位：低电平约50µs，之后高电平的宽度区分0和1
+        let mut widths = [0u32; FRAME_BITS];
+        for (i, width) in widths.iter_mut().enumerate() {
+            let high = self.wait_level(true, BIT_TIMEOUT).and_then(|rise| {
+                self.wait_level(false, BIT_TIMEOUT)
+                    .map(|fall| fall.duration_since(rise))
+            });
+            let Some(high) = high else {
+                return Err(anyhow::anyhow!(
+                    "等待DHT传感器数据超时: 只读到{}个数据位",
+                    i
+                ));
+            };
+            *width = high.as_micros() as u32;
+        }
+
+        let frame = dht::frame_from_pulses(&widths);
+        let (temperature, humidity) = dht::parse_frame(&frame, self.model)
+            .map_err(|_| anyhow::anyhow!("DHT传感器数据校验和错误: {:02X?}", frame))?;
+        trace_event!(debug, temperature, humidity, "DHT传感器GPIO读取完成");
+        // OK
+        Ok((temperature, humidity))
+    }
+
+    /// 忙等数据线变为指定电平，返回变化的时间（超时返回None）
+    fn wait_level(&self, level: bool, timeout: Duration) -> Option<Instant> {
+        let start = Instant::now();
+        loop {
+            let high = self.pin.is_high();
+            let now = Instant::now();
+            self.record(high);
+            if high == level {
+                return Some(now);
+            }
+            if now.duration_since(start) > timeout {
+                return None;
+            }
+        }
+    }
+
+    /// 记录数据线电平
+    #[inline]
+    fn record(&self, level: bool) {
+        if let Some(capture) = &self.capture {
+            capture.record(0, level);
+        }
+    }
+}
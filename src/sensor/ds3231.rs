@@ -0,0 +1,131 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use embedded_hal::i2c::I2c;
+
+use crate::i2c_bus::SharedBus;
+use crate::schedule::{UtcDateTime, days_from_civil};
+
+/// 寄存器地址
+mod reg {
+    pub const SECONDS: u8 = 0x00;
+    pub const STATUS: u8 = 0x0F;
+    pub const TEMPERATURE: u8 = 0x11;
+}
+
+/// 状态寄存器：振荡器曾经停止（掉电且没有电池，时间无效）
+const STATUS_OSF: u8 = 0x80;
+
+/// 设备地址
+pub const ADDRESS: u8 = 0x68;
+
+/// BCD转二进制
+fn from_bcd(value: u8) -> u8 {
+    (value >> 4) * 10 + (value & 0x0F)
+}
+
+/// 二进制转BCD
+fn to_bcd(value: u8) -> u8 {
+    ((value / 10) << 4) | (value % 10)
+}
+
+/// DS3231高精度实时时钟（I2C）
+///
+/// 时间按UTC保存（2000~2199年），树莓派断网启动时用来提供准确的时间
+pub struct DS3231<I = rppal::i2c::I2c> {
+    /// I2C通信总线
+    i2c_bus: SharedBus<I>,
+}
+
+impl<I: I2c> DS3231<I> {
+    /// 创建实例
+    ///
+    /// - i2c_bus: 共享的I2C通信总线
+    pub fn new<B: Into<SharedBus<I>>>(i2c_bus: B) -> Self {
+        Self {
+            i2c_bus: i2c_bus.into(),
+        }
+    }
+
+    /// 读取时间（振荡器曾经停止时返回错误）
+    pub fn read_time(&mut self) -> anyhow::Result<SystemTime> {
+        let mut status = [0u8; 1];
+        self.i2c_bus
+            .read_registers(ADDRESS, reg::STATUS, &mut status)?;
+        if status[0] & STATUS_OSF != 0 {
+            return Err(anyhow::anyhow!(
+                "DS3231振荡器曾经停止，时间无效（需要重新设置时间）"
+            ));
+        }
+
+        let mut data = [0u8; 7];
+        self.i2c_bus
+            .read_registers(ADDRESS, reg::SECONDS, &mut data)?;
+        let second = from_bcd(data[0] & 0x7F) as u64;
+        let minute = from_bcd(data[1] & 0x7F) as u64;
+        // 12小时制时第5位为下午标志
+        let hour = if data[2] & 0x40 != 0 {
+            let hour = from_bcd(data[2] & 0x1F) % 12;
+            if data[2] & 0x20 != 0 { hour + 12 } else { hour }
+        } else {
+            from_bcd(data[2] & 0x3F)
+        } as u64;
+        let day = from_bcd(data[4] & 0x3F) as u32;
+        let month = from_bcd(data[5] & 0x1F) as u32;
+        // 月份寄存器最高位为世纪标志
+        let century = if data[5] & 0x80 != 0 { 2100 } else { 2000 };
+        let year = century + from_bcd(data[6]) as i64;
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 {
+            return Err(anyhow::anyhow!("DS3231时间寄存器无效: {:02X?}", data));
+        }
+
+        let days = days_from_civil(year, month, day) as u64;
+        let seconds = days * 86400 + hour * 3600 + minute * 60 + second;
+        // OK
+        Ok(UNIX_EPOCH + Duration::from_secs(seconds))
+    }
+
+    /// 设置时间（同时清除振荡器停止标志）
+    pub fn set_time(&mut self, time: SystemTime) -> anyhow::Result<()> {
+        if time < UNIX_EPOCH {
+            return Err(anyhow::anyhow!("DS3231不支持1970年之前的时间"));
+        }
+        let utc = UtcDateTime::from_system_time(time);
+        if !(2000..2200).contains(&utc.year) {
+            return Err(anyhow::anyhow!("DS3231只支持2000~2199年: {}", utc.year));
+        }
+        let days = days_from_civil(utc.year, utc.month, utc.day);
+        // 1970-01-01为星期四，DS3231的星期为1~7
+        let weekday = ((days + 3) % 7 + 1) as u8;
+        let century = if utc.year >= 2100 { 0x80 } else { 0x00 };
+        let data = [
+            reg::SECONDS,
+            to_bcd(utc.second as u8),
+            to_bcd(utc.minute as u8),
+            to_bcd(utc.hour as u8),
+            weekday,
+            to_bcd(utc.day as u8),
+            century | to_bcd(utc.month as u8),
+            to_bcd((utc.year % 100) as u8),
+        ];
+        self.i2c_bus
+            .lock()
+            .write(ADDRESS, &data)
+            .map_err(|err| anyhow::anyhow!("设置DS3231时间失败: {:?}", err))?;
+
+        let mut status = [0u8; 1];
+        self.i2c_bus
+            .read_registers(ADDRESS, reg::STATUS, &mut status)?;
+        self.i2c_bus
+            .write_register(ADDRESS, reg::STATUS, status[0] & !STATUS_OSF)?;
+        // OK
+        Ok(())
+    }
+
+    /// 读取芯片温度（℃，精度0.25℃，每64秒更新一次）
+    pub fn read_temperature(&mut self) -> anyhow::Result<f64> {
+        let mut data = [0u8; 2];
+        self.i2c_bus
+            .read_registers(ADDRESS, reg::TEMPERATURE, &mut data)?;
+        Ok(i16::from_be_bytes(data) as f64 / 256.0)
+    }
+}
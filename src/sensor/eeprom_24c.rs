@@ -0,0 +1,192 @@
+use rppal::i2c::I2c;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// AT24Cxx芯片型号
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Model {
+    AT24C01,
+    AT24C02,
+    AT24C04,
+    AT24C08,
+    AT24C16,
+    AT24C32,
+    AT24C64,
+    AT24C128,
+    AT24C256,
+    AT24C512,
+}
+
+impl Model {
+    /// 容量（字节）
+    pub fn capacity(&self) -> usize {
+        match self {
+            Model::AT24C01 => 128,
+            Model::AT24C02 => 256,
+            Model::AT24C04 => 512,
+            Model::AT24C08 => 1024,
+            Model::AT24C16 => 2048,
+            Model::AT24C32 => 4096,
+            Model::AT24C64 => 8192,
+            Model::AT24C128 => 16384,
+            Model::AT24C256 => 32768,
+            Model::AT24C512 => 65536,
+        }
+    }
+
+    /// 页大小（字节），页写不能跨页
+    pub fn page_size(&self) -> usize {
+        match self {
+            Model::AT24C01 | Model::AT24C02 => 8,
+            Model::AT24C04 | Model::AT24C08 | Model::AT24C16 => 16,
+            Model::AT24C32 | Model::AT24C64 => 32,
+            Model::AT24C128 | Model::AT24C256 => 64,
+            Model::AT24C512 => 128,
+        }
+    }
+
+    /// 是否使用2字节字地址（24C32及以上）
+    fn two_byte_address(&self) -> bool {
+        self.capacity() > 2048
+    }
+}
+
+/// AT24Cxx I2C EEPROM封装对象
+pub struct AT24Cxx {
+    /// I2C通信总线
+    i2c_bus: Arc<Mutex<I2c>>,
+    /// 设备地址（A0~A2接地时为0x50）
+    address: u16,
+    /// 芯片型号
+    model: Model,
+}
+
+impl AT24Cxx {
+    /// 创建EEPROM实例
+    ///
+    /// - i2c_bus: 共享的I2C通信总线
+    /// - address: 设备地址，为None时使用默认地址0x50
+    /// - model: 芯片型号
+    pub fn new(i2c_bus: Arc<Mutex<I2c>>, address: Option<u16>, model: Model) -> Self {
+        Self {
+            i2c_bus,
+            address: address.unwrap_or(0x50),
+            model,
+        }
+    }
+
+    /// 获取芯片型号
+    pub fn model(&self) -> Model {
+        self.model
+    }
+
+    /// 计算指定偏移对应的设备地址和字地址
+    ///
+    /// 24C04~24C16的高位地址放在设备地址的低3位中
+    fn addressing(&self, offset: usize) -> (u16, Vec<u8>) {
+        if self.model.two_byte_address() {
+            (self.address, vec![(offset >> 8) as u8, offset as u8])
+        } else {
+            (
+                self.address | ((offset >> 8) as u16 & 0x07),
+                vec![offset as u8],
+            )
+        }
+    }
+
+    /// 检查访问范围
+    fn check_range(&self, offset: usize, len: usize) -> anyhow::Result<()> {
+        if offset + len > self.model.capacity() {
+            return Err(anyhow::anyhow!(
+                "EEPROM访问越界, 偏移: {}, 长度: {}, 容量: {}",
+                offset,
+                len,
+                self.model.capacity()
+            ));
+        }
+        Ok(())
+    }
+
+    /// 从任意偏移读取数据
+    pub fn read(&mut self, offset: usize, buffer: &mut [u8]) -> anyhow::Result<()> {
+        self.check_range(offset, buffer.len())?;
+
+        let mut i2c = self
+            .i2c_bus
+            .lock()
+            .map_err(|_| anyhow::anyhow!("I2C通信总线繁忙"))?;
+
+        let mut done = 0;
+        while done < buffer.len() {
+            let current = offset + done;
+            // 单字节地址的型号每256字节需要切换一次设备地址
+            let len = if self.model.two_byte_address() {
+                buffer.len() - done
+            } else {
+                (256 - current % 256).min(buffer.len() - done)
+            };
+            let (address, word_address) = self.addressing(current);
+            i2c.set_slave_address(address)?;
+            i2c.write_read(&word_address, &mut buffer[done..done + len])?;
+            done += len;
+        }
+        // OK
+        Ok(())
+    }
+
+    /// 向任意偏移写入数据
+    ///
+    /// 自动按页拆分写入，并在每页写入后等待内部写周期完成
+    pub fn write(&mut self, offset: usize, data: &[u8]) -> anyhow::Result<()> {
+        self.check_range(offset, data.len())?;
+
+        let page_size = self.model.page_size();
+        let mut done = 0;
+        while done < data.len() {
+            let current = offset + done;
+            // 页写入不能跨页，否则会回卷覆盖页首数据
+            let len = (page_size - current % page_size).min(data.len() - done);
+            let (address, mut frame) = self.addressing(current);
+            frame.extend_from_slice(&data[done..done + len]);
+
+            {
+                let mut i2c = self
+                    .i2c_bus
+                    .lock()
+                    .map_err(|_| anyhow::anyhow!("I2C通信总线繁忙"))?;
+                i2c.set_slave_address(address)?;
+                i2c.write(&frame)?;
+            }
+
+            self.wait_write_cycle(address)?;
+            done += len;
+        }
+        // OK
+        Ok(())
+    }
+
+    /// 等待内部写周期完成（ACK轮询）
+    ///
+    /// 写周期期间芯片不会应答，最长约5ms
+    fn wait_write_cycle(&mut self, address: u16) -> anyhow::Result<()> {
+        let start = Instant::now();
+        loop {
+            {
+                let mut i2c = self
+                    .i2c_bus
+                    .lock()
+                    .map_err(|_| anyhow::anyhow!("I2C通信总线繁忙"))?;
+                i2c.set_slave_address(address)?;
+                // 应答了字地址即表示写周期已完成
+                if i2c.write(&[0]).is_ok() {
+                    return Ok(());
+                }
+            }
+            if start.elapsed() > Duration::from_millis(20) {
+                return Err(anyhow::anyhow!("等待EEPROM写周期完成超时"));
+            }
+            thread::sleep(Duration::from_micros(500));
+        }
+    }
+}
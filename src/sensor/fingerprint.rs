@@ -0,0 +1,360 @@
+use rppal::uart::{Parity, Queue, Uart};
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 包头
+const HEADER: [u8; 2] = [0xEF, 0x01];
+/// 默认模块地址
+const DEFAULT_ADDRESS: u32 = 0xFFFF_FFFF;
+
+/// 包标识
+mod pid {
+    pub const COMMAND: u8 = 0x01;
+    pub const ACK: u8 = 0x07;
+}
+
+/// 指令码
+mod cmd {
+    pub const GEN_IMG: u8 = 0x01;
+    pub const IMG_2_TZ: u8 = 0x02;
+    pub const SEARCH: u8 = 0x04;
+    pub const REG_MODEL: u8 = 0x05;
+    pub const STORE: u8 = 0x06;
+    pub const DELETE_CHAR: u8 = 0x0C;
+    pub const EMPTY: u8 = 0x0D;
+    pub const VFY_PWD: u8 = 0x13;
+    pub const TEMPLATE_NUM: u8 = 0x1D;
+}
+
+/// 指纹模块确认码错误
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FingerprintError {
+    /// 数据包接收错误
+    PacketReceive,
+    /// 传感器上没有手指
+    NoFinger,
+    /// 录入指纹图像失败
+    EnrollFailed,
+    /// 指纹图像太乱
+    ImageMessy,
+    /// 指纹特征点太少
+    TooFewFeatures,
+    /// 指纹不匹配
+    NotMatch,
+    /// 没有搜索到指纹
+    NotFound,
+    /// 特征合并失败
+    MergeFailed,
+    /// 指纹库地址越界
+    BadLocation,
+    /// 读取模板失败
+    ReadTemplate,
+    /// 删除模板失败
+    DeleteFailed,
+    /// 清空指纹库失败
+    ClearFailed,
+    /// 口令不正确
+    WrongPassword,
+    /// 缓冲区内没有有效图像
+    InvalidImage,
+    /// 读写FLASH出错
+    Flash,
+    /// 其他确认码
+    Other(u8),
+}
+
+impl FingerprintError {
+    /// 将确认码转换为结果
+    fn check(code: u8) -> Result<(), FingerprintError> {
+        match code {
+            0x00 => Ok(()),
+            0x01 => Err(Self::PacketReceive),
+            0x02 => Err(Self::NoFinger),
+            0x03 => Err(Self::EnrollFailed),
+            0x06 => Err(Self::ImageMessy),
+            0x07 => Err(Self::TooFewFeatures),
+            0x08 => Err(Self::NotMatch),
+            0x09 => Err(Self::NotFound),
+            0x0A => Err(Self::MergeFailed),
+            0x0B => Err(Self::BadLocation),
+            0x0C => Err(Self::ReadTemplate),
+            0x10 => Err(Self::DeleteFailed),
+            0x11 => Err(Self::ClearFailed),
+            0x13 => Err(Self::WrongPassword),
+            0x15 => Err(Self::InvalidImage),
+            0x18 => Err(Self::Flash),
+            other => Err(Self::Other(other)),
+        }
+    }
+}
+
+impl fmt::Display for FingerprintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PacketReceive => write!(f, "数据包接收错误"),
+            Self::NoFinger => write!(f, "传感器上没有手指"),
+            Self::EnrollFailed => write!(f, "录入指纹图像失败"),
+            Self::ImageMessy => write!(f, "指纹图像太乱"),
+            Self::TooFewFeatures => write!(f, "指纹特征点太少"),
+            Self::NotMatch => write!(f, "指纹不匹配"),
+            Self::NotFound => write!(f, "没有搜索到指纹"),
+            Self::MergeFailed => write!(f, "特征合并失败"),
+            Self::BadLocation => write!(f, "指纹库地址越界"),
+            Self::ReadTemplate => write!(f, "读取模板失败"),
+            Self::DeleteFailed => write!(f, "删除模板失败"),
+            Self::ClearFailed => write!(f, "清空指纹库失败"),
+            Self::WrongPassword => write!(f, "口令不正确"),
+            Self::InvalidImage => write!(f, "缓冲区内没有有效图像"),
+            Self::Flash => write!(f, "读写FLASH出错"),
+            Self::Other(code) => write!(f, "未知确认码: 0x{:02X}", code),
+        }
+    }
+}
+
+impl std::error::Error for FingerprintError {}
+
+/// 指纹搜索结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Match {
+    /// 指纹库页码
+    pub page_id: u16,
+    /// 匹配得分
+    pub score: u16,
+}
+
+/// R503/AS608指纹模块封装对象（UART）
+pub struct Fingerprint {
+    /// 串口对象
+    uart: Uart,
+    /// 模块地址
+    address: u32,
+    /// 指纹库容量
+    capacity: u16,
+}
+
+impl Fingerprint {
+    /// 创建指纹模块实例
+    ///
+    /// - path: 串口设备路径，如"/dev/serial0"
+    /// - baud_rate: 波特率，模块默认为57600
+    /// - capacity: 指纹库容量（R503为200，AS608为300）
+    pub fn new(path: &str, baud_rate: u32, capacity: u16) -> anyhow::Result<Self> {
+        let mut uart = Uart::with_path(path, baud_rate, Parity::None, 8, 1)?;
+        // 阻塞读取，最多等待1秒
+        uart.set_read_mode(0, Duration::from_secs(1))?;
+        uart.flush(Queue::Both)?;
+        // OK
+        Ok(Self {
+            uart,
+            address: DEFAULT_ADDRESS,
+            capacity,
+        })
+    }
+
+    /// 发送指令包并读取应答包
+    ///
+    /// 返回确认码之后的应答数据
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "fingerprint.command",
+            level = "debug",
+            skip(self, params),
+            err(level = "warn")
+        )
+    )]
+    fn command(&mut self, code: u8, params: &[u8]) -> anyhow::Result<Vec<u8>> {
+        // 包长度 = 指令码 + 参数 + 校验和
+        let length = (params.len() + 3) as u16;
+        let mut packet = Vec::with_capacity(params.len() + 12);
+        packet.extend_from_slice(&HEADER);
+        packet.extend_from_slice(&self.address.to_be_bytes());
+        packet.push(pid::COMMAND);
+        packet.extend_from_slice(&length.to_be_bytes());
+        packet.push(code);
+        packet.extend_from_slice(params);
+        // 校验和 = 包标识到校验和之间所有字节之和
+        let checksum: u16 = packet[6..]
+            .iter()
+            .fold(0u16, |sum, &b| sum.wrapping_add(b as u16));
+        packet.extend_from_slice(&checksum.to_be_bytes());
+
+        self.uart.write(&packet)?;
+        self.uart.drain()?;
+
+        // 读取应答包头（包头2+地址4+包标识1+长度2）
+        let mut head = [0u8; 9];
+        self.read_exact(&mut head)?;
+        if head[0..2] != HEADER {
+            return Err(anyhow::anyhow!(
+                "指纹模块应答包头错误: {:02X?}",
+                &head[0..2]
+            ));
+        }
+        if head[6] != pid::ACK {
+            return Err(anyhow::anyhow!("指纹模块应答包标识错误: 0x{:02X}", head[6]));
+        }
+        let length = u16::from_be_bytes([head[7], head[8]]) as usize;
+        if length < 3 {
+            return Err(anyhow::anyhow!("指纹模块应答包长度错误: {}", length));
+        }
+
+        // 读取确认码、数据和校验和
+        let mut body = vec![0u8; length];
+        self.read_exact(&mut body)?;
+        let checksum = head[6..]
+            .iter()
+            .chain(&body[..length - 2])
+            .fold(0u16, |sum, &b| sum.wrapping_add(b as u16));
+        if checksum != u16::from_be_bytes([body[length - 2], body[length - 1]]) {
+            trace_event!(warn, code, checksum, "指纹模块应答校验和错误");
+            return Err(anyhow::anyhow!("指纹模块应答校验和错误"));
+        }
+
+        // 检查确认码
+        FingerprintError::check(body[0])?;
+        // OK
+        Ok(body[1..length - 2].to_vec())
+    }
+
+    /// 读取指定长度的数据
+    fn read_exact(&mut self, buffer: &mut [u8]) -> anyhow::Result<()> {
+        let mut offset = 0;
+        while offset < buffer.len() {
+            let n = self.uart.read(&mut buffer[offset..])?;
+            if n == 0 {
+                return Err(anyhow::anyhow!("指纹模块应答超时"));
+            }
+            offset += n;
+        }
+        Ok(())
+    }
+
+    /// 验证模块口令（出厂口令为0）
+    pub fn verify_password(&mut self, password: u32) -> anyhow::Result<()> {
+        self.command(cmd::VFY_PWD, &password.to_be_bytes())?;
+        Ok(())
+    }
+
+    /// 采集指纹图像
+    pub fn capture_image(&mut self) -> anyhow::Result<()> {
+        self.command(cmd::GEN_IMG, &[])?;
+        Ok(())
+    }
+
+    /// 将图像转换为特征存入特征缓冲区（1或2）
+    pub fn image_to_template(&mut self, buffer_id: u8) -> anyhow::Result<()> {
+        self.command(cmd::IMG_2_TZ, &[buffer_id])?;
+        Ok(())
+    }
+
+    /// 等待手指按下并采集图像
+    fn wait_finger(&mut self, timeout: Duration) -> anyhow::Result<()> {
+        let start = Instant::now();
+        loop {
+            match self.capture_image() {
+                Ok(()) => return Ok(()),
+                Err(err) => match err.downcast_ref::<FingerprintError>() {
+                    Some(FingerprintError::NoFinger) if start.elapsed() < timeout => {
+                        thread::sleep(Duration::from_millis(50));
+                    }
+                    _ => return Err(err),
+                },
+            }
+        }
+    }
+
+    /// 等待手指离开传感器
+    fn wait_release(&mut self, timeout: Duration) -> anyhow::Result<()> {
+        let start = Instant::now();
+        loop {
+            match self.capture_image() {
+                Err(err) => match err.downcast_ref::<FingerprintError>() {
+                    Some(FingerprintError::NoFinger) => return Ok(()),
+                    _ => return Err(err),
+                },
+                Ok(()) if start.elapsed() > timeout => {
+                    return Err(anyhow::anyhow!("等待手指离开传感器超时"));
+                }
+                Ok(()) => thread::sleep(Duration::from_millis(50)),
+            }
+        }
+    }
+
+    /// 录入指纹
+    ///
+    /// - page_id: 存储到指纹库的页码
+    /// - timeout: 每次等待手指按下的超时时间
+    ///
+    /// 需要同一手指按压两次，两次之间需要抬起手指
+    pub fn enroll(&mut self, page_id: u16, timeout: Duration) -> anyhow::Result<()> {
+        if page_id >= self.capacity {
+            return Err(FingerprintError::BadLocation.into());
+        }
+
+        // 两次采集分别生成特征到缓冲区1和2
+        for buffer_id in 1..=2 {
+            self.wait_finger(timeout)?;
+            self.image_to_template(buffer_id)?;
+            if buffer_id == 1 {
+                self.wait_release(timeout)?;
+            }
+        }
+
+        // 合并特征生成模板并存储
+        self.command(cmd::REG_MODEL, &[])?;
+        let mut params = vec![1];
+        params.extend_from_slice(&page_id.to_be_bytes());
+        self.command(cmd::STORE, &params)?;
+        // OK
+        Ok(())
+    }
+
+    /// 采集指纹并在整个指纹库中搜索
+    pub fn search(&mut self, timeout: Duration) -> anyhow::Result<Match> {
+        self.wait_finger(timeout)?;
+        self.image_to_template(1)?;
+
+        let mut params = vec![1, 0, 0];
+        params.extend_from_slice(&self.capacity.to_be_bytes());
+        let data = self.command(cmd::SEARCH, &params)?;
+        if data.len() < 4 {
+            return Err(anyhow::anyhow!("指纹模块搜索应答长度错误: {}", data.len()));
+        }
+        // OK
+        Ok(Match {
+            page_id: u16::from_be_bytes([data[0], data[1]]),
+            score: u16::from_be_bytes([data[2], data[3]]),
+        })
+    }
+
+    /// 删除指纹模板
+    ///
+    /// - page_id: 起始页码
+    /// - count: 删除的模板数量
+    pub fn delete(&mut self, page_id: u16, count: u16) -> anyhow::Result<()> {
+        let mut params = page_id.to_be_bytes().to_vec();
+        params.extend_from_slice(&count.to_be_bytes());
+        self.command(cmd::DELETE_CHAR, &params)?;
+        Ok(())
+    }
+
+    /// 清空指纹库
+    pub fn clear(&mut self) -> anyhow::Result<()> {
+        self.command(cmd::EMPTY, &[])?;
+        Ok(())
+    }
+
+    /// 读取已存储的模板数量
+    pub fn template_count(&mut self) -> anyhow::Result<u16> {
+        let data = self.command(cmd::TEMPLATE_NUM, &[])?;
+        if data.len() < 2 {
+            return Err(anyhow::anyhow!(
+                "指纹模块模板数量应答长度错误: {}",
+                data.len()
+            ));
+        }
+        Ok(u16::from_be_bytes([data[0], data[1]]))
+    }
+}
@@ -0,0 +1,460 @@
+use embedded_hal::digital::{self, ErrorKind};
+use rppal::gpio::{Gpio, InputPin, Trigger};
+use rppal::i2c::I2c;
+use std::sync::{Arc, Mutex};
+
+use crate::debug::{self, PinMode};
+use crate::spi_bus::SpiDeviceHandle;
+
+/// 扩展芯片型号
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Chip {
+    /// 8路准双向IO，默认地址0x20（PCF8574A为0x38）
+    PCF8574,
+    /// 16路IO（A0~A7对应0~7，B0~B7对应8~15），默认地址0x20
+    MCP23017,
+    /// SPI接口的MCP23017，引脚编号与MCP23017相同
+    MCP23S17,
+}
+
+impl Chip {
+    /// 引脚数量
+    pub fn pin_count(&self) -> u8 {
+        match self {
+            Chip::PCF8574 => 8,
+            Chip::MCP23017 | Chip::MCP23S17 => 16,
+        }
+    }
+}
+
+/// MCP23017寄存器地址（IOCON.BANK=0）
+mod mcp {
+    pub const IODIRA: u8 = 0x00;
+    pub const GPINTENA: u8 = 0x04;
+    pub const IOCON: u8 = 0x0A;
+    pub const GPPUA: u8 = 0x0C;
+    pub const GPIOA: u8 = 0x12;
+    pub const OLATA: u8 = 0x14;
+}
+
+/// 通信接口
+enum Transport {
+    /// I2C（PCF8574、MCP23017）
+    I2c {
+        i2c_bus: Arc<Mutex<I2c>>,
+        address: u16,
+    },
+    /// SPI（MCP23S17，同一片选上最多挂载8片，通过A0~A2硬件地址区分）
+    Spi {
+        device: SpiDeviceHandle,
+        hw_address: u8,
+    },
+}
+
+impl Transport {
+    /// 写数据（SPI时自动加上操作码）
+    fn write(&mut self, data: &[u8]) -> anyhow::Result<()> {
+        match self {
+            Transport::I2c { i2c_bus, address } => {
+                let mut i2c = i2c_bus
+                    .lock()
+                    .map_err(|_| anyhow::anyhow!("I2C通信总线繁忙"))?;
+                i2c.set_slave_address(*address)?;
+                i2c.write(data)?;
+            }
+            Transport::Spi { device, hw_address } => {
+                let mut frame = Vec::with_capacity(data.len() + 1);
+                frame.push(0x40 | (*hw_address << 1));
+                frame.extend_from_slice(data);
+                device.write(&frame)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 先写后读（write为空时直接读取）
+    fn write_read(&mut self, write: &[u8], read: &mut [u8]) -> anyhow::Result<()> {
+        match self {
+            Transport::I2c { i2c_bus, address } => {
+                let mut i2c = i2c_bus
+                    .lock()
+                    .map_err(|_| anyhow::anyhow!("I2C通信总线繁忙"))?;
+                i2c.set_slave_address(*address)?;
+                if write.is_empty() {
+                    i2c.read(read)?;
+                } else {
+                    i2c.write_read(write, read)?;
+                }
+            }
+            Transport::Spi { device, hw_address } => {
+                // 操作码（读）+ 寄存器地址，之后的字节为读取的数据
+                let mut tx = vec![0u8; 1 + write.len() + read.len()];
+                tx[0] = 0x41 | (*hw_address << 1);
+                tx[1..=write.len()].copy_from_slice(write);
+                let mut rx = vec![0u8; tx.len()];
+                device.transfer(&mut rx, &tx)?;
+                read.copy_from_slice(&rx[1 + write.len()..]);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 扩展芯片状态（所有引脚共享）
+struct ExpanderState {
+    /// 通信接口
+    transport: Transport,
+    /// 芯片型号
+    chip: Chip,
+    /// 输出锁存值
+    latch: u16,
+    /// 输入引脚掩码（1为输入）
+    inputs: u16,
+    /// 上一次读取到的端口电平
+    last_port: u16,
+}
+
+impl ExpanderState {
+    /// 写MCP23017的一对A/B寄存器
+    fn write_pair(&mut self, reg: u8, value: u16) -> anyhow::Result<()> {
+        self.transport
+            .write(&[reg, value as u8, (value >> 8) as u8])
+    }
+
+    /// 将方向、上拉、中断和输出锁存同步到芯片
+    fn sync(&mut self) -> anyhow::Result<()> {
+        match self.chip {
+            Chip::PCF8574 => {
+                // 准双向IO：输入引脚必须写1（弱上拉）
+                self.transport.write(&[(self.latch | self.inputs) as u8])?;
+            }
+            Chip::MCP23017 | Chip::MCP23S17 => {
+                self.write_pair(mcp::IODIRA, self.inputs)?;
+                self.write_pair(mcp::GPPUA, self.inputs)?;
+                self.write_pair(mcp::GPINTENA, self.inputs)?;
+                self.write_pair(mcp::OLATA, self.latch)?;
+            }
+        }
+        // OK
+        Ok(())
+    }
+
+    /// 刷新输出锁存
+    fn write_latch(&mut self) -> anyhow::Result<()> {
+        match self.chip {
+            Chip::PCF8574 => self.transport.write(&[(self.latch | self.inputs) as u8]),
+            Chip::MCP23017 | Chip::MCP23S17 => self.write_pair(mcp::OLATA, self.latch),
+        }
+    }
+
+    /// 读取端口电平（同时会清除芯片的中断状态）
+    fn read_port(&mut self) -> anyhow::Result<u16> {
+        let port = match self.chip {
+            Chip::PCF8574 => {
+                let mut buffer = [0u8; 1];
+                self.transport.write_read(&[], &mut buffer)?;
+                buffer[0] as u16
+            }
+            Chip::MCP23017 | Chip::MCP23S17 => {
+                let mut buffer = [0u8; 2];
+                self.transport.write_read(&[mcp::GPIOA], &mut buffer)?;
+                u16::from_le_bytes(buffer)
+            }
+        };
+        self.last_port = port;
+        // OK
+        Ok(port)
+    }
+}
+
+/// 引脚电平变化回调
+type PinCallback = Box<dyn FnMut(bool) + Send>;
+
+/// PCF8574/MCP23017/MCP23S17 GPIO扩展芯片封装对象
+///
+/// 扩展出的引脚实现了embedded_hal的InputPin/OutputPin，
+/// 可以直接替代树莓派引脚传给sensor-hal的LED、按钮、继电器驱动
+pub struct GpioExpander {
+    /// 芯片状态
+    state: Arc<Mutex<ExpanderState>>,
+    /// 引脚电平变化回调列表
+    callbacks: Arc<Mutex<Vec<(u8, PinCallback)>>>,
+    /// 芯片中断信号接入的树莓派引脚
+    int_pin: Option<InputPin>,
+}
+
+impl GpioExpander {
+    /// 创建扩展芯片实例
+    ///
+    /// - i2c_bus: 共享的I2C通信总线
+    /// - address: 设备地址，为None时使用默认地址0x20
+    /// - chip: 芯片型号
+    pub fn new(i2c_bus: Arc<Mutex<I2c>>, address: Option<u16>, chip: Chip) -> anyhow::Result<Self> {
+        if chip == Chip::MCP23S17 {
+            return Err(anyhow::anyhow!("MCP23S17请使用new_spi创建"));
+        }
+        let transport = Transport::I2c {
+            i2c_bus,
+            address: address.unwrap_or(0x20),
+        };
+        Self::with_transport(transport, chip)
+    }
+
+    /// 创建SPI接口的MCP23S17实例
+    ///
+    /// - device: SPI设备（最高10MHz，Mode0）
+    /// - hw_address: A0~A2引脚设置的硬件地址（0~7）
+    pub fn new_spi(device: SpiDeviceHandle, hw_address: u8) -> anyhow::Result<Self> {
+        if hw_address > 7 {
+            return Err(anyhow::anyhow!("MCP23S17硬件地址超出范围: {}", hw_address));
+        }
+        let transport = Transport::Spi { device, hw_address };
+        Self::with_transport(transport, Chip::MCP23S17)
+    }
+
+    /// 初始化
+    fn with_transport(transport: Transport, chip: Chip) -> anyhow::Result<Self> {
+        let mut state = ExpanderState {
+            transport,
+            chip,
+            latch: 0,
+            inputs: 0,
+            last_port: 0,
+        };
+
+        match chip {
+            Chip::PCF8574 => {}
+            // MIRROR: INTA/INTB合并输出，只需要接一根中断线
+            Chip::MCP23017 => state.transport.write(&[mcp::IOCON, 0x40])?,
+            // HAEN: 启用硬件地址，同一片选上的多片芯片才能区分
+            Chip::MCP23S17 => state.transport.write(&[mcp::IOCON, 0x48])?,
+        }
+        // 上电默认全部为输出低电平
+        state.sync()?;
+
+        // OK
+        Ok(Self {
+            state: Arc::new(Mutex::new(state)),
+            callbacks: Arc::new(Mutex::new(Vec::new())),
+            int_pin: None,
+        })
+    }
+
+    /// 检查引脚编号
+    fn check_pin(&self, pin: u8) -> anyhow::Result<()> {
+        let state = self
+            .state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("GPIO扩展芯片状态异常"))?;
+        if pin >= state.chip.pin_count() {
+            return Err(anyhow::anyhow!("GPIO扩展芯片引脚编号超出范围: {}", pin));
+        }
+        Ok(())
+    }
+
+    /// 将引脚配置为输入模式（上拉）
+    pub fn input_pin(&self, pin: u8) -> anyhow::Result<ExpanderInputPin> {
+        self.check_pin(pin)?;
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("GPIO扩展芯片状态异常"))?;
+        state.inputs |= 1 << pin;
+        state.sync()?;
+        // OK
+        Ok(ExpanderInputPin {
+            state: self.state.clone(),
+            callbacks: self.callbacks.clone(),
+            pin,
+        })
+    }
+
+    /// 将引脚配置为输出模式（默认低电平）
+    pub fn output_pin(&self, pin: u8) -> anyhow::Result<ExpanderOutputPin> {
+        self.check_pin(pin)?;
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("GPIO扩展芯片状态异常"))?;
+        state.inputs &= !(1 << pin);
+        state.latch &= !(1 << pin);
+        state.sync()?;
+        // OK
+        Ok(ExpanderOutputPin {
+            state: self.state.clone(),
+            pin,
+        })
+    }
+
+    /// 读取整个端口的电平
+    pub fn read_port(&self) -> anyhow::Result<u16> {
+        self.state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("GPIO扩展芯片状态异常"))?
+            .read_port()
+    }
+
+    /// 启用芯片中断线
+    ///
+    /// 芯片的INT引脚接到树莓派后，任何输入引脚电平变化都会触发一次端口读取，
+    /// 并分发到对应输入引脚通过`on_change`注册的回调中
+    pub fn enable_interrupt(&mut self, int_pin: u8) -> anyhow::Result<()> {
+        // 中断线为开漏低电平有效
+        let mut pin = Gpio::new()?.get(int_pin)?.into_input_pullup();
+        debug::claim(int_pin, "gpio-expander", PinMode::Input);
+
+        // 记录当前电平作为比较基准
+        self.read_port()?;
+
+        let state = self.state.clone();
+        let callbacks = self.callbacks.clone();
+        pin.set_async_interrupt(Trigger::FallingEdge, None, move |_| {
+            debug::record_interrupt(int_pin);
+            // 读取端口同时清除中断
+            let (previous, port) = match state.lock() {
+                Ok(mut state) => {
+                    let previous = state.last_port & state.inputs;
+                    match state.read_port() {
+                        Ok(port) => (previous, port & state.inputs),
+                        Err(err) => {
+                            eprintln!("读取GPIO扩展芯片端口失败: {}", err);
+                            return;
+                        }
+                    }
+                }
+                Err(_) => return,
+            };
+
+            // 分发电平发生变化的引脚
+            let changed = previous ^ port;
+            if let Ok(mut callbacks) = callbacks.lock() {
+                for (pin, cb) in callbacks.iter_mut() {
+                    if changed & (1 << *pin) != 0 {
+                        cb(port & (1 << *pin) != 0);
+                    }
+                }
+            }
+        })?;
+
+        self.int_pin = Some(pin);
+        // OK
+        Ok(())
+    }
+}
+
+/// 扩展芯片输入引脚
+pub struct ExpanderInputPin {
+    state: Arc<Mutex<ExpanderState>>,
+    callbacks: Arc<Mutex<Vec<(u8, PinCallback)>>>,
+    pin: u8,
+}
+
+impl ExpanderInputPin {
+    /// 引脚编号
+    pub fn pin(&self) -> u8 {
+        self.pin
+    }
+
+    /// 读取引脚电平
+    pub fn read(&self) -> anyhow::Result<bool> {
+        let port = self
+            .state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("GPIO扩展芯片状态异常"))?
+            .read_port()?;
+        Ok(port & (1 << self.pin) != 0)
+    }
+
+    /// 监听引脚电平变化（需要先调用`GpioExpander::enable_interrupt`）
+    ///
+    /// - True: 高电平
+    /// - False: 低电平
+    pub fn on_change<F>(&self, cb: F) -> anyhow::Result<()>
+    where
+        F: FnMut(bool) + Send + 'static,
+    {
+        self.callbacks
+            .lock()
+            .map_err(|_| anyhow::anyhow!("GPIO扩展芯片状态异常"))?
+            .push((self.pin, Box::new(cb)));
+        Ok(())
+    }
+}
+
+impl digital::ErrorType for ExpanderInputPin {
+    type Error = ErrorKind;
+}
+
+impl digital::InputPin for ExpanderInputPin {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        self.read().map_err(|_| ErrorKind::Other)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        self.is_high().map(|high| !high)
+    }
+}
+
+/// 扩展芯片输出引脚
+pub struct ExpanderOutputPin {
+    state: Arc<Mutex<ExpanderState>>,
+    pin: u8,
+}
+
+impl ExpanderOutputPin {
+    /// 引脚编号
+    pub fn pin(&self) -> u8 {
+        self.pin
+    }
+
+    /// 设置引脚电平
+    pub fn write(&mut self, high: bool) -> anyhow::Result<()> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("GPIO扩展芯片状态异常"))?;
+        if high {
+            state.latch |= 1 << self.pin;
+        } else {
+            state.latch &= !(1 << self.pin);
+        }
+
+        // 只需要刷新输出锁存
+        state.write_latch()
+    }
+
+    /// 读取当前输出锁存状态
+    pub fn is_set_high(&self) -> anyhow::Result<bool> {
+        let state = self
+            .state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("GPIO扩展芯片状态异常"))?;
+        Ok(state.latch & (1 << self.pin) != 0)
+    }
+}
+
+impl digital::ErrorType for ExpanderOutputPin {
+    type Error = ErrorKind;
+}
+
+impl digital::OutputPin for ExpanderOutputPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.write(false).map_err(|_| ErrorKind::Other)
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.write(true).map_err(|_| ErrorKind::Other)
+    }
+}
+
+impl digital::StatefulOutputPin for ExpanderOutputPin {
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        ExpanderOutputPin::is_set_high(self).map_err(|_| ErrorKind::Other)
+    }
+
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        ExpanderOutputPin::is_set_high(self)
+            .map(|high| !high)
+            .map_err(|_| ErrorKind::Other)
+    }
+}
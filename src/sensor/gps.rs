@@ -0,0 +1,215 @@
+use rppal::uart::{Parity, Uart};
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicBool, Ordering},
+};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// GPS定位信息
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GpsFix {
+    /// 是否已定位
+    pub valid: bool,
+    /// 定位质量（0:未定位，1:GPS定位，2:差分定位）
+    pub quality: u8,
+    /// UTC时间（hhmmss.ss）
+    pub utc_time: String,
+    /// 纬度（度，北纬为正）
+    pub latitude: f64,
+    /// 经度（度，东经为正）
+    pub longitude: f64,
+    /// 海拔高度（米）
+    pub altitude: f32,
+    /// 对地速度（千米/小时）
+    pub speed_kmh: f32,
+    /// 对地航向（度）
+    pub course: f32,
+    /// 水平精度因子
+    pub hdop: f32,
+    /// 参与定位的卫星数量
+    pub satellites: u8,
+}
+
+/// NEO-6M/NEO-M8 GPS模块封装对象（UART）
+pub struct Gps {
+    /// 最新定位信息
+    fix: Arc<Mutex<GpsFix>>,
+    /// 最新定位信息的更新时间
+    updated_at: Arc<Mutex<Option<Instant>>>,
+    /// 读取线程运行标志
+    running: Arc<AtomicBool>,
+}
+
+impl Gps {
+    /// 创建GPS模块实例并启动后台读取线程
+    ///
+    /// - path: 串口设备路径，如"/dev/serial0"
+    /// - baud_rate: 波特率，模块默认为9600
+    pub fn new(path: &str, baud_rate: u32) -> anyhow::Result<Self> {
+        let mut uart = Uart::with_path(path, baud_rate, Parity::None, 8, 1)?;
+        // 最多等待1秒，便于读取线程及时检查退出标志
+        uart.set_read_mode(0, Duration::from_secs(1))?;
+
+        let fix = Arc::new(Mutex::new(GpsFix::default()));
+        let updated_at = Arc::new(Mutex::new(None));
+        let running = Arc::new(AtomicBool::new(true));
+
+        Self::loop_read(uart, fix.clone(), updated_at.clone(), running.clone());
+
+        // OK
+        Ok(Self {
+            fix,
+            updated_at,
+            running,
+        })
+    }
+
+    /// 循环读取NMEA语句
+    fn loop_read(
+        mut uart: Uart,
+        fix: Arc<Mutex<GpsFix>>,
+        updated_at: Arc<Mutex<Option<Instant>>>,
+        running: Arc<AtomicBool>,
+    ) {
+        thread::spawn(move || {
+            let mut line: Vec<u8> = Vec::with_capacity(128);
+            let mut buffer = [0u8; 256];
+
+            while running.load(Ordering::Acquire) {
+                let n = match uart.read(&mut buffer) {
+                    Ok(n) => n,
+                    Err(err) => {
+                        eprintln!("读取GPS串口数据失败: {}", err);
+                        thread::sleep(Duration::from_secs(1));
+                        continue;
+                    }
+                };
+
+                for &byte in &buffer[..n] {
+                    match byte {
+                        b'\n' => {
+                            if let Ok(sentence) = std::str::from_utf8(&line)
+                                && let Ok(mut fix) = fix.lock()
+                                && parse_sentence(sentence.trim(), &mut fix)
+                                && let Ok(mut updated_at) = updated_at.lock()
+                            {
+                                *updated_at = Some(Instant::now());
+                            }
+                            line.clear();
+                        }
+                        // NMEA语句最长82字节，超长说明数据错乱
+                        _ if line.len() >= 128 => line.clear(),
+                        _ => line.push(byte),
+                    }
+                }
+            }
+        });
+    }
+
+    /// 获取最新定位信息（未定位时返回None）
+    pub fn latest_fix(&self) -> Option<GpsFix> {
+        let fix = self.fix.lock().ok()?;
+        if fix.valid { Some(fix.clone()) } else { None }
+    }
+
+    /// 距离上次收到有效语句经过的时间
+    pub fn last_update_elapsed(&self) -> Option<Duration> {
+        self.updated_at
+            .lock()
+            .ok()?
+            .map(|instant| instant.elapsed())
+    }
+}
+
+impl Drop for Gps {
+    fn drop(&mut self) {
+        // 通知读取线程退出
+        self.running.store(false, Ordering::Release);
+    }
+}
+
+/// 解析一条NMEA语句并更新定位信息
+///
+/// 返回是否成功解析
+fn parse_sentence(sentence: &str, fix: &mut GpsFix) -> bool {
+    let Some(body) = sentence.strip_prefix('$') else {
+        return false;
+    };
+    // 分离校验和
+    let Some((body, checksum)) = body.split_once('*') else {
+        return false;
+    };
+    let Ok(checksum) = u8::from_str_radix(checksum, 16) else {
+        return false;
+    };
+    if body.bytes().fold(0u8, |sum, b| sum ^ b) != checksum {
+        trace_event!(debug, sentence, "GPS语句校验和错误");
+        return false;
+    }
+
+    let fields: Vec<&str> = body.split(',').collect();
+    // 语句类型不区分GP/GN/GL等发送方
+    let kind = fields[0].get(2..).unwrap_or("");
+    match kind {
+        "GGA" if fields.len() >= 10 => {
+            fix.utc_time = fields[1].to_string();
+            if let Some(latitude) = parse_coordinate(fields[2], fields[3]) {
+                fix.latitude = latitude;
+            }
+            if let Some(longitude) = parse_coordinate(fields[4], fields[5]) {
+                fix.longitude = longitude;
+            }
+            fix.quality = fields[6].parse().unwrap_or(0);
+            fix.satellites = fields[7].parse().unwrap_or(0);
+            fix.hdop = fields[8].parse().unwrap_or(0.0);
+            fix.altitude = fields[9].parse().unwrap_or(0.0);
+            fix.valid = fix.quality > 0;
+            true
+        }
+        "RMC" if fields.len() >= 9 => {
+            fix.utc_time = fields[1].to_string();
+            fix.valid = fields[2] == "A";
+            if let Some(latitude) = parse_coordinate(fields[3], fields[4]) {
+                fix.latitude = latitude;
+            }
+            if let Some(longitude) = parse_coordinate(fields[5], fields[6]) {
+                fix.longitude = longitude;
+            }
+            // 节转换为千米/小时
+            if let Ok(knots) = fields[7].parse::<f32>() {
+                fix.speed_kmh = knots * 1.852;
+            }
+            if let Ok(course) = fields[8].parse() {
+                fix.course = course;
+            }
+            true
+        }
+        "VTG" if fields.len() >= 8 => {
+            if let Ok(course) = fields[1].parse() {
+                fix.course = course;
+            }
+            if let Ok(speed) = fields[7].parse() {
+                fix.speed_kmh = speed;
+            }
+            true
+        }
+        _ => false,
+    }
+}
+
+/// 解析NMEA经纬度（ddmm.mmmm / dddmm.mmmm）为度
+fn parse_coordinate(value: &str, hemisphere: &str) -> Option<f64> {
+    let dot = value.find('.')?;
+    if dot < 2 {
+        return None;
+    }
+    let degrees: f64 = value[..dot - 2].parse().ok()?;
+    let minutes: f64 = value[dot - 2..].parse().ok()?;
+    let coordinate = degrees + minutes / 60.0;
+    match hemisphere {
+        "S" | "W" => Some(-coordinate),
+        _ => Some(coordinate),
+    }
+}
@@ -0,0 +1,574 @@
+use rppal::gpio::{Gpio, InputPin, OutputPin};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::capture::SignalCapture;
+use crate::core::hx711::sign_extend;
+use crate::debug::{self, PinMode};
+use crate::gpio::{self, Drive, Pull};
+use crate::rate_limit::{Policy, RateLimit};
+use crate::scale::WeightAdc;
+
+pub use crate::core::hx711::ChannelGain;
+
+/// 输出数据速率（由RATE引脚电平决定）
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rate {
+    /// 10SPS（RATE接低电平，抗工频干扰更好）
+    Sps10,
+    /// 80SPS（RATE接高电平）
+    Sps80,
+}
+
+impl Rate {
+    /// 转换周期
+    pub fn period(&self) -> Duration {
+        match self {
+            Rate::Sps10 => Duration::from_millis(100),
+            Rate::Sps80 => Duration::from_micros(12_500),
+        }
+    }
+
+    /// 切换速率后的建立时间
+    fn settling_time(&self) -> Duration {
+        match self {
+            Rate::Sps10 => Duration::from_millis(400),
+            Rate::Sps80 => Duration::from_millis(50),
+        }
+    }
+}
+
+/// 推荐平均窗口的目标有效位数
+const TARGET_EFFECTIVE_BITS: f64 = 20.0;
+
+/// 噪声特性报告
+///
+/// 有效位数按log2(满量程/标准差)计算，无噪声位数按log2(满量程/峰峰值)计算；
+/// 平均N个读数后标准差约降为1/√N，推荐的平均窗口为达到20位有效分辨率所需的读数个数，
+/// 不超过1秒内的读数个数（响应时间和噪声之间的折中）
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoiseReport {
+    /// 读数个数
+    pub samples: usize,
+    /// 均值
+    pub mean: f64,
+    /// 标准差
+    pub std_dev: f64,
+    /// 峰峰值
+    pub peak_to_peak: i32,
+    /// 有效位数（ENOB）
+    pub effective_bits: f64,
+    /// 无噪声位数
+    pub noise_free_bits: f64,
+    /// 推荐的平均窗口（读数个数）
+    pub recommended_window: usize,
+}
+
+impl NoiseReport {
+    /// 按读数计算噪声特性
+    ///
+    /// - values: ADC读数（至少2个）
+    /// - rate: 读数的输出速率（用于限制推荐的平均窗口）
+    pub fn from_samples(values: &[i32], rate: Rate) -> anyhow::Result<Self> {
+        if values.len() < 2 {
+            return Err(anyhow::anyhow!("噪声测量至少需要2个读数: {}", values.len()));
+        }
+        let n = values.len() as f64;
+        let mean = values.iter().map(|&v| v as f64).sum::<f64>() / n;
+        let variance = values
+            .iter()
+            .map(|&v| (v as f64 - mean).powi(2))
+            .sum::<f64>()
+            / (n - 1.0);
+        let std_dev = variance.sqrt();
+        let min = values.iter().copied().min().unwrap_or_default();
+        let max = values.iter().copied().max().unwrap_or_default();
+        let peak_to_peak = max - min;
+        // 满量程为24位，读数完全不变时按1个LSB计算
+        let full_scale = (1u32 << 24) as f64;
+        let effective_bits = (full_scale / std_dev.max(1.0)).log2();
+        let noise_free_bits = (full_scale / (peak_to_peak as f64).max(1.0)).log2();
+        // 平均窗口不超过1秒内的读数个数
+        let per_second = (1.0 / rate.period().as_secs_f64()).round() as usize;
+        let target = full_scale / 2f64.powf(TARGET_EFFECTIVE_BITS);
+        let recommended_window = window_for(std_dev, target).min(per_second.max(1));
+        // OK
+        Ok(Self {
+            samples: values.len(),
+            mean,
+            std_dev,
+            peak_to_peak,
+            effective_bits,
+            noise_free_bits,
+            recommended_window,
+        })
+    }
+
+    /// 标准差降到指定值（ADC读数）所需的平均窗口
+    pub fn window_for(&self, target_std_dev: f64) -> usize {
+        window_for(self.std_dev, target_std_dev)
+    }
+}
+
+/// 平均N个读数后标准差约为std_dev/√N，计算达到目标标准差的N
+fn window_for(std_dev: f64, target_std_dev: f64) -> usize {
+    if target_std_dev <= 0.0 {
+        return usize::MAX;
+    }
+    ((std_dev / target_std_dev).powi(2).ceil() as usize).max(1)
+}
+
+impl std::fmt::Display for NoiseReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}个读数: 均值{:.1}, 标准差{:.1}, 峰峰值{}, 有效位数{:.1}, 无噪声位数{:.1}, 推荐平均窗口{}",
+            self.samples,
+            self.mean,
+            self.std_dev,
+            self.peak_to_peak,
+            self.effective_bits,
+            self.noise_free_bits,
+            self.recommended_window
+        )
+    }
+}
+
+/// 忙等待指定微秒（thread::sleep的精度不够，HX711时钟高电平超过60µs会进入掉电模式）
+#[inline(always)]
+fn delay_us(us: u64) {
+    let start = Instant::now();
+    let duration = Duration::from_micros(us);
+    while start.elapsed() < duration {
+        std::hint::spin_loop();
+    }
+}
+
+/// 输出一个时钟脉冲并在高电平期间读取数据引脚
+///
+/// 信号捕获的序号0为时钟，之后依次为各数据引脚
+#[inline(always)]
+fn clock_pulse(
+    clock: &mut OutputPin,
+    data: &[InputPin],
+    values: &mut [i32],
+    capture: &SignalCapture,
+) {
+    clock.set_high();
+    capture.record(0, true);
+    delay_us(1);
+    for (i, (pin, value)) in data.iter().zip(values.iter_mut()).enumerate() {
+        let level = pin.is_high();
+        capture.record(i + 1, level);
+        *value = (*value << 1) | level as i32;
+    }
+    clock.set_low();
+    capture.record(0, false);
+    delay_us(1);
+}
+
+/// 设置RATE引脚电平
+fn apply_rate(rate_pin: &mut OutputPin, rate: Rate) {
+    match rate {
+        Rate::Sps10 => rate_pin.set_low(),
+        Rate::Sps80 => rate_pin.set_high(),
+    }
+}
+
+/// 共享时钟线的HX711读取逻辑（单片和多片共用）
+struct Hx711Bus {
+    /// 时钟引脚（PD_SCK）
+    clock: OutputPin,
+    /// 数据引脚（DOUT）
+    data: Vec<InputPin>,
+    /// 速率引脚（RATE，未接线时为None）
+    rate_pin: Option<OutputPin>,
+    /// 通道和增益
+    gain: ChannelGain,
+    /// 当前速率
+    rate: Rate,
+    /// 时钟和数据引脚的信号捕获
+    capture: SignalCapture,
+    /// 上一次读取完成的时间
+    last_read: Option<Instant>,
+}
+
+impl Hx711Bus {
+    /// 创建实例
+    fn new(
+        clock_pin: u8,
+        data_pins: &[u8],
+        gain: ChannelGain,
+        rate_pin: Option<u8>,
+    ) -> anyhow::Result<Self> {
+        let gpio = Gpio::new()?;
+        let clock = gpio.get(clock_pin)?.into_output_low();
+        let mut data = Vec::with_capacity(data_pins.len());
+        for &pin in data_pins {
+            data.push(gpio.get(pin)?.into_input_pullup());
+        }
+        let rate_pin = match rate_pin {
+            Some(pin) => Some(gpio.get(pin)?.into_output_low()),
+            None => None,
+        };
+        debug::claim(clock_pin, "hx711", PinMode::Output);
+        for &pin in data_pins {
+            debug::claim(pin, "hx711", PinMode::Input);
+        }
+        if let Some(pin) = &rate_pin {
+            debug::claim(pin.pin(), "hx711", PinMode::Output);
+        }
+        let mut signals = vec!["pd_sck".to_string()];
+        if data_pins.len() == 1 {
+            signals.push("dout".to_string());
+        } else {
+            signals.extend((0..data_pins.len()).map(|i| format!("dout{}", i)));
+        }
+        let signals: Vec<&str> = signals.iter().map(String::as_str).collect();
+        // OK
+        Ok(Self {
+            clock,
+            data,
+            rate_pin,
+            gain,
+            rate: Rate::Sps10,
+            capture: SignalCapture::new(&signals),
+            last_read: None,
+        })
+    }
+
+    /// 设置数据引脚的内部上下拉（默认上拉）
+    fn set_data_pull(&mut self, pull: Pull) {
+        for pin in self.data.iter_mut() {
+            pin.set_bias(pull.bias());
+        }
+    }
+
+    /// 设置时钟引脚所在组的驱动能力
+    fn set_clock_drive(&self, drive: Drive) -> anyhow::Result<()> {
+        gpio::set_drive(self.clock.pin(), drive)
+    }
+
+    /// 设置速率（未接RATE引脚时只记录，需与硬件接线一致）
+    fn set_rate(&mut self, rate: Rate) {
+        if let Some(pin) = &mut self.rate_pin {
+            apply_rate(pin, rate);
+            if rate != self.rate {
+                // 等待滤波器建立
+                thread::sleep(rate.settling_time());
+            }
+        }
+        self.rate = rate;
+    }
+
+    /// 所有芯片的数据是否都已就绪（DOUT为低电平）
+    fn is_ready(&self) -> bool {
+        self.data.iter().all(|pin| pin.is_low())
+    }
+
+    /// 等待所有芯片的数据就绪
+    ///
+    /// 上一次读取后约一个转换周期内数据不会就绪，先睡眠到预计就绪前，之后按转换周期的1/50轮询，
+    /// 10SPS时每次读取只唤醒几次（原来每500µs唤醒一次）
+    fn wait_ready(&mut self) -> anyhow::Result<()> {
+        let period = self.rate.period();
+        if let Some(last_read) = self.last_read {
+            let expected = last_read + period - period / 10;
+            let now = Instant::now();
+            if expected > now && !self.is_ready() {
+                thread::sleep(expected - now);
+            }
+        }
+        // 最长等待两个转换周期
+        let timeout = match self.rate {
+            Rate::Sps10 => Duration::from_millis(250),
+            Rate::Sps80 => Duration::from_millis(40),
+        };
+        let poll = (period / 50).max(Duration::from_micros(500));
+        let start = Instant::now();
+        while !self.is_ready() {
+            self.record_data();
+            if start.elapsed() > timeout {
+                self.capture.finish();
+                trace_event!(
+                    warn,
+                    chips = self.data.len(),
+                    "HX711等待数据就绪超时，检查DOUT接线和供电"
+                );
+                return Err(anyhow::anyhow!("HX711等待数据就绪超时"));
+            }
+            thread::sleep(poll);
+        }
+        trace_event!(
+            trace,
+            wait_us = start.elapsed().as_micros() as u64,
+            "HX711数据就绪"
+        );
+        Ok(())
+    }
+
+    /// 同步读取所有芯片
+    fn read(&mut self) -> anyhow::Result<Vec<i32>> {
+        self.capture.begin();
+        self.record_data();
+        self.wait_ready()?;
+        self.record_data();
+
+        let mut values = vec![0i32; self.data.len()];
+        for _ in 0..24 {
+            clock_pulse(&mut self.clock, &self.data, &mut values, &self.capture);
+        }
+        // 额外的脉冲用于选择下一次转换的通道和增益
+        let mut discard = vec![0i32; self.data.len()];
+        for _ in 0..self.gain.extra_pulses() {
+            clock_pulse(&mut self.clock, &self.data, &mut discard, &self.capture);
+        }
+        self.capture.finish();
+        self.last_read = Some(Instant::now());
+        // OK
+        Ok(values.into_iter().map(sign_extend).collect())
+    }
+
+    /// 记录时钟和数据引脚的当前电平（等待数据就绪期间DOUT的下降沿）
+    fn record_data(&self) {
+        if !self.capture.is_enabled() {
+            return;
+        }
+        self.capture.record(0, self.clock.is_set_high());
+        for (i, pin) in self.data.iter().enumerate() {
+            self.capture.record(i + 1, pin.is_high());
+        }
+    }
+
+    /// 掉电（时钟保持高电平超过60µs）
+    fn power_down(&mut self) {
+        self.clock.set_low();
+        self.clock.set_high();
+        thread::sleep(Duration::from_micros(100));
+    }
+
+    /// 上电（上电后默认为A通道增益128，需重新读取一次以设置增益）
+    fn power_up(&mut self) {
+        self.clock.set_low();
+    }
+}
+
+/// HX711称重ADC封装对象（软件模拟时序）
+///
+/// 相比sensor-hal中的驱动增加了RATE引脚速率切换，并实现了`WeightAdc`
+pub struct HX711 {
+    bus: Hx711Bus,
+    /// 读取频率限制
+    limit: RateLimit,
+}
+
+impl HX711 {
+    /// 创建实例
+    ///
+    /// - clock_pin: 时钟引脚（PD_SCK）
+    /// - data_pin: 数据引脚（DOUT）
+    /// - gain: 通道和增益
+    /// - rate_pin: 速率引脚（RATE，模块上固定接地时传None）
+    pub fn new(
+        clock_pin: u8,
+        data_pin: u8,
+        gain: ChannelGain,
+        rate_pin: Option<u8>,
+    ) -> anyhow::Result<Self> {
+        let mut this = Self {
+            bus: Hx711Bus::new(clock_pin, &[data_pin], gain, rate_pin)?,
+            limit: RateLimit::new(Rate::Sps10.period(), Policy::Wait),
+        };
+        // 读取一次，使通道和增益设置生效
+        this.read()?;
+        // OK
+        Ok(this)
+    }
+
+    /// 设置输出数据速率（读取频率限制的间隔同时改为该速率的转换周期）
+    pub fn set_rate(&mut self, rate: Rate) {
+        self.bus.set_rate(rate);
+        self.limit.set_min_interval(rate.period());
+    }
+
+    /// 当前输出数据速率
+    pub fn rate(&self) -> Rate {
+        self.bus.rate
+    }
+
+    /// 设置数据引脚的内部上下拉（默认上拉；模块输出为推挽，长线缆受干扰时可以改为浮空并外接电阻）
+    pub fn set_data_pull(&mut self, pull: Pull) {
+        self.bus.set_data_pull(pull);
+    }
+
+    /// 设置时钟引脚的驱动能力（长线缆上限制边沿速率减少振铃，影响同组所有引脚）
+    pub fn set_clock_drive(&self, drive: Drive) -> anyhow::Result<()> {
+        self.bus.set_clock_drive(drive)
+    }
+
+    /// 读取频率限制
+    pub fn rate_limit(&self) -> &RateLimit {
+        &self.limit
+    }
+
+    /// 设置读取频率限制（默认间隔为一个转换周期，间隔不足时等待）
+    pub fn set_rate_limit(&mut self, limit: RateLimit) {
+        self.limit = limit;
+    }
+
+    /// 数据是否就绪
+    pub fn is_ready(&self) -> bool {
+        self.bus.is_ready()
+    }
+
+    /// 读取一次ADC读数（阻塞直到数据就绪）
+    pub fn read(&mut self) -> anyhow::Result<i32> {
+        self.limit.acquire()?;
+        Ok(self.bus.read()?[0])
+    }
+
+    /// 时钟和数据引脚的信号捕获（信号为pd_sck、dout）
+    pub fn signal_capture(&self) -> SignalCapture {
+        self.bus.capture.clone()
+    }
+
+    /// 连续读取多个读数并统计噪声特性（秤台空载、静止时测量）
+    ///
+    /// 按当前速率读取，10SPS时100个读数约需10秒
+    pub fn characterize(&mut self, samples: usize) -> anyhow::Result<NoiseReport> {
+        let values = (0..samples)
+            .map(|_| self.read())
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let report = NoiseReport::from_samples(&values, self.rate())?;
+        trace_event!(
+            debug,
+            std_dev = report.std_dev,
+            effective_bits = report.effective_bits,
+            "HX711噪声测量完成"
+        );
+        // OK
+        Ok(report)
+    }
+
+    /// 设置数据就绪回调（DOUT下降沿触发，读取数据期间的跳变也会触发）
+    #[cfg(feature = "async")]
+    pub(crate) fn set_ready_callback<C>(&mut self, callback: C) -> anyhow::Result<()>
+    where
+        C: FnMut(rppal::gpio::Event) + Send + 'static,
+    {
+        self.bus.data[0].set_async_interrupt(rppal::gpio::Trigger::FallingEdge, None, callback)?;
+        Ok(())
+    }
+
+    /// 掉电
+    pub fn power_down(&mut self) {
+        self.bus.power_down();
+    }
+
+    /// 上电
+    pub fn power_up(&mut self) -> anyhow::Result<()> {
+        self.bus.power_up();
+        self.read()?;
+        Ok(())
+    }
+}
+
+impl WeightAdc for HX711 {
+    fn read_raw(&mut self) -> anyhow::Result<i32> {
+        self.read()
+    }
+}
+
+/// 共享时钟线的多片HX711同步采样
+///
+/// 四角称重平台等场景需要多个传感器在同一时刻采样，
+/// 所有芯片使用同一根时钟线，每个时钟脉冲同时读取所有数据引脚
+pub struct Hx711Array {
+    bus: Hx711Bus,
+}
+
+impl Hx711Array {
+    /// 创建实例
+    ///
+    /// - clock_pin: 共享的时钟引脚（PD_SCK）
+    /// - data_pins: 各芯片的数据引脚（DOUT）
+    /// - gain: 通道和增益（所有芯片相同）
+    /// - rate_pin: 共享的速率引脚（RATE，固定接地时传None）
+    pub fn new(
+        clock_pin: u8,
+        data_pins: &[u8],
+        gain: ChannelGain,
+        rate_pin: Option<u8>,
+    ) -> anyhow::Result<Self> {
+        if data_pins.is_empty() {
+            return Err(anyhow::anyhow!("HX711数据引脚列表不能为空"));
+        }
+        let mut this = Self {
+            bus: Hx711Bus::new(clock_pin, data_pins, gain, rate_pin)?,
+        };
+        // 读取一次，使通道和增益设置生效，同时对齐各芯片的转换周期
+        this.read()?;
+        // OK
+        Ok(this)
+    }
+
+    /// 芯片数量
+    pub fn len(&self) -> usize {
+        self.bus.data.len()
+    }
+
+    /// 是否没有芯片（创建时已检查，始终为false）
+    pub fn is_empty(&self) -> bool {
+        self.bus.data.is_empty()
+    }
+
+    /// 设置输出数据速率
+    pub fn set_rate(&mut self, rate: Rate) {
+        self.bus.set_rate(rate);
+    }
+
+    /// 设置所有数据引脚的内部上下拉（默认上拉）
+    pub fn set_data_pull(&mut self, pull: Pull) {
+        self.bus.set_data_pull(pull);
+    }
+
+    /// 设置共享时钟引脚的驱动能力（影响同组所有引脚）
+    pub fn set_clock_drive(&self, drive: Drive) -> anyhow::Result<()> {
+        self.bus.set_clock_drive(drive)
+    }
+
+    /// 同步读取所有芯片（顺序与data_pins一致）
+    pub fn read(&mut self) -> anyhow::Result<Vec<i32>> {
+        self.bus.read()
+    }
+
+    /// 时钟和数据引脚的信号捕获（信号为pd_sck、dout0、dout1……）
+    pub fn signal_capture(&self) -> SignalCapture {
+        self.bus.capture.clone()
+    }
+
+    /// 同步读取并求和（多个称重传感器组成一台秤）
+    pub fn read_sum(&mut self) -> anyhow::Result<i32> {
+        Ok(self.read()?.iter().sum())
+    }
+
+    /// 掉电
+    pub fn power_down(&mut self) {
+        self.bus.power_down();
+    }
+
+    /// 上电
+    pub fn power_up(&mut self) -> anyhow::Result<()> {
+        self.bus.power_up();
+        self.read()?;
+        Ok(())
+    }
+}
+
+/// 多个传感器的读数之和作为整台秤的读数
+impl WeightAdc for Hx711Array {
+    fn read_raw(&mut self) -> anyhow::Result<i32> {
+        self.read_sum()
+    }
+}
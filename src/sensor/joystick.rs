@@ -0,0 +1,225 @@
+use std::thread;
+use std::time::Duration;
+
+use crate::analog::AnalogIn;
+use crate::sensor::button::Button;
+
+/// 摇杆方向
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JoystickDirection {
+    Center,
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+/// 摇杆状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JoystickState {
+    /// X轴（-1.0~1.0，正数为右）
+    pub x: f64,
+    /// Y轴（-1.0~1.0，正数为上）
+    pub y: f64,
+    /// 按键是否按下
+    pub pressed: bool,
+}
+
+impl JoystickState {
+    /// 判断方向（任一轴超过0.5视为偏向该方向）
+    pub fn direction(&self) -> JoystickDirection {
+        let horizontal = if self.x > 0.5 {
+            1
+        } else if self.x < -0.5 {
+            -1
+        } else {
+            0
+        };
+        let vertical = if self.y > 0.5 {
+            1
+        } else if self.y < -0.5 {
+            -1
+        } else {
+            0
+        };
+        match (horizontal, vertical) {
+            (0, 1) => JoystickDirection::Up,
+            (0, -1) => JoystickDirection::Down,
+            (-1, 0) => JoystickDirection::Left,
+            (1, 0) => JoystickDirection::Right,
+            (-1, 1) => JoystickDirection::UpLeft,
+            (1, 1) => JoystickDirection::UpRight,
+            (-1, -1) => JoystickDirection::DownLeft,
+            (1, -1) => JoystickDirection::DownRight,
+            _ => JoystickDirection::Center,
+        }
+    }
+}
+
+/// 摇杆事件
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JoystickEvent {
+    /// 方向变化
+    Direction(JoystickDirection),
+    /// 按键按下(True)/松开(False)
+    Button(bool),
+}
+
+/// 单轴校准参数
+struct Axis {
+    /// ADC通道
+    input: Box<dyn AnalogIn>,
+    /// 中心位置原始值
+    center: f64,
+    /// 是否反向
+    inverted: bool,
+}
+
+impl Axis {
+    /// 读取原始值
+    fn read_raw(&mut self) -> anyhow::Result<f64> {
+        Ok(self.input.read_raw()? as f64)
+    }
+
+    /// 读取归一化值（-1.0~1.0，已处理死区）
+    fn read(&mut self, dead_zone: f64) -> anyhow::Result<f64> {
+        let raw = self.read_raw()?;
+        let max = self.input.max_raw() as f64;
+        // 中心两侧分别归一化，中心点不在正中间时两侧也能达到满量程
+        let value = if raw >= self.center {
+            (raw - self.center) / (max - self.center).max(1.0)
+        } else {
+            (raw - self.center) / self.center.max(1.0)
+        };
+        let value = value.clamp(-1.0, 1.0);
+
+        // 死区内视为0，死区外重新映射到0~1，避免输出跳变
+        let value = if value.abs() <= dead_zone {
+            0.0
+        } else {
+            value.signum() * (value.abs() - dead_zone) / (1.0 - dead_zone)
+        };
+        Ok(if self.inverted { -value } else { value })
+    }
+}
+
+/// 双轴模拟摇杆封装对象（如PS2摇杆模块）
+pub struct Joystick {
+    /// X轴
+    x: Axis,
+    /// Y轴
+    y: Axis,
+    /// 按键（未接线时为None）
+    button: Option<Button>,
+    /// 死区（0.0~1.0）
+    dead_zone: f64,
+}
+
+impl Joystick {
+    /// 创建摇杆实例
+    ///
+    /// - x、y: X轴、Y轴的ADC通道
+    /// - button_pin: 按键引脚（未接线时传None）
+    pub fn new<X, Y>(x: X, y: Y, button_pin: Option<u8>) -> anyhow::Result<Self>
+    where
+        X: AnalogIn + 'static,
+        Y: AnalogIn + 'static,
+    {
+        let button = match button_pin {
+            Some(pin) => Some(Button::new(pin)?),
+            None => None,
+        };
+        let x_center = x.max_raw() as f64 / 2.0;
+        let y_center = y.max_raw() as f64 / 2.0;
+        // OK
+        Ok(Self {
+            x: Axis {
+                input: Box::new(x),
+                center: x_center,
+                inverted: false,
+            },
+            y: Axis {
+                input: Box::new(y),
+                center: y_center,
+                inverted: false,
+            },
+            button,
+            dead_zone: 0.1,
+        })
+    }
+
+    /// 校准中心位置（校准时不要触碰摇杆）
+    ///
+    /// - samples: 采样次数
+    pub fn calibrate_center(&mut self, samples: usize) -> anyhow::Result<()> {
+        let samples = samples.max(1);
+        let (mut x_sum, mut y_sum) = (0.0, 0.0);
+        for _ in 0..samples {
+            x_sum += self.x.read_raw()?;
+            y_sum += self.y.read_raw()?;
+            thread::sleep(Duration::from_millis(2));
+        }
+        self.x.center = x_sum / samples as f64;
+        self.y.center = y_sum / samples as f64;
+        Ok(())
+    }
+
+    /// 设置死区（0.0~0.9，默认0.1）
+    pub fn set_dead_zone(&mut self, dead_zone: f64) {
+        self.dead_zone = dead_zone.clamp(0.0, 0.9);
+    }
+
+    /// 设置轴是否反向（摇杆安装方向不同时使用）
+    pub fn set_inverted(&mut self, x_inverted: bool, y_inverted: bool) {
+        self.x.inverted = x_inverted;
+        self.y.inverted = y_inverted;
+    }
+
+    /// 读取摇杆状态
+    pub fn read(&mut self) -> anyhow::Result<JoystickState> {
+        let x = self.x.read(self.dead_zone)?;
+        let y = self.y.read(self.dead_zone)?;
+        let pressed = self.button.as_mut().is_some_and(|button| button.read());
+        // OK
+        Ok(JoystickState { x, y, pressed })
+    }
+
+    /// 监听方向和按键事件
+    ///
+    /// - 在独立线程中每20毫秒读取一次，状态变化时回调
+    pub fn on_event<F>(mut self, mut cb: F) -> thread::JoinHandle<()>
+    where
+        F: FnMut(JoystickEvent, &JoystickState) + Send + 'static,
+    {
+        thread::spawn(move || {
+            let mut last_direction = JoystickDirection::Center;
+            let mut last_pressed = false;
+            loop {
+                match self.read() {
+                    Ok(state) => {
+                        let direction = state.direction();
+                        if direction != last_direction {
+                            last_direction = direction;
+                            cb(JoystickEvent::Direction(direction), &state);
+                        }
+                        if state.pressed != last_pressed {
+                            last_pressed = state.pressed;
+                            cb(JoystickEvent::Button(state.pressed), &state);
+                        }
+                    }
+                    Err(err) => {
+                        eprintln!("读取摇杆失败: {}", err);
+                    }
+                }
+                thread::sleep(Duration::from_millis(20));
+            }
+        })
+    }
+}
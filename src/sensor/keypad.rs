@@ -0,0 +1,183 @@
+use rppal::gpio::{Gpio, InputPin, OutputPin};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 常见4×4薄膜键盘的按键布局
+pub const KEYMAP_4X4: [&str; 4] = ["123A", "456B", "789C", "*0#D"];
+
+/// 常见4×3薄膜键盘的按键布局
+pub const KEYMAP_4X3: [&str; 4] = ["123", "456", "789", "*0#"];
+
+/// 按键事件
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeyEvent {
+    /// 按下
+    Pressed(char),
+    /// 松开
+    Released(char),
+}
+
+/// 矩阵键盘封装对象
+///
+/// - 行引脚为输出，逐行拉低扫描
+/// - 列引脚为上拉输入，读到低电平表示该行该列的按键被按下
+/// - 支持多键同时按下；没有二极管的键盘在3个键构成矩形时会出现"鬼键"，
+///   此时无法确定的按键保持原状态，不产生事件
+pub struct Keypad {
+    /// 行引脚
+    rows: Vec<OutputPin>,
+    /// 列引脚
+    cols: Vec<InputPin>,
+    /// 按键布局
+    keymap: Vec<Vec<char>>,
+    /// 消抖时间
+    debounce: Duration,
+    /// 消抖后的按键状态
+    stable: Vec<Vec<bool>>,
+    /// 原始状态与消抖状态不一致的起始时间
+    changing_since: Vec<Vec<Option<Instant>>>,
+}
+
+impl Keypad {
+    /// 创建矩阵键盘实例
+    ///
+    /// - row_pins: 行引脚
+    /// - col_pins: 列引脚
+    /// - keymap: 按键布局，每个字符串为一行，如`KEYMAP_4X4`
+    pub fn new(row_pins: &[u8], col_pins: &[u8], keymap: &[&str]) -> anyhow::Result<Self> {
+        let keymap: Vec<Vec<char>> = keymap.iter().map(|row| row.chars().collect()).collect();
+        if keymap.len() != row_pins.len() || keymap.iter().any(|row| row.len() != col_pins.len()) {
+            return Err(anyhow::anyhow!(
+                "键盘布局与引脚数量不匹配, 行: {}, 列: {}",
+                row_pins.len(),
+                col_pins.len()
+            ));
+        }
+
+        let gpio = Gpio::new()?;
+        let mut rows = Vec::with_capacity(row_pins.len());
+        for &pin in row_pins {
+            // 空闲时输出高电平
+            rows.push(gpio.get(pin)?.into_output_high());
+        }
+        let mut cols = Vec::with_capacity(col_pins.len());
+        for &pin in col_pins {
+            cols.push(gpio.get(pin)?.into_input_pullup());
+        }
+
+        // OK
+        Ok(Self {
+            rows,
+            cols,
+            keymap,
+            debounce: Duration::from_millis(20),
+            stable: vec![vec![false; col_pins.len()]; row_pins.len()],
+            changing_since: vec![vec![None; col_pins.len()]; row_pins.len()],
+        })
+    }
+
+    /// 设置消抖时间（默认20毫秒）
+    pub fn set_debounce(&mut self, debounce: Duration) {
+        self.debounce = debounce;
+    }
+
+    /// 扫描一次，返回原始按键状态（未消抖）
+    pub fn scan_raw(&mut self) -> Vec<Vec<bool>> {
+        let mut matrix = vec![vec![false; self.cols.len()]; self.rows.len()];
+        for (r, row) in self.rows.iter_mut().enumerate() {
+            row.set_low();
+            // 等待电平稳定
+            thread::sleep(Duration::from_micros(10));
+            for (c, col) in self.cols.iter().enumerate() {
+                matrix[r][c] = col.is_low();
+            }
+            row.set_high();
+        }
+        matrix
+    }
+
+    /// 找出可能是鬼键的按键（与另外3个按下的键构成矩形）
+    fn ambiguous_keys(matrix: &[Vec<bool>]) -> Vec<Vec<bool>> {
+        let rows = matrix.len();
+        let cols = matrix.first().map_or(0, Vec::len);
+        let mut ambiguous = vec![vec![false; cols]; rows];
+        for r1 in 0..rows {
+            for r2 in (r1 + 1)..rows {
+                // 两行中同时按下的列
+                let shared: Vec<usize> = (0..cols)
+                    .filter(|&c| matrix[r1][c] && matrix[r2][c])
+                    .collect();
+                if shared.len() >= 2 {
+                    for &c in &shared {
+                        ambiguous[r1][c] = true;
+                        ambiguous[r2][c] = true;
+                    }
+                }
+            }
+        }
+        ambiguous
+    }
+
+    /// 扫描一次并消抖，返回状态变化的按键事件
+    pub fn poll(&mut self) -> Vec<KeyEvent> {
+        let raw = self.scan_raw();
+        let ambiguous = Self::ambiguous_keys(&raw);
+        let now = Instant::now();
+
+        let mut events = Vec::new();
+        for (r, row) in raw.iter().enumerate() {
+            for (c, &pressed) in row.iter().enumerate() {
+                if ambiguous[r][c] || pressed == self.stable[r][c] {
+                    self.changing_since[r][c] = None;
+                    continue;
+                }
+                // 状态持续不一致超过消抖时间才确认变化
+                let since = *self.changing_since[r][c].get_or_insert(now);
+                if now.duration_since(since) >= self.debounce {
+                    self.stable[r][c] = pressed;
+                    self.changing_since[r][c] = None;
+                    let key = self.keymap[r][c];
+                    events.push(if pressed {
+                        KeyEvent::Pressed(key)
+                    } else {
+                        KeyEvent::Released(key)
+                    });
+                }
+            }
+        }
+        events
+    }
+
+    /// 当前按下的按键（消抖后）
+    pub fn pressed_keys(&self) -> Vec<char> {
+        self.stable
+            .iter()
+            .zip(&self.keymap)
+            .flat_map(|(states, keys)| {
+                states
+                    .iter()
+                    .zip(keys)
+                    .filter(|(pressed, _)| **pressed)
+                    .map(|(_, key)| *key)
+            })
+            .collect()
+    }
+
+    /// 监听按键事件
+    ///
+    /// - 在独立线程中每5毫秒扫描一次
+    pub fn on_key<F>(mut self, mut cb: F) -> thread::JoinHandle<()>
+    where
+        F: FnMut(KeyEvent) + Send + 'static,
+    {
+        thread::spawn(move || {
+            loop {
+                for event in self.poll() {
+                    cb(event);
+                }
+                thread::sleep(Duration::from_millis(5));
+            }
+        })
+    }
+}
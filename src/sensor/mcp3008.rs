@@ -0,0 +1,88 @@
+use rppal::spi::{Bus, Mode, SlaveSelect};
+use std::sync::{Arc, Mutex};
+
+use crate::analog::AnalogIn;
+use crate::spi_bus::SpiDeviceHandle;
+
+/// 通道数量
+const CHANNEL_COUNT: u8 = 8;
+
+/// MCP3008 8通道10位ADC封装对象（SPI）
+///
+/// 树莓派没有模拟输入，摇杆、电位器、光敏电阻等需要经过ADC转换
+pub struct MCP3008 {
+    /// SPI设备（各通道共享）
+    spi: Arc<Mutex<SpiDeviceHandle>>,
+}
+
+impl MCP3008 {
+    /// 创建ADC实例
+    ///
+    /// - bus: SPI总线
+    /// - slave_select: 片选
+    pub fn new(bus: Bus, slave_select: SlaveSelect) -> anyhow::Result<Self> {
+        // 3.3V供电时最高1.35MHz
+        let spi = SpiDeviceHandle::hardware(bus, slave_select, 1_000_000, Mode::Mode0)?;
+        Ok(Self::with_device(spi))
+    }
+
+    /// 使用共享SPI总线上的设备创建ADC实例
+    ///
+    /// - spi: SPI设备（通过`SpiBus::device`创建，建议1MHz、Mode0）
+    pub fn with_device(spi: SpiDeviceHandle) -> Self {
+        Self {
+            spi: Arc::new(Mutex::new(spi)),
+        }
+    }
+
+    /// 读取指定通道（0~7）的单端输入
+    pub fn read(&self, channel: u8) -> anyhow::Result<u16> {
+        read_channel(&self.spi, channel)
+    }
+
+    /// 获取单个通道对象，可以传给基于`AnalogIn`的传感器使用
+    pub fn channel(&self, channel: u8) -> anyhow::Result<AdcChannel> {
+        if channel >= CHANNEL_COUNT {
+            return Err(anyhow::anyhow!("MCP3008通道超出范围: {}", channel));
+        }
+        // OK
+        Ok(AdcChannel {
+            spi: self.spi.clone(),
+            channel,
+        })
+    }
+}
+
+/// 读取单端输入
+fn read_channel(spi: &Mutex<SpiDeviceHandle>, channel: u8) -> anyhow::Result<u16> {
+    if channel >= CHANNEL_COUNT {
+        return Err(anyhow::anyhow!("MCP3008通道超出范围: {}", channel));
+    }
+
+    // 起始位、单端模式+通道号、占位
+    let write = [0x01, 0x80 | (channel << 4), 0x00];
+    let mut read = [0u8; 3];
+    spi.lock()
+        .map_err(|_| anyhow::anyhow!("SPI通信总线繁忙"))?
+        .transfer(&mut read, &write)?;
+    // 结果为第2字节的低2位和第3字节
+    Ok((((read[1] & 0x03) as u16) << 8) | read[2] as u16)
+}
+
+/// MCP3008单个通道
+pub struct AdcChannel {
+    /// SPI设备
+    spi: Arc<Mutex<SpiDeviceHandle>>,
+    /// 通道号
+    channel: u8,
+}
+
+impl AnalogIn for AdcChannel {
+    fn read_raw(&mut self) -> anyhow::Result<u32> {
+        Ok(read_channel(&self.spi, self.channel)? as u32)
+    }
+
+    fn max_raw(&self) -> u32 {
+        1023
+    }
+}
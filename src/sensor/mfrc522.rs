@@ -0,0 +1,452 @@
+use rppal::gpio::{Gpio, OutputPin};
+use rppal::spi::{Bus, Mode, SlaveSelect};
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::spi_bus::SpiDeviceHandle;
+
+/// MFRC522寄存器地址
+mod reg {
+    pub const COMMAND: u8 = 0x01;
+    pub const COM_I_EN: u8 = 0x02;
+    pub const COM_IRQ: u8 = 0x04;
+    pub const DIV_IRQ: u8 = 0x05;
+    pub const ERROR: u8 = 0x06;
+    pub const STATUS2: u8 = 0x08;
+    pub const FIFO_DATA: u8 = 0x09;
+    pub const FIFO_LEVEL: u8 = 0x0A;
+    pub const CONTROL: u8 = 0x0C;
+    pub const BIT_FRAMING: u8 = 0x0D;
+    pub const COLL: u8 = 0x0E;
+    pub const MODE: u8 = 0x11;
+    pub const TX_CONTROL: u8 = 0x14;
+    pub const TX_ASK: u8 = 0x15;
+    pub const CRC_RESULT_H: u8 = 0x21;
+    pub const CRC_RESULT_L: u8 = 0x22;
+    pub const T_MODE: u8 = 0x2A;
+    pub const T_PRESCALER: u8 = 0x2B;
+    pub const T_RELOAD_H: u8 = 0x2C;
+    pub const T_RELOAD_L: u8 = 0x2D;
+    pub const VERSION: u8 = 0x37;
+}
+
+/// MFRC522芯片命令
+mod pcd {
+    pub const IDLE: u8 = 0x00;
+    pub const CALC_CRC: u8 = 0x03;
+    pub const TRANSCEIVE: u8 = 0x0C;
+    pub const MF_AUTHENT: u8 = 0x0E;
+    pub const SOFT_RESET: u8 = 0x0F;
+}
+
+/// 卡片(PICC)命令
+mod picc {
+    pub const REQA: u8 = 0x26;
+    pub const SEL_CL1: u8 = 0x93;
+    pub const SEL_CL2: u8 = 0x95;
+    pub const SEL_CL3: u8 = 0x97;
+    pub const CASCADE_TAG: u8 = 0x88;
+    pub const HLTA: u8 = 0x50;
+    pub const MF_READ: u8 = 0x30;
+    pub const MF_WRITE: u8 = 0xA0;
+    pub const MF_ACK: u8 = 0x0A;
+}
+
+/// MIFARE Classic认证密钥类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyType {
+    /// A密钥
+    KeyA = 0x60,
+    /// B密钥
+    KeyB = 0x61,
+}
+
+/// MIFARE Classic出厂默认密钥
+pub const DEFAULT_KEY: [u8; 6] = [0xFF; 6];
+
+/// 卡片唯一标识
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Uid {
+    /// UID字节（4、7或10字节）
+    pub bytes: Vec<u8>,
+    /// 选卡应答（SAK）
+    pub sak: u8,
+}
+
+impl fmt::Display for Uid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, byte) in self.bytes.iter().enumerate() {
+            if i > 0 {
+                write!(f, ":")?;
+            }
+            write!(f, "{:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// RC522 RFID/NFC读卡器封装对象（SPI）
+pub struct MFRC522 {
+    /// SPI设备
+    spi: SpiDeviceHandle,
+    /// 复位引脚（可选）
+    #[allow(unused)]
+    rst: Option<OutputPin>,
+}
+
+impl MFRC522 {
+    /// 创建读卡器实例
+    ///
+    /// - bus: SPI总线
+    /// - slave_select: 片选
+    /// - rst_pin: 复位引脚（未接线时传None）
+    pub fn new(bus: Bus, slave_select: SlaveSelect, rst_pin: Option<u8>) -> anyhow::Result<Self> {
+        // RC522最高支持10MHz，这里使用1MHz保证杜邦线接线也能稳定通信
+        let spi = SpiDeviceHandle::hardware(bus, slave_select, 1_000_000, Mode::Mode0)?;
+        Self::with_device(spi, rst_pin)
+    }
+
+    /// 使用共享SPI总线上的设备创建读卡器实例
+    ///
+    /// - spi: SPI设备（通过`SpiBus::device`创建，建议1MHz、Mode0）
+    /// - rst_pin: 复位引脚（未接线时传None）
+    pub fn with_device(spi: SpiDeviceHandle, rst_pin: Option<u8>) -> anyhow::Result<Self> {
+        // 拉高复位引脚，使芯片退出掉电模式
+        let rst = match rst_pin {
+            Some(pin) => {
+                let mut rst = Gpio::new()?.get(pin)?.into_output_low();
+                thread::sleep(Duration::from_millis(1));
+                rst.set_high();
+                thread::sleep(Duration::from_millis(50));
+                Some(rst)
+            }
+            None => None,
+        };
+
+        let mut this = Self { spi, rst };
+        this.init()?;
+        // OK
+        Ok(this)
+    }
+
+    /// 初始化芯片
+    fn init(&mut self) -> anyhow::Result<()> {
+        // 软复位
+        self.write_reg(reg::COMMAND, pcd::SOFT_RESET)?;
+        thread::sleep(Duration::from_millis(50));
+
+        // 定时器：TPrescaler*TReload/13.56MHz = 25ms超时
+        self.write_reg(reg::T_MODE, 0x8D)?;
+        self.write_reg(reg::T_PRESCALER, 0x3E)?;
+        self.write_reg(reg::T_RELOAD_L, 30)?;
+        self.write_reg(reg::T_RELOAD_H, 0)?;
+        // 100% ASK调制
+        self.write_reg(reg::TX_ASK, 0x40)?;
+        // CRC初始值0x6363
+        self.write_reg(reg::MODE, 0x3D)?;
+
+        // 打开天线
+        self.set_bits(reg::TX_CONTROL, 0x03)?;
+        // OK
+        Ok(())
+    }
+
+    /// 读取芯片版本号（0x91或0x92为正版芯片）
+    pub fn version(&mut self) -> anyhow::Result<u8> {
+        self.read_reg(reg::VERSION)
+    }
+
+    /// 读寄存器
+    fn read_reg(&mut self, reg: u8) -> anyhow::Result<u8> {
+        let write = [((reg << 1) & 0x7E) | 0x80, 0];
+        let mut read = [0u8; 2];
+        self.spi.transfer(&mut read, &write)?;
+        Ok(read[1])
+    }
+
+    /// 写寄存器
+    fn write_reg(&mut self, reg: u8, value: u8) -> anyhow::Result<()> {
+        self.spi.write(&[(reg << 1) & 0x7E, value])?;
+        Ok(())
+    }
+
+    /// 寄存器置位
+    fn set_bits(&mut self, reg: u8, mask: u8) -> anyhow::Result<()> {
+        let value = self.read_reg(reg)?;
+        self.write_reg(reg, value | mask)
+    }
+
+    /// 寄存器清位
+    fn clear_bits(&mut self, reg: u8, mask: u8) -> anyhow::Result<()> {
+        let value = self.read_reg(reg)?;
+        self.write_reg(reg, value & !mask)
+    }
+
+    /// 使用芯片协处理器计算CRC_A
+    fn calculate_crc(&mut self, data: &[u8]) -> anyhow::Result<[u8; 2]> {
+        self.write_reg(reg::COMMAND, pcd::IDLE)?;
+        self.clear_bits(reg::DIV_IRQ, 0x04)?;
+        // 清空FIFO
+        self.set_bits(reg::FIFO_LEVEL, 0x80)?;
+        for &byte in data {
+            self.write_reg(reg::FIFO_DATA, byte)?;
+        }
+        self.write_reg(reg::COMMAND, pcd::CALC_CRC)?;
+
+        // 等待计算完成
+        let start = Instant::now();
+        loop {
+            if self.read_reg(reg::DIV_IRQ)? & 0x04 != 0 {
+                break;
+            }
+            if start.elapsed() > Duration::from_millis(90) {
+                return Err(anyhow::anyhow!("RC522计算CRC超时"));
+            }
+        }
+        self.write_reg(reg::COMMAND, pcd::IDLE)?;
+
+        // OK
+        Ok([
+            self.read_reg(reg::CRC_RESULT_L)?,
+            self.read_reg(reg::CRC_RESULT_H)?,
+        ])
+    }
+
+    /// 与卡片通信
+    ///
+    /// 返回卡片应答数据以及最后一个字节的有效位数
+    fn communicate(&mut self, command: u8, data: &[u8]) -> anyhow::Result<(Vec<u8>, u8)> {
+        // 认证命令只等待空闲中断，收发命令等待接收/空闲中断
+        let (irq_en, wait_irq) = match command {
+            pcd::MF_AUTHENT => (0x12, 0x10),
+            _ => (0x77, 0x30),
+        };
+
+        self.write_reg(reg::COM_I_EN, irq_en | 0x80)?;
+        self.clear_bits(reg::COM_IRQ, 0x80)?;
+        self.set_bits(reg::FIFO_LEVEL, 0x80)?;
+        self.write_reg(reg::COMMAND, pcd::IDLE)?;
+
+        // 写入FIFO并执行命令
+        for &byte in data {
+            self.write_reg(reg::FIFO_DATA, byte)?;
+        }
+        self.write_reg(reg::COMMAND, command)?;
+        if command == pcd::TRANSCEIVE {
+            // StartSend
+            self.set_bits(reg::BIT_FRAMING, 0x80)?;
+        }
+
+        // 等待命令完成（芯片定时器25ms超时，这里多留一些余量）
+        let start = Instant::now();
+        let irq = loop {
+            let irq = self.read_reg(reg::COM_IRQ)?;
+            if irq & wait_irq != 0 {
+                break irq;
+            }
+            if irq & 0x01 != 0 || start.elapsed() > Duration::from_millis(40) {
+                self.clear_bits(reg::BIT_FRAMING, 0x80)?;
+                return Err(anyhow::anyhow!("RC522等待卡片应答超时"));
+            }
+        };
+        self.clear_bits(reg::BIT_FRAMING, 0x80)?;
+
+        // 检查错误寄存器（BufferOvfl、ParityErr、ProtocolErr）
+        let error = self.read_reg(reg::ERROR)?;
+        if error & 0x13 != 0 {
+            return Err(anyhow::anyhow!("RC522通信错误: 0x{:02X}", error));
+        }
+        if irq & irq_en & 0x01 != 0 {
+            return Err(anyhow::anyhow!("RC522未检测到卡片"));
+        }
+
+        // 读取应答数据
+        let mut response = Vec::new();
+        let mut valid_bits = 0;
+        if command == pcd::TRANSCEIVE {
+            let len = self.read_reg(reg::FIFO_LEVEL)? as usize;
+            for _ in 0..len {
+                response.push(self.read_reg(reg::FIFO_DATA)?);
+            }
+            valid_bits = self.read_reg(reg::CONTROL)? & 0x07;
+        }
+        // OK
+        Ok((response, valid_bits))
+    }
+
+    /// 发送数据并附加CRC
+    fn transceive_with_crc(&mut self, data: &[u8]) -> anyhow::Result<(Vec<u8>, u8)> {
+        let crc = self.calculate_crc(data)?;
+        let mut buffer = data.to_vec();
+        buffer.extend_from_slice(&crc);
+        self.communicate(pcd::TRANSCEIVE, &buffer)
+    }
+
+    /// 检测是否有卡片进入感应区（REQA）
+    pub fn is_card_present(&mut self) -> bool {
+        // REQA是7位短帧
+        if self.write_reg(reg::BIT_FRAMING, 0x07).is_err() {
+            return false;
+        }
+        // ATQA应答为16位
+        matches!(
+            self.communicate(pcd::TRANSCEIVE, &[picc::REQA]),
+            Ok((ref atqa, 0)) if atqa.len() == 2
+        )
+    }
+
+    /// 防冲突并选卡，返回完整UID
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "rc522.select",
+            level = "debug",
+            skip_all,
+            err(level = "debug")
+        )
+    )]
+    pub fn select(&mut self) -> anyhow::Result<Uid> {
+        let mut bytes = Vec::with_capacity(10);
+
+        // 依次处理1~3级级联
+        for cascade in [picc::SEL_CL1, picc::SEL_CL2, picc::SEL_CL3] {
+            // 防冲突
+            self.write_reg(reg::BIT_FRAMING, 0x00)?;
+            self.clear_bits(reg::COLL, 0x80)?;
+            let (uid, _) = self.communicate(pcd::TRANSCEIVE, &[cascade, 0x20])?;
+            if uid.len() != 5 {
+                return Err(anyhow::anyhow!("RC522防冲突应答长度错误: {}", uid.len()));
+            }
+            // 校验BCC
+            if uid[0] ^ uid[1] ^ uid[2] ^ uid[3] != uid[4] {
+                trace_event!(warn, uid = ?uid, "RC522防冲突BCC校验错误");
+                return Err(anyhow::anyhow!("RC522防冲突BCC校验错误"));
+            }
+
+            // 选卡
+            let mut frame = vec![cascade, 0x70];
+            frame.extend_from_slice(&uid);
+            let (sak, _) = self.transceive_with_crc(&frame)?;
+            if sak.len() != 3 {
+                return Err(anyhow::anyhow!("RC522选卡应答长度错误: {}", sak.len()));
+            }
+
+            // 级联标志表示UID还未读取完整
+            if uid[0] == picc::CASCADE_TAG {
+                bytes.extend_from_slice(&uid[1..4]);
+            } else {
+                bytes.extend_from_slice(&uid[0..4]);
+            }
+            if sak[0] & 0x04 == 0 {
+                // OK
+                return Ok(Uid { bytes, sak: sak[0] });
+            }
+        }
+
+        Err(anyhow::anyhow!("RC522级联级数超出范围"))
+    }
+
+    /// 让卡片进入休眠状态（HLTA）
+    pub fn halt(&mut self) -> anyhow::Result<()> {
+        // 卡片收到HLTA后不应答，超时即表示成功
+        match self.transceive_with_crc(&[picc::HLTA, 0x00]) {
+            Ok(_) => Err(anyhow::anyhow!("RC522卡片未进入休眠状态")),
+            Err(_) => Ok(()),
+        }
+    }
+
+    /// MIFARE Classic扇区认证
+    pub fn authenticate(
+        &mut self,
+        key_type: KeyType,
+        block: u8,
+        key: &[u8; 6],
+        uid: &Uid,
+    ) -> anyhow::Result<()> {
+        // 多字节UID使用最后4个字节进行认证
+        let serial = &uid.bytes[uid.bytes.len().saturating_sub(4)..];
+
+        let mut frame = vec![key_type as u8, block];
+        frame.extend_from_slice(key);
+        frame.extend_from_slice(serial);
+        self.communicate(pcd::MF_AUTHENT, &frame)?;
+
+        // 检查MFCrypto1On位
+        if self.read_reg(reg::STATUS2)? & 0x08 == 0 {
+            return Err(anyhow::anyhow!("RC522扇区认证失败, 块: {}", block));
+        }
+        // OK
+        Ok(())
+    }
+
+    /// 结束加密通信（认证之后必须调用）
+    pub fn stop_crypto(&mut self) -> anyhow::Result<()> {
+        self.clear_bits(reg::STATUS2, 0x08)
+    }
+
+    /// 读取块数据（16字节）
+    pub fn read_block(&mut self, block: u8) -> anyhow::Result<[u8; 16]> {
+        let (data, _) = self.transceive_with_crc(&[picc::MF_READ, block])?;
+        if data.len() != 18 {
+            return Err(anyhow::anyhow!("RC522读取块数据长度错误: {}", data.len()));
+        }
+        // 校验CRC
+        let crc = self.calculate_crc(&data[..16])?;
+        if crc != data[16..18] {
+            trace_event!(warn, block, "RC522读取块数据CRC校验错误");
+            return Err(anyhow::anyhow!("RC522读取块数据CRC校验错误"));
+        }
+
+        let mut block_data = [0u8; 16];
+        block_data.copy_from_slice(&data[..16]);
+        // OK
+        Ok(block_data)
+    }
+
+    /// 写入块数据（16字节）
+    ///
+    /// - 注意不要写入扇区尾块（每个扇区的第4块），否则可能锁死扇区
+    pub fn write_block(&mut self, block: u8, data: &[u8; 16]) -> anyhow::Result<()> {
+        // 写命令与数据分两次发送，每次都需要卡片回复4位ACK
+        self.expect_ack(&[picc::MF_WRITE, block])?;
+        self.expect_ack(data)
+    }
+
+    /// 发送数据并检查ACK应答
+    fn expect_ack(&mut self, data: &[u8]) -> anyhow::Result<()> {
+        match self.transceive_with_crc(data)? {
+            (ack, 4) if ack.len() == 1 && ack[0] & 0x0F == picc::MF_ACK => Ok(()),
+            _ => Err(anyhow::anyhow!("RC522写入块数据未收到ACK")),
+        }
+    }
+
+    /// 监听卡片刷卡事件
+    ///
+    /// - 在独立线程中轮询感应区，每次刷卡只回调一次
+    /// - 回调中可继续使用读卡器进行认证和读写块
+    pub fn on_card<F>(mut self, mut cb: F) -> thread::JoinHandle<()>
+    where
+        F: FnMut(&mut MFRC522, &Uid) + Send + 'static,
+    {
+        thread::spawn(move || {
+            loop {
+                if self.is_card_present() {
+                    match self.select() {
+                        Ok(uid) => {
+                            cb(&mut self, &uid);
+                            // 休眠卡片，卡片离开感应区之前不会重复触发
+                            let _ = self.stop_crypto();
+                            let _ = self.halt();
+                        }
+                        Err(err) => {
+                            eprintln!("RC522选卡失败: {}", err);
+                        }
+                    }
+                }
+                // 100ms轮询一次
+                thread::sleep(Duration::from_millis(100));
+            }
+        })
+    }
+}
@@ -1,2 +1,57 @@
+#[cfg(feature = "button")]
 pub mod button;
+#[cfg(feature = "button")]
+pub mod button_bank;
+#[cfg(feature = "uln2003a")]
 pub mod uln2003a;
+#[cfg(feature = "mfrc522")]
+pub mod mfrc522;
+#[cfg(feature = "fingerprint")]
+pub mod fingerprint;
+#[cfg(feature = "gps")]
+pub mod gps;
+#[cfg(feature = "eeprom")]
+pub mod eeprom_24c;
+#[cfg(feature = "gpio-expander")]
+pub mod gpio_expander;
+#[cfg(feature = "dc-motor")]
+pub mod dc_motor;
+pub mod position;
+pub mod ramp;
+pub mod speed;
+#[cfg(feature = "step-dir-stepper")]
+pub mod step_dir_stepper;
+#[cfg(feature = "keypad")]
+pub mod keypad;
+#[cfg(feature = "touch")]
+pub mod touch;
+#[cfg(feature = "mcp3008")]
+pub mod mcp3008;
+#[cfg(feature = "joystick")]
+pub mod joystick;
+#[cfg(feature = "soil-moisture")]
+pub mod soil_moisture;
+#[cfg(feature = "rotary-encoder")]
+pub mod rotary_encoder;
+#[cfg(feature = "nau7802")]
+pub mod nau7802;
+#[cfg(feature = "hx711")]
+pub mod hx711;
+#[cfg(any(feature = "rain-gauge", feature = "anemometer"))]
+pub mod pulse_counter;
+#[cfg(feature = "rain-gauge")]
+pub mod rain_gauge;
+#[cfg(feature = "anemometer")]
+pub mod anemometer;
+#[cfg(feature = "bh1750")]
+pub mod bh1750;
+#[cfg(feature = "ds3231")]
+pub mod ds3231;
+#[cfg(feature = "vibration-motor")]
+pub mod vibration_motor;
+#[cfg(feature = "relay-board")]
+pub mod relay_board;
+#[cfg(feature = "dht11")]
+pub mod dht_capture;
+#[cfg(feature = "dht11")]
+pub mod dht_gpio;
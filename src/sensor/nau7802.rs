@@ -0,0 +1,293 @@
+use rppal::gpio::{Gpio, InputPin, Trigger};
+use rppal::i2c::I2c;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::scale::WeightAdc;
+
+/// 寄存器地址
+mod reg {
+    pub const PU_CTRL: u8 = 0x00;
+    pub const CTRL1: u8 = 0x01;
+    pub const CTRL2: u8 = 0x02;
+    pub const ADCO_B2: u8 = 0x12;
+    pub const ADC: u8 = 0x15;
+    pub const POWER: u8 = 0x1C;
+    pub const REVISION: u8 = 0x1F;
+}
+
+/// PU_CTRL寄存器位
+mod pu_ctrl {
+    /// 寄存器复位
+    pub const RR: u8 = 0x01;
+    /// 数字电路上电
+    pub const PUD: u8 = 0x02;
+    /// 模拟电路上电
+    pub const PUA: u8 = 0x04;
+    /// 上电就绪
+    pub const PUR: u8 = 0x08;
+    /// 开始转换
+    pub const CS: u8 = 0x10;
+    /// 转换数据就绪
+    pub const CR: u8 = 0x20;
+    /// 使用内部LDO
+    pub const AVDDS: u8 = 0x80;
+}
+
+/// CTRL2寄存器位
+mod ctrl2 {
+    /// 开始校准
+    pub const CALS: u8 = 0x04;
+    /// 校准错误
+    pub const CAL_ERR: u8 = 0x08;
+}
+
+/// PGA增益
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Gain {
+    X1 = 0,
+    X2 = 1,
+    X4 = 2,
+    X8 = 3,
+    X16 = 4,
+    X32 = 5,
+    X64 = 6,
+    X128 = 7,
+}
+
+/// 内部LDO输出电压
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Ldo {
+    V2_4 = 0b111,
+    V2_7 = 0b110,
+    V3_0 = 0b101,
+    V3_3 = 0b100,
+    V3_6 = 0b011,
+    V3_9 = 0b010,
+    V4_2 = 0b001,
+    V4_5 = 0b000,
+}
+
+/// 采样率
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SampleRate {
+    Sps10 = 0,
+    Sps20 = 1,
+    Sps40 = 2,
+    Sps80 = 3,
+    Sps320 = 7,
+}
+
+impl SampleRate {
+    /// 采样周期
+    pub fn period(&self) -> Duration {
+        let sps = match self {
+            SampleRate::Sps10 => 10,
+            SampleRate::Sps20 => 20,
+            SampleRate::Sps40 => 40,
+            SampleRate::Sps80 => 80,
+            SampleRate::Sps320 => 320,
+        };
+        Duration::from_micros(1_000_000 / sps)
+    }
+}
+
+/// NAU7802 24位称重ADC封装对象（I2C）
+///
+/// 与HX711相比使用I2C通信，不需要占用专用的时钟、数据引脚，
+/// 并且内置LDO和校准电路
+pub struct NAU7802 {
+    /// I2C通信总线
+    i2c_bus: Arc<Mutex<I2c>>,
+    /// 设备地址（固定为0x2A）
+    address: u16,
+    /// 当前采样率
+    sample_rate: SampleRate,
+    /// 数据就绪引脚（DRDY，未接线时轮询寄存器）
+    drdy_pin: Option<InputPin>,
+}
+
+impl NAU7802 {
+    /// 创建实例并初始化（默认LDO 3.3V、增益128、采样率80SPS）
+    ///
+    /// - i2c_bus: 共享的I2C通信总线
+    pub fn new(i2c_bus: Arc<Mutex<I2c>>) -> anyhow::Result<Self> {
+        let mut this = Self {
+            i2c_bus,
+            address: 0x2A,
+            sample_rate: SampleRate::Sps80,
+            drdy_pin: None,
+        };
+
+        // 复位所有寄存器
+        this.write_reg(reg::PU_CTRL, pu_ctrl::RR)?;
+        thread::sleep(Duration::from_millis(1));
+        // 数字、模拟电路上电
+        this.write_reg(reg::PU_CTRL, pu_ctrl::PUD | pu_ctrl::PUA)?;
+        // 等待上电就绪
+        let start = Instant::now();
+        while this.read_reg(reg::PU_CTRL)? & pu_ctrl::PUR == 0 {
+            if start.elapsed() > Duration::from_millis(100) {
+                return Err(anyhow::anyhow!("NAU7802上电超时"));
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        let revision = this.read_reg(reg::REVISION)? & 0x0F;
+        if revision != 0x0F {
+            return Err(anyhow::anyhow!("NAU7802芯片版本错误: 0x{:02X}", revision));
+        }
+
+        this.set_ldo(Ldo::V3_3)?;
+        this.set_gain(Gain::X128)?;
+        this.set_sample_rate(SampleRate::Sps80)?;
+        // 关闭ADC斩波时钟（数据手册推荐设置）
+        this.update_reg(reg::ADC, 0x30, 0x30)?;
+        // 打开通道2的PGA输出旁路电容，降低噪声
+        this.update_reg(reg::POWER, 0x80, 0x80)?;
+        // 开始转换
+        this.update_reg(reg::PU_CTRL, pu_ctrl::CS, pu_ctrl::CS)?;
+        this.calibrate()?;
+        // OK
+        Ok(this)
+    }
+
+    /// 读寄存器
+    fn read_reg(&mut self, reg: u8) -> anyhow::Result<u8> {
+        let mut buffer = [0u8; 1];
+        let mut i2c = self
+            .i2c_bus
+            .lock()
+            .map_err(|_| anyhow::anyhow!("I2C通信总线繁忙"))?;
+        i2c.set_slave_address(self.address)?;
+        i2c.write_read(&[reg], &mut buffer)?;
+        trace_event!(trace, reg, value = buffer[0], "NAU7802读寄存器");
+        Ok(buffer[0])
+    }
+
+    /// 写寄存器
+    fn write_reg(&mut self, reg: u8, value: u8) -> anyhow::Result<()> {
+        let mut i2c = self
+            .i2c_bus
+            .lock()
+            .map_err(|_| anyhow::anyhow!("I2C通信总线繁忙"))?;
+        i2c.set_slave_address(self.address)?;
+        i2c.write(&[reg, value])?;
+        trace_event!(trace, reg, value, "NAU7802写寄存器");
+        Ok(())
+    }
+
+    /// 修改寄存器中mask对应的位
+    fn update_reg(&mut self, reg: u8, mask: u8, value: u8) -> anyhow::Result<()> {
+        let old = self.read_reg(reg)?;
+        self.write_reg(reg, (old & !mask) | (value & mask))
+    }
+
+    /// 设置内部LDO输出电压（同时切换为使用内部LDO）
+    pub fn set_ldo(&mut self, ldo: Ldo) -> anyhow::Result<()> {
+        self.update_reg(reg::CTRL1, 0x38, (ldo as u8) << 3)?;
+        self.update_reg(reg::PU_CTRL, pu_ctrl::AVDDS, pu_ctrl::AVDDS)
+    }
+
+    /// 设置PGA增益
+    pub fn set_gain(&mut self, gain: Gain) -> anyhow::Result<()> {
+        self.update_reg(reg::CTRL1, 0x07, gain as u8)
+    }
+
+    /// 设置采样率
+    pub fn set_sample_rate(&mut self, sample_rate: SampleRate) -> anyhow::Result<()> {
+        self.update_reg(reg::CTRL2, 0x70, (sample_rate as u8) << 4)?;
+        self.sample_rate = sample_rate;
+        Ok(())
+    }
+
+    /// 内部失调校准（修改增益、采样率、LDO后需要重新校准）
+    pub fn calibrate(&mut self) -> anyhow::Result<()> {
+        // 内部校准模式并开始校准
+        self.update_reg(reg::CTRL2, 0x03 | ctrl2::CALS, ctrl2::CALS)?;
+        let start = Instant::now();
+        loop {
+            let ctrl2 = self.read_reg(reg::CTRL2)?;
+            if ctrl2 & ctrl2::CALS == 0 {
+                if ctrl2 & ctrl2::CAL_ERR != 0 {
+                    return Err(anyhow::anyhow!("NAU7802校准失败"));
+                }
+                return Ok(());
+            }
+            if start.elapsed() > Duration::from_secs(1) {
+                return Err(anyhow::anyhow!("NAU7802校准超时"));
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    /// 使用DRDY引脚中断等待数据就绪（不设置时轮询寄存器）
+    pub fn set_drdy_pin(&mut self, pin: u8) -> anyhow::Result<()> {
+        let mut pin = Gpio::new()?.get(pin)?.into_input();
+        // 转换完成时DRDY输出高电平
+        pin.set_interrupt(Trigger::RisingEdge, None)?;
+        self.drdy_pin = Some(pin);
+        Ok(())
+    }
+
+    /// 数据是否就绪
+    pub fn is_data_ready(&mut self) -> anyhow::Result<bool> {
+        Ok(self.read_reg(reg::PU_CTRL)? & pu_ctrl::CR != 0)
+    }
+
+    /// 等待数据就绪
+    fn wait_data_ready(&mut self) -> anyhow::Result<()> {
+        // 超时时间为两个采样周期
+        let timeout = self.sample_rate.period() * 2 + Duration::from_millis(10);
+        if let Some(pin) = &mut self.drdy_pin {
+            // 引脚已经是高电平时不会再产生上升沿
+            if pin.is_high() || pin.poll_interrupt(true, Some(timeout))?.is_some() {
+                return Ok(());
+            }
+            return Err(anyhow::anyhow!("NAU7802等待数据就绪超时"));
+        }
+
+        let start = Instant::now();
+        while !self.is_data_ready()? {
+            if start.elapsed() > timeout {
+                trace_event!(
+                    warn,
+                    timeout_ms = timeout.as_millis() as u64,
+                    "NAU7802等待数据就绪超时"
+                );
+                return Err(anyhow::anyhow!("NAU7802等待数据就绪超时"));
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        Ok(())
+    }
+
+    /// 读取一次ADC读数（阻塞直到数据就绪）
+    pub fn read(&mut self) -> anyhow::Result<i32> {
+        self.wait_data_ready()?;
+
+        let mut buffer = [0u8; 3];
+        {
+            let mut i2c = self
+                .i2c_bus
+                .lock()
+                .map_err(|_| anyhow::anyhow!("I2C通信总线繁忙"))?;
+            i2c.set_slave_address(self.address)?;
+            i2c.write_read(&[reg::ADCO_B2], &mut buffer)?;
+        }
+        // 24位补码扩展为32位
+        let value = i32::from_be_bytes([buffer[0], buffer[1], buffer[2], 0]) >> 8;
+        // OK
+        Ok(value)
+    }
+}
+
+impl WeightAdc for NAU7802 {
+    fn read_raw(&mut self) -> anyhow::Result<i32> {
+        self.read()
+    }
+}
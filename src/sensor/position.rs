@@ -0,0 +1,151 @@
+//! 步进电机位置记录
+//!
+//! 步进电机没有位置反馈，重启后不知道当前位置。百叶窗、阀门等执行器重启后继续运行时，
+//! 按0点计算会越过机械行程。`PositionTracker`记录当前位置，每次运行结束和释放时
+//! 保存到校准数据存储中，启动时恢复；设置软限位后拒绝超出范围的运行
+
+use crate::calibration::CalibrationStore;
+
+/// 步进电机位置记录
+///
+/// 位置以驱动当前的步进单位记录（如半步、细分步），切换步进模式时按比例换算，
+/// 保存时同时记录单位，恢复时换算为当前的单位
+pub struct PositionTracker {
+    /// 当前位置
+    position: i64,
+    /// 每个整步对应的步数
+    factor: i64,
+    /// 软限位（最小值, 最大值）
+    limits: (Option<i64>, Option<i64>),
+    /// 校准数据存储和名称前缀
+    store: Option<(Box<dyn CalibrationStore>, String)>,
+    /// 上一次保存的位置
+    saved: Option<i64>,
+}
+
+impl PositionTracker {
+    /// 创建实例（位置为0，没有软限位，不保存）
+    ///
+    /// - factor: 每个整步对应的步数
+    pub fn new(factor: u32) -> Self {
+        Self {
+            position: 0,
+            factor: factor.max(1) as i64,
+            limits: (None, None),
+            store: None,
+            saved: None,
+        }
+    }
+
+    /// 从存储中恢复位置，之后的位置变化保存到存储中
+    ///
+    /// - store: 校准数据存储（如`FileStore::new("blinds.pos")`）
+    /// - prefix: 名称前缀，同一存储保存多个电机时区分（保存为`{prefix}.position`、`{prefix}.factor`）
+    pub fn attach_store<S>(&mut self, mut store: S, prefix: &str) -> anyhow::Result<()>
+    where
+        S: CalibrationStore + 'static,
+    {
+        let calibration = store.load()?;
+        if let Some(position) = calibration.get(&format!("{}.position", prefix)) {
+            let factor = calibration
+                .get(&format!("{}.factor", prefix))
+                .map_or(self.factor, |factor| (factor as i64).max(1));
+            self.position = position as i64 * self.factor / factor;
+            trace_event!(info, prefix, position = self.position, "已恢复步进电机位置");
+        }
+        self.saved = Some(self.position);
+        self.store = Some((Box::new(store), prefix.to_string()));
+        Ok(())
+    }
+
+    /// 当前位置
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    /// 设置当前位置（如回零后设为0）
+    pub fn set_position(&mut self, position: i64) {
+        self.position = position;
+    }
+
+    /// 每个整步对应的步数
+    pub fn factor(&self) -> u32 {
+        self.factor as u32
+    }
+
+    /// 切换步进单位，按比例换算位置和软限位
+    pub fn rescale(&mut self, factor: u32) {
+        let (old, new) = (self.factor, factor.max(1) as i64);
+        self.position = self.position * new / old;
+        self.limits = (
+            self.limits.0.map(|min| min * new / old),
+            self.limits.1.map(|max| max * new / old),
+        );
+        self.factor = new;
+    }
+
+    /// 设置软限位（为None时该方向不限制）
+    pub fn set_limits(&mut self, min: Option<i64>, max: Option<i64>) -> anyhow::Result<()> {
+        if let (Some(min), Some(max)) = (min, max)
+            && min > max
+        {
+            return Err(anyhow::anyhow!("软限位最小值{}大于最大值{}", min, max));
+        }
+        self.limits = (min, max);
+        Ok(())
+    }
+
+    /// 软限位（最小值, 最大值）
+    pub fn limits(&self) -> (Option<i64>, Option<i64>) {
+        self.limits
+    }
+
+    /// 检查目标位置是否在软限位内
+    pub fn check(&self, target: i64) -> anyhow::Result<()> {
+        match self.limits {
+            (Some(min), _) if target < min => Err(anyhow::anyhow!(
+                "目标位置{}超出软限位最小值{}（当前位置{}）",
+                target,
+                min,
+                self.position
+            )),
+            (_, Some(max)) if target > max => Err(anyhow::anyhow!(
+                "目标位置{}超出软限位最大值{}（当前位置{}）",
+                target,
+                max,
+                self.position
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// 记录位置变化
+    pub fn advance(&mut self, steps: i64) {
+        self.position += steps;
+    }
+
+    /// 位置有变化时保存到存储中
+    pub fn save(&mut self) -> anyhow::Result<()> {
+        let Some((store, prefix)) = &mut self.store else {
+            return Ok(());
+        };
+        if self.saved == Some(self.position) {
+            return Ok(());
+        }
+        let mut calibration = store.load()?;
+        calibration.set(&format!("{}.position", prefix), self.position as f64);
+        calibration.set(&format!("{}.factor", prefix), self.factor as f64);
+        store.save(&calibration)?;
+        self.saved = Some(self.position);
+        Ok(())
+    }
+}
+
+/// 释放时保存位置
+impl Drop for PositionTracker {
+    fn drop(&mut self) {
+        if let Err(err) = self.save() {
+            eprintln!("保存步进电机位置失败: {}", err);
+        }
+    }
+}
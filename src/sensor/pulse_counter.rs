@@ -0,0 +1,45 @@
+use rppal::gpio::{Gpio, InputPin, Trigger};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::debug::{self, PinMode};
+
+/// 脉冲计数器（干簧管、霍尔开关等每次闭合输出一个脉冲）
+///
+/// 中断回调只记录脉冲时刻，由读取方按时间窗口统计
+pub struct PulseCounter {
+    /// 输入引脚（释放时清除中断）
+    _pin: InputPin,
+    /// 上一次取出后的脉冲时刻
+    pulses: Arc<Mutex<Vec<Instant>>>,
+}
+
+impl PulseCounter {
+    /// 创建实例（内部上拉，开关闭合时拉低引脚）
+    ///
+    /// - pin: 开关接入的GPIO针脚
+    /// - debounce: 消抖时间（干簧管触点抖动通常在数毫秒内）
+    pub fn new(pin: u8, debounce: Duration) -> anyhow::Result<Self> {
+        let mut pin = Gpio::new()?.get(pin)?.into_input_pullup();
+        let number = pin.pin();
+        debug::claim(number, "pulse-counter", PinMode::Input);
+        let pulses = Arc::new(Mutex::new(Vec::new()));
+        let recorder = pulses.clone();
+        pin.set_async_interrupt(Trigger::FallingEdge, Some(debounce), move |_| {
+            debug::record_interrupt(number);
+            if let Ok(mut pulses) = recorder.lock() {
+                pulses.push(Instant::now());
+            }
+        })?;
+        // OK
+        Ok(Self { _pin: pin, pulses })
+    }
+
+    /// 取出上一次取出后的所有脉冲时刻
+    pub fn take(&self) -> Vec<Instant> {
+        match self.pulses.lock() {
+            Ok(mut pulses) => std::mem::take(&mut *pulses),
+            Err(poisoned) => std::mem::take(&mut *poisoned.into_inner()),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use crate::sensor::pulse_counter::PulseCounter;
+
+/// 常见翻斗式雨量计每次翻斗的降雨量（mm）
+pub const DEFAULT_MM_PER_TIP: f64 = 0.2794;
+
+/// 翻斗式雨量计
+///
+/// 翻斗每翻转一次干簧管闭合一次，降雨量 = 翻斗次数 × 每次翻斗的降雨量
+pub struct RainGauge {
+    /// 脉冲计数器
+    counter: PulseCounter,
+    /// 每次翻斗的降雨量（mm）
+    mm_per_tip: f64,
+    /// 创建以来的累计降雨量（mm）
+    total: f64,
+}
+
+impl RainGauge {
+    /// 创建实例
+    ///
+    /// - pin: 干簧管接入的GPIO针脚（另一端接地）
+    pub fn new(pin: u8) -> anyhow::Result<Self> {
+        Ok(Self {
+            counter: PulseCounter::new(pin, Duration::from_millis(20))?,
+            mm_per_tip: DEFAULT_MM_PER_TIP,
+            total: 0.0,
+        })
+    }
+
+    /// 设置每次翻斗的降雨量（mm）
+    pub fn with_mm_per_tip(mut self, mm_per_tip: f64) -> Self {
+        self.mm_per_tip = mm_per_tip;
+        self
+    }
+
+    /// 上一次读取后的降雨量（mm）
+    pub fn read(&mut self) -> f64 {
+        let rainfall = self.counter.take().len() as f64 * self.mm_per_tip;
+        self.total += rainfall;
+        rainfall
+    }
+
+    /// 创建以来的累计降雨量（mm，不包含尚未读取的部分）
+    pub fn total(&self) -> f64 {
+        self.total
+    }
+
+    /// 累计降雨量清零（如每天0点）
+    pub fn reset_total(&mut self) {
+        self.total = 0.0;
+    }
+}
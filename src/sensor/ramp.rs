@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+/// 步进电机梯形加减速曲线
+///
+/// 从起始速度匀加速到最高速度，快到终点时再匀减速到起始速度，
+/// 避免电机启停时因扭矩不足而丢步
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ramp {
+    /// 起始速度（步/秒）
+    start_speed: f64,
+    /// 最高速度（步/秒）
+    max_speed: f64,
+    /// 加速度（步/秒²）
+    acceleration: f64,
+}
+
+impl Ramp {
+    /// 创建加减速曲线
+    ///
+    /// - start_speed: 起始（结束）速度，步/秒
+    /// - max_speed: 最高速度，步/秒
+    /// - acceleration: 加速度，步/秒²，为0时全程以最高速度运行
+    pub fn new(start_speed: f64, max_speed: f64, acceleration: f64) -> Self {
+        let max_speed = max_speed.max(1.0);
+        Self {
+            start_speed: start_speed.clamp(1.0, max_speed),
+            max_speed,
+            acceleration: acceleration.max(0.0),
+        }
+    }
+
+    /// 恒速运行（不加减速）
+    pub fn constant(speed: f64) -> Self {
+        Self::new(speed, speed, 0.0)
+    }
+
+    /// 最高速度（步/秒）
+    pub fn max_speed(&self) -> f64 {
+        self.max_speed
+    }
+
+    /// 从起始速度加速到最高速度需要的步数
+    pub fn accel_steps(&self) -> u32 {
+        if self.acceleration == 0.0 {
+            return 0;
+        }
+        // v² = v0² + 2as
+        ((self.max_speed.powi(2) - self.start_speed.powi(2)) / (2.0 * self.acceleration)).ceil()
+            as u32
+    }
+
+    /// 第index步（从0开始）的速度（步/秒）
+    pub fn speed_at(&self, index: u32, total: u32) -> f64 {
+        if self.acceleration == 0.0 {
+            return self.max_speed;
+        }
+        // 距离起点和终点较近的一侧决定当前速度，步数不够时形成三角形曲线
+        let distance = index.min(total.saturating_sub(index + 1)) as f64;
+        (self.start_speed.powi(2) + 2.0 * self.acceleration * distance)
+            .sqrt()
+            .min(self.max_speed)
+    }
+
+    /// 第index步之后的等待时间
+    pub fn delay_at(&self, index: u32, total: u32) -> Duration {
+        Duration::from_secs_f64(1.0 / self.speed_at(index, total))
+    }
+
+    /// 运行total步时每步的等待时间
+    pub fn delays(&self, total: u32) -> impl Iterator<Item = Duration> + use<> {
+        let ramp = *self;
+        (0..total).map(move |index| ramp.delay_at(index, total))
+    }
+}
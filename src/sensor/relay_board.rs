@@ -0,0 +1,238 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::debug::{self, PinMode};
+use crate::gpio::{self, OutputPin};
+use crate::switch::Switch;
+
+/// 继电器通道
+struct Channel {
+    /// 控制引脚
+    pin: OutputPin,
+    /// 是否吸合
+    on: bool,
+}
+
+/// 多路继电器模块（4路、8路继电器板或继电器HAT）
+///
+/// 通道编号从1开始，与模块上的IN1~IN8丝印一致；大多数模块为低电平触发。
+/// 互锁的两个通道不能同时吸合（如电机正反转、窗帘开关），打开其中一个前需要先关闭另一个
+///
+/// ```ignore
+/// let board = RelayBoard::new(&[5, 6, 13, 19], true)?.with_interlock(1, 2)?;
+/// let board = Arc::new(Mutex::new(board));
+/// let mut heater = RelayBoard::channel(&board, 3)?;
+/// heater.on()?;
+/// ```
+pub struct RelayBoard {
+    /// 各通道
+    channels: Vec<Channel>,
+    /// 低电平触发
+    active_low: bool,
+    /// 互锁的通道对
+    interlocks: Vec<(usize, usize)>,
+}
+
+impl RelayBoard {
+    /// 创建实例（所有通道初始为断开状态）
+    ///
+    /// - pins: IN1、IN2……依次接入的GPIO针脚
+    /// - active_low: 低电平触发（大多数继电器模块为true）
+    pub fn new(pins: &[u8], active_low: bool) -> anyhow::Result<Self> {
+        if pins.is_empty() {
+            return Err(anyhow::anyhow!("继电器模块至少需要1个通道"));
+        }
+        let channels = pins
+            .iter()
+            .map(|&pin| {
+                // 初始化时直接输出断开的电平，避免上电瞬间吸合
+                let output = gpio::output(pin, active_low)?;
+                debug::claim(pin, "relay-board", PinMode::Output);
+                Ok(Channel {
+                    pin: output,
+                    on: false,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        // OK
+        Ok(Self {
+            channels,
+            active_low,
+            interlocks: Vec::new(),
+        })
+    }
+
+    /// 设置两个通道互锁（不能同时吸合）
+    pub fn with_interlock(mut self, a: usize, b: usize) -> anyhow::Result<Self> {
+        self.index(a)?;
+        self.index(b)?;
+        if a == b {
+            return Err(anyhow::anyhow!("通道{}不能与自身互锁", a));
+        }
+        self.interlocks.push((a, b));
+        // OK
+        Ok(self)
+    }
+
+    /// 通道数量
+    pub fn len(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// 是否没有通道
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+    }
+
+    /// 通道编号转换为下标
+    fn index(&self, channel: usize) -> anyhow::Result<usize> {
+        if channel == 0 || channel > self.channels.len() {
+            return Err(anyhow::anyhow!(
+                "继电器通道{}不存在（1~{}）",
+                channel,
+                self.channels.len()
+            ));
+        }
+        Ok(channel - 1)
+    }
+
+    /// 吸合或断开指定通道
+    pub fn set(&mut self, channel: usize, on: bool) -> anyhow::Result<()> {
+        let index = self.index(channel)?;
+        if on {
+            for &(a, b) in &self.interlocks {
+                let other = match channel {
+                    c if c == a => b,
+                    c if c == b => a,
+                    _ => continue,
+                };
+                if self.channels[other - 1].on {
+                    return Err(anyhow::anyhow!(
+                        "通道{}与通道{}互锁，需要先断开通道{}",
+                        channel,
+                        other,
+                        other
+                    ));
+                }
+            }
+        }
+        let active_low = self.active_low;
+        let relay = &mut self.channels[index];
+        gpio::write(&mut relay.pin, on != active_low)?;
+        relay.on = on;
+        trace_event!(debug, channel, on, "继电器状态变化");
+        Ok(())
+    }
+
+    /// 吸合指定通道
+    pub fn on(&mut self, channel: usize) -> anyhow::Result<()> {
+        self.set(channel, true)
+    }
+
+    /// 断开指定通道
+    pub fn off(&mut self, channel: usize) -> anyhow::Result<()> {
+        self.set(channel, false)
+    }
+
+    /// 切换指定通道，返回切换后的状态
+    pub fn toggle(&mut self, channel: usize) -> anyhow::Result<bool> {
+        let on = !self.is_on(channel)?;
+        self.set(channel, on)?;
+        // OK
+        Ok(on)
+    }
+
+    /// 吸合指定通道一段时间后断开（阻塞，用于门锁、电铃等点动控制）
+    pub fn pulse(&mut self, channel: usize, duration: Duration) -> anyhow::Result<()> {
+        self.on(channel)?;
+        thread::sleep(duration);
+        self.off(channel)
+    }
+
+    /// 断开所有通道（尽量断开每一个通道，返回第一个错误）
+    pub fn all_off(&mut self) -> anyhow::Result<()> {
+        let mut result = Ok(());
+        for channel in 1..=self.channels.len() {
+            if let Err(err) = self.off(channel)
+                && result.is_ok()
+            {
+                result = Err(err);
+            }
+        }
+        result
+    }
+
+    /// 指定通道是否吸合
+    pub fn is_on(&self, channel: usize) -> anyhow::Result<bool> {
+        let index = self.index(channel)?;
+        // OK
+        Ok(self.channels[index].on)
+    }
+
+    /// 所有通道的状态（按通道编号）
+    pub fn states(&self) -> Vec<bool> {
+        self.channels.iter().map(|channel| channel.on).collect()
+    }
+
+    /// 单个通道的开关（共享同一个模块，互锁仍然有效）
+    pub fn channel(board: &Arc<Mutex<Self>>, channel: usize) -> anyhow::Result<RelayChannel> {
+        board
+            .lock()
+            .map_err(|_| anyhow::anyhow!("继电器模块锁已损坏"))?
+            .index(channel)?;
+        // OK
+        Ok(RelayChannel {
+            board: board.clone(),
+            channel,
+        })
+    }
+}
+
+/// 释放时断开所有通道
+impl Drop for RelayBoard {
+    fn drop(&mut self) {
+        let _ = self.all_off();
+    }
+}
+
+/// 继电器模块的单个通道
+///
+/// 实现`Switch`，可以交给恒温控制、指示器等只控制一个开关的模块使用
+pub struct RelayChannel {
+    /// 继电器模块
+    board: Arc<Mutex<RelayBoard>>,
+    /// 通道编号
+    channel: usize,
+}
+
+impl RelayChannel {
+    /// 通道编号
+    pub fn number(&self) -> usize {
+        self.channel
+    }
+
+    /// 吸合一段时间后断开（阻塞）
+    pub fn pulse(&mut self, duration: Duration) -> anyhow::Result<()> {
+        self.on()?;
+        thread::sleep(duration);
+        self.off()
+    }
+}
+
+impl Switch for RelayChannel {
+    fn set(&mut self, on: bool) -> anyhow::Result<()> {
+        self.board
+            .lock()
+            .map_err(|_| anyhow::anyhow!("继电器模块锁已损坏"))?
+            .set(self.channel, on)
+    }
+
+    fn is_on(&self) -> bool {
+        self.board
+            .lock()
+            .ok()
+            .and_then(|board| board.is_on(self.channel).ok())
+            .unwrap_or(false)
+    }
+}
@@ -0,0 +1,81 @@
+use rppal::gpio::{Gpio, InputPin, Trigger};
+use std::thread;
+
+use crate::core::encoder::QuadratureDecoder;
+use crate::debug::{self, PinMode};
+
+/// 旋转编码器封装对象（KY-040等机械编码器）
+///
+/// 按钮（SW）与普通按钮相同，使用`Button`单独创建
+pub struct RotaryEncoder {
+    /// A相（CLK）
+    clk: InputPin,
+    /// B相（DT）
+    dt: InputPin,
+    /// 每个定位格的跳变数
+    steps_per_detent: u8,
+}
+
+impl RotaryEncoder {
+    /// 创建实例（上拉输入，模块上已有上拉电阻时不影响）
+    ///
+    /// - clk_pin: A相引脚（CLK）
+    /// - dt_pin: B相引脚（DT）
+    pub fn new(clk_pin: u8, dt_pin: u8) -> anyhow::Result<Self> {
+        let gpio = Gpio::new()?;
+        let clk = gpio.get(clk_pin)?.into_input_pullup();
+        let dt = gpio.get(dt_pin)?.into_input_pullup();
+        debug::claim(clk_pin, "rotary-encoder", PinMode::Input);
+        debug::claim(dt_pin, "rotary-encoder", PinMode::Input);
+        // OK
+        Ok(Self {
+            clk,
+            dt,
+            steps_per_detent: 4,
+        })
+    }
+
+    /// 设置每个定位格的跳变数（默认4，转一格回调两次时改为2）
+    pub fn with_steps_per_detent(mut self, steps_per_detent: u8) -> Self {
+        self.steps_per_detent = steps_per_detent;
+        self
+    }
+
+    /// 监听旋转（回调参数为转过的定位格数，顺时针为正）
+    ///
+    /// - 在独立线程中同时等待两个引脚的中断
+    pub fn on_rotate<F>(mut self, mut cb: F) -> anyhow::Result<thread::JoinHandle<()>>
+    where
+        F: FnMut(i32) + Send + 'static,
+    {
+        let gpio = Gpio::new()?;
+        self.clk.set_interrupt(Trigger::Both, None)?;
+        self.dt.set_interrupt(Trigger::Both, None)?;
+        let mut levels = [self.clk.is_high(), self.dt.is_high()];
+        let mut decoder = QuadratureDecoder::new(levels[0], levels[1], self.steps_per_detent);
+
+        let handle = thread::spawn(move || {
+            let pins = [&self.clk, &self.dt];
+            loop {
+                let (pin, event) = match gpio.poll_interrupts(&pins, false, None) {
+                    Ok(Some(interrupt)) => interrupt,
+                    Ok(None) => continue,
+                    Err(err) => {
+                        eprintln!("旋转编码器等待中断失败: {}", err);
+                        break;
+                    }
+                };
+                let number = pin.pin();
+                debug::record_interrupt(number);
+                let index = if number == self.clk.pin() { 0 } else { 1 };
+                levels[index] = event.trigger == Trigger::RisingEdge;
+                let detents = decoder.update(levels[0], levels[1]);
+                if detents != 0 {
+                    cb(detents as i32);
+                }
+            }
+        });
+        // OK
+        Ok(handle)
+    }
+}
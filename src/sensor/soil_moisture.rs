@@ -0,0 +1,54 @@
+use crate::analog::AnalogIn;
+
+/// 电容式土壤湿度传感器封装对象（经ADC接入）
+///
+/// 输出电压随含水量升高而降低，需要分别记录插在干燥空气中和泡在水中时的原始值，
+/// 两者之间按线性换算为0~100%
+pub struct SoilMoisture {
+    /// ADC通道
+    input: Box<dyn AnalogIn>,
+    /// 完全干燥时的原始值
+    dry_raw: u32,
+    /// 完全浸水时的原始值
+    wet_raw: u32,
+}
+
+impl SoilMoisture {
+    /// 创建实例
+    ///
+    /// - input: ADC通道（如`MCP3008::channel`）
+    /// - dry_raw: 完全干燥时的原始值
+    /// - wet_raw: 完全浸水时的原始值
+    pub fn new<A: AnalogIn + 'static>(
+        input: A,
+        dry_raw: u32,
+        wet_raw: u32,
+    ) -> anyhow::Result<Self> {
+        if dry_raw == wet_raw {
+            return Err(anyhow::anyhow!(
+                "土壤湿度传感器的干燥值和浸水值不能相同: {}",
+                dry_raw
+            ));
+        }
+        // OK
+        Ok(Self {
+            input: Box::new(input),
+            dry_raw,
+            wet_raw,
+        })
+    }
+
+    /// 读取原始值（用于标定干燥值和浸水值）
+    pub fn read_raw(&mut self) -> anyhow::Result<u32> {
+        self.input.read_raw()
+    }
+
+    /// 读取土壤湿度（0~100%，超出标定范围时截断）
+    pub fn read(&mut self) -> anyhow::Result<f64> {
+        let raw = self.input.read_raw()? as f64;
+        let percent =
+            (raw - self.dry_raw as f64) / (self.wet_raw as f64 - self.dry_raw as f64) * 100.0;
+        // OK
+        Ok(percent.clamp(0.0, 100.0))
+    }
+}
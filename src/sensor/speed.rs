@@ -0,0 +1,109 @@
+//! 步进电机连续运行
+//!
+//! 驱动以转速（RPM）设置速度并按步进模式换算为每步间隔，`ContinuousRun`在后台线程中
+//! 按该间隔持续单步运行，直到调用`stop`或到达软限位；运行期间可以通过同一个
+//! `Arc<Mutex<..>>`修改转速
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::core::stepper::Direction;
+
+/// 可连续运行的步进电机驱动
+pub trait ContinuousStepper: Send {
+    /// 单步运行（超出软限位时返回错误）
+    fn step(&mut self, direction: Direction) -> anyhow::Result<()>;
+
+    /// 当前转速对应的每步间隔
+    fn step_delay(&self) -> Duration;
+
+    /// 保存位置
+    fn save_position(&mut self) -> anyhow::Result<()>;
+}
+
+/// 转速换算为每步间隔
+///
+/// - rpm: 转速（转/分钟，大于0）
+/// - steps_per_rev: 每圈步数
+pub fn rpm_to_delay(rpm: f32, steps_per_rev: u32) -> Duration {
+    Duration::from_secs_f64(60.0 / (rpm as f64 * steps_per_rev.max(1) as f64))
+}
+
+/// 每步间隔换算为转速
+pub fn delay_to_rpm(delay: Duration, steps_per_rev: u32) -> f32 {
+    (60.0 / (delay.as_secs_f64() * steps_per_rev.max(1) as f64)) as f32
+}
+
+/// 检查转速是否有效（有限的正数）
+pub fn validate_rpm(rpm: f32) -> anyhow::Result<()> {
+    if !rpm.is_finite() || rpm <= 0.0 {
+        return Err(anyhow::anyhow!("转速必须大于0: {}", rpm));
+    }
+    Ok(())
+}
+
+/// 后台连续运行的步进电机
+///
+/// ```ignore
+/// let motor = Arc::new(Mutex::new(ULN2003A::new(6, 13, 19, 26, StepMode::FullStep)?));
+/// motor.lock().unwrap().set_speed_rpm(8.0)?;
+/// let run = ULN2003A::run_continuous(&motor, Direction::Clockwise);
+/// thread::sleep(Duration::from_secs(5));
+/// run.stop()?;
+/// ```
+pub struct ContinuousRun {
+    /// 停止标志
+    stop: Arc<AtomicBool>,
+    /// 运行线程（到达软限位等原因停止时返回错误）
+    thread: JoinHandle<anyhow::Result<()>>,
+}
+
+impl ContinuousRun {
+    /// 在后台线程中按驱动当前的转速持续运行
+    pub fn start<M>(motor: Arc<Mutex<M>>, direction: Direction) -> Self
+    where
+        M: ContinuousStepper + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = stop.clone();
+        let thread = thread::spawn(move || {
+            let lock = || {
+                motor
+                    .lock()
+                    .map_err(|_| anyhow::anyhow!("步进电机锁已损坏"))
+            };
+            while !flag.load(Ordering::Relaxed) {
+                let delay = {
+                    let mut motor = lock()?;
+                    if let Err(err) = motor.step(direction) {
+                        trace_event!(warn, error = %err, "步进电机连续运行停止");
+                        motor.save_position()?;
+                        return Err(err);
+                    }
+                    motor.step_delay()
+                };
+                // 每步之后释放锁，运行期间可以修改转速
+                thread::sleep(delay);
+            }
+            lock()?.save_position()
+        });
+        Self { stop, thread }
+    }
+
+    /// 是否仍在运行
+    pub fn is_running(&self) -> bool {
+        !self.thread.is_finished()
+    }
+
+    /// 停止运行并等待运行线程退出（不会释放线圈）
+    ///
+    /// 运行线程因到达软限位等原因提前停止时返回该错误
+    pub fn stop(self) -> anyhow::Result<()> {
+        self.stop.store(true, Ordering::Relaxed);
+        self.thread
+            .join()
+            .map_err(|_| anyhow::anyhow!("步进电机运行线程异常退出"))?
+    }
+}
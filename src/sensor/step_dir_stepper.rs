@@ -0,0 +1,409 @@
+use rppal::gpio::{Gpio, OutputPin};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::calibration::CalibrationStore;
+use crate::core::stepper::Direction;
+use crate::debug::{self, PinMode};
+use crate::sensor::position::PositionTracker;
+use crate::sensor::ramp::Ramp;
+use crate::sensor::speed::{self, ContinuousRun, ContinuousStepper};
+
+/// A4988/DRV8825的最高步进脉冲频率（步/秒）
+const MAX_STEP_RATE: f64 = 250_000.0;
+
+/// 驱动芯片型号（决定细分引脚的电平组合）
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DriverChip {
+    /// A4988（MS1/MS2/MS3，最高1/16细分）
+    A4988,
+    /// DRV8825（M0/M1/M2，最高1/32细分）
+    DRV8825,
+}
+
+/// 细分模式
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Microstep {
+    Full,
+    Half,
+    Quarter,
+    Eighth,
+    Sixteenth,
+    ThirtySecond,
+}
+
+impl Microstep {
+    /// 每个整步对应的细分步数
+    pub fn factor(&self) -> u32 {
+        match self {
+            Microstep::Full => 1,
+            Microstep::Half => 2,
+            Microstep::Quarter => 4,
+            Microstep::Eighth => 8,
+            Microstep::Sixteenth => 16,
+            Microstep::ThirtySecond => 32,
+        }
+    }
+
+    /// 细分引脚电平（按MS1~MS3或M0~M2的顺序）
+    fn pin_levels(&self, chip: DriverChip) -> Option<[bool; 3]> {
+        match (chip, self) {
+            (_, Microstep::Full) => Some([false, false, false]),
+            (_, Microstep::Half) => Some([true, false, false]),
+            (_, Microstep::Quarter) => Some([false, true, false]),
+            (_, Microstep::Eighth) => Some([true, true, false]),
+            (DriverChip::A4988, Microstep::Sixteenth) => Some([true, true, true]),
+            (DriverChip::A4988, Microstep::ThirtySecond) => None,
+            (DriverChip::DRV8825, Microstep::Sixteenth) => Some([false, false, true]),
+            (DriverChip::DRV8825, Microstep::ThirtySecond) => Some([true, false, true]),
+        }
+    }
+}
+
+/// 细分引脚
+struct MicrostepPins {
+    chip: DriverChip,
+    pins: [OutputPin; 3],
+}
+
+/// A4988/DRV8825等STEP/DIR驱动板的步进电机封装对象
+///
+/// 位置以细分步为单位记录，切换细分模式时会按比例换算；
+/// 可以保存位置供重启后恢复，并设置软限位拒绝超出机械行程的运行
+pub struct StepDirStepper {
+    /// 步进脉冲引脚
+    step: OutputPin,
+    /// 方向引脚（高电平为顺时针）
+    dir: OutputPin,
+    /// 使能引脚（低电平有效）
+    enable: Option<OutputPin>,
+    /// 细分引脚（跳线固定细分时为None）
+    microstep_pins: Option<MicrostepPins>,
+    /// 当前细分模式
+    microstep: Microstep,
+    /// 加减速曲线（单位为细分步）
+    ramp: Ramp,
+    /// 每毫米对应的整步数
+    steps_per_mm: f64,
+    /// 电机每圈的整步数
+    steps_per_rev: u32,
+    /// 连续运行的转速（转/分钟，为None时使用加减速曲线的起始速度）
+    speed_rpm: Option<f32>,
+    /// 当前位置（细分步）和软限位
+    position: PositionTracker,
+}
+
+impl StepDirStepper {
+    /// 创建步进电机实例
+    ///
+    /// - step_pin、dir_pin: 步进脉冲、方向引脚
+    /// - enable_pin: 使能引脚（未接线时传None）
+    pub fn new(step_pin: u8, dir_pin: u8, enable_pin: Option<u8>) -> anyhow::Result<Self> {
+        let gpio = Gpio::new()?;
+        let step = gpio.get(step_pin)?.into_output_low();
+        let dir = gpio.get(dir_pin)?.into_output_low();
+        // 默认禁用，避免上电后电机一直发热
+        let enable = match enable_pin {
+            Some(pin) => Some(gpio.get(pin)?.into_output_high()),
+            None => None,
+        };
+        for pin in [Some(step_pin), Some(dir_pin), enable_pin]
+            .into_iter()
+            .flatten()
+        {
+            debug::claim(pin, "step-dir-stepper", PinMode::Output);
+        }
+        // OK
+        Ok(Self {
+            step,
+            dir,
+            enable,
+            microstep_pins: None,
+            microstep: Microstep::Full,
+            ramp: Ramp::new(200.0, 800.0, 1600.0),
+            steps_per_mm: 1.0,
+            steps_per_rev: 200,
+            speed_rpm: None,
+            position: PositionTracker::new(Microstep::Full.factor()),
+        })
+    }
+
+    /// 保存位置到存储中，并从存储中恢复上一次的位置
+    ///
+    /// 每次运行结束和释放时保存；保存时记录细分模式，恢复时换算为当前的细分步。
+    /// 需要在设置细分模式之后调用
+    ///
+    /// - store: 校准数据存储（如`FileStore::new("/var/lib/raspi-sensor/valve.pos")`）
+    /// - prefix: 名称前缀，同一存储保存多个电机时区分
+    pub fn with_position_store<S>(mut self, store: S, prefix: &str) -> anyhow::Result<Self>
+    where
+        S: CalibrationStore + 'static,
+    {
+        self.position.attach_store(store, prefix)?;
+        // OK
+        Ok(self)
+    }
+
+    /// 设置软限位（细分步，为None时该方向不限制），切换细分模式时按比例换算
+    pub fn set_limits(&mut self, min: Option<i64>, max: Option<i64>) -> anyhow::Result<()> {
+        self.position.set_limits(min, max)
+    }
+
+    /// 设置软限位（毫米）
+    pub fn set_limits_mm(&mut self, min: Option<f64>, max: Option<f64>) -> anyhow::Result<()> {
+        let to_steps = |mm: f64| (mm * self.microsteps_per_mm()).round() as i64;
+        self.position
+            .set_limits(min.map(to_steps), max.map(to_steps))
+    }
+
+    /// 软限位（细分步）
+    pub fn limits(&self) -> (Option<i64>, Option<i64>) {
+        self.position.limits()
+    }
+
+    /// 配置细分引脚
+    ///
+    /// - chip: 驱动芯片型号
+    /// - pins: MS1~MS3（A4988）或M0~M2（DRV8825）引脚
+    pub fn set_microstep_pins(&mut self, chip: DriverChip, pins: [u8; 3]) -> anyhow::Result<()> {
+        let gpio = Gpio::new()?;
+        self.microstep_pins = Some(MicrostepPins {
+            chip,
+            pins: [
+                gpio.get(pins[0])?.into_output_low(),
+                gpio.get(pins[1])?.into_output_low(),
+                gpio.get(pins[2])?.into_output_low(),
+            ],
+        });
+        // 按当前细分模式输出电平
+        self.set_microstep(self.microstep)
+    }
+
+    /// 设置细分模式
+    ///
+    /// 未配置细分引脚时只记录细分模式（用于跳线固定细分的驱动板）
+    pub fn set_microstep(&mut self, microstep: Microstep) -> anyhow::Result<()> {
+        if let Some(MicrostepPins { chip, pins }) = &mut self.microstep_pins {
+            let levels = microstep
+                .pin_levels(*chip)
+                .ok_or_else(|| anyhow::anyhow!("{:?}不支持细分模式: {:?}", chip, microstep))?;
+            for (pin, level) in pins.iter_mut().zip(levels) {
+                if level {
+                    pin.set_high();
+                } else {
+                    pin.set_low();
+                }
+            }
+        }
+
+        // 按比例换算当前位置和软限位
+        self.position.rescale(microstep.factor());
+        self.microstep = microstep;
+        Ok(())
+    }
+
+    /// 当前细分模式
+    pub fn microstep(&self) -> Microstep {
+        self.microstep
+    }
+
+    /// 设置加减速曲线（单位为细分步）
+    pub fn set_ramp(&mut self, ramp: Ramp) {
+        self.ramp = ramp;
+    }
+
+    /// 设置每毫米对应的整步数
+    ///
+    /// 如200步/圈的电机配合导程8mm的丝杆为25.0
+    pub fn set_steps_per_mm(&mut self, steps_per_mm: f64) {
+        self.steps_per_mm = steps_per_mm;
+    }
+
+    /// 设置电机每圈的整步数（默认200，即1.8°步距角）
+    pub fn set_steps_per_rev(&mut self, steps_per_rev: u32) {
+        self.steps_per_rev = steps_per_rev.max(1);
+    }
+
+    /// 当前细分模式下每圈的细分步数
+    fn microsteps_per_rev(&self) -> u32 {
+        self.steps_per_rev * self.microstep.factor()
+    }
+
+    /// 当前细分模式下加减速曲线允许的最高转速（转/分钟）
+    pub fn max_speed_rpm(&self) -> f32 {
+        (self.ramp.max_speed() * 60.0 / self.microsteps_per_rev() as f64) as f32
+    }
+
+    /// 设置连续运行的转速，返回实际生效的转速
+    ///
+    /// 超过加减速曲线的最高速度时限制为该速度；对应的脉冲频率超过驱动芯片上限
+    /// （250kHz）或不是正数时返回错误
+    pub fn set_speed_rpm(&mut self, rpm: f32) -> anyhow::Result<f32> {
+        speed::validate_rpm(rpm)?;
+        let rate = rpm as f64 * self.microsteps_per_rev() as f64 / 60.0;
+        if rate > MAX_STEP_RATE {
+            return Err(anyhow::anyhow!(
+                "转速{}对应的脉冲频率{:.0}步/秒超过驱动芯片上限{}步/秒",
+                rpm,
+                rate,
+                MAX_STEP_RATE
+            ));
+        }
+        self.speed_rpm = Some(rpm);
+        let actual = self.speed_rpm();
+        if actual < rpm {
+            trace_event!(warn, rpm, actual, "转速超过加减速曲线的最高速度，已限制");
+        }
+        // OK
+        Ok(actual)
+    }
+
+    /// 连续运行实际生效的转速（转/分钟）
+    ///
+    /// 连续运行不加减速，未设置转速时使用加减速曲线的起始速度
+    pub fn speed_rpm(&self) -> f32 {
+        let delay = self.step_delay();
+        speed::delay_to_rpm(delay, self.microsteps_per_rev())
+    }
+
+    /// 连续运行时每步的间隔
+    pub fn step_delay(&self) -> Duration {
+        let min = Duration::from_secs_f64(1.0 / self.ramp.max_speed());
+        match self.speed_rpm {
+            Some(rpm) => speed::rpm_to_delay(rpm, self.microsteps_per_rev()).max(min),
+            None => self.ramp.delay_at(0, 1),
+        }
+    }
+
+    /// 在后台线程中按连续运行的转速持续运行，直到调用`stop`或到达软限位
+    pub fn run_continuous(motor: &Arc<Mutex<Self>>, direction: Direction) -> ContinuousRun {
+        ContinuousRun::start(motor.clone(), direction)
+    }
+
+    /// 使能/禁用电机（禁用后线圈断电，电机可自由转动）
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if let Some(pin) = &mut self.enable {
+            if enabled {
+                pin.set_low();
+            } else {
+                pin.set_high();
+            }
+        }
+    }
+
+    /// 设置方向
+    fn set_direction(&mut self, direction: Direction) {
+        match direction {
+            Direction::Clockwise => self.dir.set_high(),
+            Direction::CounterClockwise => self.dir.set_low(),
+        }
+    }
+
+    /// 输出一个步进脉冲
+    fn pulse(&mut self) {
+        // A4988要求脉冲宽度至少1µs，DRV8825至少1.9µs
+        self.step.set_high();
+        thread::sleep(Duration::from_micros(2));
+        self.step.set_low();
+    }
+
+    /// 单步运行（不加减速，超出软限位时返回错误）
+    pub fn step(&mut self, direction: Direction) -> anyhow::Result<()> {
+        let delta = match direction {
+            Direction::Clockwise => 1,
+            Direction::CounterClockwise => -1,
+        };
+        self.position.check(self.position.position() + delta)?;
+        self.set_direction(direction);
+        self.pulse();
+        self.position.advance(delta);
+        Ok(())
+    }
+
+    /// 按加减速曲线运行指定步数（细分步，正数为顺时针）
+    ///
+    /// 终点超出软限位时不运行直接返回错误，运行结束后保存位置
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "stepper.move_steps", level = "debug", skip(self))
+    )]
+    pub fn move_steps(&mut self, steps: i64) -> anyhow::Result<()> {
+        self.position.check(self.position.position() + steps)?;
+        let direction = if steps >= 0 {
+            Direction::Clockwise
+        } else {
+            Direction::CounterClockwise
+        };
+        self.set_direction(direction);
+        // DIR信号需要在STEP之前稳定
+        thread::sleep(Duration::from_micros(1));
+
+        let total = steps.unsigned_abs().min(u32::MAX as u64) as u32;
+        let sign = steps.signum();
+        for delay in self.ramp.delays(total) {
+            self.pulse();
+            self.position.advance(sign);
+            thread::sleep(delay);
+        }
+        self.position.save()
+    }
+
+    /// 运行到指定位置（细分步）
+    pub fn move_to(&mut self, position: i64) -> anyhow::Result<()> {
+        self.move_steps(position - self.position.position())
+    }
+
+    /// 移动指定距离（毫米，正数为顺时针）
+    pub fn move_mm(&mut self, mm: f64) -> anyhow::Result<()> {
+        let steps = (mm * self.microsteps_per_mm()).round() as i64;
+        self.move_steps(steps)
+    }
+
+    /// 运行到指定位置（毫米）
+    pub fn move_to_mm(&mut self, mm: f64) -> anyhow::Result<()> {
+        let position = (mm * self.microsteps_per_mm()).round() as i64;
+        self.move_to(position)
+    }
+
+    /// 每毫米对应的细分步数
+    fn microsteps_per_mm(&self) -> f64 {
+        self.steps_per_mm * self.microstep.factor() as f64
+    }
+
+    /// 当前位置（细分步）
+    pub fn position(&self) -> i64 {
+        self.position.position()
+    }
+
+    /// 当前位置（毫米）
+    pub fn position_mm(&self) -> f64 {
+        self.position.position() as f64 / self.microsteps_per_mm()
+    }
+
+    /// 设置当前位置（细分步），如回零后设为0
+    pub fn set_position(&mut self, position: i64) {
+        self.position.set_position(position);
+    }
+
+    /// 立即保存位置（未设置存储时不做任何事）
+    pub fn save_position(&mut self) -> anyhow::Result<()> {
+        self.position.save()
+    }
+}
+
+impl ContinuousStepper for StepDirStepper {
+    fn step(&mut self, direction: Direction) -> anyhow::Result<()> {
+        StepDirStepper::step(self, direction)
+    }
+
+    fn step_delay(&self) -> Duration {
+        StepDirStepper::step_delay(self)
+    }
+
+    fn save_position(&mut self) -> anyhow::Result<()> {
+        StepDirStepper::save_position(self)
+    }
+}
@@ -0,0 +1,80 @@
+use rppal::gpio::{Gpio, InputPin, Level, Trigger};
+use std::time::Duration;
+
+use crate::debug::{self, PinMode};
+use crate::sensor::button::{self, ButtonEvent, EventTiming};
+
+/// 触摸事件时间参数默认值
+///
+/// 电容触摸芯片内部已经做过滤波，输出没有机械抖动，不需要消抖
+pub const TOUCH_TIMING: EventTiming = EventTiming {
+    debounce: None,
+    hold: Duration::from_millis(800),
+};
+
+/// TTP223电容触摸传感器封装对象
+pub struct Touch {
+    /// 输出引脚
+    pin: InputPin,
+    /// 触摸时的输出电平
+    active_level: Level,
+}
+
+impl Touch {
+    /// 创建触摸传感器实例
+    ///
+    /// - pin: 输出引脚
+    /// - active_level: 触摸时的输出电平，TTP223默认为高电平（焊接B跳线后为低电平）
+    pub fn new(pin: u8, active_level: Level) -> anyhow::Result<Self> {
+        // TTP223为推挽输出，不需要上下拉
+        let pin = Gpio::new()?.get(pin)?.into_input();
+        debug::claim(pin.pin(), "touch", PinMode::Input);
+        // OK
+        Ok(Self { pin, active_level })
+    }
+
+    /// 读取当前是否被触摸
+    pub fn is_touched(&self) -> bool {
+        self.pin.read() == self.active_level
+    }
+
+    /// 监听触摸状态变化
+    ///
+    /// - True: 表示被触摸
+    /// - False: 表示松开
+    pub fn on_change<F>(&mut self, mut cb: F) -> anyhow::Result<()>
+    where
+        F: FnMut(bool) + Send + 'static,
+    {
+        let active_edge = match self.active_level {
+            Level::High => Trigger::RisingEdge,
+            Level::Low => Trigger::FallingEdge,
+        };
+        let number = self.pin.pin();
+        self.pin
+            .set_async_interrupt(Trigger::Both, None, move |event| {
+                debug::record_interrupt(number);
+                cb(event.trigger == active_edge)
+            })?;
+        // OK
+        Ok(())
+    }
+
+    /// 监听触摸事件（触摸、松开、长按）
+    ///
+    /// - 不消抖，按住800毫秒触发长按
+    pub fn on_event<F>(&mut self, cb: F) -> anyhow::Result<()>
+    where
+        F: FnMut(ButtonEvent) + Send + 'static,
+    {
+        self.on_event_with_timing(TOUCH_TIMING, cb)
+    }
+
+    /// 使用自定义时间参数监听触摸事件
+    pub fn on_event_with_timing<F>(&mut self, timing: EventTiming, cb: F) -> anyhow::Result<()>
+    where
+        F: FnMut(ButtonEvent) + Send + 'static,
+    {
+        button::watch_events(&mut self.pin, self.active_level == Level::Low, timing, cb)
+    }
+}
@@ -1,23 +1,25 @@
 use rppal::gpio::{Gpio, OutputPin};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
-/// 步进电机转动方向
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum Direction {
-    /// 顺时针方向
-    Clockwise,
-    /// 逆时针方向
-    CounterClockwise,
-}
+use crate::calibration::CalibrationStore;
+use crate::debug::{self, PinMode};
+use crate::diagnostics::DiagnosticsReport;
+use crate::sensor::position::PositionTracker;
+use crate::sensor::ramp::Ramp;
+use crate::sensor::speed::{self, ContinuousRun, ContinuousStepper};
 
-/// 步进模式枚举
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum StepMode {
-    WaveDrive, // 单相激励（4步）
-    FullStep,  // 双相激励（4步）
-    HalfStep,  // 单双相交替（8步）
-}
+pub use crate::core::stepper::{Direction, StepMode};
+
+/// 28BYJ-48每圈的整步数（32步 × 64:1减速比）
+const STEPS_PER_REV: u32 = 2048;
+
+/// 28BYJ-48保证扭矩的最小步间隔
+const MIN_STEP_DELAY: Duration = Duration::from_millis(3);
+
+/// 28BYJ-48在5V供电下空载能达到的最高转速（转/分钟）
+const MAX_RPM: f32 = 15.0;
 
 /// ULN2003A驱动模块28BYJ-48电机封装对象
 pub struct ULN2003A {
@@ -29,43 +31,23 @@ pub struct ULN2003A {
     step_sequence: Vec<[bool; 4]>,
     /// 当前步
     current_step: usize,
+    /// 累计位置（以当前步进模式的步为单位）和软限位
+    position: PositionTracker,
+    /// 设置的转速（转/分钟）
+    speed_rpm: f32,
 }
 
 impl ULN2003A {
     /// 生成步进序列
     fn generate_step_sequence(mode: StepMode) -> Vec<[bool; 4]> {
+        mode.sequence().to_vec()
+    }
+
+    /// 每个整步对应的步数（半步模式为2）
+    fn step_factor(mode: StepMode) -> u32 {
         match mode {
-            StepMode::WaveDrive => {
-                // 单相激励序列（4步）
-                vec![
-                    [true, false, false, false], // A
-                    [false, true, false, false], // B
-                    [false, false, true, false], // C
-                    [false, false, false, true], // D
-                ]
-            }
-            StepMode::FullStep => {
-                // 双相激励序列（4步）
-                vec![
-                    [true, true, false, false], // AB
-                    [false, true, true, false], // BC
-                    [false, false, true, true], // CD
-                    [true, false, false, true], // DA
-                ]
-            }
-            StepMode::HalfStep => {
-                // 单双相交替序列（8步）- 提供更平滑的运动
-                vec![
-                    [true, false, false, false], // A
-                    [true, true, false, false],  // AB
-                    [false, true, false, false], // B
-                    [false, true, true, false],  // BC
-                    [false, false, true, false], // C
-                    [false, false, true, true],  // CD
-                    [false, false, false, true], // D
-                    [true, false, false, true],  // DA
-                ]
-            }
+            StepMode::HalfStep => 2,
+            StepMode::WaveDrive | StepMode::FullStep => 1,
         }
     }
 
@@ -81,6 +63,9 @@ impl ULN2003A {
             gpio.get(pin3)?.into_output_low(),
             gpio.get(pin4)?.into_output_low(),
         ];
+        for pin in &pins {
+            debug::claim(pin.pin(), "uln2003a", PinMode::Output);
+        }
 
         // 根据步进模式生成步进序列
         let step_sequence = Self::generate_step_sequence(mode);
@@ -91,16 +76,114 @@ impl ULN2003A {
             step_mode: mode,
             step_sequence,
             current_step: 0,
+            position: PositionTracker::new(Self::step_factor(mode)),
+            speed_rpm: MAX_RPM,
         })
     }
 
+    /// 保存位置到存储中，并从存储中恢复上一次的位置
+    ///
+    /// 每次运行结束和释放时保存，重启后电机从上一次停下的位置继续计算
+    ///
+    /// - store: 校准数据存储（如`FileStore::new("/var/lib/raspi-sensor/blinds.pos")`）
+    /// - prefix: 名称前缀，同一存储保存多个电机时区分
+    pub fn with_position_store<S>(mut self, store: S, prefix: &str) -> anyhow::Result<Self>
+    where
+        S: CalibrationStore + 'static,
+    {
+        self.position.attach_store(store, prefix)?;
+        // 恢复与位置对应的激励相位，避免第一步时转子跳动
+        let seq_len = self.step_sequence.len() as i64;
+        self.current_step = self.position.position().rem_euclid(seq_len) as usize;
+        // OK
+        Ok(self)
+    }
+
+    /// 设置软限位（以当前步进模式的步为单位，为None时该方向不限制）
+    ///
+    /// 超出软限位的运行会被拒绝，用于百叶窗、阀门等有机械行程的执行器
+    pub fn set_limits(&mut self, min: Option<i64>, max: Option<i64>) -> anyhow::Result<()> {
+        self.position.set_limits(min, max)
+    }
+
+    /// 软限位（最小值, 最大值）
+    pub fn limits(&self) -> (Option<i64>, Option<i64>) {
+        self.position.limits()
+    }
+
+    /// 累计位置（以当前步进模式的步为单位，顺时针为正）
+    pub fn position(&self) -> i64 {
+        self.position.position()
+    }
+
+    /// 设置累计位置，如回零后设为0
+    pub fn set_position(&mut self, position: i64) {
+        self.position.set_position(position);
+    }
+
+    /// 立即保存位置（未设置存储时不做任何事）
+    pub fn save_position(&mut self) -> anyhow::Result<()> {
+        self.position.save()
+    }
+
     /// 设置步进模式
     pub fn set_step_mode(&mut self, mode: StepMode) {
         if mode != self.step_mode {
             self.step_mode = mode;
             self.step_sequence = Self::generate_step_sequence(mode);
             self.current_step = 0;
+            // 按比例换算位置和软限位
+            self.position.rescale(Self::step_factor(mode));
+        }
+    }
+
+    /// 当前步进模式下每圈的步数（半步模式为4096）
+    pub fn steps_per_rev(&self) -> u32 {
+        STEPS_PER_REV * Self::step_factor(self.step_mode)
+    }
+
+    /// 当前步进模式下保证扭矩的最高转速（每步间隔3毫秒，整步约9.8转/分钟，半步约4.9转/分钟）
+    pub fn max_speed_rpm(&self) -> f32 {
+        speed::delay_to_rpm(MIN_STEP_DELAY, self.steps_per_rev())
+    }
+
+    /// 设置转速，返回实际生效的转速
+    ///
+    /// 超过当前步进模式保证扭矩的最高转速时限制为该转速（切换步进模式后重新限制）；
+    /// 超过28BYJ-48的最高转速（15转/分钟）或不是正数时返回错误
+    pub fn set_speed_rpm(&mut self, rpm: f32) -> anyhow::Result<f32> {
+        speed::validate_rpm(rpm)?;
+        if rpm > MAX_RPM {
+            return Err(anyhow::anyhow!(
+                "转速{}超过28BYJ-48的最高转速{}转/分钟",
+                rpm,
+                MAX_RPM
+            ));
         }
+        self.speed_rpm = rpm;
+        let actual = self.speed_rpm();
+        if actual < rpm {
+            trace_event!(warn, rpm, actual, "转速超过保证扭矩的最高转速，已限制");
+        }
+        // OK
+        Ok(actual)
+    }
+
+    /// 实际生效的转速（转/分钟）
+    pub fn speed_rpm(&self) -> f32 {
+        self.speed_rpm.min(self.max_speed_rpm())
+    }
+
+    /// 当前转速对应的每步间隔（用于`run_steps`）
+    pub fn step_delay(&self) -> Duration {
+        speed::rpm_to_delay(self.speed_rpm, self.steps_per_rev()).max(MIN_STEP_DELAY)
+    }
+
+    /// 在后台线程中按当前转速持续运行，直到调用`stop`或到达软限位
+    ///
+    /// 运行期间电机被后台线程逐步锁定，可以通过同一个`Arc<Mutex<..>>`修改转速
+    pub fn run_continuous(motor: &Arc<Mutex<Self>>, direction: Direction) -> ContinuousRun {
+        ContinuousRun::start(motor.clone(), direction)
     }
 
     /// 应用当前步进序列到GPIO引脚
@@ -116,10 +199,19 @@ impl ULN2003A {
         }
     }
 
-    /// 单步运行
+    /// 方向对应的位置变化
+    fn delta(direction: Direction) -> i64 {
+        match direction {
+            Direction::Clockwise => 1,
+            Direction::CounterClockwise => -1,
+        }
+    }
+
+    /// 单步运行（超出软限位时返回错误）
     /// 
     /// - 28BYJ-48建议每步之间的间隔时间最小为3毫秒
-    pub fn step(&mut self, direction: Direction) {
+    pub fn step(&mut self, direction: Direction) -> anyhow::Result<()> {
+        self.position.check(self.position.position() + Self::delta(direction))?;
         let seq_len = self.step_sequence.len();
 
         match direction {
@@ -136,21 +228,55 @@ impl ULN2003A {
         }
 
         self.apply_step();
+        self.position.advance(Self::delta(direction));
+        Ok(())
     }
 
     /// 运行指定步数
     /// 
+    /// 终点超出软限位时不运行直接返回错误，运行结束后保存位置
+    ///
     /// - steps: 需要步进的步数
     /// - step_delay: 每步之间的间隔时间，28BYJ-48建议最小为3毫秒，该函数限制最小值为3毫秒
     /// - direction: 电机旋转方向
-    pub fn run_steps(&mut self, steps: i32, step_delay: Duration, direction: Direction) {
-        let step_count = steps.abs() as usize;
+    pub fn run_steps(
+        &mut self,
+        steps: i32,
+        step_delay: Duration,
+        direction: Direction,
+    ) -> anyhow::Result<()> {
+        let step_count = steps.unsigned_abs();
+        self.position.check(
+            self.position.position() + step_count as i64 * Self::delta(direction),
+        )?;
 
         for _ in 0..step_count {
-            self.step(direction);
+            self.step(direction)?;
             // 确保最小步间延迟，否则丢步
             thread::sleep(step_delay.max(Duration::from_millis(3)));
         }
+        self.position.save()
+    }
+
+    /// 按加减速曲线运行指定步数
+    ///
+    /// 终点超出软限位时不运行直接返回错误，运行结束后保存位置
+    ///
+    /// - steps: 需要步进的步数
+    /// - ramp: 加减速曲线，每步间隔同样限制最小值为3毫秒
+    /// - direction: 电机旋转方向
+    pub fn run_steps_with_ramp(
+        &mut self,
+        steps: u32,
+        ramp: &Ramp,
+        direction: Direction,
+    ) -> anyhow::Result<()> {
+        self.position.check(self.position.position() + steps as i64 * Self::delta(direction))?;
+        for delay in ramp.delays(steps) {
+            self.step(direction)?;
+            thread::sleep(delay.max(Duration::from_millis(3)));
+        }
+        self.position.save()
     }
 
     /// 释放电机（停止所有线圈）
@@ -169,4 +295,64 @@ impl ULN2003A {
     pub fn sequence_length(&self) -> usize {
         self.step_sequence.len()
     }
+
+    /// 自检（线圈通断的启发式检查）
+    ///
+    /// 驱动板没有电流反馈，无法直接测量线圈通断：依次单独打开每相线圈并回读引脚电平
+    /// （引脚被短路时电平与输出不符），再正反各转64步回到原位，需要观察驱动板上的
+    /// LED是否依次点亮、电机是否转动
+    pub fn self_test(&mut self) -> DiagnosticsReport {
+        let mut report = DiagnosticsReport::new("uln2003a");
+        for i in 0..self.pins.len() {
+            self.release();
+            self.pins[i].set_high();
+            thread::sleep(Duration::from_millis(200));
+            let name = format!("线圈{}", i + 1);
+            if self.pins[i].is_set_high() {
+                report.pass(&name, format!("GPIO{}输出高电平", self.pins[i].pin()));
+            } else {
+                report.fail(
+                    &name,
+                    format!("GPIO{}回读为低电平", self.pins[i].pin()),
+                    "引脚可能对地短路，检查IN1~IN4接线",
+                );
+            }
+        }
+        self.release();
+
+        // 正反各转64步（28BYJ-48半步模式约为1/64圈），正转超出软限位时先反转
+        let (first, second) = if self.position.check(self.position.position() + 64).is_ok() {
+            (Direction::Clockwise, Direction::CounterClockwise)
+        } else {
+            (Direction::CounterClockwise, Direction::Clockwise)
+        };
+        let result = self
+            .run_steps(64, Duration::from_millis(5), first)
+            .and_then(|_| self.run_steps(64, Duration::from_millis(5), second));
+        self.release();
+        if let Err(err) = result {
+            report.fail("转动", err.to_string(), "软限位范围不足64步，放宽软限位后重试");
+            return report;
+        }
+        report.warn(
+            "转动",
+            "正反各转64步",
+            "无法检测线圈电流：确认驱动板LED A~D依次点亮且电机来回转动，只抖动不转时检查电机插头和5V供电",
+        );
+        report
+    }
+}
+
+impl ContinuousStepper for ULN2003A {
+    fn step(&mut self, direction: Direction) -> anyhow::Result<()> {
+        ULN2003A::step(self, direction)
+    }
+
+    fn step_delay(&self) -> Duration {
+        ULN2003A::step_delay(self)
+    }
+
+    fn save_position(&mut self) -> anyhow::Result<()> {
+        ULN2003A::save_position(self)
+    }
 }
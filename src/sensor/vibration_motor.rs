@@ -0,0 +1,278 @@
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::pwm_wapper::PwmWapper;
+use crate::switch::Switch;
+
+/// 振动片段
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    /// 强度（0.0~1.0，0为停止）
+    pub intensity: f64,
+    /// 持续时间
+    pub duration: Duration,
+}
+
+/// 振动模式（依次播放的振动片段，可重复）
+#[derive(Debug, Clone, PartialEq)]
+pub struct HapticPattern {
+    /// 振动片段
+    segments: Vec<Segment>,
+    /// 播放次数
+    repeat: u32,
+}
+
+impl Default for HapticPattern {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HapticPattern {
+    /// 创建空的振动模式（播放1次）
+    pub fn new() -> Self {
+        Self {
+            segments: Vec::new(),
+            repeat: 1,
+        }
+    }
+
+    /// 追加振动片段
+    pub fn then(mut self, intensity: f64, duration: Duration) -> Self {
+        self.segments.push(Segment {
+            intensity: intensity.clamp(0.0, 1.0),
+            duration,
+        });
+        self
+    }
+
+    /// 追加停顿
+    pub fn pause(self, duration: Duration) -> Self {
+        self.then(0.0, duration)
+    }
+
+    /// 设置播放次数
+    pub fn with_repeat(mut self, repeat: u32) -> Self {
+        self.repeat = repeat.max(1);
+        self
+    }
+
+    /// 振动片段
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// 播放一次的总时长（包括重复）
+    pub fn duration(&self) -> Duration {
+        self.segments
+            .iter()
+            .map(|segment| segment.duration)
+            .sum::<Duration>()
+            * self.repeat
+    }
+
+    /// 全强度振动一次
+    pub fn buzz(duration: Duration) -> Self {
+        Self::new().then(1.0, duration)
+    }
+
+    /// 连续两次短振（告警提示）
+    pub fn double_buzz() -> Self {
+        Self::new()
+            .then(1.0, Duration::from_millis(150))
+            .pause(Duration::from_millis(100))
+            .then(1.0, Duration::from_millis(150))
+    }
+
+    /// 一强一弱的心跳
+    pub fn heartbeat() -> Self {
+        Self::new()
+            .then(1.0, Duration::from_millis(100))
+            .pause(Duration::from_millis(80))
+            .then(0.5, Duration::from_millis(100))
+            .pause(Duration::from_millis(600))
+    }
+
+    /// 强度逐渐增加（轻提示）
+    pub fn ramp_up(duration: Duration, steps: u32) -> Self {
+        let steps = steps.max(1);
+        (1..=steps).fold(Self::new(), |pattern, i| {
+            pattern.then(i as f64 / steps as f64, duration / steps)
+        })
+    }
+}
+
+/// PWM驱动的振动马达（ERM偏心转子马达、扁平振动马达等）
+///
+/// 马达通过三极管或MOSFET接在PWM引脚上，占空比决定振动强度；
+/// 偏心转子马达在占空比过低时无法启动，强度按`min_duty`~1.0映射为占空比
+///
+/// ```ignore
+/// let mut motor = VibrationMotor::new(PwmWapper::software(21, 200.0)?)?.with_min_duty(0.35);
+/// motor.play(&HapticPattern::double_buzz())?;
+/// ```
+pub struct VibrationMotor {
+    /// PWM输出
+    pwm: PwmWapper,
+    /// 启动马达的最小占空比
+    min_duty: f64,
+    /// 当前强度（0.0~1.0）
+    intensity: f64,
+}
+
+impl VibrationMotor {
+    /// 创建实例（初始为停止状态，最小占空比为0）
+    pub fn new(pwm: PwmWapper) -> anyhow::Result<Self> {
+        let mut this = Self {
+            pwm,
+            min_duty: 0.0,
+            intensity: 0.0,
+        };
+        this.stop()?;
+        // OK
+        Ok(this)
+    }
+
+    /// 设置启动马达的最小占空比（强度大于0时占空比不低于该值）
+    pub fn with_min_duty(mut self, min_duty: f64) -> Self {
+        self.min_duty = min_duty.clamp(0.0, 1.0);
+        self
+    }
+
+    /// 当前强度（0.0~1.0，0为停止）
+    pub fn intensity(&self) -> f64 {
+        self.intensity
+    }
+
+    /// 设置强度并持续振动（0.0~1.0，超出范围时截断，0为停止）
+    pub fn set_intensity(&mut self, intensity: f64) -> anyhow::Result<()> {
+        let intensity = intensity.clamp(0.0, 1.0);
+        let duty = if intensity > 0.0 {
+            self.min_duty + (1.0 - self.min_duty) * intensity
+        } else {
+            0.0
+        };
+        self.pwm.set_duty_cycle(duty)?;
+        self.intensity = intensity;
+        Ok(())
+    }
+
+    /// 停止振动
+    pub fn stop(&mut self) -> anyhow::Result<()> {
+        self.set_intensity(0.0)
+    }
+
+    /// 以指定强度振动一段时间后停止（阻塞）
+    pub fn pulse(&mut self, intensity: f64, duration: Duration) -> anyhow::Result<()> {
+        self.set_intensity(intensity)?;
+        thread::sleep(duration);
+        self.stop()
+    }
+
+    /// 播放振动模式，结束后停止（阻塞）
+    pub fn play(&mut self, pattern: &HapticPattern) -> anyhow::Result<()> {
+        for _ in 0..pattern.repeat {
+            for segment in &pattern.segments {
+                self.set_intensity(segment.intensity)?;
+                thread::sleep(segment.duration);
+            }
+        }
+        self.stop()
+    }
+
+    /// 启动后台播放线程，播放时不阻塞调用方
+    pub fn into_player(self) -> HapticPlayer {
+        HapticPlayer::new(self)
+    }
+}
+
+/// 作为开关使用时，打开为全强度振动
+impl Switch for VibrationMotor {
+    fn set(&mut self, on: bool) -> anyhow::Result<()> {
+        self.set_intensity(if on { 1.0 } else { 0.0 })
+    }
+
+    fn is_on(&self) -> bool {
+        self.intensity > 0.0
+    }
+}
+
+/// 后台振动播放
+///
+/// 播放新的振动模式时立即打断正在播放的模式，所有请求处理完后马达停止
+///
+/// ```ignore
+/// let player = VibrationMotor::new(PwmWapper::software(21, 200.0)?)?.into_player();
+/// alerts.on_alert(move |event| {
+///     if event.state == AlertState::Raised {
+///         player.play(HapticPattern::double_buzz());
+///     }
+/// });
+/// ```
+pub struct HapticPlayer {
+    /// 振动模式通道（None为停止）
+    tx: Sender<Option<HapticPattern>>,
+    /// 播放线程（退出时返回马达）
+    thread: JoinHandle<VibrationMotor>,
+}
+
+impl HapticPlayer {
+    /// 启动播放线程
+    fn new(mut motor: VibrationMotor) -> Self {
+        let (tx, rx) = mpsc::channel::<Option<HapticPattern>>();
+        let thread = thread::spawn(move || {
+            let mut next = rx.recv().ok();
+            while let Some(request) = next.take() {
+                if let Some(pattern) = request {
+                    next = play_interruptible(&mut motor, &pattern, &rx);
+                }
+                let _ = motor.stop();
+                if next.is_none() {
+                    next = rx.recv().ok();
+                }
+            }
+            motor
+        });
+        Self { tx, thread }
+    }
+
+    /// 播放振动模式（打断正在播放的模式）
+    pub fn play(&self, pattern: HapticPattern) {
+        let _ = self.tx.send(Some(pattern));
+    }
+
+    /// 停止正在播放的模式
+    pub fn cancel(&self) {
+        let _ = self.tx.send(None);
+    }
+
+    /// 停止播放线程并取回马达
+    pub fn stop(self) -> anyhow::Result<VibrationMotor> {
+        drop(self.tx);
+        self.thread
+            .join()
+            .map_err(|_| anyhow::anyhow!("振动播放线程异常退出"))
+    }
+}
+
+/// 播放振动模式，期间收到新的请求时打断并返回该请求
+fn play_interruptible(
+    motor: &mut VibrationMotor,
+    pattern: &HapticPattern,
+    rx: &Receiver<Option<HapticPattern>>,
+) -> Option<Option<HapticPattern>> {
+    for _ in 0..pattern.repeat {
+        for segment in &pattern.segments {
+            if let Err(err) = motor.set_intensity(segment.intensity) {
+                eprintln!("振动马达输出失败: {}", err);
+            }
+            match rx.recv_timeout(segment.duration) {
+                Ok(next) => return Some(next),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return None,
+            }
+        }
+    }
+    None
+}
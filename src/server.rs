@@ -0,0 +1,348 @@
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, VecDeque};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tiny_http::{Header, Method, Request, Response, Server, StatusCode};
+use tungstenite::handshake::derive_accept_key;
+use tungstenite::protocol::Role;
+use tungstenite::{Message, WebSocket};
+
+use crate::debug::{self, PinState};
+use crate::health::{HealthRegistry, SensorHealth};
+use crate::reading::Sample;
+use crate::service;
+use crate::sink::Sink;
+
+/// 服务器线程间共享的状态
+struct State {
+    /// 每个传感器保留的历史读数条数
+    capacity: usize,
+    /// 各传感器的历史读数（按时间先后）
+    history: BTreeMap<String, VecDeque<Sample>>,
+    /// WebSocket客户端（只接收指定传感器时附带传感器名称）
+    clients: Vec<(Option<String>, Sender<String>)>,
+    /// 传感器健康统计
+    health: Option<HealthRegistry>,
+}
+
+/// 传感器读数HTTP服务器
+///
+/// - `GET /sensors`：所有传感器及其最新读数
+/// - `GET /sensors/{name}/latest`：指定传感器的最新读数
+/// - `GET /sensors/{name}/history?from=&to=&last=`：历史读数（Unix时间戳，`last`为最近的秒数）
+/// - `GET /ws`、`GET /sensors/{name}/ws`：WebSocket实时推送新读数
+/// - `GET /health`、`GET /sensors/{name}/health`：读取次数、失败次数等健康统计（需要`with_health`）
+/// - `GET /debug/pins`：驱动申请的GPIO的模式、电平、使用者和中断次数
+///
+/// 历史读数只保存在内存中，每个传感器最多保留`capacity`条
+#[derive(Clone)]
+pub struct SensorServer {
+    state: Arc<Mutex<State>>,
+}
+
+impl SensorServer {
+    /// 创建实例
+    ///
+    /// - capacity: 每个传感器保留的历史读数条数
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                capacity: capacity.max(1),
+                history: BTreeMap::new(),
+                clients: Vec::new(),
+                health: None,
+            })),
+        }
+    }
+
+    /// 提供传感器健康统计（如`SensorManager::health()`）
+    pub fn with_health(self, health: HealthRegistry) -> Self {
+        if let Ok(mut state) = self.state.lock() {
+            state.health = Some(health);
+        }
+        self
+    }
+
+    /// 记录一个新读数并推送给WebSocket客户端
+    pub fn record(&self, sample: Sample) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let message = sample.to_json().to_string();
+        state.clients.retain(|(filter, tx)| match filter {
+            Some(sensor) if *sensor != sample.sensor => true,
+            _ => tx.send(message.clone()).is_ok(),
+        });
+        let capacity = state.capacity;
+        let history = state.history.entry(sample.sensor.clone()).or_default();
+        if history.len() >= capacity {
+            history.pop_front();
+        }
+        history.push_back(sample);
+    }
+
+    /// 在后台线程中记录收到的所有读数（如调度器的订阅），通道关闭后线程退出
+    pub fn record_from(&self, samples: Receiver<Sample>) -> JoinHandle<()> {
+        let this = self.clone();
+        thread::spawn(move || {
+            for sample in samples {
+                this.record(sample);
+            }
+        })
+    }
+
+    /// 启动HTTP服务
+    ///
+    /// - addr: 监听地址（如"0.0.0.0:8080"）
+    pub fn serve(&self, addr: &str) -> anyhow::Result<JoinHandle<()>> {
+        let server = Server::http(addr)
+            .map_err(|err| anyhow::anyhow!("启动HTTP服务失败: {}: {}", addr, err))?;
+        // OK
+        Ok(self.spawn(server))
+    }
+
+    /// 在已经打开的监听套接字上启动HTTP服务
+    pub fn serve_listener(&self, listener: TcpListener) -> anyhow::Result<JoinHandle<()>> {
+        let server = Server::from_listener(listener, None)
+            .map_err(|err| anyhow::anyhow!("启动HTTP服务失败: {}", err))?;
+        // OK
+        Ok(self.spawn(server))
+    }
+
+    /// 优先使用systemd套接字激活传入的监听套接字，不是套接字激活启动时监听`addr`
+    pub fn serve_activated(&self, addr: &str) -> anyhow::Result<JoinHandle<()>> {
+        match service::tcp_listener()? {
+            Some(listener) => self.serve_listener(listener),
+            None => self.serve(addr),
+        }
+    }
+
+    /// 在后台线程中处理请求
+    fn spawn(&self, server: Server) -> JoinHandle<()> {
+        let this = self.clone();
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                if let Err(err) = this.handle(request) {
+                    eprintln!("处理HTTP请求失败: {}", err);
+                }
+            }
+        })
+    }
+
+    /// 处理一个请求
+    fn handle(&self, request: Request) -> anyhow::Result<()> {
+        if *request.method() != Method::Get {
+            return respond(request, 405, json!({ "error": "只支持GET请求" }));
+        }
+        let url = request.url().to_string();
+        let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        match segments.as_slice() {
+            ["sensors"] => {
+                let body = self.with_state(|state| {
+                    state
+                        .history
+                        .iter()
+                        .map(|(name, history)| {
+                            json!({
+                                "sensor": name,
+                                "count": history.len(),
+                                "latest": history.back().map(Sample::to_json),
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                })?;
+                respond(request, 200, json!(body))
+            }
+            ["sensors", name, "latest"] => {
+                let latest = self.with_state(|state| {
+                    state
+                        .history
+                        .get(*name)
+                        .and_then(|history| history.back())
+                        .map(Sample::to_json)
+                })?;
+                match latest {
+                    Some(body) => respond(request, 200, body),
+                    None => not_found(request, name),
+                }
+            }
+            ["sensors", name, "history"] => {
+                let (from, to) = match time_window(query) {
+                    Ok(window) => window,
+                    Err(err) => return respond(request, 400, json!({ "error": err.to_string() })),
+                };
+                let samples = self.with_state(|state| {
+                    state.history.get(*name).map(|history| {
+                        history
+                            .iter()
+                            .filter(|sample| {
+                                let t = sample.unix_timestamp();
+                                t >= from && t <= to
+                            })
+                            .map(Sample::to_json)
+                            .collect::<Vec<_>>()
+                    })
+                })?;
+                match samples {
+                    Some(body) => respond(request, 200, json!(body)),
+                    None => not_found(request, name),
+                }
+            }
+            ["debug", "pins"] => {
+                let body: Vec<Value> = debug::dump_pins().iter().map(PinState::to_json).collect();
+                respond(request, 200, json!(body))
+            }
+            ["health"] => {
+                let Some(health) = self.with_state(|state| state.health.clone())? else {
+                    return respond(request, 404, json!({ "error": "没有提供健康统计" }));
+                };
+                let now = SystemTime::now();
+                let body: Vec<Value> = health
+                    .snapshot()
+                    .iter()
+                    .map(|(name, health)| health_json(name, health, now))
+                    .collect();
+                respond(request, 200, json!(body))
+            }
+            ["sensors", name, "health"] => {
+                let health = self.with_state(|state| state.health.clone())?;
+                match health.and_then(|health| health.get(name)) {
+                    Some(health) => {
+                        respond(request, 200, health_json(name, &health, SystemTime::now()))
+                    }
+                    None => not_found(request, name),
+                }
+            }
+            ["ws"] => self.upgrade(request, None),
+            ["sensors", name, "ws"] => {
+                let name = name.to_string();
+                self.upgrade(request, Some(name))
+            }
+            _ => respond(request, 404, json!({ "error": "接口不存在" })),
+        }
+    }
+
+    /// 锁定共享状态后执行操作
+    fn with_state<R>(&self, f: impl FnOnce(&State) -> R) -> anyhow::Result<R> {
+        let state = self
+            .state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("HTTP服务状态异常"))?;
+        Ok(f(&state))
+    }
+
+    /// 升级为WebSocket连接并推送新读数
+    fn upgrade(&self, request: Request, sensor: Option<String>) -> anyhow::Result<()> {
+        let key = request
+            .headers()
+            .iter()
+            .find(|h| h.field.equiv("Sec-WebSocket-Key"))
+            .map(|h| derive_accept_key(h.value.as_bytes()));
+        let Some(accept) = key else {
+            return respond(request, 400, json!({ "error": "不是WebSocket请求" }));
+        };
+
+        let response = Response::empty(StatusCode(101))
+            .with_header(header("Upgrade", "websocket"))
+            .with_header(header("Connection", "Upgrade"))
+            .with_header(header("Sec-WebSocket-Accept", &accept));
+        let stream = request.upgrade("websocket", response);
+
+        let (tx, rx) = mpsc::channel::<String>();
+        self.state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("HTTP服务状态异常"))?
+            .clients
+            .push((sensor, tx));
+
+        // 每个客户端一个推送线程，发送失败（客户端断开）时退出
+        thread::spawn(move || {
+            let mut socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+            for message in rx {
+                if socket.send(Message::Text(message)).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+/// 创建响应头
+fn header(field: &str, value: &str) -> Header {
+    Header::from_bytes(field.as_bytes(), value.as_bytes()).expect("响应头格式错误")
+}
+
+/// 返回JSON响应
+fn respond(request: Request, status: u16, body: Value) -> anyhow::Result<()> {
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json; charset=utf-8"));
+    request.respond(response)?;
+    Ok(())
+}
+
+/// 传感器不存在
+fn not_found(request: Request, name: &str) -> anyhow::Result<()> {
+    let body = json!({ "error": format!("没有传感器{}的读数", name) });
+    respond(request, 404, body)
+}
+
+/// 健康统计转换为JSON（时间为Unix时间戳）
+fn health_json(name: &str, health: &SensorHealth, now: SystemTime) -> Value {
+    let unix = |time: Option<SystemTime>| {
+        time.and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|t| t.as_secs_f64())
+    };
+    let failures: BTreeMap<&str, u64> = health
+        .failures
+        .iter()
+        .map(|(kind, count)| (kind.name(), *count))
+        .collect();
+    json!({
+        "sensor": name,
+        "reads": health.reads,
+        "successes": health.successes,
+        "failures": failures,
+        "consecutive_failures": health.consecutive_failures,
+        "success_rate": health.success_rate(),
+        "last_success": unix(health.last_success),
+        "last_failure": unix(health.last_failure),
+        "last_error": health.last_error,
+        "silent_seconds": health.silent_for(now).as_secs_f64(),
+    })
+}
+
+/// 解析历史查询的时间范围（from、to为Unix时间戳，last为最近的秒数）
+fn time_window(query: &str) -> anyhow::Result<(f64, f64)> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|t| t.as_secs_f64())
+        .unwrap_or_default();
+    let (mut from, mut to) = (f64::MIN, f64::MAX);
+    for pair in query.split('&').filter(|s| !s.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value: f64 = value
+            .parse()
+            .map_err(|_| anyhow::anyhow!("查询参数无效: {}", pair))?;
+        match key {
+            "from" => from = value,
+            "to" => to = value,
+            "last" => from = now - value,
+            _ => return Err(anyhow::anyhow!("不支持的查询参数: {}", key)),
+        }
+    }
+    Ok((from, to))
+}
+
+impl Sink for SensorServer {
+    fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        self.record(sample.clone());
+        Ok(())
+    }
+}
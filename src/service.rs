@@ -0,0 +1,309 @@
+//! systemd服务集成
+//!
+//! - `Notifier`：sd_notify协议，启动完成后发送READY=1，运行中发送WATCHDOG=1、STATUS=
+//! - `listen_fds`/`tcp_listener`：套接字激活（systemd打开监听套接字后通过LISTEN_FDS传给服务），
+//!   服务重启期间的连接由systemd排队，不会被拒绝
+//! - `UnitFile`：生成服务单元（及套接字单元）文件，用于`raspi-sensor install-service`
+
+use std::env;
+use std::fs;
+use std::net::TcpListener;
+use std::os::fd::{FromRawFd, OwnedFd, RawFd};
+use std::os::linux::net::SocketAddrExt;
+use std::os::unix::net::{SocketAddr, UnixDatagram};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// 套接字激活传入的第一个文件描述符（SD_LISTEN_FDS_START）
+const LISTEN_FDS_START: RawFd = 3;
+
+/// 套接字激活的文件描述符是否已经被取走
+static LISTEN_FDS_TAKEN: AtomicBool = AtomicBool::new(false);
+
+/// systemd通知（sd_notify）
+///
+/// ```ignore
+/// if let Some(notifier) = Notifier::from_env()? {
+///     notifier.ready()?;
+/// }
+/// ```
+pub struct Notifier {
+    /// 通知套接字
+    socket: UnixDatagram,
+    /// 通知地址
+    addr: SocketAddr,
+}
+
+impl Notifier {
+    /// 按NOTIFY_SOCKET创建实例，不是由systemd启动（或服务类型不是notify）时返回None
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let Ok(path) = env::var("NOTIFY_SOCKET") else {
+            return Ok(None);
+        };
+        // 以@开头的是抽象命名空间套接字
+        let addr = match path.strip_prefix('@') {
+            Some(name) => SocketAddr::from_abstract_name(name.as_bytes()),
+            None => SocketAddr::from_pathname(&path),
+        }
+        .map_err(|err| anyhow::anyhow!("systemd通知套接字地址无效: {}", err))?;
+        let socket = UnixDatagram::unbound()?;
+        // OK
+        Ok(Some(Self { socket, addr }))
+    }
+
+    /// 发送通知（多个状态用换行分隔，如"READY=1\nSTATUS=..."）
+    pub fn notify(&self, state: &str) -> anyhow::Result<()> {
+        self.socket
+            .send_to_addr(state.as_bytes(), &self.addr)
+            .map_err(|err| anyhow::anyhow!("发送systemd通知失败: {}", err))?;
+        Ok(())
+    }
+
+    /// 启动完成（Type=notify的服务发送后systemd才认为服务已启动）
+    pub fn ready(&self) -> anyhow::Result<()> {
+        self.notify("READY=1")
+    }
+
+    /// 喂狗（需要在WatchdogSec内发送）
+    pub fn watchdog(&self) -> anyhow::Result<()> {
+        self.notify("WATCHDOG=1")
+    }
+
+    /// 正在停止
+    pub fn stopping(&self) -> anyhow::Result<()> {
+        self.notify("STOPPING=1")
+    }
+
+    /// 状态说明（显示在`systemctl status`中）
+    pub fn status(&self, text: &str) -> anyhow::Result<()> {
+        self.notify(&format!("STATUS={}", text.replace('\n', " ")))
+    }
+}
+
+/// systemd服务看门狗的超时时间（WatchdogSec），没有启用时返回None
+///
+/// WATCHDOG_PID存在且不是当前进程时同样返回None（看门狗属于其他进程）
+pub fn watchdog_timeout() -> anyhow::Result<Option<Duration>> {
+    let Ok(usec) = env::var("WATCHDOG_USEC") else {
+        return Ok(None);
+    };
+    let usec: u64 = usec
+        .parse()
+        .map_err(|_| anyhow::anyhow!("WATCHDOG_USEC的值无效: {}", usec))?;
+    if let Ok(pid) = env::var("WATCHDOG_PID")
+        && pid.parse() != Ok(std::process::id())
+    {
+        return Ok(None);
+    }
+    // OK
+    Ok(Some(Duration::from_micros(usec)))
+}
+
+/// 取出套接字激活传入的文件描述符（按套接字单元中ListenStream=的顺序）
+///
+/// 不是套接字激活启动或已经取出过时返回空列表（同一进程中只能取出一次）
+pub fn listen_fds() -> anyhow::Result<Vec<OwnedFd>> {
+    let (Ok(pid), Ok(count)) = (env::var("LISTEN_PID"), env::var("LISTEN_FDS")) else {
+        return Ok(Vec::new());
+    };
+    // LISTEN_PID不是当前进程时，环境变量是从父进程继承的
+    if pid.parse() != Ok(std::process::id()) {
+        return Ok(Vec::new());
+    }
+    let count: RawFd = count
+        .parse()
+        .map_err(|_| anyhow::anyhow!("LISTEN_FDS的值无效: {}", count))?;
+    if LISTEN_FDS_TAKEN.swap(true, Ordering::SeqCst) {
+        return Ok(Vec::new());
+    }
+    let fds = (LISTEN_FDS_START..LISTEN_FDS_START + count)
+        // SAFETY: systemd保证LISTEN_FDS_START开始的count个文件描述符已打开且由当前进程独占，
+        // LISTEN_FDS_TAKEN保证每个文件描述符只被取出一次
+        .map(|fd| unsafe { OwnedFd::from_raw_fd(fd) })
+        .collect();
+    // OK
+    Ok(fds)
+}
+
+/// 取出套接字激活传入的第一个TCP监听套接字，不是套接字激活启动时返回None
+pub fn tcp_listener() -> anyhow::Result<Option<TcpListener>> {
+    let Some(fd) = listen_fds()?.into_iter().next() else {
+        return Ok(None);
+    };
+    let listener = TcpListener::from(fd);
+    // 传入的不是套接字时无法获取本地地址
+    listener
+        .local_addr()
+        .map_err(|err| anyhow::anyhow!("套接字激活传入的不是TCP监听套接字: {}", err))?;
+    trace_event!(info, addr = ?listener.local_addr().ok(), "使用systemd套接字激活的监听套接字");
+    // OK
+    Ok(Some(listener))
+}
+
+/// systemd服务单元文件
+///
+/// ```ignore
+/// let unit = UnitFile::new("raspi-sensor", vec!["/usr/local/bin/raspi-sensor".into(), "run".into()])
+///     .with_watchdog(Duration::from_secs(30))
+///     .with_socket("0.0.0.0:8080");
+/// unit.install(Path::new("/etc/systemd/system"))?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct UnitFile {
+    /// 单元名称（不含.service后缀）
+    pub name: String,
+    /// 说明
+    pub description: String,
+    /// 启动命令（第一个为可执行文件的绝对路径）
+    pub exec_start: Vec<String>,
+    /// 运行服务的用户（None为root）
+    pub user: Option<String>,
+    /// 工作目录
+    pub working_directory: Option<PathBuf>,
+    /// 服务看门狗超时时间（WatchdogSec=）
+    pub watchdog: Option<Duration>,
+    /// 异常退出后重启的等待时间
+    pub restart_delay: Duration,
+    /// 套接字激活的监听地址（设置后同时生成.socket单元）
+    pub listen: Option<String>,
+}
+
+impl UnitFile {
+    /// 创建实例
+    ///
+    /// - name: 单元名称（不含.service后缀）
+    /// - exec_start: 启动命令（第一个为可执行文件的绝对路径）
+    pub fn new(name: &str, exec_start: Vec<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            description: "树莓派传感器采集服务".to_string(),
+            exec_start,
+            user: None,
+            working_directory: None,
+            watchdog: None,
+            restart_delay: Duration::from_secs(5),
+            listen: None,
+        }
+    }
+
+    /// 设置说明
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    /// 以指定用户运行（需要加入gpio、i2c、spi等用户组）
+    pub fn with_user(mut self, user: &str) -> Self {
+        self.user = Some(user.to_string());
+        self
+    }
+
+    /// 设置工作目录（校准数据等相对路径以此为准）
+    pub fn with_working_directory<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.working_directory = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// 启用服务看门狗（服务需要在超时时间内发送WATCHDOG=1）
+    pub fn with_watchdog(mut self, timeout: Duration) -> Self {
+        self.watchdog = Some(timeout);
+        self
+    }
+
+    /// 设置异常退出后重启的等待时间
+    pub fn with_restart_delay(mut self, delay: Duration) -> Self {
+        self.restart_delay = delay;
+        self
+    }
+
+    /// 由systemd监听指定地址（如"0.0.0.0:8080"），连接到来时启动服务
+    pub fn with_socket(mut self, listen: &str) -> Self {
+        self.listen = Some(listen.to_string());
+        self
+    }
+
+    /// 服务单元文件名
+    pub fn service_name(&self) -> String {
+        format!("{}.service", self.name)
+    }
+
+    /// 套接字单元文件名
+    pub fn socket_name(&self) -> String {
+        format!("{}.socket", self.name)
+    }
+
+    /// 生成服务单元文件内容
+    pub fn service(&self) -> String {
+        let mut unit = String::new();
+        unit.push_str("[Unit]\n");
+        unit.push_str(&format!("Description={}\n", self.description));
+        unit.push_str("After=network.target\n");
+        if self.listen.is_some() {
+            unit.push_str(&format!("Requires={}\n", self.socket_name()));
+            unit.push_str(&format!("After={}\n", self.socket_name()));
+        }
+        unit.push_str("\n[Service]\n");
+        unit.push_str("Type=notify\n");
+        unit.push_str("NotifyAccess=main\n");
+        let command: Vec<String> = self.exec_start.iter().map(|arg| quote(arg)).collect();
+        unit.push_str(&format!("ExecStart={}\n", command.join(" ")));
+        if let Some(user) = &self.user {
+            unit.push_str(&format!("User={}\n", user));
+        }
+        if let Some(dir) = &self.working_directory {
+            unit.push_str(&format!(
+                "WorkingDirectory={}\n",
+                quote(&dir.to_string_lossy())
+            ));
+        }
+        if let Some(timeout) = self.watchdog {
+            unit.push_str(&format!("WatchdogSec={}ms\n", timeout.as_millis()));
+        }
+        unit.push_str("Restart=on-failure\n");
+        unit.push_str(&format!(
+            "RestartSec={}ms\n",
+            self.restart_delay.as_millis()
+        ));
+        unit.push_str("\n[Install]\n");
+        unit.push_str("WantedBy=multi-user.target\n");
+        unit
+    }
+
+    /// 生成套接字单元文件内容（没有设置监听地址时返回None）
+    pub fn socket(&self) -> Option<String> {
+        let listen = self.listen.as_ref()?;
+        Some(format!(
+            "[Unit]\nDescription={}（监听套接字）\n\n[Socket]\nListenStream={}\n\n[Install]\nWantedBy=sockets.target\n",
+            self.description, listen
+        ))
+    }
+
+    /// 写入单元文件（如/etc/systemd/system），返回写入的文件路径
+    ///
+    /// 写入后需要执行`systemctl daemon-reload`
+    pub fn install<P: AsRef<Path>>(&self, dir: P) -> anyhow::Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        let mut files = vec![(dir.join(self.service_name()), self.service())];
+        if let Some(socket) = self.socket() {
+            files.push((dir.join(self.socket_name()), socket));
+        }
+        for (path, content) in &files {
+            fs::write(path, content)
+                .map_err(|err| anyhow::anyhow!("写入单元文件{}失败: {}", path.display(), err))?;
+        }
+        // OK
+        Ok(files.into_iter().map(|(path, _)| path).collect())
+    }
+}
+
+/// 按systemd的规则转义命令行参数（包含空白或特殊字符时加双引号，%和$需要重复）
+fn quote(arg: &str) -> String {
+    let escaped = arg.replace('%', "%%").replace('$', "$$");
+    let special = |c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '\\' | ';');
+    if !escaped.is_empty() && !escaped.contains(special) {
+        return escaped;
+    }
+    let escaped = escaped.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{}\"", escaped)
+}
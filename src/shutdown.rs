@@ -0,0 +1,222 @@
+//! 优雅退出
+//!
+//! 程序被Ctrl+C或systemd停止时，进程直接结束会让步进电机线圈保持通电、继电器保持吸合，
+//! 对加热器、水泵等负载是危险的。`Shutdown`收集退出时需要执行的清理任务（停止调度器、
+//! 释放电机、继电器切到安全状态、称重芯片断电），在收到SIGINT/SIGTERM或`ShutdownGuard`
+//! 释放时按注册顺序执行一次
+
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use signal_hook::consts::{SIGINT, SIGTERM};
+use signal_hook::iterator::Signals;
+
+use crate::scheduler::SchedulerHandle;
+use crate::switch::Switch;
+
+/// 退出时需要切换到安全状态的设备
+pub trait SafeState: Send {
+    /// 切换到安全状态
+    fn safe_state(&mut self) -> anyhow::Result<()>;
+}
+
+#[cfg(feature = "uln2003a")]
+impl SafeState for crate::sensor::uln2003a::ULN2003A {
+    fn safe_state(&mut self) -> anyhow::Result<()> {
+        // 停止所有线圈并保存位置
+        self.release();
+        self.save_position()
+    }
+}
+
+#[cfg(feature = "hx711")]
+impl SafeState for crate::sensor::hx711::HX711 {
+    fn safe_state(&mut self) -> anyhow::Result<()> {
+        self.power_down();
+        Ok(())
+    }
+}
+
+#[cfg(feature = "hx711")]
+impl SafeState for crate::sensor::hx711::Hx711Array {
+    fn safe_state(&mut self) -> anyhow::Result<()> {
+        self.power_down();
+        Ok(())
+    }
+}
+
+#[cfg(feature = "dc-motor")]
+impl SafeState for crate::sensor::dc_motor::DcMotor {
+    fn safe_state(&mut self) -> anyhow::Result<()> {
+        // 滑行停止，避免制动时的冲击电流
+        self.coast()
+    }
+}
+
+#[cfg(feature = "dc-motor")]
+impl SafeState for crate::sensor::dc_motor::DifferentialDrive {
+    fn safe_state(&mut self) -> anyhow::Result<()> {
+        self.coast()
+    }
+}
+
+#[cfg(feature = "vibration-motor")]
+impl SafeState for crate::sensor::vibration_motor::VibrationMotor {
+    fn safe_state(&mut self) -> anyhow::Result<()> {
+        self.stop()
+    }
+}
+
+#[cfg(feature = "relay-board")]
+impl SafeState for crate::sensor::relay_board::RelayBoard {
+    fn safe_state(&mut self) -> anyhow::Result<()> {
+        self.all_off()
+    }
+}
+
+/// 清理任务
+type Task = Box<dyn FnOnce() -> anyhow::Result<()> + Send>;
+
+/// 共享状态
+struct Inner {
+    /// 是否已请求退出
+    requested: AtomicBool,
+    /// 清理任务（名称, 任务），执行后清空
+    tasks: Mutex<Vec<(String, Task)>>,
+}
+
+/// 退出协调器（可克隆，克隆后共享同一组清理任务）
+#[derive(Clone)]
+pub struct Shutdown {
+    inner: Arc<Inner>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shutdown {
+    /// 创建实例
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                requested: AtomicBool::new(false),
+                tasks: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// 注册清理任务
+    pub fn on_shutdown<F>(&self, name: &str, task: F) -> &Self
+    where
+        F: FnOnce() -> anyhow::Result<()> + Send + 'static,
+    {
+        if let Ok(mut tasks) = self.inner.tasks.lock() {
+            tasks.push((name.to_string(), Box::new(task)));
+        }
+        self
+    }
+
+    /// 退出时停止调度器并等待正在进行的读取完成
+    pub fn scheduler(&self, handle: SchedulerHandle) -> &Self {
+        self.on_shutdown("scheduler", move || {
+            handle.stop();
+            Ok(())
+        })
+    }
+
+    /// 退出时将设备切换到安全状态（释放电机、称重芯片断电等）
+    pub fn device<D: SafeState + 'static>(&self, name: &str, device: Arc<Mutex<D>>) -> &Self {
+        self.on_shutdown(name, move || {
+            // 持有锁的线程异常退出时仍然尝试切换到安全状态
+            let mut device = device
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            device.safe_state()
+        })
+    }
+
+    /// 退出时将开关切换到安全状态
+    ///
+    /// - safe_on: 安全状态是否为打开（如常开的散热风扇），加热器、水泵等传false
+    pub fn switch<W: Switch + 'static>(
+        &self,
+        name: &str,
+        switch: Arc<Mutex<W>>,
+        safe_on: bool,
+    ) -> &Self {
+        self.on_shutdown(name, move || {
+            let mut switch = switch
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            switch.set(safe_on)
+        })
+    }
+
+    /// 是否已请求退出（供主循环检查）
+    pub fn is_requested(&self) -> bool {
+        self.inner.requested.load(Ordering::SeqCst)
+    }
+
+    /// 执行所有清理任务（只执行一次，单个任务失败不影响后续任务）
+    pub fn run(&self) -> anyhow::Result<()> {
+        self.inner.requested.store(true, Ordering::SeqCst);
+        let tasks = match self.inner.tasks.lock() {
+            Ok(mut tasks) => std::mem::take(&mut *tasks),
+            Err(poisoned) => std::mem::take(&mut *poisoned.into_inner()),
+        };
+        let mut failed = Vec::new();
+        for (name, task) in tasks {
+            if let Err(err) = task() {
+                trace_event!(error, task = %name, error = %err, "退出清理任务失败");
+                failed.push(format!("{}: {}", name, err));
+            }
+        }
+        if !failed.is_empty() {
+            return Err(anyhow::anyhow!("退出清理任务失败: {}", failed.join("; ")));
+        }
+        // OK
+        Ok(())
+    }
+
+    /// 监听SIGINT/SIGTERM，收到信号后执行清理任务并结束进程（退出码为128+信号值）
+    pub fn listen(&self) -> anyhow::Result<JoinHandle<()>> {
+        let mut signals = Signals::new([SIGINT, SIGTERM])
+            .map_err(|err| anyhow::anyhow!("注册退出信号处理失败: {}", err))?;
+        let shutdown = self.clone();
+        // OK
+        Ok(thread::spawn(move || {
+            if let Some(signal) = signals.forever().next() {
+                trace_event!(info, signal, "收到退出信号");
+                if let Err(err) = shutdown.run() {
+                    eprintln!("{}", err);
+                }
+                process::exit(128 + signal);
+            }
+        }))
+    }
+
+    /// 创建守卫，守卫释放时（正常返回或panic展开）执行清理任务
+    pub fn guard(&self) -> ShutdownGuard {
+        ShutdownGuard {
+            shutdown: self.clone(),
+        }
+    }
+}
+
+/// 退出守卫
+pub struct ShutdownGuard {
+    shutdown: Shutdown,
+}
+
+impl Drop for ShutdownGuard {
+    fn drop(&mut self) {
+        if let Err(err) = self.shutdown.run() {
+            eprintln!("{}", err);
+        }
+    }
+}
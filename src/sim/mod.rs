@@ -0,0 +1,389 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::manager::Sensor;
+use crate::reading::{Quantity, Reading};
+
+pub mod replay;
+
+pub use replay::{Recorder, Replay};
+
+/// 伪随机数生成器（xorshift64*，同一种子产生相同的序列，便于测试复现）
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    /// 创建实例（种子为0时使用固定的非0种子）
+    fn new(seed: u64) -> Self {
+        Self(if seed == 0 {
+            0x9E37_79B9_7F4A_7C15
+        } else {
+            seed
+        })
+    }
+
+    /// 0.0~1.0的随机数
+    fn next_f64(&mut self) -> f64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        let value = self.0.wrapping_mul(0x2545_F491_4F6C_DD1D);
+        (value >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// -1.0~1.0的随机数
+    fn next_signed(&mut self) -> f64 {
+        self.next_f64() * 2.0 - 1.0
+    }
+}
+
+/// 模拟数据的波形
+#[derive(Debug, Clone, PartialEq)]
+pub enum Signal {
+    /// 固定值
+    Constant(f64),
+    /// 正弦波（如昼夜温度变化）
+    Sine {
+        /// 中心值
+        offset: f64,
+        /// 振幅
+        amplitude: f64,
+        /// 周期
+        period: Duration,
+    },
+    /// 随机游走（每次读取在上一次的基础上随机变化，限制在范围内）
+    RandomWalk {
+        /// 初始值
+        start: f64,
+        /// 每次最大变化量
+        step: f64,
+        /// 下限
+        min: f64,
+        /// 上限
+        max: f64,
+    },
+    /// 按时间回放数据点（相对开始时间的偏移，数值），回放结束后从头循环
+    Trace(Vec<(Duration, f64)>),
+}
+
+impl Signal {
+    /// 从CSV文件加载回放数据（格式与`FileLogger`的CSV相同：timestamp,sensor,quantity,value）
+    ///
+    /// 时间戳换算为相对第一个数据点的偏移
+    pub fn from_csv<P: AsRef<Path>>(
+        path: P,
+        sensor: &str,
+        quantity: Quantity,
+    ) -> anyhow::Result<Self> {
+        let traces = load_csv(path.as_ref())?;
+        let points = traces
+            .get(sensor)
+            .and_then(|quantities| quantities.get(&quantity))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "回放文件{}中没有传感器{}的{}数据",
+                    path.as_ref().display(),
+                    sensor,
+                    quantity
+                )
+            })?;
+        Ok(Signal::Trace(points.clone()))
+    }
+}
+
+/// CSV中的回放数据（传感器名称 -> 物理量 -> 数据点）
+type Traces = BTreeMap<String, BTreeMap<Quantity, Vec<(Duration, f64)>>>;
+
+/// 解析CSV回放文件
+fn load_csv(path: &Path) -> anyhow::Result<Traces> {
+    let text = fs::read_to_string(path)
+        .map_err(|err| anyhow::anyhow!("读取回放文件{}失败: {}", path.display(), err))?;
+    let mut rows = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("timestamp") {
+            continue;
+        }
+        let error = || anyhow::anyhow!("回放文件{}第{}行格式错误", path.display(), i + 1);
+        // 传感器名称可能带引号，数值在最后一列
+        let (timestamp, rest) = line.split_once(',').ok_or_else(error)?;
+        let (rest, value) = rest.rsplit_once(',').ok_or_else(error)?;
+        let (sensor, quantity) = rest.rsplit_once(',').ok_or_else(error)?;
+        let Some(quantity) = Quantity::from_name(quantity) else {
+            continue;
+        };
+        let timestamp: f64 = timestamp.parse().map_err(|_| error())?;
+        let value: f64 = value.parse().map_err(|_| error())?;
+        let sensor = sensor.trim_matches('"').replace("\"\"", "\"");
+        rows.push((timestamp, sensor, quantity, value));
+    }
+
+    let first = rows.iter().map(|row| row.0).fold(f64::INFINITY, f64::min);
+    let mut traces = Traces::new();
+    for (timestamp, sensor, quantity, value) in rows {
+        traces
+            .entry(sensor)
+            .or_default()
+            .entry(quantity)
+            .or_default()
+            .push((Duration::from_secs_f64(timestamp - first), value));
+    }
+    for points in traces.values_mut().flat_map(|q| q.values_mut()) {
+        points.sort_by_key(|point| point.0);
+    }
+    // OK
+    Ok(traces)
+}
+
+/// 单个物理量的模拟通道
+#[derive(Debug, Clone)]
+struct SimChannel {
+    quantity: Quantity,
+    signal: Signal,
+    /// 随机游走的当前值
+    current: Option<f64>,
+}
+
+impl SimChannel {
+    /// 计算指定时刻的值
+    fn sample(&mut self, elapsed: Duration, rng: &mut Rng) -> f64 {
+        match &self.signal {
+            Signal::Constant(value) => *value,
+            Signal::Sine {
+                offset,
+                amplitude,
+                period,
+            } => {
+                let period = period.as_secs_f64().max(f64::EPSILON);
+                let phase = elapsed.as_secs_f64() / period * std::f64::consts::TAU;
+                offset + amplitude * phase.sin()
+            }
+            Signal::RandomWalk {
+                start,
+                step,
+                min,
+                max,
+            } => {
+                let value = match self.current {
+                    Some(current) => (current + rng.next_signed() * step).clamp(*min, *max),
+                    None => *start,
+                };
+                self.current = Some(value);
+                value
+            }
+            Signal::Trace(points) => {
+                let Some(last) = points.last() else {
+                    return 0.0;
+                };
+                let total = last.0.as_secs_f64();
+                let t = if total > 0.0 {
+                    elapsed.as_secs_f64() % total
+                } else {
+                    0.0
+                };
+                // 取不晚于当前时刻的最后一个数据点
+                let index = points.partition_point(|(at, _)| at.as_secs_f64() <= t);
+                points[index.saturating_sub(1)].1
+            }
+        }
+    }
+}
+
+/// 模拟传感器
+///
+/// 实现了`Sensor`，可以和真实传感器一样注册到管理器、调度器，
+/// 用于在没有树莓派的开发机和CI中运行应用
+pub struct SimSensor {
+    /// 模拟的传感器型号
+    kind: &'static str,
+    /// 最小读取间隔
+    min_interval: Duration,
+    /// 各物理量的波形
+    channels: Vec<SimChannel>,
+    /// 模拟读取失败的概率（0.0~1.0）
+    failure_rate: f64,
+    /// 随机数生成器
+    rng: Rng,
+    /// 开始时间
+    started: Instant,
+}
+
+impl SimSensor {
+    /// 创建没有任何物理量的模拟传感器
+    pub fn new(kind: &'static str, min_interval: Duration) -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|t| t.as_nanos() as u64)
+            .unwrap_or_default();
+        Self {
+            kind,
+            min_interval,
+            channels: Vec::new(),
+            failure_rate: 0.0,
+            rng: Rng::new(seed),
+            started: Instant::now(),
+        }
+    }
+
+    /// 设置物理量的波形（已存在时覆盖）
+    pub fn with(mut self, quantity: Quantity, signal: Signal) -> Self {
+        self.channels.retain(|channel| channel.quantity != quantity);
+        self.channels.push(SimChannel {
+            quantity,
+            signal,
+            current: None,
+        });
+        self
+    }
+
+    /// 设置随机数种子（相同种子产生相同的随机游走和读取失败序列）
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = Rng::new(seed);
+        self
+    }
+
+    /// 设置模拟读取失败的概率（0.0~1.0）
+    pub fn with_failure_rate(mut self, failure_rate: f64) -> Self {
+        self.failure_rate = failure_rate.clamp(0.0, 1.0);
+        self
+    }
+}
+
+impl Sensor for SimSensor {
+    fn kind(&self) -> &'static str {
+        self.kind
+    }
+
+    fn min_interval(&self) -> Duration {
+        self.min_interval
+    }
+
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        if self.failure_rate > 0.0 && self.rng.next_f64() < self.failure_rate {
+            return Err(anyhow::anyhow!("模拟读取{}传感器失败", self.kind));
+        }
+        let elapsed = self.started.elapsed();
+        let mut reading = Reading::new();
+        for channel in &mut self.channels {
+            reading.set(channel.quantity, channel.sample(elapsed, &mut self.rng));
+        }
+        // OK
+        Ok(reading)
+    }
+}
+
+/// 模拟硬件后端
+///
+/// 按型号创建与真实驱动行为一致（相同的型号名称、物理量和最小读取间隔）的模拟传感器，
+/// 默认输出合理范围内的合成数据，可用`SimSensor::with`替换波形
+#[derive(Debug, Clone)]
+pub struct SimBackend {
+    /// 随机数生成器（为每个传感器派生种子）
+    rng: Rng,
+}
+
+impl Default for SimBackend {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl SimBackend {
+    /// 创建后端（相同种子创建的传感器产生相同的数据）
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// 派生一个传感器的种子
+    fn seed(&mut self) -> u64 {
+        (self.rng.next_f64() * u64::MAX as f64) as u64
+    }
+
+    /// 模拟DHT11温湿度传感器
+    pub fn dht11(&mut self) -> SimSensor {
+        self.temperature_humidity("dht11", Duration::from_secs(2))
+    }
+
+    /// 模拟AHT30温湿度传感器
+    pub fn aht30(&mut self) -> SimSensor {
+        self.temperature_humidity("aht30", Duration::from_millis(100))
+    }
+
+    /// 模拟BME280温湿度气压传感器
+    pub fn bme280(&mut self) -> SimSensor {
+        self.temperature_humidity("bme280", Duration::from_millis(100))
+            .with(
+                Quantity::Pressure,
+                Signal::RandomWalk {
+                    start: 101325.0,
+                    step: 5.0,
+                    min: 95000.0,
+                    max: 105000.0,
+                },
+            )
+    }
+
+    /// 模拟HX711称重ADC（10SPS）
+    pub fn hx711(&mut self) -> SimSensor {
+        self.weight_adc("hx711", Duration::from_millis(100))
+    }
+
+    /// 模拟NAU7802称重ADC
+    pub fn nau7802(&mut self) -> SimSensor {
+        self.weight_adc("nau7802", Duration::ZERO)
+    }
+
+    /// 按型号名称创建模拟传感器
+    pub fn sensor(&mut self, kind: &str) -> anyhow::Result<SimSensor> {
+        match kind {
+            "dht11" => Ok(self.dht11()),
+            "aht30" => Ok(self.aht30()),
+            "bme280" => Ok(self.bme280()),
+            "hx711" => Ok(self.hx711()),
+            "nau7802" => Ok(self.nau7802()),
+            _ => Err(anyhow::anyhow!("不支持模拟的传感器型号: {}", kind)),
+        }
+    }
+
+    /// 温湿度传感器（温度按1小时周期波动，湿度随机游走）
+    fn temperature_humidity(&mut self, kind: &'static str, min_interval: Duration) -> SimSensor {
+        SimSensor::new(kind, min_interval)
+            .with_seed(self.seed())
+            .with(
+                Quantity::Temperature,
+                Signal::Sine {
+                    offset: 22.0,
+                    amplitude: 3.0,
+                    period: Duration::from_secs(3600),
+                },
+            )
+            .with(
+                Quantity::Humidity,
+                Signal::RandomWalk {
+                    start: 50.0,
+                    step: 0.5,
+                    min: 20.0,
+                    max: 90.0,
+                },
+            )
+    }
+
+    /// 称重ADC（原始读数在空载附近随机游走）
+    fn weight_adc(&mut self, kind: &'static str, min_interval: Duration) -> SimSensor {
+        SimSensor::new(kind, min_interval)
+            .with_seed(self.seed())
+            .with(
+                Quantity::Raw,
+                Signal::RandomWalk {
+                    start: 8_000.0,
+                    step: 20.0,
+                    min: 7_000.0,
+                    max: 9_000.0,
+                },
+            )
+    }
+}
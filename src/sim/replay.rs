@@ -0,0 +1,418 @@
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, Operation};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::capture::SignalCapture;
+use crate::diagnostics::DiagnosticsReport;
+use crate::manager::Sensor;
+use crate::reading::{Quantity, Reading};
+
+/// 记录文件格式（每行一条记录，字段以空格分隔）：
+///
+/// - I2C传输：`i2c {通道} {地址} w:{十六进制} r:{十六进制} ...`，传输失败时以`err`结尾
+/// - 传感器读数：`reading {通道} {Unix时间戳} {物理量}={值} ...`，读取失败时为`reading {通道} {Unix时间戳} err`
+///
+/// 以`#`开头的行为注释
+const HEADER: &str = "# raspi-sensor replay v1\n";
+
+/// 十六进制编码
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 十六进制解码
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// 记录的一次I2C操作
+#[derive(Debug, Clone, PartialEq)]
+enum RecordedOp {
+    /// 写入的数据
+    Write(Vec<u8>),
+    /// 读取到的数据
+    Read(Vec<u8>),
+}
+
+/// 记录的一次I2C传输
+#[derive(Debug, Clone, PartialEq)]
+struct RecordedTransaction {
+    address: u8,
+    ops: Vec<RecordedOp>,
+    /// 传输是否失败
+    failed: bool,
+}
+
+/// 记录器
+///
+/// 真实运行时包装I2C总线和传感器，把原始传输和读数写入记录文件，
+/// 之后可以用`Replay`在开发机上通过同样的驱动回放（如复现BME280补偿计算的问题）。
+/// 多条总线和多个传感器可以共享同一个记录器，以通道名称区分
+#[derive(Clone)]
+pub struct Recorder {
+    out: Arc<Mutex<BufWriter<File>>>,
+}
+
+impl Recorder {
+    /// 创建记录文件（已存在时覆盖）
+    pub fn create<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .map_err(|err| anyhow::anyhow!("创建记录文件{}失败: {}", path.display(), err))?;
+        let mut out = BufWriter::new(file);
+        out.write_all(HEADER.as_bytes())?;
+        out.flush()?;
+        // OK
+        Ok(Self {
+            out: Arc::new(Mutex::new(out)),
+        })
+    }
+
+    /// 写入一行（立即刷新，避免程序崩溃时丢失记录）
+    fn write_line(&self, line: &str) {
+        if let Ok(mut out) = self.out.lock() {
+            let result = writeln!(out, "{}", line).and_then(|_| out.flush());
+            if let Err(err) = result {
+                eprintln!("写入记录文件失败: {}", err);
+            }
+        }
+    }
+
+    /// 包装I2C总线，记录所有传输
+    pub fn i2c<I: I2c>(&self, channel: &str, inner: I) -> RecordingI2c<I> {
+        RecordingI2c {
+            inner,
+            channel: channel.to_string(),
+            recorder: self.clone(),
+        }
+    }
+
+    /// 包装传感器，记录所有读数
+    pub fn sensor<S: Sensor>(&self, channel: &str, inner: S) -> RecordingSensor<S> {
+        RecordingSensor {
+            inner,
+            channel: channel.to_string(),
+            recorder: self.clone(),
+        }
+    }
+}
+
+/// 记录传输的I2C总线
+pub struct RecordingI2c<I> {
+    inner: I,
+    channel: String,
+    recorder: Recorder,
+}
+
+impl<I: I2c> RecordingI2c<I> {
+    /// 记录一次传输
+    fn record(&self, address: u8, ops: &[RecordedOp], failed: bool) {
+        let mut line = format!("i2c {} {:#04x}", self.channel, address);
+        for op in ops {
+            match op {
+                RecordedOp::Write(bytes) => line.push_str(&format!(" w:{}", to_hex(bytes))),
+                RecordedOp::Read(bytes) => line.push_str(&format!(" r:{}", to_hex(bytes))),
+            }
+        }
+        if failed {
+            line.push_str(" err");
+        }
+        self.recorder.write_line(&line);
+    }
+
+    /// 取回被包装的总线
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+
+impl<I: I2c> ErrorType for RecordingI2c<I> {
+    type Error = I::Error;
+}
+
+impl<I: I2c> I2c for RecordingI2c<I> {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let result = self.inner.transaction(address, operations);
+        let ops: Vec<RecordedOp> = operations
+            .iter()
+            .map(|op| match op {
+                Operation::Write(bytes) => RecordedOp::Write(bytes.to_vec()),
+                Operation::Read(buffer) => RecordedOp::Read(buffer.to_vec()),
+            })
+            .collect();
+        self.record(address, &ops, result.is_err());
+        result
+    }
+
+    fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Self::Error> {
+        let result = self.inner.read(address, read);
+        self.record(address, &[RecordedOp::Read(read.to_vec())], result.is_err());
+        result
+    }
+
+    fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+        let result = self.inner.write(address, write);
+        self.record(
+            address,
+            &[RecordedOp::Write(write.to_vec())],
+            result.is_err(),
+        );
+        result
+    }
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        let result = self.inner.write_read(address, write, read);
+        let ops = [
+            RecordedOp::Write(write.to_vec()),
+            RecordedOp::Read(read.to_vec()),
+        ];
+        self.record(address, &ops, result.is_err());
+        result
+    }
+}
+
+/// 记录读数的传感器
+pub struct RecordingSensor<S> {
+    inner: S,
+    channel: String,
+    recorder: Recorder,
+}
+
+impl<S: Sensor> Sensor for RecordingSensor<S> {
+    fn kind(&self) -> &'static str {
+        self.inner.kind()
+    }
+
+    fn min_interval(&self) -> Duration {
+        self.inner.min_interval()
+    }
+
+    fn self_test(&mut self) -> DiagnosticsReport {
+        self.inner.self_test()
+    }
+
+    fn signal_capture(&self) -> Option<SignalCapture> {
+        self.inner.signal_capture()
+    }
+
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        let result = self.inner.read();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|t| t.as_secs_f64())
+            .unwrap_or_default();
+        let mut line = format!("reading {} {:.3}", self.channel, timestamp);
+        match &result {
+            Ok(reading) => {
+                for (quantity, value) in reading.iter() {
+                    line.push_str(&format!(" {}={}", quantity.name(), value));
+                }
+            }
+            Err(_) => line.push_str(" err"),
+        }
+        self.recorder.write_line(&line);
+        result
+    }
+}
+
+/// 回放数据
+///
+/// 加载`Recorder`生成的记录文件，按通道创建回放的I2C总线和传感器
+#[derive(Debug, Clone, Default)]
+pub struct Replay {
+    /// I2C传输（通道 -> 传输序列）
+    i2c: BTreeMap<String, Vec<RecordedTransaction>>,
+    /// 读数（通道 -> 读数序列，None为读取失败）
+    readings: BTreeMap<String, Vec<Option<Reading>>>,
+}
+
+impl Replay {
+    /// 加载记录文件
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|err| anyhow::anyhow!("读取记录文件{}失败: {}", path.display(), err))?;
+        Self::parse(&text)
+            .map_err(|err| anyhow::anyhow!("解析记录文件{}失败: {}", path.display(), err))
+    }
+
+    /// 解析记录文本
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let mut replay = Self::default();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = || anyhow::anyhow!("第{}行格式错误: {}", i + 1, line);
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["i2c", channel, address, ops @ ..] => {
+                    let address = u8::from_str_radix(address.trim_start_matches("0x"), 16)
+                        .map_err(|_| error())?;
+                    let mut transaction = RecordedTransaction {
+                        address,
+                        ops: Vec::new(),
+                        failed: false,
+                    };
+                    for op in ops {
+                        match op.split_once(':') {
+                            Some(("w", hex)) => transaction
+                                .ops
+                                .push(RecordedOp::Write(from_hex(hex).ok_or_else(error)?)),
+                            Some(("r", hex)) => transaction
+                                .ops
+                                .push(RecordedOp::Read(from_hex(hex).ok_or_else(error)?)),
+                            None if *op == "err" => transaction.failed = true,
+                            _ => return Err(error()),
+                        }
+                    }
+                    replay
+                        .i2c
+                        .entry(channel.to_string())
+                        .or_default()
+                        .push(transaction);
+                }
+                ["reading", channel, _timestamp, values @ ..] => {
+                    let reading = if values == ["err"] {
+                        None
+                    } else {
+                        let mut reading = Reading::new();
+                        for value in values {
+                            let (name, value) = value.split_once('=').ok_or_else(error)?;
+                            let quantity = Quantity::from_name(name).ok_or_else(error)?;
+                            reading.set(quantity, value.parse().map_err(|_| error())?);
+                        }
+                        Some(reading)
+                    };
+                    replay
+                        .readings
+                        .entry(channel.to_string())
+                        .or_default()
+                        .push(reading);
+                }
+                _ => return Err(error()),
+            }
+        }
+        // OK
+        Ok(replay)
+    }
+
+    /// 记录中的I2C通道名称
+    pub fn i2c_channels(&self) -> Vec<String> {
+        self.i2c.keys().cloned().collect()
+    }
+
+    /// 记录中的传感器通道名称
+    pub fn sensor_channels(&self) -> Vec<String> {
+        self.readings.keys().cloned().collect()
+    }
+
+    /// 创建回放的I2C总线（可直接传给`Bme280Sensor`等驱动）
+    pub fn i2c(&self, channel: &str) -> anyhow::Result<ReplayI2c> {
+        let transactions = self
+            .i2c
+            .get(channel)
+            .ok_or_else(|| anyhow::anyhow!("记录中没有I2C通道: {}", channel))?;
+        Ok(ReplayI2c {
+            transactions: transactions.iter().cloned().collect(),
+        })
+    }
+
+    /// 创建回放的传感器
+    pub fn sensor(&self, channel: &str) -> anyhow::Result<ReplaySensor> {
+        let readings = self
+            .readings
+            .get(channel)
+            .ok_or_else(|| anyhow::anyhow!("记录中没有传感器通道: {}", channel))?;
+        Ok(ReplaySensor {
+            readings: readings.iter().cloned().collect(),
+        })
+    }
+}
+
+/// 回放的I2C总线
+///
+/// 按记录顺序逐个匹配传输：地址、操作类型和写入的数据必须与记录一致，
+/// 读取操作返回记录的数据；不一致或记录用完时返回错误
+#[derive(Debug, Clone)]
+pub struct ReplayI2c {
+    transactions: VecDeque<RecordedTransaction>,
+}
+
+impl ReplayI2c {
+    /// 剩余未回放的传输数量
+    pub fn remaining(&self) -> usize {
+        self.transactions.len()
+    }
+}
+
+impl ErrorType for ReplayI2c {
+    type Error = ErrorKind;
+}
+
+impl I2c for ReplayI2c {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let expected = self.transactions.pop_front().ok_or(ErrorKind::Other)?;
+        if expected.address != address || expected.ops.len() != operations.len() {
+            return Err(ErrorKind::Other);
+        }
+        for (op, recorded) in operations.iter_mut().zip(&expected.ops) {
+            match (op, recorded) {
+                (Operation::Write(bytes), RecordedOp::Write(data)) if *bytes == data.as_slice() => {
+                }
+                (Operation::Read(buffer), RecordedOp::Read(data)) if buffer.len() == data.len() => {
+                    buffer.copy_from_slice(data)
+                }
+                _ => return Err(ErrorKind::Other),
+            }
+        }
+        if expected.failed {
+            return Err(ErrorKind::Other);
+        }
+        Ok(())
+    }
+}
+
+/// 回放的传感器（按记录顺序返回读数，记录用完后返回错误）
+#[derive(Debug, Clone)]
+pub struct ReplaySensor {
+    readings: VecDeque<Option<Reading>>,
+}
+
+impl Sensor for ReplaySensor {
+    fn kind(&self) -> &'static str {
+        "replay"
+    }
+
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        match self.readings.pop_front() {
+            Some(Some(reading)) => Ok(reading),
+            Some(None) => Err(anyhow::anyhow!("回放记录的读取失败")),
+            None => Err(anyhow::anyhow!("回放记录已结束")),
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use std::io::ErrorKind;
+use std::net::UdpSocket;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::codec::cbor;
+use crate::reading::Sample;
+use crate::sink::Sink;
+
+/// CoAP消息类型
+mod kind {
+    pub const CONFIRMABLE: u8 = 0;
+    pub const NON_CONFIRMABLE: u8 = 1;
+    pub const ACKNOWLEDGEMENT: u8 = 2;
+    pub const RESET: u8 = 3;
+}
+
+/// CoAP选项编号
+mod option {
+    pub const URI_PATH: u16 = 11;
+    pub const CONTENT_FORMAT: u16 = 12;
+}
+
+/// POST请求码（0.02）
+const CODE_POST: u8 = 0x02;
+
+/// application/cbor的内容格式编号
+const CONTENT_FORMAT_CBOR: u8 = 60;
+
+/// CoAP上报配置
+#[derive(Debug, Clone)]
+pub struct CoapConfig {
+    /// 服务器地址（如"192.168.1.10:5683"）
+    pub endpoint: String,
+    /// 资源路径（如"telemetry/greenhouse"）
+    pub path: String,
+    /// 是否使用可确认消息（CON，等待服务器确认并重传），否则为不可确认消息（NON，发出即不管）
+    pub confirmable: bool,
+    /// 每批读数数量（攒够后一次发送）
+    pub batch_size: usize,
+    /// 第一次等待确认的时间（之后每次重传加倍）
+    pub ack_timeout: Duration,
+    /// 最大重传次数
+    pub max_retransmit: u32,
+}
+
+impl CoapConfig {
+    /// 创建默认配置（可确认消息，每批10个读数，确认超时2秒，最多重传4次）
+    ///
+    /// - endpoint: 服务器地址
+    /// - path: 资源路径
+    pub fn new(endpoint: &str, path: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            path: path.to_string(),
+            confirmable: true,
+            batch_size: 10,
+            ack_timeout: Duration::from_secs(2),
+            max_retransmit: 4,
+        }
+    }
+}
+
+/// CoAP遥测上报
+///
+/// 适合MQTT、HTTP过重的受限网络（如NB-IoT、Thread、低速无线链路）。读数以CBOR数组
+/// 批量编码（见`codec::cbor`）后用POST请求发送到配置的资源
+///
+/// 可确认消息按RFC 7252的指数退避重传，收到4.xx/5.xx响应或重传次数用完时写入失败，
+/// 这批读数被丢弃（不阻塞之后的读数）
+pub struct CoapSink {
+    /// 配置
+    config: CoapConfig,
+    /// UDP套接字（已连接到服务器）
+    socket: UdpSocket,
+    /// 下一个消息ID
+    message_id: u16,
+    /// 等待发送的读数
+    batch: Vec<Sample>,
+}
+
+impl CoapSink {
+    /// 创建实例（UDP无连接，这里只解析服务器地址）
+    pub fn new(config: CoapConfig) -> anyhow::Result<Self> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        socket
+            .connect(&config.endpoint)
+            .map_err(|err| anyhow::anyhow!("CoAP服务器地址无效: {}: {}", config.endpoint, err))?;
+        // 消息ID从随机值开始，避免重启后与服务器的去重缓存冲突
+        let message_id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|t| t.subsec_nanos() as u16)
+            .unwrap_or_default();
+        // OK
+        Ok(Self {
+            config,
+            socket,
+            message_id,
+            batch: Vec::new(),
+        })
+    }
+
+    /// 配置
+    pub fn config(&self) -> &CoapConfig {
+        &self.config
+    }
+
+    /// 写入一个读数（攒够一批后发送）
+    pub fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        self.batch.push(sample.clone());
+        if self.batch.len() >= self.config.batch_size.max(1) {
+            self.send_batch()?;
+        }
+        Ok(())
+    }
+
+    /// 立即发送等待中的读数
+    pub fn send_batch(&mut self) -> anyhow::Result<()> {
+        if self.batch.is_empty() {
+            return Ok(());
+        }
+        let batch = std::mem::take(&mut self.batch);
+        let payload = cbor::encode_batch(&batch);
+        let message_id = self.message_id;
+        self.message_id = self.message_id.wrapping_add(1);
+        let message = self.build_message(message_id, &payload);
+
+        if !self.config.confirmable {
+            self.socket.send(&message)?;
+            return Ok(());
+        }
+
+        let mut timeout = self.config.ack_timeout;
+        for _ in 0..=self.config.max_retransmit {
+            self.socket.send(&message)?;
+            if self.wait_ack(message_id, timeout)? {
+                return Ok(());
+            }
+            trace_event!(debug, message_id, "CoAP等待确认超时，重传");
+            timeout *= 2;
+        }
+        Err(anyhow::anyhow!(
+            "CoAP服务器{}没有确认（重传{}次），丢弃{}个读数",
+            self.config.endpoint,
+            self.config.max_retransmit,
+            batch.len()
+        ))
+    }
+
+    /// 等待确认，超时返回false
+    fn wait_ack(&self, message_id: u16, timeout: Duration) -> anyhow::Result<bool> {
+        let deadline = Instant::now() + timeout;
+        let mut buffer = [0u8; 1152];
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(false);
+            }
+            self.socket.set_read_timeout(Some(remaining))?;
+            let len = match self.socket.recv(&mut buffer) {
+                Ok(len) => len,
+                Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    return Ok(false);
+                }
+                Err(err) => return Err(err.into()),
+            };
+            // 忽略其他消息（如之前超时的确认）
+            if len < 4 || u16::from_be_bytes([buffer[2], buffer[3]]) != message_id {
+                continue;
+            }
+            let code = buffer[1];
+            match buffer[0] >> 4 & 0x03 {
+                kind::ACKNOWLEDGEMENT if code >> 5 >= 4 => {
+                    return Err(anyhow::anyhow!(
+                        "CoAP服务器拒绝上报: {}.{:02}",
+                        code >> 5,
+                        code & 0x1F
+                    ));
+                }
+                // 捎带响应（2.xx）或空确认（之后单独响应）
+                kind::ACKNOWLEDGEMENT => return Ok(true),
+                kind::RESET => return Err(anyhow::anyhow!("CoAP服务器重置了消息")),
+                _ => continue,
+            }
+        }
+    }
+
+    /// 组装POST请求（令牌为消息ID）
+    fn build_message(&self, message_id: u16, payload: &[u8]) -> Vec<u8> {
+        let kind = if self.config.confirmable {
+            kind::CONFIRMABLE
+        } else {
+            kind::NON_CONFIRMABLE
+        };
+        // 版本1、类型、令牌长度2
+        let mut message = vec![0x40 | kind << 4 | 2, CODE_POST];
+        message.extend_from_slice(&message_id.to_be_bytes());
+        message.extend_from_slice(&message_id.to_be_bytes());
+
+        let mut last = 0;
+        for segment in self.config.path.split('/').filter(|s| !s.is_empty()) {
+            encode_option(&mut message, option::URI_PATH - last, segment.as_bytes());
+            last = option::URI_PATH;
+        }
+        encode_option(
+            &mut message,
+            option::CONTENT_FORMAT - last,
+            &[CONTENT_FORMAT_CBOR],
+        );
+
+        message.push(0xFF);
+        message.extend_from_slice(payload);
+        message
+    }
+}
+
+impl Sink for CoapSink {
+    fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        CoapSink::write(self, sample)
+    }
+
+    fn flush(&mut self) -> anyhow::Result<()> {
+        self.send_batch()
+    }
+}
+
+/// 编码一个CoAP选项（选项编号增量、长度使用扩展格式）
+fn encode_option(message: &mut Vec<u8>, delta: u16, value: &[u8]) {
+    /// 4位字段的取值和扩展字节
+    fn nibble(value: usize) -> (u8, Vec<u8>) {
+        match value {
+            0..=12 => (value as u8, Vec::new()),
+            13..=268 => (13, vec![(value - 13) as u8]),
+            _ => (14, ((value - 269) as u16).to_be_bytes().to_vec()),
+        }
+    }
+    let (delta, delta_ext) = nibble(delta as usize);
+    let (length, length_ext) = nibble(value.len());
+    message.push(delta << 4 | length);
+    message.extend_from_slice(&delta_ext);
+    message.extend_from_slice(&length_ext);
+    message.extend_from_slice(value);
+}
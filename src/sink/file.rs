@@ -0,0 +1,256 @@
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::codec::cbor;
+use crate::reading::Sample;
+use crate::sink::Sink;
+
+/// 文件格式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileFormat {
+    /// CSV（每个物理量一行：timestamp,sensor,quantity,value）
+    Csv,
+    /// JSON Lines（每个读数一行JSON对象）
+    JsonLines,
+    /// CBOR序列（每个读数一个CBOR映射，见`codec::cbor`，用`cbor::decode_sequence`读取）
+    Cbor,
+}
+
+/// 文件轮转策略
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rotation {
+    /// 文件超过该大小（字节）时轮转
+    pub max_size: Option<u64>,
+    /// 文件写入超过该时长时轮转
+    pub max_age: Option<Duration>,
+    /// 保留的历史文件数量（0为不限）
+    pub keep: usize,
+    /// 是否压缩历史文件（gzip）
+    pub gzip: bool,
+}
+
+impl Default for Rotation {
+    /// 超过10MB或1天轮转，保留最近7个压缩后的历史文件
+    fn default() -> Self {
+        Self {
+            max_size: Some(10 * 1024 * 1024),
+            max_age: Some(Duration::from_secs(24 * 3600)),
+            keep: 7,
+            gzip: true,
+        }
+    }
+}
+
+/// 文件数据记录器
+///
+/// 最简单的数据记录方式，现场部署后直接用SCP把文件拷走即可。
+/// 轮转后的历史文件命名为`{文件名}.{Unix时间戳}.{扩展名}[.gz]`
+pub struct FileLogger {
+    /// 当前文件路径
+    path: PathBuf,
+    /// 文件格式
+    format: FileFormat,
+    /// 轮转策略（None为不轮转）
+    rotation: Option<Rotation>,
+    /// 当前文件
+    writer: BufWriter<File>,
+    /// 当前文件大小
+    size: u64,
+    /// 当前文件的打开时间
+    opened_at: SystemTime,
+}
+
+impl FileLogger {
+    /// 打开记录文件（已存在时追加写入）
+    pub fn new<P: AsRef<Path>>(path: P, format: FileFormat) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let (writer, size) = Self::open_file(&path, format)?;
+        // OK
+        Ok(Self {
+            path,
+            format,
+            rotation: Some(Rotation::default()),
+            writer,
+            size,
+            opened_at: SystemTime::now(),
+        })
+    }
+
+    /// 打开文件，新文件写入CSV表头
+    fn open_file(path: &Path, format: FileFormat) -> anyhow::Result<(BufWriter<File>, u64)> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|err| anyhow::anyhow!("打开记录文件{}失败: {}", path.display(), err))?;
+        let mut size = file.metadata()?.len();
+        let mut writer = BufWriter::new(file);
+        if size == 0 && format == FileFormat::Csv {
+            let header = "timestamp,sensor,quantity,value\n";
+            writer.write_all(header.as_bytes())?;
+            writer.flush()?;
+            size = header.len() as u64;
+        }
+        Ok((writer, size))
+    }
+
+    /// 设置轮转策略
+    pub fn set_rotation(&mut self, rotation: Option<Rotation>) {
+        self.rotation = rotation;
+    }
+
+    /// 当前文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 写入一个读数（每次写入后刷新到磁盘，避免断电丢失数据）
+    pub fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        if self.should_rotate() {
+            self.rotate()?;
+        }
+
+        let mut data = Vec::new();
+        match self.format {
+            FileFormat::Csv => {
+                let timestamp = sample.unix_timestamp();
+                for (quantity, value) in sample.reading.iter() {
+                    data.extend_from_slice(
+                        format!(
+                            "{:.3},{},{},{}\n",
+                            timestamp,
+                            csv_field(&sample.sensor),
+                            quantity.name(),
+                            value
+                        )
+                        .as_bytes(),
+                    );
+                }
+            }
+            FileFormat::JsonLines => {
+                data.extend_from_slice(sample.to_json().to_string().as_bytes());
+                data.push(b'\n');
+            }
+            FileFormat::Cbor => cbor::encode_into(&mut data, sample),
+        }
+        self.writer.write_all(&data)?;
+        self.writer.flush()?;
+        self.size += data.len() as u64;
+        Ok(())
+    }
+
+    /// 是否需要轮转
+    fn should_rotate(&self) -> bool {
+        let Some(rotation) = &self.rotation else {
+            return false;
+        };
+        let too_large = rotation.max_size.is_some_and(|max| self.size >= max);
+        let too_old = rotation
+            .max_age
+            .is_some_and(|max| self.opened_at.elapsed().is_ok_and(|elapsed| elapsed >= max));
+        too_large || too_old
+    }
+
+    /// 立即轮转：当前文件改名为历史文件并重新创建
+    pub fn rotate(&mut self) -> anyhow::Result<()> {
+        self.writer.flush()?;
+        let rotated = self.rotated_path(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|t| t.as_secs())
+                .unwrap_or_default(),
+        );
+        fs::rename(&self.path, &rotated)?;
+        let (writer, size) = Self::open_file(&self.path, self.format)?;
+        self.writer = writer;
+        self.size = size;
+        self.opened_at = SystemTime::now();
+
+        if let Some(rotation) = self.rotation {
+            if rotation.gzip {
+                gzip_file(&rotated)?;
+            }
+            if rotation.keep > 0 {
+                self.remove_old(rotation.keep)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 历史文件路径
+    fn rotated_path(&self, timestamp: u64) -> PathBuf {
+        let stem = self
+            .path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let name = match self.path.extension() {
+            Some(ext) => format!("{}.{}.{}", stem, timestamp, ext.to_string_lossy()),
+            None => format!("{}.{}", stem, timestamp),
+        };
+        self.path.with_file_name(name)
+    }
+
+    /// 删除超出保留数量的历史文件（按文件名中的时间戳从旧到新删除）
+    fn remove_old(&self, keep: usize) -> anyhow::Result<()> {
+        let dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let stem = self
+            .path
+            .file_stem()
+            .map(|s| format!("{}.", s.to_string_lossy()))
+            .unwrap_or_default();
+        let mut rotated: Vec<(u64, PathBuf)> = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let timestamp = name.strip_prefix(&stem)?.split('.').next()?.parse().ok()?;
+                Some((timestamp, entry.path()))
+            })
+            .collect();
+        rotated.sort();
+        let excess = rotated.len().saturating_sub(keep);
+        for (_, path) in rotated.into_iter().take(excess) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+/// CSV字段转义（包含逗号、引号或换行时加引号）
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 压缩文件为`.gz`并删除原文件
+fn gzip_file(path: &Path) -> anyhow::Result<()> {
+    let mut gz_name = path.as_os_str().to_owned();
+    gz_name.push(".gz");
+    let mut input = File::open(path)?;
+    let mut encoder = GzEncoder::new(File::create(&gz_name)?, Compression::default());
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?;
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+impl Sink for FileLogger {
+    fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        FileLogger::write(self, sample)
+    }
+
+    fn flush(&mut self) -> anyhow::Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::thread::{self, JoinHandle};
+
+use crate::reading::Sample;
+
+#[cfg(feature = "coap-sink")]
+pub mod coap;
+#[cfg(feature = "file-sink")]
+pub mod file;
+#[cfg(feature = "spool")]
+pub mod spool;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+/// 读数输出（数据记录、网络发布等）
+pub trait Sink: Send {
+    /// 写入一个读数
+    fn write(&mut self, sample: &Sample) -> anyhow::Result<()>;
+
+    /// 刷新缓冲（管道关闭时调用）
+    fn flush(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// 打印到控制台
+#[derive(Debug, Default)]
+pub struct ConsoleSink;
+
+impl Sink for ConsoleSink {
+    fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        println!("{}: {}", sample.sensor, sample.reading);
+        Ok(())
+    }
+}
+
+/// 输出队列已满时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backpressure {
+    /// 等待队列空出（慢速输出会拖慢所有输出）
+    Block,
+    /// 丢弃新读数并计数（默认）
+    DropNewest,
+}
+
+/// 单个输出的统计
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SinkStats {
+    /// 写入成功的读数数量
+    pub written: u64,
+    /// 写入失败的读数数量
+    pub errors: u64,
+    /// 队列已满被丢弃的读数数量
+    pub dropped: u64,
+}
+
+/// 输出线程和分发线程共享的计数
+#[derive(Default)]
+struct Counters {
+    written: AtomicU64,
+    errors: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl Counters {
+    /// 当前统计
+    fn snapshot(&self) -> SinkStats {
+        SinkStats {
+            written: self.written.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// 待启动的输出
+struct Stage {
+    name: String,
+    sink: Box<dyn Sink>,
+    capacity: usize,
+    backpressure: Backpressure,
+}
+
+/// 读数分发管道
+///
+/// - 每个输出在独立线程中写入，一个输出写入失败或阻塞不影响其他输出
+/// - 每个输出有独立的有界队列，队列满时按`Backpressure`处理
+/// - 输入通道关闭（如调度器停止）后，各输出写完队列中的读数并刷新后退出
+#[derive(Default)]
+pub struct Pipeline {
+    stages: Vec<Stage>,
+}
+
+impl Pipeline {
+    /// 创建空管道
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加输出（队列容量256，队列满时丢弃新读数）
+    pub fn add<S: Sink + 'static>(&mut self, name: &str, sink: S) -> &mut Self {
+        self.add_with(name, sink, 256, Backpressure::DropNewest)
+    }
+
+    /// 添加输出并指定队列容量和队列满时的处理方式
+    pub fn add_with<S: Sink + 'static>(
+        &mut self,
+        name: &str,
+        sink: S,
+        capacity: usize,
+        backpressure: Backpressure,
+    ) -> &mut Self {
+        self.stages.push(Stage {
+            name: name.to_string(),
+            sink: Box::new(sink),
+            capacity: capacity.max(1),
+            backpressure,
+        });
+        self
+    }
+
+    /// 启动管道，从输入通道（如调度器的订阅）读取读数并分发到所有输出
+    pub fn start(self, samples: Receiver<Sample>) -> PipelineHandle {
+        let mut outputs: Vec<(SyncSender<Sample>, Backpressure, Arc<Counters>)> = Vec::new();
+        let mut stats = Vec::new();
+        let mut threads = Vec::new();
+
+        for stage in self.stages {
+            let (tx, rx) = mpsc::sync_channel::<Sample>(stage.capacity);
+            let counters = Arc::new(Counters::default());
+            outputs.push((tx, stage.backpressure, counters.clone()));
+            stats.push((stage.name.clone(), counters.clone()));
+
+            let Stage { name, mut sink, .. } = stage;
+            threads.push(thread::spawn(move || {
+                for sample in rx {
+                    match sink.write(&sample) {
+                        Ok(()) => {
+                            counters.written.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Err(err) => {
+                            counters.errors.fetch_add(1, Ordering::Relaxed);
+                            eprintln!("输出{}写入传感器{}的读数失败: {}", name, sample.sensor, err);
+                        }
+                    }
+                }
+                if let Err(err) = sink.flush() {
+                    eprintln!("输出{}刷新失败: {}", name, err);
+                }
+            }));
+        }
+
+        // 分发线程
+        threads.push(thread::spawn(move || {
+            for sample in samples {
+                // 已退出（如写入时panic）的输出不再分发
+                outputs.retain(|(tx, backpressure, counters)| {
+                    let result = match backpressure {
+                        Backpressure::Block => tx.send(sample.clone()).map_err(|_| ()),
+                        Backpressure::DropNewest => match tx.try_send(sample.clone()) {
+                            Err(TrySendError::Full(_)) => {
+                                counters.dropped.fetch_add(1, Ordering::Relaxed);
+                                Ok(())
+                            }
+                            Err(TrySendError::Disconnected(_)) => Err(()),
+                            Ok(()) => Ok(()),
+                        },
+                    };
+                    result.is_ok()
+                });
+            }
+        }));
+
+        PipelineHandle { stats, threads }
+    }
+}
+
+/// 运行中的管道
+pub struct PipelineHandle {
+    /// 各输出的统计
+    stats: Vec<(String, Arc<Counters>)>,
+    /// 分发线程和输出线程
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl PipelineHandle {
+    /// 各输出的统计
+    pub fn stats(&self) -> Vec<(String, SinkStats)> {
+        self.stats
+            .iter()
+            .map(|(name, counters)| (name.clone(), counters.snapshot()))
+            .collect()
+    }
+
+    /// 等待管道结束（输入通道关闭且所有输出写完）
+    pub fn join(self) {
+        for thread in self.threads {
+            let _ = thread.join();
+        }
+    }
+}
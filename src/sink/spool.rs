@@ -0,0 +1,441 @@
+//! 离线暂存（存储转发）
+//!
+//! 网络输出（MQTT、CoAP等）写入失败时，读数暂存到固定大小的环形文件中，网络恢复后按顺序补发。
+//! 文件大小固定，写满后覆盖最早的读数，长时间断网也不会写满SD卡
+//!
+//! 文件格式（多字节数值为小端）：
+//!
+//! - 文件头（32字节）：魔数`RSQ1`、数据区大小、队首位置、队首序号、CRC32
+//! - 数据区（环形）：每个读数一帧，帧头为同步字`0xA5 0x5A`、长度（2字节）、序号（4字节）、
+//!   CRC32（4字节，覆盖序号和内容），内容为CBOR编码的读数（见`codec::cbor`）
+//!
+//! 只有出队时更新文件头，入队只追加数据帧。打开文件时从队首开始按序号扫描有效的帧，
+//! 断电时写了一半的帧、损坏的帧会被跳过（按同步字重新对齐），不影响其他读数
+
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::codec::cbor;
+use crate::reading::Sample;
+use crate::sink::Sink;
+
+/// 文件头魔数
+const MAGIC: &[u8; 4] = b"RSQ1";
+
+/// 文件头长度
+const HEADER_LEN: u64 = 32;
+
+/// 帧同步字
+const SYNC: [u8; 2] = [0xA5, 0x5A];
+
+/// 帧头长度
+const FRAME_HEADER_LEN: usize = 12;
+
+/// 数据区最小大小
+const MIN_CAPACITY: u64 = 4096;
+
+/// CRC-32（IEEE 802.3，多项式0xEDB88320）
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// 读取并校验一帧（序号小于`min_sequence`的为旧数据）
+///
+/// - read: 从数据区的逻辑位置读取（跨越数据区末尾时回到开头）
+fn parse_frame<R: Fn(u64, &mut [u8])>(read: R, position: u64, min_sequence: u32) -> Option<Frame> {
+    let mut header = [0u8; FRAME_HEADER_LEN];
+    read(position, &mut header);
+    if header[..2] != SYNC {
+        return None;
+    }
+    let len = u16::from_le_bytes([header[2], header[3]]) as usize;
+    let sequence = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    let crc = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+    if sequence.wrapping_sub(min_sequence) > u32::MAX / 2 {
+        return None;
+    }
+    let mut body = vec![0u8; 4 + len];
+    body[..4].copy_from_slice(&header[4..8]);
+    read(position + FRAME_HEADER_LEN as u64, &mut body[4..]);
+    if crc32(&body) != crc {
+        return None;
+    }
+    Some(Frame {
+        position,
+        len: (FRAME_HEADER_LEN + len) as u64,
+        sequence,
+    })
+}
+
+/// 队列中的一帧
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    /// 起始位置（逻辑位置，单调递增，对数据区大小取余为文件中的位置）
+    position: u64,
+    /// 帧长度
+    len: u64,
+    /// 序号
+    sequence: u32,
+}
+
+/// 文件中的固定大小环形队列
+///
+/// ```ignore
+/// let mut queue = DiskQueue::open("/var/lib/raspi-sensor/spool.bin", 8 * 1024 * 1024)?;
+/// queue.push(&sample)?;
+/// while let Some(sample) = queue.peek()? {
+///     upload(&sample)?;
+///     queue.pop()?;
+/// }
+/// ```
+pub struct DiskQueue {
+    /// 文件
+    file: File,
+    /// 数据区大小
+    capacity: u64,
+    /// 有效的帧（从队首到队尾）
+    frames: VecDeque<Frame>,
+    /// 队首位置（队列为空时等于队尾位置）
+    head: u64,
+    /// 下一帧的位置
+    tail: u64,
+    /// 下一帧的序号
+    next_sequence: u32,
+    /// 写满后被覆盖的读数数量
+    overwritten: u64,
+}
+
+impl DiskQueue {
+    /// 打开队列文件（不存在或文件头损坏时新建）
+    ///
+    /// - path: 文件路径
+    /// - capacity: 数据区大小（字节，至少4096），与已有文件不同时以已有文件为准
+    pub fn open<P: AsRef<Path>>(path: P, capacity: u64) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .map_err(|err| anyhow::anyhow!("打开暂存文件{}失败: {}", path.display(), err))?;
+
+        let mut header = [0u8; HEADER_LEN as usize];
+        let valid = file.read_exact_at(&mut header, 0).is_ok()
+            && &header[..4] == MAGIC
+            && crc32(&header[..28]) == u32::from_le_bytes(header[28..32].try_into()?);
+        let mut this = Self {
+            file,
+            capacity: capacity.max(MIN_CAPACITY),
+            frames: VecDeque::new(),
+            head: 0,
+            tail: 0,
+            next_sequence: 0,
+            overwritten: 0,
+        };
+        if valid {
+            this.capacity = u64::from_le_bytes(header[4..12].try_into()?);
+            this.head = u64::from_le_bytes(header[12..20].try_into()?);
+            this.next_sequence = u32::from_le_bytes(header[20..24].try_into()?);
+            this.scan()?;
+        } else {
+            if this.file.metadata()?.len() > 0 {
+                eprintln!("暂存文件{}的文件头损坏，重新创建", path.display());
+            }
+            this.file.set_len(HEADER_LEN + this.capacity)?;
+            this.write_header()?;
+        }
+        trace_event!(info, frames = this.frames.len(), "打开暂存文件");
+        // OK
+        Ok(this)
+    }
+
+    /// 从队首开始扫描有效的帧（序号必须递增，跳过损坏的数据）
+    fn scan(&mut self) -> anyhow::Result<()> {
+        // 逐字节重新对齐时读取次数很多，先把整个数据区读入内存
+        let mut data = vec![0u8; self.capacity as usize];
+        self.file.read_exact_at(&mut data, HEADER_LEN)?;
+        let read = |position: u64, buffer: &mut [u8]| {
+            for (i, byte) in buffer.iter_mut().enumerate() {
+                *byte = data[((position + i as u64) % self.capacity) as usize];
+            }
+        };
+
+        let end = self.head + self.capacity;
+        let mut position = self.head;
+        let mut min_sequence = self.next_sequence;
+        self.tail = self.head;
+        while position + FRAME_HEADER_LEN as u64 <= end {
+            match parse_frame(read, position, min_sequence) {
+                Some(frame) if position + frame.len <= end => {
+                    position += frame.len;
+                    min_sequence = frame.sequence.wrapping_add(1);
+                    self.tail = position;
+                    self.next_sequence = min_sequence;
+                    self.frames.push_back(frame);
+                }
+                // 重新对齐：从下一个字节开始查找同步字
+                _ => position += 1,
+            }
+        }
+        if let Some(first) = self.frames.front() {
+            self.head = first.position;
+        }
+        Ok(())
+    }
+
+    /// 读取数据区（跨越数据区末尾时分两次读取）
+    fn read_data(&self, position: u64, buffer: &mut [u8]) -> std::io::Result<()> {
+        let offset = position % self.capacity;
+        let first = buffer.len().min((self.capacity - offset) as usize);
+        let (a, b) = buffer.split_at_mut(first);
+        self.file.read_exact_at(a, HEADER_LEN + offset)?;
+        if !b.is_empty() {
+            self.file.read_exact_at(b, HEADER_LEN)?;
+        }
+        Ok(())
+    }
+
+    /// 写入数据区（跨越数据区末尾时分两次写入）
+    fn write_data(&self, position: u64, data: &[u8]) -> std::io::Result<()> {
+        let offset = position % self.capacity;
+        let first = data.len().min((self.capacity - offset) as usize);
+        self.file
+            .write_all_at(&data[..first], HEADER_LEN + offset)?;
+        if first < data.len() {
+            self.file.write_all_at(&data[first..], HEADER_LEN)?;
+        }
+        Ok(())
+    }
+
+    /// 写入文件头
+    fn write_header(&self) -> anyhow::Result<()> {
+        let sequence = self
+            .frames
+            .front()
+            .map(|frame| frame.sequence)
+            .unwrap_or(self.next_sequence);
+        let mut header = [0u8; HEADER_LEN as usize];
+        header[..4].copy_from_slice(MAGIC);
+        header[4..12].copy_from_slice(&self.capacity.to_le_bytes());
+        header[12..20].copy_from_slice(&self.head.to_le_bytes());
+        header[20..24].copy_from_slice(&sequence.to_le_bytes());
+        let crc = crc32(&header[..28]);
+        header[28..32].copy_from_slice(&crc.to_le_bytes());
+        self.file.write_all_at(&header, 0)?;
+        Ok(())
+    }
+
+    /// 读数数量
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// 是否为空
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// 已使用的字节数
+    pub fn used(&self) -> u64 {
+        self.tail - self.head
+    }
+
+    /// 写满后被覆盖的读数数量（本次打开以来）
+    pub fn overwritten(&self) -> u64 {
+        self.overwritten
+    }
+
+    /// 读数加入队尾（空间不足时覆盖最早的读数）
+    pub fn push(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        let payload = cbor::encode(sample);
+        let len = (FRAME_HEADER_LEN + payload.len()) as u64;
+        if payload.len() > u16::MAX as usize || len > self.capacity {
+            return Err(anyhow::anyhow!("读数过大，无法暂存: {}字节", payload.len()));
+        }
+
+        let mut dropped = false;
+        while self.tail + len - self.head > self.capacity {
+            let Some(frame) = self.frames.pop_front() else {
+                break;
+            };
+            self.head = frame.position + frame.len;
+            self.overwritten += 1;
+            dropped = true;
+        }
+        if self.frames.is_empty() {
+            self.head = self.tail;
+        }
+
+        let sequence = self.next_sequence;
+        let mut frame = Vec::with_capacity(len as usize);
+        frame.extend_from_slice(&SYNC);
+        frame.extend_from_slice(&(payload.len() as u16).to_le_bytes());
+        frame.extend_from_slice(&sequence.to_le_bytes());
+        let mut body = sequence.to_le_bytes().to_vec();
+        body.extend_from_slice(&payload);
+        frame.extend_from_slice(&crc32(&body).to_le_bytes());
+        frame.extend_from_slice(&payload);
+        self.write_data(self.tail, &frame)?;
+
+        self.frames.push_back(Frame {
+            position: self.tail,
+            len,
+            sequence,
+        });
+        self.tail += len;
+        self.next_sequence = sequence.wrapping_add(1);
+        if dropped {
+            self.write_header()?;
+        }
+        Ok(())
+    }
+
+    /// 读取队首的读数（不出队）
+    pub fn peek(&mut self) -> anyhow::Result<Option<Sample>> {
+        while let Some(frame) = self.frames.front().copied() {
+            let mut payload = vec![0u8; frame.len as usize - FRAME_HEADER_LEN];
+            self.read_data(frame.position + FRAME_HEADER_LEN as u64, &mut payload)?;
+            match cbor::decode(&payload) {
+                Ok((sample, _)) => return Ok(Some(sample)),
+                Err(err) => {
+                    eprintln!(
+                        "暂存文件中序号{}的读数无法解码，已丢弃: {}",
+                        frame.sequence, err
+                    );
+                    self.pop()?;
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// 队首的读数出队
+    pub fn pop(&mut self) -> anyhow::Result<()> {
+        if self.frames.pop_front().is_some() {
+            self.head = self
+                .frames
+                .front()
+                .map(|frame| frame.position)
+                .unwrap_or(self.tail);
+            self.write_header()?;
+        }
+        Ok(())
+    }
+}
+
+/// 带离线暂存的输出
+///
+/// 写入失败时读数加入暂存队列并返回成功（读数没有丢失）；暂存队列不为空时新读数也先排队，
+/// 保证补发顺序。每次写入时如果距离上次失败超过重试间隔，先按顺序补发暂存的读数
+///
+/// 实际的输出需要在写入时立即发送（如CoAP的批量大小设为1），否则输出内部缓冲的读数
+/// 发送失败时无法转入暂存
+///
+/// ```ignore
+/// let mqtt = MqttPublisher::connect(config)?;
+/// let queue = DiskQueue::open("/var/lib/raspi-sensor/mqtt.spool", 8 * 1024 * 1024)?;
+/// pipeline.add("mqtt", StoreAndForward::new(mqtt, queue));
+/// ```
+pub struct StoreAndForward<S> {
+    /// 实际的输出
+    inner: S,
+    /// 暂存队列
+    queue: DiskQueue,
+    /// 重试间隔
+    retry_interval: Duration,
+    /// 上次写入失败的时间
+    last_failure: Option<Instant>,
+}
+
+impl<S: Sink> StoreAndForward<S> {
+    /// 创建实例（重试间隔30秒）
+    pub fn new(inner: S, queue: DiskQueue) -> Self {
+        Self {
+            inner,
+            queue,
+            retry_interval: Duration::from_secs(30),
+            last_failure: None,
+        }
+    }
+
+    /// 设置重试间隔
+    pub fn with_retry_interval(mut self, interval: Duration) -> Self {
+        self.retry_interval = interval;
+        self
+    }
+
+    /// 实际的输出
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// 暂存队列
+    pub fn queue(&self) -> &DiskQueue {
+        &self.queue
+    }
+
+    /// 是否在重试间隔内（刚失败过，暂不尝试写入）
+    fn backing_off(&self) -> bool {
+        self.last_failure
+            .is_some_and(|at| at.elapsed() < self.retry_interval)
+    }
+
+    /// 按顺序补发暂存的读数，返回队列是否已经清空
+    pub fn drain(&mut self) -> anyhow::Result<bool> {
+        if self.backing_off() {
+            return Ok(self.queue.is_empty());
+        }
+        let mut sent = 0;
+        while let Some(sample) = self.queue.peek()? {
+            if let Err(err) = self.inner.write(&sample) {
+                trace_event!(warn, error = %err, "补发暂存的读数失败");
+                eprintln!("补发暂存的读数失败（剩余{}个）: {}", self.queue.len(), err);
+                self.last_failure = Some(Instant::now());
+                return Ok(false);
+            }
+            self.queue.pop()?;
+            sent += 1;
+        }
+        if sent > 0 {
+            trace_event!(info, sent, "暂存的读数已全部补发");
+            self.last_failure = None;
+        }
+        Ok(true)
+    }
+}
+
+impl<S: Sink> Sink for StoreAndForward<S> {
+    fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        if self.drain()? && !self.backing_off() {
+            match self.inner.write(sample) {
+                Ok(()) => return Ok(()),
+                Err(err) => {
+                    eprintln!("输出写入失败，读数转入暂存: {}", err);
+                    self.last_failure = Some(Instant::now());
+                }
+            }
+        }
+        self.queue.push(sample)
+    }
+
+    fn flush(&mut self) -> anyhow::Result<()> {
+        self.last_failure = None;
+        self.drain()?;
+        self.inner.flush()
+    }
+}
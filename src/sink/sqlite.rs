@@ -0,0 +1,221 @@
+use rusqlite::{Connection, params};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::reading::{Quantity, Reading, Sample};
+use crate::sink::Sink;
+
+/// 自动创建的表结构（每个物理量一行，bucket为0表示原始读数，否则为降采样的时间段长度）
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS samples (
+    id        INTEGER PRIMARY KEY AUTOINCREMENT,
+    sensor    TEXT    NOT NULL,
+    timestamp REAL    NOT NULL,
+    quantity  TEXT    NOT NULL,
+    value     REAL    NOT NULL,
+    bucket    INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS idx_samples_sensor_time ON samples (sensor, timestamp);
+";
+
+/// 历史数据保留策略
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetentionPolicy {
+    /// 原始读数保留时长，超过后降采样
+    pub raw: Duration,
+    /// 降采样的时间段长度（每段每个物理量保留一个平均值）
+    pub bucket: Duration,
+    /// 所有数据的最长保留时长，超过后删除
+    pub max_age: Duration,
+    /// 自动执行保留策略的间隔
+    pub interval: Duration,
+}
+
+impl Default for RetentionPolicy {
+    /// 原始读数保留7天，之后按10分钟降采样，最多保留1年，每小时整理一次
+    fn default() -> Self {
+        Self {
+            raw: Duration::from_secs(7 * 24 * 3600),
+            bucket: Duration::from_secs(600),
+            max_age: Duration::from_secs(365 * 24 * 3600),
+            interval: Duration::from_secs(3600),
+        }
+    }
+}
+
+/// 系统时间转换为Unix时间戳（秒）
+fn unix_seconds(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|t| t.as_secs_f64())
+        .unwrap_or_default()
+}
+
+/// SQLite本地数据记录器
+///
+/// 离线部署时在本地保存历史读数，无需外部数据库
+pub struct SqliteLogger {
+    /// 数据库连接
+    conn: Connection,
+    /// 保留策略（None为永久保留原始读数）
+    retention: Option<RetentionPolicy>,
+    /// 上一次执行保留策略的时间
+    last_maintain: Instant,
+}
+
+impl SqliteLogger {
+    /// 打开数据库文件（不存在时自动创建）
+    pub fn open<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let conn = Connection::open(path)
+            .map_err(|err| anyhow::anyhow!("打开数据库{}失败: {}", path.display(), err))?;
+        Self::with_connection(conn)
+    }
+
+    /// 使用内存数据库（程序退出后数据丢失）
+    pub fn open_in_memory() -> anyhow::Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    /// 初始化表结构
+    fn with_connection(conn: Connection) -> anyhow::Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        // OK
+        Ok(Self {
+            conn,
+            retention: Some(RetentionPolicy::default()),
+            last_maintain: Instant::now(),
+        })
+    }
+
+    /// 设置保留策略
+    pub fn set_retention(&mut self, retention: Option<RetentionPolicy>) {
+        self.retention = retention;
+    }
+
+    /// 写入一个读数（到达整理间隔时顺便执行保留策略）
+    pub fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        let timestamp = sample.unix_timestamp();
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO samples (sensor, timestamp, quantity, value) VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for (quantity, value) in sample.reading.iter() {
+                stmt.execute(params![sample.sensor, timestamp, quantity.name(), value])?;
+            }
+        }
+        tx.commit()?;
+
+        if let Some(retention) = self.retention
+            && self.last_maintain.elapsed() >= retention.interval
+        {
+            self.apply_retention(&retention)?;
+        }
+        Ok(())
+    }
+
+    /// 执行保留策略：先降采样，再删除过期数据
+    pub fn apply_retention(&mut self, retention: &RetentionPolicy) -> anyhow::Result<()> {
+        self.downsample(retention.raw, retention.bucket)?;
+        self.prune(retention.max_age)?;
+        self.last_maintain = Instant::now();
+        Ok(())
+    }
+
+    /// 将早于指定时长的原始读数按时间段求平均值
+    ///
+    /// 返回被合并的原始读数行数
+    pub fn downsample(&mut self, older_than: Duration, bucket: Duration) -> anyhow::Result<usize> {
+        let bucket = bucket.as_secs().max(1) as i64;
+        let cutoff = unix_seconds(SystemTime::now()) - older_than.as_secs_f64();
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO samples (sensor, timestamp, quantity, value, bucket)
+             SELECT sensor, CAST(timestamp / ?1 AS INTEGER) * ?1, quantity, AVG(value), ?1
+             FROM samples
+             WHERE bucket = 0 AND timestamp < ?2
+             GROUP BY sensor, quantity, CAST(timestamp / ?1 AS INTEGER)",
+            params![bucket, cutoff],
+        )?;
+        let merged = tx.execute(
+            "DELETE FROM samples WHERE bucket = 0 AND timestamp < ?1",
+            params![cutoff],
+        )?;
+        tx.commit()?;
+        // OK
+        Ok(merged)
+    }
+
+    /// 删除早于指定时长的所有数据，返回删除的行数
+    pub fn prune(&mut self, older_than: Duration) -> anyhow::Result<usize> {
+        let cutoff = unix_seconds(SystemTime::now()) - older_than.as_secs_f64();
+        Ok(self
+            .conn
+            .execute("DELETE FROM samples WHERE timestamp < ?1", params![cutoff])?)
+    }
+
+    /// 有记录的传感器名称
+    pub fn sensors(&self) -> anyhow::Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT DISTINCT sensor FROM samples ORDER BY sensor")?;
+        let names = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(names)
+    }
+
+    /// 查询指定传感器在时间范围内的读数（按时间先后，降采样后的数据为时间段内的平均值）
+    pub fn range(
+        &self,
+        sensor: &str,
+        from: SystemTime,
+        to: SystemTime,
+    ) -> anyhow::Result<Vec<Sample>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT timestamp, quantity, value FROM samples
+             WHERE sensor = ?1 AND timestamp >= ?2 AND timestamp <= ?3
+             ORDER BY timestamp, id",
+        )?;
+        let rows = stmt.query_map(
+            params![sensor, unix_seconds(from), unix_seconds(to)],
+            |row| {
+                Ok((
+                    row.get::<_, f64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, f64>(2)?,
+                ))
+            },
+        )?;
+
+        // 同一时间戳的多行合并为一个读数
+        let mut samples: Vec<Sample> = Vec::new();
+        let mut current: Option<(f64, Reading)> = None;
+        for row in rows {
+            let (timestamp, quantity, value) = row?;
+            let Some(quantity) = Quantity::from_name(&quantity) else {
+                continue;
+            };
+            match &mut current {
+                Some((t, reading)) if *t == timestamp => reading.set(quantity, value),
+                _ => {
+                    if let Some((t, reading)) = current.take() {
+                        samples.push(Sample::at_unix_timestamp(sensor, t, reading)?);
+                    }
+                    current = Some((timestamp, Reading::new().with(quantity, value)));
+                }
+            }
+        }
+        if let Some((t, reading)) = current {
+            samples.push(Sample::at_unix_timestamp(sensor, t, reading)?);
+        }
+        // OK
+        Ok(samples)
+    }
+}
+
+impl Sink for SqliteLogger {
+    fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        SqliteLogger::write(self, sample)
+    }
+}
@@ -0,0 +1,254 @@
+//! 多通道软件PWM
+//!
+//! 树莓派只有两路硬件PWM，rppal的软件PWM每个引脚占用一个线程。`SoftPwm`用一个
+//! 专用的计时线程同时驱动任意数量的GPIO引脚：每个周期开始时拉高所有通道，
+//! 按占空比排序依次拉低，所有通道共用同一频率（最高5kHz）。
+//! 适合LED调光、无源蜂鸣器发声和电机调速等在硬件PWM通道被占用时的后备输出
+//!
+//! 计时线程短时间等待时忙等以减少抖动，会占用一个CPU核心的部分时间；
+//! 单核设备上可以启用`timing::set_low_cpu`，以略大的抖动为代价让出CPU
+
+use std::collections::BTreeMap;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::debug::{self, PinMode};
+use crate::gpio::{self, OutputPin};
+use crate::timing::wait_until;
+
+/// 最高频率（Hz）
+const MAX_FREQUENCY: f64 = 5000.0;
+
+/// 计时线程命令
+enum Command {
+    /// 添加通道
+    Add(u8, OutputPin),
+    /// 移除通道（输出低电平）
+    Remove(u8),
+}
+
+/// 共享设置
+struct Settings {
+    /// 频率（Hz）
+    frequency: f64,
+    /// 各通道的占空比（0.0~1.0）
+    duties: BTreeMap<u8, f64>,
+}
+
+/// 检查频率
+fn validate_frequency(frequency: f64) -> anyhow::Result<()> {
+    if !(frequency > 0.0 && frequency <= MAX_FREQUENCY) {
+        return Err(anyhow::anyhow!(
+            "软件PWM频率需要在0~{}Hz之间: {}",
+            MAX_FREQUENCY,
+            frequency
+        ));
+    }
+    Ok(())
+}
+
+/// 多通道软件PWM引擎（可克隆，克隆后共享同一个计时线程）
+///
+/// 所有引擎和通道句柄释放后计时线程退出
+///
+/// ```ignore
+/// let engine = SoftPwm::new(800.0)?;
+/// let mut red = engine.channel(17)?;
+/// let mut green = engine.channel(27)?;
+/// red.set_duty_cycle(0.8)?;
+/// green.set_duty_cycle(0.2)?;
+/// // 电机调速：硬件PWM被占用时使用软件PWM
+/// let motor_pwm = PwmWapper::hardware_or(18, 800.0, &engine)?;
+/// ```
+#[derive(Clone)]
+pub struct SoftPwm {
+    /// 共享设置
+    settings: Arc<Mutex<Settings>>,
+    /// 命令通道
+    tx: Sender<Command>,
+}
+
+impl SoftPwm {
+    /// 创建引擎并启动计时线程
+    ///
+    /// - frequency: 频率（Hz，最高5kHz）
+    pub fn new(frequency: f64) -> anyhow::Result<Self> {
+        validate_frequency(frequency)?;
+        let settings = Arc::new(Mutex::new(Settings {
+            frequency,
+            duties: BTreeMap::new(),
+        }));
+        let (tx, rx) = mpsc::channel();
+        let shared = settings.clone();
+        thread::Builder::new()
+            .name("softpwm".to_string())
+            .spawn(move || run(&shared, rx))?;
+        // OK
+        Ok(Self { settings, tx })
+    }
+
+    /// 当前频率（Hz）
+    pub fn frequency(&self) -> f64 {
+        self.settings
+            .lock()
+            .map_or(0.0, |settings| settings.frequency)
+    }
+
+    /// 修改频率（所有通道同时生效，如无源蜂鸣器切换音调）
+    pub fn set_frequency(&self, frequency: f64) -> anyhow::Result<()> {
+        validate_frequency(frequency)?;
+        self.settings
+            .lock()
+            .map_err(|_| anyhow::anyhow!("软件PWM设置锁已损坏"))?
+            .frequency = frequency;
+        Ok(())
+    }
+
+    /// 添加通道（初始占空比为0）
+    pub fn channel(&self, pin: u8) -> anyhow::Result<SoftPwmChannel> {
+        {
+            let mut settings = self
+                .settings
+                .lock()
+                .map_err(|_| anyhow::anyhow!("软件PWM设置锁已损坏"))?;
+            if settings.duties.contains_key(&pin) {
+                return Err(anyhow::anyhow!("GPIO{}已经是软件PWM通道", pin));
+            }
+            settings.duties.insert(pin, 0.0);
+        }
+        let output = match gpio::output(pin, false) {
+            Ok(output) => {
+                debug::claim(pin, "softpwm", PinMode::Output);
+                output
+            }
+            Err(err) => {
+                if let Ok(mut settings) = self.settings.lock() {
+                    settings.duties.remove(&pin);
+                }
+                return Err(err);
+            }
+        };
+        self.tx
+            .send(Command::Add(pin, output))
+            .map_err(|_| anyhow::anyhow!("软件PWM计时线程已退出"))?;
+        // OK
+        Ok(SoftPwmChannel {
+            pin,
+            engine: self.clone(),
+        })
+    }
+}
+
+/// 计时线程
+fn run(settings: &Mutex<Settings>, rx: Receiver<Command>) {
+    let mut pins: BTreeMap<u8, OutputPin> = BTreeMap::new();
+    let handle = |command: Command, pins: &mut BTreeMap<u8, OutputPin>| match command {
+        Command::Add(pin, output) => {
+            pins.insert(pin, output);
+        }
+        Command::Remove(pin) => {
+            if let Some(mut output) = pins.remove(&pin) {
+                let _ = gpio::write(&mut output, false);
+            }
+        }
+    };
+    loop {
+        // 没有通道时阻塞等待命令
+        if pins.is_empty() {
+            match rx.recv_timeout(Duration::from_millis(100)) {
+                Ok(command) => handle(command, &mut pins),
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        match rx.try_recv() {
+            Ok(command) => {
+                handle(command, &mut pins);
+                continue;
+            }
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Disconnected) => break,
+        }
+
+        let Ok((period, duties)) = settings.lock().map(|settings| {
+            (
+                Duration::from_secs_f64(1.0 / settings.frequency),
+                settings.duties.clone(),
+            )
+        }) else {
+            break;
+        };
+        let start = Instant::now();
+        let mut offs = Vec::with_capacity(pins.len());
+        for (pin, output) in pins.iter_mut() {
+            let duty = duties.get(pin).copied().unwrap_or(0.0);
+            let _ = gpio::write(output, duty > 0.0);
+            if duty > 0.0 && duty < 1.0 {
+                offs.push((period.mul_f64(duty), *pin));
+            }
+        }
+        offs.sort();
+        for (at, pin) in offs {
+            wait_until(start + at);
+            if let Some(output) = pins.get_mut(&pin) {
+                let _ = gpio::write(output, false);
+            }
+        }
+        wait_until(start + period);
+    }
+    for output in pins.values_mut() {
+        let _ = gpio::write(output, false);
+    }
+}
+
+/// 软件PWM通道（释放时输出低电平并移除）
+pub struct SoftPwmChannel {
+    /// GPIO针脚
+    pin: u8,
+    /// 所属引擎
+    engine: SoftPwm,
+}
+
+impl SoftPwmChannel {
+    /// GPIO针脚
+    pub fn pin(&self) -> u8 {
+        self.pin
+    }
+
+    /// 频率（Hz，由引擎决定）
+    pub fn frequency(&self) -> f64 {
+        self.engine.frequency()
+    }
+
+    /// 设置占空比（0.0~1.0，超出范围时截断），下一个周期生效
+    pub fn set_duty_cycle(&mut self, duty_cycle: f64) -> anyhow::Result<()> {
+        self.engine
+            .settings
+            .lock()
+            .map_err(|_| anyhow::anyhow!("软件PWM设置锁已损坏"))?
+            .duties
+            .insert(self.pin, duty_cycle.clamp(0.0, 1.0));
+        Ok(())
+    }
+
+    /// 当前占空比（0.0~1.0）
+    pub fn duty_cycle(&self) -> f64 {
+        self.engine
+            .settings
+            .lock()
+            .ok()
+            .and_then(|settings| settings.duties.get(&self.pin).copied())
+            .unwrap_or(0.0)
+    }
+}
+
+impl Drop for SoftPwmChannel {
+    fn drop(&mut self) {
+        if let Ok(mut settings) = self.engine.settings.lock() {
+            settings.duties.remove(&self.pin);
+        }
+        let _ = self.engine.tx.send(Command::Remove(self.pin));
+    }
+}
@@ -0,0 +1,176 @@
+use embedded_hal::spi::{self, ErrorKind, Operation};
+use rppal::gpio::{Gpio, OutputPin};
+use rppal::spi::{Bus, Mode, SlaveSelect, Spi};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// 共享的SPI通信总线
+///
+/// 树莓派每条SPI总线只有2~3个硬件片选，挂载更多设备时使用GPIO作为片选，
+/// 与I2C的`Arc<Mutex<I2c>>`一样由多个设备共享同一个总线对象
+///
+/// 注意：创建总线时指定的硬件片选引脚在每次传输时都会被拉低，
+/// 不要在该引脚上连接其他设备
+pub struct SpiBus {
+    spi: Arc<Mutex<Spi>>,
+}
+
+impl SpiBus {
+    /// 创建共享的SPI通信总线
+    ///
+    /// - bus: SPI总线
+    /// - slave_select: 占用的硬件片选（该引脚悬空）
+    pub fn new(bus: Bus, slave_select: SlaveSelect) -> anyhow::Result<Self> {
+        let spi = Spi::new(bus, slave_select, 1_000_000, Mode::Mode0)?;
+        // OK
+        Ok(Self {
+            spi: Arc::new(Mutex::new(spi)),
+        })
+    }
+
+    /// 创建使用GPIO片选的设备
+    ///
+    /// - cs_pin: 片选引脚（低电平有效）
+    /// - clock_speed: 设备的通信速率（Hz），每次传输前自动切换
+    /// - mode: 设备的SPI模式
+    pub fn device(
+        &self,
+        cs_pin: u8,
+        clock_speed: u32,
+        mode: Mode,
+    ) -> anyhow::Result<SpiDeviceHandle> {
+        let cs = Gpio::new()?.get(cs_pin)?.into_output_high();
+        // OK
+        Ok(SpiDeviceHandle {
+            spi: self.spi.clone(),
+            cs: Some(cs),
+            clock_speed,
+            mode,
+        })
+    }
+}
+
+/// SPI设备句柄
+///
+/// 每次传输时自动锁定总线、切换通信速率和模式，并控制片选引脚
+pub struct SpiDeviceHandle {
+    /// SPI通信对象
+    spi: Arc<Mutex<Spi>>,
+    /// GPIO片选引脚（使用硬件片选时为None）
+    cs: Option<OutputPin>,
+    /// 通信速率
+    clock_speed: u32,
+    /// SPI模式
+    mode: Mode,
+}
+
+impl SpiDeviceHandle {
+    /// 创建独占硬件片选的设备
+    ///
+    /// 硬件片选在每次读写之间都会释放，`transaction`中的多次读写不会保持片选
+    pub fn hardware(
+        bus: Bus,
+        slave_select: SlaveSelect,
+        clock_speed: u32,
+        mode: Mode,
+    ) -> anyhow::Result<Self> {
+        let spi = Spi::new(bus, slave_select, clock_speed, mode)?;
+        // OK
+        Ok(Self {
+            spi: Arc::new(Mutex::new(spi)),
+            cs: None,
+            clock_speed,
+            mode,
+        })
+    }
+
+    /// 在一次片选期间执行多次读写
+    pub fn transaction<R, F>(&mut self, f: F) -> anyhow::Result<R>
+    where
+        F: FnOnce(&mut Spi) -> anyhow::Result<R>,
+    {
+        let mut spi = self
+            .spi
+            .lock()
+            .map_err(|_| anyhow::anyhow!("SPI通信总线繁忙"))?;
+        // 同一总线上的设备速率和模式可能不同
+        if self.cs.is_some() {
+            spi.set_clock_speed(self.clock_speed)?;
+            spi.set_mode(self.mode)?;
+        }
+
+        if let Some(cs) = &mut self.cs {
+            cs.set_low();
+        }
+        let result = f(&mut spi);
+        if let Some(cs) = &mut self.cs {
+            cs.set_high();
+        }
+        trace_event!(
+            trace,
+            clock_speed = self.clock_speed,
+            ok = result.is_ok(),
+            "SPI传输"
+        );
+        result
+    }
+
+    /// 写数据
+    pub fn write(&mut self, data: &[u8]) -> anyhow::Result<()> {
+        self.transaction(|spi| {
+            spi.write(data)?;
+            Ok(())
+        })
+    }
+
+    /// 读数据
+    pub fn read(&mut self, buffer: &mut [u8]) -> anyhow::Result<()> {
+        self.transaction(|spi| {
+            spi.read(buffer)?;
+            Ok(())
+        })
+    }
+
+    /// 全双工传输
+    pub fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> anyhow::Result<()> {
+        self.transaction(|spi| {
+            spi.transfer(read, write)?;
+            Ok(())
+        })
+    }
+}
+
+impl spi::ErrorType for SpiDeviceHandle {
+    type Error = ErrorKind;
+}
+
+/// 兼容embedded-hal的SPI设备接口，可以直接传给第三方驱动使用
+impl spi::SpiDevice for SpiDeviceHandle {
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+        SpiDeviceHandle::transaction(self, |spi| {
+            for operation in operations.iter_mut() {
+                match operation {
+                    Operation::Read(buffer) => {
+                        spi.read(buffer)?;
+                    }
+                    Operation::Write(data) => {
+                        spi.write(data)?;
+                    }
+                    Operation::Transfer(read, write) => {
+                        spi.transfer(read, write)?;
+                    }
+                    Operation::TransferInPlace(buffer) => {
+                        let write = buffer.to_vec();
+                        spi.transfer(buffer, &write)?;
+                    }
+                    Operation::DelayNs(ns) => {
+                        thread::sleep(Duration::from_nanos(*ns as u64));
+                    }
+                }
+            }
+            Ok(())
+        })
+        .map_err(|_| ErrorKind::Other)
+    }
+}
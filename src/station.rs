@@ -0,0 +1,378 @@
+//! 气象站
+//!
+//! 气象站由多个传感器组成：温湿度气压（BME280、AHT30、DHT等）、雨量计、风速计和光照度传感器。
+//! `Weather`按固定间隔读取所有传感器，合并为一条观测记录，并计算露点、海平面气压等
+//! 派生量；观测记录可以订阅，也可以转换为读数样本交给输出管道（数据记录、MQTT等）
+
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::diagnostics::{Check, DiagnosticsReport};
+use crate::event_bus::{broadcast, subscribe};
+use crate::manager::Sensor;
+use crate::reading::{Quantity, Reading, Sample};
+
+/// 露点计算的Magnus公式系数
+const MAGNUS_A: f64 = 17.62;
+const MAGNUS_B: f64 = 243.12;
+
+/// 没有温度读数时计算海平面气压使用的标准大气温度（℃）
+const STANDARD_TEMPERATURE: f64 = 15.0;
+
+/// 露点（℃）
+///
+/// - temperature: 温度（℃）
+/// - humidity: 相对湿度（%）
+pub fn dew_point(temperature: f64, humidity: f64) -> f64 {
+    let gamma = (humidity / 100.0).ln() + MAGNUS_A * temperature / (MAGNUS_B + temperature);
+    MAGNUS_B * gamma / (MAGNUS_A - gamma)
+}
+
+/// 酷热指数（℃，美国国家气象局的Rothfusz回归公式）
+///
+/// 体感温度低于约27℃时使用Steadman简化公式，结果接近实际温度
+///
+/// - temperature: 温度（℃）
+/// - humidity: 相对湿度（%）
+pub fn heat_index(temperature: f64, humidity: f64) -> f64 {
+    let t = temperature * 1.8 + 32.0;
+    let rh = humidity.clamp(0.0, 100.0);
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    let index = if (simple + t) / 2.0 < 80.0 {
+        simple
+    } else {
+        let mut index = -42.379 + 2.04901523 * t + 10.14333127 * rh
+            - 0.22475541 * t * rh
+            - 0.00683783 * t * t
+            - 0.05481717 * rh * rh
+            + 0.00122874 * t * t * rh
+            + 0.00085282 * t * rh * rh
+            - 0.00000199 * t * t * rh * rh;
+        // 干燥和高湿时的修正
+        if rh < 13.0 && (80.0..=112.0).contains(&t) {
+            index -= (13.0 - rh) / 4.0 * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
+        } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+            index += (rh - 85.0) / 10.0 * (87.0 - t) / 5.0;
+        }
+        index
+    };
+    (index - 32.0) / 1.8
+}
+
+/// 海平面气压（Pa）
+///
+/// - pressure: 测站气压（Pa）
+/// - temperature: 测站温度（℃）
+/// - elevation: 测站海拔（m）
+pub fn sea_level_pressure(pressure: f64, temperature: f64, elevation: f64) -> f64 {
+    let lapse = 0.0065 * elevation;
+    pressure * (1.0 - lapse / (temperature + lapse + 273.15)).powf(-5.257)
+}
+
+/// 饱和水汽压（kPa，Magnus公式）
+///
+/// - temperature: 温度（℃）
+pub(crate) fn saturation_vapor_pressure(temperature: f64) -> f64 {
+    0.6112 * (MAGNUS_A * temperature / (MAGNUS_B + temperature)).exp()
+}
+
+/// 饱和水汽压差VPD（kPa），温室中衡量植物蒸腾强度，过高时叶片气孔关闭
+///
+/// - temperature: 温度（℃）
+/// - humidity: 相对湿度（%）
+pub fn vapor_pressure_deficit(temperature: f64, humidity: f64) -> f64 {
+    saturation_vapor_pressure(temperature) * (1.0 - humidity.clamp(0.0, 100.0) / 100.0)
+}
+
+/// 观测记录
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Observation {
+    /// 观测时间
+    #[cfg_attr(feature = "serde", serde(with = "crate::reading::unix_seconds"))]
+    pub timestamp: SystemTime,
+    /// 温度（℃）
+    pub temperature: Option<f64>,
+    /// 相对湿度（%）
+    pub humidity: Option<f64>,
+    /// 测站气压（Pa）
+    pub pressure: Option<f64>,
+    /// 露点（℃）
+    pub dew_point: Option<f64>,
+    /// 海平面气压（Pa）
+    pub sea_level_pressure: Option<f64>,
+    /// 平均风速（m/s）
+    pub wind_speed: Option<f64>,
+    /// 阵风风速（m/s）
+    pub wind_gust: Option<f64>,
+    /// 上一次观测以来的降雨量（mm）
+    pub rainfall: Option<f64>,
+    /// 光照度（lx）
+    pub illuminance: Option<f64>,
+    /// 读取失败的传感器（传感器名称、错误信息）
+    pub errors: Vec<(String, String)>,
+}
+
+impl Observation {
+    /// 转换为读数样本
+    pub fn to_sample(&self, station: &str) -> Sample {
+        Sample {
+            sensor: station.to_string(),
+            timestamp: self.timestamp,
+            reading: self.into(),
+        }
+    }
+}
+
+impl From<&Observation> for Reading {
+    fn from(observation: &Observation) -> Self {
+        let mut reading = Reading::new();
+        for (quantity, value) in [
+            (Quantity::Temperature, observation.temperature),
+            (Quantity::Humidity, observation.humidity),
+            (Quantity::Pressure, observation.pressure),
+            (Quantity::DewPoint, observation.dew_point),
+            (Quantity::SeaLevelPressure, observation.sea_level_pressure),
+            (Quantity::WindSpeed, observation.wind_speed),
+            (Quantity::WindGust, observation.wind_gust),
+            (Quantity::Rainfall, observation.rainfall),
+            (Quantity::Illuminance, observation.illuminance),
+        ] {
+            if let Some(value) = value {
+                reading.set(quantity, value);
+            }
+        }
+        reading
+    }
+}
+
+impl fmt::Display for Observation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Reading::from(self))?;
+        for (sensor, error) in &self.errors {
+            write!(f, " [{}: {}]", sensor, error)?;
+        }
+        Ok(())
+    }
+}
+
+/// 气象站
+///
+/// 传感器按添加顺序读取，多个传感器测量同一物理量时使用先添加的（如BME280的温度优先于
+/// DHT的温度），先添加的传感器读取失败时使用后面的：
+///
+/// ```ignore
+/// let bus = SharedBus::open(1)?;
+/// let station = Weather::new(120.0)
+///     .with_sensor("bme280", Bme280Sensor::new(bus.clone(), Some(0x76))?)
+///     .with_sensor("aht30", Aht30Sensor::new(bus.clone(), None)?)
+///     .with_sensor("rain", RainGauge::new(6)?)
+///     .with_sensor("wind", Anemometer::new(5)?)
+///     .with_sensor("light", BH1750::new(bus, bh1750::ADDRESS_LOW)?)
+///     .start("station", Duration::from_secs(60));
+/// pipeline.start(station.subscribe_samples());
+/// ```
+pub struct Weather {
+    /// 测站海拔（m）
+    elevation: f64,
+    /// 传感器（名称, 传感器）
+    sensors: Vec<(String, Box<dyn Sensor>)>,
+}
+
+impl Weather {
+    /// 创建实例
+    ///
+    /// - elevation: 测站海拔（m），用于计算海平面气压
+    pub fn new(elevation: f64) -> Self {
+        Self {
+            elevation,
+            sensors: Vec::new(),
+        }
+    }
+
+    /// 添加传感器
+    pub fn with_sensor<S: Sensor + 'static>(mut self, name: &str, sensor: S) -> Self {
+        self.sensors.push((name.to_string(), Box::new(sensor)));
+        self
+    }
+
+    /// 测站海拔（m）
+    pub fn elevation(&self) -> f64 {
+        self.elevation
+    }
+
+    /// 读取所有传感器并合并为观测记录（所有传感器都读取失败时返回错误）
+    pub fn observe(&mut self) -> anyhow::Result<Observation> {
+        let mut merged = Reading::new();
+        let mut errors = Vec::new();
+        for (name, sensor) in &mut self.sensors {
+            match sensor.read() {
+                Ok(reading) => {
+                    for (quantity, value) in reading.iter() {
+                        if merged.get(quantity).is_none() {
+                            merged.set(quantity, value);
+                        }
+                    }
+                }
+                Err(err) => {
+                    trace_event!(warn, sensor = %name, error = %err, "气象站传感器读取失败");
+                    errors.push((name.clone(), err.to_string()));
+                }
+            }
+        }
+        if !self.sensors.is_empty() && errors.len() == self.sensors.len() {
+            let errors: Vec<String> = errors
+                .iter()
+                .map(|(name, err)| format!("{}: {}", name, err))
+                .collect();
+            return Err(anyhow::anyhow!(
+                "气象站传感器全部读取失败: {}",
+                errors.join("; ")
+            ));
+        }
+
+        let temperature = merged.temperature();
+        let humidity = merged.humidity();
+        let pressure = merged.pressure();
+        let dew_point = match (temperature, humidity) {
+            (Some(temperature), Some(humidity)) if humidity > 0.0 => {
+                Some(dew_point(temperature, humidity))
+            }
+            _ => None,
+        };
+        let sea_level_pressure = pressure.map(|pressure| {
+            sea_level_pressure(
+                pressure,
+                temperature.unwrap_or(STANDARD_TEMPERATURE),
+                self.elevation,
+            )
+        });
+        // OK
+        Ok(Observation {
+            timestamp: SystemTime::now(),
+            temperature,
+            humidity,
+            pressure,
+            dew_point,
+            sea_level_pressure,
+            wind_speed: merged.get(Quantity::WindSpeed),
+            wind_gust: merged.get(Quantity::WindGust),
+            rainfall: merged.get(Quantity::Rainfall),
+            illuminance: merged.get(Quantity::Illuminance),
+            errors,
+        })
+    }
+
+    /// 在后台线程中按固定间隔观测
+    ///
+    /// - name: 气象站名称（写入读数样本）
+    /// - interval: 观测间隔（不小于各传感器的最小读取间隔）
+    pub fn start(mut self, name: &str, interval: Duration) -> StationHandle {
+        let interval = interval.max(self.min_interval());
+        let name = name.to_string();
+        let shared = Arc::new(Subscribers::default());
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let subscribers = shared.clone();
+        let thread = thread::spawn(move || {
+            let mut next = Instant::now();
+            loop {
+                match self.observe() {
+                    Ok(observation) => {
+                        let sample = observation.to_sample(&name);
+                        broadcast(&subscribers.observations, observation);
+                        broadcast(&subscribers.samples, sample);
+                    }
+                    Err(err) => eprintln!("气象站{}观测失败: {}", name, err),
+                }
+                // 按固定节拍观测，期间收到停止信号则退出
+                next += interval;
+                let now = Instant::now();
+                if next < now {
+                    next = now;
+                }
+                match stop_rx.recv_timeout(next - now) {
+                    Err(RecvTimeoutError::Timeout) => {}
+                    _ => break,
+                }
+            }
+        });
+        StationHandle {
+            subscribers: shared,
+            stop_tx,
+            thread,
+        }
+    }
+}
+
+/// 气象站作为一个传感器读取时输出观测记录的所有物理量
+impl Sensor for Weather {
+    fn kind(&self) -> &'static str {
+        "weather"
+    }
+
+    fn min_interval(&self) -> Duration {
+        self.sensors
+            .iter()
+            .map(|(_, sensor)| sensor.min_interval())
+            .max()
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// 依次自检所有传感器，合并为一份报告
+    fn self_test(&mut self) -> DiagnosticsReport {
+        let mut report = DiagnosticsReport::new("weather");
+        for (name, sensor) in &mut self.sensors {
+            for check in sensor.self_test().checks {
+                report.checks.push(Check {
+                    name: format!("{}.{}", name, check.name),
+                    ..check
+                });
+            }
+        }
+        report
+    }
+
+    fn read(&mut self) -> anyhow::Result<Reading> {
+        Ok((&self.observe()?).into())
+    }
+}
+
+/// 订阅者
+#[derive(Default)]
+struct Subscribers {
+    /// 观测记录订阅者
+    observations: Mutex<Vec<Sender<Observation>>>,
+    /// 读数样本订阅者
+    samples: Mutex<Vec<Sender<Sample>>>,
+}
+
+/// 运行中的气象站
+pub struct StationHandle {
+    /// 订阅者
+    subscribers: Arc<Subscribers>,
+    /// 停止信号
+    stop_tx: Sender<()>,
+    /// 观测线程
+    thread: JoinHandle<()>,
+}
+
+impl StationHandle {
+    /// 订阅观测记录
+    pub fn subscribe(&self) -> Receiver<Observation> {
+        subscribe(&self.subscribers.observations)
+    }
+
+    /// 订阅读数样本（供输出管道使用）
+    pub fn subscribe_samples(&self) -> Receiver<Sample> {
+        subscribe(&self.subscribers.samples)
+    }
+
+    /// 停止观测并等待观测线程退出
+    pub fn stop(self) {
+        let _ = self.stop_tx.send(());
+        let _ = self.thread.join();
+    }
+}
@@ -1,4 +1,5 @@
 use embedded_timers::clock::Clock;
+use std::sync::OnceLock;
 
 /// 自己实现一个标准时钟
 pub struct StdClock {}
@@ -9,6 +10,12 @@ impl StdClock {
     }
 }
 
+/// 全局共享的标准时钟（sensor-hal的驱动需要持有时钟的'static引用）
+pub fn global() -> &'static StdClock {
+    static CLOCK: OnceLock<StdClock> = OnceLock::new();
+    CLOCK.get_or_init(StdClock::new)
+}
+
 impl Clock for StdClock {
     type Instant = std::time::Instant;
 
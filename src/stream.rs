@@ -0,0 +1,75 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::manager::Sensor;
+use crate::reading::Sample;
+
+/// 按固定间隔持续读取传感器的迭代器
+///
+/// 每次`next`等待到下一个读取时间后读取一次，迭代不会结束，配合`take`、`take_while`、
+/// `filter`、`zip`等组合器使用：
+///
+/// ```ignore
+/// let hot = SensorStream::new("greenhouse", Dht11Sensor::new(4)?)
+///     .filter_map(Result::ok)
+///     .find(|sample| sample.reading.temperature().is_some_and(|t| t > 30.0));
+/// ```
+pub struct SensorStream<S: Sensor> {
+    /// 传感器名称（写入样本）
+    name: String,
+    /// 传感器
+    sensor: S,
+    /// 读取间隔
+    interval: Duration,
+    /// 下一次读取时间
+    next: Option<Instant>,
+}
+
+impl<S: Sensor> SensorStream<S> {
+    /// 创建实例（读取间隔为传感器的最小间隔）
+    pub fn new(name: &str, sensor: S) -> Self {
+        Self {
+            name: name.to_string(),
+            interval: sensor.min_interval(),
+            sensor,
+            next: None,
+        }
+    }
+
+    /// 设置读取间隔（不小于传感器的最小间隔）
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval.max(self.sensor.min_interval());
+        self
+    }
+
+    /// 读取间隔
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// 取回传感器
+    pub fn into_inner(self) -> S {
+        self.sensor
+    }
+}
+
+impl<S: Sensor> Iterator for SensorStream<S> {
+    type Item = anyhow::Result<Sample>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(next) = self.next {
+            let now = Instant::now();
+            if next > now {
+                thread::sleep(next - now);
+            }
+        }
+        // 读取耗时计入间隔，按固定节拍读取
+        let started = Instant::now();
+        self.next = Some(started + self.interval);
+        Some(
+            self.sensor
+                .read()
+                .map(|reading| Sample::new(&self.name, reading)),
+        )
+    }
+}
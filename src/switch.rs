@@ -0,0 +1,257 @@
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::debug::{self, PinMode};
+use crate::gpio::{self, OutputPin};
+use crate::pwm_wapper::PwmWapper;
+
+/// 开关型执行器（继电器、加热片、风扇等）
+pub trait Switch: Send {
+    /// 打开或关闭
+    fn set(&mut self, on: bool) -> anyhow::Result<()>;
+
+    /// 当前是否打开
+    fn is_on(&self) -> bool;
+
+    /// 打开
+    fn on(&mut self) -> anyhow::Result<()> {
+        self.set(true)
+    }
+
+    /// 关闭
+    fn off(&mut self) -> anyhow::Result<()> {
+        self.set(false)
+    }
+}
+
+/// GPIO控制的开关（继电器模块等）
+pub struct GpioSwitch {
+    /// 输出引脚
+    pin: OutputPin,
+    /// 高电平有效
+    active_high: bool,
+    /// 当前状态
+    on: bool,
+}
+
+impl GpioSwitch {
+    /// 创建实例（初始为关闭状态）
+    ///
+    /// - pin: 输出引脚
+    /// - active_high: 高电平打开（低电平触发的继电器模块传false）
+    pub fn new(pin: u8, active_high: bool) -> anyhow::Result<Self> {
+        let pin_number = pin;
+        let pin = gpio::output(pin, !active_high)?;
+        debug::claim(pin_number, "switch", PinMode::Output);
+        // OK
+        Ok(Self {
+            pin,
+            active_high,
+            on: false,
+        })
+    }
+}
+
+impl Switch for GpioSwitch {
+    fn set(&mut self, on: bool) -> anyhow::Result<()> {
+        gpio::write(&mut self.pin, on == self.active_high)?;
+        self.on = on;
+        Ok(())
+    }
+
+    fn is_on(&self) -> bool {
+        self.on
+    }
+}
+
+/// PWM控制的执行器（风扇调速、加热片功率控制等）
+///
+/// 作为开关使用时，打开为100%占空比，关闭为0
+pub struct PwmSwitch {
+    /// PWM输出
+    pwm: PwmWapper,
+    /// 当前输出（0.0~1.0）
+    level: f64,
+}
+
+impl PwmSwitch {
+    /// 创建实例（初始输出为0）
+    pub fn new(mut pwm: PwmWapper) -> anyhow::Result<Self> {
+        pwm.set_duty_cycle(0.0)?;
+        // OK
+        Ok(Self { pwm, level: 0.0 })
+    }
+
+    /// 设置输出（0.0~1.0，超出范围时截断）
+    pub fn set_level(&mut self, level: f64) -> anyhow::Result<()> {
+        let level = level.clamp(0.0, 1.0);
+        self.pwm.set_duty_cycle(level)?;
+        self.level = level;
+        Ok(())
+    }
+
+    /// 当前输出（0.0~1.0）
+    pub fn level(&self) -> f64 {
+        self.level
+    }
+}
+
+impl Switch for PwmSwitch {
+    fn set(&mut self, on: bool) -> anyhow::Result<()> {
+        self.set_level(if on { 1.0 } else { 0.0 })
+    }
+
+    fn is_on(&self) -> bool {
+        self.level > 0.0
+    }
+}
+
+/// 共享的开关（如同时交给温控器和退出协调器）
+impl<W: Switch> Switch for Arc<Mutex<W>> {
+    fn set(&mut self, on: bool) -> anyhow::Result<()> {
+        self.lock()
+            .map_err(|_| anyhow::anyhow!("开关状态锁已损坏"))?
+            .set(on)
+    }
+
+    fn is_on(&self) -> bool {
+        self.lock().is_ok_and(|switch| switch.is_on())
+    }
+}
+
+/// 保持运行的共享状态
+struct Hold<W: Switch> {
+    /// 执行器
+    output: W,
+    /// 最近一次喂狗的时间
+    last_alive: Instant,
+    /// 是否因超时被关闭
+    tripped: bool,
+}
+
+/// 锁定保持运行的共享状态（锁损坏时仍然可以关闭执行器）
+fn lock_hold<W: Switch>(hold: &Mutex<Hold<W>>) -> std::sync::MutexGuard<'_, Hold<W>> {
+    match hold.lock() {
+        Ok(hold) => hold,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// 保持运行（死人开关）
+///
+/// 加热器、水泵等执行器打开后，应用需要在超时时间内反复调用`keep_alive`；
+/// 应用卡死或崩溃的线程不再喂狗时，后台线程自动关闭执行器，防止一直处于打开状态。
+/// 超时关闭后保持关闭，直到再次调用`set(true)`
+///
+/// ```ignore
+/// let heater = HoldToRun::new(GpioSwitch::new(17, true)?, Duration::from_secs(30));
+/// let keeper = heater.keeper();
+/// let thermostat = Thermostat::new(sensor, heater, ThermostatConfig::default())?;
+/// thermostat.run(Duration::from_secs(10), move |_| keeper.keep_alive());
+/// ```
+pub struct HoldToRun<W: Switch + 'static> {
+    /// 共享状态
+    hold: Arc<Mutex<Hold<W>>>,
+    /// 停止信号
+    stop_tx: Option<Sender<()>>,
+    /// 检查线程
+    thread: Option<JoinHandle<()>>,
+}
+
+impl<W: Switch + 'static> HoldToRun<W> {
+    /// 包装执行器并启动检查线程（执行器初始为关闭状态）
+    ///
+    /// - output: 执行器
+    /// - timeout: 两次喂狗之间的最长间隔
+    pub fn new(mut output: W, timeout: Duration) -> Self {
+        if let Err(err) = output.off() {
+            eprintln!("关闭执行器失败: {}", err);
+        }
+        let hold = Arc::new(Mutex::new(Hold {
+            output,
+            last_alive: Instant::now(),
+            tripped: false,
+        }));
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let shared = hold.clone();
+        let interval = (timeout / 4).max(Duration::from_millis(10));
+        let thread = thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(interval) {
+                let mut hold = lock_hold(&shared);
+                if hold.output.is_on() && hold.last_alive.elapsed() > timeout {
+                    eprintln!("执行器超过{}毫秒没有喂狗，已自动关闭", timeout.as_millis());
+                    if let Err(err) = hold.output.off() {
+                        eprintln!("关闭执行器失败: {}", err);
+                    }
+                    hold.tripped = true;
+                }
+            }
+        });
+        Self {
+            hold,
+            stop_tx: Some(stop_tx),
+            thread: Some(thread),
+        }
+    }
+
+    /// 喂狗
+    pub fn keep_alive(&self) {
+        lock_hold(&self.hold).last_alive = Instant::now();
+    }
+
+    /// 喂狗句柄（可克隆，交给应用的主循环或其他线程）
+    pub fn keeper(&self) -> KeepAlive {
+        let hold = self.hold.clone();
+        KeepAlive {
+            feed: Arc::new(move || lock_hold(&hold).last_alive = Instant::now()),
+        }
+    }
+
+    /// 是否因超时被关闭（再次打开后清除）
+    pub fn is_tripped(&self) -> bool {
+        lock_hold(&self.hold).tripped
+    }
+}
+
+impl<W: Switch + 'static> Switch for HoldToRun<W> {
+    /// 打开时同时喂狗
+    fn set(&mut self, on: bool) -> anyhow::Result<()> {
+        let mut hold = lock_hold(&self.hold);
+        if on {
+            hold.last_alive = Instant::now();
+            hold.tripped = false;
+        }
+        hold.output.set(on)
+    }
+
+    fn is_on(&self) -> bool {
+        lock_hold(&self.hold).output.is_on()
+    }
+}
+
+/// 释放时停止检查线程并关闭执行器
+impl<W: Switch + 'static> Drop for HoldToRun<W> {
+    fn drop(&mut self) {
+        drop(self.stop_tx.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        let _ = lock_hold(&self.hold).output.off();
+    }
+}
+
+/// 保持运行的喂狗句柄
+#[derive(Clone)]
+pub struct KeepAlive {
+    /// 喂狗
+    feed: Arc<dyn Fn() + Send + Sync>,
+}
+
+impl KeepAlive {
+    /// 喂狗
+    pub fn keep_alive(&self) {
+        (self.feed)();
+    }
+}
@@ -0,0 +1,273 @@
+//! 时间同步和单调时钟到墙上时间的映射
+//!
+//! 没有网络的树莓派启动时系统时间停在上次关机附近（fake-hwclock）甚至1970年，
+//! 这段时间的读数时间戳全部错误。`TimeService`位于调度器和输出之间：
+//!
+//! - 每个读数按单调时钟（开机后经过的时间，不受系统时间调整影响）记录
+//! - 系统时间同步前，读数暂存在内存中；检测到NTP同步后，按单调时钟的差值推算每个读数
+//!   的准确时间，修正后依次输出
+//! - 配置了DS3231实时时钟时，启动时从RTC读取准确时间，读数立即修正后输出；
+//!   NTP同步后把系统时间写回RTC，保持RTC的准确
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::reading::Sample;
+#[cfg(feature = "ds3231")]
+use crate::sensor::ds3231::DS3231;
+
+/// systemd-timesyncd同步后创建的标志文件
+const TIMESYNCD_FLAG: &str = "/run/systemd/timesync/synchronized";
+
+/// 系统时间是否已经通过NTP同步
+///
+/// 先检查systemd-timesyncd的标志文件，再询问`timedatectl`（chrony、ntpd等同步服务）
+pub fn ntp_synchronized() -> bool {
+    if Path::new(TIMESYNCD_FLAG).exists() {
+        return true;
+    }
+    Command::new("timedatectl")
+        .args(["show", "--property=NTPSynchronized", "--value"])
+        .output()
+        .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "yes")
+}
+
+/// 时间来源
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeSource {
+    /// 还没有准确时间
+    Unsynced,
+    /// 实时时钟
+    Rtc,
+    /// NTP同步后的系统时间
+    Ntp,
+}
+
+/// 单调时钟和准确墙上时间的对应关系
+#[derive(Debug, Clone, Copy)]
+struct Anchor {
+    /// 单调时钟
+    instant: Instant,
+    /// 该时刻的准确时间
+    wall: SystemTime,
+    /// 来源
+    source: TimeSource,
+}
+
+/// 共享状态
+struct State {
+    /// 对应关系（还没有准确时间时为None）
+    anchor: Option<Anchor>,
+    /// 实时时钟
+    #[cfg(feature = "ds3231")]
+    rtc: Option<DS3231>,
+}
+
+/// 时间服务（可克隆，克隆后共享同一份同步状态）
+///
+/// ```ignore
+/// let time = TimeService::new().with_rtc(DS3231::new(bus.clone()))?;
+/// let samples = time.correct_from(scheduler.subscribe());
+/// pipeline.start(samples);
+/// ```
+#[derive(Clone)]
+pub struct TimeService {
+    /// 共享状态
+    state: Arc<Mutex<State>>,
+    /// 检查NTP同步的间隔
+    poll_interval: Duration,
+    /// 同步前最多暂存的读数数量（超出后最早的读数不经修正直接输出）
+    max_pending: usize,
+}
+
+impl Default for TimeService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimeService {
+    /// 创建实例（每10秒检查一次NTP同步，最多暂存10000个读数）
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                anchor: None,
+                #[cfg(feature = "ds3231")]
+                rtc: None,
+            })),
+            poll_interval: Duration::from_secs(10),
+            max_pending: 10_000,
+        }
+    }
+
+    /// 使用DS3231实时时钟（立即读取RTC时间，NTP同步后写回系统时间）
+    #[cfg(feature = "ds3231")]
+    pub fn with_rtc(self, mut rtc: DS3231) -> anyhow::Result<Self> {
+        match rtc.read_time() {
+            Ok(wall) => self.set_anchor(Instant::now(), wall, TimeSource::Rtc),
+            // RTC时间无效时等待NTP同步，同步后写回
+            Err(err) => eprintln!("读取RTC时间失败，等待NTP同步: {}", err),
+        }
+        self.state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("时间服务状态异常"))?
+            .rtc = Some(rtc);
+        // OK
+        Ok(self)
+    }
+
+    /// 设置检查NTP同步的间隔
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// 设置同步前最多暂存的读数数量
+    pub fn with_max_pending(mut self, max_pending: usize) -> Self {
+        self.max_pending = max_pending.max(1);
+        self
+    }
+
+    /// 记录单调时钟和准确时间的对应关系
+    fn set_anchor(&self, instant: Instant, wall: SystemTime, source: TimeSource) {
+        if let Ok(mut state) = self.state.lock() {
+            state.anchor = Some(Anchor {
+                instant,
+                wall,
+                source,
+            });
+        }
+        trace_event!(info, source = ?source, "时间已同步");
+    }
+
+    /// 当前的时间来源
+    pub fn source(&self) -> TimeSource {
+        self.state
+            .lock()
+            .ok()
+            .and_then(|state| state.anchor)
+            .map(|anchor| anchor.source)
+            .unwrap_or(TimeSource::Unsynced)
+    }
+
+    /// 单调时钟对应的准确时间（还没有准确时间时为None）
+    pub fn wall_time(&self, at: Instant) -> Option<SystemTime> {
+        let anchor = self.state.lock().ok()?.anchor?;
+        if at >= anchor.instant {
+            anchor.wall.checked_add(at - anchor.instant)
+        } else {
+            anchor.wall.checked_sub(anchor.instant - at)
+        }
+    }
+
+    /// 检查NTP同步状态，第一次检测到同步时记录对应关系并写回RTC
+    ///
+    /// 返回系统时间是否已经同步
+    pub fn check_sync(&self) -> bool {
+        if self.source() == TimeSource::Ntp {
+            return true;
+        }
+        if !ntp_synchronized() {
+            return false;
+        }
+        let now = SystemTime::now();
+        self.set_anchor(Instant::now(), now, TimeSource::Ntp);
+        #[cfg(feature = "ds3231")]
+        if let Ok(mut state) = self.state.lock()
+            && let Some(rtc) = state.rtc.as_mut()
+            && let Err(err) = rtc.set_time(now)
+        {
+            eprintln!("NTP同步后写入RTC时间失败: {}", err);
+        }
+        true
+    }
+
+    /// 修正读数的时间戳
+    ///
+    /// - at: 读数对应的单调时钟
+    fn correct(&self, mut sample: Sample, at: Instant) -> Sample {
+        if let Some(wall) = self.wall_time(at) {
+            sample.timestamp = wall;
+        }
+        sample
+    }
+
+    /// 在后台线程中修正收到的所有读数，返回修正后的读数通道
+    ///
+    /// 系统时间已经同步时读数直接输出；有RTC时间时立即修正后输出；
+    /// 都没有时暂存，检测到NTP同步后修正并输出。输入通道关闭时暂存的读数不经修正输出
+    pub fn correct_from(&self, samples: Receiver<Sample>) -> Receiver<Sample> {
+        let (tx, rx) = mpsc::channel();
+        let this = self.clone();
+        thread::spawn(move || {
+            let mut pending: VecDeque<(Instant, Sample)> = VecDeque::new();
+            let mut synced = this.check_sync();
+            let mut last_poll = Instant::now();
+            loop {
+                let sample = match samples.recv_timeout(this.poll_interval) {
+                    Ok(sample) => Some(sample),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                };
+
+                if !synced && last_poll.elapsed() >= this.poll_interval {
+                    last_poll = Instant::now();
+                    synced = this.check_sync();
+                    if synced {
+                        trace_event!(info, pending = pending.len(), "NTP已同步，修正暂存的读数");
+                        for (at, sample) in pending.drain(..) {
+                            if tx.send(this.correct(sample, at)).is_err() {
+                                return;
+                            }
+                        }
+                    }
+                }
+
+                let Some(sample) = sample else {
+                    continue;
+                };
+                if synced {
+                    if tx.send(sample).is_err() {
+                        return;
+                    }
+                    continue;
+                }
+
+                // 按读数时间与当前系统时间的差值推算读数对应的单调时钟
+                let now = Instant::now();
+                let at = SystemTime::now()
+                    .duration_since(sample.timestamp)
+                    .ok()
+                    .and_then(|age| now.checked_sub(age))
+                    .unwrap_or(now);
+                if this.source() == TimeSource::Rtc {
+                    if tx.send(this.correct(sample, at)).is_err() {
+                        return;
+                    }
+                    continue;
+                }
+                pending.push_back((at, sample));
+                if pending.len() > this.max_pending
+                    && let Some((_, oldest)) = pending.pop_front()
+                {
+                    eprintln!(
+                        "等待时间同步的读数过多，传感器{}的读数未经修正输出",
+                        oldest.sensor
+                    );
+                    if tx.send(oldest).is_err() {
+                        return;
+                    }
+                }
+            }
+            for (_, sample) in pending {
+                let _ = tx.send(sample);
+            }
+        });
+        rx
+    }
+}
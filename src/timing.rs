@@ -0,0 +1,53 @@
+//! 短延时和低CPU占用模式
+//!
+//! `thread::sleep`在树莓派上通常比要求的时间多睡眠几十微秒，微秒级的等待只能忙等。
+//! `wait_until`在剩余时间超过100µs时先睡眠，只在最后一段忙等；启用低CPU占用模式后
+//! 最后一段也让出CPU，定时精度略差，但Pi Zero等单核设备上不会被计时线程占满。
+//!
+//! 协议要求的微秒级时序（HX711时钟高电平不超过60µs、DHT11的数据位宽度）不受低CPU占用模式
+//! 影响，这些驱动在短时间的传输期间仍然忙等
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 剩余时间小于该值时不再睡眠
+pub const SPIN_THRESHOLD: Duration = Duration::from_micros(100);
+
+/// 是否启用低CPU占用模式
+static LOW_CPU: AtomicBool = AtomicBool::new(false);
+
+/// 启用或停用低CPU占用模式（全局生效）
+pub fn set_low_cpu(enabled: bool) {
+    LOW_CPU.store(enabled, Ordering::Relaxed);
+    trace_event!(debug, enabled, "低CPU占用模式");
+}
+
+/// 是否启用低CPU占用模式
+pub fn is_low_cpu() -> bool {
+    LOW_CPU.load(Ordering::Relaxed)
+}
+
+/// 等待到指定时间（剩余时间较长时睡眠，最后一段忙等或让出CPU）
+pub fn wait_until(deadline: Instant) {
+    let low_cpu = is_low_cpu();
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            break;
+        }
+        let remaining = deadline - now;
+        if remaining > SPIN_THRESHOLD {
+            thread::sleep(remaining - SPIN_THRESHOLD);
+        } else if low_cpu {
+            thread::yield_now();
+        } else {
+            std::hint::spin_loop();
+        }
+    }
+}
+
+/// 等待指定时间
+pub fn delay(duration: Duration) {
+    wait_until(Instant::now() + duration);
+}
@@ -0,0 +1,14 @@
+//! 诊断日志埋点
+//!
+//! 启用`tracing`功能时输出到`tracing`，由应用选择订阅器（如命令行工具的`--verbose`）；
+//! 未启用时宏展开为空，不产生任何开销
+
+/// 输出诊断日志事件，用法与`tracing::debug!`等相同：`trace_event!(debug, reg, "读寄存器")`
+macro_rules! trace_event {
+    ($level:ident, $($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        {
+            tracing::$level!($($arg)+);
+        }
+    }};
+}
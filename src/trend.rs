@@ -0,0 +1,357 @@
+//! 变化率和趋势
+//!
+//! 对时间窗口内的读数做最小二乘直线拟合，斜率换算为每单位时间的变化量（如℃/min、Pa/h），
+//! 再按阈值分为上升、下降和平稳。拟合比首尾两点相减更不容易受单个噪声读数影响，
+//! 气压3小时下降超过200Pa（2hPa）等天气预警可以直接写成告警规则
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use crate::reading::{Quantity, Sample};
+use crate::sink::Sink;
+
+/// 趋势
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Trend {
+    /// 上升
+    Rising,
+    /// 下降
+    Falling,
+    /// 平稳
+    Steady,
+}
+
+impl Trend {
+    /// 按变化率分类（变化率的绝对值不超过threshold时为平稳）
+    pub fn classify(rate: f64, threshold: f64) -> Self {
+        if rate > threshold.abs() {
+            Trend::Rising
+        } else if rate < -threshold.abs() {
+            Trend::Falling
+        } else {
+            Trend::Steady
+        }
+    }
+
+    /// 名称（rising、falling、steady）
+    pub fn name(&self) -> &'static str {
+        match self {
+            Trend::Rising => "rising",
+            Trend::Falling => "falling",
+            Trend::Steady => "steady",
+        }
+    }
+
+    /// 按名称查找
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Trend::Rising, Trend::Falling, Trend::Steady]
+            .into_iter()
+            .find(|trend| trend.name() == name)
+    }
+}
+
+impl fmt::Display for Trend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// 时间窗口内的变化率
+///
+/// 读数覆盖的时间不足窗口的一半时不计算，避免刚启动时用几分钟的数据推算几小时的变化
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateEstimator {
+    /// 时间窗口
+    window: Duration,
+    /// 窗口内的读数
+    points: VecDeque<(SystemTime, f64)>,
+}
+
+impl RateEstimator {
+    /// 创建实例
+    ///
+    /// - window: 参与拟合的时间窗口
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            points: VecDeque::new(),
+        }
+    }
+
+    /// 时间窗口
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// 添加一个读数（时间早于上一个读数时丢弃之前的读数）
+    pub fn push(&mut self, timestamp: SystemTime, value: f64) {
+        if let Some((last, _)) = self.points.back()
+            && timestamp < *last
+        {
+            self.points.clear();
+        }
+        self.points.push_back((timestamp, value));
+        while let Some((t, _)) = self.points.front()
+            && timestamp.duration_since(*t).unwrap_or_default() > self.window
+        {
+            self.points.pop_front();
+        }
+    }
+
+    /// 清空读数
+    pub fn reset(&mut self) {
+        self.points.clear();
+    }
+
+    /// 窗口内读数覆盖的时间
+    pub fn span(&self) -> Duration {
+        match (self.points.front(), self.points.back()) {
+            (Some((first, _)), Some((last, _))) => last.duration_since(*first).unwrap_or_default(),
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// 每`per`时间的变化量（如per为1小时时单位为每小时），读数不足时为None
+    pub fn rate(&self, per: Duration) -> Option<f64> {
+        if self.points.len() < 2 || self.span() < self.window / 2 {
+            return None;
+        }
+        let (origin, _) = self.points.front()?;
+        let n = self.points.len() as f64;
+        let xs = self
+            .points
+            .iter()
+            .map(|(t, _)| t.duration_since(*origin).unwrap_or_default().as_secs_f64());
+        let mean_x = xs.clone().sum::<f64>() / n;
+        let mean_y = self.points.iter().map(|(_, v)| v).sum::<f64>() / n;
+        let (mut sxy, mut sxx) = (0.0, 0.0);
+        for (x, (_, y)) in xs.zip(self.points.iter()) {
+            sxy += (x - mean_x) * (y - mean_y);
+            sxx += (x - mean_x) * (x - mean_x);
+        }
+        if sxx <= 0.0 {
+            return None;
+        }
+        Some(sxy / sxx * per.as_secs_f64())
+    }
+}
+
+/// 单个物理量的变化率和趋势
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrendUpdate {
+    /// 传感器名称
+    pub sensor: String,
+    /// 物理量
+    pub quantity: Quantity,
+    /// 每`per`时间的变化量
+    pub rate: f64,
+    /// 变化率的时间单位
+    pub per: Duration,
+    /// 趋势
+    pub trend: Trend,
+    /// 读数时间
+    #[cfg_attr(feature = "serde", serde(with = "crate::reading::unix_seconds"))]
+    pub timestamp: SystemTime,
+}
+
+impl fmt::Display for TrendUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {:+.3}{}/{}",
+            self.sensor,
+            self.quantity,
+            self.trend,
+            self.rate,
+            self.quantity.unit(),
+            per_label(self.per)
+        )
+    }
+}
+
+/// 时间单位的文本形式（s、min、h，其他时长如`90s`）
+fn per_label(per: Duration) -> String {
+    match (per.as_secs(), per.subsec_nanos()) {
+        (1, 0) => "s".to_string(),
+        (60, 0) => "min".to_string(),
+        (3600, 0) => "h".to_string(),
+        _ => format!("{:?}", per),
+    }
+}
+
+/// 物理量的趋势参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TrendConfig {
+    /// 时间窗口
+    window: Duration,
+    /// 变化率的时间单位
+    per: Duration,
+    /// 平稳的阈值（每per时间）
+    threshold: f64,
+}
+
+/// 共享状态
+struct State {
+    /// 默认参数
+    default: TrendConfig,
+    /// 各物理量的参数（覆盖默认参数）
+    configs: BTreeMap<Quantity, TrendConfig>,
+    /// 各传感器、物理量的变化率
+    estimators: BTreeMap<(String, Quantity), RateEstimator>,
+    /// 最新的结果
+    latest: BTreeMap<(String, Quantity), TrendUpdate>,
+    /// 订阅者
+    subscribers: Vec<Sender<TrendUpdate>>,
+}
+
+/// 各传感器的变化率和趋势
+///
+/// 实现了`Sink`，加入读数分发管道后由其他副本查询结果或订阅更新，可以克隆
+///
+/// ```ignore
+/// let trends = TrendTracker::new(Duration::from_secs(600), Duration::from_secs(60))
+///     .with_quantity(Quantity::Pressure, Duration::from_secs(3 * 3600), Duration::from_secs(3600), 50.0);
+/// pipeline.add("trends", trends.clone());
+/// // 之后
+/// if let Some(update) = trends.get("outdoor", Quantity::Pressure) {
+///     println!("{}", update);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct TrendTracker {
+    state: Arc<Mutex<State>>,
+}
+
+impl TrendTracker {
+    /// 创建实例（平稳阈值为0，即只要有变化就算上升或下降）
+    ///
+    /// - window: 默认时间窗口
+    /// - per: 变化率的默认时间单位（如60秒时温度的单位为℃/min）
+    pub fn new(window: Duration, per: Duration) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                default: TrendConfig {
+                    window,
+                    per,
+                    threshold: 0.0,
+                },
+                configs: BTreeMap::new(),
+                estimators: BTreeMap::new(),
+                latest: BTreeMap::new(),
+                subscribers: Vec::new(),
+            })),
+        }
+    }
+
+    /// 设置默认的平稳阈值（每per时间的变化量不超过阈值时为平稳）
+    pub fn with_threshold(self, threshold: f64) -> Self {
+        if let Ok(mut state) = self.state.lock() {
+            state.default.threshold = threshold.abs();
+        }
+        self
+    }
+
+    /// 单独设置物理量的时间窗口、时间单位和平稳阈值
+    pub fn with_quantity(
+        self,
+        quantity: Quantity,
+        window: Duration,
+        per: Duration,
+        threshold: f64,
+    ) -> Self {
+        if let Ok(mut state) = self.state.lock() {
+            state.configs.insert(
+                quantity,
+                TrendConfig {
+                    window,
+                    per,
+                    threshold: threshold.abs(),
+                },
+            );
+        }
+        self
+    }
+
+    /// 处理一个读数，返回读数足够的物理量的变化率和趋势
+    pub fn update(&self, sample: &Sample) -> Vec<TrendUpdate> {
+        let Ok(mut state) = self.state.lock() else {
+            return Vec::new();
+        };
+        let mut updates = Vec::new();
+        for (quantity, value) in sample.reading.iter() {
+            let config = state
+                .configs
+                .get(&quantity)
+                .copied()
+                .unwrap_or(state.default);
+            let estimator = state
+                .estimators
+                .entry((sample.sensor.clone(), quantity))
+                .or_insert_with(|| RateEstimator::new(config.window));
+            estimator.push(sample.timestamp, value);
+            let Some(rate) = estimator.rate(config.per) else {
+                continue;
+            };
+            updates.push(TrendUpdate {
+                sensor: sample.sensor.clone(),
+                quantity,
+                rate,
+                per: config.per,
+                trend: Trend::classify(rate, config.threshold),
+                timestamp: sample.timestamp,
+            });
+        }
+        for update in &updates {
+            state
+                .latest
+                .insert((update.sensor.clone(), update.quantity), update.clone());
+            state
+                .subscribers
+                .retain(|tx| tx.send(update.clone()).is_ok());
+        }
+        updates
+    }
+
+    /// 指定传感器、物理量的最新结果
+    pub fn get(&self, sensor: &str, quantity: Quantity) -> Option<TrendUpdate> {
+        self.state
+            .lock()
+            .ok()?
+            .latest
+            .get(&(sensor.to_string(), quantity))
+            .cloned()
+    }
+
+    /// 所有传感器、物理量的最新结果
+    pub fn snapshot(&self) -> Vec<TrendUpdate> {
+        self.state
+            .lock()
+            .map(|state| state.latest.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// 订阅变化率和趋势的更新
+    pub fn subscribe(&self) -> Receiver<TrendUpdate> {
+        let (tx, rx) = mpsc::channel();
+        if let Ok(mut state) = self.state.lock() {
+            state.subscribers.push(tx);
+        }
+        rx
+    }
+}
+
+impl Sink for TrendTracker {
+    fn write(&mut self, sample: &Sample) -> anyhow::Result<()> {
+        self.update(sample);
+        Ok(())
+    }
+}
@@ -0,0 +1,462 @@
+//! 旋转编码器 + 按钮操作的菜单
+//!
+//! 菜单为树形结构，叶子为数值项（如温控设定值）或动作项（如去皮），显示在HD44780字符屏或
+//! SSD1306 OLED上。旋转移动光标，短按进入子菜单、开始/确认编辑或执行动作，长按返回上一级
+//! 或取消编辑。显示按行输出文本，屏幕行数不足时随光标滚动
+//!
+//! ```ignore
+//! let thermostat = Arc::new(Mutex::new(thermostat));
+//! let (get, set) = (thermostat.clone(), thermostat.clone());
+//! let items = vec![
+//!     MenuItem::value(
+//!         "Setpoint",
+//!         10.0..=35.0,
+//!         0.5,
+//!         move || get.lock().map_or(0.0, |t| t.config().setpoint),
+//!         move |value| {
+//!             set.lock().map_err(|_| anyhow::anyhow!("温控器被锁定"))?.set_setpoint(value);
+//!             Ok(())
+//!         },
+//!     )
+//!     .with_unit("C"),
+//!     MenuItem::submenu("Scale", vec![MenuItem::action("Tare", move || scale.tare())]),
+//! ];
+//! let mut button = Button::new(13)?;
+//! let inputs = menu::inputs(RotaryEncoder::new(5, 6)?, &mut button, EventTiming::default())?;
+//! Menu::new(lcd, "Main", items).run(inputs);
+//! ```
+
+use std::ops::RangeInclusive;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "hd44780")]
+use crate::display::hd44780::HD44780;
+#[cfg(feature = "ssd1306")]
+use crate::display::ssd1306::{self, SSD1306};
+use crate::sensor::button::{Button, ButtonEvent, EventTiming};
+use crate::sensor::rotary_encoder::RotaryEncoder;
+
+/// 没有输入时刷新显示的间隔（数值项的当前值可能在其他地方被修改）
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 菜单输入
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MenuInput {
+    /// 旋转（定位格数，顺时针为正）
+    Rotate(i32),
+    /// 确认（短按）
+    Select,
+    /// 返回（长按）
+    Back,
+}
+
+impl MenuInput {
+    /// 按钮事件转换为菜单输入（长按为返回，松开时按下时长小于长按时长为确认）
+    ///
+    /// - hold: 长按判定时长，与监听按钮时的`EventTiming::hold`相同
+    pub fn from_button(event: ButtonEvent, hold: Duration) -> Option<Self> {
+        match event {
+            ButtonEvent::Held(_) => Some(MenuInput::Back),
+            ButtonEvent::Released(duration) if duration < hold => Some(MenuInput::Select),
+            _ => None,
+        }
+    }
+}
+
+/// 监听旋转编码器和按钮，转换为菜单输入
+///
+/// - encoder: 旋转编码器
+/// - button: 编码器的按钮（SW）
+/// - timing: 按钮的消抖和长按时间
+pub fn inputs(
+    encoder: RotaryEncoder,
+    button: &mut Button,
+    timing: EventTiming,
+) -> anyhow::Result<Receiver<MenuInput>> {
+    let (tx, rx) = mpsc::channel();
+    let rotate = tx.clone();
+    encoder.on_rotate(move |steps| {
+        let _ = rotate.send(MenuInput::Rotate(steps));
+    })?;
+    button.on_event_with_timing(timing, move |event| {
+        if let Some(input) = MenuInput::from_button(event, timing.hold) {
+            let _ = tx.send(input);
+        }
+    })?;
+    // OK
+    Ok(rx)
+}
+
+/// 菜单显示屏（按行显示文本）
+pub trait MenuDisplay {
+    /// 行数
+    fn rows(&self) -> usize;
+
+    /// 每行字符数
+    fn cols(&self) -> usize;
+
+    /// 显示所有行（行数不超过`rows`，每行不超过`cols`个字符）
+    fn show(&mut self, lines: &[String]) -> anyhow::Result<()>;
+}
+
+#[cfg(feature = "hd44780")]
+impl MenuDisplay for HD44780 {
+    fn rows(&self) -> usize {
+        HD44780::rows(self) as usize
+    }
+
+    fn cols(&self) -> usize {
+        HD44780::cols(self) as usize
+    }
+
+    fn show(&mut self, lines: &[String]) -> anyhow::Result<()> {
+        for row in 0..MenuDisplay::rows(self) {
+            let line = lines.get(row).map_or("", String::as_str);
+            self.write_line(row as u8, line)?;
+        }
+        // OK
+        Ok(())
+    }
+}
+
+#[cfg(feature = "ssd1306")]
+impl MenuDisplay for SSD1306 {
+    fn rows(&self) -> usize {
+        (self.size().height() / ssd1306::CHAR_HEIGHT) as usize
+    }
+
+    fn cols(&self) -> usize {
+        (self.size().width() / ssd1306::CHAR_WIDTH) as usize
+    }
+
+    fn show(&mut self, lines: &[String]) -> anyhow::Result<()> {
+        self.clear();
+        for (row, line) in lines.iter().enumerate() {
+            self.draw_text(0, row as u32 * ssd1306::CHAR_HEIGHT, line);
+        }
+        self.flush()
+    }
+}
+
+/// 数值项
+struct ValueItem {
+    /// 取值范围
+    range: RangeInclusive<f64>,
+    /// 每个定位格的步长
+    step: f64,
+    /// 显示的小数位数
+    decimals: usize,
+    /// 单位
+    unit: String,
+    /// 读取当前值
+    get: Box<dyn Fn() -> f64 + Send>,
+    /// 确认编辑后写入
+    set: Box<dyn FnMut(f64) -> anyhow::Result<()> + Send>,
+}
+
+impl ValueItem {
+    /// 格式化数值
+    fn format(&self, value: f64) -> String {
+        format!("{:.*}{}", self.decimals, value, self.unit)
+    }
+}
+
+/// 菜单项类型
+enum ItemKind {
+    /// 子菜单
+    Submenu(Vec<MenuItem>),
+    /// 数值
+    Value(ValueItem),
+    /// 动作
+    Action(Box<dyn FnMut() -> anyhow::Result<()> + Send>),
+}
+
+/// 菜单项
+pub struct MenuItem {
+    /// 显示的名称
+    label: String,
+    kind: ItemKind,
+}
+
+impl MenuItem {
+    /// 子菜单
+    pub fn submenu<S: Into<String>>(label: S, items: Vec<MenuItem>) -> Self {
+        Self {
+            label: label.into(),
+            kind: ItemKind::Submenu(items),
+        }
+    }
+
+    /// 可编辑的数值（编辑时旋转按步长调整，短按确认后写入，长按取消）
+    ///
+    /// - range: 取值范围
+    /// - step: 每个定位格的步长
+    /// - get: 读取当前值
+    /// - set: 写入新值
+    pub fn value<S, G, F>(label: S, range: RangeInclusive<f64>, step: f64, get: G, set: F) -> Self
+    where
+        S: Into<String>,
+        G: Fn() -> f64 + Send + 'static,
+        F: FnMut(f64) -> anyhow::Result<()> + Send + 'static,
+    {
+        // 按步长决定默认的小数位数
+        let decimals = (0..4)
+            .find(|&decimals| (step * 10f64.powi(decimals)).fract().abs() < 1e-9)
+            .unwrap_or(4) as usize;
+        Self {
+            label: label.into(),
+            kind: ItemKind::Value(ValueItem {
+                range,
+                step,
+                decimals,
+                unit: String::new(),
+                get: Box::new(get),
+                set: Box::new(set),
+            }),
+        }
+    }
+
+    /// 动作（短按执行，执行结果显示到下一次输入）
+    pub fn action<S, F>(label: S, action: F) -> Self
+    where
+        S: Into<String>,
+        F: FnMut() -> anyhow::Result<()> + Send + 'static,
+    {
+        Self {
+            label: label.into(),
+            kind: ItemKind::Action(Box::new(action)),
+        }
+    }
+
+    /// 设置数值的单位（只对数值项有效）
+    pub fn with_unit<S: Into<String>>(mut self, unit: S) -> Self {
+        if let ItemKind::Value(value) = &mut self.kind {
+            value.unit = unit.into();
+        }
+        self
+    }
+
+    /// 设置数值显示的小数位数（只对数值项有效，默认按步长决定）
+    pub fn with_decimals(mut self, decimals: usize) -> Self {
+        if let ItemKind::Value(value) = &mut self.kind {
+            value.decimals = decimals;
+        }
+        self
+    }
+
+    /// 名称
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+/// 菜单
+pub struct Menu<D: MenuDisplay> {
+    display: D,
+    /// 根菜单（子菜单项）
+    root: MenuItem,
+    /// 已进入的子菜单（各级的光标位置）
+    path: Vec<usize>,
+    /// 当前光标
+    cursor: usize,
+    /// 第一行显示的菜单项
+    scroll: usize,
+    /// 正在编辑的数值
+    editing: Option<f64>,
+    /// 动作的执行结果（显示到下一次输入）
+    message: Option<String>,
+}
+
+impl<D: MenuDisplay> Menu<D> {
+    /// 创建菜单
+    ///
+    /// - display: 显示屏
+    /// - title: 根菜单名称（屏幕超过2行时显示在第一行）
+    /// - items: 根菜单项
+    pub fn new<S: Into<String>>(display: D, title: S, items: Vec<MenuItem>) -> Self {
+        Self {
+            display,
+            root: MenuItem::submenu(title, items),
+            path: Vec::new(),
+            cursor: 0,
+            scroll: 0,
+            editing: None,
+            message: None,
+        }
+    }
+
+    /// 当前子菜单
+    fn current(&self) -> &MenuItem {
+        let mut menu = &self.root;
+        for &index in &self.path {
+            if let ItemKind::Submenu(items) = &menu.kind {
+                menu = &items[index];
+            }
+        }
+        menu
+    }
+
+    /// 当前子菜单的菜单项
+    fn items(&self) -> &[MenuItem] {
+        match &self.current().kind {
+            ItemKind::Submenu(items) => items,
+            _ => &[],
+        }
+    }
+
+    /// 光标所在的菜单项
+    fn selected_mut(&mut self) -> Option<&mut MenuItem> {
+        let mut items: &mut [MenuItem] = match &mut self.root.kind {
+            ItemKind::Submenu(items) => items,
+            _ => return None,
+        };
+        for &index in &self.path {
+            items = match &mut items[index].kind {
+                ItemKind::Submenu(children) => children,
+                _ => return None,
+            };
+        }
+        items.get_mut(self.cursor)
+    }
+
+    /// 是否正在编辑数值
+    pub fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    /// 处理一次输入并刷新显示
+    pub fn handle(&mut self, input: MenuInput) -> anyhow::Result<()> {
+        self.message = None;
+        let cursor = self.cursor;
+        match (input, self.editing) {
+            (MenuInput::Rotate(steps), Some(value)) => {
+                if let Some(MenuItem {
+                    kind: ItemKind::Value(item),
+                    ..
+                }) = self.selected_mut()
+                {
+                    let value = (value + steps as f64 * item.step)
+                        .clamp(*item.range.start(), *item.range.end());
+                    self.editing = Some(value);
+                }
+            }
+            (MenuInput::Select, Some(value)) => {
+                self.editing = None;
+                if let Some(MenuItem {
+                    kind: ItemKind::Value(item),
+                    ..
+                }) = self.selected_mut()
+                    && let Err(err) = (item.set)(value)
+                {
+                    self.message = Some(format!("! {}", err));
+                }
+            }
+            (MenuInput::Back, Some(_)) => self.editing = None,
+            (MenuInput::Rotate(steps), None) => {
+                let last = self.items().len().saturating_sub(1) as i64;
+                self.cursor = (cursor as i64 + steps as i64).clamp(0, last) as usize;
+            }
+            (MenuInput::Select, None) => match self.selected_mut().map(|item| &mut item.kind) {
+                Some(ItemKind::Submenu(_)) => {
+                    self.path.push(cursor);
+                    self.cursor = 0;
+                    self.scroll = 0;
+                }
+                Some(ItemKind::Value(item)) => self.editing = Some((item.get)()),
+                Some(ItemKind::Action(action)) => {
+                    self.message = Some(match action() {
+                        Ok(()) => "OK".to_string(),
+                        Err(err) => format!("! {}", err),
+                    });
+                }
+                None => {}
+            },
+            (MenuInput::Back, None) => {
+                if let Some(parent) = self.path.pop() {
+                    self.cursor = parent;
+                    self.scroll = 0;
+                }
+            }
+        }
+        self.render()
+    }
+
+    /// 刷新显示
+    pub fn render(&mut self) -> anyhow::Result<()> {
+        let rows = self.display.rows();
+        let cols = self.display.cols();
+        let mut lines = Vec::with_capacity(rows);
+        // 行数较多时第一行显示菜单名称
+        if rows > 2 {
+            lines.push(self.current().label.clone());
+        }
+        // 最后一行显示动作的执行结果
+        let visible = rows
+            .saturating_sub(lines.len() + self.message.is_some() as usize)
+            .max(1);
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + visible {
+            self.scroll = self.cursor + 1 - visible;
+        }
+        for (index, item) in self
+            .items()
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(visible)
+        {
+            let selected = index == self.cursor;
+            let marker = if selected { '>' } else { ' ' };
+            let line = match &item.kind {
+                ItemKind::Submenu(_) => format!("{}{}", marker, item.label),
+                ItemKind::Action(_) => format!("{}{}", marker, item.label),
+                ItemKind::Value(value) => {
+                    let text = match self.editing {
+                        Some(editing) if selected => format!("[{}]", value.format(editing)),
+                        _ => value.format((value.get)()),
+                    };
+                    // 数值右对齐
+                    let label: String = item
+                        .label
+                        .chars()
+                        .take(cols.saturating_sub(text.chars().count() + 2))
+                        .collect();
+                    let padding =
+                        cols.saturating_sub(label.chars().count() + text.chars().count() + 1);
+                    format!("{}{}{}{}", marker, label, " ".repeat(padding), text)
+                }
+            };
+            lines.push(line.chars().take(cols).collect());
+        }
+        if let Some(message) = &self.message {
+            lines.truncate(rows.saturating_sub(1));
+            lines.push(message.chars().take(cols).collect());
+        }
+        self.display.show(&lines)
+    }
+
+    /// 在独立线程中处理输入，输入端全部关闭后退出
+    pub fn run(mut self, inputs: Receiver<MenuInput>) -> thread::JoinHandle<()>
+    where
+        D: Send + 'static,
+    {
+        thread::spawn(move || {
+            if let Err(err) = self.render() {
+                eprintln!("刷新菜单显示失败: {}", err);
+            }
+            loop {
+                let result = match inputs.recv_timeout(REFRESH_INTERVAL) {
+                    Ok(input) => self.handle(input),
+                    Err(RecvTimeoutError::Timeout) => self.render(),
+                    Err(RecvTimeoutError::Disconnected) => break,
+                };
+                if let Err(err) = result {
+                    eprintln!("刷新菜单显示失败: {}", err);
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,2 @@
+#[cfg(feature = "menu")]
+pub mod menu;
@@ -0,0 +1,3 @@
+//! 单位换算和显示格式
+
+pub mod weight;
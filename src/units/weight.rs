@@ -0,0 +1,264 @@
+//! 重量单位、显示分度值和零点跟踪
+//!
+//! 电子秤按分度值显示（OIML R76：分度值为1、2或5乘以10的整数次幂），读数取整到最近的
+//! 分度值；秤台空载时的缓慢漂移（零点蠕变）由零点跟踪在稳定时自动归零
+
+use std::fmt;
+use std::str::FromStr;
+
+/// 每盎司的克数
+const GRAMS_PER_OUNCE: f64 = 28.349_523_125;
+
+/// 每磅的克数
+const GRAMS_PER_POUND: f64 = 453.592_37;
+
+/// 重量单位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WeightUnit {
+    /// 克
+    Gram,
+    /// 千克
+    Kilogram,
+    /// 盎司
+    Ounce,
+    /// 磅
+    Pound,
+}
+
+impl WeightUnit {
+    /// 克换算为当前单位
+    pub fn from_grams(self, grams: f64) -> f64 {
+        match self {
+            Self::Gram => grams,
+            Self::Kilogram => grams / 1000.0,
+            Self::Ounce => grams / GRAMS_PER_OUNCE,
+            Self::Pound => grams / GRAMS_PER_POUND,
+        }
+    }
+
+    /// 当前单位换算为克
+    pub fn to_grams(self, value: f64) -> f64 {
+        match self {
+            Self::Gram => value,
+            Self::Kilogram => value * 1000.0,
+            Self::Ounce => value * GRAMS_PER_OUNCE,
+            Self::Pound => value * GRAMS_PER_POUND,
+        }
+    }
+
+    /// 单位符号
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Gram => "g",
+            Self::Kilogram => "kg",
+            Self::Ounce => "oz",
+            Self::Pound => "lb",
+        }
+    }
+}
+
+impl fmt::Display for WeightUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+impl FromStr for WeightUnit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "g" => Ok(Self::Gram),
+            "kg" => Ok(Self::Kilogram),
+            "oz" => Ok(Self::Ounce),
+            "lb" => Ok(Self::Pound),
+            _ => Err(anyhow::anyhow!("无效的重量单位: {}", s)),
+        }
+    }
+}
+
+/// 显示分度值（显示单位下的1、2或5乘以10的整数次幂，如1g、0.005kg）
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "f64", into = "f64"))]
+pub struct Division {
+    /// 分度值
+    value: f64,
+    /// 小数位数
+    decimals: usize,
+}
+
+impl Division {
+    /// 创建分度值（不是1、2、5乘以10的整数次幂时返回错误）
+    pub fn new(value: f64) -> anyhow::Result<Self> {
+        if !value.is_finite() || value <= 0.0 {
+            return Err(anyhow::anyhow!("分度值必须大于0: {}", value));
+        }
+        let exponent = value.log10().floor() as i32;
+        let mantissa = value / 10f64.powi(exponent);
+        let Some(&leading) = [1.0, 2.0, 5.0, 10.0]
+            .iter()
+            .find(|&&m| (mantissa - m).abs() < 1e-6)
+        else {
+            return Err(anyhow::anyhow!(
+                "分度值必须为1、2或5乘以10的整数次幂: {}",
+                value
+            ));
+        };
+        // 尾数为10时为浮点误差导致的进位
+        let exponent = if leading == 10.0 {
+            exponent + 1
+        } else {
+            exponent
+        };
+        // OK
+        Ok(Self {
+            value,
+            decimals: (-exponent).max(0) as usize,
+        })
+    }
+
+    /// 分度值
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// 显示的小数位数
+    pub fn decimals(&self) -> usize {
+        self.decimals
+    }
+
+    /// 取整到最近的分度值（负零显示为0）
+    pub fn round(&self, value: f64) -> f64 {
+        let rounded = (value / self.value).round() * self.value;
+        if rounded == 0.0 { 0.0 } else { rounded }
+    }
+}
+
+impl TryFrom<f64> for Division {
+    type Error = anyhow::Error;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<Division> for f64 {
+    fn from(division: Division) -> Self {
+        division.value
+    }
+}
+
+/// 重量显示格式（单位和分度值）
+///
+/// ```ignore
+/// let format = WeightFormat::new(WeightUnit::Kilogram).with_division(Division::new(0.005)?);
+/// assert_eq!(format.format(1236.0), "1.235kg");
+/// lcd.write_weight(0, 1236.0, &format)?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightFormat {
+    /// 显示单位
+    pub unit: WeightUnit,
+    /// 分度值，为None时保留1位小数且不取整
+    pub division: Option<Division>,
+}
+
+impl WeightFormat {
+    /// 创建显示格式（不取整，保留1位小数）
+    pub fn new(unit: WeightUnit) -> Self {
+        Self {
+            unit,
+            division: None,
+        }
+    }
+
+    /// 设置分度值
+    pub fn with_division(mut self, division: Division) -> Self {
+        self.division = Some(division);
+        self
+    }
+
+    /// 按显示单位换算并取整到分度值
+    pub fn round(&self, grams: f64) -> f64 {
+        let value = self.unit.from_grams(grams);
+        match self.division {
+            Some(division) => division.round(value),
+            None => value,
+        }
+    }
+
+    /// 显示的小数位数
+    pub fn decimals(&self) -> usize {
+        self.division.map_or(1, |division| division.decimals())
+    }
+
+    /// 格式化数值（不带单位，适合数码管）
+    pub fn format_value(&self, grams: f64) -> String {
+        format!("{:.*}", self.decimals(), self.round(grams))
+    }
+
+    /// 格式化数值和单位
+    pub fn format(&self, grams: f64) -> String {
+        format!("{}{}", self.format_value(grams), self.unit)
+    }
+}
+
+/// 零点跟踪（自动零点维持）
+///
+/// 读数稳定且偏离零点不超过跟踪范围时，把偏离量计入零点修正，显示回到0；
+/// 累计修正量不超过限值（OIML R76为最大称量的4%），超过后需要手动去皮
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZeroTracker {
+    /// 跟踪范围（克，通常为0.5个分度值）
+    band: f64,
+    /// 累计修正限值（克）
+    limit: f64,
+    /// 累计修正量（克）
+    offset: f64,
+}
+
+impl ZeroTracker {
+    /// 创建零点跟踪
+    ///
+    /// - band: 跟踪范围（克）
+    /// - limit: 累计修正限值（克）
+    pub fn new(band: f64, limit: f64) -> Self {
+        Self {
+            band: band.abs(),
+            limit: limit.abs(),
+            offset: 0.0,
+        }
+    }
+
+    /// 修改跟踪范围和累计修正限值（克），已有的修正量保留
+    pub fn set_range(&mut self, band: f64, limit: f64) {
+        self.band = band.abs();
+        self.limit = limit.abs();
+    }
+
+    /// 累计修正量（克）
+    pub fn offset(&self) -> f64 {
+        self.offset
+    }
+
+    /// 清除累计修正量（去皮后调用）
+    pub fn reset(&mut self) {
+        self.offset = 0.0;
+    }
+
+    /// 修正读数
+    ///
+    /// - grams: 去皮后的重量（克）
+    /// - stable: 读数是否稳定
+    pub fn apply(&mut self, grams: f64, stable: bool) -> f64 {
+        let net = grams - self.offset;
+        if stable && net != 0.0 && net.abs() <= self.band && (self.offset + net).abs() <= self.limit
+        {
+            self.offset += net;
+            return 0.0;
+        }
+        net
+    }
+}
@@ -0,0 +1,235 @@
+//! 看门狗
+//!
+//! 无人值守部署时，I2C总线卡死或互斥锁死锁会让采集静默停止而进程仍在运行。
+//! `Watchdog`订阅调度器的读数，只有在每个传感器都在期限内读取成功时才喂狗，
+//! 采集停滞后由硬件看门狗（/dev/watchdog）重启设备，或由systemd（WatchdogSec=）重启服务
+
+use std::collections::HashMap;
+use std::env;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::health::HealthRegistry;
+use crate::reading::Sample;
+use crate::service::{self, Notifier};
+
+/// 硬件看门狗设备
+const WATCHDOG_DEVICE: &str = "/dev/watchdog";
+
+/// 没有指定期限时，任意传感器读取成功的期限
+const DEFAULT_DEADLINE: Duration = Duration::from_secs(60);
+
+/// 喂狗方式
+enum Backend {
+    /// 硬件看门狗（写入任意字符喂狗，写入'V'后关闭为正常停止）
+    Device(File),
+    /// systemd服务看门狗（向NOTIFY_SOCKET发送WATCHDOG=1）
+    Systemd(Notifier),
+}
+
+/// 看门狗
+pub struct Watchdog {
+    /// 喂狗方式
+    backend: Backend,
+    /// 喂狗间隔
+    interval: Duration,
+    /// 各传感器的读取期限
+    deadlines: HashMap<String, Duration>,
+    /// 任意传感器读取成功的期限（没有指定传感器期限时默认为60秒）
+    any_deadline: Option<Duration>,
+    /// 健康统计及所有传感器读取成功的期限
+    health: Option<(HealthRegistry, Duration)>,
+}
+
+impl Watchdog {
+    /// 创建实例
+    fn new(backend: Backend, interval: Duration) -> Self {
+        Self {
+            backend,
+            interval,
+            deadlines: HashMap::new(),
+            any_deadline: None,
+            health: None,
+        }
+    }
+
+    /// 打开硬件看门狗（/dev/watchdog，树莓派需要`dtparam=watchdog=on`）
+    ///
+    /// 打开后看门狗即开始计时（bcm2835_wdt默认超时15秒），默认每5秒喂狗一次
+    pub fn open() -> anyhow::Result<Self> {
+        Self::open_device(WATCHDOG_DEVICE)
+    }
+
+    /// 打开指定的硬件看门狗设备
+    pub fn open_device(path: &str) -> anyhow::Result<Self> {
+        let file = OpenOptions::new()
+            .write(true)
+            .open(path)
+            .map_err(|err| anyhow::anyhow!("打开看门狗设备{}失败: {}", path, err))?;
+        // OK
+        Ok(Self::new(Backend::Device(file), Duration::from_secs(5)))
+    }
+
+    /// 使用systemd服务看门狗（需要在服务单元中配置`WatchdogSec=`）
+    ///
+    /// 喂狗间隔为WATCHDOG_USEC的一半
+    pub fn systemd() -> anyhow::Result<Self> {
+        if env::var_os("WATCHDOG_USEC").is_none() {
+            return Err(anyhow::anyhow!(
+                "没有启用systemd看门狗（WATCHDOG_USEC未设置）"
+            ));
+        }
+        // WATCHDOG_PID存在时只有对应的进程需要喂狗
+        let timeout = service::watchdog_timeout()?
+            .ok_or_else(|| anyhow::anyhow!("systemd看门狗不属于当前进程"))?;
+        let notifier = Notifier::from_env()?
+            .ok_or_else(|| anyhow::anyhow!("没有systemd通知套接字（NOTIFY_SOCKET未设置）"))?;
+        // OK
+        Ok(Self::new(Backend::Systemd(notifier), timeout / 2))
+    }
+
+    /// 优先使用systemd服务看门狗，没有配置时使用硬件看门狗
+    pub fn detect() -> anyhow::Result<Self> {
+        if env::var_os("WATCHDOG_USEC").is_some() {
+            Self::systemd()
+        } else {
+            Self::open()
+        }
+    }
+
+    /// 设置喂狗间隔（需要小于看门狗超时时间）
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// 要求指定传感器在期限内读取成功
+    pub fn expect(mut self, sensor: &str, deadline: Duration) -> Self {
+        self.deadlines.insert(sensor.to_string(), deadline);
+        self
+    }
+
+    /// 要求任意传感器在期限内读取成功
+    pub fn expect_any(mut self, deadline: Duration) -> Self {
+        self.any_deadline = Some(deadline);
+        self
+    }
+
+    /// 要求管理器中的所有传感器在期限内读取成功（根据健康统计判断，包括没有订阅读数的传感器）
+    pub fn expect_healthy(mut self, health: HealthRegistry, deadline: Duration) -> Self {
+        self.health = Some((health, deadline));
+        self
+    }
+
+    /// 发送systemd通知
+    fn notify(&self, state: &str) -> anyhow::Result<()> {
+        match &self.backend {
+            Backend::Systemd(notifier) => notifier.notify(state),
+            Backend::Device(_) => Ok(()),
+        }
+    }
+
+    /// 喂狗
+    pub fn feed(&mut self) -> anyhow::Result<()> {
+        match &mut self.backend {
+            Backend::Device(file) => file
+                .write_all(b"1")
+                .and_then(|_| file.flush())
+                .map_err(|err| anyhow::anyhow!("喂狗失败: {}", err)),
+            Backend::Systemd(_) => self.notify("WATCHDOG=1"),
+        }
+    }
+
+    /// 正常停止看门狗（硬件看门狗写入'V'后关闭，systemd发送STOPPING=1）
+    pub fn close(mut self) -> anyhow::Result<()> {
+        match &mut self.backend {
+            Backend::Device(file) => file
+                .write_all(b"V")
+                .map_err(|err| anyhow::anyhow!("关闭看门狗失败: {}", err)),
+            Backend::Systemd(_) => self.notify("STOPPING=1"),
+        }
+    }
+
+    /// 超过期限的传感器（为空时表示采集正常）
+    fn overdue(&self, last_seen: &HashMap<String, Instant>, started: Instant) -> Vec<String> {
+        let now = Instant::now();
+        let elapsed = |since: Option<&Instant>| now.duration_since(*since.unwrap_or(&started));
+        let mut overdue: Vec<String> = self
+            .deadlines
+            .iter()
+            .filter(|(sensor, deadline)| elapsed(last_seen.get(*sensor)) > **deadline)
+            .map(|(sensor, _)| sensor.clone())
+            .collect();
+        let any_deadline = match self.any_deadline {
+            Some(deadline) => Some(deadline),
+            None if self.deadlines.is_empty() => Some(DEFAULT_DEADLINE),
+            None => None,
+        };
+        if let Some(deadline) = any_deadline
+            && elapsed(last_seen.values().max()) > deadline
+        {
+            overdue.push("任意传感器".to_string());
+        }
+        if let Some((health, deadline)) = &self.health {
+            for (sensor, _) in health.silent(*deadline) {
+                if !overdue.contains(&sensor) {
+                    overdue.push(sensor);
+                }
+            }
+        }
+        overdue.sort();
+        overdue
+    }
+
+    /// 在后台线程中根据调度器的读数喂狗
+    ///
+    /// 启动时视为所有传感器刚读取成功；读数通道关闭（调度器正常停止）时关闭看门狗并退出
+    pub fn run(mut self, samples: Receiver<Sample>) -> JoinHandle<()> {
+        thread::spawn(move || {
+            if let Err(err) = self.notify("READY=1") {
+                eprintln!("{}", err);
+            }
+            let started = Instant::now();
+            let mut last_seen: HashMap<String, Instant> = HashMap::new();
+            let mut next_feed = started;
+            let mut starving = false;
+            loop {
+                let timeout = next_feed.saturating_duration_since(Instant::now());
+                match samples.recv_timeout(timeout) {
+                    Ok(sample) => {
+                        last_seen.insert(sample.sensor, Instant::now());
+                        if Instant::now() < next_feed {
+                            continue;
+                        }
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => {
+                        if let Err(err) = self.close() {
+                            eprintln!("{}", err);
+                        }
+                        return;
+                    }
+                }
+                next_feed = Instant::now() + self.interval;
+                let overdue = self.overdue(&last_seen, started);
+                if overdue.is_empty() {
+                    if starving {
+                        trace_event!(info, "采集恢复，继续喂狗");
+                        starving = false;
+                    }
+                    if let Err(err) = self.feed() {
+                        eprintln!("{}", err);
+                    }
+                } else if !starving {
+                    // 停止喂狗，等待看门狗超时重启
+                    trace_event!(error, sensors = ?overdue, "传感器读取超过期限，停止喂狗");
+                    eprintln!("传感器读取超过期限，停止喂狗: {}", overdue.join(", "));
+                    starving = true;
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,298 @@
+//! 补偿计算和协议解析测试（不需要硬件）
+//!
+//! 使用数据手册中的示例数据检查BME280补偿计算、AHT30原始值换算、DHT11/DHT22帧解析和
+//! HX711补码扩展，并用随机输入检查取值范围，修改算法（如改为定点计算）后可以直接回归
+
+use proptest::prelude::*;
+use raspi_sensor::core::aht30;
+use raspi_sensor::core::bme280::{Calibration, Compensation, RawData};
+use raspi_sensor::core::dht::{self, DhtModel, FRAME_BITS};
+use raspi_sensor::core::hx711::sign_extend;
+
+/// BME280数据手册8.2节的校准参数示例（湿度参数为常见模块的典型值）
+fn bme280_calibration() -> Calibration {
+    Calibration {
+        t1: 27504,
+        t2: 26435,
+        t3: -1000,
+        p1: 36477,
+        p2: -10685,
+        p3: 3024,
+        p4: 2855,
+        p5: 140,
+        p6: -7,
+        p7: 15500,
+        p8: -14600,
+        p9: 6000,
+        h1: 75,
+        h2: 362,
+        h3: 0,
+        h4: 324,
+        h5: 50,
+        h6: 30,
+    }
+}
+
+/// 组装AHT30测量结果（状态 + 湿度、温度各20位 + CRC）
+fn aht30_frame(raw_humidity: u32, raw_temperature: u32) -> [u8; 7] {
+    let mut data = [
+        0x1C,
+        (raw_humidity >> 12) as u8,
+        (raw_humidity >> 4) as u8,
+        ((raw_humidity as u8 & 0x0F) << 4) | (raw_temperature >> 16) as u8 & 0x0F,
+        (raw_temperature >> 8) as u8,
+        raw_temperature as u8,
+        0,
+    ];
+    data[6] = aht30::crc8(&data[..6]);
+    data
+}
+
+/// 组装DHT帧（校验和为前4字节之和）
+fn dht_frame(data: [u8; 4]) -> [u8; 5] {
+    let sum = data.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    [data[0], data[1], data[2], data[3], sum]
+}
+
+#[test]
+fn bme280_datasheet_temperature() {
+    let (temperature, t_fine) = bme280_calibration().compensate_temperature(519888);
+    assert!((temperature - 25.08).abs() < 0.01, "{}", temperature);
+    assert!((t_fine - 128422.0).abs() < 1.0, "{}", t_fine);
+}
+
+#[test]
+fn bme280_datasheet_pressure() {
+    let calibration = bme280_calibration();
+    let (_, t_fine) = calibration.compensate_temperature(519888);
+    let pressure = calibration.compensate_pressure(415148, t_fine);
+    assert!((pressure - 100653.27).abs() < 0.5, "{}", pressure);
+}
+
+#[test]
+fn bme280_datasheet_integer() {
+    let calibration = bme280_calibration();
+    let (temperature, t_fine) = calibration.compensate_temperature_int(519888);
+    assert_eq!(temperature, 2508);
+    assert_eq!(t_fine, 128422);
+    let pressure = calibration.compensate_pressure_int(415148, t_fine);
+    assert!(
+        (pressure as f64 / 256.0 - 100653.27).abs() < 1.0,
+        "{}",
+        pressure
+    );
+}
+
+#[test]
+fn bme280_compensation_select() {
+    let calibration = bme280_calibration();
+    let (float, _) = calibration.compensate_temperature_with(519888, Compensation::Float);
+    let (integer, _) = calibration.compensate_temperature_with(519888, Compensation::Integer);
+    assert_eq!(integer, 25.08);
+    assert!((float - integer).abs() < 0.01);
+}
+
+#[test]
+fn bme280_pressure_invalid_calibration() {
+    let calibration = Calibration {
+        p1: 0,
+        ..bme280_calibration()
+    };
+    assert_eq!(calibration.compensate_pressure(415148, 128422.0), 0.0);
+}
+
+#[test]
+fn bme280_raw_data_parse() {
+    let raw = RawData::parse(&[0x65, 0x5A, 0xC0, 0x7E, 0xED, 0x00, 0x75, 0x30]);
+    assert_eq!(raw.pressure, 415148);
+    assert_eq!(raw.temperature, 519888);
+    assert_eq!(raw.humidity, 30000);
+}
+
+#[test]
+fn bme280_calibration_parse() {
+    let expected = bme280_calibration();
+    let mut tp = [0u8; 26];
+    let words = [
+        expected.t1,
+        expected.t2 as u16,
+        expected.t3 as u16,
+        expected.p1,
+        expected.p2 as u16,
+        expected.p3 as u16,
+        expected.p4 as u16,
+        expected.p5 as u16,
+        expected.p6 as u16,
+        expected.p7 as u16,
+        expected.p8 as u16,
+        expected.p9 as u16,
+    ];
+    for (i, word) in words.iter().enumerate() {
+        tp[i * 2..i * 2 + 2].copy_from_slice(&word.to_le_bytes());
+    }
+    tp[25] = expected.h1;
+    let h4 = expected.h4;
+    let h5 = expected.h5;
+    let h2 = expected.h2.to_le_bytes();
+    let h = [
+        h2[0],
+        h2[1],
+        expected.h3,
+        (h4 >> 4) as u8,
+        (h4 & 0x0F) as u8 | ((h5 & 0x0F) << 4) as u8,
+        (h5 >> 4) as u8,
+        expected.h6 as u8,
+    ];
+    assert_eq!(Calibration::parse(&tp, &h), expected);
+}
+
+#[test]
+fn aht30_crc8_vector() {
+    // CRC-8/NRSC-5（多项式0x31，初始值0xFF）的标准测试数据
+    assert_eq!(aht30::crc8(&[0xBE, 0xEF]), 0x92);
+}
+
+#[test]
+fn aht30_raw_to_physical() {
+    let (temperature, humidity) = aht30::parse(&aht30_frame(0x80000, 0x60000)).unwrap();
+    assert!((humidity - 50.0).abs() < 1e-9);
+    assert!((temperature - 25.0).abs() < 1e-9);
+}
+
+#[test]
+fn aht30_checksum_error() {
+    let mut data = aht30_frame(0x80000, 0x60000);
+    data[6] ^= 0xFF;
+    assert!(aht30::parse(&data).is_err());
+}
+
+#[test]
+fn aht30_busy_bit() {
+    assert!(aht30::is_busy(0x9C));
+    assert!(!aht30::is_busy(0x1C));
+}
+
+#[test]
+fn dht11_frame() {
+    let frame = dht_frame([0x35, 0x00, 0x18, 0x00]);
+    assert_eq!(
+        dht::parse_frame(&frame, DhtModel::Dht11).unwrap(),
+        (24.0, 53.0)
+    );
+}
+
+#[test]
+fn dht11_negative_decimal() {
+    let (temperature, _) =
+        dht::parse_frame(&dht_frame([0x30, 0x00, 0x02, 0x85]), DhtModel::Dht11).unwrap();
+    assert!((temperature + 2.5).abs() < 1e-9, "{}", temperature);
+}
+
+#[test]
+fn dht22_datasheet_frames() {
+    // AM2302数据手册示例：湿度65.2%、温度35.1℃，以及温度-10.1℃
+    let (temperature, humidity) =
+        dht::parse_frame(&[0x02, 0x8C, 0x01, 0x5F, 0xEE], DhtModel::Dht22).unwrap();
+    assert!((humidity - 65.2).abs() < 1e-9);
+    assert!((temperature - 35.1).abs() < 1e-9);
+    let (temperature, _) =
+        dht::parse_frame(&dht_frame([0x02, 0x8C, 0x80, 0x65]), DhtModel::Dht22).unwrap();
+    assert!((temperature + 10.1).abs() < 1e-9);
+}
+
+#[test]
+fn dht_checksum_error() {
+    let mut frame = dht_frame([0x35, 0x00, 0x18, 0x00]);
+    frame[4] = frame[4].wrapping_add(1);
+    assert!(dht::parse_frame(&frame, DhtModel::Dht11).is_err());
+}
+
+#[test]
+fn dht_frame_from_pulses() {
+    let frame = dht_frame([0x35, 0x00, 0x18, 0x00]);
+    let mut widths = [0u32; FRAME_BITS];
+    for (i, width) in widths.iter_mut().enumerate() {
+        let bit = frame[i / 8] & (0x80 >> (i % 8)) != 0;
+        *width = if bit { 70 } else { 27 };
+    }
+    assert_eq!(dht::frame_from_pulses(&widths), frame);
+}
+
+#[test]
+fn hx711_sign_extend_limits() {
+    assert_eq!(sign_extend(0x7FFFFF), 8_388_607);
+    assert_eq!(sign_extend(0x800000), -8_388_608);
+    assert_eq!(sign_extend(0xFFFFFF), -1);
+    assert_eq!(sign_extend(0), 0);
+}
+
+proptest! {
+    #[test]
+    fn bme280_temperature_monotonic(adc in 0i32..0xFFFFF) {
+        let calibration = bme280_calibration();
+        let (low, _) = calibration.compensate_temperature(adc);
+        let (high, _) = calibration.compensate_temperature(adc + 1);
+        prop_assert!(high >= low);
+    }
+
+    #[test]
+    fn bme280_pressure_in_range(adc_t in 400_000i32..600_000, adc_p in 250_000i32..600_000) {
+        let calibration = bme280_calibration();
+        let (temperature, t_fine) = calibration.compensate_temperature(adc_t);
+        let pressure = calibration.compensate_pressure(adc_p, t_fine);
+        prop_assert!((-40.0..=85.0).contains(&temperature));
+        prop_assert!(pressure > 0.0 && pressure.is_finite());
+    }
+
+    #[test]
+    fn bme280_humidity_clamped(adc_h in 0i32..=0xFFFF, adc_t in 0i32..0xFFFFF) {
+        let calibration = bme280_calibration();
+        let (_, t_fine) = calibration.compensate_temperature(adc_t);
+        let humidity = calibration.compensate_humidity(adc_h, t_fine);
+        prop_assert!((0.0..=100.0).contains(&humidity));
+    }
+
+    #[test]
+    fn bme280_integer_pressure_matches_float(adc_t in 400_000i32..600_000, adc_p in 250_000i32..600_000) {
+        let calibration = bme280_calibration();
+        let (_, t_fine) = calibration.compensate_temperature(adc_t);
+        let (_, t_fine_int) = calibration.compensate_temperature_int(adc_t);
+        let float = calibration.compensate_pressure(adc_p, t_fine);
+        let integer = calibration.compensate_pressure_int(adc_p, t_fine_int) as f64 / 256.0;
+        prop_assert!((float - integer).abs() < 2.0, "{} {}", float, integer);
+    }
+
+    #[test]
+    fn bme280_integer_humidity_matches_float(adc_t in 400_000i32..600_000, adc_h in 20_000i32..40_000) {
+        let calibration = bme280_calibration();
+        let (_, t_fine) = calibration.compensate_temperature(adc_t);
+        let (_, t_fine_int) = calibration.compensate_temperature_int(adc_t);
+        let float = calibration.compensate_humidity(adc_h, t_fine);
+        let integer = calibration.compensate_humidity_int(adc_h, t_fine_int) as f64 / 1024.0;
+        prop_assert!((float - integer).abs() < 0.1, "{} {}", float, integer);
+    }
+
+    #[test]
+    fn aht30_in_range(raw_humidity in 0u32..1 << 20, raw_temperature in 0u32..1 << 20) {
+        let (temperature, humidity) =
+            aht30::parse(&aht30_frame(raw_humidity, raw_temperature)).unwrap();
+        prop_assert!((0.0..100.0).contains(&humidity));
+        prop_assert!((-50.0..150.0).contains(&temperature));
+    }
+
+    #[test]
+    fn dht22_sign_symmetric(magnitude in 0u16..0x8000, humidity in 0u16..1000) {
+        let [h0, h1] = humidity.to_be_bytes();
+        let [t0, t1] = magnitude.to_be_bytes();
+        let (positive, _) =
+            dht::parse_frame(&dht_frame([h0, h1, t0, t1]), DhtModel::Dht22).unwrap();
+        let (negative, _) =
+            dht::parse_frame(&dht_frame([h0, h1, t0 | 0x80, t1]), DhtModel::Dht22).unwrap();
+        prop_assert_eq!(positive, -negative);
+    }
+
+    #[test]
+    fn hx711_sign_extend_roundtrip(value in -(1i32 << 23)..(1i32 << 23)) {
+        prop_assert_eq!(sign_extend(value & 0xFF_FFFF), value);
+    }
+}
@@ -0,0 +1,103 @@
+//! 外壳内温湿度补偿测试（不需要硬件）
+//!
+//! 检查偏差随开机时间的变化、补偿前后水汽压（露点）不变、校准数据的保存和读取，
+//! 以及按补偿后的温湿度计算的酷热指数
+
+use std::time::Duration;
+
+use raspi_sensor::calibration::Calibration;
+use raspi_sensor::enclosure::EnclosureModel;
+use raspi_sensor::reading::{Quantity, Reading};
+use raspi_sensor::station::{dew_point, heat_index};
+
+/// 固定偏差1℃，每小时升温2℃，1.5小时后稳定
+fn model() -> EnclosureModel {
+    EnclosureModel::new(1.0, 2.0, Duration::from_secs(5400))
+}
+
+fn hours(hours: f64) -> Duration {
+    Duration::from_secs_f64(hours * 3600.0)
+}
+
+#[test]
+fn bias_grows_until_settled() {
+    let model = model();
+    assert!((model.bias(Duration::ZERO) - 1.0).abs() < 1e-9);
+    assert!((model.bias(hours(1.0)) - 3.0).abs() < 1e-9);
+    assert!((model.bias(hours(1.5)) - 4.0).abs() < 1e-9);
+    assert!((model.bias(hours(10.0)) - 4.0).abs() < 1e-9);
+}
+
+#[test]
+fn correct_keeps_vapor_pressure() {
+    let reading = Reading::new()
+        .with(Quantity::Temperature, 30.0)
+        .with(Quantity::Humidity, 40.0)
+        .with(Quantity::Pressure, 101_325.0);
+    let corrected = model().correct(&reading, hours(1.0));
+
+    let temperature = corrected.get(Quantity::Temperature).unwrap();
+    let humidity = corrected.get(Quantity::Humidity).unwrap();
+    assert!((temperature - 27.0).abs() < 1e-9);
+    // 温度降低后相对湿度升高，露点不变
+    assert!(humidity > 40.0);
+    assert!((dew_point(temperature, humidity) - dew_point(30.0, 40.0)).abs() < 1e-6);
+    assert_eq!(corrected.get(Quantity::Pressure), Some(101_325.0));
+}
+
+#[test]
+fn correct_without_temperature_is_unchanged() {
+    let reading = Reading::new().with(Quantity::Humidity, 40.0);
+    assert_eq!(model().correct(&reading, hours(1.0)), reading);
+}
+
+#[test]
+fn save_load_round_trip() {
+    let mut calibration = Calibration::new();
+    assert_eq!(EnclosureModel::load(&calibration, "dht11").unwrap(), None);
+
+    model().save(&mut calibration, "dht11");
+    assert_eq!(
+        EnclosureModel::load(&calibration, "dht11").unwrap(),
+        Some(model())
+    );
+    assert_eq!(EnclosureModel::load(&calibration, "aht30").unwrap(), None);
+}
+
+#[test]
+fn load_rejects_invalid_settle() {
+    let mut calibration = Calibration::new();
+    calibration.set("dht11.enclosure_offset", 1.0);
+    calibration.set("dht11.enclosure_settle", -60.0);
+    assert!(EnclosureModel::load(&calibration, "dht11").is_err());
+    calibration.set("dht11.enclosure_settle", f64::INFINITY);
+    assert!(EnclosureModel::load(&calibration, "dht11").is_err());
+}
+
+#[test]
+fn heat_index_uses_corrected_reading() {
+    // 美国国家气象局酷热指数表：90℉、相对湿度70%时为106℉
+    let fahrenheit = |celsius: f64| celsius * 1.8 + 32.0;
+    assert!((fahrenheit(heat_index((90.0 - 32.0) / 1.8, 70.0)) - 106.0).abs() < 0.5);
+    // 低温时接近实际温度
+    assert!((heat_index(20.0, 50.0) - 20.0).abs() < 1.0);
+
+    let reading = Reading::new()
+        .with(Quantity::Temperature, 36.0)
+        .with(Quantity::Humidity, 50.0);
+    let model = model();
+    let corrected = model.correct(&reading, hours(1.0));
+    let expected = heat_index(
+        corrected.get(Quantity::Temperature).unwrap(),
+        corrected.get(Quantity::Humidity).unwrap(),
+    );
+    assert_eq!(model.heat_index(&reading, hours(1.0)), Some(expected));
+    assert!(expected < heat_index(36.0, 50.0));
+    assert_eq!(
+        model.heat_index(
+            &Reading::new().with(Quantity::Temperature, 36.0),
+            hours(1.0)
+        ),
+        None
+    );
+}
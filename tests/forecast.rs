@@ -0,0 +1,36 @@
+//! Zambretti天气预报测试（不需要硬件）
+//!
+//! 用3小时平稳的海平面气压检查查表结果，以及超出950~1050hPa范围时的极端天气标记
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use raspi_sensor::metrics::{Hemisphere, zambretti};
+
+/// 冬季（2024-01-15 UTC）开始的3小时平稳气压，每10分钟一个读数
+fn steady_history(hpa: f64) -> Vec<(SystemTime, f64)> {
+    let start = UNIX_EPOCH + Duration::from_secs(1_705_276_800);
+    (0..=18)
+        .map(|i| (start + Duration::from_secs(i * 600), hpa * 100.0))
+        .collect()
+}
+
+#[test]
+fn zambretti_normal_pressure() {
+    let forecast = zambretti(&steady_history(1013.0), None, Hemisphere::North).unwrap();
+    assert!(!forecast.exceptional);
+    assert_eq!(forecast.code, 'E');
+}
+
+#[test]
+fn zambretti_above_range_is_exceptional() {
+    let forecast = zambretti(&steady_history(1060.0), None, Hemisphere::North).unwrap();
+    assert!(forecast.exceptional);
+    assert_eq!(forecast.code, 'A');
+}
+
+#[test]
+fn zambretti_below_range_is_exceptional() {
+    let forecast = zambretti(&steady_history(940.0), None, Hemisphere::North).unwrap();
+    assert!(forecast.exceptional);
+    assert_eq!(forecast.code, 'Z');
+}